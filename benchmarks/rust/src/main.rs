@@ -7,18 +7,28 @@ use tikv_jemallocator::Jemalloc;
 #[global_allocator]
 static GLOBAL: Jemalloc = Jemalloc;
 
-use average::{Mean, Variance};
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use futures::{self, StreamExt, future::join_all, stream};
-use glide_core::client::{Client, ConnectionRequest, NodeAddress, TlsMode};
+use glide_core::client::{Client, ConnectionRequest, NodeAddress, ReadFrom, TlsMode};
+use glide_core::cluster_slots::{SLOT_COUNT, parse_cluster_slots};
+use hdrhistogram::Histogram;
+use hdrhistogram::serialization::V2DeflateSerializer;
+use hdrhistogram::serialization::interval_log::{IntervalLogWriterBuilder, Tag};
 use rand::{Rng, thread_rng};
+use rand_distr::{Distribution, Exp, Zipf};
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::cluster_topology::get_slot;
+use redis::{PipelineRetryStrategy, RedisError, RedisResult};
 use serde_json::Value;
 use std::{
-    cmp::max,
+    cmp::{max, min},
     collections::HashMap,
-    path::Path,
-    sync::{Arc, atomic::AtomicUsize},
-    time::{Duration, Instant},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
 #[derive(Parser, Debug)]
@@ -54,22 +64,678 @@ struct Args {
 
     #[arg(long, default_value_t = false)]
     minimal: bool,
+
+    #[arg(long, value_enum, default_value_t = KeyDistribution::Uniform)]
+    distribution: KeyDistribution,
+
+    #[arg(name = "zipfExponent", long, default_value_t = 0.99)]
+    zipf_exponent: f64,
+
+    /// Fraction of the operations that are GETs, the rest are SETs.
+    #[arg(name = "getRatio", long, default_value_t = 0.8)]
+    get_ratio: f64,
+
+    /// Fraction of the GETs that read keys in the populated part of the keyspace.
+    #[arg(name = "getExistingRatio", long, default_value_t = 0.8)]
+    get_existing_ratio: f64,
+
+    #[arg(name = "valueSizeDistribution", long, value_enum, default_value_t = ValueSizeDistribution::Fixed)]
+    value_size_distribution: ValueSizeDistribution,
+
+    /// Smallest value size of the uniform value-size distribution.
+    #[arg(name = "minDataSize", long, default_value_t = 1)]
+    min_data_size: usize,
+
+    /// Number of commands sent together in a pipeline by each task.
+    #[arg(name = "pipelineDepth", long, default_value_t = 1)]
+    pipeline_depth: usize,
+
+    /// Seconds to run the workload before measuring it, for each concurrency level.
+    #[arg(name = "warmupSeconds", long, default_value_t = 0)]
+    warmup_seconds: u64,
+
+    /// Run each concurrency level for this many seconds, instead of for a fixed number of operations.
+    #[arg(name = "durationSeconds", long)]
+    duration_seconds: Option<u64>,
+
+    /// Operations per second to send across all tasks. Each task then sends at fixed intended
+    /// times, and latencies are measured from the intended send time, so that stalls aren't hidden
+    /// by coordinated omission. Unset sends each request as soon as the task's previous one completes.
+    #[arg(name = "targetRate", long)]
+    target_rate: Option<f64>,
+
+    /// Directory to write an HDR histogram log of each concurrency level to.
+    #[arg(name = "hdrLogDir", long)]
+    hdr_log_dir: Option<PathBuf>,
+
+    #[arg(long, value_enum, default_value_t = Scenario::Standard)]
+    scenario: Scenario,
+
+    /// Number of keys read by each MGET of the MGET fan-out scenario.
+    #[arg(name = "mgetKeys", long, default_value_t = 10)]
+    mget_keys: usize,
+}
+
+/// The benchmarked scenario. The scenarios other than `standard` exercise the routing paths of
+/// cluster mode, and require it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Scenario {
+    /// GETs and SETs, sent one by one or in pipelines of `pipelineDepth` commands.
+    Standard,
+    /// MGETs of `mgetKeys` keys spread over the slots, which are split into one MGET per node.
+    MgetFanOut,
+    /// Pipelines of `pipelineDepth` GETs for each primary.
+    PipelinePerNode,
+    /// The standard workload, with the reads served by replicas.
+    ReadFromReplica,
+    /// The standard workload, while a slot is migrated back and forth between two primaries, so
+    /// that requests are redirected and the topology is refreshed.
+    Resharding,
+}
+
+impl Scenario {
+    fn name(&self) -> &'static str {
+        match self {
+            Scenario::Standard => "standard",
+            Scenario::MgetFanOut => "mget_fan_out",
+            Scenario::PipelinePerNode => "pipeline_per_node",
+            Scenario::ReadFromReplica => "read_from_replica",
+            Scenario::Resharding => "resharding",
+        }
+    }
+}
+
+/// How keys are picked from the keyspace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum KeyDistribution {
+    /// Every key is equally likely.
+    Uniform,
+    /// Key popularity follows a Zipf distribution, with lower keys being the most popular.
+    Zipf,
+    /// A small set of hot keys receives most of the traffic.
+    Hotkey,
+    /// Keys are accessed in order, wrapping around at the end of the keyspace.
+    Sequential,
+}
+
+impl KeyDistribution {
+    fn name(&self) -> &'static str {
+        match self {
+            KeyDistribution::Uniform => "uniform",
+            KeyDistribution::Zipf => "zipf",
+            KeyDistribution::Hotkey => "hotkey",
+            KeyDistribution::Sequential => "sequential",
+        }
+    }
+}
+
+/// How the sizes of the values written by SETs are picked.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ValueSizeDistribution {
+    /// Every value has `dataSize` bytes.
+    Fixed,
+    /// Sizes are uniform between `minDataSize` and `dataSize` bytes.
+    Uniform,
+    /// Sizes are exponentially distributed with a mean of `dataSize` bytes, so most values are
+    /// small and a few are large.
+    Exponential,
+}
+
+impl ValueSizeDistribution {
+    fn name(&self) -> &'static str {
+        match self {
+            ValueSizeDistribution::Fixed => "fixed",
+            ValueSizeDistribution::Uniform => "uniform",
+            ValueSizeDistribution::Exponential => "exponential",
+        }
+    }
 }
 
 // Connection constants - these should be adjusted to fit your connection.
 const PORT: u32 = 6379;
 
 // Benchmark constants - adjusting these will change the meaning of the benchmark.
-const PROB_GET: f64 = 0.8;
-const PROB_GET_EXISTING_KEY: f64 = 0.8;
 const SIZE_GET_KEYSPACE: u32 = 3_750_000;
 const SIZE_SET_KEYSPACE: u32 = 3_000_000;
+// Exponentially distributed value sizes are capped at this multiple of the mean.
+const MAX_EXPONENTIAL_SIZE_FACTOR: f64 = 10.0;
+// Latencies are recorded in microseconds, with this many significant digits.
+const HISTOGRAM_SIGNIFICANT_DIGITS: u8 = 3;
+// The percentiles reported for each operation, with the names used in the results.
+const PERCENTILES: [(&str, f64); 7] = [
+    ("p50", 0.5),
+    ("p75", 0.75),
+    ("p90", 0.9),
+    ("p95", 0.95),
+    ("p99", 0.99),
+    ("p99_9", 0.999),
+    ("p99_99", 0.9999),
+];
+// Number of keys moved by each MIGRATE of the resharding scenario, and the pause between two
+// migrations of the slot.
+const KEYS_PER_MIGRATE: usize = 100;
+const MIGRATION_PAUSE: Duration = Duration::from_millis(500);
+// Hot-key distribution: this fraction of the keyspace receives HOT_KEY_ACCESS_PROBABILITY of the accesses.
+const HOT_KEY_FRACTION: f64 = 0.01;
+const HOT_KEY_ACCESS_PROBABILITY: f64 = 0.9;
 
-#[derive(Eq, PartialEq, Hash)]
+#[derive(Eq, PartialEq, Hash, Clone, Copy)]
 enum ChosenAction {
     GetNonExisting,
     GetExisting,
     Set,
+    Mget,
+}
+
+impl ChosenAction {
+    const ALL: [ChosenAction; 4] = [
+        ChosenAction::GetExisting,
+        ChosenAction::GetNonExisting,
+        ChosenAction::Set,
+        ChosenAction::Mget,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            ChosenAction::GetExisting => "get_existing",
+            ChosenAction::GetNonExisting => "get_non_existing",
+            ChosenAction::Set => "set",
+            ChosenAction::Mget => "mget",
+        }
+    }
+}
+
+/// A command of the workload, with the indexes of the primaries it's sent to.
+struct Operation {
+    action: ChosenAction,
+    cmd: redis::Cmd,
+    nodes: Vec<usize>,
+}
+
+/// The result of an operation.
+struct Outcome {
+    action: ChosenAction,
+    nodes: Vec<usize>,
+    result: Result<(), ErrorClass>,
+}
+
+/// Coarse classification of failed operations, used to report error rates in the results.
+#[derive(Eq, PartialEq, Hash, Clone, Copy)]
+enum ErrorClass {
+    Timeout,
+    Connection,
+    Server,
+    Other,
+}
+
+impl ErrorClass {
+    const ALL: [ErrorClass; 4] = [
+        ErrorClass::Timeout,
+        ErrorClass::Connection,
+        ErrorClass::Server,
+        ErrorClass::Other,
+    ];
+
+    fn from_error(error: &RedisError) -> Self {
+        if error.is_timeout() {
+            ErrorClass::Timeout
+        } else if error.is_connection_dropped()
+            || error.is_connection_refusal()
+            || error.is_io_error()
+        {
+            ErrorClass::Connection
+        } else if error.code().is_some() {
+            ErrorClass::Server
+        } else {
+            ErrorClass::Other
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ErrorClass::Timeout => "timeout",
+            ErrorClass::Connection => "connection",
+            ErrorClass::Server => "server",
+            ErrorClass::Other => "other",
+        }
+    }
+}
+
+/// Picks keys from a contiguous range of the keyspace according to a `KeyDistribution`.
+struct KeyRange {
+    start: u32,
+    len: u32,
+    distribution: KeyDistribution,
+    zipf: Option<Zipf<f64>>,
+}
+
+impl KeyRange {
+    fn new(start: u32, end: u32, distribution: KeyDistribution, zipf_exponent: f64) -> Self {
+        let len = end - start;
+        let zipf = (distribution == KeyDistribution::Zipf).then(|| {
+            Zipf::new(len as u64, zipf_exponent).expect("zipfExponent must be non-negative")
+        });
+        Self {
+            start,
+            len,
+            distribution,
+            zipf,
+        }
+    }
+
+    /// `operation_index` is the global index of the operation, used by the sequential distribution.
+    fn sample(&self, operation_index: usize) -> u32 {
+        let mut rng = thread_rng();
+        let offset = match self.distribution {
+            KeyDistribution::Uniform => rng.gen_range(0..self.len),
+            // Zipf samples are ranks in [1, len].
+            KeyDistribution::Zipf => self.zipf.as_ref().unwrap().sample(&mut rng) as u32 - 1,
+            KeyDistribution::Hotkey => {
+                let hot_keys = max(1, (self.len as f64 * HOT_KEY_FRACTION) as u32);
+                if hot_keys == self.len || rng.gen_bool(HOT_KEY_ACCESS_PROBABILITY) {
+                    rng.gen_range(0..hot_keys)
+                } else {
+                    rng.gen_range(hot_keys..self.len)
+                }
+            }
+            KeyDistribution::Sequential => (operation_index % self.len as usize) as u32,
+        };
+        self.start + offset
+    }
+}
+
+/// The key ranges used by the workload.
+struct Keyspace {
+    existing: KeyRange,
+    non_existing: KeyRange,
+}
+
+impl Keyspace {
+    fn new(distribution: KeyDistribution, zipf_exponent: f64) -> Self {
+        Self {
+            existing: KeyRange::new(0, SIZE_SET_KEYSPACE, distribution, zipf_exponent),
+            non_existing: KeyRange::new(
+                SIZE_SET_KEYSPACE,
+                SIZE_GET_KEYSPACE,
+                distribution,
+                zipf_exponent,
+            ),
+        }
+    }
+}
+
+/// The primaries of the cluster, and the slots they owned when the benchmark started. Operations
+/// are attributed to the primaries of their keys' slots - including the reads served by replicas.
+struct NodeMap {
+    addresses: Vec<String>,
+    /// The index of the primary owning each slot.
+    slot_owners: Vec<usize>,
+    /// The keys of the populated part of the keyspace owned by each primary, for the scenarios
+    /// that target every primary.
+    existing_keys: Vec<Vec<u32>>,
+}
+
+impl NodeMap {
+    async fn load(client: &mut Client, scenario: Scenario) -> Self {
+        let slots = client
+            .send_command(&mut redis::cmd("CLUSTER").arg("SLOTS").clone(), None)
+            .await
+            .and_then(parse_cluster_slots)
+            .expect("Failed to read the cluster slots");
+        let mut addresses: Vec<String> = Vec::new();
+        let mut slot_owners = vec![0; SLOT_COUNT as usize];
+        for range in slots {
+            let owner = match addresses
+                .iter()
+                .position(|address| *address == range.primary)
+            {
+                Some(owner) => owner,
+                None => {
+                    addresses.push(range.primary);
+                    addresses.len() - 1
+                }
+            };
+            slot_owners[range.start as usize..=range.end as usize].fill(owner);
+        }
+
+        let mut existing_keys = vec![Vec::new(); addresses.len()];
+        if scenario == Scenario::PipelinePerNode {
+            let mut buffer = itoa::Buffer::new();
+            for key in 0..SIZE_SET_KEYSPACE {
+                let owner = slot_owners[get_slot(buffer.format(key).as_bytes()) as usize];
+                existing_keys[owner].push(key);
+            }
+        }
+        Self {
+            addresses,
+            slot_owners,
+            existing_keys,
+        }
+    }
+
+    fn node_of(&self, key: &str) -> usize {
+        self.slot_owners[get_slot(key.as_bytes()) as usize]
+    }
+}
+
+/// Migrates a slot back and forth between two primaries in the background, until stopped.
+struct Resharding {
+    stop: Arc<AtomicBool>,
+    task: tokio::task::JoinHandle<ReshardingResults>,
+}
+
+#[derive(Default)]
+struct ReshardingResults {
+    /// The number of completed migrations.
+    migrations: usize,
+    /// The failed migrations, by the class of their error.
+    errors: HashMap<ErrorClass, usize>,
+}
+
+impl Resharding {
+    fn start(mut client: Client, nodes: &NodeMap) -> Self {
+        assert!(
+            nodes.addresses.len() >= 2,
+            "The resharding scenario requires at least two primaries"
+        );
+        // The slot of the first key of the keyspace, which is accessed by the workload.
+        let slot = get_slot(b"0");
+        let mut source = nodes.addresses[nodes.slot_owners[slot as usize]].clone();
+        let mut target = nodes
+            .addresses
+            .iter()
+            .find(|address| **address != source)
+            .unwrap()
+            .clone();
+        let stop = Arc::new(AtomicBool::new(false));
+        let task = tokio::spawn({
+            let stop = stop.clone();
+            async move {
+                let mut results = ReshardingResults::default();
+                while !stop.load(Ordering::Relaxed) {
+                    match migrate_slot(&mut client, slot, &source, &target).await {
+                        Ok(()) => {
+                            results.migrations += 1;
+                            std::mem::swap(&mut source, &mut target);
+                        }
+                        // The migration is retried after the pause.
+                        Err(error) => {
+                            *results
+                                .errors
+                                .entry(ErrorClass::from_error(&error))
+                                .or_default() += 1;
+                        }
+                    }
+                    tokio::time::sleep(MIGRATION_PAUSE).await;
+                }
+                results
+            }
+        });
+        Self { stop, task }
+    }
+
+    /// Stops after the migration in progress, and returns the completed and failed migrations.
+    async fn stop(self) -> ReshardingResults {
+        self.stop.store(true, Ordering::Relaxed);
+        self.task.await.unwrap()
+    }
+}
+
+/// Sends `cmd` to the node at `address`.
+async fn send_to_node(
+    client: &mut Client,
+    address: &str,
+    cmd: &mut redis::Cmd,
+) -> RedisResult<redis::Value> {
+    let (host, port) = address.rsplit_once(':').unwrap();
+    let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+        host: host.to_string(),
+        port: port.parse().unwrap(),
+    });
+    client.send_command(cmd, Some(routing)).await
+}
+
+async fn node_id(client: &mut Client, address: &str) -> RedisResult<String> {
+    let id = send_to_node(client, address, redis::cmd("CLUSTER").arg("MYID")).await?;
+    redis::from_owned_redis_value(id)
+}
+
+/// Migrates `slot`, with its keys, from the primary at `source` to the primary at `target`.
+async fn migrate_slot(
+    client: &mut Client,
+    slot: u16,
+    source: &str,
+    target: &str,
+) -> RedisResult<()> {
+    let source_id = node_id(client, source).await?;
+    let target_id = node_id(client, target).await?;
+    send_to_node(
+        client,
+        target,
+        redis::cmd("CLUSTER")
+            .arg("SETSLOT")
+            .arg(slot)
+            .arg("IMPORTING")
+            .arg(&source_id),
+    )
+    .await?;
+    send_to_node(
+        client,
+        source,
+        redis::cmd("CLUSTER")
+            .arg("SETSLOT")
+            .arg(slot)
+            .arg("MIGRATING")
+            .arg(&target_id),
+    )
+    .await?;
+
+    let (target_host, target_port) = target.rsplit_once(':').unwrap();
+    loop {
+        let keys = send_to_node(
+            client,
+            source,
+            redis::cmd("CLUSTER")
+                .arg("GETKEYSINSLOT")
+                .arg(slot)
+                .arg(KEYS_PER_MIGRATE),
+        )
+        .await?;
+        let keys: Vec<Vec<u8>> = redis::from_owned_redis_value(keys)?;
+        if keys.is_empty() {
+            break;
+        }
+        let mut migrate = redis::cmd("MIGRATE");
+        migrate
+            .arg(target_host)
+            .arg(target_port)
+            .arg("")
+            .arg(0)
+            .arg(5000)
+            .arg("REPLACE")
+            .arg("KEYS")
+            .arg(keys);
+        send_to_node(client, source, &mut migrate).await?;
+    }
+
+    // The target is updated first, so that it owns the slot before the source redirects to it.
+    for node in [target, source] {
+        send_to_node(
+            client,
+            node,
+            redis::cmd("CLUSTER")
+                .arg("SETSLOT")
+                .arg(slot)
+                .arg("NODE")
+                .arg(&target_id),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Picks the sizes of the values written by SETs.
+struct ValueSizes {
+    distribution: ValueSizeDistribution,
+    min: usize,
+    mean: usize,
+    exponential: Exp<f64>,
+}
+
+impl ValueSizes {
+    fn new(distribution: ValueSizeDistribution, min: usize, mean: usize) -> Self {
+        Self {
+            distribution,
+            min: min.clamp(1, mean),
+            mean,
+            exponential: Exp::new(1.0 / mean as f64).expect("dataSize must be positive"),
+        }
+    }
+
+    fn sample(&self) -> usize {
+        let mut rng = thread_rng();
+        match self.distribution {
+            ValueSizeDistribution::Fixed => self.mean,
+            ValueSizeDistribution::Uniform => rng.gen_range(self.min..=self.mean),
+            ValueSizeDistribution::Exponential => {
+                let max_size = self.mean as f64 * MAX_EXPONENTIAL_SIZE_FACTOR;
+                max(1, self.exponential.sample(&mut rng).min(max_size) as usize)
+            }
+        }
+    }
+}
+
+/// The mix of commands sent by the benchmark.
+struct Workload {
+    keyspace: Keyspace,
+    value_sizes: ValueSizes,
+    get_ratio: f64,
+    get_existing_ratio: f64,
+    pipeline_depth: usize,
+    target_rate: Option<f64>,
+    scenario: Scenario,
+    mget_keys: usize,
+    /// The primaries of the cluster, in cluster mode.
+    nodes: Option<NodeMap>,
+}
+
+impl Workload {
+    fn new(args: &Args) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&args.get_ratio),
+            "getRatio must be between 0 and 1"
+        );
+        assert!(
+            (0.0..=1.0).contains(&args.get_existing_ratio),
+            "getExistingRatio must be between 0 and 1"
+        );
+        assert!(args.pipeline_depth > 0, "pipelineDepth must be positive");
+        assert!(
+            args.target_rate.is_none_or(|rate| rate > 0.0),
+            "targetRate must be positive"
+        );
+        assert!(
+            args.scenario == Scenario::Standard || args.cluster_mode_enabled,
+            "The {} scenario requires cluster mode",
+            args.scenario.name()
+        );
+        assert!(args.mget_keys > 0, "mgetKeys must be positive");
+        Self {
+            keyspace: Keyspace::new(args.distribution, args.zipf_exponent),
+            value_sizes: ValueSizes::new(
+                args.value_size_distribution,
+                args.min_data_size,
+                args.data_size,
+            ),
+            get_ratio: args.get_ratio,
+            get_existing_ratio: args.get_existing_ratio,
+            pipeline_depth: args.pipeline_depth,
+            target_rate: args.target_rate,
+            scenario: args.scenario,
+            mget_keys: args.mget_keys,
+            nodes: None,
+        }
+    }
+
+    /// The number of operations sent together by each task.
+    fn batch_depth(&self) -> usize {
+        match (self.scenario, &self.nodes) {
+            (Scenario::PipelinePerNode, Some(nodes)) => self.pipeline_depth * nodes.addresses.len(),
+            _ => self.pipeline_depth,
+        }
+    }
+
+    /// Returns the interval between the batches sent by each of `concurrent_tasks` tasks, when
+    /// the sends are paced to the target rate.
+    fn send_interval(&self, concurrent_tasks: usize) -> Option<Duration> {
+        self.target_rate.map(|rate| {
+            Duration::from_secs_f64((concurrent_tasks * self.batch_depth()) as f64 / rate)
+        })
+    }
+}
+
+/// When a phase of the benchmark ends.
+#[derive(Clone, Copy)]
+enum StopCondition {
+    /// After this many operations.
+    Operations(usize),
+    /// Once this instant has passed.
+    Deadline(Instant),
+}
+
+impl StopCondition {
+    /// Returns how many operations of a batch starting at `first_operation` should run, or `None`
+    /// if the phase has ended.
+    fn batch_size(&self, first_operation: usize, depth: usize) -> Option<usize> {
+        match self {
+            StopCondition::Operations(count) => {
+                (first_operation < *count).then(|| min(depth, count - first_operation))
+            }
+            StopCondition::Deadline(deadline) => (Instant::now() < *deadline).then_some(depth),
+        }
+    }
+}
+
+/// The latencies of one kind of operation, in microseconds.
+struct LatencyRecorder {
+    /// From the intended send time to the completion - the latency seen by the application.
+    response: Histogram<u64>,
+    /// From the actual send time to the completion. Only recorded when the sends are paced, as it
+    /// equals the response latency otherwise.
+    service: Option<Histogram<u64>>,
+}
+
+impl LatencyRecorder {
+    fn new(paced: bool) -> Self {
+        let histogram = || Histogram::new(HISTOGRAM_SIGNIFICANT_DIGITS).unwrap();
+        Self {
+            response: histogram(),
+            service: paced.then(histogram),
+        }
+    }
+
+    fn record(&mut self, response: Duration, service: Duration) {
+        self.response.saturating_record(response.as_micros() as u64);
+        if let Some(histogram) = &mut self.service {
+            histogram.saturating_record(service.as_micros() as u64);
+        }
+    }
+
+    fn add(&mut self, other: &LatencyRecorder) {
+        self.response.add(&other.response).unwrap();
+        if let (Some(histogram), Some(other)) = (&mut self.service, &other.service) {
+            histogram.add(other).unwrap();
+        }
+    }
+}
+
+#[derive(Default)]
+struct TaskResults {
+    latencies: HashMap<ChosenAction, LatencyRecorder>,
+    errors: HashMap<ErrorClass, usize>,
+    operations: usize,
+    /// The number of requests sent to each primary, by index.
+    node_requests: HashMap<usize, usize>,
 }
 
 fn main() {
@@ -91,14 +757,26 @@ fn main() {
 }
 
 async fn perform_benchmark(args: Args) {
+    let mut workload = Workload::new(&args);
+    // Used to read the topology, and to migrate slots in the resharding scenario.
+    let mut admin_connection = get_connection(&args).await;
+    if args.cluster_mode_enabled {
+        workload.nodes = Some(NodeMap::load(&mut admin_connection, args.scenario).await);
+    }
     let mut total_results = Vec::new();
     for concurrent_tasks_count in args.concurrent_tasks.iter() {
         println!(
             "
-        Starting data size: {} concurrency: {concurrent_tasks_count} client count: {} is_cluster: {} {}",
-            args.data_size, args.client_count, args.cluster_mode_enabled, chrono::offset::Utc::now()
+        Starting data size: {} concurrency: {concurrent_tasks_count} client count: {} is_cluster: {} distribution: {} get ratio: {} pipeline depth: {} scenario: {} {}",
+            args.data_size,
+            args.client_count,
+            args.cluster_mode_enabled,
+            args.distribution.name(),
+            args.get_ratio,
+            args.pipeline_depth,
+            args.scenario.name(),
+            chrono::offset::Utc::now()
         );
-        let counter = Arc::new(AtomicUsize::new(0));
         let number_of_operations = if args.minimal {
             1000
         } else {
@@ -112,31 +790,37 @@ async fn perform_benchmark(args: Args) {
             })
             .await;
 
-        let start = Instant::now();
-        let results = join_all((0..*concurrent_tasks_count).map(|_| async {
-            single_benchmark_task(
+        if args.warmup_seconds > 0 {
+            let deadline = Instant::now() + Duration::from_secs(args.warmup_seconds);
+            run_tasks(
                 &connections,
-                counter.clone(),
-                number_of_operations,
+                &workload,
+                StopCondition::Deadline(deadline),
                 *concurrent_tasks_count,
-                args.data_size,
             )
-            .await
-        }))
-        .await;
-        let elapsed = start.elapsed();
-        let combined_results = results.into_iter().fold(HashMap::new(), |mut acc, map| {
-            if acc.is_empty() {
-                return map;
-            }
-            for key in map.keys() {
-                acc.get_mut(key)
-                    .unwrap()
-                    .extend_from_slice(map.get(key).unwrap());
-            }
+            .await;
+        }
 
-            acc
-        });
+        let stop = match args.duration_seconds {
+            Some(seconds) => StopCondition::Deadline(Instant::now() + Duration::from_secs(seconds)),
+            None => StopCondition::Operations(number_of_operations),
+        };
+        let resharding = match (args.scenario, &workload.nodes) {
+            (Scenario::Resharding, Some(nodes)) => {
+                Some(Resharding::start(admin_connection.clone(), nodes))
+            }
+            _ => None,
+        };
+        let start = Instant::now();
+        let combined_results =
+            run_tasks(&connections, &workload, stop, *concurrent_tasks_count).await;
+        let elapsed = start.elapsed();
+        let resharding_results = match resharding {
+            Some(resharding) => Some(resharding.stop().await),
+            None => None,
+        };
+        let end_time = SystemTime::now();
+        let completed_operations = combined_results.operations;
         let mut results_json = HashMap::new();
         results_json.insert("client".to_string(), Value::String("glide".to_string()));
         results_json.insert(
@@ -149,7 +833,13 @@ async fn perform_benchmark(args: Args) {
         );
         results_json.insert(
             "tps".to_string(),
-            Value::Number((number_of_operations as i64 * 1000 / elapsed.as_millis() as i64).into()),
+            Value::Number(
+                ((completed_operations as u128 * 1000 / max(1, elapsed.as_millis())) as u64).into(),
+            ),
+        );
+        results_json.insert(
+            "num_of_operations".to_string(),
+            Value::Number(completed_operations.into()),
         );
         results_json.insert(
             "client_count".to_string(),
@@ -159,17 +849,71 @@ async fn perform_benchmark(args: Args) {
             "is_cluster".to_string(),
             Value::Bool(args.cluster_mode_enabled),
         );
-        results_json.extend(calculate_latencies(
-            combined_results.get(&ChosenAction::GetExisting).unwrap(),
-            "get_existing",
-        ));
-        results_json.extend(calculate_latencies(
-            combined_results.get(&ChosenAction::GetNonExisting).unwrap(),
-            "get_non_existing",
-        ));
-        results_json.extend(calculate_latencies(
-            combined_results.get(&ChosenAction::Set).unwrap(),
-            "set",
+        results_json.insert(
+            "key_distribution".to_string(),
+            Value::String(args.distribution.name().to_string()),
+        );
+        results_json.insert(
+            "value_size_distribution".to_string(),
+            Value::String(args.value_size_distribution.name().to_string()),
+        );
+        results_json.insert("get_ratio".to_string(), args.get_ratio.into());
+        results_json.insert(
+            "scenario".to_string(),
+            Value::String(args.scenario.name().to_string()),
+        );
+        if let Some(nodes) = &workload.nodes {
+            let per_node_tps = nodes
+                .addresses
+                .iter()
+                .enumerate()
+                .map(|(node, address)| {
+                    let requests = combined_results
+                        .node_requests
+                        .get(&node)
+                        .copied()
+                        .unwrap_or_default();
+                    let tps = (requests as u128 * 1000 / max(1, elapsed.as_millis())) as u64;
+                    (address.clone(), Value::Number(tps.into()))
+                })
+                .collect();
+            results_json.insert("per_node_tps".to_string(), Value::Object(per_node_tps));
+        }
+        if let Some(resharding_results) = resharding_results {
+            results_json.insert(
+                "slot_migrations".to_string(),
+                Value::Number(resharding_results.migrations.into()),
+            );
+            results_json.extend(count_errors(&resharding_results.errors, "slot_migration_"));
+        }
+        results_json.insert(
+            "pipeline_depth".to_string(),
+            Value::Number(args.pipeline_depth.into()),
+        );
+        if let Some(rate) = args.target_rate {
+            results_json.insert("target_rate".to_string(), rate.into());
+        }
+        for action in ChosenAction::ALL {
+            if let Some(latencies) = combined_results.latencies.get(&action) {
+                results_json.extend(calculate_latencies(&latencies.response, action.name()));
+                if let Some(service) = &latencies.service {
+                    let prefix = format!("{}_service", action.name());
+                    results_json.extend(calculate_latencies(service, &prefix));
+                }
+            }
+        }
+        if let Some(dir) = &args.hdr_log_dir {
+            let path = dir.join(format!("glide-rust-{concurrent_tasks_count}-tasks.hlog"));
+            write_hdr_log(&path, &combined_results, end_time - elapsed, elapsed)
+                .expect("Failed to write the HDR histogram log");
+            results_json.insert(
+                "hdr_log".to_string(),
+                Value::String(path.display().to_string()),
+            );
+        }
+        results_json.extend(calculate_error_rates(
+            &combined_results.errors,
+            completed_operations,
         ));
         total_results.push(results_json);
     }
@@ -181,38 +925,135 @@ async fn perform_benchmark(args: Args) {
     .unwrap();
 }
 
-fn calculate_latencies(values: &[Duration], prefix: &str) -> HashMap<String, Value> {
-    let mut latencies: Vec<f64> = values
-        .iter()
-        .map(|duration| duration.as_secs_f64() * 1000.0) // Convert to milliseconds
-        .collect();
-
-    latencies.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+/// Runs the workload on `concurrent_tasks` tasks until `stop`, and combines their results.
+async fn run_tasks(
+    connections: &[Client],
+    workload: &Workload,
+    stop: StopCondition,
+    concurrent_tasks: usize,
+) -> TaskResults {
+    let counter = Arc::new(AtomicUsize::new(0));
+    let send_interval = workload.send_interval(concurrent_tasks);
+    let start = Instant::now();
+    let results = join_all((0..concurrent_tasks).map(|task_index| {
+        // Paced tasks are staggered, so that the sends are spread evenly over the interval.
+        let schedule = send_interval.map(|interval| {
+            let first_send = start + interval.mul_f64(task_index as f64 / concurrent_tasks as f64);
+            (first_send, interval)
+        });
+        single_benchmark_task(connections, workload, counter.clone(), stop, schedule)
+    }))
+    .await;
+    results
+        .into_iter()
+        .fold(TaskResults::default(), |mut acc, task_results| {
+            for (action, latencies) in task_results.latencies {
+                acc.latencies
+                    .entry(action)
+                    .or_insert_with(|| LatencyRecorder::new(send_interval.is_some()))
+                    .add(&latencies);
+            }
+            for (class, count) in task_results.errors {
+                *acc.errors.entry(class).or_default() += count;
+            }
+            acc.operations += task_results.operations;
+            for (node, requests) in task_results.node_requests {
+                *acc.node_requests.entry(node).or_default() += requests;
+            }
+            acc
+        })
+}
 
+fn calculate_latencies(histogram: &Histogram<u64>, prefix: &str) -> HashMap<String, Value> {
     let mut map = HashMap::new();
-    let len = latencies.len() as f64;
-    if len == 0.0 {
-        panic!("No latencies were found");
+    if histogram.is_empty() {
+        // Every operation of this kind failed - the error rates already describe the run.
+        return map;
     }
 
-    let p50 = latencies[(len * 0.5) as usize];
-    let p90 = latencies[(len * 0.9) as usize];
-    let p99 = latencies[(len * 0.99) as usize];
-
-    let mean_calc: Mean = latencies.iter().copied().collect();
-    let avg = mean_calc.mean();
+    // Convert microseconds to milliseconds
+    let to_millis = |micros: f64| micros / 1000.0;
+    for (name, quantile) in PERCENTILES {
+        map.insert(
+            format!("{prefix}_{name}_latency"),
+            to_millis(histogram.value_at_quantile(quantile) as f64).into(),
+        );
+    }
+    map.insert(
+        format!("{prefix}_max_latency"),
+        to_millis(histogram.max() as f64).into(),
+    );
+    map.insert(
+        format!("{prefix}_average_latency"),
+        to_millis(histogram.mean()).into(),
+    );
+    map.insert(
+        format!("{prefix}_std_dev"),
+        to_millis(histogram.stdev()).into(),
+    );
+    map
+}
 
-    let variance_calc: Variance = latencies.iter().copied().collect();
-    let stddev = variance_calc.population_variance().sqrt();
+/// Writes the latency histograms of a run to an HDR histogram log, tagged with the operation names.
+fn write_hdr_log(
+    path: &Path,
+    results: &TaskResults,
+    start_time: SystemTime,
+    duration: Duration,
+) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    let mut serializer = V2DeflateSerializer::new();
+    let mut writer = IntervalLogWriterBuilder::new()
+        .with_start_time(start_time)
+        .with_base_time(start_time)
+        // Latencies are reported in milliseconds.
+        .with_max_value_divisor(1000.0)
+        .begin_log_with(&mut file, &mut serializer)?;
+    for action in ChosenAction::ALL {
+        if let Some(latencies) = results.latencies.get(&action) {
+            writer
+                .write_histogram(
+                    &latencies.response,
+                    Duration::ZERO,
+                    duration,
+                    Tag::new(action.name()),
+                )
+                .map_err(std::io::Error::other)?;
+        }
+    }
+    Ok(())
+}
 
-    map.insert(format!("{prefix}_p50_latency"), p50.into());
-    map.insert(format!("{prefix}_p90_latency"), p90.into());
-    map.insert(format!("{prefix}_p99_latency"), p99.into());
-    map.insert(format!("{prefix}_average_latency"), avg.into());
-    map.insert(format!("{prefix}_std_dev"), stddev.into());
+fn calculate_error_rates(
+    errors: &HashMap<ErrorClass, usize>,
+    number_of_operations: usize,
+) -> HashMap<String, Value> {
+    let mut map = count_errors(errors, "");
+    let total_errors: usize = errors.values().sum();
+    map.insert(
+        "total_errors".to_string(),
+        Value::Number(total_errors.into()),
+    );
+    map.insert(
+        "error_rate".to_string(),
+        (total_errors as f64 / number_of_operations as f64).into(),
+    );
     map
 }
 
+/// Reports the number of errors of each class, under `{prefix}{class}_errors`.
+fn count_errors(errors: &HashMap<ErrorClass, usize>, prefix: &str) -> HashMap<String, Value> {
+    ErrorClass::ALL
+        .into_iter()
+        .map(|class| {
+            (
+                format!("{prefix}{}_errors", class.name()),
+                Value::Number(errors.get(&class).copied().unwrap_or_default().into()),
+            )
+        })
+        .collect()
+}
+
 fn generate_random_string(length: usize) -> String {
     rand::thread_rng()
         .sample_iter(&rand::distributions::Alphanumeric)
@@ -235,6 +1076,7 @@ async fn get_connection(args: &Args) -> Client {
         } else {
             Some(TlsMode::NoTls)
         },
+        read_from: (args.scenario == Scenario::ReadFromReplica).then_some(ReadFrom::PreferReplica),
         ..Default::default()
     };
 
@@ -245,61 +1087,191 @@ async fn get_connection(args: &Args) -> Client {
 
 async fn single_benchmark_task(
     connections: &[Client],
+    workload: &Workload,
     counter: Arc<AtomicUsize>,
-    number_of_operations: usize,
-    number_of_concurrent_tasks: usize,
-    data_size: usize,
-) -> HashMap<ChosenAction, Vec<Duration>> {
+    stop: StopCondition,
+    // When the sends are paced, the time of the first send and the interval between sends.
+    mut schedule: Option<(Instant, Duration)>,
+) -> TaskResults {
     let mut buffer = itoa::Buffer::new();
-    let mut results = HashMap::new();
-    results.insert(
-        ChosenAction::GetNonExisting,
-        Vec::with_capacity(number_of_operations / number_of_concurrent_tasks),
-    );
-    results.insert(
-        ChosenAction::GetExisting,
-        Vec::with_capacity(number_of_operations / number_of_concurrent_tasks),
-    );
-    results.insert(
-        ChosenAction::Set,
-        Vec::with_capacity(number_of_operations / number_of_concurrent_tasks),
-    );
+    let mut results = TaskResults::default();
+    for action in ChosenAction::ALL {
+        results
+            .latencies
+            .insert(action, LatencyRecorder::new(schedule.is_some()));
+    }
+    let depth = workload.batch_depth();
     loop {
-        let current_op = counter.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
-        if current_op >= number_of_operations {
+        // A batch that's late because the previous ones were slow is sent immediately, and its
+        // latency includes the delay.
+        let intended_send = match schedule {
+            Some((intended_send, _)) => {
+                tokio::time::sleep_until(intended_send.into()).await;
+                Some(intended_send)
+            }
+            None => None,
+        };
+        let first_op = counter.fetch_add(depth, Ordering::Relaxed);
+        let Some(batch_size) = stop.batch_size(first_op, depth) else {
             return results;
-        }
-        let index = current_op % connections.len();
+        };
+        let index = (first_op / depth) % connections.len();
         let mut connection = connections[index].clone();
         let start = Instant::now();
-        let action = perform_operation(&mut connection, &mut buffer, data_size).await;
-        let elapsed = start.elapsed();
-        results.get_mut(&action).unwrap().push(elapsed);
+        let outcomes =
+            perform_operations(&mut connection, workload, first_op, batch_size, &mut buffer).await;
+        // All the commands of a pipeline complete together.
+        let service_time = start.elapsed();
+        let response_time =
+            intended_send.map_or(service_time, |intended_send| intended_send.elapsed());
+        if let Some((intended_send, interval)) = &mut schedule {
+            *intended_send += *interval;
+        }
+        results.operations += outcomes.len();
+        for outcome in outcomes {
+            for node in outcome.nodes {
+                *results.node_requests.entry(node).or_default() += 1;
+            }
+            match outcome.result {
+                Ok(()) => results
+                    .latencies
+                    .get_mut(&outcome.action)
+                    .unwrap()
+                    .record(response_time, service_time),
+                Err(class) => {
+                    // Keep the workload running so that transient errors are reported, not fatal.
+                    *results.errors.entry(class).or_default() += 1;
+                }
+            }
+        }
     }
 }
 
-async fn perform_operation(
-    connection: &mut Client,
+fn choose_command(
+    workload: &Workload,
+    operation_index: usize,
     buffer: &mut itoa::Buffer,
-    data_size: usize,
-) -> ChosenAction {
+) -> Operation {
+    let keyspace = &workload.keyspace;
+    let nodes = workload.nodes.as_ref();
     let mut cmd = redis::Cmd::new();
-    let action = if rand::thread_rng().gen_bool(PROB_GET) {
-        if rand::thread_rng().gen_bool(PROB_GET_EXISTING_KEY) {
-            cmd.arg("GET")
-                .arg(buffer.format(thread_rng().gen_range(0..SIZE_SET_KEYSPACE)));
+    let mut key_nodes = Vec::new();
+    let mut add_key = |cmd: &mut redis::Cmd, key: u32| {
+        let key = buffer.format(key);
+        if let Some(nodes) = nodes {
+            let node = nodes.node_of(key);
+            if !key_nodes.contains(&node) {
+                key_nodes.push(node);
+            }
+        }
+        cmd.arg(key);
+    };
+    let action = match (workload.scenario, nodes) {
+        (Scenario::MgetFanOut, _) => {
+            cmd.arg("MGET");
+            for index in 0..workload.mget_keys {
+                let key = keyspace
+                    .existing
+                    .sample(operation_index * workload.mget_keys + index);
+                add_key(&mut cmd, key);
+            }
+            ChosenAction::Mget
+        }
+        (Scenario::PipelinePerNode, Some(nodes)) => {
+            // The operations of a batch are spread evenly over the primaries.
+            let node_keys = &nodes.existing_keys[operation_index % nodes.addresses.len()];
+            let key = node_keys[thread_rng().gen_range(0..node_keys.len())];
+            cmd.arg("GET");
+            add_key(&mut cmd, key);
             ChosenAction::GetExisting
-        } else {
-            cmd.arg("GET")
-                .arg(buffer.format(thread_rng().gen_range(SIZE_SET_KEYSPACE..SIZE_GET_KEYSPACE)));
-            ChosenAction::GetNonExisting
-        }
-    } else {
-        cmd.arg("SET")
-            .arg(buffer.format(thread_rng().gen_range(0..SIZE_SET_KEYSPACE)))
-            .arg(generate_random_string(data_size));
-        ChosenAction::Set
+        }
+        _ if rand::thread_rng().gen_bool(workload.get_ratio) => {
+            cmd.arg("GET");
+            if rand::thread_rng().gen_bool(workload.get_existing_ratio) {
+                add_key(&mut cmd, keyspace.existing.sample(operation_index));
+                ChosenAction::GetExisting
+            } else {
+                add_key(&mut cmd, keyspace.non_existing.sample(operation_index));
+                ChosenAction::GetNonExisting
+            }
+        }
+        _ => {
+            cmd.arg("SET");
+            add_key(&mut cmd, keyspace.existing.sample(operation_index));
+            cmd.arg(generate_random_string(workload.value_sizes.sample()));
+            ChosenAction::Set
+        }
     };
-    connection.send_command(&mut cmd, None).await.unwrap();
-    action
+    Operation {
+        action,
+        cmd,
+        nodes: key_nodes,
+    }
+}
+
+/// Sends `count` operations starting at `first_operation` - as a single command, or as a pipeline.
+async fn perform_operations(
+    connection: &mut Client,
+    workload: &Workload,
+    first_operation: usize,
+    count: usize,
+    buffer: &mut itoa::Buffer,
+) -> Vec<Outcome> {
+    let mut operations: Vec<_> = (first_operation..first_operation + count)
+        .map(|operation_index| choose_command(workload, operation_index, buffer))
+        .collect();
+    if count == 1 {
+        let Operation {
+            action,
+            mut cmd,
+            nodes,
+        } = operations.pop().unwrap();
+        let result = connection.send_command(&mut cmd, None).await;
+        return vec![Outcome {
+            action,
+            nodes,
+            result: result
+                .map(|_| ())
+                .map_err(|error| ErrorClass::from_error(&error)),
+        }];
+    }
+
+    let mut pipeline = redis::pipe();
+    let mut outcomes = Vec::with_capacity(count);
+    for operation in operations {
+        pipeline.add_command(operation.cmd);
+        outcomes.push(Outcome {
+            action: operation.action,
+            nodes: operation.nodes,
+            result: Ok(()),
+        });
+    }
+    let result = connection
+        .send_pipeline(
+            &pipeline,
+            None,
+            false,
+            None,
+            PipelineRetryStrategy::default(),
+        )
+        .await;
+    match result {
+        Ok(redis::Value::Array(values)) => {
+            for (outcome, value) in outcomes.iter_mut().zip(values) {
+                if let redis::Value::ServerError(_) = value {
+                    outcome.result = Err(ErrorClass::Server);
+                }
+            }
+        }
+        Ok(_) => outcomes
+            .iter_mut()
+            .for_each(|outcome| outcome.result = Err(ErrorClass::Other)),
+        Err(error) => {
+            let class = ErrorClass::from_error(&error);
+            outcomes
+                .iter_mut()
+                .for_each(|outcome| outcome.result = Err(class));
+        }
+    }
+    outcomes
 }
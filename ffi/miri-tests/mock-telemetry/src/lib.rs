@@ -138,6 +138,23 @@ impl Telemetry {
     pub fn subscription_out_of_sync_count() -> usize { 0 }
     pub fn update_subscription_last_sync_timestamp(_timestamp: u64) -> u64 { 0 }
     pub fn subscription_last_sync_timestamp() -> u64 { 0 }
+    pub fn latency_breakdown_samples() -> u64 { 0 }
+    pub fn total_queue_time_us() -> u64 { 0 }
+    pub fn total_network_time_us() -> u64 { 0 }
+    pub fn total_decode_time_us() -> u64 { 0 }
+    pub fn hot_key_warnings() -> u64 { 0 }
+    pub fn memory_budget_bytes_in_use() -> u64 { 0 }
+    pub fn memory_budget_rejections() -> u64 { 0 }
+    pub fn process_memory_bytes() -> u64 { 0 }
+    pub fn soft_memory_limit_rejections() -> u64 { 0 }
+    pub fn large_requests() -> u64 { 0 }
+    pub fn large_responses() -> u64 { 0 }
+    pub fn health_checks() -> u64 { 0 }
+    pub fn health_check_failures() -> u64 { 0 }
+    pub fn health_check_evictions() -> u64 { 0 }
+    pub fn unhealthy_nodes() -> u64 { 0 }
+    pub fn total_connection_errors() -> u64 { 0 }
+    pub fn recent_connection_errors_json() -> String { "[]".to_string() }
     pub fn reset() {}
 }
 
@@ -2,6 +2,8 @@
 
 use glide_core::ConnectionRequest;
 use glide_core::client::Client as GlideClient;
+use glide_core::client::MemoryReservation;
+use glide_core::client_runtime::{ClientRuntime, RuntimeMode};
 use glide_core::cluster_scan_container::get_cluster_scan_cursor;
 use glide_core::command_request::SimpleRoutes;
 use glide_core::command_request::{CacheMetricsType, Routes, SlotTypes};
@@ -12,10 +14,11 @@ use glide_core::otel_db_semantics::{
     set_db_attributes, set_db_batch_attributes, set_db_script_attributes,
 };
 use glide_core::request_type::RequestType;
+use glide_core::runtime_config::GlideRuntimeConfig;
 use glide_core::scripts_container;
 use glide_core::{
-    DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, GlideOpenTelemetry, GlideOpenTelemetryConfigBuilder,
-    GlideOpenTelemetrySignalsExporter, GlideSpan, Telemetry,
+    GlideOpenTelemetry, GlideOpenTelemetryConfigBuilder, GlideOpenTelemetrySignalsExporter,
+    GlideSpan, Telemetry,
 };
 use protobuf::{Enum, Message};
 use redis::ErrorKind;
@@ -708,7 +711,8 @@ pub unsafe extern "C" fn init_async_pipe(pipe_write_fd: i32) {
 
 /// A `GlideClient` adapter.
 pub struct ClientAdapter {
-    runtime: Runtime,
+    /// The runtime of the client's commands - of its own, or shared as its `runtime_mode` selects.
+    runtime: ClientRuntime,
     pipe_client_id: std::sync::atomic::AtomicU64,
     /// Background runtime for spawned tasks (connection drivers, reconnection, cluster manager).
     /// Only used by sync clients with current_thread main runtime — tokio::spawn calls during
@@ -746,6 +750,17 @@ impl ClientAdapter {
     where
         Fut: Future<Output = RedisResult<Value>> + Send + 'static,
     {
+        // The response is accounted against the client's memory budget until the application
+        // frees it.
+        let client = self.core.client.clone();
+        let request_future = async move {
+            let result = request_future.await;
+            let memory = result
+                .as_ref()
+                .ok()
+                .and_then(|value| client.reserve_response_memory(value));
+            (result, memory)
+        };
         match self.core.client_type {
             ClientType::AsyncClient {
                 success_callback,
@@ -757,10 +772,11 @@ impl ClientAdapter {
                     .load(std::sync::atomic::Ordering::Relaxed);
                 if cid != 0 && ASYNC_PIPE.get().is_some() {
                     self.runtime.spawn(async move {
-                        match request_future.await {
+                        let (result, memory) = request_future.await;
+                        match result {
                             Ok(value) => {
                                 let buf = response_buf.map(|rb| (rb.0, rb.1));
-                                match valkey_value_to_arena_response(value, buf) {
+                                match valkey_value_to_arena_response(value, buf, memory) {
                                     Ok((root_ptr, arena_ptr)) => {
                                         if let Some(w) = ASYNC_PIPE.get() {
                                             w.push_success(
@@ -798,9 +814,10 @@ impl ClientAdapter {
                     return std::ptr::null_mut();
                 }
                 self.runtime.spawn(async move {
-                    let result = request_future.await;
+                    let (result, memory) = request_future.await;
                     let _ = Self::handle_result(
                         result,
+                        memory,
                         Some(success_callback),
                         Some(failure_callback),
                         request_id,
@@ -820,11 +837,11 @@ impl ClientAdapter {
                     .background_runtime
                     .as_ref()
                     .map(|rt| rt.handle().clone());
-                let result = self.runtime.block_on(async {
+                let (result, memory) = self.runtime.block_on(async {
                     let _guard = bg.as_ref().map(|h| h.enter());
                     request_future.await
                 });
-                Self::handle_result(result, None, None, request_id, response_buf, false)
+                Self::handle_result(result, memory, None, None, request_id, response_buf, false)
             }
         }
     }
@@ -837,6 +854,7 @@ impl ClientAdapter {
     #[must_use]
     fn handle_result(
         result: RedisResult<Value>,
+        memory: Option<MemoryReservation>,
         success_callback: Option<SuccessCallback>,
         failure_callback: Option<FailureCallback>,
         request_id: usize,
@@ -906,7 +924,7 @@ impl ClientAdapter {
                         }
                     }
                     // Heap path: arena-allocated response
-                    match valkey_value_to_arena_response(value, buf) {
+                    match valkey_value_to_arena_response(value, buf, memory) {
                         Ok((root_ptr, _arena_ptr)) => {
                             unsafe { (success_callback)(request_id, root_ptr) };
                         }
@@ -920,7 +938,7 @@ impl ClientAdapter {
                     }
                 } else {
                     // Sync path: always use arena
-                    match valkey_value_to_arena_response(value, buf) {
+                    match valkey_value_to_arena_response(value, buf, memory) {
                         Ok((root_ptr, _arena_ptr)) => {
                             return Box::into_raw(Box::new(CommandResult {
                                 response: root_ptr,
@@ -1086,6 +1104,9 @@ pub enum PushKind {
     PushSubscribe,
     PushPSubscribe,
     PushSSubscribe,
+    PushTopologyChange,
+    PushSubscriptionDenied,
+    PushCredentialExpiry,
 }
 
 impl From<redis::PushKind> for PushKind {
@@ -1103,6 +1124,9 @@ impl From<redis::PushKind> for PushKind {
             redis::PushKind::Subscribe => PushKind::PushSubscribe,
             redis::PushKind::PSubscribe => PushKind::PushPSubscribe,
             redis::PushKind::SSubscribe => PushKind::PushSSubscribe,
+            redis::PushKind::TopologyChange => PushKind::PushTopologyChange,
+            redis::PushKind::SubscriptionDenied => PushKind::PushSubscriptionDenied,
+            redis::PushKind::CredentialExpiry => PushKind::PushCredentialExpiry,
         }
     }
 }
@@ -1214,38 +1238,49 @@ fn create_client_internal(
 ) -> Result<*const ClientAdapter, String> {
     let request = connection_request::ConnectionRequest::parse_from_bytes(connection_request_bytes)
         .map_err(|err| err.to_string())?;
+    let mut connection_request = ConnectionRequest::from(request);
     let runtime = match &client_type {
         ClientType::SyncClient => {
+            // A sync client runs its commands on the calling thread.
+            if connection_request.runtime_mode != RuntimeMode::Dedicated {
+                return Err("The runtime mode isn't supported by sync clients".to_string());
+            }
             // current_thread runtime: block_on drives the reactor directly on the
             // calling thread, eliminating the condvar park/wake overhead that occurs
             // with multi_thread when the calling thread waits for a worker.
-            Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .map_err(|err| {
+            ClientRuntime::dedicated(Builder::new_current_thread().enable_all().build().map_err(
+                |err| {
                     let redis_error: redis::RedisError = err.into();
                     errors::error_message(&redis_error)
-                })?
+                },
+            )?)
         }
         ClientType::AsyncClient { .. } => {
-            // Async clients need a background worker thread to drive the reactor
-            // since the calling thread is owned by the foreign language's event loop.
-            // GLIDE_TOKIO_WORKER_THREADS controls the number of tokio worker threads
-            // (default 1). More workers can help concurrent large-response workloads.
-            let worker_threads = std::env::var("GLIDE_TOKIO_WORKER_THREADS")
-                .ok()
-                .and_then(|s| s.parse::<usize>().ok())
-                .unwrap_or(1)
-                .max(1);
-            Builder::new_multi_thread()
-                .enable_all()
-                .worker_threads(worker_threads)
-                .thread_name("Valkey-GLIDE thread")
-                .build()
-                .map_err(|err| {
-                    let redis_error: redis::RedisError = err.into();
-                    errors::error_message(&redis_error)
-                })?
+            // Clients that select a shared runtime don't start threads of their own.
+            if let Some(shared) = ClientRuntime::for_mode(connection_request.runtime_mode)? {
+                shared
+            } else {
+                // Async clients need a background worker thread to drive the reactor
+                // since the calling thread is owned by the foreign language's event loop.
+                // GLIDE_TOKIO_WORKER_THREADS controls the number of tokio worker threads
+                // (default 1). More workers can help concurrent large-response workloads.
+                let worker_threads = std::env::var("GLIDE_TOKIO_WORKER_THREADS")
+                    .ok()
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .unwrap_or(1)
+                    .max(1);
+                ClientRuntime::dedicated(
+                    Builder::new_multi_thread()
+                        .enable_all()
+                        .worker_threads(worker_threads)
+                        .thread_name("Valkey-GLIDE thread")
+                        .build()
+                        .map_err(|err| {
+                            let redis_error: redis::RedisError = err.into();
+                            errors::error_message(&redis_error)
+                        })?,
+                )
+            }
         }
     };
 
@@ -1275,9 +1310,6 @@ fn create_client_internal(
         // Create the client on the background runtime so all TCP I/O and spawned
         // tasks (connection drivers, cluster manager) are registered there.
         // The current_thread runtime is only used for block_on in the command path.
-        let create_rt = background_runtime.as_ref().unwrap_or(&runtime);
-        let mut connection_request = ConnectionRequest::from(request);
-
         // Set the address resolver if provided
         if let Some(resolver_callback) = address_resolver {
             connection_request.address_resolver = Some(Arc::new(FFIAddressResolver {
@@ -1286,9 +1318,12 @@ fn create_client_internal(
             }));
         }
 
-        create_rt
-            .block_on(GlideClient::new(connection_request, Some(push_tx)))
-            .map_err(|err| err.to_string())?
+        let create = GlideClient::new(connection_request, Some(push_tx));
+        match &background_runtime {
+            Some(background_runtime) => background_runtime.block_on(create),
+            None => runtime.block_on(create),
+        }
+        .map_err(|err| err.to_string())?
     };
 
     // Create the client adapter that will be returned and used as conn_ptr
@@ -1312,7 +1347,7 @@ fn create_client_internal(
     let spawn_runtime = client_adapter
         .background_runtime
         .as_ref()
-        .unwrap_or(&client_adapter.runtime);
+        .map_or(client_adapter.runtime.handle(), Runtime::handle);
     let callback_store = pubsub_callback_store.clone();
     let pipe_cid = client_id as u64;
     if is_sync {
@@ -2475,6 +2510,8 @@ pub struct ResponseArena {
     nodes: Vec<CommandResponse>,
     /// Owned string buffers (kept alive until arena is freed).
     strings: Vec<Vec<u8>>,
+    /// The response's memory budget reservation, released when the arena is freed.
+    memory: Option<MemoryReservation>,
 }
 
 const MAX_ARENA_POOL_SIZE: usize = 16;
@@ -2493,11 +2530,13 @@ impl ResponseArena {
                 ResponseArena {
                     nodes: Vec::with_capacity(nc),
                     strings: Vec::new(),
+                    memory: None,
                 }
             }
         })
     }
-    fn return_to_pool(self) {
+    fn return_to_pool(mut self) {
+        self.memory = None;
         ARENA_POOL.with(|p| {
             let mut p = p.borrow_mut();
             if p.len() < MAX_ARENA_POOL_SIZE {
@@ -2756,8 +2795,10 @@ impl ResponseArena {
 fn valkey_value_to_arena_response(
     value: Value,
     response_buf: Option<(*mut u8, usize)>,
+    memory: Option<MemoryReservation>,
 ) -> RedisResult<(*mut CommandResponse, *mut ResponseArena)> {
     let mut arena = ResponseArena::from_pool(&value);
+    arena.memory = memory;
     arena.build(value, response_buf)?;
     Ok(arena.finalize())
 }
@@ -3076,16 +3117,13 @@ fn to_c_error(err: RedisError) -> (*const c_char, RequestErrorType) {
 
 fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<RoutingInfo>> {
     use glide_core::command_request::routes::Value;
+    let aggregation = route.aggregation;
     let route = match route.value {
         Some(route) => route,
         None => return Ok(None),
     };
-    let get_response_policy = |cmd: Option<&Cmd>| {
-        cmd.and_then(|cmd| {
-            cmd.command()
-                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
-        })
-    };
+    let get_response_policy =
+        |cmd: Option<&Cmd>| glide_core::multi_node_response_policy(aggregation, cmd);
     match route {
         Value::SimpleRoutes(simple_route) => {
             let simple_route = match simple_route.enum_value() {
@@ -3101,11 +3139,11 @@ fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<RoutingInfo
             match simple_route {
                 SimpleRoutes::AllNodes => Ok(Some(RoutingInfo::MultiNode((
                     MultipleNodeRoutingInfo::AllNodes,
-                    get_response_policy(cmd),
+                    get_response_policy(cmd)?,
                 )))),
                 SimpleRoutes::AllPrimaries => Ok(Some(RoutingInfo::MultiNode((
                     MultipleNodeRoutingInfo::AllMasters,
-                    get_response_policy(cmd),
+                    get_response_policy(cmd)?,
                 )))),
                 SimpleRoutes::Random => {
                     Ok(Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)))
@@ -3489,7 +3527,7 @@ pub unsafe extern "C-unwind" fn get_cache_metrics(
         || (cid != 0 && ASYNC_PIPE.get().is_some());
     if is_pipe_or_sync {
         match result {
-            Ok(value) => match valkey_value_to_arena_response(value, None) {
+            Ok(value) => match valkey_value_to_arena_response(value, None, None) {
                 Ok((root_ptr, arena_ptr)) => Box::into_raw(Box::new(CommandResult {
                     response: root_ptr,
                     command_error: std::ptr::null_mut(),
@@ -4625,11 +4663,11 @@ pub unsafe extern "C" fn init_open_telemetry(
             Ok(exporter) => {
                 let sample_percentage =
                     if unsafe { (*(*open_telemetry_config).traces).has_sample_percentage } {
-                        Some(unsafe { (*(*open_telemetry_config).traces).sample_percentage })
+                        unsafe { (*(*open_telemetry_config).traces).sample_percentage }
                     } else {
-                        None
+                        GlideRuntimeConfig::get().telemetry_trace_sample_percentage
                     };
-                config = config.with_trace_exporter(exporter, sample_percentage);
+                config = config.with_trace_exporter(exporter, Some(sample_percentage));
             }
             Err(e) => {
                 let error_msg = format!("Invalid traces exporter configuration: {e}");
@@ -4665,7 +4703,9 @@ pub unsafe extern "C" fn init_open_telemetry(
     let flush_interval_ms = if unsafe { (*open_telemetry_config).has_flush_interval_ms } {
         unsafe { (*open_telemetry_config).flush_interval_ms }
     } else {
-        DEFAULT_FLUSH_SIGNAL_INTERVAL_MS as i64
+        GlideRuntimeConfig::get()
+            .telemetry_flush_interval
+            .as_millis() as i64
     };
 
     if flush_interval_ms <= 0 {
@@ -4703,6 +4743,32 @@ pub unsafe extern "C" fn init_open_telemetry(
     }
 }
 
+/// Sets the process-wide runtime configuration - the client defaults and limits - from a Protobuf `RuntimeConfig`.
+/// Must be called before the first client is created, and at most once.
+///
+/// Returns `null` on success, or an error message that must be freed with [`free_c_string`].
+///
+/// # Safety
+/// * `runtime_config_bytes` must point to `runtime_config_len` consecutive properly initialized bytes. The array must
+///   be allocated by the caller and subsequently freed by the caller after this function returns.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn init_runtime_config(
+    runtime_config_bytes: *const u8,
+    runtime_config_len: usize,
+) -> *const c_char {
+    let config_bytes = if runtime_config_bytes.is_null() {
+        &[]
+    } else {
+        unsafe { std::slice::from_raw_parts(runtime_config_bytes, runtime_config_len) }
+    };
+    match glide_core::runtime_config::GlideRuntimeConfig::initialize_from_protobuf(config_bytes) {
+        Ok(()) => std::ptr::null(),
+        Err(e) => CString::new(e)
+            .unwrap_or_else(|_| CString::new("Couldn't convert error message to C string").unwrap())
+            .into_raw(),
+    }
+}
+
 /// Frees a C string.
 ///
 /// # Safety
@@ -4950,6 +5016,38 @@ pub struct Statistics {
     pub subscription_out_of_sync_count: c_ulong,
     /// Timestamp of last successful subscription sync (milliseconds since epoch)
     pub subscription_last_sync_timestamp: c_ulong,
+    /// Number of commands whose latency breakdown was recorded
+    pub latency_breakdown_samples: c_ulong,
+    /// Total time (in microseconds) commands spent queued in the client before being sent
+    pub total_queue_time_us: c_ulong,
+    /// Total time (in microseconds) between sending commands and receiving their responses
+    pub total_network_time_us: c_ulong,
+    /// Total time (in microseconds) spent decoding responses
+    pub total_decode_time_us: c_ulong,
+    /// Number of times a key was detected as hot
+    pub hot_key_warnings: c_ulong,
+    /// Bytes currently held by in-flight requests and undelivered responses of clients with a memory budget
+    pub memory_budget_bytes_in_use: c_ulong,
+    /// Number of requests rejected because a client's memory budget was exhausted
+    pub memory_budget_rejections: c_ulong,
+    /// Memory of the process, as last measured for the soft memory limit
+    pub process_memory_bytes: c_ulong,
+    /// Number of requests rejected because the process exceeded its soft memory limit
+    pub soft_memory_limit_rejections: c_ulong,
+    /// Number of commands whose arguments exceeded the large request threshold
+    pub large_requests: c_ulong,
+    /// Number of commands whose response exceeded the large response threshold
+    pub large_responses: c_ulong,
+    /// Number of health-check PINGs sent to nodes
+    pub health_checks: c_ulong,
+    /// Number of health-check PINGs that failed or timed out
+    pub health_check_failures: c_ulong,
+    /// Number of node connections closed and rebuilt after failing consecutive health checks
+    pub health_check_evictions: c_ulong,
+    /// Number of nodes whose last health check failed
+    pub unhealthy_nodes: c_ulong,
+    /// Number of connection errors recorded, see [`get_recent_connection_errors`]
+    pub total_connection_errors: c_ulong,
 }
 
 /// Get compression and connection statistics.
@@ -4973,6 +5071,48 @@ pub extern "C" fn get_statistics() -> Statistics {
         compression_skipped_count: Telemetry::compression_skipped_count() as c_ulong,
         subscription_out_of_sync_count: Telemetry::subscription_out_of_sync_count() as c_ulong,
         subscription_last_sync_timestamp: Telemetry::subscription_last_sync_timestamp() as c_ulong,
+        latency_breakdown_samples: Telemetry::latency_breakdown_samples() as c_ulong,
+        total_queue_time_us: Telemetry::total_queue_time_us() as c_ulong,
+        total_network_time_us: Telemetry::total_network_time_us() as c_ulong,
+        total_decode_time_us: Telemetry::total_decode_time_us() as c_ulong,
+        hot_key_warnings: Telemetry::hot_key_warnings() as c_ulong,
+        memory_budget_bytes_in_use: Telemetry::memory_budget_bytes_in_use() as c_ulong,
+        memory_budget_rejections: Telemetry::memory_budget_rejections() as c_ulong,
+        process_memory_bytes: Telemetry::process_memory_bytes() as c_ulong,
+        soft_memory_limit_rejections: Telemetry::soft_memory_limit_rejections() as c_ulong,
+        large_requests: Telemetry::large_requests() as c_ulong,
+        large_responses: Telemetry::large_responses() as c_ulong,
+        health_checks: Telemetry::health_checks() as c_ulong,
+        health_check_failures: Telemetry::health_check_failures() as c_ulong,
+        health_check_evictions: Telemetry::health_check_evictions() as c_ulong,
+        unhealthy_nodes: Telemetry::unhealthy_nodes() as c_ulong,
+        total_connection_errors: Telemetry::total_connection_errors() as c_ulong,
+    }
+}
+
+/// Get the most recent connection errors of all clients.
+///
+/// # Returns
+///
+/// A JSON array of the errors, oldest first. Each error has a `timestamp` (milliseconds since
+/// epoch), `node`, `error_class` and `message`. The string must be freed with
+/// [`free_recent_connection_errors`].
+#[unsafe(no_mangle)]
+pub extern "C" fn get_recent_connection_errors() -> *mut c_char {
+    CString::new(Telemetry::recent_connection_errors_json())
+        .unwrap_or_default()
+        .into_raw()
+}
+
+/// Free a string returned by [`get_recent_connection_errors`].
+///
+/// # Safety
+///
+/// * `errors` must be a string returned by [`get_recent_connection_errors`], or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn free_recent_connection_errors(errors: *mut c_char) {
+    if !errors.is_null() {
+        _ = unsafe { CString::from_raw(errors) };
     }
 }
 
@@ -5181,6 +5321,7 @@ pub unsafe extern "C-unwind" fn create_monitor_client(
             Some(connection_request.lib_name.to_string())
         },
         server_assisted_cache: false,
+        protocol_fallback: false,
         cache: None,
     };
 
@@ -1,5 +1,6 @@
 #![allow(deprecated)]
 
+use super::dns::{connect_happy_eyeballs, DnsResolver, DnsResolverConfig};
 use super::ConnectionLike;
 use super::{setup_connection, AsyncStream, RedisRuntime};
 use crate::cmd::{cmd, Cmd};
@@ -10,13 +11,10 @@ use crate::connection::{
 #[cfg(feature = "tokio-comp")]
 use crate::parser::ValueCodec;
 use crate::pipeline::PipelineRetryStrategy;
-use crate::types::{ErrorKind, FromRedisValue, RedisError, RedisFuture, RedisResult, Value};
+use crate::types::{FromRedisValue, RedisError, RedisFuture, RedisResult, Value};
 use crate::{from_owned_redis_value, ProtocolVersion, ToRedisArgs};
 use ::tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
-#[cfg(feature = "tokio-comp")]
-use ::tokio::net::lookup_host;
 use combine::{parser::combinator::AnySendSyncPartialState, stream::PointerOffset};
-use futures_util::future::select_ok;
 use futures_util::{
     future::FutureExt,
     stream::{Stream, StreamExt},
@@ -66,7 +64,7 @@ where
             pubsub: false,
             protocol: connection_info.protocol,
         };
-        setup_connection(connection_info, &mut rv, false).await?;
+        rv.protocol = setup_connection(connection_info, &mut rv, false).await?;
         Ok(rv)
     }
 
@@ -388,20 +386,19 @@ where
     }
 }
 
+/// Resolves `host` with `dns_resolver`, or with the system resolver if it's `None`.
 pub(crate) async fn get_socket_addrs(
     host: &str,
     port: u16,
-) -> RedisResult<impl Iterator<Item = SocketAddr> + Send + '_> {
-    #[cfg(feature = "tokio-comp")]
-    let socket_addrs = lookup_host((host, port)).await?;
-
-    let mut socket_addrs = socket_addrs.peekable();
-    match socket_addrs.peek() {
-        Some(_) => Ok(socket_addrs),
-        None => Err(RedisError::from((
-            ErrorKind::InvalidClientConfig,
-            "No address found for host",
-        ))),
+    dns_resolver: Option<&DnsResolver>,
+) -> RedisResult<Vec<SocketAddr>> {
+    match dns_resolver {
+        Some(dns_resolver) => dns_resolver.resolve(host, port).await,
+        None => {
+            DnsResolver::new(&DnsResolverConfig::System)
+                .resolve(host, port)
+                .await
+        }
     }
 }
 
@@ -420,6 +417,7 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
     connection_info: &ConnectionInfo,
     _socket_addr: Option<SocketAddr>,
     tcp_nodelay: bool,
+    dns_resolver: Option<&DnsResolver>,
 ) -> RedisResult<(T, Option<IpAddr>)> {
     Ok(match connection_info.addr {
         ConnectionAddr::Tcp(ref host, port) => {
@@ -429,18 +427,17 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
                     Some(socket_addr.ip()),
                 ));
             }
-            let socket_addrs = get_socket_addrs(host, port).await?;
-            select_ok(socket_addrs.map(|socket_addr| {
+            let socket_addrs = get_socket_addrs(host, port, dns_resolver).await?;
+            connect_happy_eyeballs(socket_addrs, |socket_addr| {
                 log_conn_creation("TCP", format!("{host}:{port}"), Some(socket_addr.ip()));
-                Box::pin(async move {
+                async move {
                     Ok::<_, RedisError>((
                         <T>::connect_tcp(socket_addr, tcp_nodelay).await?,
                         Some(socket_addr.ip()),
                     ))
-                })
-            }))
+                }
+            })
             .await?
-            .0
         }
 
         ConnectionAddr::TcpTls {
@@ -456,23 +453,22 @@ pub(crate) async fn connect_simple<T: RedisRuntime>(
                     Some(socket_addr.ip()),
                 ));
             }
-            let socket_addrs = get_socket_addrs(host, port).await?;
-            select_ok(socket_addrs.map(|socket_addr| {
+            let socket_addrs = get_socket_addrs(host, port, dns_resolver).await?;
+            connect_happy_eyeballs(socket_addrs, |socket_addr| {
                 log_conn_creation(
                     "TCP with TLS",
                     format!("{host}:{port}"),
                     Some(socket_addr.ip()),
                 );
-                Box::pin(async move {
+                async move {
                     Ok::<_, RedisError>((
                         <T>::connect_tcp_tls(host, socket_addr, insecure, tls_params, tcp_nodelay)
                             .await?,
                         Some(socket_addr.ip()),
                     ))
-                })
-            }))
+                }
+            })
             .await?
-            .0
         }
 
         #[cfg(unix)]
@@ -0,0 +1,287 @@
+//! Host name resolution for new connections.
+//!
+//! Host names are resolved again for every new connection, including reconnects, so that clients
+//! follow DNS-based failovers. The addresses of a host are rotated between resolutions, to spread
+//! connections across all of the host's records, and interleaved by address family so that
+//! [`connect_happy_eyeballs`] alternates between IPv6 and IPv4 attempts.
+
+use crate::types::{ErrorKind, RedisError, RedisResult};
+use futures_util::future::{select, Either};
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::future::Future;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time to wait for a connection attempt before starting an attempt to the next address, as
+/// recommended by RFC 8305.
+pub const CONNECTION_ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// How host names are resolved to IP addresses.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DnsResolverConfig {
+    /// A non-blocking resolver, configured from the system's resolver configuration
+    /// (`/etc/resolv.conf` on Unix) and hosts file. Falls back to [`DnsResolverConfig::System`]
+    /// if the system configuration can't be read, or if the `async-dns` feature is disabled.
+    #[default]
+    Async,
+    /// A non-blocking resolver that queries the given name servers over UDP and TCP.
+    AsyncWithNameServers(Vec<SocketAddr>),
+    /// The operating system's resolver (`getaddrinfo`), called on a blocking thread.
+    System,
+}
+
+enum Resolver {
+    System,
+    #[cfg(feature = "async-dns")]
+    Async(Box<hickory_resolver::TokioResolver>),
+}
+
+/// Resolves host names to the socket addresses to connect to. Clones share the same cache and
+/// rotation.
+#[derive(Clone)]
+pub struct DnsResolver {
+    resolver: Arc<Resolver>,
+    rotation: Arc<AtomicUsize>,
+}
+
+impl std::fmt::Debug for DnsResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match *self.resolver {
+            Resolver::System => "System",
+            #[cfg(feature = "async-dns")]
+            Resolver::Async(_) => "Async",
+        };
+        f.debug_struct("DnsResolver").field("kind", &kind).finish()
+    }
+}
+
+impl Default for DnsResolver {
+    fn default() -> Self {
+        Self::new(&DnsResolverConfig::default())
+    }
+}
+
+impl DnsResolver {
+    /// Creates a resolver with the given configuration.
+    pub fn new(config: &DnsResolverConfig) -> Self {
+        Self {
+            resolver: Arc::new(Resolver::new(config)),
+            rotation: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Resolves `host`, and returns its addresses in the order they should be attempted.
+    pub async fn resolve(&self, host: &str, port: u16) -> RedisResult<Vec<SocketAddr>> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![SocketAddr::new(ip, port)]);
+        }
+        let mut addresses: Vec<SocketAddr> = match &*self.resolver {
+            Resolver::System => ::tokio::net::lookup_host((host, port)).await?.collect(),
+            #[cfg(feature = "async-dns")]
+            Resolver::Async(resolver) => resolver
+                .lookup_ip(host)
+                .await
+                .map_err(|err| {
+                    RedisError::from((
+                        ErrorKind::IoError,
+                        "Failed to resolve host",
+                        format!("{host}: {err}"),
+                    ))
+                })?
+                .iter()
+                .map(|ip| SocketAddr::new(ip, port))
+                .collect(),
+        };
+        if addresses.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "No address found for host",
+            )));
+        }
+        let len = addresses.len();
+        addresses.rotate_left(self.rotation.fetch_add(1, Ordering::Relaxed) % len);
+        Ok(interleave_families(addresses))
+    }
+}
+
+impl Resolver {
+    #[cfg(feature = "async-dns")]
+    fn new(config: &DnsResolverConfig) -> Self {
+        use hickory_resolver::config::{
+            LookupIpStrategy, NameServerConfig, NameServerConfigGroup, ResolverConfig,
+        };
+        use hickory_resolver::name_server::TokioConnectionProvider;
+        use hickory_resolver::proto::xfer::Protocol;
+        use hickory_resolver::TokioResolver;
+
+        let mut builder = match config {
+            DnsResolverConfig::System => return Resolver::System,
+            DnsResolverConfig::Async => match TokioResolver::builder_tokio() {
+                Ok(builder) => builder,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed to read the system DNS configuration, using the system resolver: {err}"
+                    );
+                    return Resolver::System;
+                }
+            },
+            DnsResolverConfig::AsyncWithNameServers(name_servers) => {
+                let name_servers: NameServerConfigGroup = name_servers
+                    .iter()
+                    .flat_map(|address| {
+                        [Protocol::Udp, Protocol::Tcp]
+                            .map(|protocol| NameServerConfig::new(*address, protocol))
+                    })
+                    .collect::<Vec<_>>()
+                    .into();
+                TokioResolver::builder_with_config(
+                    ResolverConfig::from_parts(None, Vec::new(), name_servers),
+                    TokioConnectionProvider::default(),
+                )
+            }
+        };
+        // Both families are needed for Happy Eyeballs
+        builder.options_mut().ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+        Resolver::Async(Box::new(builder.build()))
+    }
+
+    #[cfg(not(feature = "async-dns"))]
+    fn new(_config: &DnsResolverConfig) -> Self {
+        Resolver::System
+    }
+}
+
+/// Orders addresses by alternating between IPv6 and IPv4, starting with IPv6, as described in
+/// RFC 8305. The relative order of the addresses of each family is kept.
+fn interleave_families(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let (ipv6, ipv4): (Vec<SocketAddr>, Vec<SocketAddr>) =
+        addresses.into_iter().partition(SocketAddr::is_ipv6);
+    let mut ipv6 = ipv6.into_iter();
+    let mut ipv4 = ipv4.into_iter();
+    let mut interleaved = Vec::with_capacity(ipv6.len() + ipv4.len());
+    loop {
+        match (ipv6.next(), ipv4.next()) {
+            (None, None) => return interleaved,
+            (first, second) => interleaved.extend(first.into_iter().chain(second)),
+        }
+    }
+}
+
+/// Connects to the first address that accepts a connection, following RFC 8305: an attempt is
+/// started every [`CONNECTION_ATTEMPT_DELAY`], or as soon as the previous attempt fails, and the
+/// first successful attempt wins.
+pub(crate) async fn connect_happy_eyeballs<T, F, Fut>(
+    addresses: Vec<SocketAddr>,
+    connect: F,
+) -> RedisResult<T>
+where
+    F: Fn(SocketAddr) -> Fut,
+    Fut: Future<Output = RedisResult<T>>,
+{
+    let mut addresses = addresses.into_iter().peekable();
+    let mut attempts = FuturesUnordered::new();
+    let mut last_error = None;
+    loop {
+        if let Some(address) = addresses.next() {
+            attempts.push(connect(address));
+        }
+        let result = if addresses.peek().is_some() {
+            let delay = Box::pin(::tokio::time::sleep(CONNECTION_ATTEMPT_DELAY));
+            match select(attempts.next(), delay).await {
+                Either::Left((result, _)) => result,
+                Either::Right(_) => continue,
+            }
+        } else {
+            attempts.next().await
+        };
+        match result {
+            Some(Ok(connection)) => return Ok(connection),
+            Some(Err(err)) => last_error = Some(err),
+            None => {
+                return Err(last_error.unwrap_or_else(|| {
+                    RedisError::from((ErrorKind::InvalidClientConfig, "No address found for host"))
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(address: &str) -> SocketAddr {
+        address.parse().unwrap()
+    }
+
+    #[test]
+    fn test_interleave_families() {
+        let addresses = vec![
+            address("10.0.0.1:6379"),
+            address("10.0.0.2:6379"),
+            address("10.0.0.3:6379"),
+            address("[::1]:6379"),
+            address("[::2]:6379"),
+        ];
+        assert_eq!(
+            interleave_families(addresses),
+            vec![
+                address("[::1]:6379"),
+                address("10.0.0.1:6379"),
+                address("[::2]:6379"),
+                address("10.0.0.2:6379"),
+                address("10.0.0.3:6379"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ip_literals_are_not_resolved() {
+        let resolver = DnsResolver::new(&DnsResolverConfig::System);
+        assert_eq!(
+            resolver.resolve("::1", 7000).await.unwrap(),
+            vec![address("[::1]:7000")]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_happy_eyeballs_starts_next_attempt_after_delay() {
+        let addresses = vec![address("[::1]:6379"), address("10.0.0.1:6379")];
+        let start = ::tokio::time::Instant::now();
+        // The first address never answers, so the second attempt starts after the delay
+        let connected = connect_happy_eyeballs(addresses, |address| async move {
+            if address.is_ipv6() {
+                std::future::pending::<()>().await;
+            }
+            Ok(address)
+        })
+        .await
+        .unwrap();
+        assert_eq!(connected, address("10.0.0.1:6379"));
+        assert_eq!(start.elapsed(), CONNECTION_ATTEMPT_DELAY);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_happy_eyeballs_moves_on_after_failure() {
+        let addresses = vec![
+            address("[::1]:6379"),
+            address("10.0.0.1:6379"),
+            address("[::2]:6379"),
+        ];
+        let start = ::tokio::time::Instant::now();
+        let result: RedisResult<SocketAddr> =
+            connect_happy_eyeballs(addresses, |address| async move {
+                Err(RedisError::from((
+                    ErrorKind::IoError,
+                    "Connection refused",
+                    address.to_string(),
+                )))
+            })
+            .await;
+        // Failed attempts start the next one without waiting, and the last error is returned
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        assert_eq!(result.unwrap_err().detail(), Some("[::2]:6379"));
+    }
+}
@@ -1,6 +1,8 @@
 //! Adds async IO support to redis.
 use crate::cmd::{cmd, Cmd};
-use crate::connection::{get_resp3_hello_command_error, RedisConnectionInfo};
+use crate::connection::{
+    get_resp3_hello_command_error, should_fallback_to_resp2, RedisConnectionInfo,
+};
 use crate::pipeline::PipelineRetryStrategy;
 use crate::types::{
     ErrorKind, FromRedisValue, InfoDict, ProtocolVersion, RedisError, RedisFuture, RedisResult,
@@ -143,24 +145,37 @@ where
     }
 }
 
-// Initial setup for every connection.
+// Initial setup for every connection. Returns the protocol negotiated with the server, which is
+// RESP2 if the connection fell back from RESP3.
 async fn setup_connection<C>(
     connection_info: &RedisConnectionInfo,
     con: &mut C,
     // This parameter is set to 'true' if ReadFromReplica strategy is set to AZAffinity or AZAffinityReplicasAndPrimary.
     // An INFO command will be triggered in the connection's setup to update the 'availability_zone' property.
     discover_az: bool,
-) -> RedisResult<()>
+) -> RedisResult<ProtocolVersion>
 where
     C: ConnectionLike,
 {
-    if connection_info.protocol != ProtocolVersion::RESP2 {
+    let mut protocol = connection_info.protocol;
+    if protocol != ProtocolVersion::RESP2 {
         let hello_cmd = resp3_hello(connection_info);
         let val: RedisResult<Value> = hello_cmd.query_async(con).await;
         if let Err(err) = val {
-            return Err(get_resp3_hello_command_error(err));
+            let err = get_resp3_hello_command_error(err);
+            if !should_fallback_to_resp2(&err, connection_info) {
+                return Err(err);
+            }
+            // The server predates RESP3 - continue with RESP2, authenticating with AUTH below.
+            protocol = ProtocolVersion::RESP2;
         }
-    } else if let Some(password) = &connection_info.password {
+    }
+    // With RESP3, HELLO has already authenticated the connection.
+    if let Some(password) = connection_info
+        .password
+        .as_ref()
+        .filter(|_| protocol == ProtocolVersion::RESP2)
+    {
         let mut command = cmd("AUTH");
         if let Some(username) = &connection_info.username {
             command.arg(username);
@@ -226,7 +241,7 @@ where
     }
 
     if connection_info.server_assisted_cache {
-        if connection_info.protocol == ProtocolVersion::RESP2 {
+        if protocol == ProtocolVersion::RESP2 {
             return Err(RedisError::from((
                 ErrorKind::InvalidClientConfig,
                 "server_assisted_cache requires RESP3 protocol",
@@ -266,11 +281,13 @@ where
         crate::connection::client_set_info_pipeline(connection_info.lib_name.as_deref())
             .query_async(con)
             .await;
-    Ok(())
+    Ok(protocol)
 }
 
 mod connection;
 pub use connection::*;
+mod dns;
+pub use dns::{DnsResolver, DnsResolverConfig, CONNECTION_ATTEMPT_DELAY};
 mod multiplexed_connection;
 pub use multiplexed_connection::*;
 #[cfg(feature = "connection-manager")]
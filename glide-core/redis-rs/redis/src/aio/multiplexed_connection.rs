@@ -5,7 +5,7 @@ use crate::cache::glide_cache::GlideCache;
 use crate::client::GlideConnectionOptions;
 use crate::cmd::Cmd;
 #[cfg(feature = "tokio-comp")]
-use crate::parser::ValueCodec;
+use crate::parser::{FrameClock, ValueCodec};
 use crate::pipeline::PipelineRetryStrategy;
 use crate::push_manager::PushManager;
 use crate::types::{RedisError, RedisFuture, RedisResult, Value};
@@ -30,15 +30,16 @@ use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::task::{self, Poll};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 #[cfg(feature = "tokio-comp")]
 use tokio_util::codec::Decoder;
 
 // Default connection timeout in ms
 const DEFAULT_CONNECTION_ATTEMPT_TIMEOUT: Duration = Duration::from_millis(2000);
 
-// Senders which the result of a single request are sent through
-type PipelineOutput = oneshot::Sender<RedisResult<Value>>;
+// Senders which the result of a single request are sent through, with the time the first byte of
+// its response arrived
+type PipelineOutput = oneshot::Sender<(RedisResult<Value>, Option<Instant>)>;
 
 enum ResponseAggregate {
     SingleCommand,
@@ -71,6 +72,7 @@ struct InFlight {
     response_aggregate: ResponseAggregate,
     is_fenced: bool,
     fenced_result: Option<RedisResult<Value>>,
+    first_byte_at: Option<Instant>,
 }
 
 // A single message sent through the pipeline
@@ -125,6 +127,7 @@ pin_project! {
         response_sync_lost: bool,
         cache: Option<Arc<dyn GlideCache>>,
         progress: Arc<AtomicU64>,
+        frame_clock: Option<FrameClock>,
     }
 
         impl<T> PinnedDrop for PipelineSink<T> {
@@ -154,6 +157,7 @@ where
         is_stream_closed: Arc<AtomicBool>,
         cache: Option<Arc<dyn GlideCache>>,
         progress: Arc<AtomicU64>,
+        frame_clock: Option<FrameClock>,
     ) -> Self
     where
         T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
@@ -168,6 +172,7 @@ where
             response_sync_lost: false,
             cache,
             progress,
+            frame_clock,
         }
     }
 
@@ -205,7 +210,7 @@ where
                     crate::ErrorKind::ProtocolDesync,
                     "Response synchronization lost - connection must be reestablished",
                 ));
-                entry.output.send(Err(err)).ok();
+                entry.output.send((Err(err), None)).ok();
             }
             return;
         }
@@ -243,6 +248,9 @@ where
             Some(entry) => entry,
             None => return,
         };
+        if entry.first_byte_at.is_none() {
+            entry.first_byte_at = self_.frame_clock.as_ref().map(FrameClock::last_frame_start);
+        }
 
         // Handle fenced commands
         if entry.is_fenced {
@@ -254,7 +262,7 @@ where
             ResponseAggregate::SingleCommand => {
                 entry
                     .output
-                    .send(result.and_then(|v| v.extract_error()))
+                    .send((result.and_then(|v| v.extract_error()), entry.first_byte_at))
                     .ok();
             }
             ResponseAggregate::Pipeline {
@@ -299,7 +307,7 @@ where
                 // `Err` means that the receiver was dropped in which case it does not
                 // care about the output and we can continue by just dropping the value
                 // and sender
-                entry.output.send(response).ok();
+                entry.output.send((response, entry.first_byte_at)).ok();
             }
         }
     }
@@ -337,7 +345,10 @@ where
             // This means the fenced command had no response
             Ok(Value::SimpleString(ref s)) if s == "PONG" || s == "pong" => {
                 // Return Ok(Nil) to indicate success with no data
-                entry.output.send(Ok(Value::Nil)).ok();
+                entry
+                    .output
+                    .send((Ok(Value::Nil), entry.first_byte_at))
+                    .ok();
             }
 
             // Case 2: First response is an error
@@ -385,13 +396,13 @@ where
                 "Expected PONG for fenced command but received different response",
                 format!("Response synchronization lost. Got: {:?}", pong_result),
             ));
-            entry.output.send(Err(err)).ok();
+            entry.output.send((Err(err), entry.first_byte_at)).ok();
             return;
         }
 
         // ✅ Got PONG as expected, return the stored result
         let final_result = stored_result.and_then(|v| v.extract_error());
-        entry.output.send(final_result).ok();
+        entry.output.send((final_result, entry.first_byte_at)).ok();
     }
 }
 
@@ -464,7 +475,7 @@ where
         let self_ = self.as_mut().project();
 
         if let Some(err) = self_.error.take() {
-            let _ = output.send(Err(err));
+            let _ = output.send((Err(err), None));
             return Err(());
         }
 
@@ -473,7 +484,7 @@ where
                 crate::ErrorKind::ProtocolDesync,
                 "Response synchronization lost - connection must be reestablished",
             ));
-            let _ = output.send(Err(err));
+            let _ = output.send((Err(err), None));
             return Err(());
         }
 
@@ -486,13 +497,14 @@ where
                     response_aggregate,
                     is_fenced,
                     fenced_result: None,
+                    first_byte_at: None,
                 };
 
                 self_.in_flight.push_back(entry);
                 Ok(())
             }
             Err(err) => {
-                let _ = output.send(Err(err));
+                let _ = output.send((Err(err), None));
                 Err(())
             }
         }
@@ -560,6 +572,7 @@ where
     /// burst headroom.
     const DEFAULT_BUFFER_SIZE: usize = 50;
 
+    #[cfg(test)]
     fn new<T>(
         sink_stream: T,
         disconnect_notifier: Option<Box<dyn DisconnectNotifier>>,
@@ -589,12 +602,55 @@ where
     /// payloads. A larger capacity raises burst headroom before producers block,
     /// at the cost of more memory held in the channel (≈ capacity × payload size)
     /// and weaker backpressure pacing of the writer task.
+    #[cfg(test)]
     fn new_with_buffer_size<T>(
         sink_stream: T,
         disconnect_notifier: Option<Box<dyn DisconnectNotifier>>,
         cache: Option<Arc<dyn GlideCache>>,
         buffer_size: usize,
     ) -> (Self, impl Future<Output = ()>)
+    where
+        T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
+        T: Send + 'static,
+        T::Item: Send,
+        T::Error: Send,
+        T::Error: ::std::fmt::Debug,
+    {
+        Self::start(sink_stream, disconnect_notifier, cache, buffer_size, None)
+    }
+
+    /// Creates a pipeline over a `sink_stream` decoding with a codec that records in
+    /// `frame_clock`, if set, when each response starts arriving. The pipeline then returns the
+    /// time the first byte of each response arrived.
+    fn with_frame_clock<T>(
+        sink_stream: T,
+        disconnect_notifier: Option<Box<dyn DisconnectNotifier>>,
+        cache: Option<Arc<dyn GlideCache>>,
+        frame_clock: Option<FrameClock>,
+    ) -> (Self, impl Future<Output = ()>)
+    where
+        T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
+        T: Send + 'static,
+        T::Item: Send,
+        T::Error: Send,
+        T::Error: ::std::fmt::Debug,
+    {
+        Self::start(
+            sink_stream,
+            disconnect_notifier,
+            cache,
+            Self::DEFAULT_BUFFER_SIZE,
+            frame_clock,
+        )
+    }
+
+    fn start<T>(
+        sink_stream: T,
+        disconnect_notifier: Option<Box<dyn DisconnectNotifier>>,
+        cache: Option<Arc<dyn GlideCache>>,
+        buffer_size: usize,
+        frame_clock: Option<FrameClock>,
+    ) -> (Self, impl Future<Output = ()>)
     where
         T: Sink<SinkItem, Error = RedisError> + Stream<Item = RedisResult<Value>> + 'static,
         T: Send + 'static,
@@ -614,6 +670,7 @@ where
             is_stream_closed.clone(),
             cache,
             progress.clone(),
+            frame_clock,
         );
         let f = stream::poll_fn(move |cx| receiver.poll_recv(cx))
             .map(Ok)
@@ -631,6 +688,7 @@ where
     }
 
     // `None` means that the stream was out of items causing that poll loop to shut down.
+    #[cfg(test)]
     async fn send_single(
         &mut self,
         item: SinkItem,
@@ -640,6 +698,18 @@ where
         self.send_recv(item, None, timeout, true, is_fenced).await
     }
 
+    /// Like [`Self::send_single`], also returning when the first byte of the response arrived, if
+    /// the pipeline has a frame clock.
+    async fn send_single_timed(
+        &mut self,
+        item: SinkItem,
+        timeout: Duration,
+        is_fenced: bool,
+    ) -> RedisResult<(Value, Option<Instant>)> {
+        self.send_recv_timed(item, None, timeout, true, is_fenced)
+            .await
+    }
+
     async fn send_recv(
         &mut self,
         input: SinkItem,
@@ -649,6 +719,25 @@ where
         is_atomic: bool,
         is_fenced: bool,
     ) -> Result<Value, RedisError> {
+        self.send_recv_timed(
+            input,
+            pipeline_response_count,
+            timeout,
+            is_atomic,
+            is_fenced,
+        )
+        .await
+        .map(|(value, _)| value)
+    }
+
+    async fn send_recv_timed(
+        &mut self,
+        input: SinkItem,
+        pipeline_response_count: Option<usize>,
+        timeout: Duration,
+        is_atomic: bool,
+        is_fenced: bool,
+    ) -> RedisResult<(Value, Option<Instant>)> {
         let (sender, receiver) = oneshot::channel();
 
         // Acquire a slot in the bounded pipeline channel, distinguishing a
@@ -744,7 +833,7 @@ where
             );
         }
         match recv_result {
-            Ok(Ok(result)) => result,
+            Ok(Ok((result, first_byte_at))) => result.map(|value| (value, first_byte_at)),
             Ok(Err(err)) => {
                 // The `sender` was dropped, likely indicating a failure in the stream.
                 // This error suggests that it's unclear whether the server received the request before the connection failed,
@@ -824,13 +913,21 @@ impl MultiplexedConnection {
     where
         C: Unpin + AsyncRead + AsyncWrite + Send + 'static,
     {
-        let codec = ValueCodec::default()
-            .framed(stream)
-            .and_then(|msg| async move { msg });
-        let (mut pipeline, driver) = Pipeline::new(
+        // The arrival of the responses is only timestamped for the latency breakdown.
+        let frame_clock = glide_connection_options
+            .record_latency_breakdown
+            .then(FrameClock::new);
+        let codec = match &frame_clock {
+            Some(frame_clock) => ValueCodec::with_frame_clock(frame_clock.clone()),
+            None => ValueCodec::default(),
+        }
+        .framed(stream)
+        .and_then(|msg| async move { msg });
+        let (mut pipeline, driver) = Pipeline::with_frame_clock(
             codec,
             glide_connection_options.disconnect_notifier,
             connection_info.redis.cache.clone(),
+            frame_clock,
         );
         let driver = Box::pin(driver);
         let pm = PushManager::new(
@@ -852,7 +949,7 @@ impl MultiplexedConnection {
             .build()
             .await?;
 
-        let driver = {
+        let (protocol, driver) = {
             let auth = setup_connection(
                 &connection_info.redis,
                 &mut con,
@@ -862,10 +959,7 @@ impl MultiplexedConnection {
             futures_util::pin_mut!(auth);
 
             match futures_util::future::select(auth, driver).await {
-                futures_util::future::Either::Left((result, driver)) => {
-                    result?;
-                    driver
-                }
+                futures_util::future::Either::Left((result, driver)) => (result?, driver),
                 futures_util::future::Either::Right(((), _)) => {
                     return Err(RedisError::from((
                         crate::ErrorKind::IoError,
@@ -874,6 +968,7 @@ impl MultiplexedConnection {
                 }
             }
         };
+        con.protocol = protocol;
 
         Ok((con, driver))
     }
@@ -895,8 +990,14 @@ impl MultiplexedConnection {
         let timeout = cmd.response_timeout().unwrap_or(self.response_timeout);
         let result = self
             .pipeline
-            .send_single(cmd.get_packed_command(), timeout, cmd.is_fenced())
-            .await;
+            .send_single_timed(cmd.get_packed_command(), timeout, cmd.is_fenced())
+            .await
+            .map(|(value, first_byte_at)| {
+                if let Some(first_byte_at) = first_byte_at {
+                    cmd.mark_first_byte(first_byte_at);
+                }
+                value
+            });
         if self.protocol != ProtocolVersion::RESP2 {
             if let Err(e) = &result {
                 if e.is_connection_dropped() {
@@ -967,6 +1068,12 @@ impl MultiplexedConnection {
         self.pipeline.set_push_manager(push_manager);
     }
 
+    /// Returns the protocol negotiated with the server - RESP2 if the connection fell back from
+    /// RESP3.
+    pub fn get_protocol(&self) -> ProtocolVersion {
+        self.protocol
+    }
+
     /// For external visibility (glide-core)
     pub fn get_availability_zone(&self) -> Option<String> {
         self.availability_zone.clone()
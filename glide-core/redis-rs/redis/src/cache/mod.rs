@@ -192,6 +192,21 @@ pub enum CacheMetricType {
     TotalLookups,
 }
 
+/// Removes all the entries of every active cache, to release their memory.
+/// Returns the number of flushed caches.
+pub fn flush_all_caches() -> usize {
+    let caches: Vec<Arc<dyn GlideCache>> = CACHE_REGISTRY
+        .read()
+        .unwrap()
+        .values()
+        .filter_map(Weak::upgrade)
+        .collect();
+    for cache in &caches {
+        cache.flush_all();
+    }
+    caches.len()
+}
+
 /// Query a specific cache metric directly from the global cache registry.
 ///
 /// This provides a synchronous path to read cache metrics (which are atomic counters)
@@ -394,6 +409,25 @@ mod tests {
         cleanup_cache("test_operations");
     }
 
+    #[tokio::test]
+    async fn test_flush_all_caches() {
+        use crate::Value;
+        use glide_cache::CachedKeyType;
+
+        let cache = get_or_create_cache("test_flush_all", 10_000, 0, None, false);
+        cache.insert(
+            b"key1".to_vec(),
+            CachedKeyType::String,
+            Value::BulkString(b"value1".to_vec()),
+        );
+        assert_eq!(cache.entry_count(), 1);
+
+        assert!(flush_all_caches() >= 1);
+        assert_eq!(cache.entry_count(), 0);
+
+        cleanup_cache("test_flush_all");
+    }
+
     // ==================== Concurrent Access ====================
 
     fn run_concurrent_cache_test(cache: std::sync::Arc<dyn glide_cache::GlideCache>) {
@@ -109,6 +109,12 @@ pub struct GlideConnectionOptions {
     /// The callback should return `Some(token)` if a valid token is available,
     /// or `None` if token retrieval failed.
     pub iam_token_provider: Option<Arc<dyn IAMTokenProvider>>,
+    #[cfg(feature = "aio")]
+    /// Resolver of the host names of new connections. If `None`, the system resolver is used.
+    pub dns_resolver: Option<crate::aio::DnsResolver>,
+    /// Whether to record when the first byte of each response arrives, for the latency
+    /// breakdown of the commands. Off by default, as it takes a timestamp per response.
+    pub record_latency_breakdown: bool,
 }
 
 /// Trait for providing IAM tokens to the reconnection path.
@@ -139,7 +145,7 @@ impl Client {
                 // Note: tcp_nodelay is hardcoded to true (default) since this deprecated API
                 // doesn't accept GlideConnectionOptions. Modern code should use
                 // get_multiplexed_async_connection which allows configuring tcp_nodelay.
-                self.get_simple_async_connection::<crate::aio::tokio::Tokio>(None, true)
+                self.get_simple_async_connection::<crate::aio::tokio::Tokio>(None, true, None)
                     .await?
             }
         };
@@ -452,7 +458,11 @@ impl Client {
         T: crate::aio::RedisRuntime,
     {
         let (con, ip) = self
-            .get_simple_async_connection::<T>(socket_addr, glide_connection_options.tcp_nodelay)
+            .get_simple_async_connection::<T>(
+                socket_addr,
+                glide_connection_options.tcp_nodelay,
+                glide_connection_options.dns_resolver.as_ref(),
+            )
             .await?;
         crate::aio::MultiplexedConnection::new_with_response_timeout(
             &self.connection_info,
@@ -468,6 +478,7 @@ impl Client {
         &self,
         socket_addr: Option<SocketAddr>,
         tcp_nodelay: bool,
+        dns_resolver: Option<&crate::aio::DnsResolver>,
     ) -> RedisResult<(
         Pin<Box<dyn crate::aio::AsyncStream + Send + Sync>>,
         Option<IpAddr>,
@@ -475,9 +486,13 @@ impl Client {
     where
         T: crate::aio::RedisRuntime,
     {
-        let (conn, ip) =
-            crate::aio::connect_simple::<T>(&self.connection_info, socket_addr, tcp_nodelay)
-                .await?;
+        let (conn, ip) = crate::aio::connect_simple::<T>(
+            &self.connection_info,
+            socket_addr,
+            tcp_nodelay,
+            dns_resolver,
+        )
+        .await?;
         Ok((conn.boxed(), ip))
     }
 
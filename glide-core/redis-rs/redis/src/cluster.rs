@@ -1029,6 +1029,7 @@ pub(crate) fn get_connection_info(
             db: cluster_params.database_id,
             cache: cluster_params.cache,
             server_assisted_cache: cluster_params.server_assisted_cache,
+            protocol_fallback: cluster_params.protocol_fallback,
         },
     })
 }
@@ -1070,6 +1071,12 @@ pub(crate) fn slot_cmd() -> Cmd {
     cmd
 }
 
+pub(crate) fn shards_cmd() -> Cmd {
+    let mut cmd = Cmd::new();
+    cmd.arg("CLUSTER").arg("SHARDS");
+    cmd
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
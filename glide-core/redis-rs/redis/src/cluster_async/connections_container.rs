@@ -2,6 +2,7 @@ use crate::cluster_async::ConnectionFuture;
 use crate::cluster_routing::{Route, ShardAddrs, SlotAddr};
 use crate::cluster_slotmap::{ReadFromReplicaStrategy, SlotMap, SlotMapValue};
 use crate::cluster_topology::TopologyHash;
+use crate::replica_selector::ReplicaSelection;
 use dashmap::DashMap;
 use futures::FutureExt;
 use rand::seq::IteratorRandom;
@@ -518,6 +519,41 @@ where
         self.round_robin_read_from_replica(slot_map_value)
     }
 
+    /// Returns the connection of the replica chosen by `selection` among the connected replicas,
+    /// or of the primary if no replica was chosen.
+    fn read_from_replica_with_selector(
+        &self,
+        slot_map_value: &SlotMapValue,
+        slot: u16,
+        selection: &ReplicaSelection,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        let addrs = &slot_map_value.addrs;
+        let connected: Vec<(String, ConnectionDetails<Connection>)> = addrs
+            .replicas()
+            .iter()
+            .filter_map(|replica| self.connection_details_for_address(replica.as_str()))
+            .collect();
+        let replicas: Vec<(&str, Option<&str>)> = connected
+            .iter()
+            .map(|(address, details)| (address.as_str(), details.az.as_deref()))
+            .collect();
+        match selection.select(Some(slot), &replicas) {
+            Some(index) => connected
+                .into_iter()
+                .nth(index)
+                .map(|(address, details)| (address, details.conn)),
+            None => self.connection_for_address(addrs.primary().as_str()),
+        }
+    }
+
+    /// Returns the replica selection of the client, if it reads with a custom replica selector.
+    pub(crate) fn replica_selection(&self) -> Option<&ReplicaSelection> {
+        match &self.read_from_replica_strategy {
+            ReadFromReplicaStrategy::Custom(selection) => Some(selection),
+            _ => None,
+        }
+    }
+
     fn lookup_route(&self, route: &Route) -> Option<ConnectionAndAddress<Connection>> {
         let slot_map_value = self.slot_map.slot_value_for_route(route)?;
         let addrs = &slot_map_value.addrs;
@@ -549,6 +585,9 @@ where
                         slot_map_value,
                         az.to_string(),
                     ),
+                ReadFromReplicaStrategy::Custom(selection) => {
+                    self.read_from_replica_with_selector(slot_map_value, route.slot(), selection)
+                }
             },
             // when the user strategy per command is replica_preffered
             SlotAddr::ReplicaRequired => match &self.read_from_replica_strategy {
@@ -562,6 +601,9 @@ where
                         slot_map_value,
                         az.to_string(),
                     ),
+                ReadFromReplicaStrategy::Custom(selection) => {
+                    self.read_from_replica_with_selector(slot_map_value, route.slot(), selection)
+                }
                 _ => self.round_robin_read_from_replica(slot_map_value),
             },
         }
@@ -1062,6 +1104,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn get_replica_connection_chosen_by_custom_selector() {
+        use crate::replica_selector::{
+            AzPreferredReplicaSelector, ReplicaCandidate, ReplicaSelector,
+        };
+
+        let container = create_container_with_az_strategy(
+            false,
+            Some(ReadFromReplicaStrategy::Custom(ReplicaSelection::new(
+                Arc::new(AzPreferredReplicaSelector::new("use-1b")),
+            ))),
+        );
+        for _ in 0..3 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+
+        #[derive(Debug)]
+        struct PrimaryOnly;
+        impl ReplicaSelector for PrimaryOnly {
+            fn select(&self, _: Option<u16>, _: &[ReplicaCandidate<'_>]) -> Option<usize> {
+                None
+            }
+        }
+        let container = create_container_with_strategy(
+            ReadFromReplicaStrategy::Custom(ReplicaSelection::new(Arc::new(PrimaryOnly))),
+            false,
+        );
+        assert_eq!(
+            3,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::ReplicaRequired))
+                .unwrap()
+                .1
+        );
+    }
+
     #[test]
     fn get_replica_connection_for_replica_route_if_replica_is_required_even_if_strategy_is_always_from_primary(
     ) {
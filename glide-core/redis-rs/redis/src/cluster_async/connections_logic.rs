@@ -196,6 +196,8 @@ where
             tcp_nodelay: params.tcp_nodelay,
             pubsub_synchronizer: None,
             iam_token_provider: None,
+            dns_resolver: params.dns_resolver.clone(),
+            record_latency_breakdown: params.record_latency_breakdown,
         },
     )
     .await
@@ -35,7 +35,7 @@ use crate::{
     cluster_routing::{Routable, RoutingInfo, ShardUpdateResult},
     cluster_slotmap::SlotMap,
     cluster_topology::{
-        calculate_topology, SlotRefreshState, TopologyHash,
+        calculate_topology, shards_to_slots_view, SlotRefreshState, TopologyHash,
         DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES, DEFAULT_REFRESH_SLOTS_RETRY_BASE_DURATION_MILLIS,
         DEFAULT_REFRESH_SLOTS_RETRY_BASE_FACTOR,
     },
@@ -77,10 +77,11 @@ use crate::aio::DisconnectNotifier;
 use telemetrylib::{GlideOpenTelemetry, GlideSpan, Telemetry};
 
 use crate::{
-    aio::{get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
-    cluster::slot_cmd,
+    aio::{get_socket_addrs, ConnectionLike, DnsResolver, MultiplexedConnection, Runtime},
+    cluster::{shards_cmd, slot_cmd},
     cluster_async::connections_logic::{
-        get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture, RefreshConnectionType,
+        get_host_and_port_from_addr, get_or_create_conn, AsyncClusterNode, ConnectionFuture,
+        RefreshConnectionType,
     },
     cluster_client::{ClusterParams, RetryParams},
     cluster_routing::{
@@ -88,8 +89,8 @@ use crate::{
     },
     push_manager::PushInfo,
     types::ProtocolVersion,
-    Cmd, ConnectionInfo, ErrorKind, IntoConnectionInfo, RedisError, RedisFuture, RedisResult,
-    Value,
+    Cmd, ConnectionInfo, DeadlinePhase, ErrorKind, IntoConnectionInfo, PushKind, RedisError,
+    RedisFuture, RedisResult, Value,
 };
 use futures::{
     future::Shared,
@@ -123,6 +124,9 @@ use self::{
 };
 use crate::types::RetryMethod;
 
+/// How long the connections to the other initial nodes are awaited, once one of them connected.
+const INITIAL_NODES_GRACE_PERIOD: Duration = Duration::from_millis(100);
+
 /// Parses a `"host:port"` address string into its components.
 /// Returns `None` if the address has no `:` separator or the port is not a valid integer.
 fn parse_node_address(address: &str) -> Option<(&str, i64)> {
@@ -310,7 +314,9 @@ where
             })
             .map(|response| match response {
                 Response::ClusterScanResult(new_scan_state_ref, key) => (new_scan_state_ref, key),
-                Response::Single(_) | Response::Multiple(_) => unreachable!(),
+                Response::Single(_) | Response::Multiple(_) | Response::ConnectionInfo(_) => {
+                    unreachable!()
+                }
             })
     }
 
@@ -322,10 +328,11 @@ where
     ) -> RedisResult<Value> {
         log_trace_lazy!("cluster", "route_command");
         let (sender, receiver) = oneshot::channel();
+        let routed_cmd = Arc::new(cmd.clone());
         self.0
             .send(Message {
                 cmd: CmdArg::Cmd {
-                    cmd: Arc::new(cmd.clone()),
+                    cmd: routed_cmd.clone(),
                     routing: routing.into(),
                 },
                 sender,
@@ -337,8 +344,11 @@ where
                     format!("Cluster: Error occurred while trying to send command to internal sender. {e:?}"),
                 ))
             })?;
-        receiver
-            .await
+        let result = receiver.await;
+        // The routed command is a clone, so expose its send and response times to the caller's
+        // command.
+        cmd.inherit_timestamps(&routed_cmd);
+        result
             .unwrap_or_else(|e| {
                 Err(RedisError::from(io::Error::new(
                     io::ErrorKind::BrokenPipe,
@@ -349,7 +359,9 @@ where
             })
             .map(|response| match response {
                 Response::Single(value) => value,
-                Response::ClusterScanResult(..) | Response::Multiple(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Multiple(_)
+                | Response::ConnectionInfo(_) => unreachable!(),
             })
     }
 
@@ -394,7 +406,9 @@ where
             })
             .map(|response| match response {
                 Response::Multiple(values) => values,
-                Response::ClusterScanResult(..) | Response::Single(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Single(_)
+                | Response::ConnectionInfo(_) => unreachable!(),
             })
     }
     /// Update the password used to authenticate with all cluster servers
@@ -460,6 +474,50 @@ where
         self.route_operation_request(Operation::GetUsername).await
     }
 
+    /// Get the addresses of all the nodes the client holds connections to
+    pub async fn get_node_addresses(&mut self) -> RedisResult<Vec<String>> {
+        let addresses = self
+            .route_operation_request(Operation::GetNodeAddresses)
+            .await?;
+        crate::from_owned_redis_value(addresses)
+    }
+
+    /// Get the connection info of the primary serving `slot`, for opening a dedicated connection
+    /// to it - with the same authentication, TLS and database as the cluster connections.
+    pub async fn get_primary_connection_info(&mut self, slot: u16) -> RedisResult<ConnectionInfo> {
+        let (sender, receiver) = oneshot::channel();
+        self.0
+            .send(Message {
+                cmd: CmdArg::OperationRequest(Operation::GetPrimaryConnectionInfo(slot)),
+                sender,
+            })
+            .await
+            .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+
+        receiver
+            .await
+            .unwrap_or_else(|err| {
+                Err(RedisError::from(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    err.to_string(),
+                )))
+            })
+            .map(|response| match response {
+                Response::ConnectionInfo(info) => *info,
+                Response::Single(_) | Response::ClusterScanResult(..) | Response::Multiple(_) => {
+                    unreachable!()
+                }
+            })
+    }
+
+    /// Close the connections to the node at `address` and connect to it again in the background.
+    ///
+    /// Requests routed to the node while it reconnects wait for the new connections.
+    pub async fn refresh_node_connections(&mut self, address: String) -> RedisResult<Value> {
+        self.route_operation_request(Operation::RefreshNodeConnections(address))
+            .await
+    }
+
     /// Routes an operation request to the appropriate handler.
     async fn route_operation_request(
         &mut self,
@@ -484,7 +542,9 @@ where
             })
             .map(|response| match response {
                 Response::Single(values) => values,
-                Response::ClusterScanResult(..) | Response::Multiple(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Multiple(_)
+                | Response::ConnectionInfo(_) => unreachable!(),
             })
     }
 }
@@ -642,6 +702,21 @@ impl<C> Dispose for ClusterConnInner<C> {
                 count = node.connections_count();
             }
             Telemetry::decr_total_connections(count);
+
+            // Include the nodes that are still being reconnected to, they are the most likely to have errors
+            let addresses: Vec<String> = conn_lock
+                .connection_map()
+                .iter()
+                .map(|node| node.key().clone())
+                .chain(
+                    conn_lock
+                        .refresh_conn_state
+                        .refresh_address_in_progress
+                        .keys()
+                        .cloned(),
+                )
+                .collect();
+            Telemetry::log_connection_errors(&addresses);
         }
 
         if let Some(handle) = self.periodic_checks_handler {
@@ -780,6 +855,9 @@ enum Operation {
     UpdateConnectionUsername(Option<String>),
     UpdateConnectionProtocol(ProtocolVersion),
     GetUsername,
+    GetNodeAddresses,
+    GetPrimaryConnectionInfo(u16),
+    RefreshNodeConnections(String),
 }
 
 fn boxed_sleep(duration: Duration) -> BoxFuture<'static, ()> {
@@ -791,6 +869,7 @@ pub(crate) enum Response {
     Single(Value),
     ClusterScanResult(ScanStateRC, Vec<Value>),
     Multiple(Vec<Value>),
+    ConnectionInfo(Box<ConnectionInfo>),
 }
 
 #[derive(Debug)]
@@ -967,11 +1046,55 @@ pin_project! {
 /// For fan-out commands, `Arc<Cmd>` is cloned per shard — each clone
 /// shares the same tracker. The slot is released only when all
 /// sub-commands finish.
-struct InflightSlotGuard(Arc<AtomicIsize>);
+struct InflightSlotGuard {
+    counter: Arc<AtomicIsize>,
+    notifier: Option<Arc<InflightReleaseNotifier>>,
+}
 
 impl Drop for InflightSlotGuard {
     fn drop(&mut self) {
-        self.0.fetch_add(1, Ordering::SeqCst);
+        self.counter.fetch_add(1, Ordering::SeqCst);
+        if let Some(notifier) = &self.notifier {
+            notifier.released();
+        }
+    }
+}
+
+/// Wakes the tasks waiting for inflight slots to be released. Releasing a slot only notifies
+/// while a task is waiting, so the notifier costs an atomic load otherwise.
+#[derive(Debug, Default)]
+pub struct InflightReleaseNotifier {
+    waiting: AtomicUsize,
+    notify: Notify,
+}
+
+impl InflightReleaseNotifier {
+    /// Waits until `done` returns true, checking it again whenever a slot is released.
+    pub async fn wait_until(&self, done: impl Fn() -> bool) {
+        // Stops counting the task as waiting when it's done, or dropped by a timeout.
+        struct Waiting<'a>(&'a AtomicUsize);
+        impl Drop for Waiting<'_> {
+            fn drop(&mut self) {
+                self.0.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+        self.waiting.fetch_add(1, Ordering::SeqCst);
+        let _waiting = Waiting(&self.waiting);
+        loop {
+            let mut notified = std::pin::pin!(self.notify.notified());
+            // Registered before checking, so a release right after the check isn't missed.
+            notified.as_mut().enable();
+            if done() {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    fn released(&self) {
+        if self.waiting.load(Ordering::SeqCst) > 0 {
+            self.notify.notify_waiters();
+        }
     }
 }
 
@@ -987,6 +1110,21 @@ impl InflightRequestTracker {
     /// Try to reserve one inflight slot atomically. Returns `None` if
     /// no slots are available (counter <= 0).
     pub fn try_new(counter: Arc<AtomicIsize>) -> Option<Self> {
+        Self::reserve(counter, None)
+    }
+
+    /// Like [`Self::try_new`], waking the tasks waiting on `notifier` when the slot is released.
+    pub fn try_new_notifying(
+        counter: Arc<AtomicIsize>,
+        notifier: Arc<InflightReleaseNotifier>,
+    ) -> Option<Self> {
+        Self::reserve(counter, Some(notifier))
+    }
+
+    fn reserve(
+        counter: Arc<AtomicIsize>,
+        notifier: Option<Arc<InflightReleaseNotifier>>,
+    ) -> Option<Self> {
         loop {
             let current = counter.load(Ordering::SeqCst);
             if current <= 0 {
@@ -997,7 +1135,7 @@ impl InflightRequestTracker {
                 .is_ok()
             {
                 return Some(Self {
-                    _guard: Arc::new(InflightSlotGuard(counter)),
+                    _guard: Arc::new(InflightSlotGuard { counter, notifier }),
                 });
             }
         }
@@ -1043,6 +1181,36 @@ mod inflight_tracker_tests {
         drop(clone2);
         assert_eq!(counter.load(Ordering::Relaxed), 5); // last clone → released
     }
+
+    #[tokio::test]
+    async fn notifier_wakes_waiters_when_slots_are_released() {
+        let counter = Arc::new(AtomicIsize::new(5));
+        let notifier = Arc::new(InflightReleaseNotifier::default());
+        let trackers: Vec<_> = (0..2)
+            .map(|_| {
+                InflightRequestTracker::try_new_notifying(counter.clone(), notifier.clone())
+                    .unwrap()
+            })
+            .collect();
+
+        let waiter = tokio::spawn({
+            let (counter, notifier) = (counter.clone(), notifier.clone());
+            async move {
+                notifier
+                    .wait_until(|| counter.load(Ordering::SeqCst) == 5)
+                    .await
+            }
+        });
+        while notifier.waiting.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        drop(trackers);
+        tokio::time::timeout(Duration::from_secs(5), waiter)
+            .await
+            .expect("the waiter should be woken")
+            .unwrap();
+        assert_eq!(notifier.waiting.load(Ordering::SeqCst), 0);
+    }
 }
 
 #[cfg(test)]
@@ -1099,6 +1267,8 @@ mod iam_token_refresh_tests {
             tcp_nodelay: false,
             pubsub_synchronizer: None,
             iam_token_provider: provider,
+            dns_resolver: None,
+            record_latency_breakdown: false,
         }
     }
 
@@ -1315,9 +1485,27 @@ where
                     return next;
                 }
                 request.retry = request.retry.saturating_add(1);
-                // Notify diagnostic handle of retry
-                if let CmdArg::Cmd { cmd, .. } = &request.info.cmd {
-                    cmd.mark_retry();
+                // Notify diagnostic handle of retry, and fail instead of retrying once the
+                // request's deadline passed
+                let deadline_error = match &request.info.cmd {
+                    CmdArg::Cmd { cmd, .. } => {
+                        cmd.mark_retry();
+                        match cmd.deadline() {
+                            Some(deadline) if deadline.is_expired() => {
+                                Some(deadline.expired_error())
+                            }
+                            Some(deadline) => {
+                                deadline.enter(DeadlinePhase::of_retry(&err));
+                                None
+                            }
+                            None => None,
+                        }
+                    }
+                    _ => None,
+                };
+                if let Some(deadline_error) = deadline_error {
+                    self.respond(Err(deadline_error));
+                    return Next::Done.into();
                 }
                 // Record retry attempts metric if telemetry is initialized
                 if let Err(e) = GlideOpenTelemetry::record_retry_attempt() {
@@ -1535,6 +1723,8 @@ where
             tcp_nodelay: cluster_params.tcp_nodelay,
             pubsub_synchronizer,
             iam_token_provider,
+            dns_resolver: cluster_params.dns_resolver.clone(),
+            record_latency_breakdown: cluster_params.record_latency_breakdown,
         };
 
         let connections = Self::create_initial_connections(
@@ -1606,124 +1796,154 @@ where
     /// Go through each of the initial nodes and attempt to retrieve all IP entries from them.
     /// If there's a DNS endpoint that directs to several IP addresses, add all addresses to the initial nodes list.
     /// Returns a vector of tuples, each containing a node's address (including the hostname) and its corresponding SocketAddr if retrieved.
+    /// The initial nodes are resolved concurrently, and the addresses keep the order of the initial nodes.
     pub(crate) async fn try_to_expand_initial_nodes(
         initial_nodes: &[ConnectionInfo],
+        dns_resolver: Option<&DnsResolver>,
     ) -> Vec<(String, Option<SocketAddr>)> {
-        stream::iter(initial_nodes)
-            .fold(
-                Vec::with_capacity(initial_nodes.len()),
-                |mut acc, info| async {
-                    let (host, port) = match &info.addr {
-                        crate::ConnectionAddr::Tcp(host, port) => (host, port),
-                        crate::ConnectionAddr::TcpTls {
-                            host,
-                            port,
-                            insecure: _,
-                            tls_params: _,
-                        } => (host, port),
-                        crate::ConnectionAddr::Unix(_) => {
-                            // We don't support multiple addresses for a Unix address. Store the initial node address and continue
-                            acc.push((info.addr.to_string(), None));
-                            return acc;
-                        }
-                    };
-                    match get_socket_addrs(host, *port).await {
-                        Ok(socket_addrs) => {
-                            for addr in socket_addrs {
-                                acc.push((info.addr.to_string(), Some(addr)));
-                            }
-                        }
-                        Err(_) => {
-                            // Couldn't find socket addresses, store the initial node address and continue
-                            acc.push((info.addr.to_string(), None));
-                        }
-                    };
-                    acc
-                },
-            )
-            .await
+        let resolved = futures::future::join_all(initial_nodes.iter().map(|info| async move {
+            let (host, port) = match &info.addr {
+                crate::ConnectionAddr::Tcp(host, port) => (host, port),
+                crate::ConnectionAddr::TcpTls {
+                    host,
+                    port,
+                    insecure: _,
+                    tls_params: _,
+                } => (host, port),
+                crate::ConnectionAddr::Unix(_) => {
+                    // We don't support multiple addresses for a Unix address. Store the initial node address and continue
+                    return vec![(info.addr.to_string(), None)];
+                }
+            };
+            match get_socket_addrs(host, *port, dns_resolver).await {
+                Ok(socket_addrs) => socket_addrs
+                    .into_iter()
+                    .map(|addr| (info.addr.to_string(), Some(addr)))
+                    .collect(),
+                Err(_) => {
+                    // Couldn't find socket addresses, store the initial node address and continue
+                    vec![(info.addr.to_string(), None)]
+                }
+            }
+        }))
+        .await;
+        resolved.into_iter().flatten().collect()
     }
 
+    /// Connects to the initial nodes concurrently. Once the first one connects, the others are awaited
+    /// for [`INITIAL_NODES_GRACE_PERIOD`], and the attempts still pending are cancelled - the slots are
+    /// refreshed from the connected nodes, and the connections to the rest of the cluster are created
+    /// once the topology is known.
     async fn create_initial_connections(
         initial_nodes: &[ConnectionInfo],
         params: &ClusterParams,
         glide_connection_options: GlideConnectionOptions,
     ) -> RedisResult<ConnectionMap<C>> {
-        let initial_nodes: Vec<(String, Option<SocketAddr>)> =
-            Self::try_to_expand_initial_nodes(initial_nodes).await;
-        let connections =
-            stream::iter(initial_nodes.iter().cloned())
-                .map(|(node_addr, socket_addr)| {
-                    let params: ClusterParams = params.clone();
-                    let glide_connection_options = glide_connection_options.clone();
-                    // set subscriptions to none, they will be applied upon the topology discovery
-
-                    async move {
-                        let result = connect_and_check::<C>(
-                            &node_addr,
-                            params,
-                            socket_addr,
-                            RefreshConnectionType::AllConnections,
-                            None,
-                            glide_connection_options,
-                        )
-                        .await
-                        .get_node();
-                        // The PushManager is initialized with connection_info.addr
-                        // (the original hostname, e.g. "localhost:6379"), but the
-                        // ConnectionsMap key uses the resolved IP from socket_addr
-                        // (e.g. "127.0.0.1:6379"). When these differ, align them so
-                        // PubSub synchronization can match subscriptions to nodes.
-                        let (node_address, push_manager_needs_update) =
-                            if let Some(socket_addr) = socket_addr {
-                                let resolved = socket_addr.to_string();
-                                let differs = resolved != node_addr;
-                                (resolved, differs)
-                            } else {
-                                (node_addr, false)
-                            };
-                        if push_manager_needs_update {
-                            if let Ok(ref node) = result {
-                                node.user_connection
-                                    .conn
-                                    .clone()
-                                    .await
-                                    .update_push_manager_node_address(node_address.clone());
-                            }
-                        }
-                        result.map(|node| (node_address, node))
-                    }
-                })
-                .buffer_unordered(initial_nodes.len())
-                .fold(
-                    (
-                        ConnectionsMap(DashMap::with_capacity(initial_nodes.len())),
-                        None,
-                    ),
-                    |connections: (ConnectionMap<C>, Option<String>),
-                     addr_conn_res: RedisResult<_>| async move {
-                        match addr_conn_res {
-                            Ok((addr, node)) => {
-                                connections.0 .0.insert(addr, node);
-                                (connections.0, None)
-                            }
-                            Err(e) => (connections.0, Some(e.to_string())),
-                        }
-                    },
+        let initial_nodes: Vec<(String, Option<SocketAddr>)> = Self::try_to_expand_initial_nodes(
+            initial_nodes,
+            glide_connection_options.dns_resolver.as_ref(),
+        )
+        .await;
+        if initial_nodes.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::IoError,
+                "Failed to create initial connections",
+                "No initial nodes".to_string(),
+            )));
+        }
+        let mut attempts = initial_nodes
+            .into_iter()
+            .map(|(node_addr, socket_addr)| {
+                Self::connect_to_initial_node(
+                    node_addr,
+                    socket_addr,
+                    params.clone(),
+                    glide_connection_options.clone(),
                 )
-                .await;
-        if connections.0 .0.is_empty() {
+            })
+            .collect::<FuturesUnordered<_>>();
+        let connections = ConnectionsMap(DashMap::with_capacity(attempts.len()));
+        let mut last_error = None;
+        while let Some(result) = attempts.next().await {
+            match result {
+                Ok((node_address, node)) => {
+                    connections.0.insert(node_address, node);
+                    break;
+                }
+                Err(err) => last_error = Some(err),
+            }
+        }
+        if connections.0.is_empty() {
             return Err(RedisError::from((
                 ErrorKind::IoError,
                 "Failed to create initial connections",
-                connections.1.unwrap_or("".to_string()),
+                last_error.map(|err| err.to_string()).unwrap_or_default(),
             )));
         }
+        // The seeds that connect shortly after the first one are kept too, so that the slots are
+        // refreshed from the views of several nodes. Dropping the pending attempts cancels them.
+        let _ = tokio::time::timeout(INITIAL_NODES_GRACE_PERIOD, async {
+            while let Some(result) = attempts.next().await {
+                if let Ok((node_address, node)) = result {
+                    connections.0.insert(node_address, node);
+                }
+            }
+        })
+        .await;
         log_info_lazy!(
             "cluster",
-            format!("Connected to initial nodes:\n{}", connections.0)
+            format!("Connected to initial nodes:\n{connections}")
         );
-        Ok(connections.0)
+        Ok(connections)
+    }
+
+    /// Connects to an initial node, and returns the connection with the address it's stored under.
+    async fn connect_to_initial_node(
+        node_addr: String,
+        socket_addr: Option<SocketAddr>,
+        params: ClusterParams,
+        glide_connection_options: GlideConnectionOptions,
+    ) -> RedisResult<(String, AsyncClusterNode<C>)> {
+        // set subscriptions to none, they will be applied upon the topology discovery
+        let result = connect_and_check::<C>(
+            &node_addr,
+            params,
+            socket_addr,
+            RefreshConnectionType::AllConnections,
+            None,
+            glide_connection_options,
+        )
+        .await
+        .get_node();
+        // The PushManager is initialized with connection_info.addr
+        // (the original hostname, e.g. "localhost:6379"), but the
+        // ConnectionsMap key uses the resolved IP from socket_addr
+        // (e.g. "127.0.0.1:6379"). When these differ, align them so
+        // PubSub synchronization can match subscriptions to nodes.
+        let (node_address, push_manager_needs_update) = if let Some(socket_addr) = socket_addr {
+            let resolved = socket_addr.to_string();
+            let differs = resolved != node_addr;
+            (resolved, differs)
+        } else {
+            (node_addr, false)
+        };
+        if let Err(ref err) = result {
+            Telemetry::record_connection_error(
+                &node_address,
+                &format!("{:?}", err.kind()),
+                &err.to_string(),
+            );
+        }
+        if push_manager_needs_update {
+            if let Ok(ref node) = result {
+                node.user_connection
+                    .conn
+                    .clone()
+                    .await
+                    .update_push_manager_node_address(node_address.clone());
+            }
+        }
+        result.map(|node| (node_address, node))
     }
 
     /// If IAM authentication is configured, refresh the token in `cluster_params` so that
@@ -1973,6 +2193,11 @@ where
                             break;
                         }
                         Err(ref err) => {
+                            Telemetry::record_connection_error(
+                                &address_clone_for_task,
+                                &format!("{:?}", err.kind()),
+                                &err.to_string(),
+                            );
                             if first_attempt {
                                 if let Some(ref mut conn_state) = inner_clone
                                     .conn_lock
@@ -2094,7 +2319,9 @@ where
         // Helper: extract a single Value from a Response::Single
         let extract_result = |response| match response {
             Response::Single(value) => value,
-            Response::Multiple(_) | Response::ClusterScanResult(_, _) => unreachable!(
+            Response::Multiple(_)
+            | Response::ClusterScanResult(_, _)
+            | Response::ConnectionInfo(_) => unreachable!(
                 "aggregate_results only handles `Response::Single` for multi-node commands"
             ),
         };
@@ -2701,21 +2928,25 @@ where
                             // If it's a DNS endpoint, it could have been stored in the existing connections vector
                             // using the resolved IP address instead of the DNS endpoint's name.
                             // We shall check if a connection already exists under the resolved IP name.
-                            let conn = if let Some((host, port)) =
-                                get_host_and_port_from_addr(&addr)
-                            {
-                                if let Ok(mut socket_addresses) = get_socket_addrs(host, port).await
-                                {
-                                    let conn_lock = inner.conn_lock.read();
-                                    socket_addresses.find_map(|socket_addr| {
-                                        conn_lock.node_for_address(&socket_addr.to_string())
-                                    })
+                            let conn =
+                                if let Some((host, port)) = get_host_and_port_from_addr(&addr) {
+                                    if let Ok(socket_addresses) = get_socket_addrs(
+                                        host,
+                                        port,
+                                        glide_connection_options.dns_resolver.as_ref(),
+                                    )
+                                    .await
+                                    {
+                                        let conn_lock = inner.conn_lock.read();
+                                        socket_addresses.into_iter().find_map(|socket_addr| {
+                                            conn_lock.node_for_address(&socket_addr.to_string())
+                                        })
+                                    } else {
+                                        None
+                                    }
                                 } else {
                                     None
-                                }
-                            } else {
-                                None
-                            };
+                                };
 
                             // If we found a connection by IP lookup, update the PushManager. This ensures
                             // the PushManager stores the DNS address (which matches the connection_map key)
@@ -2747,8 +2978,16 @@ where
             }
         });
 
-        // Await all connection futures, this is bounded by `connection_timeout`.
-        let results = futures::future::join_all(connection_futures).await;
+        // Await all connection futures, this is bounded by `connection_timeout`. At most
+        // `connection_concurrency_limit` connections are set up at a time.
+        let concurrency_limit = cluster_params
+            .connection_concurrency_limit
+            .unwrap_or(nodes_len)
+            .max(1);
+        let results: Vec<_> = stream::iter(connection_futures)
+            .buffer_unordered(concurrency_limit)
+            .collect()
+            .await;
 
         // Collect successful connections and extract resolved IPs for reverse lookup.
         let new_connections = ConnectionsMap(DashMap::with_capacity(nodes_len));
@@ -2777,6 +3016,14 @@ where
         // TODO - Maybe we can take the running refresh tasks and use them instead of running new connection creation
         write_guard.refresh_conn_state.clear_refresh_state();
         new_slots.carry_over_ips_from(&write_guard.slot_map);
+        // The initial slot map is empty, so the first refresh isn't reported as a topology change.
+        let topology_changes = if inner.get_cluster_param(|params| params.topology_change_events)
+            && !write_guard.slot_map.all_node_addresses().is_empty()
+        {
+            write_guard.slot_map.topology_changes(&new_slots)
+        } else {
+            Vec::new()
+        };
         let read_from_replicas =
             inner.get_cluster_param(|params| params.read_from_replicas.clone());
         *write_guard = ConnectionsContainer::new(
@@ -2792,6 +3039,17 @@ where
         if let Some(sync) = &inner.glide_connection_options.pubsub_synchronizer {
             sync.handle_topology_refresh(&write_guard.slot_map);
         }
+        drop(write_guard);
+
+        if let Some(push_sender) = &inner.glide_connection_options.push_sender {
+            for event in topology_changes {
+                log_info_lazy!("slot_refresh", format!("Topology change: {event:?}"));
+                let _ = push_sender.send(PushInfo {
+                    kind: PushKind::TopologyChange,
+                    data: event.to_push_data(),
+                });
+            }
+        }
 
         log_info_lazy!(
             "slot_refresh",
@@ -3047,6 +3305,8 @@ where
         };
         log_trace_lazy!("cluster", "route request to single node");
 
+        // A custom replica selector chooses by the latency and failures of the nodes
+        let replica_selection = core.conn_lock.read().replica_selection().cloned();
         let (address, mut conn) = Self::get_connection(routing, core, Some(cmd.clone()))
             .await
             .map_err(|err| (OperationTarget::NotFound, err))?;
@@ -3055,13 +3315,28 @@ where
         }
 
         // Mark command as sent for watchdog diagnostics
-        cmd.watchdog_phase
-            .store(crate::cmd::PHASE_SENT, std::sync::atomic::Ordering::Release);
+        cmd.mark_sent();
 
-        conn.req_packed_command(&cmd)
-            .await
-            .map(Response::Single)
-            .map_err(|err| (address.into(), err))
+        let Some(replica_selection) = replica_selection else {
+            return conn
+                .req_packed_command(&cmd)
+                .await
+                .map(Response::Single)
+                .map_err(|err| (address.into(), err));
+        };
+        let start = std::time::Instant::now();
+        match conn.req_packed_command(&cmd).await {
+            Ok(value) => {
+                replica_selection.record_success(&address, start.elapsed());
+                Ok(Response::Single(value))
+            }
+            Err(err) => {
+                if err.is_unrecoverable_error() || err.is_timeout() {
+                    replica_selection.record_failure(&address);
+                }
+                Err((address.into(), err))
+            }
+        }
     }
 
     async fn try_pipeline_request(
@@ -3078,8 +3353,7 @@ where
 
         // Mark diagnostic handles on pipeline commands as sent
         for cmd in pipeline.cmd_iter() {
-            cmd.watchdog_phase
-                .store(crate::cmd::PHASE_SENT, std::sync::atomic::Ordering::Release);
+            cmd.mark_sent();
         }
 
         conn.req_packed_commands(&pipeline, offset, count, None)
@@ -3165,6 +3439,45 @@ where
                     };
                     Ok(Response::Single(username))
                 }
+                Operation::GetNodeAddresses => {
+                    let addresses = core
+                        .conn_lock
+                        .read()
+                        .all_node_connections()
+                        .map(|(address, _)| Value::BulkString(address.as_bytes().to_vec()))
+                        .collect();
+                    Ok(Response::Single(Value::Array(addresses)))
+                }
+                Operation::GetPrimaryConnectionInfo(slot) => {
+                    let address = core
+                        .conn_lock
+                        .read()
+                        .address_for_route(&Route::new(slot, cluster_routing::SlotAddr::Master))
+                        .ok_or_else(|| {
+                            (
+                                OperationTarget::NotFound,
+                                RedisError::from((
+                                    ErrorKind::AllConnectionsUnavailable,
+                                    "No primary serves the slot",
+                                    slot.to_string(),
+                                )),
+                            )
+                        })?;
+                    let params = core.get_cluster_param(|params| params.clone());
+                    let info = crate::cluster::get_connection_info(&address, params)
+                        .map_err(|err| (OperationTarget::FatalError, err))?;
+                    Ok(Response::ConnectionInfo(Box::new(info)))
+                }
+                Operation::RefreshNodeConnections(address) => {
+                    ClusterConnInner::trigger_refresh_connection_tasks(
+                        core,
+                        HashSet::from([address]),
+                        RefreshConnectionType::AllConnections,
+                        false,
+                    )
+                    .await;
+                    Ok(Response::Single(Value::Okay))
+                }
             },
         }
     }
@@ -4109,8 +4422,11 @@ where
 
     // Resolve initial nodes and select random addresses for topology query.
     let selected_pairs = {
-        let resolved =
-            ClusterConnInner::<C>::try_to_expand_initial_nodes(&inner.initial_nodes).await;
+        let resolved = ClusterConnInner::<C>::try_to_expand_initial_nodes(
+            &inner.initial_nodes,
+            inner.glide_connection_options.dns_resolver.as_ref(),
+        )
+        .await;
         let mut rng = rand::rng();
         resolved
             .into_iter()
@@ -4350,9 +4666,32 @@ where
         };
     };
 
+    let tls_mode = inner.get_cluster_param(|params| params.tls);
+    let topology_from_cluster_shards =
+        inner.get_cluster_param(|params| params.topology_from_cluster_shards);
     let topology_join_results =
         futures::future::join_all(requested_nodes.into_iter().map(|(addr, conn)| async move {
             let mut conn: C = conn.await;
+            if topology_from_cluster_shards {
+                match conn.req_packed_command(&shards_cmd()).await {
+                    Ok(shards) => match shards_to_slots_view(&shards, tls_mode) {
+                        Ok(view) => return (addr, Ok(view)),
+                        Err(err) => log_warn_lazy!(
+                            "slot_refresh",
+                            format!("Failed to parse CLUSTER SHARDS from {addr}: {err}")
+                        ),
+                    },
+                    // ACL errors are returned as is, since CLUSTER SLOTS would fail the same way
+                    Err(err) if err.kind() == ErrorKind::PermissionDenied => {
+                        return (addr, Err(err));
+                    }
+                    Err(err) if err.is_unrecoverable_error() => return (addr, Err(err)),
+                    Err(err) => log_debug_lazy!(
+                        "slot_refresh",
+                        format!("CLUSTER SHARDS failed on {addr}, using CLUSTER SLOTS: {err}")
+                    ),
+                }
+            }
             let res = conn.req_packed_command(&slot_cmd()).await;
             (addr, res)
         }))
@@ -4387,8 +4726,6 @@ where
             .ok()
             .and_then(|value| get_host_and_port_from_addr(addr).map(|(host, _)| (host, value)))
     });
-    let tls_mode = inner.get_cluster_param(|params| params.tls);
-
     let read_from_replicas = inner.get_cluster_param(|params| params.read_from_replicas.clone());
     let address_resolver = inner.get_cluster_param(|params| params.address_resolver.clone());
     TopologyQueryResult {
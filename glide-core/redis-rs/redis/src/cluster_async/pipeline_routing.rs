@@ -635,6 +635,14 @@ fn process_pipeline_responses(
                 },
                 RetryMethod::NoRetry,
             ),
+            // We are not supposed to get in here, but it's better than using unreachable!()
+            Ok(Ok(Response::ConnectionInfo(_))) => (
+                ServerError::ExtensionError {
+                    code: ("ConnectionInfoError".to_string()),
+                    detail: (Some("Received connection info inside a pipeline.".to_string())),
+                },
+                RetryMethod::NoRetry,
+            ),
 
             // If we received a redis error, we will convert it to a ServerError and append it to the relevant indices
             Ok(Err(err)) => {
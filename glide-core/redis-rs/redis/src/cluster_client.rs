@@ -47,11 +47,18 @@ struct BuilderParams {
     protocol: ProtocolVersion,
     reconnect_retry_strategy: Option<RetryStrategy>,
     refresh_topology_from_initial_nodes: bool,
+    topology_from_cluster_shards: bool,
+    topology_change_events: bool,
+    connection_concurrency_limit: Option<usize>,
     database_id: i64,
     tcp_nodelay: bool,
+    record_latency_breakdown: bool,
     cache: Option<Arc<dyn GlideCache>>,
     server_assisted_cache: bool,
+    protocol_fallback: bool,
     address_resolver: Option<Arc<dyn AddressResolver>>,
+    #[cfg(feature = "cluster-async")]
+    dns_resolver: Option<crate::aio::DnsResolver>,
 }
 
 #[derive(Clone)]
@@ -153,12 +160,24 @@ pub struct ClusterParams {
     pub(crate) protocol: ProtocolVersion,
     pub(crate) reconnect_retry_strategy: Option<RetryStrategy>,
     pub(crate) refresh_topology_from_initial_nodes: bool,
+    /// Discover the topology with `CLUSTER SHARDS`, falling back to `CLUSTER SLOTS`.
+    pub(crate) topology_from_cluster_shards: bool,
+    /// Send a [`crate::PushKind::TopologyChange`] push for every topology change found by a slot refresh.
+    pub(crate) topology_change_events: bool,
+    /// The most connections to the discovered nodes that are set up at a time. If `None`, all are set up at once.
+    pub(crate) connection_concurrency_limit: Option<usize>,
     pub(crate) database_id: i64,
     pub(crate) tcp_nodelay: bool,
+    /// Record when the first byte of each response arrives, for the latency breakdown.
+    pub(crate) record_latency_breakdown: bool,
     pub(crate) cache: Option<Arc<dyn GlideCache>>,
     pub(crate) server_assisted_cache: bool,
+    pub(crate) protocol_fallback: bool,
     /// Optional callback for resolving addresses before connection.
     pub(crate) address_resolver: Option<Arc<dyn AddressResolver>>,
+    /// Resolver of the host names of new connections. If `None`, the system resolver is used.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) dns_resolver: Option<crate::aio::DnsResolver>,
 }
 
 impl ClusterParams {
@@ -189,11 +208,18 @@ impl ClusterParams {
             protocol: value.protocol,
             reconnect_retry_strategy: value.reconnect_retry_strategy,
             refresh_topology_from_initial_nodes: value.refresh_topology_from_initial_nodes,
+            topology_from_cluster_shards: value.topology_from_cluster_shards,
+            topology_change_events: value.topology_change_events,
+            connection_concurrency_limit: value.connection_concurrency_limit,
             database_id: value.database_id,
             tcp_nodelay: value.tcp_nodelay,
+            record_latency_breakdown: value.record_latency_breakdown,
             cache: value.cache,
             server_assisted_cache: value.server_assisted_cache,
+            protocol_fallback: value.protocol_fallback,
             address_resolver: value.address_resolver,
+            #[cfg(feature = "cluster-async")]
+            dns_resolver: value.dns_resolver,
         })
     }
 }
@@ -222,11 +248,18 @@ impl ClusterParams {
             protocol: ProtocolVersion::RESP2,
             reconnect_retry_strategy: None,
             refresh_topology_from_initial_nodes: false,
+            topology_from_cluster_shards: false,
+            topology_change_events: false,
+            connection_concurrency_limit: None,
             database_id: 0,
             tcp_nodelay: false,
+            record_latency_breakdown: false,
             cache: None,
             server_assisted_cache: false,
+            protocol_fallback: false,
             address_resolver: None,
+            #[cfg(feature = "cluster-async")]
+            dns_resolver: None,
         }
     }
 }
@@ -540,6 +573,40 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Enables topology discovery with `CLUSTER SHARDS`.
+    ///
+    /// When enabled, the client queries `CLUSTER SHARDS` to build its view of the cluster topology,
+    /// and replicas that aren't healthy - e.g. replicas that are still loading their data - aren't
+    /// used for reads. Nodes that don't support `CLUSTER SHARDS` are queried with `CLUSTER SLOTS`.
+    pub fn topology_from_cluster_shards(
+        mut self,
+        topology_from_cluster_shards: bool,
+    ) -> ClusterClientBuilder {
+        self.builder_params.topology_from_cluster_shards = topology_from_cluster_shards;
+        self
+    }
+
+    /// Enables topology change events.
+    ///
+    /// When enabled, every slot refresh that finds added or removed nodes, or a shard whose
+    /// replica was promoted to primary, sends a [`crate::PushKind::TopologyChange`] push for each
+    /// change to the push sender of the connection. The push data is described in
+    /// [`crate::cluster_topology::TopologyChangeEvent::to_push_data`].
+    pub fn topology_change_events(mut self, topology_change_events: bool) -> ClusterClientBuilder {
+        self.builder_params.topology_change_events = topology_change_events;
+        self
+    }
+
+    /// Sets the most connections to the cluster's nodes that are set up at a time, after the
+    /// topology is discovered.
+    ///
+    /// Connecting to every node of a large cluster at once may overload the client's host or the
+    /// network. By default, all the connections are set up at once.
+    pub fn connection_concurrency_limit(mut self, limit: usize) -> ClusterClientBuilder {
+        self.builder_params.connection_concurrency_limit = Some(limit);
+        self
+    }
+
     /// Sets the TCP_NODELAY socket option.
     ///
     /// When true, disables Nagle's algorithm for lower latency.
@@ -550,6 +617,15 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets whether the connections record when the first byte of each response arrives, for
+    /// the latency breakdown of the commands.
+    ///
+    /// Defaults to false.
+    pub fn record_latency_breakdown(mut self, record: bool) -> ClusterClientBuilder {
+        self.builder_params.record_latency_breakdown = record;
+        self
+    }
+
     /// Sets an address resolver callback for resolving node addresses.
     ///
     /// When set, the resolver will be called to resolve host:port pairs
@@ -560,6 +636,16 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets the resolver of the host names of new connections.
+    ///
+    /// Host names are resolved again for every connection, including reconnects, so the client
+    /// follows DNS changes. If not set, the system resolver is used.
+    #[cfg(feature = "cluster-async")]
+    pub fn dns_resolver(mut self, dns_resolver: crate::aio::DnsResolver) -> ClusterClientBuilder {
+        self.builder_params.dns_resolver = Some(dns_resolver);
+        self
+    }
+
     /// Enables timing out on slow connection time.
     ///
     /// If enabled, the cluster will only wait the given time on each connection attempt to each node.
@@ -582,6 +668,13 @@ impl ClusterClientBuilder {
         self
     }
 
+    /// Sets whether connections fall back to RESP2 when RESP3 was requested but the server
+    /// doesn't support the `HELLO` command.
+    pub fn protocol_fallback(mut self, protocol_fallback: bool) -> ClusterClientBuilder {
+        self.builder_params.protocol_fallback = protocol_fallback;
+        self
+    }
+
     /// Sets the database ID for the new ClusterClient.
     ///
     /// Note: Database selection in cluster mode requires server support for multiple databases.
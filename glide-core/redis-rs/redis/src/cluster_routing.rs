@@ -635,18 +635,33 @@ impl ResponsePolicy {
     }
 }
 
-enum RouteBy {
+/// How a command is routed in cluster mode, based on its name alone.
+/// Argument indices count the command name as argument 0.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RouteBy {
+    /// Sent to all nodes.
     AllNodes,
+    /// Sent to all primaries.
     AllPrimaries,
+    /// Routed by the key in argument 1.
     FirstKey,
+    /// Split by the slots of its keys, which are laid out according to the pattern from argument 1.
     MultiShard(MultiSlotArgPattern),
+    /// Sent to a random node.
     Random,
+    /// Routed by the key in argument 2.
     SecondArg,
+    /// Argument 1 is the number of keys, which follow it.
     SecondArgAfterKeyCount,
+    /// Routed by the slot number in argument 2.
     SecondArgSlot,
+    /// Routed by the first key after the `STREAMS` argument.
     StreamsIndex,
+    /// Routed by the key in argument 3.
     ThirdArg,
+    /// Argument 2 is the number of keys, which follow it.
     ThirdArgAfterKeyCount,
+    /// Can't be routed without an explicit route.
     Undefined,
 }
 
@@ -813,6 +828,11 @@ fn base_routing(cmd: &[u8]) -> RouteBy {
 }
 
 impl RoutingInfo {
+    /// Returns how the `cmd` is routed in cluster mode, before looking at its arguments.
+    pub fn base_route(cmd: &[u8]) -> RouteBy {
+        base_routing(cmd)
+    }
+
     /// Returns true if the `cmd` should be routed to all nodes.
     pub fn is_all_nodes(cmd: &[u8]) -> bool {
         matches!(base_routing(cmd), RouteBy::AllNodes)
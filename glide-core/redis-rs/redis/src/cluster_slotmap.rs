@@ -1,3 +1,4 @@
+use crate::replica_selector::ReplicaSelection;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
@@ -8,6 +9,7 @@ use std::{
 use dashmap::DashMap;
 
 use crate::cluster_routing::{Route, ShardAddrs, Slot, SlotAddr};
+use crate::cluster_topology::TopologyChangeEvent;
 use crate::ErrorKind;
 use crate::RedisError;
 use crate::RedisResult;
@@ -44,6 +46,9 @@ pub enum ReadFromReplicaStrategy {
     AZAffinityReplicasAndPrimary(String),
     /// Spread the read requests between all nodes (primary and replicas) in a round robin manner.
     AllNodes,
+    /// Choose the replica of each read request with a user-provided selector,
+    /// falling back to the primary if no replica is available or none was chosen.
+    Custom(ReplicaSelection),
 }
 
 #[derive(Debug, Default)]
@@ -58,6 +63,7 @@ fn get_address_from_slot(
     slot: &SlotMapValue,
     read_from_replica: ReadFromReplicaStrategy,
     slot_addr: SlotAddr,
+    key_slot: u16,
 ) -> Arc<String> {
     let addrs = &slot.addrs;
     if slot_addr == SlotAddr::Master || addrs.replicas().is_empty() {
@@ -91,6 +97,17 @@ fn get_address_from_slot(
         // behavior of these strategies when no local node is known.
         ReadFromReplicaStrategy::AZAffinity(_az) => round_robin_replica(),
         ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(_az) => round_robin_all_nodes(),
+        ReadFromReplicaStrategy::Custom(selection) => {
+            let replica_addrs = addrs.replicas();
+            let replicas: Vec<(&str, Option<&str>)> = replica_addrs
+                .iter()
+                .map(|replica| (replica.as_str(), None))
+                .collect();
+            match selection.select(Some(key_slot), &replicas) {
+                Some(index) => replica_addrs[index].clone(),
+                None => addrs.primary(),
+            }
+        }
     }
 }
 
@@ -187,6 +204,7 @@ impl SlotMap {
                 slot_value,
                 self.read_from_replica.clone(),
                 route.slot_addr(),
+                route.slot(),
             )
         })
     }
@@ -258,6 +276,53 @@ impl SlotMap {
             .collect()
     }
 
+    /// Returns the changes from this topology to `new` - the nodes that were added or removed,
+    /// and the shards whose primary was replaced by one of its replicas.
+    pub(crate) fn topology_changes(&self, new: &SlotMap) -> Vec<TopologyChangeEvent> {
+        let old_nodes = self.all_node_addresses();
+        let new_nodes = new.all_node_addresses();
+        let mut events: Vec<_> = new_nodes
+            .difference(&old_nodes)
+            .map(|address| TopologyChangeEvent::NodeAdded {
+                address: address.to_string(),
+            })
+            .chain(old_nodes.difference(&new_nodes).map(|address| {
+                TopologyChangeEvent::NodeRemoved {
+                    address: address.to_string(),
+                }
+            }))
+            .collect();
+
+        for old_primary in self.addresses_for_all_primaries() {
+            let Some(old_shard) = self
+                .nodes_map
+                .get(&old_primary)
+                .map(|entry| entry.1.clone())
+            else {
+                continue;
+            };
+            let old_replicas = old_shard.replicas().clone();
+            // If the old primary is still known, its shard tells who replaced it. Otherwise, look
+            // for one of its replicas that became a primary.
+            let new_primary = match new.nodes_map.get(&old_primary) {
+                Some(entry) => Some(entry.1.primary()),
+                None => old_replicas
+                    .iter()
+                    .find(|replica| new.is_primary(replica))
+                    .cloned(),
+            };
+            if let Some(new_primary) = new_primary {
+                if new_primary != old_primary && old_replicas.contains(&new_primary) {
+                    events.push(TopologyChangeEvent::Failover {
+                        old_primary: old_primary.to_string(),
+                        new_primary: new_primary.to_string(),
+                    });
+                }
+            }
+        }
+        events
+    }
+
     /// Returns an iterator of node addresses for each route, or `None` if a slot is not covered.
     pub fn addresses_for_multi_slot<'a, 'b>(
         &'a self,
@@ -295,6 +360,7 @@ impl SlotMap {
                     slot_value,
                     self.read_from_replica.clone(),
                     slot_addr,
+                    slot,
                 ))
             } else {
                 None
@@ -1717,4 +1783,62 @@ mod tests_cluster_slotmap {
             "Stale IP should not override fresh IP"
         );
     }
+
+    #[test]
+    fn test_topology_changes() {
+        let old_map = SlotMap::new(
+            vec![
+                Slot::new(
+                    0,
+                    8191,
+                    "node1:6379".to_owned(),
+                    vec!["replica1:6379".to_owned()],
+                ),
+                Slot::new(
+                    8192,
+                    16383,
+                    "node2:6379".to_owned(),
+                    vec!["replica2:6379".to_owned()],
+                ),
+            ],
+            HashMap::new(),
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+        );
+        // replica1 was promoted, node2 left its shard to replica2, and node3 joined
+        let new_map = SlotMap::new(
+            vec![
+                Slot::new(
+                    0,
+                    8191,
+                    "replica1:6379".to_owned(),
+                    vec!["node1:6379".to_owned(), "node3:6379".to_owned()],
+                ),
+                Slot::new(8192, 16383, "replica2:6379".to_owned(), vec![]),
+            ],
+            HashMap::new(),
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+        );
+
+        let changes: HashSet<_> = old_map.topology_changes(&new_map).into_iter().collect();
+        assert_eq!(
+            changes,
+            HashSet::from([
+                TopologyChangeEvent::NodeAdded {
+                    address: "node3:6379".to_owned()
+                },
+                TopologyChangeEvent::NodeRemoved {
+                    address: "node2:6379".to_owned()
+                },
+                TopologyChangeEvent::Failover {
+                    old_primary: "node1:6379".to_owned(),
+                    new_primary: "replica1:6379".to_owned()
+                },
+                TopologyChangeEvent::Failover {
+                    old_primary: "node2:6379".to_owned(),
+                    new_primary: "replica2:6379".to_owned()
+                },
+            ])
+        );
+        assert!(new_map.topology_changes(&new_map).is_empty());
+    }
 }
@@ -79,7 +79,9 @@ pub(crate) fn slot(key: &[u8]) -> u16 {
     crc16::State::<crc16::XMODEM>::calculate(key) % SLOT_SIZE
 }
 
-fn get_hashtag(key: &[u8]) -> Option<&[u8]> {
+/// Returns the hash tag of `key` - the part between its first `{` and the following `}` - if it
+/// has a non-empty one. Only the hash tag of a key is hashed to find its slot.
+pub fn get_hashtag(key: &[u8]) -> Option<&[u8]> {
     let open = key.iter().position(|v| *v == b'{')?;
 
     let close = key[open..].iter().position(|v| *v == b'}')?;
@@ -304,6 +306,159 @@ pub(crate) fn parse_and_count_slots(
     })
 }
 
+/// Returns the value of `field` in a `CLUSTER SHARDS` entry, which is a map in RESP3, and an
+/// array of alternating keys and values in RESP2.
+fn shards_field<'a>(entry: &'a Value, field: &str) -> Option<&'a Value> {
+    entry.as_map_iter()?.find_map(|(key, value)| match key {
+        Value::BulkString(key) if key == field.as_bytes() => Some(value),
+        Value::SimpleString(key) if key == field => Some(value),
+        _ => None,
+    })
+}
+
+fn shards_field_str(entry: &Value, field: &str) -> Option<String> {
+    match shards_field(entry, field)? {
+        Value::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(string) => Some(string.clone()),
+        _ => None,
+    }
+}
+
+fn shards_field_int(entry: &Value, field: &str) -> Option<i64> {
+    match shards_field(entry, field)? {
+        Value::Int(value) => Some(*value),
+        Value::BulkString(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Converts a node of a `CLUSTER SHARDS` entry to a `CLUSTER SLOTS` node entry -
+/// `[endpoint, port, node_id, [ip, ..., hostname, ...]]`.
+fn shards_node_to_slots_node(node: &Value, tls: Option<TlsMode>) -> Option<Value> {
+    let plain_port = shards_field_int(node, "port");
+    let tls_port = shards_field_int(node, "tls-port");
+    let port = match tls {
+        Some(_) => tls_port.or(plain_port),
+        None => plain_port.or(tls_port),
+    }?;
+    let ip = shards_field_str(node, "ip");
+    let endpoint = shards_field_str(node, "endpoint").or_else(|| ip.clone())?;
+    let id = shards_field_str(node, "id").unwrap_or_default();
+
+    let mut metadata = Vec::with_capacity(4);
+    if let Some(ip) = ip {
+        metadata.push(Value::BulkString(b"ip".to_vec()));
+        metadata.push(Value::BulkString(ip.into_bytes()));
+    }
+    if let Some(hostname) = shards_field_str(node, "hostname") {
+        metadata.push(Value::BulkString(b"hostname".to_vec()));
+        metadata.push(Value::BulkString(hostname.into_bytes()));
+    }
+    Some(Value::Array(vec![
+        Value::BulkString(endpoint.into_bytes()),
+        Value::Int(port),
+        Value::BulkString(id.into_bytes()),
+        Value::Array(metadata),
+    ]))
+}
+
+/// Converts a `CLUSTER SHARDS` response to the `CLUSTER SLOTS` format, so that it can be parsed
+/// by [`parse_and_count_slots`].
+///
+/// Unlike `CLUSTER SLOTS`, `CLUSTER SHARDS` reports the health of each node, so replicas that
+/// aren't `online` - e.g. replicas that are still loading their data - are left out of the
+/// shard's replica set. The primary is always kept, as it's the only owner of the shard's slots.
+pub(crate) fn shards_to_slots_view(
+    raw_shards_resp: &Value,
+    tls: Option<TlsMode>,
+) -> RedisResult<Value> {
+    let Some(shards) = raw_shards_resp.as_sequence() else {
+        return Err(RedisError::from((
+            ErrorKind::ResponseError,
+            "Error parsing shards: unexpected response type",
+            format!("Raw shards response: {raw_shards_resp:?}"),
+        )));
+    };
+
+    let mut slot_ranges = Vec::new();
+    for shard in shards {
+        let Some(nodes) = shards_field(shard, "nodes").and_then(Value::as_sequence) else {
+            continue;
+        };
+        let mut primary = None;
+        let mut replicas = Vec::new();
+        for node in nodes {
+            let role = shards_field_str(node, "role").unwrap_or_default();
+            if role == "master" || role == "primary" {
+                primary = shards_node_to_slots_node(node, tls);
+            } else if shards_field_str(node, "health").as_deref() == Some("online") {
+                replicas.extend(shards_node_to_slots_node(node, tls));
+            }
+        }
+        let Some(primary) = primary else {
+            continue;
+        };
+
+        let slots: Vec<i64> = shards_field(shard, "slots")
+            .and_then(Value::as_sequence)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|slot| match slot {
+                Value::Int(slot) => Some(*slot),
+                Value::BulkString(bytes) => std::str::from_utf8(bytes).ok()?.parse().ok(),
+                _ => None,
+            })
+            .collect();
+        for range in slots.chunks_exact(2) {
+            let mut entry = vec![Value::Int(range[0]), Value::Int(range[1]), primary.clone()];
+            entry.extend(replicas.iter().cloned());
+            slot_ranges.push(Value::Array(entry));
+        }
+    }
+    Ok(Value::Array(slot_ranges))
+}
+
+/// A change in the cluster topology, found when the client refreshes its slot map.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TopologyChangeEvent {
+    /// A node joined the cluster.
+    NodeAdded {
+        /// The address of the node.
+        address: String,
+    },
+    /// A node left the cluster.
+    NodeRemoved {
+        /// The address of the node.
+        address: String,
+    },
+    /// A replica was promoted to be the primary of its shard.
+    Failover {
+        /// The address of the previous primary.
+        old_primary: String,
+        /// The address of the promoted replica.
+        new_primary: String,
+    },
+}
+
+impl TopologyChangeEvent {
+    /// Returns the event as the data of a [`crate::PushKind::TopologyChange`] push -
+    /// `["node_added", address]`, `["node_removed", address]`, or
+    /// `["failover", old_primary, new_primary]`.
+    pub fn to_push_data(&self) -> Vec<Value> {
+        let bulk = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        match self {
+            TopologyChangeEvent::NodeAdded { address } => vec![bulk("node_added"), bulk(address)],
+            TopologyChangeEvent::NodeRemoved { address } => {
+                vec![bulk("node_removed"), bulk(address)]
+            }
+            TopologyChangeEvent::Failover {
+                old_primary,
+                new_primary,
+            } => vec![bulk("failover"), bulk(old_primary), bulk(new_primary)],
+        }
+    }
+}
+
 fn calculate_hash<T: Hash>(t: &T) -> u64 {
     let mut s = DefaultHasher::new();
     t.hash(&mut s);
@@ -1242,4 +1397,141 @@ mod tests {
             &["resolved.replica.example.com:6380".to_string()]
         );
     }
+
+    fn shards_node(fields: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            fields
+                .into_iter()
+                .map(|(key, value)| (Value::BulkString(key.as_bytes().to_vec()), value))
+                .collect(),
+        )
+    }
+
+    fn bulk(value: &str) -> Value {
+        Value::BulkString(value.as_bytes().to_vec())
+    }
+
+    fn shards_node_with_role(endpoint: &str, port: i64, role: &str, health: &str) -> Value {
+        shards_node(vec![
+            ("id", bulk(endpoint)),
+            ("port", Value::Int(port)),
+            ("tls-port", Value::Int(port + 1000)),
+            ("ip", bulk("10.0.0.1")),
+            ("endpoint", bulk(endpoint)),
+            ("role", bulk(role)),
+            ("health", bulk(health)),
+        ])
+    }
+
+    #[test]
+    fn shards_view_is_parsed_like_slots_view() {
+        let shards = Value::Array(vec![
+            shards_node(vec![
+                (
+                    "slots",
+                    Value::Array(vec![
+                        Value::Int(0),
+                        Value::Int(100),
+                        Value::Int(200),
+                        Value::Int(300),
+                    ]),
+                ),
+                (
+                    "nodes",
+                    Value::Array(vec![
+                        shards_node_with_role("replica-1", 6380, "replica", "online"),
+                        shards_node_with_role("primary-1", 6379, "master", "online"),
+                        shards_node_with_role("replica-2", 6381, "replica", "loading"),
+                    ]),
+                ),
+            ]),
+            // A shard without slots
+            shards_node(vec![
+                ("slots", Value::Array(vec![])),
+                (
+                    "nodes",
+                    Value::Array(vec![shards_node_with_role(
+                        "primary-2",
+                        6379,
+                        "master",
+                        "online",
+                    )]),
+                ),
+            ]),
+        ]);
+
+        let view = shards_to_slots_view(&shards, None).unwrap();
+        let parsed = parse_and_count_slots(&view, None, "fallback", None).unwrap();
+        assert_eq!(parsed.slots_count, 202);
+        let ranges: Vec<_> = parsed
+            .slots
+            .iter()
+            .map(|slot| (slot.start, slot.end, slot.master(), slot.replicas()))
+            .collect();
+        assert_eq!(
+            ranges,
+            vec![
+                (0, 100, "primary-1:6379", vec!["replica-1:6380".to_string()]),
+                (
+                    200,
+                    300,
+                    "primary-1:6379",
+                    vec!["replica-1:6380".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(
+            parsed.address_to_ip_map.get("primary-1:6379"),
+            Some(&"10.0.0.1".parse().unwrap())
+        );
+
+        let tls_view = shards_to_slots_view(&shards, Some(TlsMode::Secure)).unwrap();
+        let parsed = parse_and_count_slots(&tls_view, None, "fallback", None).unwrap();
+        assert_eq!(parsed.slots[0].master(), "primary-1:7379");
+    }
+
+    #[test]
+    fn shards_view_accepts_resp2_arrays() {
+        let node = Value::Array(vec![
+            bulk("port"),
+            Value::Int(6379),
+            bulk("ip"),
+            bulk("127.0.0.1"),
+            bulk("role"),
+            bulk("master"),
+            bulk("health"),
+            bulk("online"),
+        ]);
+        let shards = Value::Array(vec![Value::Array(vec![
+            bulk("slots"),
+            Value::Array(vec![Value::Int(0), Value::Int(16383)]),
+            bulk("nodes"),
+            Value::Array(vec![node]),
+        ])]);
+
+        let view = shards_to_slots_view(&shards, None).unwrap();
+        let parsed = parse_and_count_slots(&view, None, "fallback", None).unwrap();
+        assert_eq!(parsed.slots_count, 16384);
+        assert_eq!(parsed.slots[0].master(), "127.0.0.1:6379");
+        assert!(shards_to_slots_view(&Value::Okay, None).is_err());
+    }
+
+    #[test]
+    fn topology_change_event_push_data() {
+        assert_eq!(
+            TopologyChangeEvent::Failover {
+                old_primary: "a:6379".into(),
+                new_primary: "b:6379".into(),
+            }
+            .to_push_data(),
+            vec![bulk("failover"), bulk("a:6379"), bulk("b:6379")]
+        );
+        assert_eq!(
+            TopologyChangeEvent::NodeAdded {
+                address: "c:6379".into()
+            }
+            .to_push_data(),
+            vec![bulk("node_added"), bulk("c:6379")]
+        );
+    }
 }
@@ -6,9 +6,12 @@ use futures_util::{
 };
 #[cfg(feature = "aio")]
 use std::pin::Pin;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 use std::{borrow::Borrow, fmt, io};
 
+use crate::deadline::{DeadlinePhase, RequestDeadline};
 use crate::pipeline::Pipeline;
 use crate::types::{from_owned_redis_value, FromRedisValue, RedisResult, RedisWrite, ToRedisArgs};
 use crate::{cache::glide_cache::CachedKeyType, connection::ConnectionLike};
@@ -44,6 +47,9 @@ pub struct Cmd {
     /// response_timeout for this specific command. Used to propagate the
     /// caller's request_timeout into the multiplexed connection layer.
     response_timeout: Option<std::time::Duration>,
+    /// End-to-end deadline of the request, shared by all the attempts - and clones - of the command.
+    /// When set, every attempt's response timeout is capped by the time left before the deadline.
+    deadline: Option<Arc<RequestDeadline>>,
     /// Inflight slot tracker. When set, the slot is released when the last
     /// clone of this Cmd (or its Arc) is dropped. Used to decouple user-facing
     /// timeout from internal pipeline cleanup.
@@ -54,6 +60,11 @@ pub struct Cmd {
     pub watchdog_phase: AtomicU8,
     /// Number of retries attempted. Incremented by the routing layer.
     pub watchdog_retry_count: AtomicU8,
+    /// Time at which the command was first handed to a connection. Set by `mark_sent`.
+    sent_at: OnceLock<Instant>,
+    /// Time at which the first byte of the last response to the command arrived, in nanoseconds
+    /// since `timestamp_origin()`, or 0 if no response was timestamped. Set by `mark_first_byte`.
+    first_byte_at: AtomicU64,
 }
 
 // Manual Clone implementation: AtomicU8 and OnceLock don't implement Clone,
@@ -69,15 +80,25 @@ impl Clone for Cmd {
             span: self.span.clone(),
             is_fenced: self.is_fenced,
             response_timeout: self.response_timeout,
+            deadline: self.deadline.clone(),
             #[cfg(feature = "cluster-async")]
             inflight_tracker: self.inflight_tracker.clone(),
             // Reset watchdog fields — each clone is a fresh command attempt
             watchdog_phase: AtomicU8::new(PHASE_QUEUED),
             watchdog_retry_count: AtomicU8::new(0),
+            sent_at: OnceLock::new(),
+            first_byte_at: AtomicU64::new(0),
         }
     }
 }
 
+/// The instant the response timestamps of the commands are counted from, so they fit in an
+/// atomic integer.
+fn timestamp_origin() -> Instant {
+    static ORIGIN: OnceLock<Instant> = OnceLock::new();
+    *ORIGIN.get_or_init(Instant::now)
+}
+
 /// The PING command used to fence other commands for ordering guarantees
 const FENCE_COMMAND: &[u8] = b"*1\r\n$4\r\nPING\r\n";
 
@@ -398,10 +419,13 @@ impl Cmd {
             span: None,
             is_fenced: false,
             response_timeout: None,
+            deadline: None,
             #[cfg(feature = "cluster-async")]
             inflight_tracker: None,
             watchdog_phase: AtomicU8::new(PHASE_QUEUED),
             watchdog_retry_count: AtomicU8::new(0),
+            sent_at: OnceLock::new(),
+            first_byte_at: AtomicU64::new(0),
         }
     }
 
@@ -415,10 +439,13 @@ impl Cmd {
             span: None,
             is_fenced: false,
             response_timeout: None,
+            deadline: None,
             #[cfg(feature = "cluster-async")]
             inflight_tracker: None,
             watchdog_phase: AtomicU8::new(PHASE_QUEUED),
             watchdog_retry_count: AtomicU8::new(0),
+            sent_at: OnceLock::new(),
+            first_byte_at: AtomicU64::new(0),
         }
     }
 
@@ -458,6 +485,54 @@ impl Cmd {
         self
     }
 
+    /// Marks the command as sent to a node, for watchdog diagnostics and latency breakdown.
+    /// Only the first send is timestamped, so time spent on retries counts as network time.
+    #[inline]
+    pub fn mark_sent(&self) {
+        self.watchdog_phase.store(PHASE_SENT, Ordering::Release);
+        if let Some(deadline) = &self.deadline {
+            deadline.enter(DeadlinePhase::Sent);
+        }
+        // Fixes the origin of the response timestamps before the command is sent.
+        timestamp_origin();
+        let _ = self.sent_at.set(Instant::now());
+    }
+
+    /// Returns the time at which the command was first sent, if it was sent.
+    #[inline]
+    pub fn sent_at(&self) -> Option<Instant> {
+        self.sent_at.get().copied()
+    }
+
+    /// Marks the arrival of the first byte of a response to the command. A command that is
+    /// retried keeps the time of its last response.
+    #[inline]
+    pub fn mark_first_byte(&self, at: Instant) {
+        let nanos = at.saturating_duration_since(timestamp_origin()).as_nanos() as u64;
+        self.first_byte_at.store(nanos.max(1), Ordering::Relaxed);
+    }
+
+    /// Returns the time at which the first byte of the last response to the command arrived.
+    #[inline]
+    pub fn first_byte_at(&self) -> Option<Instant> {
+        match self.first_byte_at.load(Ordering::Relaxed) {
+            0 => None,
+            nanos => Some(timestamp_origin() + std::time::Duration::from_nanos(nanos)),
+        }
+    }
+
+    /// Copies the send and response times of `other`, a clone of this command that was sent in
+    /// its place.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) fn inherit_timestamps(&self, other: &Cmd) {
+        if let Some(sent_at) = other.sent_at() {
+            let _ = self.sent_at.set(sent_at);
+        }
+        if let Some(first_byte_at) = other.first_byte_at() {
+            self.mark_first_byte(first_byte_at);
+        }
+    }
+
     /// Works similar to `arg` but adds a cursor argument.  This is always
     /// an integer and also flips the command implementation to support a
     /// different mode for the iterators where the iterator will ask for
@@ -718,10 +793,32 @@ impl Cmd {
         self.response_timeout = timeout;
     }
 
-    /// Get the per-command response timeout, if set.
+    /// Get the per-command response timeout, if set. With a deadline, this is at most the time left
+    /// before the deadline.
     #[inline]
     pub fn response_timeout(&self) -> Option<std::time::Duration> {
-        self.response_timeout
+        match &self.deadline {
+            Some(deadline) => {
+                let remaining = deadline.remaining();
+                Some(
+                    self.response_timeout
+                        .map_or(remaining, |timeout| timeout.min(remaining)),
+                )
+            }
+            None => self.response_timeout,
+        }
+    }
+
+    /// Set the end-to-end deadline of the request.
+    #[inline]
+    pub fn set_deadline(&mut self, deadline: Option<Arc<RequestDeadline>>) {
+        self.deadline = deadline;
+    }
+
+    /// Get the end-to-end deadline of the request, if set.
+    #[inline]
+    pub fn deadline(&self) -> Option<&Arc<RequestDeadline>> {
+        self.deadline.as_ref()
     }
 
     /// Attach an inflight slot tracker. The slot is released when the last
@@ -814,8 +911,23 @@ pub fn pipe() -> Pipeline {
 #[cfg(feature = "cluster")]
 mod tests {
     use super::Cmd;
+    use crate::RequestDeadline;
+    use std::sync::Arc;
     use std::time::Duration;
 
+    #[test]
+    fn test_cmd_keeps_the_last_first_byte_time() {
+        let cmd = Cmd::new();
+        assert_eq!(cmd.first_byte_at(), None);
+        cmd.mark_sent();
+        let first = std::time::Instant::now();
+        cmd.mark_first_byte(first);
+        let last = first + Duration::from_micros(5);
+        cmd.mark_first_byte(last);
+        assert_eq!(cmd.first_byte_at(), Some(last));
+        assert_eq!(cmd.clone().first_byte_at(), None);
+    }
+
     #[test]
     fn test_cmd_arg_idx() {
         let mut c = Cmd::new();
@@ -849,4 +961,22 @@ mod tests {
         cmd.set_response_timeout(None);
         assert_eq!(cmd.response_timeout(), None);
     }
+
+    #[test]
+    fn test_response_timeout_is_capped_by_the_deadline() {
+        let mut cmd = Cmd::new();
+        cmd.arg("GET").arg("key");
+        cmd.set_response_timeout(Some(Duration::from_secs(60)));
+        cmd.set_deadline(Some(Arc::new(RequestDeadline::new(Duration::from_millis(
+            100,
+        )))));
+
+        let timeout = cmd.response_timeout().unwrap();
+        assert!(timeout <= Duration::from_millis(100));
+        // Clones, as sent on retries, share the deadline.
+        assert!(Arc::ptr_eq(
+            cmd.clone().deadline().unwrap(),
+            cmd.deadline().unwrap()
+        ));
+    }
 }
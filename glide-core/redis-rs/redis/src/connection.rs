@@ -239,6 +239,9 @@ pub struct RedisConnectionInfo {
     pub cache: Option<Arc<dyn GlideCache>>,
     /// Whether to enable server-assisted client tracking (CLIENT TRACKING ON BCAST)
     pub server_assisted_cache: bool,
+    /// If RESP3 was requested and the server doesn't support `HELLO`, fall back to RESP2
+    /// instead of failing the connection.
+    pub protocol_fallback: bool,
 }
 
 impl FromStr for ConnectionInfo {
@@ -399,6 +402,7 @@ fn url_to_tcp_connection_info(url: url::Url) -> RedisResult<ConnectionInfo> {
             lib_name: None,
             cache: None,
             server_assisted_cache: false,
+            protocol_fallback: false,
         },
     })
 }
@@ -434,6 +438,7 @@ fn url_to_unix_connection_info(url: url::Url) -> RedisResult<ConnectionInfo> {
             lib_name: None,
             cache: None,
             server_assisted_cache: false,
+            protocol_fallback: false,
         },
     })
 }
@@ -970,7 +975,14 @@ fn setup_connection(
         let hello_cmd = resp3_hello(connection_info);
         let val: RedisResult<Value> = hello_cmd.query(&mut rv);
         if let Err(err) = val {
-            return Err(get_resp3_hello_command_error(err));
+            let err = get_resp3_hello_command_error(err);
+            if !should_fallback_to_resp2(&err, connection_info) {
+                return Err(err);
+            }
+            rv.protocol = ProtocolVersion::RESP2;
+            if connection_info.password.is_some() {
+                connect_auth(&mut rv, connection_info)?;
+            }
         }
     } else if connection_info.password.is_some() {
         connect_auth(&mut rv, connection_info)?;
@@ -1720,6 +1732,14 @@ pub fn resp3_is_pub_sub_state_cleared(
     *received_unsub && *received_punsub && num == 0
 }
 
+/// Returns true if a failed RESP3 `HELLO` should be retried as a RESP2 connection.
+pub(crate) fn should_fallback_to_resp2(
+    hello_error: &RedisError,
+    connection_info: &RedisConnectionInfo,
+) -> bool {
+    connection_info.protocol_fallback && hello_error.kind() == ErrorKind::RESP3NotSupported
+}
+
 /// Common logic for checking real cause of hello3 command error
 pub fn get_resp3_hello_command_error(err: RedisError) -> RedisError {
     if let Some(detail) = err.detail() {
@@ -1738,6 +1758,32 @@ pub fn get_resp3_hello_command_error(err: RedisError) -> RedisError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_should_fallback_to_resp2() {
+        let hello_unsupported = get_resp3_hello_command_error(RedisError::from((
+            ErrorKind::ResponseError,
+            "An error was signalled by the server",
+            "unknown command `HELLO`, with args beginning with: `3`".to_string(),
+        )));
+        let auth_failed = RedisError::from((ErrorKind::AuthenticationFailed, "WRONGPASS"));
+
+        let mut connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::RESP3,
+            ..Default::default()
+        };
+        assert!(!should_fallback_to_resp2(
+            &hello_unsupported,
+            &connection_info
+        ));
+
+        connection_info.protocol_fallback = true;
+        assert!(should_fallback_to_resp2(
+            &hello_unsupported,
+            &connection_info
+        ));
+        assert!(!should_fallback_to_resp2(&auth_failed, &connection_info));
+    }
+
     #[test]
     fn test_client_set_info_pipeline_default_lib_name() {
         let pipeline = client_set_info_pipeline(None);
@@ -1883,6 +1929,7 @@ mod tests {
                         lib_name: None,
                         cache: None,
                         server_assisted_cache: false,
+                        protocol_fallback: false,
                     },
                 },
             ),
@@ -0,0 +1,191 @@
+//! End-to-end deadlines of requests.
+//!
+//! A [`RequestDeadline`] is a time budget shared by every attempt of a request: the time spent
+//! waiting for a connection, on the wire, backing off between retries, following redirects and
+//! reconnecting is deducted from the same budget, and a request whose budget ran out is failed
+//! instead of retried. The deadline also records how the time was spent, so the timeout error
+//! returned to the user tells which phase consumed the budget.
+
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::types::{RedisError, RetryMethod};
+
+/// A phase of a request's lifetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlinePhase {
+    /// Waiting in the client before being sent - e.g. for the client to connect.
+    Queued = 0,
+    /// Sent, and waiting for the response.
+    Sent = 1,
+    /// Waiting before a retry.
+    Backoff = 2,
+    /// Following a MOVED or ASK redirect, or waiting for the slots to be refreshed.
+    Redirect = 3,
+    /// Waiting for a connection to be reestablished.
+    Reconnect = 4,
+}
+
+const PHASES: usize = 5;
+
+impl DeadlinePhase {
+    /// Returns the phase a request enters before it's retried after `err`.
+    pub fn of_retry(err: &RedisError) -> Self {
+        match err.retry_method() {
+            RetryMethod::AskRedirect
+            | RetryMethod::MovedRedirect
+            | RetryMethod::RefreshSlotsAndRetry => Self::Redirect,
+            RetryMethod::Reconnect | RetryMethod::ReconnectAndRetry => Self::Reconnect,
+            _ => Self::Backoff,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PhaseClock {
+    phase: DeadlinePhase,
+    since: Instant,
+    spent: [Duration; PHASES],
+}
+
+/// The time budget of a request, shared by all of its attempts.
+#[derive(Debug)]
+pub struct RequestDeadline {
+    budget: Duration,
+    expires_at: Instant,
+    clock: Mutex<PhaseClock>,
+}
+
+impl RequestDeadline {
+    /// Creates a deadline `budget` from now, with the request in the `Queued` phase.
+    pub fn new(budget: Duration) -> Self {
+        let now = Instant::now();
+        Self {
+            budget,
+            expires_at: now + budget,
+            clock: Mutex::new(PhaseClock {
+                phase: DeadlinePhase::Queued,
+                since: now,
+                spent: [Duration::ZERO; PHASES],
+            }),
+        }
+    }
+
+    /// Returns the time left before the deadline.
+    pub fn remaining(&self) -> Duration {
+        self.expires_at.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns whether the deadline passed.
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+
+    /// Moves the request to `phase`, charging the time since the last change to the previous one.
+    pub fn enter(&self, phase: DeadlinePhase) {
+        let now = Instant::now();
+        let mut clock = self.clock.lock().unwrap();
+        let elapsed = now.saturating_duration_since(clock.since);
+        let previous = clock.phase as usize;
+        clock.spent[previous] += elapsed;
+        clock.phase = phase;
+        clock.since = now;
+    }
+
+    /// Returns the time spent in each phase so far.
+    pub fn breakdown(&self) -> DeadlineBreakdown {
+        let clock = self.clock.lock().unwrap();
+        let mut spent = clock.spent;
+        spent[clock.phase as usize] += clock.since.elapsed();
+        DeadlineBreakdown {
+            budget: self.budget,
+            queued: spent[DeadlinePhase::Queued as usize],
+            sent: spent[DeadlinePhase::Sent as usize],
+            backoff: spent[DeadlinePhase::Backoff as usize],
+            redirect: spent[DeadlinePhase::Redirect as usize],
+            reconnect: spent[DeadlinePhase::Reconnect as usize],
+        }
+    }
+
+    /// Returns the timeout error of a request whose deadline passed, describing where the time was
+    /// spent.
+    pub fn expired_error(&self) -> RedisError {
+        io::Error::new(io::ErrorKind::TimedOut, self.breakdown().to_string()).into()
+    }
+}
+
+/// How the time of a request was spent, by phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineBreakdown {
+    /// The request's whole budget.
+    pub budget: Duration,
+    /// Time spent before the request was sent.
+    pub queued: Duration,
+    /// Time spent waiting for responses.
+    pub sent: Duration,
+    /// Time spent backing off between retries.
+    pub backoff: Duration,
+    /// Time spent following redirects and refreshing the slots.
+    pub redirect: Duration,
+    /// Time spent waiting for reconnections.
+    pub reconnect: Duration,
+}
+
+impl fmt::Display for DeadlineBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Request deadline of {:?} exceeded (queued: {:?}, sent: {:?}, backoff: {:?}, redirect: {:?}, reconnect: {:?})",
+            self.budget, self.queued, self.sent, self.backoff, self.redirect, self.reconnect
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ErrorKind;
+
+    #[test]
+    fn test_time_is_charged_to_the_phase_it_was_spent_in() {
+        let deadline = RequestDeadline::new(Duration::from_secs(60));
+        std::thread::sleep(Duration::from_millis(5));
+        deadline.enter(DeadlinePhase::Sent);
+        std::thread::sleep(Duration::from_millis(5));
+        deadline.enter(DeadlinePhase::Backoff);
+        deadline.enter(DeadlinePhase::Sent);
+
+        let breakdown = deadline.breakdown();
+        assert!(breakdown.queued >= Duration::from_millis(5));
+        assert!(breakdown.sent >= Duration::from_millis(5));
+        assert!(breakdown.backoff < Duration::from_millis(5));
+        assert_eq!(breakdown.redirect, Duration::ZERO);
+        assert!(!deadline.is_expired());
+        assert!(deadline.remaining() <= Duration::from_secs(60) - Duration::from_millis(10));
+    }
+
+    #[test]
+    fn test_expired_deadline_error_is_a_timeout() {
+        let deadline = RequestDeadline::new(Duration::ZERO);
+        assert!(deadline.is_expired());
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+        let err = deadline.expired_error();
+        assert!(err.is_timeout());
+        assert!(err.to_string().contains("Request deadline of 0ns exceeded"));
+    }
+
+    #[test]
+    fn test_retry_phases() {
+        let moved = RedisError::from((ErrorKind::Moved, "MOVED", "1 127.0.0.1:6380".to_string()));
+        assert_eq!(DeadlinePhase::of_retry(&moved), DeadlinePhase::Redirect);
+        let unavailable = RedisError::from((ErrorKind::AllConnectionsUnavailable, "down"));
+        assert_eq!(
+            DeadlinePhase::of_retry(&unavailable),
+            DeadlinePhase::Reconnect
+        );
+        let try_again = RedisError::from((ErrorKind::TryAgain, "TRYAGAIN"));
+        assert_eq!(DeadlinePhase::of_retry(&try_again), DeadlinePhase::Backoff);
+    }
+}
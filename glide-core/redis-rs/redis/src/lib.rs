@@ -350,6 +350,7 @@ pub use crate::connection::{
     IntoConnectionInfo, Msg, PubSub, PubSubChannelOrPattern, PubSubSubscriptionInfo,
     PubSubSubscriptionKind, RedisConnectionInfo, TlsMode,
 };
+pub use crate::deadline::{DeadlineBreakdown, DeadlinePhase, RequestDeadline};
 pub use crate::parser::{parse_redis_value, Parser};
 pub use crate::pipeline::{Pipeline, PipelineRetryStrategy};
 pub use crate::pubsub_synchronizer::PubSubSynchronizer;
@@ -412,6 +413,10 @@ pub mod cluster;
 /// Used for ReadFromReplicaStrategy information.
 pub mod cluster_slotmap;
 
+#[cfg(feature = "cluster")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cluster")))]
+pub mod replica_selector;
+
 #[cfg(feature = "cluster-async")]
 pub use crate::commands::ScanStateRC;
 
@@ -460,6 +465,7 @@ mod client;
 mod cmd;
 mod commands;
 mod connection;
+mod deadline;
 mod parser;
 mod pubsub_synchronizer;
 mod push_manager;
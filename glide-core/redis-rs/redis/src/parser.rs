@@ -320,20 +320,70 @@ mod aio_support {
     use super::*;
 
     use bytes::{Buf, BytesMut};
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
     use tokio::io::AsyncRead;
     use tokio_util::codec::{Decoder, Encoder};
 
+    /// Shares when the first byte of the last frame a [`ValueCodec`] decoded arrived, so the
+    /// reader of the frames can tell it for the request each frame answers.
+    #[derive(Clone)]
+    pub(crate) struct FrameClock {
+        origin: Instant,
+        last_frame_start_nanos: Arc<AtomicU64>,
+    }
+
+    impl FrameClock {
+        pub(crate) fn new() -> Self {
+            Self {
+                origin: Instant::now(),
+                last_frame_start_nanos: Arc::new(AtomicU64::new(0)),
+            }
+        }
+
+        fn record(&self, frame_start: Instant) {
+            let nanos = frame_start
+                .saturating_duration_since(self.origin)
+                .as_nanos();
+            self.last_frame_start_nanos
+                .store(nanos as u64, Ordering::Relaxed);
+        }
+
+        /// Returns when the first byte of the last decoded frame arrived.
+        pub(crate) fn last_frame_start(&self) -> Instant {
+            self.origin
+                + std::time::Duration::from_nanos(
+                    self.last_frame_start_nanos.load(Ordering::Relaxed),
+                )
+        }
+    }
+
     #[derive(Default)]
     pub struct ValueCodec {
         state: AnySendSyncPartialState,
+        frame_clock: Option<FrameClock>,
+        /// When the first byte of the frame being decoded arrived, if `frame_clock` is set.
+        frame_started_at: Option<Instant>,
     }
 
     impl ValueCodec {
+        /// Records the arrival of the first byte of every decoded frame in `frame_clock`.
+        pub(crate) fn with_frame_clock(frame_clock: FrameClock) -> Self {
+            Self {
+                frame_clock: Some(frame_clock),
+                ..Default::default()
+            }
+        }
+
         fn decode_stream(
             &mut self,
             bytes: &mut BytesMut,
             eof: bool,
         ) -> RedisResult<Option<RedisResult<Value>>> {
+            if self.frame_clock.is_some() && self.frame_started_at.is_none() && !bytes.is_empty() {
+                self.frame_started_at = Some(Instant::now());
+            }
             let (opt, removed_len) = {
                 let buffer = &bytes[..];
                 let mut stream =
@@ -356,7 +406,14 @@ mod aio_support {
 
             bytes.advance(removed_len);
             match opt {
-                Some(result) => Ok(Some(Ok(result))),
+                Some(result) => {
+                    if let (Some(clock), Some(frame_start)) =
+                        (&self.frame_clock, self.frame_started_at.take())
+                    {
+                        clock.record(frame_start);
+                    }
+                    Ok(Some(Ok(result)))
+                }
                 None => Ok(None),
             }
         }
@@ -502,6 +559,28 @@ mod tests {
         assert_eq!(codec.decode_eof(&mut bytes), Ok(None));
     }
 
+    #[cfg(feature = "aio")]
+    #[test]
+    fn frame_clock_records_the_first_byte_of_a_partial_frame() {
+        use tokio_util::codec::Decoder;
+        let clock = FrameClock::new();
+        let mut codec = ValueCodec::with_frame_clock(clock.clone());
+
+        let mut bytes = bytes::BytesMut::from(&b"$5\r\nhel"[..]);
+        let before_first_byte = std::time::Instant::now();
+        assert_eq!(codec.decode(&mut bytes), Ok(None));
+        let after_first_byte = std::time::Instant::now();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+
+        bytes.extend_from_slice(b"lo\r\n");
+        assert_eq!(
+            codec.decode(&mut bytes),
+            Ok(Some(Ok(Value::BulkString(b"hello".to_vec()))))
+        );
+        let frame_start = clock.last_frame_start();
+        assert!(frame_start >= before_first_byte && frame_start <= after_first_byte);
+    }
+
     #[cfg(feature = "aio")]
     #[test]
     fn decode_eof_returns_error_inside_array_and_can_parse_more_inputs() {
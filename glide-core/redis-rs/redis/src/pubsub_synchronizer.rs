@@ -2,7 +2,7 @@
 
 use crate::cluster_slotmap::SlotMap;
 use crate::connection::{PubSubChannelOrPattern, PubSubSubscriptionKind};
-use crate::{Cmd, RedisResult, Value};
+use crate::{Cmd, RedisError, RedisResult, Value};
 use async_trait::async_trait;
 use std::collections::{HashMap, HashSet};
 
@@ -24,6 +24,12 @@ pub trait PubSubSynchronizer: Send + Sync {
         subscription_type: PubSubSubscriptionKind,
     );
 
+    /// Add patterns to desired subscriptions that are subscribed to on every primary of a cluster,
+    /// instead of on a single node, such as keyspace notification patterns
+    fn add_all_primaries_subscriptions(&self, patterns: HashSet<PubSubChannelOrPattern>) {
+        self.add_desired_subscriptions(patterns, PubSubSubscriptionKind::Pattern);
+    }
+
     /// Add channels to current (actual) subscriptions
     fn add_current_subscriptions(
         &self,
@@ -48,6 +54,20 @@ pub trait PubSubSynchronizer: Send + Sync {
         HashMap<PubSubSubscriptionKind, HashSet<PubSubChannelOrPattern>>,
     );
 
+    /// Get the desired subscriptions that the server refused due to missing ACL permissions
+    fn get_denied_subscriptions(
+        &self,
+    ) -> HashMap<PubSubSubscriptionKind, HashSet<PubSubChannelOrPattern>> {
+        HashMap::new()
+    }
+
+    /// Get the `PermissionDenied` error the server returned for each denied subscription
+    fn get_subscription_errors(
+        &self,
+    ) -> Vec<(PubSubSubscriptionKind, PubSubChannelOrPattern, RedisError)> {
+        Vec::new()
+    }
+
     /// Trigger the reconciliation task to run immediately (non-blocking)
     fn trigger_reconciliation(&self);
 
@@ -98,6 +118,11 @@ pub trait PubSubSynchronizer: Send + Sync {
         Ok(()) // Default: no-op
     }
 
+    /// Stop the background tasks of the synchronizer, when its client shuts down
+    fn stop(&self) {
+        // Default: no-op
+    }
+
     /// Allows downcasting to concrete types
     fn as_any(&self) -> &dyn std::any::Any;
 }
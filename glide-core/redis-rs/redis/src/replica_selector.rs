@@ -0,0 +1,318 @@
+//! Pluggable selection of the replica that serves a read.
+//!
+//! A [`ReplicaSelector`] is given the connected replicas that can serve a read, along with their
+//! availability zone and the statistics collected by the client, and chooses one of them. It's
+//! used with [`ReadFromReplicaStrategy::Custom`](crate::cluster_slotmap::ReadFromReplicaStrategy::Custom).
+//! [`RoundRobinReplicaSelector`], [`LatencyWeightedReplicaSelector`] and
+//! [`AzPreferredReplicaSelector`] are provided.
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// The weight of a new sample in the smoothed latency of a node.
+const LATENCY_SMOOTHING_FACTOR: f64 = 0.2;
+
+/// A replica that can serve a read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplicaCandidate<'a> {
+    /// The replica's address, as `host:port`.
+    pub address: &'a str,
+    /// The replica's availability zone, if known.
+    pub az: Option<&'a str>,
+    /// The smoothed round trip time of the requests sent to the replica, or `None` if no request
+    /// completed yet.
+    pub latency: Option<Duration>,
+    /// The number of requests to the replica that failed since the last successful one.
+    pub consecutive_failures: u32,
+}
+
+/// Chooses the replica that serves a read.
+pub trait ReplicaSelector: Send + Sync + Debug {
+    /// Returns the index in `candidates` of the replica to read from, or `None` to read from the
+    /// primary. `slot` is the slot of the read's keys in cluster mode, and `None` in standalone
+    /// mode. `candidates` is never empty, and only contains connected replicas.
+    fn select(&self, slot: Option<u16>, candidates: &[ReplicaCandidate<'_>]) -> Option<usize>;
+}
+
+/// Picks the replicas in turn.
+#[derive(Debug, Default)]
+pub struct RoundRobinReplicaSelector {
+    next: AtomicUsize,
+}
+
+impl ReplicaSelector for RoundRobinReplicaSelector {
+    fn select(&self, _slot: Option<u16>, candidates: &[ReplicaCandidate<'_>]) -> Option<usize> {
+        Some(self.next.fetch_add(1, Ordering::Relaxed) % candidates.len())
+    }
+}
+
+/// Picks replicas at random, with a probability inversely proportional to their latency.
+/// Replicas with recent failures are picked less often, and replicas without a measured latency
+/// are picked like the fastest replica, so that their latency gets measured.
+#[derive(Debug, Default)]
+pub struct LatencyWeightedReplicaSelector;
+
+impl LatencyWeightedReplicaSelector {
+    fn weights(candidates: &[ReplicaCandidate<'_>]) -> Vec<f64> {
+        let fastest = candidates
+            .iter()
+            .filter_map(|candidate| candidate.latency)
+            .min()
+            .unwrap_or(Duration::from_millis(1));
+        candidates
+            .iter()
+            .map(|candidate| {
+                // Sub-microsecond latencies are rounded up, to avoid dividing by zero.
+                let latency = candidate
+                    .latency
+                    .unwrap_or(fastest)
+                    .max(Duration::from_micros(1));
+                1.0 / latency.as_secs_f64() / f64::from(candidate.consecutive_failures + 1)
+            })
+            .collect()
+    }
+}
+
+impl ReplicaSelector for LatencyWeightedReplicaSelector {
+    fn select(&self, _slot: Option<u16>, candidates: &[ReplicaCandidate<'_>]) -> Option<usize> {
+        let weights = Self::weights(candidates);
+        let mut target = rand::random::<f64>() * weights.iter().sum::<f64>();
+        for (index, weight) in weights.iter().enumerate() {
+            if target < *weight {
+                return Some(index);
+            }
+            target -= weight;
+        }
+        Some(candidates.len() - 1)
+    }
+}
+
+/// Picks the replicas in the client's availability zone in turn, and the other replicas in turn
+/// if none of them is available.
+#[derive(Debug)]
+pub struct AzPreferredReplicaSelector {
+    az: String,
+    round_robin: RoundRobinReplicaSelector,
+}
+
+impl AzPreferredReplicaSelector {
+    /// Creates a selector that prefers the replicas in `az`.
+    pub fn new(az: impl Into<String>) -> Self {
+        Self {
+            az: az.into(),
+            round_robin: RoundRobinReplicaSelector::default(),
+        }
+    }
+}
+
+impl ReplicaSelector for AzPreferredReplicaSelector {
+    fn select(&self, slot: Option<u16>, candidates: &[ReplicaCandidate<'_>]) -> Option<usize> {
+        let local: Vec<usize> = candidates
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| candidate.az == Some(self.az.as_str()))
+            .map(|(index, _)| index)
+            .collect();
+        if local.is_empty() {
+            return self.round_robin.select(slot, candidates);
+        }
+        Some(local[self.round_robin.next.fetch_add(1, Ordering::Relaxed) % local.len()])
+    }
+}
+
+#[derive(Debug, Default)]
+struct NodeStats {
+    /// The smoothed latency in microseconds, or 0 if no request completed yet.
+    latency_micros: AtomicU64,
+    consecutive_failures: AtomicU32,
+}
+
+/// A replica selector, with the statistics of the nodes it chooses between.
+/// Clones share the selector and the statistics.
+#[derive(Clone, Debug)]
+pub struct ReplicaSelection {
+    selector: Arc<dyn ReplicaSelector>,
+    stats: Arc<RwLock<HashMap<String, Arc<NodeStats>>>>,
+}
+
+impl PartialEq for ReplicaSelection {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.stats, &other.stats)
+    }
+}
+
+impl ReplicaSelection {
+    /// Creates a selection that chooses replicas with `selector`.
+    pub fn new(selector: Arc<dyn ReplicaSelector>) -> Self {
+        Self {
+            selector,
+            stats: Default::default(),
+        }
+    }
+
+    fn node_stats(&self, address: &str) -> Arc<NodeStats> {
+        if let Some(stats) = self
+            .stats
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(address)
+        {
+            return stats.clone();
+        }
+        self.stats
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(address.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Records a request to `address` that completed after `latency`.
+    pub fn record_success(&self, address: &str, latency: Duration) {
+        let stats = self.node_stats(address);
+        let sample = latency.as_micros().max(1) as f64;
+        let previous = stats.latency_micros.load(Ordering::Relaxed);
+        let smoothed = if previous == 0 {
+            sample
+        } else {
+            previous as f64 + LATENCY_SMOOTHING_FACTOR * (sample - previous as f64)
+        };
+        stats
+            .latency_micros
+            .store(smoothed.round().max(1.0) as u64, Ordering::Relaxed);
+        stats.consecutive_failures.store(0, Ordering::Relaxed);
+    }
+
+    /// Records a request to `address` that failed.
+    pub fn record_failure(&self, address: &str) {
+        self.node_stats(address)
+            .consecutive_failures
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Chooses between `replicas`, given as `(address, availability zone)` pairs. Returns the
+    /// index of the chosen replica, or `None` to read from the primary.
+    pub fn select(&self, slot: Option<u16>, replicas: &[(&str, Option<&str>)]) -> Option<usize> {
+        if replicas.is_empty() {
+            return None;
+        }
+        let stats = self
+            .stats
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let candidates: Vec<ReplicaCandidate<'_>> = replicas
+            .iter()
+            .map(|(address, az)| {
+                let node_stats = stats.get(*address);
+                ReplicaCandidate {
+                    address,
+                    az: *az,
+                    latency: node_stats
+                        .map(|stats| stats.latency_micros.load(Ordering::Relaxed))
+                        .filter(|micros| *micros > 0)
+                        .map(Duration::from_micros),
+                    consecutive_failures: node_stats.map_or(0, |stats| {
+                        stats.consecutive_failures.load(Ordering::Relaxed)
+                    }),
+                }
+            })
+            .collect();
+        drop(stats);
+        self.selector
+            .select(slot, &candidates)
+            .filter(|index| *index < candidates.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        address: &'static str,
+        az: Option<&'static str>,
+        latency_ms: u64,
+    ) -> ReplicaCandidate<'static> {
+        ReplicaCandidate {
+            address,
+            az,
+            latency: Some(Duration::from_millis(latency_ms)),
+            consecutive_failures: 0,
+        }
+    }
+
+    #[test]
+    fn test_round_robin_selector() {
+        let selector = RoundRobinReplicaSelector::default();
+        let candidates = [candidate("a:1", None, 1), candidate("b:1", None, 1)];
+        let picks: Vec<_> = (0..4)
+            .map(|_| selector.select(Some(0), &candidates).unwrap())
+            .collect();
+        assert_eq!(picks, vec![0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_az_preferred_selector() {
+        let selector = AzPreferredReplicaSelector::new("az-1");
+        let candidates = [
+            candidate("a:1", Some("az-2"), 1),
+            candidate("b:1", Some("az-1"), 1),
+            candidate("c:1", None, 1),
+        ];
+        for _ in 0..3 {
+            assert_eq!(selector.select(Some(0), &candidates), Some(1));
+        }
+        // Without local replicas, all replicas are used
+        let remote = &candidates[..1];
+        assert_eq!(selector.select(None, remote), Some(0));
+    }
+
+    #[test]
+    fn test_latency_weights() {
+        let mut slow = candidate("b:1", None, 4);
+        let unmeasured = ReplicaCandidate {
+            latency: None,
+            ..candidate("c:1", None, 0)
+        };
+        let weights = LatencyWeightedReplicaSelector::weights(&[
+            candidate("a:1", None, 1),
+            slow.clone(),
+            unmeasured.clone(),
+        ]);
+        assert_eq!(weights[0], weights[2]);
+        assert!((weights[0] / weights[1] - 4.0).abs() < 1e-9);
+
+        slow.consecutive_failures = 1;
+        let weights = LatencyWeightedReplicaSelector::weights(&[slow, unmeasured]);
+        // Unmeasured replicas are weighted like the fastest measured one
+        assert!((weights[1] / weights[0] - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_selection_passes_recorded_stats() {
+        #[derive(Debug)]
+        struct Fastest;
+        impl ReplicaSelector for Fastest {
+            fn select(&self, _: Option<u16>, candidates: &[ReplicaCandidate<'_>]) -> Option<usize> {
+                (0..candidates.len())
+                    .filter(|index| candidates[*index].consecutive_failures == 0)
+                    .min_by_key(|index| candidates[*index].latency.unwrap_or(Duration::MAX))
+            }
+        }
+
+        let selection = ReplicaSelection::new(Arc::new(Fastest));
+        let replicas = [("a:1", None), ("b:1", None), ("c:1", None)];
+        assert_eq!(selection.select(Some(1), &replicas), Some(0));
+
+        selection.record_success("b:1", Duration::from_millis(2));
+        selection.record_success("c:1", Duration::from_millis(1));
+        assert_eq!(selection.select(Some(1), &replicas), Some(2));
+
+        selection.record_failure("c:1");
+        assert_eq!(selection.select(Some(1), &replicas), Some(1));
+        assert_eq!(selection.select(Some(1), &[]), None);
+    }
+}
@@ -132,6 +132,13 @@ pub enum ErrorKind {
     ClientError,
     /// Client circuit breaker is open, rejecting requests.
     CircuitBreakerOpen,
+    /// The client's memory budget is exhausted, rejecting requests.
+    OutOfClientMemory,
+    /// The response doesn't match the shape expected for the command, in strict response
+    /// validation mode.
+    UnexpectedResponseShape,
+    /// The client is shutting down, and rejected or cancelled the request.
+    ClientClosing,
     /// An extension error.  This is an error created by the server
     /// that is not directly understood by the library.
     ExtensionError,
@@ -421,6 +428,17 @@ pub enum VerbatimFormat {
 pub enum PushKind {
     /// `Disconnection` is sent from the **library** when connection is closed.
     Disconnection,
+    /// `TopologyChange` is sent from the **library** when a slot refresh finds that nodes were
+    /// added or removed, or that a shard failed over. See [`crate::cluster_topology::TopologyChangeEvent`].
+    TopologyChange,
+    /// `SubscriptionDenied` is sent from the **library** for each channel whose subscription the
+    /// server refused due to missing ACL permissions. Its data is `[kind, channel, error]`, where
+    /// kind is `exact`, `pattern` or `sharded`.
+    SubscriptionDenied,
+    /// `CredentialExpiry` is sent from the **library** when a TLS client certificate or the IAM
+    /// credentials are about to expire, or have expired. Its data is
+    /// `[credential, "expiring_soon", remaining_seconds]` or `[credential, "expired"]`.
+    CredentialExpiry,
     /// Other kind to catch future kinds.
     Other(String),
     /// `invalidate` is received when a key is changed/deleted.
@@ -492,6 +510,9 @@ impl fmt::Display for PushKind {
             PushKind::PSubscribe => write!(f, "psubscribe"),
             PushKind::SSubscribe => write!(f, "ssubscribe"),
             PushKind::Disconnection => write!(f, "disconnection"),
+            PushKind::TopologyChange => write!(f, "topology_change"),
+            PushKind::SubscriptionDenied => write!(f, "subscription_denied"),
+            PushKind::CredentialExpiry => write!(f, "credential_expiry"),
         }
     }
 }
@@ -958,6 +979,9 @@ impl RedisError {
             ErrorKind::ExtensionError => "extension error",
             ErrorKind::ClientError => "client error",
             ErrorKind::CircuitBreakerOpen => "circuit breaker open",
+            ErrorKind::OutOfClientMemory => "out of client memory",
+            ErrorKind::UnexpectedResponseShape => "unexpected response shape",
+            ErrorKind::ClientClosing => "client closing",
             ErrorKind::ReadOnly => "read-only",
             ErrorKind::MasterNameNotFoundBySentinel => "master name not found by sentinel",
             ErrorKind::NoValidReplicasFoundBySentinel => "no valid replicas found by sentinel",
@@ -1146,6 +1170,9 @@ impl RedisError {
             ErrorKind::CrossSlot => RetryMethod::NoRetry,
             ErrorKind::ClientError => RetryMethod::NoRetry,
             ErrorKind::CircuitBreakerOpen => RetryMethod::NoRetry,
+            ErrorKind::OutOfClientMemory => RetryMethod::NoRetry,
+            ErrorKind::UnexpectedResponseShape => RetryMethod::NoRetry,
+            ErrorKind::ClientClosing => RetryMethod::NoRetry,
             ErrorKind::EmptySentinelList => RetryMethod::NoRetry,
             ErrorKind::NotBusy => RetryMethod::NoRetry,
             ErrorKind::RESP3NotSupported => RetryMethod::NoRetry,
@@ -1906,6 +1906,40 @@ mod cluster_async {
         assert_eq!(requests.load(atomic::Ordering::SeqCst), 3);
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_refreshes_slots_from_all_connected_seeds() {
+        let name = "refreshes_slots_from_all_connected_seeds";
+
+        let slots_requests = Arc::new(std::sync::Mutex::new(std::collections::HashSet::new()));
+
+        let MockEnv {
+            handler: _handler, ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![
+                &*format!("redis://{name}:6379"),
+                &*format!("redis://{name}:6380"),
+            ]),
+            name,
+            {
+                let slots_requests = slots_requests.clone();
+                move |cmd: &[u8], port| {
+                    if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                        slots_requests.lock().unwrap().insert(port);
+                    }
+                    respond_startup_two_nodes(name, cmd)?;
+                    Ok(())
+                }
+            },
+        );
+
+        // Both seeds connect at once, so the initial topology is the view of both.
+        assert_eq!(
+            *slots_requests.lock().unwrap(),
+            std::collections::HashSet::from([6379, 6380])
+        );
+    }
+
     // Obtain the view index associated with the node with [called_port] port
     fn get_node_view_index(num_of_views: usize, ports: &Vec<u16>, called_port: u16) -> usize {
         let port_index = ports
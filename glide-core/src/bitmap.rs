@@ -0,0 +1,422 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Typed builders for bitmap commands.
+//!
+//! [`bitcount_cmd`] and [`bitpos_cmd`] encode the optional ranges of `BITCOUNT` and `BITPOS`, and
+//! [`BitFieldBuilder`] builds `BITFIELD` and `BITFIELD_RO` commands from typed subcommands, with
+//! [`BitFieldBuilder::parse_response`] returning one result per `GET`, `SET` and `INCRBY`.
+
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+
+fn client_error(description: &'static str) -> RedisError {
+    RedisError::from((ErrorKind::ClientError, description))
+}
+
+/// Whether the offsets of a range count bytes or bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitmapIndexType {
+    Byte,
+    Bit,
+}
+
+impl BitmapIndexType {
+    fn as_arg(self) -> &'static str {
+        match self {
+            BitmapIndexType::Byte => "BYTE",
+            BitmapIndexType::Bit => "BIT",
+        }
+    }
+}
+
+/// The range of a `BITCOUNT` or `BITPOS`. Negative offsets count from the end of the string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitRange {
+    pub start: i64,
+    /// The inclusive end of the range. Required by `BITCOUNT`, and defaults to the end of the
+    /// string for `BITPOS`.
+    pub end: Option<i64>,
+    /// Requires `end`. Defaults to bytes.
+    pub index_type: Option<BitmapIndexType>,
+}
+
+impl BitRange {
+    fn append_to(&self, cmd: &mut Cmd) -> RedisResult<()> {
+        cmd.arg(self.start);
+        match (self.end, self.index_type) {
+            (Some(end), index_type) => {
+                cmd.arg(end);
+                if let Some(index_type) = index_type {
+                    cmd.arg(index_type.as_arg());
+                }
+            }
+            (None, Some(_)) => {
+                return Err(client_error(
+                    "A bitmap index type requires the end of the range",
+                ));
+            }
+            (None, None) => {}
+        }
+        Ok(())
+    }
+}
+
+/// Builds `BITCOUNT key [start end [BYTE|BIT]]`.
+pub fn bitcount_cmd(key: &[u8], range: Option<BitRange>) -> RedisResult<Cmd> {
+    let mut cmd = redis::cmd("BITCOUNT");
+    cmd.arg(key);
+    if let Some(range) = range {
+        if range.end.is_none() {
+            return Err(client_error("BITCOUNT requires the end of the range"));
+        }
+        range.append_to(&mut cmd)?;
+    }
+    Ok(cmd)
+}
+
+/// Builds `BITPOS key bit [start [end [BYTE|BIT]]]`.
+pub fn bitpos_cmd(key: &[u8], bit: bool, range: Option<BitRange>) -> RedisResult<Cmd> {
+    let mut cmd = redis::cmd("BITPOS");
+    cmd.arg(key).arg(u8::from(bit));
+    if let Some(range) = range {
+        range.append_to(&mut cmd)?;
+    }
+    Ok(cmd)
+}
+
+/// The type of a `BITFIELD` integer, e.g. `i8` or `u16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitFieldEncoding {
+    pub signed: bool,
+    /// Up to 64 bits for signed integers, and 63 for unsigned ones.
+    pub bits: u8,
+}
+
+impl BitFieldEncoding {
+    pub fn signed(bits: u8) -> Self {
+        Self { signed: true, bits }
+    }
+
+    pub fn unsigned(bits: u8) -> Self {
+        Self {
+            signed: false,
+            bits,
+        }
+    }
+
+    fn to_arg(self) -> RedisResult<String> {
+        let max_bits = if self.signed { 64 } else { 63 };
+        if self.bits == 0 || self.bits > max_bits {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid BITFIELD encoding",
+                format!(
+                    "{} integers must have 1 to {max_bits} bits, got {}",
+                    if self.signed { "Signed" } else { "Unsigned" },
+                    self.bits
+                ),
+            )));
+        }
+        Ok(format!(
+            "{}{}",
+            if self.signed { "i" } else { "u" },
+            self.bits
+        ))
+    }
+}
+
+/// The position of a `BITFIELD` integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFieldOffset {
+    /// An offset in bits.
+    Bits(u64),
+    /// An offset in multiples of the encoding's size, e.g. `#2` is the third `u8` (`#`-prefixed).
+    Multiplied(u64),
+}
+
+impl BitFieldOffset {
+    fn to_arg(self) -> String {
+        match self {
+            BitFieldOffset::Bits(offset) => offset.to_string(),
+            BitFieldOffset::Multiplied(offset) => format!("#{offset}"),
+        }
+    }
+}
+
+/// How the following `SET` and `INCRBY` subcommands handle overflows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFieldOverflow {
+    /// Wrap around (the default).
+    Wrap,
+    /// Saturate at the minimal or maximal value.
+    Sat,
+    /// Don't write, and return nil.
+    Fail,
+}
+
+impl BitFieldOverflow {
+    fn as_arg(self) -> &'static str {
+        match self {
+            BitFieldOverflow::Wrap => "WRAP",
+            BitFieldOverflow::Sat => "SAT",
+            BitFieldOverflow::Fail => "FAIL",
+        }
+    }
+}
+
+/// A `BITFIELD` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitFieldSubCommand {
+    Get {
+        encoding: BitFieldEncoding,
+        offset: BitFieldOffset,
+    },
+    Set {
+        encoding: BitFieldEncoding,
+        offset: BitFieldOffset,
+        value: i64,
+    },
+    IncrBy {
+        encoding: BitFieldEncoding,
+        offset: BitFieldOffset,
+        increment: i64,
+    },
+    Overflow(BitFieldOverflow),
+}
+
+impl BitFieldSubCommand {
+    /// Returns whether the server returns a result for the subcommand.
+    fn has_result(&self) -> bool {
+        !matches!(self, BitFieldSubCommand::Overflow(_))
+    }
+}
+
+/// Builder for `BITFIELD key [GET encoding offset | [OVERFLOW WRAP|SAT|FAIL]
+/// SET encoding offset value | INCRBY encoding offset increment] ...`, or `BITFIELD_RO` when
+/// read-only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitFieldBuilder {
+    key: Vec<u8>,
+    read_only: bool,
+    subcommands: Vec<BitFieldSubCommand>,
+}
+
+impl BitFieldBuilder {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            read_only: false,
+            subcommands: Vec::new(),
+        }
+    }
+
+    /// If `true`, builds `BITFIELD_RO`, which can be sent to replicas but only accepts `GET`.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    pub fn get(self, encoding: BitFieldEncoding, offset: BitFieldOffset) -> Self {
+        self.subcommand(BitFieldSubCommand::Get { encoding, offset })
+    }
+
+    pub fn set(self, encoding: BitFieldEncoding, offset: BitFieldOffset, value: i64) -> Self {
+        self.subcommand(BitFieldSubCommand::Set {
+            encoding,
+            offset,
+            value,
+        })
+    }
+
+    pub fn incr_by(
+        self,
+        encoding: BitFieldEncoding,
+        offset: BitFieldOffset,
+        increment: i64,
+    ) -> Self {
+        self.subcommand(BitFieldSubCommand::IncrBy {
+            encoding,
+            offset,
+            increment,
+        })
+    }
+
+    pub fn overflow(self, overflow: BitFieldOverflow) -> Self {
+        self.subcommand(BitFieldSubCommand::Overflow(overflow))
+    }
+
+    pub fn subcommand(mut self, subcommand: BitFieldSubCommand) -> Self {
+        self.subcommands.push(subcommand);
+        self
+    }
+
+    pub fn build(&self) -> RedisResult<Cmd> {
+        let mut cmd = redis::cmd(if self.read_only {
+            "BITFIELD_RO"
+        } else {
+            "BITFIELD"
+        });
+        cmd.arg(&self.key);
+        for subcommand in &self.subcommands {
+            match *subcommand {
+                BitFieldSubCommand::Get { encoding, offset } => {
+                    cmd.arg("GET").arg(encoding.to_arg()?).arg(offset.to_arg());
+                }
+                _ if self.read_only => {
+                    return Err(client_error("BITFIELD_RO only supports GET subcommands"));
+                }
+                BitFieldSubCommand::Set {
+                    encoding,
+                    offset,
+                    value,
+                } => {
+                    cmd.arg("SET")
+                        .arg(encoding.to_arg()?)
+                        .arg(offset.to_arg())
+                        .arg(value);
+                }
+                BitFieldSubCommand::IncrBy {
+                    encoding,
+                    offset,
+                    increment,
+                } => {
+                    cmd.arg("INCRBY")
+                        .arg(encoding.to_arg()?)
+                        .arg(offset.to_arg())
+                        .arg(increment);
+                }
+                BitFieldSubCommand::Overflow(overflow) => {
+                    cmd.arg("OVERFLOW").arg(overflow.as_arg());
+                }
+            }
+        }
+        Ok(cmd)
+    }
+
+    /// Parses the response of the command built by this builder into one result per `GET`, `SET`
+    /// and `INCRBY`, in order. `SET` returns the previous value, and a result is `None` if the
+    /// subcommand failed on `OVERFLOW FAIL`.
+    pub fn parse_response(&self, value: Value) -> RedisResult<Vec<Option<i64>>> {
+        let expected = self
+            .subcommands
+            .iter()
+            .filter(|subcommand| subcommand.has_result())
+            .count();
+        let unexpected = |value: &Value| {
+            RedisError::from((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to BITFIELD results",
+                format!("(expected {expected} integers, response was {value:?})"),
+            ))
+        };
+        let Value::Array(results) = value else {
+            return Err(unexpected(&value));
+        };
+        if results.len() != expected {
+            return Err(unexpected(&Value::Array(results)));
+        }
+        results
+            .into_iter()
+            .map(|result| match result {
+                Value::Int(result) => Ok(Some(result)),
+                Value::Nil => Ok(None),
+                other => Err(unexpected(&other)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bitcount_and_bitpos() {
+        let range = BitRange {
+            start: 1,
+            end: Some(-1),
+            index_type: Some(BitmapIndexType::Bit),
+        };
+        let cmd = bitcount_cmd(b"bits", Some(range)).unwrap();
+        assert_eq!(args(&cmd), vec!["BITCOUNT", "bits", "1", "-1", "BIT"]);
+        let cmd = bitcount_cmd(b"bits", None).unwrap();
+        assert_eq!(args(&cmd), vec!["BITCOUNT", "bits"]);
+
+        let open_range = BitRange {
+            start: 2,
+            end: None,
+            index_type: None,
+        };
+        assert!(bitcount_cmd(b"bits", Some(open_range)).is_err());
+        let cmd = bitpos_cmd(b"bits", true, Some(open_range)).unwrap();
+        assert_eq!(args(&cmd), vec!["BITPOS", "bits", "1", "2"]);
+
+        let invalid_range = BitRange {
+            index_type: Some(BitmapIndexType::Byte),
+            ..open_range
+        };
+        assert!(bitpos_cmd(b"bits", false, Some(invalid_range)).is_err());
+    }
+
+    #[test]
+    fn test_bitfield_builder() {
+        let builder = BitFieldBuilder::new("counters")
+            .get(BitFieldEncoding::unsigned(8), BitFieldOffset::Bits(0))
+            .overflow(BitFieldOverflow::Fail)
+            .incr_by(
+                BitFieldEncoding::signed(16),
+                BitFieldOffset::Multiplied(2),
+                5,
+            )
+            .set(BitFieldEncoding::unsigned(4), BitFieldOffset::Bits(100), 7);
+        assert_eq!(
+            args(&builder.build().unwrap()),
+            vec![
+                "BITFIELD", "counters", "GET", "u8", "0", "OVERFLOW", "FAIL", "INCRBY", "i16",
+                "#2", "5", "SET", "u4", "100", "7"
+            ]
+        );
+        assert!(builder.clone().read_only(true).build().is_err());
+
+        let response = Value::Array(vec![Value::Int(3), Value::Nil, Value::Int(0)]);
+        assert_eq!(
+            builder.parse_response(response).unwrap(),
+            vec![Some(3), None, Some(0)]
+        );
+        assert!(
+            builder
+                .parse_response(Value::Array(vec![Value::Int(3)]))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_bitfield_read_only_and_encodings() {
+        let cmd = BitFieldBuilder::new("counters")
+            .read_only(true)
+            .get(BitFieldEncoding::signed(64), BitFieldOffset::Bits(8))
+            .build()
+            .unwrap();
+        assert_eq!(
+            args(&cmd),
+            vec!["BITFIELD_RO", "counters", "GET", "i64", "8"]
+        );
+
+        for encoding in [BitFieldEncoding::unsigned(64), BitFieldEncoding::signed(0)] {
+            assert!(
+                BitFieldBuilder::new("counters")
+                    .get(encoding, BitFieldOffset::Bits(0))
+                    .build()
+                    .is_err()
+            );
+        }
+    }
+}
@@ -0,0 +1,374 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Administrative commands that act on every node, and the graceful closing of a client.
+//!
+//! `SCRIPT KILL`, `FUNCTION KILL`, `CLIENT KILL` and `CLIENT UNPAUSE` only affect the node they
+//! are sent to, while the scripts, connections and pauses they target may be on any node -
+//! replicas included. The helpers send the command to every node, and return the result of each
+//! node by its address, so that a node failing doesn't hide the outcome on the others.
+//!
+//! [`Client::close_all_connections`] and [`Client::shutdown`] close a client and all its clones:
+//! new requests are rejected, the requests in flight are given until a deadline to complete, and
+//! the requests still in flight at the deadline are cancelled with a `ClientClosing` error. A
+//! shutdown also stops the client's background tasks and flushes the telemetry.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use futures::future::join_all;
+use redis::cluster_async::InflightReleaseNotifier;
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+use telemetrylib::GlideOpenTelemetry;
+use tokio::sync::watch;
+
+use super::health_check::parse_address;
+use super::{Client, ClientWrapper, LazyClient};
+
+/// The closing state shared by a client and its clones.
+#[derive(Debug)]
+pub(crate) struct CloseSignal {
+    closed: AtomicBool,
+    cancelled: watch::Sender<bool>,
+    /// Woken when a request in flight releases its slot, while the client drains its requests.
+    inflight_released: Arc<InflightReleaseNotifier>,
+}
+
+impl Default for CloseSignal {
+    fn default() -> Self {
+        Self {
+            closed: AtomicBool::new(false),
+            cancelled: watch::channel(false).0,
+            inflight_released: Default::default(),
+        }
+    }
+}
+
+impl CloseSignal {
+    /// Returns whether the client rejects new requests.
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+    }
+
+    pub(crate) fn inflight_released(&self) -> Arc<InflightReleaseNotifier> {
+        self.inflight_released.clone()
+    }
+
+    /// Cancels the requests in flight.
+    fn cancel(&self) {
+        self.close();
+        self.cancelled.send_replace(true);
+    }
+
+    /// Runs `request`, unless the client cancels its requests in flight first.
+    pub(crate) async fn cancellable<T>(
+        self: Arc<Self>,
+        request: impl Future<Output = RedisResult<T>>,
+    ) -> RedisResult<T> {
+        let mut cancelled = self.cancelled.subscribe();
+        tokio::select! {
+            biased;
+            result = request => result,
+            // The sender is owned by `self`, so the channel can't close.
+            _ = cancelled.wait_for(|cancelled| *cancelled) => Err(RedisError::from((
+                ErrorKind::ClientClosing,
+                "The client shut down before the request completed",
+            ))),
+        }
+    }
+}
+
+/// The results of a command sent to several nodes, by node address.
+#[derive(Debug, Default)]
+pub struct NodeResults<T> {
+    pub by_node: BTreeMap<String, RedisResult<T>>,
+}
+
+impl<T> NodeResults<T> {
+    /// Returns the errors of the nodes that failed.
+    pub fn errors(&self) -> impl Iterator<Item = (&str, &RedisError)> {
+        self.by_node
+            .iter()
+            .filter_map(|(address, result)| Some((address.as_str(), result.as_ref().err()?)))
+    }
+
+    /// Returns the result of each node, or the first error if any node failed.
+    pub fn into_result(self) -> RedisResult<BTreeMap<String, T>> {
+        self.by_node
+            .into_iter()
+            .map(|(address, result)| Ok((address, result?)))
+            .collect()
+    }
+}
+
+impl NodeResults<bool> {
+    /// Returns whether the command took effect on any node.
+    pub fn any(&self) -> bool {
+        self.by_node
+            .values()
+            .any(|result| matches!(result, Ok(true)))
+    }
+}
+
+impl NodeResults<i64> {
+    /// Returns the sum of the nodes' successful results.
+    pub fn total(&self) -> i64 {
+        self.by_node
+            .values()
+            .filter_map(|result| result.as_ref().ok())
+            .sum()
+    }
+}
+
+/// Converts the response of a kill command, where `NOTBUSY` means there was nothing to kill.
+fn killed(result: RedisResult<Value>) -> RedisResult<bool> {
+    match result {
+        Ok(_) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::NotBusy => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+fn integer(result: RedisResult<Value>) -> RedisResult<i64> {
+    match result? {
+        Value::Int(integer) => Ok(integer),
+        response => Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Unexpected response",
+            format!("expected an integer, got {response:?}"),
+        ))),
+    }
+}
+
+impl Client {
+    /// Runs `SCRIPT KILL` on every node. A node's result is whether it was running a script.
+    pub async fn script_kill(&mut self) -> RedisResult<NodeResults<bool>> {
+        let mut cmd = redis::cmd("SCRIPT");
+        cmd.arg("KILL");
+        self.send_to_each_node(cmd, killed).await
+    }
+
+    /// Runs `FUNCTION KILL` on every node. A node's result is whether it was running a function.
+    pub async fn function_kill(&mut self) -> RedisResult<NodeResults<bool>> {
+        let mut cmd = redis::cmd("FUNCTION");
+        cmd.arg("KILL");
+        self.send_to_each_node(cmd, killed).await
+    }
+
+    /// Runs `CLIENT KILL` with the given filters, such as `("ID", "42")` or `("USER", "app")`, on
+    /// every node. A node's result is the number of connections it closed.
+    pub async fn client_kill(&mut self, filters: &[(&str, &str)]) -> RedisResult<NodeResults<i64>> {
+        if filters.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "CLIENT KILL requires at least one filter",
+            )));
+        }
+        let mut cmd = redis::cmd("CLIENT");
+        cmd.arg("KILL");
+        for (filter, value) in filters {
+            cmd.arg(filter).arg(value);
+        }
+        self.send_to_each_node(cmd, integer).await
+    }
+
+    /// Runs `CLIENT UNPAUSE` on every node.
+    pub async fn client_unpause(&mut self) -> RedisResult<NodeResults<()>> {
+        let mut cmd = redis::cmd("CLIENT");
+        cmd.arg("UNPAUSE");
+        self.send_to_each_node(cmd, |result| result.map(|_| ()))
+            .await
+    }
+
+    /// Closes the client: new requests fail at once, the requests in flight are given until
+    /// `drain_timeout` to complete, and the connections are then closed. Clones of the client are
+    /// closed too. Returns the number of requests still in flight at the timeout - these fail with
+    /// a `ClientClosing` error.
+    pub async fn close_all_connections(&self, drain_timeout: Duration) -> usize {
+        self.close_signal.close();
+        let cancelled = self.drain_inflight_requests(drain_timeout).await;
+        self.close_signal.cancel();
+        self.drop_connections().await;
+        cancelled
+    }
+
+    /// Shuts the client down: new requests fail at once, and the requests in flight are given
+    /// until `deadline` to complete. The requests still in flight then fail with a `ClientClosing`
+    /// error, the pubsub and IAM token refresh tasks are stopped, the connections are closed and
+    /// the telemetry is flushed. Clones of the client are shut down too. Returns the number of
+    /// requests cancelled.
+    pub async fn shutdown(&self, deadline: Duration) -> usize {
+        self.close_signal.close();
+        let cancelled = self.drain_inflight_requests(deadline).await;
+        self.close_signal.cancel();
+        self.pubsub_synchronizer.stop();
+        if let Some(iam_token_manager) = &self.iam_token_manager {
+            iam_token_manager.stop();
+        }
+        self.drop_connections().await;
+        if GlideOpenTelemetry::is_initialized() {
+            // Flushing blocks until the telemetry is exported by the runtime's tasks.
+            let _ = tokio::task::spawn_blocking(GlideOpenTelemetry::flush).await;
+        }
+        cancelled
+    }
+
+    /// Waits until the requests in flight complete or `timeout` elapses, and returns the number
+    /// of requests still in flight.
+    async fn drain_inflight_requests(&self, timeout: Duration) -> usize {
+        let drained = self
+            .close_signal
+            .inflight_released
+            .wait_until(|| self.inflight_request_count() <= 0);
+        let _ = tokio::time::timeout(timeout, drained).await;
+        self.inflight_request_count().max(0) as usize
+    }
+
+    async fn drop_connections(&self) {
+        // The connections close once the requests in flight, which hold clones of the wrapper,
+        // complete. The replacement is never connected, as the client rejects new requests.
+        let wrapper = std::mem::replace(
+            &mut *self.internal_client.write().await,
+            ClientWrapper::Lazy(Box::new(LazyClient {
+                config: Default::default(),
+                push_sender: None,
+            })),
+        );
+        drop(wrapper);
+    }
+
+    /// Sends `cmd` to every node, and converts the response of each node with `convert`.
+    async fn send_to_each_node<T>(
+        &mut self,
+        cmd: redis::Cmd,
+        convert: impl Fn(RedisResult<Value>) -> RedisResult<T>,
+    ) -> RedisResult<NodeResults<T>> {
+        let responses = match self.get_or_initialize_client().await? {
+            ClientWrapper::Standalone(client) => {
+                let requests = client.node_addresses().into_iter().map(|address| {
+                    let (client, cmd) = (&client, &cmd);
+                    async move {
+                        let response = client.send_command_to_node(cmd, &address).await;
+                        (address, response)
+                    }
+                });
+                join_all(requests).await
+            }
+            ClientWrapper::Cluster { mut client } => {
+                let requests = client
+                    .get_node_addresses()
+                    .await?
+                    .into_iter()
+                    .map(|address| {
+                        let (mut client, mut cmd) = (self.clone(), cmd.clone());
+                        async move {
+                            let response =
+                                match parse_address(&address) {
+                                    Ok((host, port)) => {
+                                        let routing = RoutingInfo::SingleNode(
+                                            SingleNodeRoutingInfo::ByAddress { host, port },
+                                        );
+                                        client.send_command(&mut cmd, Some(routing)).await
+                                    }
+                                    Err(err) => Err(err),
+                                };
+                            (address, response)
+                        }
+                    });
+                join_all(requests).await
+            }
+            ClientWrapper::Lazy(_) => {
+                return Err(RedisError::from((
+                    ErrorKind::ClientError,
+                    "Client is not initialized",
+                )));
+            }
+        };
+        Ok(NodeResults {
+            by_node: responses
+                .into_iter()
+                .map(|(address, response)| (address, convert(response)))
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results<T>(by_node: Vec<(&str, RedisResult<T>)>) -> NodeResults<T> {
+        NodeResults {
+            by_node: by_node
+                .into_iter()
+                .map(|(address, result)| (address.to_string(), result))
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cancel_fails_pending_requests() {
+        let signal = Arc::new(CloseSignal::default());
+        let completed = signal.clone().cancellable(async { Ok(1) }).await;
+        assert_eq!(completed.unwrap(), 1);
+
+        let pending = tokio::spawn(
+            signal
+                .clone()
+                .cancellable(futures::future::pending::<RedisResult<()>>()),
+        );
+        assert!(!signal.is_closed());
+        signal.cancel();
+        assert!(signal.is_closed());
+        let err = pending.await.unwrap().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientClosing);
+
+        // Requests started after the cancellation fail at once.
+        let late = signal
+            .clone()
+            .cancellable(futures::future::pending::<RedisResult<()>>())
+            .await;
+        assert_eq!(late.unwrap_err().kind(), ErrorKind::ClientClosing);
+    }
+
+    #[test]
+    fn test_not_busy_means_nothing_was_killed() {
+        assert!(killed(Ok(Value::Okay)).unwrap());
+        let not_busy = RedisError::from((ErrorKind::NotBusy, "No scripts in execution right now"));
+        assert!(!killed(Err(not_busy)).unwrap());
+        let io_error = RedisError::from(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert!(killed(Err(io_error)).is_err());
+    }
+
+    #[test]
+    fn test_node_results_aggregation() {
+        let kills = results(vec![("a:6379", Ok(false)), ("b:6379", Ok(true))]);
+        assert!(kills.any());
+        assert_eq!(kills.errors().count(), 0);
+
+        let counts = results(vec![
+            ("a:6379", Ok(2)),
+            ("b:6379", Ok(3)),
+            (
+                "c:6379",
+                Err(RedisError::from((ErrorKind::ResponseError, "ERR"))),
+            ),
+        ]);
+        assert_eq!(counts.total(), 5);
+        assert_eq!(
+            counts
+                .errors()
+                .map(|(address, _)| address)
+                .collect::<Vec<_>>(),
+            ["c:6379"]
+        );
+        assert!(counts.into_result().is_err());
+    }
+}
@@ -0,0 +1,371 @@
+//! Warming of the client-side cache.
+//!
+//! [`Client::cache_warm`] loads the values of string keys into the client-side cache before the
+//! application reads them, so that a freshly deployed service starts with a hot cache. The keys are
+//! fetched with one `MGET` per slot, all sent in a single pipeline per batch. Keys matching a pattern
+//! are fetched after each page of the scan. The values are cached as if they were read with `GET`,
+//! and warming stops once the cache is full, so it never evicts entries that are already cached.
+
+use std::collections::{BTreeMap, HashSet};
+
+use logger_core::log_warn;
+use redis::cache::glide_cache::{CachedKeyType, GlideCache, calculate_entry_size};
+use redis::cluster_async::ClusterConnection;
+use redis::cluster_topology::get_slot;
+use redis::{
+    ClusterScanArgs, ErrorKind, Pipeline, PipelineRetryStrategy, RedisError, RedisResult,
+    ScanStateRC, Value,
+};
+
+use super::{Client, ClientWrapper};
+
+/// Maximal number of keys fetched by a single pipeline, and by a single `MGET`.
+pub(super) const KEYS_PER_BATCH: usize = 1000;
+/// The `COUNT` hint of the scan used to find the keys matching a pattern.
+const SCAN_COUNT: u32 = 1000;
+
+/// The keys loaded by [`Client::cache_warm`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheWarmTarget {
+    /// The given keys.
+    Keys(Vec<Vec<u8>>),
+    /// The keys matching a glob-style pattern, as accepted by `SCAN MATCH`.
+    Pattern(Vec<u8>),
+}
+
+/// The position of a scan for the keys matching a pattern.
+enum KeyScan {
+    Cluster {
+        client: ClusterConnection,
+        args: ClusterScanArgs,
+        cursor: ScanStateRC,
+    },
+    Standalone {
+        cursor: Vec<u8>,
+    },
+    Finished,
+}
+
+impl Client {
+    /// Loads the values of the target keys into the client-side cache, and returns the number of
+    /// keys that were cached. Keys that don't exist or don't hold a string are skipped.
+    /// Returns an error if client-side caching is not enabled.
+    pub async fn cache_warm(&mut self, target: CacheWarmTarget) -> RedisResult<usize> {
+        let cache = self.client_side_cache.clone().ok_or_else(|| {
+            RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "Client-side caching is not enabled",
+            ))
+        })?;
+        match target {
+            CacheWarmTarget::Keys(keys) => {
+                let (cached, _) = self.fetch_into_cache(cache.as_ref(), keys).await?;
+                Ok(cached)
+            }
+            CacheWarmTarget::Pattern(pattern) => {
+                // Each page of the scan is fetched before the next one is scanned, so that a
+                // full cache stops the scan too.
+                let mut scan = self.start_key_scan(&pattern).await?;
+                let mut seen = HashSet::new();
+                let mut cached = 0;
+                while let Some(keys) = self.next_scanned_keys(&mut scan, &pattern).await? {
+                    let keys = keys
+                        .into_iter()
+                        .filter(|key| seen.insert(key.clone()))
+                        .collect();
+                    let (page_cached, cache_full) =
+                        self.fetch_into_cache(cache.as_ref(), keys).await?;
+                    cached += page_cached;
+                    if cache_full {
+                        break;
+                    }
+                }
+                Ok(cached)
+            }
+        }
+    }
+
+    /// Fetches `keys` into the cache. Returns the number of cached keys, and whether warming
+    /// stopped because the cache is full.
+    async fn fetch_into_cache(
+        &mut self,
+        cache: &dyn GlideCache,
+        keys: Vec<Vec<u8>>,
+    ) -> RedisResult<(usize, bool)> {
+        let mut cached = 0;
+        for batch in batch_keys_by_slot(keys) {
+            let pipeline = mget_pipeline(&batch);
+            let retry_strategy = PipelineRetryStrategy {
+                retry_server_error: true,
+                retry_connection_error: true,
+            };
+            let Value::Array(responses) = self
+                .send_pipeline(&pipeline, None, false, None, retry_strategy)
+                .await?
+            else {
+                return Err(RedisError::from((
+                    ErrorKind::ResponseError,
+                    "Unexpected response to the cache warming pipeline",
+                )));
+            };
+            let (batch_cached, cache_full) = cache_mget_responses(cache, &batch, responses);
+            cached += batch_cached;
+            if cache_full {
+                return Ok((cached, true));
+            }
+        }
+        Ok((cached, false))
+    }
+
+    /// Starts a scan for the keys matching `pattern`, over all the primaries in cluster mode.
+    async fn start_key_scan(&mut self, pattern: &[u8]) -> RedisResult<KeyScan> {
+        Ok(match self.get_or_initialize_client().await? {
+            ClientWrapper::Cluster { client } => KeyScan::Cluster {
+                client,
+                args: ClusterScanArgs::builder()
+                    .with_match_pattern(pattern)
+                    .with_count(SCAN_COUNT)
+                    .build(),
+                cursor: ScanStateRC::new(),
+            },
+            _ => KeyScan::Standalone {
+                cursor: b"0".to_vec(),
+            },
+        })
+    }
+
+    /// Returns the keys of the next page of `scan`, or `None` once the scan is finished.
+    async fn next_scanned_keys(
+        &mut self,
+        scan: &mut KeyScan,
+        pattern: &[u8],
+    ) -> RedisResult<Option<Vec<Vec<u8>>>> {
+        match std::mem::replace(scan, KeyScan::Finished) {
+            KeyScan::Cluster {
+                mut client,
+                args,
+                cursor,
+            } => {
+                let (next_cursor, found) = client.cluster_scan(cursor, args.clone()).await?;
+                if !next_cursor.is_finished() {
+                    *scan = KeyScan::Cluster {
+                        client,
+                        args,
+                        cursor: next_cursor,
+                    };
+                }
+                Ok(Some(found.into_iter().filter_map(key_bytes).collect()))
+            }
+            KeyScan::Standalone { cursor } => {
+                let mut cmd = redis::cmd("SCAN");
+                cmd.arg(&cursor)
+                    .arg("MATCH")
+                    .arg(pattern)
+                    .arg("COUNT")
+                    .arg(SCAN_COUNT);
+                let (next_cursor, found) =
+                    parse_scan_response(self.send_command(&mut cmd, None).await?)?;
+                if next_cursor != b"0" {
+                    *scan = KeyScan::Standalone {
+                        cursor: next_cursor,
+                    };
+                }
+                Ok(Some(found))
+            }
+            KeyScan::Finished => Ok(None),
+        }
+    }
+}
+
+fn key_bytes(value: Value) -> Option<Vec<u8>> {
+    match value {
+        Value::BulkString(bytes) => Some(bytes),
+        Value::SimpleString(string) => Some(string.into_bytes()),
+        _ => None,
+    }
+}
+
+/// Parses a `SCAN` response into the next cursor and the found keys.
+fn parse_scan_response(response: Value) -> RedisResult<(Vec<u8>, Vec<Vec<u8>>)> {
+    if let Value::Array(mut values) = response
+        && let (2, Some(Value::Array(found))) = (values.len(), values.pop())
+        && let Some(cursor) = values.pop().and_then(key_bytes)
+    {
+        return Ok((cursor, found.into_iter().filter_map(key_bytes).collect()));
+    }
+    Err(RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected SCAN response",
+    )))
+}
+
+/// Keys of the same slot, fetched by a single `MGET`.
+pub(super) type KeyGroup = Vec<Vec<u8>>;
+
+/// Removes duplicate keys, and splits the rest into batches of at most [`KEYS_PER_BATCH`] keys.
+/// Each batch is made of groups of keys of the same slot, so that each group can be fetched by a
+/// single `MGET` in cluster mode.
+pub(super) fn batch_keys_by_slot(keys: Vec<Vec<u8>>) -> Vec<Vec<KeyGroup>> {
+    let mut seen = HashSet::new();
+    let mut slots: BTreeMap<u16, KeyGroup> = BTreeMap::new();
+    for key in keys {
+        if seen.insert(key.clone()) {
+            slots.entry(get_slot(&key)).or_default().push(key);
+        }
+    }
+
+    let mut batches = Vec::new();
+    let mut batch: Vec<KeyGroup> = Vec::new();
+    let mut batch_len = 0;
+    for mut group in slots.into_values() {
+        while !group.is_empty() {
+            let rest = group.split_off(group.len().min(KEYS_PER_BATCH - batch_len));
+            batch_len += group.len();
+            batch.push(group);
+            group = rest;
+            if batch_len == KEYS_PER_BATCH {
+                batches.push(std::mem::take(&mut batch));
+                batch_len = 0;
+            }
+        }
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+pub(super) fn mget_pipeline(groups: &[KeyGroup]) -> Pipeline {
+    let mut pipeline = Pipeline::new();
+    for group in groups {
+        pipeline.add_command(mget_cmd(group));
+    }
+    pipeline
+}
+
+fn mget_cmd(keys: &[Vec<u8>]) -> redis::Cmd {
+    let mut cmd = redis::cmd("MGET");
+    for key in keys {
+        cmd.arg(key);
+    }
+    cmd
+}
+
+/// Caches the values returned by the `MGET`s of `groups`. Returns the number of cached keys, and
+/// whether warming should stop because the cache is full.
+fn cache_mget_responses(
+    cache: &dyn GlideCache,
+    groups: &[KeyGroup],
+    responses: Vec<Value>,
+) -> (usize, bool) {
+    let mut cached = 0;
+    for (keys, response) in groups.iter().zip(responses) {
+        let values = match response {
+            Value::Array(values) => values,
+            Value::ServerError(error) => {
+                log_warn(
+                    "cache_warm",
+                    format!("Failed to fetch {} keys: {error:?}", keys.len()),
+                );
+                continue;
+            }
+            _ => continue,
+        };
+        for (key, value) in keys.iter().zip(values) {
+            // Like cached GET responses, missing keys aren't cached
+            if value == Value::Nil {
+                continue;
+            }
+            if cache
+                .core()
+                .needs_eviction(calculate_entry_size(key, &value))
+            {
+                return (cached, true);
+            }
+            cache.insert(key.clone(), CachedKeyType::String, value);
+            cached += 1;
+        }
+    }
+    (cached, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::cache::glide_cache::CacheConfig;
+    use redis::cache::lru_cache::new_lru_cache;
+
+    #[test]
+    fn test_batch_keys_by_slot() {
+        let keys = vec![
+            b"{user}:1".to_vec(),
+            b"other".to_vec(),
+            b"{user}:2".to_vec(),
+            b"{user}:1".to_vec(),
+        ];
+        let mut batches = batch_keys_by_slot(keys);
+        assert_eq!(batches.len(), 1);
+        batches[0].sort();
+        assert_eq!(
+            batches[0],
+            vec![
+                vec![b"other".to_vec()],
+                vec![b"{user}:1".to_vec(), b"{user}:2".to_vec()],
+            ]
+        );
+
+        let keys: Vec<Vec<u8>> = (0..KEYS_PER_BATCH + 10)
+            .map(|i| format!("{{tag}}:{i}").into_bytes())
+            .chain([b"other".to_vec()])
+            .collect();
+        let batches = batch_keys_by_slot(keys);
+        let batch_lens: Vec<usize> = batches
+            .iter()
+            .map(|batch| batch.iter().map(Vec::len).sum())
+            .collect();
+        assert_eq!(batch_lens, vec![KEYS_PER_BATCH, 11]);
+    }
+
+    #[test]
+    fn test_parse_scan_response() {
+        let response = Value::Array(vec![
+            Value::BulkString(b"17".to_vec()),
+            Value::Array(vec![
+                Value::BulkString(b"a".to_vec()),
+                Value::BulkString(b"b".to_vec()),
+            ]),
+        ]);
+        assert_eq!(
+            parse_scan_response(response).unwrap(),
+            (b"17".to_vec(), vec![b"a".to_vec(), b"b".to_vec()])
+        );
+        assert!(parse_scan_response(Value::Okay).is_err());
+    }
+
+    #[test]
+    fn test_cache_mget_responses_stops_when_full() {
+        let cache = new_lru_cache(CacheConfig {
+            max_memory_bytes: 2 * calculate_entry_size(b"k1", &Value::BulkString(vec![0; 100])),
+            ttl: None,
+            enable_metrics: false,
+        });
+        let groups = vec![
+            vec![b"k1".to_vec(), b"missing".to_vec()],
+            vec![b"k2".to_vec(), b"k3".to_vec()],
+        ];
+        let responses = vec![
+            Value::Array(vec![Value::BulkString(vec![0; 100]), Value::Nil]),
+            Value::Array(vec![
+                Value::BulkString(vec![0; 100]),
+                Value::BulkString(vec![0; 100]),
+            ]),
+        ];
+
+        let (cached, full) = cache_mget_responses(cache.as_ref(), &groups, responses);
+        assert_eq!((cached, full), (2, true));
+        assert_eq!(cache.entry_count(), 2);
+        assert!(cache.get(b"k1", CachedKeyType::String).is_some());
+        assert!(cache.get(b"missing", CachedKeyType::String).is_none());
+        assert!(cache.get(b"k3", CachedKeyType::String).is_none());
+    }
+}
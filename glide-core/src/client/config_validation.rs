@@ -0,0 +1,516 @@
+//! Validation of connection requests without connecting to the servers.
+//!
+//! [`validate_connection_request`] reports the problems that would make creating a client fail,
+//! or make it behave differently than requested - missing or unresolvable addresses, TLS
+//! certificates that can't be parsed or are provided with TLS disabled, and options that conflict
+//! or don't apply to the requested mode. Resolving the addresses is the only network access.
+
+use std::time::Duration;
+
+use futures::future::join_all;
+use redis::{ClientTlsConfig, TlsCertificates, Value, retrieve_tls_certificates};
+use rustls_pki_types::CertificateDer;
+use rustls_pki_types::pem::PemObject;
+
+use super::{create_compression_manager, parse_name_server};
+use crate::address_resolver_registry;
+use crate::compression::{CompressionBackendType, CompressionConfig};
+use crate::connection_request as protobuf;
+use crate::runtime_config::GlideRuntimeConfig;
+
+/// Longest IAM token refresh interval, in seconds.
+const MAX_IAM_REFRESH_INTERVAL_SECONDS: u32 = 12 * 60 * 60;
+
+/// A problem found in a connection request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    /// The connection request field with the problem.
+    pub field: &'static str,
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            message: message.into(),
+        }
+    }
+
+    /// Returns a map of the error's `field` and `message`, the way errors are returned over the
+    /// socket.
+    pub fn into_value(self) -> Value {
+        Value::Map(vec![
+            (
+                Value::SimpleString("field".to_string()),
+                Value::SimpleString(self.field.to_string()),
+            ),
+            (
+                Value::SimpleString("message".to_string()),
+                Value::BulkString(self.message.into_bytes()),
+            ),
+        ])
+    }
+}
+
+/// Returns the problems found in `request`, or an empty list if it's valid.
+pub async fn validate_connection_request(
+    request: &protobuf::ConnectionRequest,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    validate_tls(request, &mut errors);
+    validate_authentication(request, &mut errors);
+    validate_modes(request, &mut errors);
+    validate_compression(request, &mut errors);
+    validate_dns(request, &mut errors);
+    validate_addresses(request, &mut errors).await;
+    errors
+}
+
+fn validate_tls(request: &protobuf::ConnectionRequest, errors: &mut Vec<ValidationError>) {
+    let tls_mode = match request.tls_mode.enum_value() {
+        Ok(tls_mode) => tls_mode,
+        Err(value) => {
+            errors.push(ValidationError::new(
+                "tls_mode",
+                format!("Unknown TLS mode {value}"),
+            ));
+            return;
+        }
+    };
+    let has_client_cert = !request.client_cert.is_empty();
+    let has_client_key = !request.client_key.is_empty();
+    if has_client_cert != has_client_key {
+        errors.push(ValidationError::new(
+            if has_client_cert {
+                "client_key"
+            } else {
+                "client_cert"
+            },
+            "client_cert and client_key must both be provided or both be empty",
+        ));
+        return;
+    }
+    if request.root_certs.is_empty() && !has_client_cert {
+        return;
+    }
+    if tls_mode == protobuf::TlsMode::NoTls {
+        errors.push(ValidationError::new(
+            "tls_mode",
+            "TLS certificates are provided, but TLS is disabled",
+        ));
+        return;
+    }
+    if request.root_certs.iter().any(|cert| cert.is_empty()) {
+        errors.push(ValidationError::new(
+            "root_certs",
+            "Root certificates can't be empty",
+        ));
+        return;
+    }
+    let root_certs = request.root_certs.concat();
+    for (field, pem) in [
+        ("root_certs", &root_certs),
+        ("client_cert", &request.client_cert.to_vec()),
+    ] {
+        if !pem.is_empty() && CertificateDer::pem_slice_iter(pem).next().is_none() {
+            errors.push(ValidationError::new(field, "No PEM certificate found"));
+            return;
+        }
+    }
+    let certificates = TlsCertificates {
+        client_tls: has_client_cert.then(|| ClientTlsConfig {
+            client_cert: request.client_cert.to_vec(),
+            client_key: request.client_key.to_vec(),
+        }),
+        root_cert: (!root_certs.is_empty()).then_some(root_certs),
+    };
+    if let Err(err) = retrieve_tls_certificates(certificates) {
+        let field = if request.root_certs.is_empty() {
+            "client_cert"
+        } else {
+            "root_certs"
+        };
+        errors.push(ValidationError::new(
+            field,
+            format!("Invalid TLS certificates: {err}"),
+        ));
+    }
+}
+
+fn validate_authentication(
+    request: &protobuf::ConnectionRequest,
+    errors: &mut Vec<ValidationError>,
+) {
+    let Some(authentication_info) = request.authentication_info.as_ref() else {
+        return;
+    };
+    let Some(iam_credentials) = authentication_info.iam_credentials.as_ref() else {
+        return;
+    };
+    if authentication_info.username.is_empty() {
+        errors.push(ValidationError::new(
+            "authentication_info.username",
+            "IAM authentication requires a username",
+        ));
+    }
+    if iam_credentials.cluster_name.is_empty() {
+        errors.push(ValidationError::new(
+            "authentication_info.iam_credentials.cluster_name",
+            "IAM authentication requires a cluster name",
+        ));
+    }
+    if iam_credentials.region.is_empty() {
+        errors.push(ValidationError::new(
+            "authentication_info.iam_credentials.region",
+            "IAM authentication requires a region",
+        ));
+    }
+    if let Some(interval) = iam_credentials.refresh_interval_seconds
+        && !(1..=MAX_IAM_REFRESH_INTERVAL_SECONDS).contains(&interval)
+    {
+        errors.push(ValidationError::new(
+            "authentication_info.iam_credentials.refresh_interval_seconds",
+            format!(
+                "The refresh interval must be between 1 and {MAX_IAM_REFRESH_INTERVAL_SECONDS} seconds"
+            ),
+        ));
+    }
+    if request.tls_mode.enum_value() == Ok(protobuf::TlsMode::NoTls) {
+        errors.push(ValidationError::new(
+            "tls_mode",
+            "IAM authentication requires TLS",
+        ));
+    }
+}
+
+fn validate_modes(request: &protobuf::ConnectionRequest, errors: &mut Vec<ValidationError>) {
+    match request.read_from.enum_value() {
+        Ok(protobuf::ReadFrom::LowestLatency) => errors.push(ValidationError::new(
+            "read_from",
+            "LowestLatency isn't supported",
+        )),
+        Ok(protobuf::ReadFrom::AZAffinity | protobuf::ReadFrom::AZAffinityReplicasAndPrimary)
+            if request.client_az.is_empty() =>
+        {
+            errors.push(ValidationError::new(
+                "client_az",
+                "The AZ affinity read strategies require the client's availability zone",
+            ))
+        }
+        Ok(_) => {}
+        Err(value) => errors.push(ValidationError::new(
+            "read_from",
+            format!("Unknown read strategy {value}"),
+        )),
+    }
+    if let Err(value) = request.protocol.enum_value() {
+        errors.push(ValidationError::new(
+            "protocol",
+            format!("Unknown protocol version {value}"),
+        ));
+    }
+
+    if request.cluster_mode_enabled {
+        if request.node_discovery_mode.enum_value() != Ok(protobuf::NodeDiscoveryMode::Standard) {
+            errors.push(ValidationError::new(
+                "node_discovery_mode",
+                "Node discovery modes only apply to standalone mode",
+            ));
+        }
+        return;
+    }
+    let cluster_options = [
+        ("periodic_checks", request.periodic_checks.is_some()),
+        (
+            "refresh_topology_from_initial_nodes",
+            request.refresh_topology_from_initial_nodes,
+        ),
+        (
+            "topology_from_cluster_shards",
+            request.topology_from_cluster_shards,
+        ),
+        ("topology_change_events", request.topology_change_events),
+        (
+            "connection_concurrency_limit",
+            request
+                .connection_concurrency_limit
+                .is_some_and(|limit| limit > 0),
+        ),
+    ];
+    for (field, _) in cluster_options.into_iter().filter(|(_, set)| *set) {
+        errors.push(ValidationError::new(
+            field,
+            format!("{field} only applies to cluster mode"),
+        ));
+    }
+}
+
+fn validate_compression(request: &protobuf::ConnectionRequest, errors: &mut Vec<ValidationError>) {
+    let Some(config) = request.compression_config.as_ref() else {
+        return;
+    };
+    let backend = match config.backend.enum_value() {
+        Ok(protobuf::CompressionBackend::ZSTD) => CompressionBackendType::Zstd,
+        Ok(protobuf::CompressionBackend::LZ4) => CompressionBackendType::Lz4,
+        Err(value) => {
+            errors.push(ValidationError::new(
+                "compression_config.backend",
+                format!("Unknown compression backend {value}"),
+            ));
+            return;
+        }
+    };
+    let config = CompressionConfig {
+        enabled: config.enabled,
+        backend,
+        compression_level: config.compression_level,
+        min_compression_size: config.min_compression_size as usize,
+        max_decompressed_size: config
+            .max_decompressed_size
+            .map(|size| size as usize)
+            .or(Some(crate::compression::DEFAULT_MAX_DECOMPRESSED_SIZE)),
+    };
+    if let Err(err) = create_compression_manager(Some(config)) {
+        errors.push(ValidationError::new("compression_config", err.to_string()));
+    }
+}
+
+fn validate_dns(request: &protobuf::ConnectionRequest, errors: &mut Vec<ValidationError>) {
+    let Some(dns) = request.dns.as_ref() else {
+        return;
+    };
+    if let Err(value) = dns.resolver.enum_value() {
+        errors.push(ValidationError::new(
+            "dns",
+            format!("Unknown DNS resolver type {value}"),
+        ));
+    }
+    for name_server in &dns.name_servers {
+        if parse_name_server(name_server).is_err() {
+            errors.push(ValidationError::new(
+                "dns",
+                format!("Invalid DNS name server '{name_server}'"),
+            ));
+        }
+    }
+}
+
+async fn validate_addresses(
+    request: &protobuf::ConnectionRequest,
+    errors: &mut Vec<ValidationError>,
+) {
+    if request.addresses.is_empty() {
+        errors.push(ValidationError::new(
+            "addresses",
+            "At least one address is required",
+        ));
+        return;
+    }
+    let resolver = match request.address_resolver_key.as_deref() {
+        None | Some("") => None,
+        Some(key) => {
+            let resolver = address_resolver_registry::get(key);
+            if resolver.is_none() {
+                errors.push(ValidationError::new(
+                    "address_resolver_key",
+                    format!("No address resolver is registered under '{key}'"),
+                ));
+            }
+            resolver
+        }
+    };
+    let timeout = match request.connection_timeout {
+        0 => GlideRuntimeConfig::get().default_connection_timeout,
+        timeout => Duration::from_millis(timeout.into()),
+    };
+
+    let lookups = request.addresses.iter().map(|address| {
+        let resolver = resolver.clone();
+        async move {
+            let host = address.host.to_string();
+            if host.is_empty() {
+                return Some("The host can't be empty".to_string());
+            }
+            let Ok(port) = u16::try_from(address.port) else {
+                return Some(format!("Invalid port {} of {host}", address.port));
+            };
+            if port == 0 {
+                return Some(format!("Invalid port 0 of {host}"));
+            }
+            let (host, port) = match resolver {
+                Some(resolver) => resolver.resolve(&host, port),
+                None => (host, port),
+            };
+            match tokio::time::timeout(timeout, tokio::net::lookup_host((host.as_str(), port)))
+                .await
+            {
+                Ok(Ok(mut addresses)) => match addresses.next() {
+                    Some(_) => None,
+                    None => Some(format!("{host} didn't resolve to any address")),
+                },
+                Ok(Err(err)) => Some(format!("Failed to resolve {host}: {err}")),
+                Err(_) => Some(format!("Resolving {host} took longer than {timeout:?}")),
+            }
+        }
+    });
+    for message in join_all(lookups).await.into_iter().flatten() {
+        errors.push(ValidationError::new("addresses", message));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request_with_address(host: &str, port: u32) -> protobuf::ConnectionRequest {
+        let mut request = protobuf::ConnectionRequest::new();
+        request.addresses.push(protobuf::NodeAddress {
+            host: host.into(),
+            port,
+            ..Default::default()
+        });
+        request
+    }
+
+    fn fields(errors: &[ValidationError]) -> Vec<&'static str> {
+        errors.iter().map(|error| error.field).collect()
+    }
+
+    #[tokio::test]
+    async fn test_valid_request() {
+        let request = request_with_address("127.0.0.1", 6379);
+        assert_eq!(validate_connection_request(&request).await, Vec::new());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_addresses() {
+        let request = protobuf::ConnectionRequest::new();
+        assert_eq!(
+            fields(&validate_connection_request(&request).await),
+            vec!["addresses"]
+        );
+
+        let mut request = request_with_address("", 6379);
+        request.addresses.push(protobuf::NodeAddress {
+            host: "127.0.0.1".into(),
+            port: 70000,
+            ..Default::default()
+        });
+        request.addresses.push(protobuf::NodeAddress {
+            host: "localhost.invalid".into(),
+            port: 6379,
+            ..Default::default()
+        });
+        let errors = validate_connection_request(&request).await;
+        assert_eq!(fields(&errors), vec!["addresses"; 3]);
+    }
+
+    #[tokio::test]
+    async fn test_unregistered_address_resolver() {
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request.address_resolver_key = Some("missing-resolver".into());
+        assert_eq!(
+            fields(&validate_connection_request(&request).await),
+            vec!["address_resolver_key"]
+        );
+    }
+
+    #[test]
+    fn test_tls_validation() {
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request.client_cert = b"cert".to_vec().into();
+        let mut errors = Vec::new();
+        validate_tls(&request, &mut errors);
+        assert_eq!(fields(&errors), vec!["client_key"]);
+
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request
+            .root_certs
+            .push(b"not a certificate".to_vec().into());
+        let mut errors = Vec::new();
+        validate_tls(&request, &mut errors);
+        assert_eq!(fields(&errors), vec!["tls_mode"]);
+
+        request.tls_mode = protobuf::TlsMode::SecureTls.into();
+        let mut errors = Vec::new();
+        validate_tls(&request, &mut errors);
+        assert_eq!(fields(&errors), vec!["root_certs"]);
+    }
+
+    #[test]
+    fn test_conflicting_options() {
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request.read_from = protobuf::ReadFrom::AZAffinity.into();
+        request.topology_change_events = true;
+        let mut errors = Vec::new();
+        validate_modes(&request, &mut errors);
+        assert_eq!(fields(&errors), vec!["client_az", "topology_change_events"]);
+
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request.cluster_mode_enabled = true;
+        request.node_discovery_mode = protobuf::NodeDiscoveryMode::DiscoverAll.into();
+        request.topology_change_events = true;
+        let mut errors = Vec::new();
+        validate_modes(&request, &mut errors);
+        assert_eq!(fields(&errors), vec!["node_discovery_mode"]);
+    }
+
+    #[test]
+    fn test_iam_validation() {
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request.authentication_info = Some(protobuf::AuthenticationInfo {
+            iam_credentials: Some(protobuf::IamCredentials {
+                cluster_name: "cluster".into(),
+                region: "us-east-1".into(),
+                refresh_interval_seconds: Some(0),
+                ..Default::default()
+            })
+            .into(),
+            ..Default::default()
+        })
+        .into();
+        let mut errors = Vec::new();
+        validate_authentication(&request, &mut errors);
+        assert_eq!(
+            fields(&errors),
+            vec![
+                "authentication_info.username",
+                "authentication_info.iam_credentials.refresh_interval_seconds",
+                "tls_mode"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dns_validation() {
+        let mut request = request_with_address("127.0.0.1", 6379);
+        request.dns = Some(protobuf::DnsConfig {
+            name_servers: vec!["10.0.0.2".into(), "[::1]:5353".into(), "dns.local".into()],
+            ..Default::default()
+        })
+        .into();
+        let mut errors = Vec::new();
+        validate_dns(&request, &mut errors);
+        assert_eq!(fields(&errors), vec!["dns"]);
+        assert!(errors[0].message.contains("dns.local"));
+    }
+
+    #[test]
+    fn test_error_value() {
+        let error = ValidationError::new("client_az", "missing");
+        assert_eq!(
+            error.into_value(),
+            Value::Map(vec![
+                (
+                    Value::SimpleString("field".to_string()),
+                    Value::SimpleString("client_az".to_string())
+                ),
+                (
+                    Value::SimpleString("message".to_string()),
+                    Value::BulkString(b"missing".to_vec())
+                ),
+            ])
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Credential expiry preflight.
+//!
+//! Long-lived clients may outlive the credentials they were created with: a TLS client
+//! certificate expires at its `notAfter` date, and temporary AWS credentials used for IAM
+//! authentication expire with their session. The monitor periodically inspects these expiry
+//! times and emits a warning once a credential is within its warning threshold, and an error
+//! once it has expired, so operators can rotate them before connections fail. Each is logged,
+//! and sent to the client's push channel as a [`PushKind::CredentialExpiry`] push.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use logger_core::{log_error, log_warn};
+use redis::{PushInfo, PushKind, Value};
+use rustls_pki_types::CertificateDer;
+use rustls_pki_types::pem::PemObject;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{MissedTickBehavior, interval};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+use crate::runtime_config::GlideRuntimeConfig;
+
+/// Interval between two expiry checks.
+pub const CREDENTIAL_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const EXPIRY_LOG_IDENTIFIER: &str = "credential_expiry";
+
+/// A credential whose expiry is monitored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CredentialKind {
+    /// The TLS client certificate chain.
+    TlsClientCertificate,
+    /// The AWS credentials used to sign IAM authentication tokens.
+    IamSession,
+}
+
+impl CredentialKind {
+    fn name(self) -> &'static str {
+        match self {
+            CredentialKind::TlsClientCertificate => "tls_client_certificate",
+            CredentialKind::IamSession => "iam_session",
+        }
+    }
+}
+
+/// The state of a credential relative to its expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpiryStatus {
+    /// The credential is valid for longer than the warning threshold.
+    Valid,
+    /// The credential expires within the warning threshold.
+    ExpiringSoon(Duration),
+    /// The credential has expired.
+    Expired,
+}
+
+impl ExpiryStatus {
+    /// Returns the data of a [`PushKind::CredentialExpiry`] push - `[credential, "expiring_soon",
+    /// remaining seconds]` or `[credential, "expired"]`. `None` for a valid credential.
+    fn to_push_data(self, kind: CredentialKind) -> Option<Vec<Value>> {
+        let credential = Value::BulkString(kind.name().as_bytes().to_vec());
+        match self {
+            ExpiryStatus::Valid => None,
+            ExpiryStatus::ExpiringSoon(remaining) => Some(vec![
+                credential,
+                Value::BulkString(b"expiring_soon".to_vec()),
+                Value::Int(remaining.as_secs() as i64),
+            ]),
+            ExpiryStatus::Expired => Some(vec![credential, Value::BulkString(b"expired".to_vec())]),
+        }
+    }
+
+    fn evaluate(expiry: SystemTime, now: SystemTime, warning_threshold: Duration) -> Self {
+        match expiry.duration_since(now) {
+            Ok(remaining) if remaining > warning_threshold => ExpiryStatus::Valid,
+            Ok(remaining) if !remaining.is_zero() => ExpiryStatus::ExpiringSoon(remaining),
+            _ => ExpiryStatus::Expired,
+        }
+    }
+}
+
+/// Tracks a single credential, and emits an event whenever its status changes.
+struct MonitoredCredential {
+    kind: CredentialKind,
+    expiry: Box<dyn Fn() -> Option<SystemTime> + Send>,
+    warning_threshold: Duration,
+    // The last reported expiry and status, so each status is reported once per expiry value.
+    last_reported: Option<(SystemTime, ExpiryStatus)>,
+}
+
+impl MonitoredCredential {
+    fn check(&mut self, now: SystemTime, push_sender: Option<&mpsc::UnboundedSender<PushInfo>>) {
+        let Some(expiry) = (self.expiry)() else {
+            return;
+        };
+        let status = ExpiryStatus::evaluate(expiry, now, self.warning_threshold);
+        let changed = match self.last_reported {
+            Some((last_expiry, last_status)) => {
+                last_expiry != expiry
+                    || std::mem::discriminant(&last_status) != std::mem::discriminant(&status)
+            }
+            None => true,
+        };
+        if !changed {
+            return;
+        }
+        self.last_reported = Some((expiry, status));
+
+        match status {
+            ExpiryStatus::Valid => {}
+            ExpiryStatus::ExpiringSoon(remaining) => log_warn(
+                EXPIRY_LOG_IDENTIFIER,
+                format!(
+                    "{:?} expires in {}s, rotate it before connections start failing",
+                    self.kind,
+                    remaining.as_secs()
+                ),
+            ),
+            ExpiryStatus::Expired => log_error(
+                EXPIRY_LOG_IDENTIFIER,
+                format!("{:?} has expired, new connections will fail", self.kind),
+            ),
+        }
+        if let (Some(push_sender), Some(data)) = (push_sender, status.to_push_data(self.kind)) {
+            let _ = push_sender.send(PushInfo {
+                kind: PushKind::CredentialExpiry,
+                data,
+            });
+        }
+    }
+}
+
+/// Background task that periodically checks the expiry of the client's credentials.
+/// The task is stopped when the monitor is dropped.
+pub(crate) struct CredentialExpiryMonitor {
+    task: JoinHandle<()>,
+}
+
+impl CredentialExpiryMonitor {
+    /// Starts monitoring the given TLS client certificate and IAM credentials, reporting to
+    /// `push_sender` if set. Returns `None` if there's nothing to monitor.
+    pub(crate) fn start(
+        client_cert: &[u8],
+        iam_credentials_expiry: Option<Arc<Mutex<Option<SystemTime>>>>,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) -> Option<Self> {
+        let runtime_config = GlideRuntimeConfig::get();
+        let mut credentials = Vec::new();
+
+        if !client_cert.is_empty() {
+            match certificate_expiry(client_cert) {
+                Ok(expiry) => credentials.push(MonitoredCredential {
+                    kind: CredentialKind::TlsClientCertificate,
+                    expiry: Box::new(move || Some(expiry)),
+                    warning_threshold: runtime_config.tls_cert_expiry_warning,
+                    last_reported: None,
+                }),
+                Err(err) => log_warn(
+                    EXPIRY_LOG_IDENTIFIER,
+                    format!("Can't read the TLS client certificate expiry: {err}"),
+                ),
+            }
+        }
+        if let Some(expiry) = iam_credentials_expiry {
+            credentials.push(MonitoredCredential {
+                kind: CredentialKind::IamSession,
+                expiry: Box::new(move || *expiry.lock().expect("Lock poisoned")),
+                warning_threshold: runtime_config.iam_credentials_expiry_warning,
+                last_reported: None,
+            });
+        }
+        if credentials.is_empty() {
+            return None;
+        }
+
+        let task = tokio::spawn(async move {
+            let mut timer = interval(CREDENTIAL_EXPIRY_CHECK_INTERVAL);
+            timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            loop {
+                timer.tick().await;
+                let now = SystemTime::now();
+                for credential in credentials.iter_mut() {
+                    credential.check(now, push_sender.as_ref());
+                }
+            }
+        });
+        Some(Self { task })
+    }
+}
+
+impl Drop for CredentialExpiryMonitor {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Returns the earliest `notAfter` time of the certificates in the given PEM chain.
+pub fn certificate_expiry(pem: &[u8]) -> Result<SystemTime, String> {
+    let mut earliest: Option<SystemTime> = None;
+    for cert in CertificateDer::pem_slice_iter(pem) {
+        let cert = cert.map_err(|err| format!("invalid PEM certificate: {err}"))?;
+        let (_, cert) = X509Certificate::from_der(&cert)
+            .map_err(|err| format!("invalid DER certificate: {err}"))?;
+        let not_after = u64::try_from(cert.validity().not_after.timestamp())
+            .map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds))
+            .map_err(|_| "certificate expires before 1970".to_string())?;
+        earliest = Some(earliest.map_or(not_after, |current| current.min(not_after)));
+    }
+    earliest.ok_or_else(|| "no certificate found".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed certificate, valid from 2026-10-17 22:50:48 UTC to 2036-10-14 22:50:48 UTC.
+    const TEST_CERT: &[u8] = b"-----BEGIN CERTIFICATE-----
+MIIBgDCCASWgAwIBAgIUUZseHQlBIzfRoEGJzsiSkKYNNHwwCgYIKoZIzj0EAwIw
+FTETMBEGA1UEAwwKZ2xpZGUtdGVzdDAeFw0yNjEwMTcyMjUwNDhaFw0zNjEwMTQy
+MjUwNDhaMBUxEzARBgNVBAMMCmdsaWRlLXRlc3QwWTATBgcqhkjOPQIBBggqhkjO
+PQMBBwNCAAQRC4DyoUr/M6P6XXJGSBi3z7GQ3wxI2XVbGkbNU0hMfg7OPh9zET5h
+iJoxr8MW0vvs3EEzWbtx7QtGwQy0uTi1o1MwUTAdBgNVHQ4EFgQUjCdscFRGrzHE
+FkIyOsjgAAM1GTIwHwYDVR0jBBgwFoAUjCdscFRGrzHEFkIyOsjgAAM1GTIwDwYD
+VR0TAQH/BAUwAwEB/zAKBggqhkjOPQQDAgNJADBGAiEAj+4dYRlvdvdRVGSnjvG6
+XNdxAkTxAv1MWmJAc+J9zSQCIQDIqBkY2+DjKbT76KeNf3LJdA0tBpg51QB6+hwj
++rx0mg==
+-----END CERTIFICATE-----
+";
+
+    #[test]
+    fn test_certificate_expiry_is_read_from_pem() {
+        assert_eq!(
+            certificate_expiry(TEST_CERT),
+            Ok(UNIX_EPOCH + Duration::from_secs(2_107_637_448))
+        );
+        assert!(certificate_expiry(b"not a certificate").is_err());
+    }
+
+    #[test]
+    fn test_expiry_status() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let threshold = Duration::from_secs(60);
+
+        assert_eq!(
+            ExpiryStatus::evaluate(now + Duration::from_secs(120), now, threshold),
+            ExpiryStatus::Valid
+        );
+        assert_eq!(
+            ExpiryStatus::evaluate(now + Duration::from_secs(30), now, threshold),
+            ExpiryStatus::ExpiringSoon(Duration::from_secs(30))
+        );
+        assert_eq!(
+            ExpiryStatus::evaluate(now, now, threshold),
+            ExpiryStatus::Expired
+        );
+        assert_eq!(
+            ExpiryStatus::evaluate(now - Duration::from_secs(1), now, threshold),
+            ExpiryStatus::Expired
+        );
+    }
+
+    #[test]
+    fn test_status_change_is_pushed() {
+        let (push_sender, mut push_receiver) = mpsc::unbounded_channel();
+        let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let mut credential = MonitoredCredential {
+            kind: CredentialKind::IamSession,
+            expiry: Box::new(move || Some(now + Duration::from_secs(30))),
+            warning_threshold: Duration::from_secs(60),
+            last_reported: None,
+        };
+
+        credential.check(now, Some(&push_sender));
+        credential.check(now + Duration::from_secs(1), Some(&push_sender));
+
+        let push = push_receiver.try_recv().unwrap();
+        assert_eq!(push.kind, PushKind::CredentialExpiry);
+        assert_eq!(
+            push.data,
+            vec![
+                Value::BulkString(b"iam_session".to_vec()),
+                Value::BulkString(b"expiring_soon".to_vec()),
+                Value::Int(30),
+            ]
+        );
+        // The status didn't change on the second check
+        assert!(push_receiver.try_recv().is_err());
+    }
+}
@@ -0,0 +1,111 @@
+//! Replication durability for write commands.
+//!
+//! [`Client::send_command_with_durability`] sends a write command followed by
+//! `WAIT numreplicas timeout` in the same pipeline, so both are written to the same connection, and
+//! the `WAIT` covers the write. The number of replicas that acknowledged the write is returned with
+//! the command's response, so callers that need read-your-writes after a failover can tell whether
+//! the write reached enough replicas.
+
+use std::time::Duration;
+
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo, is_readonly};
+use redis::{Cmd, ErrorKind, PipelineRetryStrategy, RedisError, RedisResult, Value};
+
+use super::Client;
+
+/// The replication acknowledgment requested for a write command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Durability {
+    /// The number of replicas that should acknowledge the write.
+    pub num_replicas: u32,
+    /// How long the server waits for the acknowledgments. Must be positive, since a `WAIT` without
+    /// a timeout blocks until the replicas acknowledge.
+    pub timeout: Duration,
+}
+
+impl Client {
+    /// Sends a command, followed by a `WAIT` on the same connection if the command is a write.
+    /// Returns the command's response, and the number of replicas that acknowledged the write, or
+    /// `None` if the command is read-only and no `WAIT` was sent.
+    /// In cluster mode, returns an error for write commands routed to multiple nodes.
+    pub async fn send_command_with_durability(
+        &mut self,
+        cmd: &mut Cmd,
+        routing: Option<RoutingInfo>,
+        durability: Durability,
+    ) -> RedisResult<(Value, Option<u32>)> {
+        if is_readonly(cmd) {
+            return Ok((self.send_command(cmd, routing).await?, None));
+        }
+        if durability.timeout.is_zero() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Durability timeout must be positive",
+            )));
+        }
+        let routing = match routing.or_else(|| RoutingInfo::for_routable(cmd)) {
+            Some(RoutingInfo::MultiNode(_)) if self.is_cluster().await? => {
+                return Err(RedisError::from((
+                    ErrorKind::ClientError,
+                    "Durability is only supported for commands sent to a single node",
+                )));
+            }
+            Some(RoutingInfo::SingleNode(route)) => RoutingInfo::SingleNode(route),
+            _ => RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random),
+        };
+
+        let mut pipeline = redis::pipe();
+        pipeline.add_command(cmd.clone());
+        pipeline
+            .cmd("WAIT")
+            .arg(durability.num_replicas)
+            .arg(durability.timeout.as_millis() as u64);
+        let timeout = self.request_timeout + durability.timeout;
+        // A write can't be safely retried, since it might have been applied before the failure
+        let retry_strategy = PipelineRetryStrategy {
+            retry_server_error: false,
+            retry_connection_error: false,
+        };
+        let response = self
+            .send_pipeline(
+                &pipeline,
+                Some(routing),
+                true,
+                Some(u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX)),
+                retry_strategy,
+            )
+            .await?;
+        parse_durable_response(response)
+    }
+}
+
+/// Parses the `[response, acknowledged replicas]` response of the write and `WAIT` pipeline.
+fn parse_durable_response(response: Value) -> RedisResult<(Value, Option<u32>)> {
+    if let Value::Array(mut responses) = response
+        && responses.len() == 2
+        && let Some(Value::Int(acknowledged)) = responses.pop()
+        && let Some(value) = responses.pop()
+    {
+        return Ok((value, Some(u32::try_from(acknowledged).unwrap_or_default())));
+    }
+    Err(RedisError::from((
+        ErrorKind::TypeError,
+        "Unexpected response to a command sent with durability",
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_durable_response() {
+        let response = Value::Array(vec![Value::Okay, Value::Int(2)]);
+        assert_eq!(
+            parse_durable_response(response).unwrap(),
+            (Value::Okay, Some(2))
+        );
+        assert!(parse_durable_response(Value::Array(vec![Value::Okay])).is_err());
+        assert!(parse_durable_response(Value::Array(vec![Value::Okay, Value::Nil])).is_err());
+    }
+}
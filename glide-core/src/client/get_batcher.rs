@@ -0,0 +1,271 @@
+//! Automatic batching of `GET` commands.
+//!
+//! When a batching window is configured, each `GET` sent without explicit routing is handed to a
+//! [`GetBatcher`] instead of being sent on its own. The batcher collects the keys requested within
+//! the window, fetches them with one `MGET` per slot, all sent in a single pipeline, and hands each
+//! caller the value of its key. This adds up to the window's length to the latency of each `GET`,
+//! but greatly reduces the number of requests sent by read-heavy applications.
+//!
+//! The `MGET`s are read-only, so they're sent to the nodes chosen by the client's `ReadFrom`
+//! strategy, like the `GET`s would be. If a batch fails, or the `MGET` of a key's slot returns an
+//! error, the `GET`s of the failed keys are sent on their own, so the callers receive the same
+//! errors they would without batching. `MGET` returns nil for keys that hold a non-string value
+//! instead of a `WRONGTYPE` error, so the types of the keys that came back nil are checked with a
+//! pipeline of `TYPE`s, one per batch: missing keys are answered with nil, and the `GET`s of the
+//! other keys are sent on their own.
+
+use std::collections::HashMap;
+use std::sync::Weak;
+use std::time::Duration;
+
+use logger_core::log_debug;
+use redis::cluster_routing::{Routable, RoutingInfo};
+use redis::{Cmd, PipelineRetryStrategy, RedisResult, Value};
+use tokio::sync::{RwLock, mpsc, oneshot};
+use tokio::time::Instant;
+
+use super::cache_warmer::{KEYS_PER_BATCH, KeyGroup, batch_keys_by_slot, mget_pipeline};
+use super::{Client, ClientWrapper};
+
+/// A `GET` waiting for its batch. The value is `None` if the key couldn't be fetched by the batch.
+struct PendingGet {
+    key: Vec<u8>,
+    response: oneshot::Sender<Option<Value>>,
+}
+
+/// Coalesces the `GET`s sent within a time window into per-slot `MGET`s.
+pub(crate) struct GetBatcher {
+    sender: mpsc::UnboundedSender<PendingGet>,
+}
+
+impl GetBatcher {
+    /// Starts the batching task. The task holds a weak reference to the internal client, and stops
+    /// once the batcher is dropped.
+    pub(crate) fn new(internal_client: Weak<RwLock<ClientWrapper>>, window: Duration) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run_batches(internal_client, receiver, window));
+        Self { sender }
+    }
+
+    /// Returns the value of `key` read by the next batch, or `None` if it couldn't be read.
+    async fn get(&self, key: Vec<u8>) -> Option<Value> {
+        let (response, receiver) = oneshot::channel();
+        self.sender.send(PendingGet { key, response }).ok()?;
+        receiver.await.ok().flatten()
+    }
+}
+
+async fn run_batches(
+    internal_client: Weak<RwLock<ClientWrapper>>,
+    mut receiver: mpsc::UnboundedReceiver<PendingGet>,
+    window: Duration,
+) {
+    while let Some(first) = receiver.recv().await {
+        let deadline = Instant::now() + window;
+        let mut pending = vec![first];
+        while pending.len() < KEYS_PER_BATCH {
+            match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(get)) => pending.push(get),
+                _ => break,
+            }
+        }
+        let Some(internal_client) = internal_client.upgrade() else {
+            break;
+        };
+        let client = internal_client.read().await.clone();
+        // The next batch is collected while this one is in flight
+        tokio::spawn(send_batch(client, pending));
+    }
+}
+
+async fn send_batch(client: ClientWrapper, pending: Vec<PendingGet>) {
+    let keys = pending.iter().map(|get| get.key.clone()).collect();
+    let mut values = HashMap::new();
+    for groups in batch_keys_by_slot(keys) {
+        let count = groups.len();
+        match send_pipeline(client.clone(), &mget_pipeline(&groups), count).await {
+            Ok(responses) => {
+                let (found, nil_keys) = demultiplex(groups, responses);
+                values.extend(found);
+                values.extend(find_missing(client.clone(), nil_keys).await);
+            }
+            Err(err) => log_debug(
+                "get_batcher",
+                format!("Batched GETs failed, sending them separately: {err}"),
+            ),
+        }
+    }
+    for get in pending {
+        let _ = get.response.send(values.get(&get.key).cloned());
+    }
+}
+
+/// Returns the keys among `nil_keys` that don't exist, each with a nil value, checked with a
+/// single pipeline of `TYPE`s. The other keys hold a value of another type, or were set after the
+/// `MGET`, so their `GET`s are sent on their own.
+async fn find_missing(client: ClientWrapper, nil_keys: Vec<Vec<u8>>) -> Vec<(Vec<u8>, Value)> {
+    if nil_keys.is_empty() {
+        return Vec::new();
+    }
+    let mut pipeline = redis::Pipeline::new();
+    for key in &nil_keys {
+        pipeline.cmd("TYPE").arg(key);
+    }
+    match send_pipeline(client, &pipeline, nil_keys.len()).await {
+        Ok(types) => nil_keys
+            .into_iter()
+            .zip(types)
+            .filter(|(_, key_type)| is_missing(key_type))
+            .map(|(key, _)| (key, Value::Nil))
+            .collect(),
+        Err(err) => {
+            log_debug(
+                "get_batcher",
+                format!("Checking the types of the nil keys failed, sending their GETs: {err}"),
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Returns whether a `TYPE` response is the type of a missing key.
+fn is_missing(key_type: &Value) -> bool {
+    matches!(key_type, Value::SimpleString(key_type) if key_type == "none")
+}
+
+async fn send_pipeline(
+    client: ClientWrapper,
+    pipeline: &redis::Pipeline,
+    count: usize,
+) -> RedisResult<Vec<Value>> {
+    match client {
+        ClientWrapper::Standalone(mut client) => {
+            client.send_read_only_pipeline(pipeline, 0, count).await
+        }
+        ClientWrapper::Cluster { mut client } => {
+            // MGET and TYPE are read-only, so it's safe to retry
+            let retry_strategy = PipelineRetryStrategy {
+                retry_server_error: true,
+                retry_connection_error: true,
+            };
+            client
+                .route_pipeline(pipeline, 0, count, None, Some(retry_strategy))
+                .await
+        }
+        ClientWrapper::Lazy(_) => Ok(Vec::new()),
+    }
+}
+
+/// Maps each key of `groups` to its value in the `MGET` responses. The keys of groups whose `MGET`
+/// failed are left out. The keys with nil values are returned apart, since the key may be missing
+/// or hold another type.
+fn demultiplex(
+    groups: Vec<KeyGroup>,
+    responses: Vec<Value>,
+) -> (HashMap<Vec<u8>, Value>, Vec<Vec<u8>>) {
+    let (nils, values): (Vec<_>, Vec<_>) = groups
+        .into_iter()
+        .zip(responses)
+        .filter_map(|(keys, response)| match response {
+            Value::Array(values) if values.len() == keys.len() => {
+                Some(keys.into_iter().zip(values))
+            }
+            _ => None,
+        })
+        .flatten()
+        .partition(|(_, value)| *value == Value::Nil);
+    (
+        values.into_iter().collect(),
+        nils.into_iter().map(|(key, _)| key).collect(),
+    )
+}
+
+/// Returns the key of a `GET` command, or `None` for any other command.
+fn get_key(cmd: &Cmd) -> Option<&[u8]> {
+    if cmd.command()? != b"GET" || cmd.args_iter().count() != 2 {
+        return None;
+    }
+    RoutingInfo::key_for_command(cmd)
+}
+
+impl Client {
+    /// Reads the value of a `GET` through the batcher. Returns `None` if the command should be sent
+    /// on its own - when batching is disabled, the command isn't a `GET` or has explicit routing, or
+    /// the batch couldn't read the key.
+    pub(super) async fn batched_get(
+        &self,
+        cmd: &Cmd,
+        routing: Option<&RoutingInfo>,
+    ) -> Option<Value> {
+        let batcher = self.get_batcher.as_ref()?;
+        if routing.is_some() {
+            return None;
+        }
+        let key = get_key(cmd)?;
+        let cache = self.client_side_cache.as_deref();
+        if let Some(value) = cache.and_then(|cache| cache.get_cached_cmd(cmd)) {
+            return Some(value);
+        }
+        let value = batcher.get(key.to_vec()).await?;
+        // Missing keys aren't cached, like the responses of the GETs sent on their own.
+        if let Some(cache) = cache
+            && value != Value::Nil
+        {
+            cache.set_cached_cmd(cmd, value.clone());
+        }
+        Some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_key() {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("user:1");
+        assert_eq!(get_key(&cmd), Some(&b"user:1"[..]));
+        let mut cmd = redis::cmd("GETEX");
+        cmd.arg("user:1");
+        assert_eq!(get_key(&cmd), None);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("user:1").arg("extra");
+        assert_eq!(get_key(&cmd), None);
+    }
+
+    #[test]
+    fn test_demultiplex() {
+        let groups = vec![
+            vec![b"a".to_vec(), b"missing".to_vec()],
+            vec![b"{tag}:1".to_vec()],
+        ];
+        let responses = vec![
+            Value::Array(vec![Value::BulkString(b"1".to_vec()), Value::Nil]),
+            redis::parse_redis_value(b"-MOVED 1 127.0.0.1:6380\r\n").unwrap(),
+        ];
+        let (values, nil_keys) = demultiplex(groups, responses);
+        assert_eq!(values.len(), 1);
+        assert_eq!(
+            values.get(b"a".as_slice()),
+            Some(&Value::BulkString(b"1".to_vec()))
+        );
+        // Nil might hide a WRONGTYPE error, so the type of the key is checked
+        assert_eq!(values.get(b"missing".as_slice()), None);
+        assert_eq!(nil_keys, vec![b"missing".to_vec()]);
+        assert_eq!(values.get(b"{tag}:1".as_slice()), None);
+    }
+
+    #[test]
+    fn test_is_missing() {
+        assert!(is_missing(&Value::SimpleString("none".to_string())));
+        assert!(!is_missing(&Value::SimpleString("hash".to_string())));
+        assert!(!is_missing(&Value::SimpleString("string".to_string())));
+    }
+
+    #[tokio::test]
+    async fn test_batcher_without_client_falls_back() {
+        let batcher = GetBatcher::new(Weak::new(), Duration::from_micros(100));
+        assert_eq!(batcher.get(b"key".to_vec()).await, None);
+    }
+}
@@ -0,0 +1,352 @@
+//! Per-node connection health checks.
+//!
+//! When enabled with `ConnectionRequest::health_check`, a background task PINGs every node the
+//! client is connected to once per interval, and records the round-trip time of each PING. A PING
+//! that fails, or doesn't complete within the timeout, is a failed check. After `failure_threshold`
+//! consecutive failed checks, the node's connections are closed and rebuilt in the background,
+//! instead of waiting for a request to find out that they're broken.
+//!
+//! The latest state of each node is returned by `Client::node_health`, and the totals of all
+//! clients are reported by the `health_checks`, `health_check_failures`, `health_check_evictions`
+//! and `unhealthy_nodes` statistics.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use futures::future::join_all;
+use logger_core::{log_debug, log_warn};
+use redis::cluster_routing::{RoutingInfo, SingleNodeRoutingInfo};
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+use telemetrylib::Telemetry;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use super::ClientWrapper;
+use super::types::HealthCheckConfig;
+
+/// Default time between the checks of each node.
+pub const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Default time to wait for a PING response before the check fails.
+pub const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+/// Default number of consecutive failed checks before a node's connections are rebuilt.
+pub const DEFAULT_HEALTH_CHECK_FAILURE_THRESHOLD: u32 = 3;
+
+/// The health of a node, according to its latest checks.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeHealth {
+    pub address: String,
+    /// Whether the latest check of the node succeeded. Nodes are healthy until their first check.
+    pub healthy: bool,
+    /// Round-trip time of the latest successful check.
+    pub last_rtt: Option<Duration>,
+    /// Number of checks failed since the latest successful check or eviction.
+    pub consecutive_failures: u32,
+    /// Number of times the node's connections were rebuilt by health checks.
+    pub evictions: u64,
+}
+
+impl NodeHealth {
+    fn new(address: String) -> Self {
+        Self {
+            address,
+            healthy: true,
+            last_rtt: None,
+            consecutive_failures: 0,
+            evictions: 0,
+        }
+    }
+}
+
+/// The health of each node of a client.
+struct HealthTable {
+    failure_threshold: u32,
+    nodes: HashMap<String, NodeHealth>,
+}
+
+impl HealthTable {
+    fn new(failure_threshold: u32) -> Self {
+        Self {
+            failure_threshold,
+            nodes: HashMap::new(),
+        }
+    }
+
+    fn node(&mut self, address: &str) -> &mut NodeHealth {
+        self.nodes
+            .entry(address.to_string())
+            .or_insert_with(|| NodeHealth::new(address.to_string()))
+    }
+
+    fn record_success(&mut self, address: &str, rtt: Duration) {
+        let node = self.node(address);
+        if !node.healthy {
+            Telemetry::decr_unhealthy_nodes(1);
+        }
+        node.healthy = true;
+        node.last_rtt = Some(rtt);
+        node.consecutive_failures = 0;
+    }
+
+    /// Records a failed check, and returns whether the node's connections should be rebuilt.
+    fn record_failure(&mut self, address: &str) -> bool {
+        Telemetry::incr_health_check_failures();
+        let failure_threshold = self.failure_threshold;
+        let node = self.node(address);
+        if node.healthy {
+            Telemetry::incr_unhealthy_nodes(1);
+        }
+        node.healthy = false;
+        node.consecutive_failures += 1;
+        if node.consecutive_failures < failure_threshold {
+            return false;
+        }
+        // The rebuilt connections get a fresh count of failures
+        node.consecutive_failures = 0;
+        node.evictions += 1;
+        Telemetry::incr_health_check_evictions();
+        true
+    }
+
+    /// Forgets the nodes that the client is no longer connected to.
+    fn retain(&mut self, addresses: &[String]) {
+        self.nodes.retain(|address, node| {
+            let connected = addresses.contains(address);
+            if !connected && !node.healthy {
+                Telemetry::decr_unhealthy_nodes(1);
+            }
+            connected
+        });
+    }
+}
+
+impl Drop for HealthTable {
+    fn drop(&mut self) {
+        let unhealthy = self.nodes.values().filter(|node| !node.healthy).count();
+        Telemetry::decr_unhealthy_nodes(unhealthy as u64);
+    }
+}
+
+/// Periodically checks the health of the nodes of a client.
+pub(crate) struct HealthChecker {
+    table: Arc<Mutex<HealthTable>>,
+}
+
+impl HealthChecker {
+    /// Starts the checking task. The task holds weak references to the internal client and to the
+    /// checker's state, and stops once either is dropped.
+    pub(crate) fn new(
+        internal_client: Weak<RwLock<ClientWrapper>>,
+        config: &HealthCheckConfig,
+    ) -> Self {
+        let interval = match config.interval_ms {
+            0 => DEFAULT_HEALTH_CHECK_INTERVAL,
+            interval => Duration::from_millis(interval.into()),
+        };
+        let timeout = match config.timeout_ms {
+            0 => DEFAULT_HEALTH_CHECK_TIMEOUT,
+            timeout => Duration::from_millis(timeout.into()),
+        };
+        let failure_threshold = match config.failure_threshold {
+            0 => DEFAULT_HEALTH_CHECK_FAILURE_THRESHOLD,
+            threshold => threshold,
+        };
+        let table = Arc::new(Mutex::new(HealthTable::new(failure_threshold)));
+        tokio::spawn(run_checks(
+            internal_client,
+            Arc::downgrade(&table),
+            interval,
+            timeout,
+        ));
+        Self { table }
+    }
+
+    /// Returns the health of each node, ordered by address.
+    pub(crate) fn node_health(&self) -> Vec<NodeHealth> {
+        let mut nodes: Vec<NodeHealth> =
+            self.table.lock().unwrap().nodes.values().cloned().collect();
+        nodes.sort_unstable_by(|first, second| first.address.cmp(&second.address));
+        nodes
+    }
+}
+
+async fn run_checks(
+    internal_client: Weak<RwLock<ClientWrapper>>,
+    table: Weak<Mutex<HealthTable>>,
+    interval: Duration,
+    timeout: Duration,
+) {
+    loop {
+        tokio::time::sleep(interval).await;
+        let Some(internal_client) = internal_client.upgrade() else {
+            break;
+        };
+        let client = internal_client.read().await.clone();
+        drop(internal_client);
+
+        let addresses = match node_addresses(client.clone()).await {
+            Ok(addresses) => addresses,
+            Err(err) => {
+                log_debug(
+                    "health_check",
+                    format!("Failed to get the node addresses: {err}"),
+                );
+                continue;
+            }
+        };
+        let checks = addresses.iter().map(|address| {
+            let client = client.clone();
+            async move {
+                Telemetry::incr_health_checks();
+                let start = Instant::now();
+                match tokio::time::timeout(timeout, ping_node(client, address)).await {
+                    Ok(Ok(_)) => Ok(start.elapsed()),
+                    Ok(Err(err)) => Err(err.to_string()),
+                    Err(_) => Err(format!("no response within {timeout:?}")),
+                }
+            }
+        });
+        let results = join_all(checks).await;
+
+        let Some(table) = table.upgrade() else {
+            break;
+        };
+        let mut evicted = Vec::new();
+        {
+            let mut table = table.lock().unwrap();
+            table.retain(&addresses);
+            for (address, result) in addresses.iter().zip(results) {
+                match result {
+                    Ok(rtt) => table.record_success(address, rtt),
+                    Err(err) => {
+                        log_debug(
+                            "health_check",
+                            format!("Health check of {address} failed: {err}"),
+                        );
+                        if table.record_failure(address) {
+                            evicted.push(address.clone());
+                        }
+                    }
+                }
+            }
+        }
+        for address in evicted {
+            log_warn(
+                "health_check",
+                format!("{address} failed consecutive health checks, rebuilding its connections"),
+            );
+            if let Err(err) = evict_node(client.clone(), address).await {
+                log_warn(
+                    "health_check",
+                    format!("Failed to rebuild the connections of a node: {err}"),
+                );
+            }
+        }
+    }
+}
+
+async fn node_addresses(client: ClientWrapper) -> RedisResult<Vec<String>> {
+    match client {
+        ClientWrapper::Standalone(client) => Ok(client.node_addresses()),
+        ClientWrapper::Cluster { mut client } => client.get_node_addresses().await,
+        ClientWrapper::Lazy(_) => Ok(Vec::new()),
+    }
+}
+
+async fn ping_node(client: ClientWrapper, address: &str) -> RedisResult<Value> {
+    match client {
+        ClientWrapper::Standalone(client) => client.ping_node(address).await,
+        ClientWrapper::Cluster { mut client } => {
+            let (host, port) = parse_address(address)?;
+            let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress { host, port });
+            client.route_command(&redis::cmd("PING"), routing).await
+        }
+        ClientWrapper::Lazy(_) => Ok(Value::Nil),
+    }
+}
+
+async fn evict_node(client: ClientWrapper, address: String) -> RedisResult<()> {
+    match client {
+        ClientWrapper::Standalone(client) => client.reconnect_node(&address),
+        ClientWrapper::Cluster { mut client } => {
+            client.refresh_node_connections(address).await.map(|_| ())
+        }
+        ClientWrapper::Lazy(_) => Ok(()),
+    }
+}
+
+/// Splits a `host:port` node address.
+pub(super) fn parse_address(address: &str) -> RedisResult<(String, u16)> {
+    address
+        .rsplit_once(':')
+        .and_then(|(host, port)| Some((host.to_string(), port.parse().ok()?)))
+        .ok_or_else(|| {
+            RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid node address",
+                address.to_string(),
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failures_evict_at_threshold() {
+        let mut table = HealthTable::new(3);
+        table.record_success("node:6379", Duration::from_millis(2));
+        assert!(!table.record_failure("node:6379"));
+        assert!(!table.record_failure("node:6379"));
+        assert!(table.record_failure("node:6379"));
+
+        let node = &table.nodes["node:6379"];
+        assert!(!node.healthy);
+        assert_eq!(node.consecutive_failures, 0);
+        assert_eq!(node.evictions, 1);
+        assert_eq!(node.last_rtt, Some(Duration::from_millis(2)));
+
+        // The count of failures starts over after the eviction
+        assert!(!table.record_failure("node:6379"));
+        table.record_success("node:6379", Duration::from_millis(1));
+        assert!(!table.record_failure("node:6379"));
+        assert!(!table.record_failure("node:6379"));
+        assert_eq!(table.nodes["node:6379"].evictions, 1);
+    }
+
+    #[test]
+    fn test_success_resets_failures() {
+        let mut table = HealthTable::new(2);
+        assert!(!table.record_failure("node:6379"));
+        table.record_success("node:6379", Duration::from_millis(5));
+        let node = &table.nodes["node:6379"];
+        assert!(node.healthy);
+        assert_eq!(node.consecutive_failures, 0);
+        assert_eq!(node.last_rtt, Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_retain_forgets_removed_nodes() {
+        let mut table = HealthTable::new(2);
+        table.record_success("first:6379", Duration::from_millis(1));
+        table.record_failure("second:6379");
+        table.retain(&["first:6379".to_string()]);
+        assert_eq!(table.nodes.len(), 1);
+        assert!(table.nodes.contains_key("first:6379"));
+    }
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("10.0.0.1:6379").unwrap(),
+            ("10.0.0.1".to_string(), 6379)
+        );
+        assert_eq!(
+            parse_address("::1:7000").unwrap(),
+            ("::1".to_string(), 7000)
+        );
+        assert!(parse_address("localhost").is_err());
+        assert!(parse_address("localhost:port").is_err());
+    }
+}
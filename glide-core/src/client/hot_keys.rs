@@ -0,0 +1,299 @@
+//! Hot-key detection.
+//!
+//! When enabled with `ConnectionRequest::hot_key_tracking`, the client samples one out of every
+//! `sample_rate` commands, and counts the accesses of the sampled keys in a count-min sketch.
+//! Counts are kept per time window, and a small set of the most frequent keys is tracked
+//! alongside the sketch, so the hottest keys can be returned with `Client::get_hot_keys`.
+//! A warning is logged, and the `hot_key_warnings` statistic is incremented, the first time in
+//! a window that a key's estimated rate exceeds `qps_threshold`.
+//!
+//! Keys are reported with their slot, which identifies the node serving them in cluster mode.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use logger_core::log_warn;
+use redis::Cmd;
+use redis::cluster_routing::RoutingInfo;
+use redis::cluster_topology::get_slot;
+use telemetrylib::Telemetry;
+
+use super::types::HotKeyTrackingConfig;
+
+/// Default sampling rate - one out of every 100 commands is tracked.
+pub const DEFAULT_HOT_KEY_SAMPLE_RATE: u32 = 100;
+/// Default rate above which a key is reported as hot.
+pub const DEFAULT_HOT_KEY_QPS_THRESHOLD: u32 = 1000;
+/// Duration of a counting window.
+pub const HOT_KEY_WINDOW: Duration = Duration::from_secs(10);
+
+const SKETCH_DEPTH: usize = 4;
+const SKETCH_WIDTH: usize = 2048;
+/// Maximum number of candidate keys tracked per window.
+const MAX_CANDIDATES: usize = 128;
+/// Minimal elapsed time used for rate estimation, so that the first accesses of a window don't
+/// produce inflated rates.
+const MIN_RATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A frequently accessed key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HotKey {
+    pub key: Vec<u8>,
+    /// The slot of the key. In cluster mode, the node owning this slot serves the key.
+    pub slot: u16,
+    /// Estimated accesses per second, including the commands that weren't sampled.
+    pub estimated_qps: f64,
+}
+
+/// Count-min sketch - an approximate frequency table that never underestimates.
+struct CountMinSketch {
+    counters: Vec<u32>,
+    hashers: [std::hash::RandomState; SKETCH_DEPTH],
+}
+
+impl CountMinSketch {
+    fn new() -> Self {
+        Self {
+            counters: vec![0; SKETCH_DEPTH * SKETCH_WIDTH],
+            hashers: std::array::from_fn(|_| std::hash::RandomState::new()),
+        }
+    }
+
+    fn index(&self, row: usize, key: &[u8]) -> usize {
+        let mut hasher = self.hashers[row].build_hasher();
+        hasher.write(key);
+        row * SKETCH_WIDTH + (hasher.finish() as usize % SKETCH_WIDTH)
+    }
+
+    /// Increments the count of the key, and returns its new estimated count.
+    fn increment(&mut self, key: &[u8]) -> u32 {
+        let mut estimate = u32::MAX;
+        for row in 0..SKETCH_DEPTH {
+            let index = self.index(row, key);
+            self.counters[index] = self.counters[index].saturating_add(1);
+            estimate = estimate.min(self.counters[index]);
+        }
+        estimate
+    }
+
+    fn clear(&mut self) {
+        self.counters.fill(0);
+    }
+}
+
+struct Window {
+    sketch: CountMinSketch,
+    /// Estimated sampled counts of the most frequent keys in the current window.
+    candidates: HashMap<Vec<u8>, u32>,
+    /// Keys that were already reported as hot in the current window.
+    reported: HashSet<Vec<u8>>,
+    started_at: Instant,
+    /// The hottest keys of the previous window, sorted by rate.
+    previous: Vec<HotKey>,
+}
+
+pub(crate) struct HotKeyTracker {
+    sample_rate: u64,
+    qps_threshold: f64,
+    commands_seen: AtomicU64,
+    window: Mutex<Window>,
+}
+
+impl HotKeyTracker {
+    pub(crate) fn new(config: &HotKeyTrackingConfig) -> Self {
+        let sample_rate = if config.sample_rate > 0 {
+            config.sample_rate
+        } else {
+            DEFAULT_HOT_KEY_SAMPLE_RATE
+        };
+        let qps_threshold = if config.qps_threshold > 0 {
+            config.qps_threshold
+        } else {
+            DEFAULT_HOT_KEY_QPS_THRESHOLD
+        };
+        Self {
+            sample_rate: sample_rate as u64,
+            qps_threshold: qps_threshold as f64,
+            commands_seen: AtomicU64::new(0),
+            window: Mutex::new(Window {
+                sketch: CountMinSketch::new(),
+                candidates: HashMap::new(),
+                reported: HashSet::new(),
+                started_at: Instant::now(),
+                previous: Vec::new(),
+            }),
+        }
+    }
+
+    /// Records the key accessed by the command, if the command is sampled.
+    pub(crate) fn record(&self, cmd: &Cmd) {
+        if !self
+            .commands_seen
+            .fetch_add(1, Ordering::Relaxed)
+            .is_multiple_of(self.sample_rate)
+        {
+            return;
+        }
+        if let Some(key) = RoutingInfo::key_for_command(cmd) {
+            self.record_key(key, Instant::now());
+        }
+    }
+
+    fn record_key(&self, key: &[u8], now: Instant) {
+        let mut window = self.window.lock().expect("Lock poisoned");
+        if now.duration_since(window.started_at) >= HOT_KEY_WINDOW {
+            window.previous = self.hottest(&window, now);
+            window.sketch.clear();
+            window.candidates.clear();
+            window.reported.clear();
+            window.started_at = now;
+        }
+
+        let count = window.sketch.increment(key);
+        if let Some(candidate) = window.candidates.get_mut(key) {
+            *candidate = count;
+        } else if window.candidates.len() < MAX_CANDIDATES {
+            window.candidates.insert(key.to_vec(), count);
+        } else if let Some((coldest, coldest_count)) = window
+            .candidates
+            .iter()
+            .min_by_key(|(_, count)| **count)
+            .map(|(key, count)| (key.clone(), *count))
+            && coldest_count < count
+        {
+            window.candidates.remove(&coldest);
+            window.candidates.insert(key.to_vec(), count);
+        }
+
+        let qps = self.estimate_qps(count, now.duration_since(window.started_at));
+        if qps > self.qps_threshold && window.reported.insert(key.to_vec()) {
+            Telemetry::incr_hot_key_warnings();
+            log_warn(
+                "hot_key",
+                format!(
+                    "Key `{}` (slot {}) is accessed about {qps:.0} times per second, above the threshold of {}",
+                    String::from_utf8_lossy(key),
+                    get_slot(key),
+                    self.qps_threshold
+                ),
+            );
+        }
+    }
+
+    fn estimate_qps(&self, sampled_count: u32, elapsed: Duration) -> f64 {
+        (sampled_count as u64 * self.sample_rate) as f64
+            / elapsed.max(MIN_RATE_INTERVAL).as_secs_f64()
+    }
+
+    fn hottest(&self, window: &Window, now: Instant) -> Vec<HotKey> {
+        let elapsed = now.duration_since(window.started_at);
+        let mut keys: Vec<_> = window
+            .candidates
+            .iter()
+            .map(|(key, count)| HotKey {
+                key: key.clone(),
+                slot: get_slot(key),
+                estimated_qps: self.estimate_qps(*count, elapsed),
+            })
+            .collect();
+        keys.sort_by(|a, b| b.estimated_qps.total_cmp(&a.estimated_qps));
+        keys
+    }
+
+    /// Returns up to `n` of the most frequently accessed keys, hottest first.
+    /// Until the current window has collected enough samples, the previous window is used.
+    pub(crate) fn hot_keys(&self, n: usize) -> Vec<HotKey> {
+        let window = self.window.lock().expect("Lock poisoned");
+        let now = Instant::now();
+        let mut keys = if now.duration_since(window.started_at) < MIN_RATE_INTERVAL
+            && !window.previous.is_empty()
+        {
+            window.previous.clone()
+        } else {
+            self.hottest(&window, now)
+        };
+        keys.truncate(n);
+        keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker(sample_rate: u32, qps_threshold: u32) -> HotKeyTracker {
+        HotKeyTracker::new(&HotKeyTrackingConfig {
+            sample_rate,
+            qps_threshold,
+        })
+    }
+
+    #[test]
+    fn test_count_min_sketch_never_underestimates() {
+        let mut sketch = CountMinSketch::new();
+        for i in 0..10_000u32 {
+            sketch.increment(&i.to_le_bytes());
+        }
+        for _ in 0..99 {
+            sketch.increment(b"hot");
+        }
+        assert!(sketch.increment(b"hot") >= 100);
+    }
+
+    #[test]
+    fn test_hot_keys_are_ranked_by_rate() {
+        let tracker = tracker(1, 1_000_000);
+        let now = Instant::now();
+        for _ in 0..50 {
+            tracker.record_key(b"hot", now);
+        }
+        for _ in 0..10 {
+            tracker.record_key(b"warm", now);
+        }
+        tracker.record_key(b"cold", now);
+
+        let keys = tracker.hot_keys(2);
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].key, b"hot");
+        assert_eq!(keys[0].slot, get_slot(b"hot"));
+        assert_eq!(keys[1].key, b"warm");
+        assert!(keys[0].estimated_qps > keys[1].estimated_qps);
+    }
+
+    #[test]
+    fn test_sampling_scales_rate_estimate() {
+        let tracker = tracker(10, 1_000_000);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("key");
+        for _ in 0..100 {
+            tracker.record(&cmd);
+        }
+
+        let keys = tracker.hot_keys(1);
+        assert_eq!(keys.len(), 1);
+        // 10 sampled accesses, each standing for 10 commands, within the first second
+        assert_eq!(keys[0].estimated_qps, 100.0);
+    }
+
+    #[test]
+    fn test_keyless_commands_are_ignored() {
+        let tracker = tracker(1, 1);
+        tracker.record(&redis::cmd("PING"));
+        assert!(tracker.hot_keys(10).is_empty());
+    }
+
+    #[test]
+    fn test_key_above_threshold_is_reported_once_per_window() {
+        let tracker = tracker(1, 5);
+        let now = Instant::now();
+        for _ in 0..20 {
+            tracker.record_key(b"hot", now);
+        }
+        let window = tracker.window.lock().unwrap();
+        assert_eq!(window.reported.len(), 1);
+        assert!(window.reported.contains(&b"hot"[..]));
+    }
+}
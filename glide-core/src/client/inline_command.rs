@@ -0,0 +1,238 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Commands given as text, such as `XINFO STREAM mystream FULL`, for server and module commands
+//! that have no request type yet.
+//!
+//! The text is split into arguments the way `valkey-cli` splits its input: arguments are separated
+//! by whitespace, and an argument may be quoted. Double-quoted arguments support the `\n`, `\r`,
+//! `\t`, `\b`, `\a`, `\\`, `\"` and `\xHH` escapes, so any binary value can be written. Single-quoted
+//! arguments are taken as is, except for `\'`. A closing quote must be followed by whitespace or the
+//! end of the text.
+
+use redis::cluster_routing::{Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr, is_readonly};
+use redis::cluster_topology::get_slot;
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+
+use super::Client;
+
+fn invalid_text(detail: impl Into<String>) -> RedisError {
+    RedisError::from((
+        ErrorKind::ClientError,
+        "Invalid command text",
+        detail.into(),
+    ))
+}
+
+/// Splits `text` into the arguments of a command.
+pub fn tokenize(text: &str) -> RedisResult<Vec<Vec<u8>>> {
+    let mut args = Vec::new();
+    let mut chars = text.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Ok(args);
+        };
+        let mut arg = Vec::new();
+        match first {
+            '"' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('x') => {
+                                let hex: String = chars.by_ref().take(2).collect();
+                                let byte = (hex.len() == 2)
+                                    .then(|| u8::from_str_radix(&hex, 16).ok())
+                                    .flatten()
+                                    .ok_or_else(|| {
+                                        invalid_text(format!("invalid escape \\x{hex}"))
+                                    })?;
+                                arg.push(byte);
+                            }
+                            Some(escaped) => {
+                                let byte = match escaped {
+                                    'n' => Some('\n'),
+                                    'r' => Some('\r'),
+                                    't' => Some('\t'),
+                                    'b' => Some('\u{8}'),
+                                    'a' => Some('\u{7}'),
+                                    _ => None,
+                                };
+                                push_char(&mut arg, byte.unwrap_or(escaped));
+                            }
+                            None => return Err(invalid_text("unbalanced quotes")),
+                        },
+                        Some(c) => push_char(&mut arg, c),
+                        None => return Err(invalid_text("unbalanced quotes")),
+                    }
+                }
+                expect_separator(chars.peek())?;
+            }
+            '\'' => {
+                chars.next();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            arg.push(b'\'');
+                        }
+                        Some(c) => push_char(&mut arg, c),
+                        None => return Err(invalid_text("unbalanced quotes")),
+                    }
+                }
+                expect_separator(chars.peek())?;
+            }
+            _ => {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    push_char(&mut arg, c);
+                }
+            }
+        }
+        args.push(arg);
+    }
+}
+
+fn push_char(arg: &mut Vec<u8>, c: char) {
+    arg.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+fn expect_separator(next: Option<&char>) -> RedisResult<()> {
+    match next {
+        Some(c) if !c.is_whitespace() => Err(invalid_text(
+            "closing quote must be followed by a space or the end of the text",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns the routing of `cmd` to the node that owns its argument at `key_index` - the command
+/// name is at index 0. Read-only commands may be routed to replicas.
+pub fn key_routing(cmd: &Cmd, key_index: usize) -> RedisResult<RoutingInfo> {
+    let key = cmd.arg_idx(key_index).ok_or_else(|| {
+        invalid_text(format!(
+            "key index {key_index} is out of range of the {} arguments",
+            cmd.args_iter().count()
+        ))
+    })?;
+    let slot_addr = if is_readonly(cmd) {
+        SlotAddr::ReplicaOptional
+    } else {
+        SlotAddr::Master
+    };
+    Ok(RoutingInfo::SingleNode(
+        SingleNodeRoutingInfo::SpecificNode(Route::new(get_slot(key), slot_addr)),
+    ))
+}
+
+/// Builds the command written in `text`. With `key_index`, the command is routed by
+/// [`key_routing`]. Otherwise, it's routed as the client routes unknown commands.
+pub fn inline_command(
+    text: &str,
+    key_index: Option<usize>,
+) -> RedisResult<(Cmd, Option<RoutingInfo>)> {
+    let args = tokenize(text)?;
+    if args.is_empty() {
+        return Err(invalid_text("no command name"));
+    }
+    let mut cmd = Cmd::new();
+    for arg in &args {
+        cmd.arg(arg);
+    }
+    let routing = key_index
+        .map(|index| key_routing(&cmd, index))
+        .transpose()?;
+    Ok((cmd, routing))
+}
+
+impl Client {
+    /// Sends the command written in `text`, e.g. `XINFO STREAM mystream FULL`. See
+    /// [`inline_command`] for how the text is split into arguments and routed by `key_index`.
+    pub async fn custom_command_str(
+        &mut self,
+        text: &str,
+        key_index: Option<usize>,
+    ) -> RedisResult<Value> {
+        let (mut cmd, routing) = inline_command(text, key_index)?;
+        self.send_command(&mut cmd, routing).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(text: &str) -> Vec<String> {
+        tokenize(text)
+            .unwrap()
+            .into_iter()
+            .map(|arg| String::from_utf8(arg).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize_plain_and_quoted_arguments() {
+        assert_eq!(
+            args("  XINFO STREAM\tmystream  FULL "),
+            ["XINFO", "STREAM", "mystream", "FULL"]
+        );
+        assert_eq!(
+            args(r#"SET "my key" "line\nbreak \"quoted\"" 'raw\n' 'don\'t' """#),
+            [
+                "SET",
+                "my key",
+                "line\nbreak \"quoted\"",
+                "raw\\n",
+                "don't",
+                ""
+            ]
+        );
+        assert!(args("   ").is_empty());
+    }
+
+    #[test]
+    fn test_tokenize_binary_escapes() {
+        assert_eq!(
+            tokenize(r#"SET k "\x00\xff\xAB""#).unwrap()[2],
+            vec![0x00, 0xff, 0xab]
+        );
+        assert!(tokenize(r#"SET k "\xZZ""#).is_err());
+        assert!(tokenize(r#"SET k "\x1""#).is_err());
+    }
+
+    #[test]
+    fn test_tokenize_rejects_malformed_quotes() {
+        for text in [r#"GET "key"#, "GET 'key", r#"GET "key"suffix"#, "GET 'a'b"] {
+            let err = tokenize(text).unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::ClientError, "{text}");
+        }
+    }
+
+    #[test]
+    fn test_inline_command_routing() {
+        let (cmd, routing) = inline_command("XINFO STREAM {user1}:events FULL", Some(2)).unwrap();
+        assert_eq!(cmd.arg_idx(0), Some(&b"XINFO"[..]));
+        assert_eq!(
+            routing,
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(
+                    get_slot(b"user1"),
+                    SlotAddr::ReplicaOptional
+                ))
+            ))
+        );
+
+        let (_, routing) = inline_command("MODULE.SET key value", Some(1)).unwrap();
+        assert_eq!(
+            routing,
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(get_slot(b"key"), SlotAddr::Master))
+            ))
+        );
+
+        assert_eq!(inline_command("PING", None).unwrap().1, None);
+        assert!(inline_command("GET key", Some(2)).is_err());
+        assert!(inline_command("", None).is_err());
+    }
+}
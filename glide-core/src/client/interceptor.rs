@@ -0,0 +1,198 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Request/response interceptors.
+//!
+//! Interceptors are user-registered hooks, set in `ConnectionRequest::interceptors`, that run
+//! around every command sent through `Client::send_command`. They allow auditing, key prefixing
+//! and client-side value transformations without changing the client itself.
+//!
+//! `before_command` hooks run in registration order, and `after_response` hooks run in reverse
+//! order, so the first registered interceptor sees the final result. If a `before_command` hook
+//! fails, the command isn't sent and the error is returned without running the `after_response`
+//! hooks. Pipelines, transactions and scripts are not intercepted.
+
+use std::sync::Arc;
+
+use redis::cluster_routing::RoutingInfo;
+use redis::{Cmd, RedisFuture, RedisResult, Value};
+
+/// A hook that can inspect and change commands before they're sent, and their results after
+/// they're received.
+pub trait CommandInterceptor: Send + Sync + std::fmt::Debug {
+    /// Called before the command is sent. The command and its routing may be modified.
+    /// Returning an error aborts the command.
+    ///
+    /// A replacement command should keep the original span, using `Cmd::set_span(cmd.span())`.
+    fn before_command<'a>(
+        &'a self,
+        _cmd: &'a mut Cmd,
+        _routing: &'a mut Option<RoutingInfo>,
+    ) -> RedisFuture<'a, ()> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Called with the result of the command, which may be transformed or replaced.
+    fn after_response<'a>(
+        &'a self,
+        _cmd: &'a Cmd,
+        result: RedisResult<Value>,
+    ) -> RedisFuture<'a, Value> {
+        Box::pin(async move { result })
+    }
+}
+
+pub(crate) async fn run_before_command(
+    interceptors: &[Arc<dyn CommandInterceptor>],
+    cmd: &mut Cmd,
+    routing: &mut Option<RoutingInfo>,
+) -> RedisResult<()> {
+    for interceptor in interceptors {
+        interceptor.before_command(cmd, routing).await?;
+    }
+    Ok(())
+}
+
+pub(crate) async fn run_after_response(
+    interceptors: &[Arc<dyn CommandInterceptor>],
+    cmd: &Cmd,
+    mut result: RedisResult<Value>,
+) -> RedisResult<Value> {
+    for interceptor in interceptors.iter().rev() {
+        result = interceptor.after_response(cmd, result).await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::cluster_routing::{Routable, SingleNodeRoutingInfo};
+    use redis::{ErrorKind, RedisError};
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct KeyPrefixer(&'static str);
+
+    impl CommandInterceptor for KeyPrefixer {
+        fn before_command<'a>(
+            &'a self,
+            cmd: &'a mut Cmd,
+            routing: &'a mut Option<RoutingInfo>,
+        ) -> RedisFuture<'a, ()> {
+            Box::pin(async move {
+                let mut prefixed = Cmd::new();
+                for (index, arg) in cmd.args_iter().enumerate() {
+                    if let redis::Arg::Simple(bytes) = arg {
+                        if index == 1 {
+                            prefixed.arg([self.0.as_bytes(), bytes].concat());
+                        } else {
+                            prefixed.arg(bytes);
+                        }
+                    }
+                }
+                prefixed.set_span(cmd.span());
+                *cmd = prefixed;
+                *routing = Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::RandomPrimary,
+                ));
+                Ok(())
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct Recorder {
+        name: &'static str,
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl CommandInterceptor for Recorder {
+        fn after_response<'a>(
+            &'a self,
+            cmd: &'a Cmd,
+            result: RedisResult<Value>,
+        ) -> RedisFuture<'a, Value> {
+            Box::pin(async move {
+                let command =
+                    String::from_utf8_lossy(&cmd.command().unwrap_or_default()).to_string();
+                self.log
+                    .lock()
+                    .unwrap()
+                    .push(format!("{}:{command}", self.name));
+                result.map(|_| Value::SimpleString(self.name.to_string()))
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct Rejector;
+
+    impl CommandInterceptor for Rejector {
+        fn before_command<'a>(
+            &'a self,
+            _cmd: &'a mut Cmd,
+            _routing: &'a mut Option<RoutingInfo>,
+        ) -> RedisFuture<'a, ()> {
+            Box::pin(async { Err(RedisError::from((ErrorKind::ClientError, "rejected"))) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_before_command_can_rewrite_command_and_routing() {
+        let interceptors: Vec<Arc<dyn CommandInterceptor>> = vec![Arc::new(KeyPrefixer("t1:"))];
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("key");
+        let mut routing = None;
+
+        run_before_command(&interceptors, &mut cmd, &mut routing)
+            .await
+            .unwrap();
+
+        assert_eq!(cmd.arg_idx(1), Some(&b"t1:key"[..]));
+        assert!(matches!(
+            routing,
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::RandomPrimary
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_before_command_error_aborts_chain() {
+        let interceptors: Vec<Arc<dyn CommandInterceptor>> =
+            vec![Arc::new(Rejector), Arc::new(KeyPrefixer("t1:"))];
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("key");
+
+        let err = run_before_command(&interceptors, &mut cmd, &mut None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        assert_eq!(cmd.arg_idx(1), Some(&b"key"[..]));
+    }
+
+    #[tokio::test]
+    async fn test_after_response_runs_in_reverse_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let interceptors: Vec<Arc<dyn CommandInterceptor>> = vec![
+            Arc::new(Recorder {
+                name: "first",
+                log: log.clone(),
+            }),
+            Arc::new(Recorder {
+                name: "second",
+                log: log.clone(),
+            }),
+        ];
+        let cmd = redis::cmd("SET");
+
+        let result = run_after_response(&interceptors, &cmd, Ok(Value::Okay)).await;
+
+        assert_eq!(result, Ok(Value::SimpleString("first".to_string())));
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec!["second:SET".to_string(), "first:SET".to_string(),]
+        );
+    }
+}
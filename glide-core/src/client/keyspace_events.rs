@@ -0,0 +1,353 @@
+//! Keyspace notifications.
+//!
+//! [`Client::subscribe_keyspace_events`] enables the requested event classes in the servers'
+//! `notify-keyspace-events` configuration, and subscribes to the keyspace notification channels.
+//! Keyspace notifications are only published by the node that holds the key, so in cluster mode the
+//! patterns are subscribed on every primary.
+//!
+//! The notifications are delivered through the client's push channel as regular `pmessage` pushes,
+//! and [`KeyspaceEvent::from_push`] converts them into typed events.
+
+use std::collections::BTreeSet;
+
+use logger_core::log_warn;
+use redis::cluster_routing::{MultipleNodeRoutingInfo, ResponsePolicy, RoutingInfo};
+use redis::{PushInfo, PushKind, RedisResult, Value};
+
+use super::{Client, ClientWrapper};
+
+const NOTIFY_KEYSPACE_EVENTS: &str = "notify-keyspace-events";
+const KEYSPACE_PREFIX: &str = "__keyspace@";
+const KEYEVENT_PREFIX: &str = "__keyevent@";
+/// The classes enabled by the `A` flag.
+const ALL_CLASSES_FLAGS: &str = "g$lshzxetd";
+
+/// A class of keyspace events, as configured in `notify-keyspace-events`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyspaceEventClass {
+    /// Type-independent commands, such as `DEL`, `EXPIRE` and `RENAME`.
+    Generic,
+    String,
+    List,
+    Set,
+    Hash,
+    SortedSet,
+    Stream,
+    /// Keys that expired.
+    Expired,
+    /// Keys evicted by `maxmemory`.
+    Evicted,
+    /// Reads of missing keys.
+    KeyMiss,
+    /// Newly created keys.
+    New,
+    /// All the classes except [`KeyspaceEventClass::KeyMiss`] and [`KeyspaceEventClass::New`].
+    All,
+}
+
+impl KeyspaceEventClass {
+    fn flags(self) -> &'static str {
+        match self {
+            KeyspaceEventClass::Generic => "g",
+            KeyspaceEventClass::String => "$",
+            KeyspaceEventClass::List => "l",
+            KeyspaceEventClass::Set => "s",
+            KeyspaceEventClass::Hash => "h",
+            KeyspaceEventClass::SortedSet => "z",
+            KeyspaceEventClass::Stream => "t",
+            KeyspaceEventClass::Expired => "x",
+            KeyspaceEventClass::Evicted => "e",
+            KeyspaceEventClass::KeyMiss => "m",
+            KeyspaceEventClass::New => "n",
+            KeyspaceEventClass::All => "A",
+        }
+    }
+}
+
+/// The operation that triggered a keyspace event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyspaceEventKind {
+    Set,
+    Del,
+    Expire,
+    Expired,
+    Evicted,
+    New,
+    RenameFrom,
+    RenameTo,
+    /// Any other event, e.g. `hset` or `lpush`.
+    Other(String),
+}
+
+impl From<&str> for KeyspaceEventKind {
+    fn from(event: &str) -> Self {
+        match event {
+            "set" => KeyspaceEventKind::Set,
+            "del" => KeyspaceEventKind::Del,
+            "expire" => KeyspaceEventKind::Expire,
+            "expired" => KeyspaceEventKind::Expired,
+            "evicted" => KeyspaceEventKind::Evicted,
+            "new" => KeyspaceEventKind::New,
+            "rename_from" => KeyspaceEventKind::RenameFrom,
+            "rename_to" => KeyspaceEventKind::RenameTo,
+            other => KeyspaceEventKind::Other(other.to_string()),
+        }
+    }
+}
+
+/// A keyspace notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyspaceEvent {
+    /// The logical database of the key.
+    pub db: u32,
+    pub key: Vec<u8>,
+    pub kind: KeyspaceEventKind,
+}
+
+impl KeyspaceEvent {
+    /// Converts a `message` or `pmessage` push received on a `__keyspace@` or `__keyevent@`
+    /// channel. Returns `None` for any other push.
+    pub fn from_push(push: &PushInfo) -> Option<Self> {
+        let (channel, message) = match (&push.kind, push.data.as_slice()) {
+            (PushKind::Message, [channel, message])
+            | (PushKind::PMessage, [_, channel, message]) => (channel, message),
+            _ => return None,
+        };
+        let (Value::BulkString(channel), Value::BulkString(message)) = (channel, message) else {
+            return None;
+        };
+        let parse_channel = |prefix: &str| -> Option<(u32, &[u8])> {
+            let rest = channel.strip_prefix(prefix.as_bytes())?;
+            let separator = rest.windows(3).position(|window| window == b"__:")?;
+            let db = std::str::from_utf8(&rest[..separator]).ok()?.parse().ok()?;
+            Some((db, &rest[separator + 3..]))
+        };
+        if let Some((db, key)) = parse_channel(KEYSPACE_PREFIX) {
+            return Some(KeyspaceEvent {
+                db,
+                key: key.to_vec(),
+                kind: std::str::from_utf8(message).ok()?.into(),
+            });
+        }
+        let (db, event) = parse_channel(KEYEVENT_PREFIX)?;
+        Some(KeyspaceEvent {
+            db,
+            key: message.clone(),
+            kind: std::str::from_utf8(event).ok()?.into(),
+        })
+    }
+}
+
+impl Client {
+    /// Subscribes to the keyspace events of the keys matching `patterns`, or to all the keyspace
+    /// events of the client's database if `patterns` is empty.
+    ///
+    /// The event classes are added to the `notify-keyspace-events` configuration of all the nodes.
+    /// Managed services often deny `CONFIG`, so failing to update the configuration only logs a
+    /// warning, and the events are received only if the configuration was already set.
+    ///
+    /// In cluster mode, the patterns are registered with the pubsub synchronizer as subscriptions of
+    /// every primary, so primaries that are added or replaced are subscribed to as well.
+    pub async fn subscribe_keyspace_events(
+        &mut self,
+        patterns: &[&str],
+        event_classes: &[KeyspaceEventClass],
+    ) -> RedisResult<()> {
+        let is_cluster = self.is_cluster().await?;
+        // Keyspace channels are named after the keys, and keyevent channels after the events
+        let db = self.db_namespace().to_string();
+        let (channel_flag, channels) = if patterns.is_empty() {
+            ('E', vec![format!("{KEYEVENT_PREFIX}{db}__:*")])
+        } else {
+            let channels = patterns
+                .iter()
+                .map(|pattern| format!("{KEYSPACE_PREFIX}{db}__:{pattern}"))
+                .collect();
+            ('K', channels)
+        };
+        if let Err(err) = self
+            .enable_keyspace_events(is_cluster, channel_flag, event_classes)
+            .await
+        {
+            log_warn(
+                "keyspace_events",
+                format!(
+                    "Failed to update {NOTIFY_KEYSPACE_EVENTS}, events might not be published: {err}"
+                ),
+            );
+        }
+
+        if is_cluster {
+            self.pubsub_synchronizer.add_all_primaries_subscriptions(
+                channels
+                    .iter()
+                    .map(|channel| channel.as_bytes().to_vec())
+                    .collect(),
+            );
+        }
+        let mut cmd = redis::cmd("PSUBSCRIBE");
+        cmd.arg(&channels);
+        self.send_command(&mut cmd, None).await?;
+        if is_cluster {
+            // Subscribe right away to surface errors, the synchronizer keeps the primaries subscribed
+            if let ClientWrapper::Cluster { mut client } = self.get_or_initialize_client().await? {
+                let routing = RoutingInfo::MultiNode((
+                    MultipleNodeRoutingInfo::AllMasters,
+                    Some(ResponsePolicy::AllSucceeded),
+                ));
+                client.route_command(&cmd, routing).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds the flags of `event_classes` and `channel_flag` to `notify-keyspace-events` on all
+    /// the nodes, unless they're all set already.
+    async fn enable_keyspace_events(
+        &mut self,
+        is_cluster: bool,
+        channel_flag: char,
+        event_classes: &[KeyspaceEventClass],
+    ) -> RedisResult<()> {
+        let routing = is_cluster.then_some(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::AllNodes,
+            None,
+        )));
+        let mut cmd = redis::cmd("CONFIG");
+        cmd.arg("GET").arg(NOTIFY_KEYSPACE_EVENTS);
+        let response = self.send_command(&mut cmd, routing.clone()).await?;
+        let current = if is_cluster {
+            match response {
+                Value::Map(nodes) => nodes
+                    .iter()
+                    .map(|(_, config)| notify_flags(config))
+                    .collect(),
+                _ => vec![None],
+            }
+        } else {
+            vec![notify_flags(&response)]
+        };
+
+        let required = required_flags(channel_flag, event_classes);
+        let Some(flags) = merged_flags(&current, &required) else {
+            return Ok(());
+        };
+        let mut cmd = redis::cmd("CONFIG");
+        cmd.arg("SET").arg(NOTIFY_KEYSPACE_EVENTS).arg(flags);
+        self.send_command(&mut cmd, routing).await.map(|_| ())
+    }
+}
+
+/// Returns the value of `notify-keyspace-events` in a `CONFIG GET` response.
+fn notify_flags(response: &Value) -> Option<String> {
+    response.as_map_iter()?.find_map(|(_, value)| match value {
+        Value::BulkString(flags) => Some(String::from_utf8_lossy(flags).into_owned()),
+        Value::SimpleString(flags) => Some(flags.clone()),
+        _ => None,
+    })
+}
+
+/// Expands the `A` alias of a `notify-keyspace-events` value.
+fn expand_flags(flags: &str) -> BTreeSet<char> {
+    flags
+        .chars()
+        .flat_map(|flag| {
+            if flag == 'A' {
+                ALL_CLASSES_FLAGS.chars().collect()
+            } else {
+                vec![flag]
+            }
+        })
+        .collect()
+}
+
+fn required_flags(channel_flag: char, event_classes: &[KeyspaceEventClass]) -> BTreeSet<char> {
+    let classes: String = event_classes.iter().map(|class| class.flags()).collect();
+    let mut flags = expand_flags(&classes);
+    flags.insert(channel_flag);
+    flags
+}
+
+/// Returns the value of `notify-keyspace-events` that keeps the flags set on any node and adds the
+/// required ones, or `None` if all the nodes have the required flags.
+fn merged_flags(current: &[Option<String>], required: &BTreeSet<char>) -> Option<String> {
+    let current: Vec<BTreeSet<char>> = current
+        .iter()
+        .map(|flags| expand_flags(flags.as_deref().unwrap_or_default()))
+        .collect();
+    if current.iter().all(|flags| flags.is_superset(required)) {
+        return None;
+    }
+    let merged: BTreeSet<char> = current
+        .into_iter()
+        .flatten()
+        .chain(required.iter().copied())
+        .collect();
+    Some(merged.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bulk;
+
+    #[test]
+    fn test_event_from_push() {
+        let push = PushInfo {
+            kind: PushKind::PMessage,
+            data: vec![
+                bulk("__keyspace@0__:user:*"),
+                bulk("__keyspace@0__:user:1"),
+                bulk("expired"),
+            ],
+        };
+        assert_eq!(
+            KeyspaceEvent::from_push(&push),
+            Some(KeyspaceEvent {
+                db: 0,
+                key: b"user:1".to_vec(),
+                kind: KeyspaceEventKind::Expired,
+            })
+        );
+
+        let push = PushInfo {
+            kind: PushKind::PMessage,
+            data: vec![
+                bulk("__keyevent@3__:*"),
+                bulk("__keyevent@3__:hset"),
+                bulk("a__:b"),
+            ],
+        };
+        assert_eq!(
+            KeyspaceEvent::from_push(&push),
+            Some(KeyspaceEvent {
+                db: 3,
+                key: b"a__:b".to_vec(),
+                kind: KeyspaceEventKind::Other("hset".to_string()),
+            })
+        );
+
+        let push = PushInfo {
+            kind: PushKind::Message,
+            data: vec![bulk("news"), bulk("set")],
+        };
+        assert_eq!(KeyspaceEvent::from_push(&push), None);
+    }
+
+    #[test]
+    fn test_merged_flags() {
+        let required = required_flags(
+            'K',
+            &[KeyspaceEventClass::Expired, KeyspaceEventClass::String],
+        );
+        assert_eq!(merged_flags(&[Some("KA".to_string())], &required), None);
+        assert_eq!(
+            merged_flags(&[Some("Kx$".to_string()), Some("".to_string())], &required),
+            Some("$Kx".to_string())
+        );
+        assert_eq!(
+            merged_flags(&[Some("Eg".to_string()), None], &required),
+            Some("$EKgx".to_string())
+        );
+    }
+}
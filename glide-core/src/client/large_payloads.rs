@@ -0,0 +1,146 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Guardrails against large keys.
+//!
+//! Values of many megabytes destabilize a cluster long before they fail - they block the server
+//! while being read or written, and make slot migrations and failovers slow. With
+//! `GlideRuntimeConfig::large_request_threshold` and `large_response_threshold`, commands whose
+//! arguments or response exceed the threshold are logged with the command's name and size, and
+//! counted in the `large_requests` and `large_responses` statistics, so the applications that
+//! store such values can be found. With `reject_large_requests`, large requests fail with a
+//! `ClientError` instead of being sent.
+
+use logger_core::log_warn_rate_limited;
+use redis::cluster_routing::Routable;
+use redis::{Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value};
+use telemetrylib::Telemetry;
+
+use super::memory_budget::response_size;
+use crate::runtime_config::GlideRuntimeConfig;
+
+fn command_name(cmd: &Cmd) -> String {
+    cmd.command().map_or_else(
+        || "UNKNOWN".to_string(),
+        |name| String::from_utf8_lossy(&name).into_owned(),
+    )
+}
+
+/// Counts and logs the request if its arguments, of `bytes` bytes, exceed the large request
+/// threshold. Returns a `ClientError` if large requests are rejected.
+pub(super) fn check_request(cmd: &Cmd, bytes: u64) -> RedisResult<()> {
+    let config = GlideRuntimeConfig::get();
+    check_request_size(
+        cmd,
+        bytes,
+        config.large_request_threshold,
+        config.reject_large_requests,
+    )
+}
+
+fn check_request_size(
+    cmd: &Cmd,
+    bytes: u64,
+    threshold: Option<u64>,
+    reject: bool,
+) -> RedisResult<()> {
+    let Some(threshold) = threshold.filter(|threshold| bytes > *threshold) else {
+        return Ok(());
+    };
+    Telemetry::incr_large_requests();
+    let command = command_name(cmd);
+    let action = if reject { "Rejected" } else { "Sent" };
+    log_warn_rate_limited!(
+        "large_payloads",
+        10,
+        format!("{action} a large request. command={command}, size={bytes}, threshold={threshold}")
+    );
+    if !reject {
+        return Ok(());
+    }
+    Err(RedisError::from((
+        ErrorKind::ClientError,
+        "Request exceeds the large request threshold",
+        format!("the {command} request of {bytes} bytes is larger than {threshold} bytes"),
+    )))
+}
+
+/// Counts and logs the response if it exceeds the large response threshold.
+pub(super) fn check_response(cmd: &Cmd, response: &Value) {
+    check_response_size(
+        cmd,
+        response,
+        GlideRuntimeConfig::get().large_response_threshold,
+    );
+}
+
+/// Checks each response of a batch against the large response threshold, matching them to the
+/// batch's commands by position.
+pub(super) fn check_batch_responses(pipeline: &Pipeline, responses: &RedisResult<Value>) {
+    let Some(threshold) = GlideRuntimeConfig::get().large_response_threshold else {
+        return;
+    };
+    if let Ok(Value::Array(responses)) = responses {
+        for (cmd, response) in pipeline.cmd_iter().zip(responses) {
+            check_response_size(cmd, response, Some(threshold));
+        }
+    }
+}
+
+fn check_response_size(cmd: &Cmd, response: &Value, threshold: Option<u64>) -> bool {
+    let Some(threshold) = threshold else {
+        return false;
+    };
+    let bytes = response_size(response);
+    if bytes <= threshold {
+        return false;
+    }
+    Telemetry::incr_large_responses();
+    log_warn_rate_limited!(
+        "large_payloads",
+        10,
+        format!(
+            "Received a large response. command={}, size={bytes}, threshold={threshold}",
+            command_name(cmd)
+        )
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::memory_budget::request_size;
+    use redis::cmd;
+
+    fn set_command(value_size: usize) -> Cmd {
+        let mut set = cmd("SET");
+        set.arg("key").arg(vec![b'a'; value_size]);
+        set
+    }
+
+    #[test]
+    fn test_large_requests_are_counted_and_optionally_rejected() {
+        let small = set_command(10);
+        let large = set_command(2000);
+        let before = Telemetry::large_requests();
+
+        assert!(check_request_size(&small, request_size(&small), Some(1000), true).is_ok());
+        assert!(check_request_size(&large, request_size(&large), None, true).is_ok());
+        assert!(check_request_size(&large, request_size(&large), Some(1000), false).is_ok());
+        let err = check_request_size(&large, request_size(&large), Some(1000), true).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ClientError);
+        assert!(err.to_string().contains("SET"));
+        assert!(Telemetry::large_requests() >= before + 2);
+    }
+
+    #[test]
+    fn test_large_responses_are_detected() {
+        let get = cmd("GET");
+        let small = Value::BulkString(vec![b'a'; 10]);
+        let large = Value::Array(vec![Value::BulkString(vec![b'a'; 600]); 2]);
+
+        assert!(!check_response_size(&get, &small, Some(1000)));
+        assert!(!check_response_size(&get, &large, None));
+        assert!(check_response_size(&get, &large, Some(1000)));
+    }
+}
@@ -0,0 +1,176 @@
+//! Per-client memory budget.
+//!
+//! When enabled with `ConnectionRequest::memory_budget_bytes`, the client tracks the bytes held by
+//! its in-flight requests, and by responses that were decoded but not yet delivered to the
+//! application. Once the tracked bytes exceed the budget, new requests fail with an
+//! `OutOfClientMemory` error, instead of letting a slow consumer or a burst of large responses
+//! exhaust the memory of the host process.
+//!
+//! The sizes are estimates of the payload sizes, not of the exact allocations. The bytes in use by
+//! all the clients are reported in the `memory_budget_bytes_in_use` statistic, and the rejected
+//! requests in `memory_budget_rejections`.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use redis::{Cmd, ErrorKind, RedisError, Value};
+use telemetrylib::Telemetry;
+
+/// Estimated overhead of a single value, in addition to its payload.
+const VALUE_OVERHEAD: u64 = 16;
+
+pub(crate) struct MemoryBudget {
+    limit: u64,
+    used: AtomicU64,
+}
+
+/// Bytes accounted against a [`MemoryBudget`], released when dropped.
+pub struct MemoryReservation {
+    budget: Arc<MemoryBudget>,
+    bytes: u64,
+}
+
+impl MemoryBudget {
+    pub(crate) fn new(limit: u64) -> Arc<Self> {
+        Arc::new(Self {
+            limit,
+            used: AtomicU64::new(0),
+        })
+    }
+
+    pub(crate) fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// Returns the number of bytes currently accounted against the budget.
+    pub(crate) fn used(&self) -> u64 {
+        self.used.load(Ordering::Relaxed)
+    }
+
+    /// Reserves `bytes`, or returns `None` if the reservation would exceed the budget.
+    pub(crate) fn try_reserve(self: &Arc<Self>, bytes: u64) -> Option<MemoryReservation> {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(bytes).filter(|total| *total <= self.limit)
+            })
+            .ok()?;
+        Telemetry::incr_memory_budget_bytes_in_use(bytes);
+        Some(MemoryReservation {
+            budget: self.clone(),
+            bytes,
+        })
+    }
+
+    /// Reserves `bytes` even if the budget is exceeded. Used for memory that is already held, such
+    /// as a decoded response - the budget then rejects new requests until it's released.
+    pub(crate) fn force_reserve(self: &Arc<Self>, bytes: u64) -> MemoryReservation {
+        self.used.fetch_add(bytes, Ordering::AcqRel);
+        Telemetry::incr_memory_budget_bytes_in_use(bytes);
+        MemoryReservation {
+            budget: self.clone(),
+            bytes,
+        }
+    }
+
+    pub(crate) fn exhausted_error(&self, requested: u64) -> RedisError {
+        Telemetry::incr_memory_budget_rejections();
+        RedisError::from((
+            ErrorKind::OutOfClientMemory,
+            "Client memory budget exceeded",
+            format!(
+                "{} of {} bytes are in use, and the request needs {requested} bytes",
+                self.used(),
+                self.limit
+            ),
+        ))
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        self.budget.used.fetch_sub(self.bytes, Ordering::AcqRel);
+        Telemetry::decr_memory_budget_bytes_in_use(self.bytes);
+    }
+}
+
+/// Estimates the bytes held by a request.
+pub(crate) fn request_size(cmd: &Cmd) -> u64 {
+    cmd.args_iter()
+        .map(|arg| match arg {
+            redis::Arg::Simple(bytes) => bytes.len() as u64 + VALUE_OVERHEAD,
+            redis::Arg::Cursor => VALUE_OVERHEAD,
+        })
+        .sum()
+}
+
+/// Estimates the bytes held by a decoded response.
+pub(crate) fn response_size(value: &Value) -> u64 {
+    VALUE_OVERHEAD
+        + match value {
+            Value::BulkString(bytes) => bytes.len() as u64,
+            Value::SimpleString(string) => string.len() as u64,
+            Value::VerbatimString { text, .. } => text.len() as u64,
+            Value::BigNumber(number) => number.bits() / 8,
+            Value::Array(values) | Value::Set(values) => values.iter().map(response_size).sum(),
+            Value::Map(pairs) => pairs_size(pairs),
+            Value::Attribute { data, attributes } => response_size(data) + pairs_size(attributes),
+            Value::Push { data, .. } => data.iter().map(response_size).sum(),
+            Value::ServerError(error) => error.details().map_or(0, |details| details.len() as u64),
+            Value::Nil | Value::Int(_) | Value::Double(_) | Value::Boolean(_) | Value::Okay => 0,
+        }
+}
+
+fn pairs_size(pairs: &[(Value, Value)]) -> u64 {
+    pairs
+        .iter()
+        .map(|(key, value)| response_size(key) + response_size(value))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reservations_are_released_on_drop() {
+        let budget = MemoryBudget::new(100);
+        let first = budget.try_reserve(60).unwrap();
+        assert!(budget.try_reserve(50).is_none());
+        let second = budget.try_reserve(40).unwrap();
+        assert_eq!(budget.used(), 100);
+
+        drop(first);
+        assert_eq!(budget.used(), 40);
+        drop(second);
+        assert_eq!(budget.used(), 0);
+    }
+
+    #[test]
+    fn test_forced_reservation_blocks_new_requests() {
+        let budget = MemoryBudget::new(100);
+        let response = budget.force_reserve(150);
+        assert_eq!(budget.used(), 150);
+        assert!(budget.try_reserve(1).is_none());
+        assert_eq!(
+            budget.exhausted_error(1).kind(),
+            ErrorKind::OutOfClientMemory
+        );
+
+        drop(response);
+        assert!(budget.try_reserve(1).is_some());
+    }
+
+    #[test]
+    fn test_size_estimates() {
+        let mut cmd = redis::cmd("SET");
+        cmd.arg("key").arg(vec![0u8; 1000]);
+        assert_eq!(request_size(&cmd), 3 + 3 + 1000 + 3 * VALUE_OVERHEAD);
+
+        let response = Value::Array(vec![
+            Value::BulkString(vec![0; 1000]),
+            Value::Nil,
+            Value::Map(vec![(Value::Int(1), Value::SimpleString("ab".into()))]),
+        ]);
+        assert_eq!(response_size(&response), 1000 + 2 + 6 * VALUE_OVERHEAD);
+    }
+}
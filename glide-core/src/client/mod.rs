@@ -1,30 +1,65 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
+pub mod admin;
+pub mod cache_warmer;
 pub mod circuit_breaker;
+#[cfg(feature = "proto")]
+pub mod config_validation;
+pub mod credential_expiry;
+pub mod durability;
+mod get_batcher;
+pub mod health_check;
+pub mod hot_keys;
+pub mod inline_command;
+pub mod interceptor;
+pub mod keyspace_events;
+mod large_payloads;
+mod memory_budget;
+mod read_coalescer;
+pub mod server_info;
+pub mod watch;
+use admin::CloseSignal;
+use credential_expiry::CredentialExpiryMonitor;
+use get_batcher::GetBatcher;
+use health_check::{HealthChecker, NodeHealth};
+use hot_keys::{HotKey, HotKeyTracker};
+pub use interceptor::CommandInterceptor;
+pub(crate) use memory_budget::MemoryBudget;
+pub use memory_budget::MemoryReservation;
+#[cfg(feature = "socket-layer")]
+pub(crate) use memory_budget::response_size;
+use read_coalescer::{CoalescedRead, ReadCoalescer};
 mod types;
 
+use crate::client_runtime::RuntimeMode;
 use crate::cluster_scan_container::insert_cluster_scan_cursor;
+use crate::cluster_slots::transaction_slot;
 use crate::compression::CompressionBackendType;
 use crate::compression::lz4_backend::Lz4Backend;
 use crate::compression::zstd_backend::ZstdBackend;
 use crate::compression::{CompressionConfig, CompressionManager};
+use crate::experimental::ExperimentalFeature;
+use crate::memory_limit;
 use crate::scripts_container::get_script;
 use futures::FutureExt;
 use logger_core::{log_debug, log_error, log_info, log_warn, log_warn_rate_limited};
 use once_cell::sync::OnceCell;
-use redis::aio::ConnectionLike;
+use redis::aio::{ConnectionLike, DnsResolver, DnsResolverConfig};
 use redis::cache::{get_or_create_cache, glide_cache::GlideCache};
 use redis::cluster_async::ClusterConnection;
 use redis::cluster_routing::{
     MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo, SingleNodeRoutingInfo,
 };
 use redis::cluster_slotmap::ReadFromReplicaStrategy;
+use redis::replica_selector::ReplicaSelection;
 use redis::{
-    AddressResolver, ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, PipelineRetryStrategy,
-    PushInfo, RedisError, RedisResult, RetryStrategy, ScanStateRC, Value,
+    AddressResolver, ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, GlideConnectionOptions,
+    PipelineRetryStrategy, PushInfo, RedisError, RedisResult, RequestDeadline, RetryStrategy,
+    ScanStateRC, Value,
 };
 pub use standalone_client::StandaloneClient;
 use std::io;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicIsize, Ordering};
 use std::thread;
@@ -33,7 +68,7 @@ use std::time::{Duration, Instant};
 use tokio::runtime::{Builder, Handle};
 pub use types::*;
 
-use self::value_conversion::{convert_to_expected_type, expected_type_for_cmd, get_value_type};
+use self::value_conversion::{convert_response, expected_type_for_cmd, get_value_type};
 mod reconnecting_connection;
 pub use reconnecting_connection::IAMTokenHandle;
 pub mod monitor_client;
@@ -42,6 +77,7 @@ mod standalone_client;
 mod value_conversion;
 use crate::pubsub::{PubSubSynchronizer, create_pubsub_synchronizer};
 use crate::request_type::RequestType;
+use crate::runtime_config::{ClientSlot, GlideRuntimeConfig};
 use redis::InfoDict;
 use std::future::Future;
 use std::pin::Pin;
@@ -87,6 +123,10 @@ fn extract_request_type_from_cmd(cmd: &Cmd) -> Option<RequestType> {
         "GETEX" => Some(RequestType::GetEx),
         "GETDEL" => Some(RequestType::GetDel),
         "GETSET" => Some(RequestType::GetSet),
+        "HGET" => Some(RequestType::HGet),
+        "HMGET" => Some(RequestType::HMGet),
+        "HVALS" => Some(RequestType::HVals),
+        "HGETALL" => Some(RequestType::HGetAll),
         "SET" => {
             // SET with GET option returns the old value, which needs decompression
             // Check if the command has the GET option by looking for "GET" in the arguments
@@ -190,6 +230,7 @@ pub async fn get_valkey_connection_info(
     iam_token_manager: Option<&Arc<crate::iam::IAMTokenManager>>,
 ) -> redis::RedisConnectionInfo {
     let protocol = connection_request.protocol.unwrap_or_default();
+    let protocol_fallback = connection_request.protocol_fallback;
     let db = connection_request.database_id;
     let client_name = connection_request.client_name.clone();
     let lib_name = connection_request.lib_name.clone();
@@ -228,6 +269,7 @@ pub async fn get_valkey_connection_info(
                     username: info.username.clone(),
                     password: Some(token),
                     protocol,
+                    protocol_fallback,
                     client_name,
                     lib_name,
                     cache,
@@ -240,6 +282,7 @@ pub async fn get_valkey_connection_info(
                     username: info.username.clone(),
                     password: info.password.clone(),
                     protocol,
+                    protocol_fallback,
                     client_name,
                     lib_name,
                     cache,
@@ -250,6 +293,7 @@ pub async fn get_valkey_connection_info(
         None => redis::RedisConnectionInfo {
             db,
             protocol,
+            protocol_fallback,
             client_name,
             lib_name,
             cache,
@@ -325,6 +369,30 @@ pub struct Client {
     latency_tracker: Arc<crate::timeout_watchdog::LatencyTracker>,
     // Optional Client-wide circuit breaker
     circuit_breaker: Option<Arc<circuit_breaker::ClientCircuitBreaker>>,
+    // User-registered hooks that run around every command
+    interceptors: Arc<[Arc<dyn CommandInterceptor>]>,
+    // Background check of the TLS client certificate and IAM credentials expiry
+    credential_expiry_monitor: Option<Arc<CredentialExpiryMonitor>>,
+    // Optional sampling of accessed keys to detect hot keys
+    hot_key_tracker: Option<Arc<HotKeyTracker>>,
+    // Optional limit on the bytes held by in-flight requests and undelivered responses
+    memory_budget: Option<Arc<MemoryBudget>>,
+    // Optional coalescing of GET commands into per-slot MGETs
+    get_batcher: Option<Arc<GetBatcher>>,
+    // Optional periodic PINGs of each node, rebuilding the connections of unresponsive nodes
+    health_checker: Option<Arc<HealthChecker>>,
+    // Whether responses of an unexpected shape fail instead of being coerced
+    strict_response_validation: bool,
+    // Whether the request timeout is a deadline shared by all the attempts of a request
+    end_to_end_deadline: bool,
+    // Sharing of responses between identical reads in flight, with the `read_coalescing` feature
+    read_coalescer: Option<Arc<ReadCoalescer>>,
+    // Closed by `close_all_connections` or `shutdown`, rejecting new requests
+    close_signal: Arc<CloseSignal>,
+    // The socket options of the dedicated connections opened by `watch_transaction`
+    watch_connection_options: GlideConnectionOptions,
+    // The client's place in the process-wide `max_clients` limit, released with the last clone
+    _client_slot: Option<Arc<ClientSlot>>,
 }
 
 async fn run_with_timeout<T>(
@@ -431,6 +499,26 @@ fn get_timeout_from_cmd_arg(
     }
 }
 
+/// Returns whether the command can block on the server, in which case its request timeout is
+/// derived from its own timeout argument by [`get_request_timeout`].
+pub(crate) fn is_blocking_command(command: &[u8]) -> bool {
+    matches!(
+        command,
+        b"BLPOP"
+            | b"BRPOP"
+            | b"BLMOVE"
+            | b"BZPOPMAX"
+            | b"BZPOPMIN"
+            | b"BRPOPLPUSH"
+            | b"BLMPOP"
+            | b"BZMPOP"
+            | b"XREAD"
+            | b"XREADGROUP"
+            | b"WAIT"
+            | b"WAITAOF"
+    )
+}
+
 fn get_request_timeout(cmd: &Cmd, default_timeout: Duration) -> RedisResult<Option<Duration>> {
     let command = cmd.command().unwrap_or_default();
     let timeout = match command.as_slice() {
@@ -845,6 +933,12 @@ impl Client {
     }
 
     async fn get_or_initialize_client(&self) -> RedisResult<ClientWrapper> {
+        if self.close_signal.is_closed() {
+            return Err(RedisError::from((
+                ErrorKind::ClientClosing,
+                "Client is closed",
+            )));
+        }
         {
             let guard = self.internal_client.read().await;
             if !matches!(&*guard, ClientWrapper::Lazy(_)) {
@@ -934,10 +1028,23 @@ impl Client {
         routing: Option<RoutingInfo>,
         client: ClientWrapper,
         compression_manager: Option<Arc<CompressionManager>>,
+        cmd_start: Instant,
     ) -> RedisResult<Value> {
-        let raw_value = match client {
-            ClientWrapper::Standalone(mut client) => client.send_command(&cmd).await,
-            ClientWrapper::Cluster { mut client } => {
+        let mut in_flight_read = None;
+        let batched_value = match self_clone.batched_get(&cmd, routing.as_ref()).await {
+            Some(value) => Some(value),
+            None => match self_clone.coalesced_read(&cmd, routing.as_ref()).await {
+                CoalescedRead::Response(value) => Some(value),
+                CoalescedRead::Send(read) => {
+                    in_flight_read = read;
+                    None
+                }
+            },
+        };
+        let raw_value = match (batched_value, client) {
+            (Some(value), _) => value,
+            (None, ClientWrapper::Standalone(mut client)) => client.send_command(&cmd).await?,
+            (None, ClientWrapper::Cluster { mut client }) => {
                 let final_routing = if let Some(RoutingInfo::SingleNode(
                     SingleNodeRoutingInfo::Random,
                 )) = routing
@@ -960,10 +1067,16 @@ impl Client {
                         .or_else(|| RoutingInfo::for_routable(cmd.as_ref()))
                         .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
                 };
-                client.route_command(&cmd, final_routing).await
+                client.route_command(&cmd, final_routing).await?
             }
-            ClientWrapper::Lazy(_) => unreachable!("Lazy client should have been initialized"),
-        }?;
+            (None, ClientWrapper::Lazy(_)) => {
+                unreachable!("Lazy client should have been initialized")
+            }
+        };
+        if let Some(read) = in_flight_read {
+            read.complete(&raw_value);
+        }
+        let received_at = Instant::now();
 
         // Post-process: decompress and convert to expected type.
         // Done after the mutable borrow on cmd is released.
@@ -1000,7 +1113,22 @@ impl Client {
         };
 
         let expected_type = expected_type_for_cmd(&cmd);
-        let value = convert_to_expected_type(processed_value, expected_type)?;
+        let value = convert_response(
+            processed_value,
+            expected_type,
+            self_clone.strict_response_validation,
+        )?;
+
+        if GlideRuntimeConfig::get().record_latency_breakdown
+            && let Some(breakdown) = crate::timeout_watchdog::LatencyBreakdown::new(
+                cmd_start,
+                cmd.sent_at(),
+                cmd.first_byte_at().unwrap_or(received_at),
+                Instant::now(),
+            )
+        {
+            breakdown.record(cmd.span().as_ref());
+        }
 
         if self_clone.is_client_set_name_command(&cmd) {
             self_clone.handle_client_set_name_command(&cmd).await?;
@@ -1020,12 +1148,48 @@ impl Client {
         Ok(value)
     }
 
+    /// Send a single command, running the client's interceptors around it.
     pub fn send_command<'a>(
+        &'a mut self,
+        cmd: &'a mut Cmd,
+        mut routing: Option<RoutingInfo>,
+    ) -> redis::RedisFuture<'a, Value> {
+        Box::pin(self.close_signal.clone().cancellable(async move {
+            if self.interceptors.is_empty() {
+                return self.dispatch_command(cmd, routing).await;
+            }
+            let interceptors = self.interceptors.clone();
+            interceptor::run_before_command(&interceptors, cmd, &mut routing).await?;
+            let result = self.dispatch_command(cmd, routing).await;
+            interceptor::run_after_response(&interceptors, cmd, result).await
+        }))
+    }
+
+    /// Send a single command the client issues itself, such as the commands of a script,
+    /// without running the interceptors.
+    async fn send_command_without_interceptors(
+        &mut self,
+        cmd: &mut Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<Value> {
+        self.close_signal
+            .clone()
+            .cancellable(self.dispatch_command(cmd, routing))
+            .await
+    }
+
+    fn dispatch_command<'a>(
         &'a mut self,
         cmd: &'a mut Cmd,
         routing: Option<RoutingInfo>,
     ) -> redis::RedisFuture<'a, Value> {
         Box::pin(async move {
+            let request_timeout = get_request_timeout(cmd, self.request_timeout)?;
+            // Started before the client is initialized, so the time spent connecting is deducted too
+            let deadline = request_timeout
+                .filter(|_| self.end_to_end_deadline)
+                .map(|timeout| Arc::new(RequestDeadline::new(timeout)));
+
             // Check for IAM token changes and update the password without authentication if needed (pull model)
             if let Some(iam_manager) = &self.iam_token_manager
                 && iam_manager.token_changed()
@@ -1060,7 +1224,14 @@ impl Client {
                 return result;
             }
 
-            let request_timeout = get_request_timeout(cmd, self.request_timeout)?;
+            if let Some(hot_key_tracker) = &self.hot_key_tracker {
+                hot_key_tracker.record(cmd);
+            }
+
+            let request_size = memory_budget::request_size(cmd);
+            large_payloads::check_request(cmd, request_size)?;
+            // Held until the response is received.
+            let _request_memory = self.reserve_request_memory(request_size)?;
 
             // Reserve an inflight slot. The tracker holds the slot until the
             // last clone of the Cmd is dropped (i.e. all sub-commands in the
@@ -1107,6 +1278,7 @@ impl Client {
 
             cmd.set_inflight_tracker(tracker);
             cmd.set_response_timeout(request_timeout);
+            cmd.set_deadline(deadline.clone());
 
             // Clone compression_manager reference only if compression is enabled
             let compression_manager = if self.is_compression_enabled() {
@@ -1117,6 +1289,9 @@ impl Client {
             let self_clone = self.clone();
             let owned_cmd = cmd.clone();
 
+            // Single Instant::now() shared between watchdog and latency tracking
+            let cmd_start = Instant::now();
+
             let result = match request_timeout {
                 Some(duration) => {
                     // Compute inflight count (cheap atomic load)
@@ -1129,11 +1304,11 @@ impl Client {
                     // Wrap Cmd in Arc so the timeout arm can still read watchdog fields after execute takes ownership
                     let owned_cmd = Arc::new(owned_cmd);
 
-                    // Single Instant::now() shared between watchdog and latency tracking
-                    let cmd_start = Instant::now();
-
+                    let budget = deadline
+                        .as_ref()
+                        .map_or(duration, |deadline| deadline.remaining());
                     let timeout_rx = crate::timeout_watchdog::TimeoutWatchdog::global()
-                        .register(duration, cmd_start);
+                        .register(budget, cmd_start);
                     let routing_desc = routing
                         .as_ref()
                         .map(|r| format!("{:?}", r))
@@ -1144,6 +1319,7 @@ impl Client {
                         routing,
                         client,
                         compression_manager,
+                        cmd_start,
                     );
 
                     tokio::pin!(execute);
@@ -1228,7 +1404,10 @@ impl Client {
                                             format!("Failed to record timeout error: {e}"),
                                         );
                                     }
-                                    Err(io::Error::from(io::ErrorKind::TimedOut).into())
+                                    Err(deadline.as_ref().map_or_else(
+                                        || io::Error::from(io::ErrorKind::TimedOut).into(),
+                                        |deadline| deadline.expired_error(),
+                                    ))
                                 }
                             }
                         }
@@ -1242,11 +1421,24 @@ impl Client {
                         routing,
                         client,
                         compression_manager,
+                        cmd_start,
                     );
                     execute.await
                 }
             };
 
+            // An attempt that timed out at the deadline fails with the deadline's breakdown
+            let result = match (result, &deadline) {
+                (Err(err), Some(deadline)) if err.is_timeout() && deadline.is_expired() => {
+                    Err(deadline.expired_error())
+                }
+                (result, _) => result,
+            };
+
+            if let Ok(response) = &result {
+                large_payloads::check_response(cmd, response);
+            }
+
             // Report result to client-wide circuit breaker
             if let Some(cb) = &self.circuit_breaker {
                 let (is_error, error_kind) = match result.as_ref() {
@@ -1417,6 +1609,7 @@ impl Client {
         command_count: usize,
         offset: usize,
         raise_on_error: bool,
+        strict_response_validation: bool,
     ) -> RedisResult<Value> {
         assert_eq!(values.len(), 1);
         let value = values.pop();
@@ -1450,6 +1643,7 @@ impl Client {
             values,
             command_count,
             raise_on_error,
+            strict_response_validation,
         )
     }
 
@@ -1458,6 +1652,7 @@ impl Client {
         values: Vec<Value>,
         command_count: usize,
         raise_on_error: bool,
+        strict_response_validation: bool,
     ) -> RedisResult<Value> {
         let values = values
             .into_iter()
@@ -1473,7 +1668,9 @@ impl Client {
                     .cmd_iter()
                     .map(|cmd| expected_type_for_cmd(cmd.as_ref())),
             )
-            .map(|(value, expected_type)| convert_to_expected_type(value?, expected_type))
+            .map(|(value, expected_type)| {
+                convert_response(value?, expected_type, strict_response_validation)
+            })
             .try_fold(
                 Vec::with_capacity(command_count),
                 |mut acc, result| -> RedisResult<_> {
@@ -1494,8 +1691,10 @@ impl Client {
         transaction_timeout: Option<u32>,
         raise_on_error: bool,
     ) -> redis::RedisFuture<'a, Value> {
-        Box::pin(async move {
+        Box::pin(self.close_signal.clone().cancellable(async move {
             let client = self.get_or_initialize_client().await?;
+            let _request_memory = self.reserve_request_memory(checked_pipeline_size(pipeline)?)?;
+            self.record_batch_for_read_coalescing(pipeline);
 
             let command_count = pipeline.cmd_iter().count();
             // The offset is set to command_count + 1 to account for:
@@ -1504,8 +1703,9 @@ impl Client {
             // After these initial responses (OK and QUEUED), we expect a single response,
             // which is an array containing the results of all the commands in the pipeline.
             let offset = command_count + 1;
+            let strict_response_validation = self.strict_response_validation;
 
-            run_with_timeout(
+            let result = run_with_timeout(
                 Some(to_duration(transaction_timeout, self.request_timeout)),
                 async move {
                     match client {
@@ -1517,6 +1717,7 @@ impl Client {
                                 command_count,
                                 offset,
                                 raise_on_error,
+                                strict_response_validation,
                             )
                         }
                         ClientWrapper::Cluster { mut client } => {
@@ -1527,6 +1728,8 @@ impl Client {
                                         .await?
                                 }
                                 _ => {
+                                    // Fail cross-slot transactions before they reach a node.
+                                    transaction_slot(pipeline)?;
                                     client
                                         .req_packed_commands(pipeline, offset, 1, None)
                                         .await?
@@ -1538,6 +1741,7 @@ impl Client {
                                 command_count,
                                 offset,
                                 raise_on_error,
+                                strict_response_validation,
                             )
                         }
                         ClientWrapper::Lazy(_) => {
@@ -1546,8 +1750,10 @@ impl Client {
                     }
                 },
             )
-            .await
-        })
+            .await;
+            large_payloads::check_batch_responses(pipeline, &result);
+            result
+        }))
     }
 
     /// Send a pipeline to the server.
@@ -1571,8 +1777,10 @@ impl Client {
         pipeline_timeout: Option<u32>,
         pipeline_retry_strategy: PipelineRetryStrategy,
     ) -> redis::RedisFuture<'a, Value> {
-        Box::pin(async move {
+        Box::pin(self.close_signal.clone().cancellable(async move {
             let client = self.get_or_initialize_client().await?;
+            let _request_memory = self.reserve_request_memory(checked_pipeline_size(pipeline)?)?;
+            self.record_batch_for_read_coalescing(pipeline);
 
             let command_count = pipeline.cmd_iter().count();
             if pipeline.is_empty() {
@@ -1581,8 +1789,9 @@ impl Client {
                     "Received empty pipeline",
                 )));
             }
+            let strict_response_validation = self.strict_response_validation;
 
-            run_with_timeout(
+            let result = run_with_timeout(
                 Some(to_duration(pipeline_timeout, self.request_timeout)),
                 async move {
                     let values = match client {
@@ -1624,11 +1833,14 @@ impl Client {
                         values,
                         command_count,
                         raise_on_error,
+                        strict_response_validation,
                     )
                 },
             )
-            .await
-        })
+            .await;
+            large_payloads::check_batch_responses(pipeline, &result);
+            result
+        }))
     }
 
     pub async fn invoke_script<'a>(
@@ -1640,8 +1852,11 @@ impl Client {
     ) -> redis::RedisResult<Value> {
         let _ = self.get_or_initialize_client().await?;
 
+        // Scripts aren't intercepted.
         let mut eval = eval_cmd(hash, keys, args);
-        let result = self.send_command(&mut eval, routing.clone()).await;
+        let result = self
+            .send_command_without_interceptors(&mut eval, routing.clone())
+            .await;
         let Err(err) = result else {
             return result;
         };
@@ -1650,18 +1865,69 @@ impl Client {
                 return Err(err);
             };
             let mut load = load_cmd(&code);
-            self.send_command(&mut load, None).await?;
-            self.send_command(&mut eval, routing).await
+            self.send_command_without_interceptors(&mut load, None)
+                .await?;
+            self.send_command_without_interceptors(&mut eval, routing)
+                .await
         } else {
             Err(err)
         }
     }
 
+    /// Accounts the bytes of a request against the memory budget, if one is configured.
+    /// Returns an `OutOfClientMemory` error if the budget is exhausted, or if the request is
+    /// shed because the process exceeds its soft memory limit.
+    fn reserve_request_memory(&self, bytes: u64) -> RedisResult<Option<MemoryReservation>> {
+        memory_limit::check_request(bytes)?;
+        let Some(budget) = &self.memory_budget else {
+            return Ok(None);
+        };
+        match budget.try_reserve(bytes) {
+            Some(reservation) => Ok(Some(reservation)),
+            None => {
+                log_warn_rate_limited!(
+                    "memory_budget",
+                    10,
+                    format!(
+                        "Memory budget exhausted. limit={}, used={}",
+                        budget.limit(),
+                        budget.used()
+                    )
+                );
+                Err(budget.exhausted_error(bytes))
+            }
+        }
+    }
+
+    /// Accounts a decoded response against the memory budget, if one is configured. The bytes
+    /// stay accounted until the returned reservation is dropped, so a binding should hold it
+    /// until the response is handed to the application.
+    pub fn reserve_response_memory(&self, response: &Value) -> Option<MemoryReservation> {
+        self.memory_budget
+            .as_ref()
+            .map(|budget| budget.force_reserve(memory_budget::response_size(response)))
+    }
+
+    /// Returns the memory budget of the client, if one is configured.
+    #[cfg(feature = "socket-layer")]
+    pub(crate) fn memory_budget(&self) -> Option<Arc<MemoryBudget>> {
+        self.memory_budget.clone()
+    }
+
+    /// Returns the bytes accounted against the memory budget, and the budget's limit.
+    /// Returns `None` if the client has no memory budget.
+    pub fn memory_budget_usage(&self) -> Option<(u64, u64)> {
+        self.memory_budget
+            .as_ref()
+            .map(|budget| (budget.used(), budget.limit()))
+    }
+
     /// Reserve an inflight slot, returning a tracker whose Drop releases it.
     /// Returns `None` if no slots available.
     pub fn reserve_inflight_request(&self) -> Option<redis::cluster_async::InflightRequestTracker> {
-        redis::cluster_async::InflightRequestTracker::try_new(
+        redis::cluster_async::InflightRequestTracker::try_new_notifying(
             self.inflight_requests_allowed.clone(),
+            self.close_signal.inflight_released(),
         )
     }
 
@@ -1672,6 +1938,11 @@ impl Client {
         self.inflight_requests_allowed.load(Ordering::Relaxed)
     }
 
+    /// Returns the number of requests that are currently in flight.
+    pub fn inflight_request_count(&self) -> isize {
+        self.inflight_requests_limit - self.available_inflight_count()
+    }
+
     /// Returns true if the client-wide circuit breaker allows requests.
     /// If CB is not configured, always returns true.
     /// Fast path (Closed state) is a single atomic load. Open state may acquire a lock
@@ -1683,6 +1954,49 @@ impl Client {
             .is_none_or(|cb| cb.is_healthy())
     }
 
+    /// Returns up to `n` of the most frequently accessed keys, hottest first.
+    /// Returns an empty list if hot-key tracking isn't enabled.
+    pub fn get_hot_keys(&self, n: usize) -> Vec<HotKey> {
+        self.hot_key_tracker
+            .as_ref()
+            .map(|tracker| tracker.hot_keys(n))
+            .unwrap_or_default()
+    }
+
+    /// Returns the health of each node according to the latest health checks, ordered by address.
+    /// Returns an empty list if health checks aren't enabled.
+    pub fn node_health(&self) -> Vec<NodeHealth> {
+        self.health_checker
+            .as_ref()
+            .map(|checker| checker.node_health())
+            .unwrap_or_default()
+    }
+
+    /// Returns whether the client is connected to a cluster.
+    pub async fn is_cluster(&self) -> RedisResult<bool> {
+        Ok(matches!(
+            self.get_or_initialize_client().await?,
+            ClientWrapper::Cluster { .. }
+        ))
+    }
+
+    /// Returns the desired subscriptions that the server refused due to missing ACL permissions.
+    /// These are retried when subscribed to again.
+    pub fn get_denied_subscriptions(&self) -> redis::PubSubSubscriptionInfo {
+        self.pubsub_synchronizer.get_denied_subscriptions()
+    }
+
+    /// Returns the `PermissionDenied` error the server refused each denied subscription with.
+    pub fn get_subscription_errors(
+        &self,
+    ) -> Vec<(
+        redis::PubSubSubscriptionKind,
+        redis::PubSubChannelOrPattern,
+        RedisError,
+    )> {
+        self.pubsub_synchronizer.get_subscription_errors()
+    }
+
     /// Update the password used to authenticate with the servers.
     /// If None is passed, the password will be removed.
     /// If `immediate_auth` is true, the password will be used to authenticate with the servers immediately using the `AUTH` command.
@@ -1712,11 +2026,14 @@ impl Client {
         })
         .await
         {
+            // Only authenticate once the stored credentials were updated, so that a failed
+            // update isn't masked by a successful `AUTH`.
             Ok(result) => {
+                let result = result?;
                 if immediate_auth {
                     self.send_immediate_auth(password).await
                 } else {
-                    result
+                    Ok(result)
                 }
             }
             Err(_elapsed) => Err(RedisError::from((
@@ -1978,11 +2295,13 @@ async fn create_cluster_client(
     } else {
         (None, None)
     };
+    let runtime_config = GlideRuntimeConfig::get();
     let periodic_topology_checks = match request.periodic_checks {
         Some(PeriodicCheck::Disabled) => None,
-        Some(PeriodicCheck::Enabled) => Some(DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL),
+        Some(PeriodicCheck::Enabled) | None => {
+            Some(runtime_config.default_periodic_topology_checks_interval)
+        }
         Some(PeriodicCheck::ManualInterval(interval)) => Some(interval),
-        None => Some(DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL),
     };
     let connection_timeout = request.get_connection_timeout();
     let address_resolver = &request.address_resolver;
@@ -2002,7 +2321,7 @@ async fn create_cluster_client(
 
     let mut builder = redis::cluster::ClusterClientBuilder::new(initial_nodes)
         .connection_timeout(connection_timeout)
-        .retries(DEFAULT_RETRIES);
+        .retries(runtime_config.default_retries);
     let read_from_strategy = request.read_from.unwrap_or_default();
     builder = builder.read_from(match read_from_strategy {
         ReadFrom::AZAffinity(az) => ReadFromReplicaStrategy::AZAffinity(az),
@@ -2013,10 +2332,16 @@ async fn create_cluster_client(
         ReadFrom::AllNodes => ReadFromReplicaStrategy::AllNodes,
         ReadFrom::Primary => ReadFromReplicaStrategy::AlwaysFromPrimary,
     });
+    if let Some(selector) = request.replica_selector.clone() {
+        builder = builder.read_from(ReadFromReplicaStrategy::Custom(ReplicaSelection::new(
+            selector,
+        )));
+    }
     if let Some(interval_duration) = periodic_topology_checks {
         builder = builder.periodic_topology_checks(interval_duration);
     }
     builder = builder.use_protocol(request.protocol.unwrap_or_default());
+    builder = builder.protocol_fallback(valkey_connection_info.protocol_fallback);
     builder = builder.database_id(valkey_connection_info.db);
     builder = builder.cache(valkey_connection_info.cache);
     builder = builder.server_assisted_cache(valkey_connection_info.server_assisted_cache);
@@ -2052,7 +2377,17 @@ async fn create_cluster_client(
     builder =
         builder.refresh_topology_from_initial_nodes(request.refresh_topology_from_initial_nodes);
 
-    builder = builder.tcp_nodelay(request.tcp_nodelay);
+    builder = builder
+        .topology_from_cluster_shards(request.topology_from_cluster_shards)
+        .topology_change_events(request.topology_change_events);
+    if let Some(limit) = request.connection_concurrency_limit {
+        builder = builder.connection_concurrency_limit(limit as usize);
+    }
+
+    builder = builder
+        .tcp_nodelay(request.tcp_nodelay)
+        .record_latency_breakdown(GlideRuntimeConfig::get().record_latency_breakdown)
+        .dns_resolver(create_dns_resolver(&request.dns)?);
 
     // Pass the address resolver to the builder for use during topology refresh
     if let Some(resolver) = address_resolver.clone() {
@@ -2151,6 +2486,16 @@ impl std::fmt::Display for ConnectionError {
     }
 }
 
+/// Returns the size of the pipeline's requests, after checking each of them against the large
+/// request threshold.
+fn checked_pipeline_size(pipeline: &redis::Pipeline) -> RedisResult<u64> {
+    pipeline.cmd_iter().try_fold(0, |total, cmd| {
+        let size = memory_budget::request_size(cmd);
+        large_payloads::check_request(cmd, size)?;
+        Ok(total + size)
+    })
+}
+
 fn format_optional_value<T>(name: &'static str, value: Option<T>) -> String
 where
     T: std::fmt::Display,
@@ -2162,7 +2507,7 @@ where
     }
 }
 
-fn sanitized_request_string(request: &ConnectionRequest) -> String {
+pub(crate) fn sanitized_request_string(request: &ConnectionRequest) -> String {
     let addresses = request
         .addresses
         .iter()
@@ -2189,9 +2534,11 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
     };
     let request_timeout = format!(
         "\nRequest timeout: {}",
-        request
-            .request_timeout
-            .unwrap_or(DEFAULT_RESPONSE_TIMEOUT.as_millis() as u32)
+        request.request_timeout.unwrap_or(
+            GlideRuntimeConfig::get()
+                .default_request_timeout
+                .as_millis() as u32
+        )
     );
     let connection_timeout = format!(
         "\nConnection timeout: {}",
@@ -2215,12 +2562,24 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
             )
         })
         .unwrap_or_default();
+    let replica_selector = request
+        .replica_selector
+        .as_ref()
+        .map(|selector| format!("\nReplica selector: {selector:?}"))
+        .unwrap_or_default();
     let connection_retry_strategy = request.connection_retry_strategy.as_ref().map(|strategy|
             format!("\nreconnect backoff strategy: number of increasing duration retries: {}, base: {}, factor: {}, jitter: {:?}",
         strategy.number_of_retries, strategy.exponent_base, strategy.factor, strategy.jitter_percent)).unwrap_or_default();
     let protocol = request
         .protocol
-        .map(|protocol| format!("\nProtocol: {protocol:?}"))
+        .map(|protocol| {
+            let fallback = if request.protocol_fallback {
+                " (fallback to RESP2 enabled)"
+            } else {
+                ""
+            };
+            format!("\nProtocol: {protocol:?}{fallback}")
+        })
         .unwrap_or_default();
     let client_name = request
         .client_name
@@ -2231,7 +2590,8 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
         match request.periodic_checks {
             Some(PeriodicCheck::Disabled) => "\nPeriodic Checks: Disabled".to_string(),
             Some(PeriodicCheck::Enabled) => format!(
-                "\nPeriodic Checks: Enabled with default interval of {DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL:?}"
+                "\nPeriodic Checks: Enabled with default interval of {:?}",
+                GlideRuntimeConfig::get().default_periodic_topology_checks_interval
             ),
             Some(PeriodicCheck::ManualInterval(interval)) => format!(
                 "\nPeriodic Checks: Enabled with manual interval of {:?}s",
@@ -2254,14 +2614,68 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
         request.inflight_requests_limit,
     );
 
+    let memory_budget =
+        format_optional_value("\nMemory budget (bytes): {}", request.memory_budget_bytes);
+    let get_batching_window = request
+        .get_batching_window
+        .map(|window| format!("\nGET batching window: {window:?}"))
+        .unwrap_or_default();
+    let connection_concurrency_limit = format_optional_value(
+        "\nConnection concurrency limit: {}",
+        request.connection_concurrency_limit,
+    );
+
+    let health_check = request
+        .health_check
+        .as_ref()
+        .map(|config| {
+            format!(
+                "\nHealth checks: interval {}ms, timeout {}ms, failure threshold {}",
+                config.interval_ms, config.timeout_ms, config.failure_threshold
+            )
+        })
+        .unwrap_or_default();
+
+    let dns = match (request.dns.resolver, request.dns.name_servers.as_slice()) {
+        (DnsResolverType::Async, []) => String::new(),
+        (DnsResolverType::Async, name_servers) => {
+            format!("\nDNS name servers: {}", name_servers.join(", "))
+        }
+        (DnsResolverType::System, _) => "\nDNS resolver: System".to_string(),
+    };
+
     let node_discovery_mode = match request.node_discovery_mode {
         NodeDiscoveryMode::Standard => "\nNode discovery mode: Standard",
         NodeDiscoveryMode::Static => "\nNode discovery mode: Static",
         NodeDiscoveryMode::DiscoverAll => "\nNode discovery mode: DiscoverAll",
     };
 
+    let runtime_mode = match request.runtime_mode {
+        RuntimeMode::Dedicated => "",
+        RuntimeMode::Shared => "\nRuntime mode: Shared",
+        RuntimeMode::ThreadPerCore => "\nRuntime mode: ThreadPerCore",
+    };
+
+    let hot_key_tracking = request
+        .hot_key_tracking
+        .as_ref()
+        .map(|config| {
+            format!(
+                "\nHot-key tracking: sample rate {}, QPS threshold {}",
+                config.sample_rate, config.qps_threshold
+            )
+        })
+        .unwrap_or_default();
+
+    let experimental_features = if request.experimental_features.is_empty() {
+        String::new()
+    } else {
+        let names: Vec<_> = request.experimental_features.names().collect();
+        format!("\nExperimental features: {}", names.join(", "))
+    };
+
     format!(
-        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}{node_discovery_mode}",
+        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{replica_selector}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}{memory_budget}{get_batching_window}{connection_concurrency_limit}{health_check}{dns}{node_discovery_mode}{runtime_mode}{hot_key_tracking}{experimental_features}",
     )
 }
 
@@ -2290,6 +2704,40 @@ fn create_compression_manager(
     Ok(Some(Arc::new(manager)))
 }
 
+/// Creates the resolver of the host names of nodes.
+fn create_dns_resolver(config: &DnsConfig) -> RedisResult<DnsResolver> {
+    let resolver_config = match config.resolver {
+        DnsResolverType::System => DnsResolverConfig::System,
+        DnsResolverType::Async if config.name_servers.is_empty() => DnsResolverConfig::Async,
+        DnsResolverType::Async => DnsResolverConfig::AsyncWithNameServers(
+            config
+                .name_servers
+                .iter()
+                .map(|name_server| parse_name_server(name_server))
+                .collect::<RedisResult<_>>()?,
+        ),
+    };
+    Ok(DnsResolver::new(&resolver_config))
+}
+
+/// Parses an `ip` or `ip:port` name server address. The default port is 53.
+fn parse_name_server(name_server: &str) -> RedisResult<SocketAddr> {
+    name_server
+        .parse()
+        .or_else(|_| {
+            name_server
+                .parse::<IpAddr>()
+                .map(|ip| SocketAddr::new(ip, 53))
+        })
+        .map_err(|_| {
+            RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "Invalid DNS name server",
+                name_server.to_string(),
+            ))
+        })
+}
+
 impl Client {
     pub async fn new(
         request: ConnectionRequest,
@@ -2302,16 +2750,37 @@ impl Client {
             "Connection configuration",
             sanitized_request_string(&request),
         );
-        let request_timeout = to_duration(request.request_timeout, DEFAULT_RESPONSE_TIMEOUT);
+        if request.push_queue.is_some() {
+            return Err(ConnectionError::Configuration(
+                "The push queue is only supported by the socket listener".to_string(),
+            ));
+        }
+        let runtime_config = GlideRuntimeConfig::get();
+        let client_slot = ClientSlot::reserve().map_err(ConnectionError::Configuration)?;
+        memory_limit::start_monitor();
+        let request_timeout = to_duration(
+            request.request_timeout,
+            runtime_config.default_request_timeout,
+        );
         let inflight_requests_limit = request
             .inflight_requests_limit
-            .unwrap_or(DEFAULT_MAX_INFLIGHT_REQUESTS);
+            .unwrap_or(runtime_config.default_inflight_requests_limit);
         let inflight_requests_allowed = Arc::new(AtomicIsize::new(
             inflight_requests_limit.try_into().unwrap(),
         ));
 
         // Create compression manager from configuration
         let compression_manager = create_compression_manager(request.compression_config.clone())?;
+        let watch_connection_options = GlideConnectionOptions {
+            connection_timeout: Some(request.get_connection_timeout()),
+            tcp_nodelay: request.tcp_nodelay,
+            record_latency_breakdown: GlideRuntimeConfig::get().record_latency_breakdown,
+            dns_resolver: Some(
+                create_dns_resolver(&request.dns)
+                    .map_err(|err| ConnectionError::Configuration(err.to_string()))?,
+            ),
+            ..Default::default()
+        };
 
         let reconciliation_interval = match request.pubsub_reconciliation_interval_ms {
             Some(ms) if ms > 0 => Some(Duration::from_millis(ms as u64)),
@@ -2414,6 +2883,34 @@ impl Client {
                         },
                     ))
                 }),
+                interceptors: Arc::from(request.interceptors.clone()),
+                credential_expiry_monitor: None,
+                hot_key_tracker: request
+                    .hot_key_tracking
+                    .as_ref()
+                    .map(|config| Arc::new(HotKeyTracker::new(config))),
+                memory_budget: request.memory_budget_bytes.map(MemoryBudget::new),
+                get_batcher: request.get_batching_window.map(|window| {
+                    Arc::new(GetBatcher::new(
+                        Arc::downgrade(&internal_client_arc),
+                        window,
+                    ))
+                }),
+                health_checker: request.health_check.as_ref().map(|config| {
+                    Arc::new(HealthChecker::new(
+                        Arc::downgrade(&internal_client_arc),
+                        config,
+                    ))
+                }),
+                strict_response_validation: request.strict_response_validation,
+                end_to_end_deadline: request.end_to_end_deadline,
+                read_coalescer: request
+                    .experimental_features
+                    .is_enabled(ExperimentalFeature::ReadCoalescing)
+                    .then(Arc::default),
+                close_signal: Arc::default(),
+                watch_connection_options,
+                _client_slot: Some(Arc::new(client_slot)),
             };
 
             let client_arc = Arc::new(RwLock::new(client));
@@ -2425,10 +2922,19 @@ impl Client {
                 None
             };
 
+            let credential_expiry_monitor = CredentialExpiryMonitor::start(
+                &request.client_cert,
+                iam_token_manager
+                    .as_ref()
+                    .map(|manager| manager.credentials_expiry_handle()),
+                push_sender.clone(),
+            );
+
             // Update the client with the IAM token manager
             {
                 let mut client_guard = client_arc.write().await;
                 client_guard.iam_token_manager = iam_token_manager.clone();
+                client_guard.credential_expiry_monitor = credential_expiry_monitor.map(Arc::new);
             }
 
             let is_lazy = request.lazy_connect;
@@ -2599,6 +3105,18 @@ impl Client {
             client_side_cache: None,
             latency_tracker: Arc::new(crate::timeout_watchdog::LatencyTracker::new(64)),
             circuit_breaker: None,
+            interceptors: Arc::from([]),
+            credential_expiry_monitor: None,
+            hot_key_tracker: None,
+            memory_budget: None,
+            get_batcher: None,
+            health_checker: None,
+            strict_response_validation: false,
+            end_to_end_deadline: false,
+            read_coalescer: None,
+            close_signal: Arc::default(),
+            watch_connection_options: GlideConnectionOptions::default(),
+            _client_slot: None,
         }
     }
 }
@@ -2898,6 +3416,18 @@ mod tests {
             client_side_cache: None,
             latency_tracker: Arc::new(crate::timeout_watchdog::LatencyTracker::new(64)),
             circuit_breaker: None,
+            interceptors: Arc::from([]),
+            credential_expiry_monitor: None,
+            hot_key_tracker: None,
+            memory_budget: None,
+            get_batcher: None,
+            health_checker: None,
+            strict_response_validation: false,
+            end_to_end_deadline: false,
+            read_coalescer: None,
+            close_signal: Arc::default(),
+            watch_connection_options: redis::GlideConnectionOptions::default(),
+            _client_slot: None,
         }
     }
 
@@ -3230,4 +3760,23 @@ mod tests {
         cmd.arg("PING");
         assert!(!client.is_reset_command(&cmd));
     }
+
+    #[test]
+    fn test_parse_name_server() {
+        use super::parse_name_server;
+
+        assert_eq!(
+            parse_name_server("10.0.0.2").unwrap(),
+            "10.0.0.2:53".parse().unwrap()
+        );
+        assert_eq!(
+            parse_name_server("[::1]:5353").unwrap(),
+            "[::1]:5353".parse().unwrap()
+        );
+        assert_eq!(
+            parse_name_server("::1").unwrap(),
+            "[::1]:53".parse().unwrap()
+        );
+        assert!(parse_name_server("dns.example.com").is_err());
+    }
 }
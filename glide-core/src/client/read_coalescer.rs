@@ -0,0 +1,302 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Coalescing of identical concurrent reads, enabled by the experimental `read_coalescing` feature.
+//!
+//! While a read-only command is in flight, identical commands - with the same arguments, and
+//! without explicit routing - sent by other callers wait for its response instead of being sent.
+//! This reduces the load of applications whose callers read the same hot keys at the same time.
+//! Only key-based read-only commands that always return the same response for the same data are
+//! coalesced - not blocking commands, nor the ones returning random elements.
+//!
+//! A read only joins a read that was sent after the client's last write, so it never receives a
+//! value older than a write the caller has already sent. Any command or batch that isn't read-only
+//! counts as a write, whatever its keys.
+//!
+//! If the shared command fails or is cancelled, the commands waiting for it are sent on their own,
+//! so their callers receive the same errors they would without coalescing.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use redis::cluster_routing::{Routable, RoutingInfo, is_readonly_cmd};
+use redis::{Cmd, Value};
+use tokio::sync::oneshot;
+
+use super::{Client, is_blocking_command};
+
+/// The senders of the reads waiting for the response of a read in flight.
+type Waiters = Arc<Mutex<Vec<oneshot::Sender<Value>>>>;
+
+/// A read in flight, which identical reads can join while no write was sent after it.
+struct InFlightEntry {
+    /// The number of writes sent before the read.
+    writes: u64,
+    waiters: Waiters,
+}
+
+/// The reads in flight, by packed command, with the senders of the identical reads waiting for
+/// their response.
+#[derive(Default)]
+pub(crate) struct ReadCoalescer {
+    in_flight: Mutex<HashMap<Vec<u8>, InFlightEntry>>,
+    /// The number of writes sent by the client.
+    writes: AtomicU64,
+}
+
+/// The outcome of joining the identical read in flight.
+pub(super) enum CoalescedRead {
+    /// The response of the identical read.
+    Response(Value),
+    /// The command must be sent. If it's the first of its identical reads, the response must be
+    /// shared through the [`InFlightRead`].
+    Send(Option<InFlightRead>),
+}
+
+/// A read whose response is shared with the identical reads sent while it's in flight. If it's
+/// dropped without being completed, the waiting reads are sent on their own.
+pub(super) struct InFlightRead {
+    coalescer: Arc<ReadCoalescer>,
+    key: Vec<u8>,
+    waiters: Waiters,
+}
+
+impl InFlightRead {
+    /// Hands the response to the identical reads waiting for it.
+    pub(super) fn complete(mut self, response: &Value) {
+        for waiter in self.take_waiters() {
+            let _ = waiter.send(response.clone());
+        }
+    }
+
+    fn take_waiters(&mut self) -> Vec<oneshot::Sender<Value>> {
+        {
+            let mut in_flight = self.coalescer.in_flight.lock().unwrap();
+            // After a write, a newer read of the same command may have taken this read's place.
+            if in_flight
+                .get(&self.key)
+                .is_some_and(|entry| Arc::ptr_eq(&entry.waiters, &self.waiters))
+            {
+                in_flight.remove(&self.key);
+            }
+        }
+        std::mem::take(&mut *self.waiters.lock().unwrap())
+    }
+}
+
+impl Drop for InFlightRead {
+    fn drop(&mut self) {
+        // Dropping the senders makes the waiting reads send their own commands.
+        self.take_waiters();
+    }
+}
+
+impl ReadCoalescer {
+    async fn join(self: &Arc<Self>, cmd: &Cmd) -> CoalescedRead {
+        if !is_coalescable(cmd) {
+            self.record_command(cmd);
+            return CoalescedRead::Send(None);
+        }
+        let key = cmd.get_packed_command();
+        let receiver = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            let writes = self.writes.load(Ordering::SeqCst);
+            match in_flight.get(&key) {
+                Some(entry) if entry.writes == writes => {
+                    let (sender, receiver) = oneshot::channel();
+                    entry.waiters.lock().unwrap().push(sender);
+                    receiver
+                }
+                _ => {
+                    let waiters = Waiters::default();
+                    in_flight.insert(
+                        key.clone(),
+                        InFlightEntry {
+                            writes,
+                            waiters: waiters.clone(),
+                        },
+                    );
+                    return CoalescedRead::Send(Some(InFlightRead {
+                        coalescer: self.clone(),
+                        key,
+                        waiters,
+                    }));
+                }
+            }
+        };
+        match receiver.await {
+            Ok(response) => CoalescedRead::Response(response),
+            Err(_) => CoalescedRead::Send(None),
+        }
+    }
+
+    /// Counts `cmd` as a write unless it's read-only, so that later reads don't join the reads
+    /// sent before it.
+    fn record_command(&self, cmd: &Cmd) {
+        if is_write(cmd) {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Returns whether `cmd` may modify data.
+fn is_write(cmd: &Cmd) -> bool {
+    !cmd.command()
+        .is_some_and(|command| is_readonly_cmd(&command))
+}
+
+/// Returns whether identical calls of `cmd` can share a response.
+fn is_coalescable(cmd: &Cmd) -> bool {
+    let Some(command) = cmd.command() else {
+        return false;
+    };
+    is_readonly_cmd(&command)
+        && !is_blocking_command(&command)
+        && !matches!(
+            command.as_slice(),
+            b"HRANDFIELD" | b"SRANDMEMBER" | b"ZRANDMEMBER"
+        )
+        && RoutingInfo::key_for_command(cmd).is_some()
+}
+
+impl Client {
+    /// Joins the identical read in flight, if read coalescing is enabled and the command has no
+    /// explicit routing.
+    pub(super) async fn coalesced_read(
+        &self,
+        cmd: &Cmd,
+        routing: Option<&RoutingInfo>,
+    ) -> CoalescedRead {
+        match &self.read_coalescer {
+            Some(coalescer) if routing.is_none() => coalescer.join(cmd).await,
+            Some(coalescer) => {
+                coalescer.record_command(cmd);
+                CoalescedRead::Send(None)
+            }
+            None => CoalescedRead::Send(None),
+        }
+    }
+
+    /// Counts a batch with a command that isn't read-only as a write, if read coalescing is
+    /// enabled.
+    pub(super) fn record_batch_for_read_coalescing(&self, pipeline: &redis::Pipeline) {
+        if let Some(coalescer) = &self.read_coalescer
+            && pipeline.cmd_iter().any(|cmd| is_write(cmd))
+        {
+            coalescer.writes.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get(key: &str) -> Cmd {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(key);
+        cmd
+    }
+
+    async fn wait_for_waiter(coalescer: &ReadCoalescer, cmd: &Cmd) {
+        let key = cmd.get_packed_command();
+        while coalescer.in_flight.lock().unwrap()[&key]
+            .waiters
+            .lock()
+            .unwrap()
+            .is_empty()
+        {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[test]
+    fn test_coalescable_commands() {
+        assert!(is_coalescable(&get("key")));
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("value");
+        assert!(!is_coalescable(&set));
+        let mut srandmember = redis::cmd("SRANDMEMBER");
+        srandmember.arg("key");
+        assert!(!is_coalescable(&srandmember));
+        assert!(!is_coalescable(&redis::cmd("DBSIZE")));
+    }
+
+    #[tokio::test]
+    async fn test_identical_reads_share_the_response() {
+        let coalescer = Arc::new(ReadCoalescer::default());
+        let CoalescedRead::Send(Some(first)) = coalescer.join(&get("key")).await else {
+            panic!("the first read should be sent");
+        };
+        assert!(matches!(
+            coalescer.join(&get("other")).await,
+            CoalescedRead::Send(Some(_))
+        ));
+
+        let waiting = tokio::spawn({
+            let coalescer = coalescer.clone();
+            async move { coalescer.join(&get("key")).await }
+        });
+        wait_for_waiter(&coalescer, &get("key")).await;
+        first.complete(&Value::BulkString(b"value".to_vec()));
+        assert!(matches!(
+            waiting.await.unwrap(),
+            CoalescedRead::Response(Value::BulkString(value)) if value == b"value"
+        ));
+        assert!(
+            !coalescer
+                .in_flight
+                .lock()
+                .unwrap()
+                .contains_key(&get("key").get_packed_command())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_waiting_reads_are_sent_when_the_shared_read_fails() {
+        let coalescer = Arc::new(ReadCoalescer::default());
+        let CoalescedRead::Send(Some(first)) = coalescer.join(&get("key")).await else {
+            panic!("the first read should be sent");
+        };
+        let waiting = tokio::spawn({
+            let coalescer = coalescer.clone();
+            async move { coalescer.join(&get("key")).await }
+        });
+        wait_for_waiter(&coalescer, &get("key")).await;
+        drop(first);
+        assert!(matches!(waiting.await.unwrap(), CoalescedRead::Send(None)));
+    }
+
+    #[tokio::test]
+    async fn test_reads_after_a_write_dont_join_earlier_reads() {
+        let coalescer = Arc::new(ReadCoalescer::default());
+        let CoalescedRead::Send(Some(before_write)) = coalescer.join(&get("key")).await else {
+            panic!("the first read should be sent");
+        };
+        let mut set = redis::cmd("SET");
+        set.arg("key").arg("new");
+        assert!(matches!(
+            coalescer.join(&set).await,
+            CoalescedRead::Send(None)
+        ));
+
+        let CoalescedRead::Send(Some(after_write)) = coalescer.join(&get("key")).await else {
+            panic!("a read after a write should be sent");
+        };
+        let waiting = tokio::spawn({
+            let coalescer = coalescer.clone();
+            async move { coalescer.join(&get("key")).await }
+        });
+        wait_for_waiter(&coalescer, &get("key")).await;
+
+        // The earlier read completing must not hand its value to the reads after the write.
+        before_write.complete(&Value::BulkString(b"old".to_vec()));
+        assert!(!waiting.is_finished());
+        after_write.complete(&Value::BulkString(b"new".to_vec()));
+        assert!(matches!(
+            waiting.await.unwrap(),
+            CoalescedRead::Response(Value::BulkString(value)) if value == b"new"
+        ));
+        assert!(coalescer.in_flight.lock().unwrap().is_empty());
+    }
+}
@@ -4,7 +4,7 @@ use super::{NodeAddress, TlsMode};
 use async_trait::async_trait;
 use futures_intrusive::sync::ManualResetEvent;
 use logger_core::{log_debug, log_error, log_trace, log_warn};
-use redis::aio::{DisconnectNotifier, MultiplexedConnection};
+use redis::aio::{DisconnectNotifier, DnsResolver, MultiplexedConnection};
 use redis::{
     AddressResolver, GlideConnectionOptions, PushInfo, RedisConnectionInfo, RedisError,
     RedisResult, RetryStrategy,
@@ -21,7 +21,8 @@ use tokio::task;
 use tokio::time::timeout;
 use tokio_retry2::{Retry, RetryError};
 
-use super::{run_with_timeout, types::DEFAULT_CONNECTION_TIMEOUT};
+use super::run_with_timeout;
+use crate::runtime_config::GlideRuntimeConfig;
 
 const WRITE_LOCK_ERR: &str = "Failed to acquire the write lock";
 const READ_LOCK_ERR: &str = "Failed to acquire the read lock";
@@ -157,7 +158,7 @@ async fn get_multiplexed_connection(
         Some(
             connection_options
                 .connection_timeout
-                .unwrap_or(DEFAULT_CONNECTION_TIMEOUT),
+                .unwrap_or_else(|| GlideRuntimeConfig::get().default_connection_timeout),
         ),
         client.get_multiplexed_async_connection(connection_options.clone()),
     )
@@ -195,6 +196,7 @@ impl TokioDisconnectNotifier {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn create_connection(
     connection_backend: ConnectionBackend,
     retry_strategy: RetryStrategy,
@@ -203,6 +205,7 @@ async fn create_connection(
     connection_timeout: Duration,
     tcp_nodelay: bool,
     pubsub_synchronizer: Option<Arc<dyn crate::pubsub::PubSubSynchronizer>>,
+    dns_resolver: DnsResolver,
 ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
     let client = {
         let guard = connection_backend
@@ -223,6 +226,9 @@ async fn create_connection(
         tcp_nodelay,
         pubsub_synchronizer,
         iam_token_provider: None,
+        dns_resolver: Some(dns_resolver),
+        record_latency_breakdown: crate::runtime_config::GlideRuntimeConfig::get()
+            .record_latency_breakdown,
     };
 
     // Wrap retry loop in timeout so total time respects connection_timeout
@@ -275,16 +281,16 @@ async fn create_connection(
                 Ok(Err(e)) => e,
                 _ => std::io::Error::from(std::io::ErrorKind::TimedOut).into(),
             };
+            let address = connection_backend
+                .get_backend_client()
+                .get_connection_info()
+                .addr
+                .to_string();
             log_warn(
                 "connection creation",
-                format!(
-                    "Failed connecting to {}, due to {err}",
-                    connection_backend
-                        .get_backend_client()
-                        .get_connection_info()
-                        .addr
-                ),
+                format!("Failed connecting to {address}, due to {err}"),
             );
+            record_connection_error(&address, &err);
             let connection = ReconnectingConnection {
                 inner: Arc::new(InnerReconnectingConnection {
                     state: Mutex::new(ConnectionState::InitializedDisconnected),
@@ -298,6 +304,11 @@ async fn create_connection(
     }
 }
 
+/// Records a failed connection attempt to `address` in the telemetry.
+fn record_connection_error(address: &str, err: &RedisError) {
+    Telemetry::record_connection_error(address, &format!("{:?}", err.kind()), &err.to_string());
+}
+
 // tls_params should be only set if tls_mode is SecureTls
 // this should be validated before calling this function
 fn get_client(
@@ -338,6 +349,7 @@ impl ReconnectingConnection {
         tcp_nodelay: bool,
         pubsub_synchronizer: Option<Arc<dyn crate::pubsub::PubSubSynchronizer>>,
         address_resolver: Option<&std::sync::Arc<dyn AddressResolver>>,
+        dns_resolver: DnsResolver,
         iam_token_handle: Option<IAMTokenHandle>,
     ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
         log_debug(
@@ -366,6 +378,7 @@ impl ReconnectingConnection {
             connection_timeout,
             tcp_nodelay,
             pubsub_synchronizer,
+            dns_resolver,
         )
         .await
     }
@@ -379,6 +392,15 @@ impl ReconnectingConnection {
             .to_string()
     }
 
+    /// Returns the connection info of the node, including its current credentials.
+    pub(crate) fn connection_info(&self) -> redis::ConnectionInfo {
+        self.inner
+            .backend
+            .get_backend_client()
+            .get_connection_info()
+            .clone()
+    }
+
     pub(super) fn is_dropped(&self) -> bool {
         self.inner
             .backend
@@ -436,6 +458,11 @@ impl ReconnectingConnection {
             // Attempting to reconnect a connection that was dropped (for any reason) - update the telemetry by reducing
             // the number of opened connections by 1, it will be incremented by 1 after a successful re-connect
             Telemetry::decr_total_connections(1);
+            Telemetry::record_connection_error(
+                &self.node_address(),
+                "ConnectionDropped",
+                "The connection was dropped, reconnecting",
+            );
         }
 
         // The reconnect task is spawned instead of awaited here, so that the reconnect attempt will continue in the
@@ -502,11 +529,9 @@ impl ReconnectingConnection {
                     .await
                 {
                     Ok(mut connection) => {
-                        if connection
-                            .send_packed_command(&redis::cmd("PING"))
-                            .await
-                            .is_err()
+                        if let Err(err) = connection.send_packed_command(&redis::cmd("PING")).await
                         {
+                            record_connection_error(&connection_clone.node_address(), &err);
                             tokio::time::sleep(sleep_duration).await;
                             continue;
                         }
@@ -524,7 +549,10 @@ impl ReconnectingConnection {
                         Telemetry::incr_total_connections(1);
                         return;
                     }
-                    Err(_) => tokio::time::sleep(sleep_duration).await,
+                    Err(err) => {
+                        record_connection_error(&connection_clone.node_address(), &err);
+                        tokio::time::sleep(sleep_duration).await
+                    }
                 }
             }
         });
@@ -0,0 +1,352 @@
+//! Structured `INFO`, `CONFIG GET` and `CLIENT LIST` responses of multiple nodes.
+//!
+//! The helpers send the command to every selected node, and parse each node's response into
+//! typed fields, keyed by the node's address. Values that are integers or decimals are converted
+//! to numbers, and compound values such as `db0:keys=1,expires=0` or the `cmdstat_*` fields are
+//! split into nested fields.
+
+use std::collections::BTreeMap;
+
+use futures::future::try_join_all;
+use redis::cluster_routing::{MultipleNodeRoutingInfo, RoutingInfo, SingleNodeRoutingInfo};
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+use super::health_check::parse_address;
+use super::{Client, ClientWrapper};
+use crate::config_drift::parse_config_get_response;
+use crate::errors::unexpected_response;
+
+/// The nodes to send a command to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeSelection {
+    AllNodes,
+    /// All the primaries. In standalone read-only mode, where the primary is unknown, all nodes.
+    AllPrimaries,
+    /// The nodes with the given `host:port` addresses.
+    Nodes(Vec<String>),
+}
+
+/// A parsed field value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+    /// A compound value of comma separated `name=value` pairs.
+    Nested(BTreeMap<String, FieldValue>),
+}
+
+impl FieldValue {
+    fn parse(value: &str) -> Self {
+        if let Ok(integer) = value.parse() {
+            return FieldValue::Integer(integer);
+        }
+        // Only plain decimals, so that values like `inf` or `1e5` stay as they were sent
+        let is_decimal = value
+            .strip_prefix('-')
+            .unwrap_or(value)
+            .split_once('.')
+            .is_some_and(|(whole, fraction)| {
+                !whole.is_empty()
+                    && whole.bytes().all(|byte| byte.is_ascii_digit())
+                    && fraction.bytes().all(|byte| byte.is_ascii_digit())
+            });
+        if is_decimal && let Ok(float) = value.parse() {
+            return FieldValue::Float(float);
+        }
+        FieldValue::Text(value.to_string())
+    }
+
+    /// Parses an `INFO` value, which is nested if it's made of `name=value` pairs.
+    fn parse_info(value: &str) -> Self {
+        let pairs: Option<BTreeMap<String, FieldValue>> = value
+            .split(',')
+            .map(|pair| {
+                let (name, value) = pair.split_once('=')?;
+                Some((name.to_string(), FieldValue::parse(value)))
+            })
+            .collect();
+        match pairs {
+            Some(pairs) => FieldValue::Nested(pairs),
+            None => FieldValue::parse(value),
+        }
+    }
+}
+
+/// The fields of an `INFO` response, by lowercase section name.
+pub type InfoSections = BTreeMap<String, BTreeMap<String, FieldValue>>;
+
+/// The fields of a single connection in a `CLIENT LIST` response.
+pub type ClientInfo = BTreeMap<String, FieldValue>;
+
+/// Parses the text of an `INFO` response. Fields that precede the first section header are in
+/// the section named `""`.
+pub fn parse_info(text: &str) -> InfoSections {
+    let mut sections = InfoSections::new();
+    let mut section = String::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(header) = line.strip_prefix('#') {
+            section = header.trim().to_lowercase();
+            sections.entry(section.clone()).or_default();
+        } else if let Some((name, value)) = line.split_once(':') {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(name.to_string(), FieldValue::parse_info(value));
+        }
+    }
+    sections
+}
+
+/// Parses the text of a `CLIENT LIST` response.
+pub fn parse_client_list(text: &str) -> Vec<ClientInfo> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split(' ')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(name, value)| (name.to_string(), FieldValue::parse(value)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Parses a `CONFIG GET` response, in either its map or its flat array form.
+pub fn parse_config(response: &Value) -> RedisResult<BTreeMap<String, FieldValue>> {
+    Ok(parse_config_get_response(response)?
+        .into_iter()
+        .map(|(name, value)| (name, FieldValue::parse(&value)))
+        .collect())
+}
+
+fn text(value: &Value) -> RedisResult<String> {
+    match value {
+        Value::BulkString(bytes) => Ok(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(text) => Ok(text.clone()),
+        Value::VerbatimString { text, .. } => Ok(text.clone()),
+        Value::Int(integer) => Ok(integer.to_string()),
+        _ => Err(unexpected_response("text", value)),
+    }
+}
+
+impl Client {
+    /// Runs `INFO` with the given sections, or the default sections if `sections` is empty, on
+    /// the selected nodes.
+    pub async fn info_by_node(
+        &mut self,
+        sections: &[&str],
+        nodes: NodeSelection,
+    ) -> RedisResult<BTreeMap<String, InfoSections>> {
+        let mut cmd = redis::cmd("INFO");
+        cmd.arg(sections);
+        self.send_to_nodes(cmd, nodes)
+            .await?
+            .into_iter()
+            .map(|(address, response)| Ok((address, parse_info(&text(&response)?))))
+            .collect()
+    }
+
+    /// Runs `CONFIG GET` with the given parameters, which may be glob-style patterns, on the
+    /// selected nodes.
+    pub async fn config_get_by_node(
+        &mut self,
+        parameters: &[&str],
+        nodes: NodeSelection,
+    ) -> RedisResult<BTreeMap<String, BTreeMap<String, FieldValue>>> {
+        let mut cmd = redis::cmd("CONFIG");
+        cmd.arg("GET").arg(parameters);
+        self.send_to_nodes(cmd, nodes)
+            .await?
+            .into_iter()
+            .map(|(address, response)| Ok((address, parse_config(&response)?)))
+            .collect()
+    }
+
+    /// Runs `CLIENT LIST` on the selected nodes.
+    pub async fn client_list_by_node(
+        &mut self,
+        nodes: NodeSelection,
+    ) -> RedisResult<BTreeMap<String, Vec<ClientInfo>>> {
+        let mut cmd = redis::cmd("CLIENT");
+        cmd.arg("LIST");
+        self.send_to_nodes(cmd, nodes)
+            .await?
+            .into_iter()
+            .map(|(address, response)| Ok((address, parse_client_list(&text(&response)?))))
+            .collect()
+    }
+
+    /// Sends `cmd` to the selected nodes, and returns the response of each node by its address.
+    async fn send_to_nodes(
+        &mut self,
+        mut cmd: redis::Cmd,
+        nodes: NodeSelection,
+    ) -> RedisResult<Vec<(String, Value)>> {
+        match self.get_or_initialize_client().await? {
+            ClientWrapper::Standalone(client) => {
+                let addresses = match nodes {
+                    NodeSelection::AllNodes => client.node_addresses(),
+                    NodeSelection::AllPrimaries => client
+                        .primary_address()
+                        .map(|address| vec![address])
+                        .unwrap_or_else(|| client.node_addresses()),
+                    NodeSelection::Nodes(addresses) => addresses,
+                };
+                let requests = addresses.into_iter().map(|address| {
+                    let (client, cmd) = (&client, &cmd);
+                    async move {
+                        let response = client.send_command_to_node(cmd, &address).await?;
+                        Ok::<_, RedisError>((address, response))
+                    }
+                });
+                try_join_all(requests).await
+            }
+            ClientWrapper::Cluster { .. } => {
+                let routing = match nodes {
+                    NodeSelection::AllNodes => MultipleNodeRoutingInfo::AllNodes,
+                    NodeSelection::AllPrimaries => MultipleNodeRoutingInfo::AllMasters,
+                    NodeSelection::Nodes(addresses) => {
+                        let mut responses = Vec::with_capacity(addresses.len());
+                        for address in addresses {
+                            let (host, port) = parse_address(&address)?;
+                            let routing =
+                                RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+                                    host,
+                                    port,
+                                });
+                            let response = self.send_command(&mut cmd, Some(routing)).await?;
+                            responses.push((address, response));
+                        }
+                        return Ok(responses);
+                    }
+                };
+                // Without a response policy, the responses are returned by node address
+                let response = self
+                    .send_command(&mut cmd, Some(RoutingInfo::MultiNode((routing, None))))
+                    .await?;
+                let Value::Map(responses) = response else {
+                    return Err(unexpected_response("a response per node", &response));
+                };
+                responses
+                    .into_iter()
+                    .map(|(address, response)| Ok((text(&address)?, response)))
+                    .collect()
+            }
+            ClientWrapper::Lazy(_) => Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Client is not initialized",
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested(pairs: &[(&str, FieldValue)]) -> FieldValue {
+        FieldValue::Nested(
+            pairs
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_parse_info() {
+        let text = "# Server\r\nredis_version:7.2.4\r\nuptime_in_seconds:42\r\n\r\n\
+                    # Memory\r\nmem_fragmentation_ratio:1.25\r\nmaxmemory_policy:noeviction\r\n\r\n\
+                    # Keyspace\r\ndb0:keys=3,expires=0,avg_ttl=0\r\n";
+        let sections = parse_info(text);
+        assert_eq!(
+            sections.keys().collect::<Vec<_>>(),
+            ["keyspace", "memory", "server"]
+        );
+        assert_eq!(
+            sections["server"]["redis_version"],
+            FieldValue::Text("7.2.4".to_string())
+        );
+        assert_eq!(
+            sections["server"]["uptime_in_seconds"],
+            FieldValue::Integer(42)
+        );
+        assert_eq!(
+            sections["memory"]["mem_fragmentation_ratio"],
+            FieldValue::Float(1.25)
+        );
+        assert_eq!(
+            sections["memory"]["maxmemory_policy"],
+            FieldValue::Text("noeviction".to_string())
+        );
+        assert_eq!(
+            sections["keyspace"]["db0"],
+            nested(&[
+                ("avg_ttl", FieldValue::Integer(0)),
+                ("expires", FieldValue::Integer(0)),
+                ("keys", FieldValue::Integer(3)),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_field_value() {
+        assert_eq!(FieldValue::parse("-3"), FieldValue::Integer(-3));
+        assert_eq!(FieldValue::parse("-0.5"), FieldValue::Float(-0.5));
+        assert_eq!(
+            FieldValue::parse("inf"),
+            FieldValue::Text("inf".to_string())
+        );
+        assert_eq!(
+            FieldValue::parse("1e5"),
+            FieldValue::Text("1e5".to_string())
+        );
+        assert_eq!(FieldValue::parse(".5"), FieldValue::Text(".5".to_string()));
+        assert_eq!(FieldValue::parse(""), FieldValue::Text(String::new()));
+    }
+
+    #[test]
+    fn test_parse_client_list() {
+        let text = "id=3 addr=127.0.0.1:50188 name= age=12 db=0 flags=N cmd=client|list\n\
+                    id=4 addr=127.0.0.1:50190 name=worker age=1 db=2 flags=P cmd=subscribe\n";
+        let clients = parse_client_list(text);
+        assert_eq!(clients.len(), 2);
+        assert_eq!(clients[0]["id"], FieldValue::Integer(3));
+        assert_eq!(
+            clients[0]["addr"],
+            FieldValue::Text("127.0.0.1:50188".to_string())
+        );
+        assert_eq!(clients[0]["name"], FieldValue::Text(String::new()));
+        assert_eq!(
+            clients[0]["cmd"],
+            FieldValue::Text("client|list".to_string())
+        );
+        assert_eq!(clients[1]["db"], FieldValue::Integer(2));
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let bulk = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        let expected = BTreeMap::from([
+            ("maxmemory".to_string(), FieldValue::Integer(0)),
+            (
+                "maxmemory-policy".to_string(),
+                FieldValue::Text("allkeys-lru".to_string()),
+            ),
+        ]);
+        let map = Value::Map(vec![
+            (bulk("maxmemory"), bulk("0")),
+            (bulk("maxmemory-policy"), bulk("allkeys-lru")),
+        ]);
+        assert_eq!(parse_config(&map).unwrap(), expected);
+        let array = Value::Array(vec![
+            bulk("maxmemory"),
+            bulk("0"),
+            bulk("maxmemory-policy"),
+            bulk("allkeys-lru"),
+        ]);
+        assert_eq!(parse_config(&array).unwrap(), expected);
+        assert!(parse_config(&Value::Array(vec![bulk("maxmemory")])).is_err());
+    }
+}
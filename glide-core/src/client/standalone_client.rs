@@ -8,13 +8,14 @@ use futures::{StreamExt, future, stream};
 use logger_core::log_debug;
 use logger_core::log_info;
 use logger_core::log_warn;
-use redis::aio::ConnectionLike;
+use redis::aio::{ConnectionLike, DnsResolver};
 use redis::cluster_routing::{self, ResponsePolicy, Routable, RoutingInfo, is_readonly_cmd};
-use redis::{AddressResolver, PushInfo, RedisError, RedisResult, RetryStrategy, Value};
+use redis::replica_selector::ReplicaSelection;
+use redis::{AddressResolver, ErrorKind, PushInfo, RedisError, RedisResult, RetryStrategy, Value};
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use telemetrylib::Telemetry;
 use tokio::sync::mpsc;
 use tokio::task;
@@ -36,6 +37,7 @@ enum ReadFrom {
         client_az: String,
         last_read_replica_index: Arc<AtomicUsize>,
     },
+    Custom(ReplicaSelection),
 }
 
 #[derive(Debug)]
@@ -53,6 +55,8 @@ impl Drop for DropWrapper {
         for node in self.nodes.iter() {
             node.mark_as_dropped();
         }
+        let addresses: Vec<String> = self.nodes.iter().map(|node| node.node_address()).collect();
+        Telemetry::log_connection_errors(&addresses);
     }
 }
 
@@ -237,10 +241,14 @@ impl StandaloneClient {
             None
         };
 
+        let dns_resolver = super::create_dns_resolver(&connection_request.dns)
+            .map_err(|err| StandaloneClientConnectionError::FailedConnection(vec![(None, err)]))?;
+
         let read_only = connection_request.read_only;
         let node_discovery_mode = connection_request.node_discovery_mode;
         let addresses = connection_request.addresses.clone();
         let read_from_option = connection_request.read_from.clone();
+        let replica_selector = connection_request.replica_selector.clone();
 
         let iam_token_handle = iam_token_manager.map(|m| m.get_token_handle());
 
@@ -251,6 +259,7 @@ impl StandaloneClient {
         let discovery_pubsub_sync = pubsub_synchronizer.clone();
         let discovery_iam_handle = iam_token_handle.clone();
         let discovery_resolver = connection_request.address_resolver.clone();
+        let discovery_dns_resolver = dns_resolver.clone();
 
         let mut stream = stream::iter(addresses)
             .map(move |address| {
@@ -266,6 +275,7 @@ impl StandaloneClient {
                 let skip_replication =
                     read_only || node_discovery_mode == NodeDiscoveryMode::Static;
                 let resolver = connection_request.address_resolver.clone();
+                let dns_resolver = dns_resolver.clone();
                 let iam_handle = iam_token_handle.clone();
                 async move {
                     get_connection_and_replication_info(
@@ -281,6 +291,7 @@ impl StandaloneClient {
                         &sync,
                         skip_replication,
                         resolver.as_ref(),
+                        &dns_resolver,
                         iam_handle,
                     )
                     .await
@@ -387,6 +398,7 @@ impl StandaloneClient {
                     let sync = discovery_pubsub_sync.clone();
                     let iam_handle = discovery_iam_handle.clone();
                     let resolver = discovery_resolver.clone();
+                    let dns_resolver = discovery_dns_resolver.clone();
                     async move {
                         let result = get_connection_and_replication_info(
                             &address,
@@ -401,6 +413,7 @@ impl StandaloneClient {
                             &sync,
                             false,
                             resolver.as_ref(),
+                            &dns_resolver,
                             iam_handle,
                         )
                         .await;
@@ -457,6 +470,7 @@ impl StandaloneClient {
                         let sync = discovery_pubsub_sync.clone();
                         let iam_handle = discovery_iam_handle.clone();
                         let resolver = discovery_resolver.clone();
+                        let dns_resolver = discovery_dns_resolver.clone();
                         async move {
                             let result = get_connection_and_replication_info(
                                 &address,
@@ -471,6 +485,7 @@ impl StandaloneClient {
                                 &sync,
                                 false,
                                 resolver.as_ref(),
+                                &dns_resolver,
                                 iam_handle,
                             )
                             .await;
@@ -543,7 +558,9 @@ impl StandaloneClient {
                 ),
             );
         }
-        let read_from = if read_only && read_from_option.is_none() {
+        let read_from = if let Some(selector) = replica_selector {
+            ReadFrom::Custom(ReplicaSelection::new(selector))
+        } else if read_only && read_from_option.is_none() {
             // Default to PreferReplica when read_only=true and no ReadFrom specified
             ReadFrom::PreferReplica {
                 latest_read_replica_index: Default::default(),
@@ -723,6 +740,33 @@ impl StandaloneClient {
         self.round_robin_read_from_all_nodes(latest_read_replica_index)
     }
 
+    /// Returns the replica chosen by `selection` among the connected replicas, or the primary if
+    /// no replica was chosen.
+    async fn read_from_replica_with_selector(
+        &self,
+        selection: &ReplicaSelection,
+    ) -> &ReconnectingConnection {
+        let mut replicas = Vec::new();
+        for (index, node) in self.inner.nodes.iter().enumerate() {
+            if index == self.inner.primary_index || !node.is_connected() {
+                continue;
+            }
+            let az = match node.get_connection().await {
+                Ok(connection) => connection.get_az(),
+                Err(_) => continue,
+            };
+            replicas.push((index, node.node_address(), az));
+        }
+        let candidates: Vec<(&str, Option<&str>)> = replicas
+            .iter()
+            .map(|(_, address, az)| (address.as_str(), az.as_deref()))
+            .collect();
+        match selection.select(None, &candidates) {
+            Some(candidate) => &self.inner.nodes[replicas[candidate].0],
+            None => self.get_primary_connection(),
+        }
+    }
+
     async fn get_connection(&self, readonly: bool) -> &ReconnectingConnection {
         if self.inner.nodes.len() == 1 || !readonly {
             return self.get_primary_connection();
@@ -753,6 +797,7 @@ impl StandaloneClient {
                 )
                 .await
             }
+            ReadFrom::Custom(selection) => self.read_from_replica_with_selector(selection).await,
         }
     }
 
@@ -761,8 +806,7 @@ impl StandaloneClient {
         reconnecting_connection: &ReconnectingConnection,
     ) -> RedisResult<Value> {
         // Mark command as sent for watchdog diagnostics
-        cmd.watchdog_phase
-            .store(redis::PHASE_SENT, std::sync::atomic::Ordering::Release);
+        cmd.mark_sent();
         let mut connection = reconnecting_connection.get_connection().await?;
         let result = connection.send_packed_command(cmd).await;
         match result {
@@ -855,7 +899,22 @@ impl StandaloneClient {
         readonly: bool,
     ) -> RedisResult<Value> {
         let reconnecting_connection = self.get_connection(readonly).await;
-        Self::send_request(cmd, reconnecting_connection).await
+        let ReadFrom::Custom(selection) = &self.inner.read_from else {
+            return Self::send_request(cmd, reconnecting_connection).await;
+        };
+        // A custom replica selector chooses by the latency and failures of the nodes
+        let start = Instant::now();
+        let result = Self::send_request(cmd, reconnecting_connection).await;
+        match &result {
+            Ok(_) => {
+                selection.record_success(&reconnecting_connection.node_address(), start.elapsed())
+            }
+            Err(err) if err.is_unrecoverable_error() || err.is_timeout() => {
+                selection.record_failure(&reconnecting_connection.node_address())
+            }
+            Err(_) => {}
+        }
+        result
     }
 
     pub async fn send_command(&mut self, cmd: &redis::Cmd) -> RedisResult<Value> {
@@ -885,7 +944,26 @@ impl StandaloneClient {
         offset: usize,
         count: usize,
     ) -> RedisResult<Vec<Value>> {
-        let reconnecting_connection = self.get_primary_connection();
+        Self::send_pipeline_to(self.get_primary_connection(), pipeline, offset, count).await
+    }
+
+    /// Sends a pipeline of read-only commands to the node chosen by the client's read strategy.
+    pub async fn send_read_only_pipeline(
+        &mut self,
+        pipeline: &redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
+        let reconnecting_connection = self.get_connection(true).await;
+        Self::send_pipeline_to(reconnecting_connection, pipeline, offset, count).await
+    }
+
+    async fn send_pipeline_to(
+        reconnecting_connection: &ReconnectingConnection,
+        pipeline: &redis::Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisResult<Vec<Value>> {
         let mut connection = reconnecting_connection.get_connection().await?;
         let result = connection
             .send_packed_commands(pipeline, offset, count)
@@ -979,6 +1057,69 @@ impl StandaloneClient {
         });
     }
 
+    /// Returns the addresses of the nodes the client is connected to.
+    pub(crate) fn node_addresses(&self) -> Vec<String> {
+        self.inner
+            .nodes
+            .iter()
+            .map(|node| node.node_address())
+            .collect()
+    }
+
+    /// Sends a PING to the node at `address`, without waiting for a reconnecting connection.
+    pub(crate) async fn ping_node(&self, address: &str) -> RedisResult<Value> {
+        let node = self.node_by_address(address)?;
+        let Some(mut connection) = node.try_get_connection().await else {
+            return Err(RedisError::from((
+                ErrorKind::IoError,
+                "Node is reconnecting",
+                address.to_string(),
+            )));
+        };
+        connection.send_packed_command(&redis::cmd("PING")).await
+    }
+
+    /// Returns the address of the primary node, or `None` in read-only mode, where the primary is
+    /// unknown.
+    pub(crate) fn primary_address(&self) -> Option<String> {
+        (!self.inner.read_only).then(|| self.inner.nodes[self.inner.primary_index].node_address())
+    }
+
+    /// Returns the connection info of the primary node, or `None` in read-only mode.
+    pub(crate) fn primary_connection_info(&self) -> Option<redis::ConnectionInfo> {
+        (!self.inner.read_only).then(|| self.get_primary_connection().connection_info())
+    }
+
+    /// Sends `cmd` to the node at `address`.
+    pub(crate) async fn send_command_to_node(
+        &self,
+        cmd: &redis::Cmd,
+        address: &str,
+    ) -> RedisResult<Value> {
+        Self::send_request(cmd, self.node_by_address(address)?).await
+    }
+
+    /// Closes the connection to the node at `address`, and connects to it again in the background.
+    pub(crate) fn reconnect_node(&self, address: &str) -> RedisResult<()> {
+        self.node_by_address(address)?
+            .reconnect(ReconnectReason::ConnectionDropped);
+        Ok(())
+    }
+
+    fn node_by_address(&self, address: &str) -> RedisResult<&ReconnectingConnection> {
+        self.inner
+            .nodes
+            .iter()
+            .find(|node| node.node_address() == address)
+            .ok_or_else(|| {
+                RedisError::from((
+                    ErrorKind::ClientError,
+                    "Unknown node address",
+                    address.to_string(),
+                ))
+            })
+    }
+
     /// Update the password used to authenticate with the servers.
     /// If the password is `None`, the password will be removed.
     pub async fn update_connection_password(
@@ -1074,6 +1215,7 @@ async fn get_connection_and_replication_info(
     pubsub_synchronizer: &Option<Arc<dyn crate::pubsub::PubSubSynchronizer>>,
     skip_replication_check: bool,
     address_resolver: Option<&Arc<dyn AddressResolver>>,
+    dns_resolver: &DnsResolver,
     iam_token_handle: Option<super::IAMTokenHandle>,
 ) -> Result<(ReconnectingConnection, Option<Value>), (ReconnectingConnection, RedisError)> {
     let reconnecting_connection = ReconnectingConnection::new(
@@ -1088,6 +1230,7 @@ async fn get_connection_and_replication_info(
         tcp_nodelay,
         pubsub_synchronizer.clone(),
         address_resolver,
+        dns_resolver.clone(),
         iam_token_handle,
     )
     .await?;
@@ -4,17 +4,24 @@
 use logger_core::log_warn;
 use redis::AddressResolver;
 use redis::cache::EvictionPolicy;
+use redis::replica_selector::ReplicaSelector;
 #[allow(unused_imports)]
 use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::client::interceptor::CommandInterceptor;
+use crate::client_runtime::RuntimeMode;
 #[cfg(feature = "proto")]
 use crate::compression::CompressionBackendType;
 use crate::compression::CompressionConfig;
 #[cfg(feature = "proto")]
 use crate::connection_request as protobuf;
+use crate::experimental::ExperimentalFeatures;
 use crate::iam::ServiceType;
+use crate::pubsub::push_queue::PushQueueConfig;
+#[cfg(feature = "proto")]
+use crate::pubsub::push_queue::{DEFAULT_PUSH_QUEUE_CAPACITY, OverflowPolicy};
 #[cfg(feature = "proto")]
 #[allow(unused_imports)]
 use ::protobuf::EnumOrUnknown;
@@ -27,6 +34,7 @@ pub struct ConnectionRequest {
     pub authentication_info: Option<AuthenticationInfo>,
     pub database_id: i64,
     pub protocol: Option<redis::ProtocolVersion>,
+    pub protocol_fallback: bool,
     pub tls_mode: Option<TlsMode>,
     pub addresses: Vec<NodeAddress>,
     pub cluster_mode_enabled: bool,
@@ -38,6 +46,16 @@ pub struct ConnectionRequest {
     pub inflight_requests_limit: Option<u32>,
     pub lazy_connect: bool,
     pub refresh_topology_from_initial_nodes: bool,
+    pub topology_from_cluster_shards: bool,
+    pub topology_change_events: bool,
+    /// Fail responses that don't match the shape expected for their command, instead of coercing
+    /// them.
+    pub strict_response_validation: bool,
+    /// Deduct the time of all the attempts of a request from the same request timeout.
+    pub end_to_end_deadline: bool,
+    /// The most connections to cluster nodes set up at a time. `None` is unlimited.
+    pub connection_concurrency_limit: Option<u32>,
+    pub experimental_features: ExperimentalFeatures,
     pub root_certs: Vec<Vec<u8>>,
     pub client_cert: Vec<u8>,
     pub client_key: Vec<u8>,
@@ -48,10 +66,28 @@ pub struct ConnectionRequest {
     pub client_side_cache: Option<ClientSideCache>,
     pub node_discovery_mode: NodeDiscoveryMode,
     pub address_resolver: Option<Arc<dyn AddressResolver>>,
+    /// Chooses the replica that serves each read, instead of the `read_from` strategy.
+    pub replica_selector: Option<Arc<dyn ReplicaSelector>>,
     pub client_circuit_breaker: Option<ClientCircuitBreakerConfig>,
+    pub hot_key_tracking: Option<HotKeyTrackingConfig>,
+    /// Maximum bytes held by in-flight requests and undelivered responses. `None` is unlimited.
+    pub memory_budget_bytes: Option<u64>,
+    /// How long GET commands are collected before they're sent together as MGETs. `None` disables
+    /// GET batching.
+    pub get_batching_window: Option<Duration>,
+    pub health_check: Option<HealthCheckConfig>,
+    /// Bounds the push messages of each subscription waiting to be delivered. `None` is unbounded. Only the socket
+    /// listener, which delivers the push messages of its clients, supports it - `Client::new` rejects
+    /// it, as the caller owns the client's push channel.
+    pub push_queue: Option<PushQueueConfig>,
+    /// Where the client runs, in the bindings that run each client on a runtime of its own.
+    pub runtime_mode: RuntimeMode,
+    pub dns: DnsConfig,
+    pub interceptors: Vec<Arc<dyn CommandInterceptor>>,
 }
 
-/// Default connection timeout used when not specified in the request.
+/// Default connection timeout used when not specified in the request, unless overridden by
+/// [`GlideRuntimeConfig`](crate::runtime_config::GlideRuntimeConfig).
 /// Note: If you change this value, make sure to change the documentation in *all* wrappers.
 pub const DEFAULT_CONNECTION_TIMEOUT: Duration = Duration::from_millis(2000);
 
@@ -61,7 +97,9 @@ impl ConnectionRequest {
     pub fn get_connection_timeout(&self) -> Duration {
         self.connection_timeout
             .map(|val| Duration::from_millis(val as u64))
-            .unwrap_or(DEFAULT_CONNECTION_TIMEOUT)
+            .unwrap_or_else(|| {
+                crate::runtime_config::GlideRuntimeConfig::get().default_connection_timeout
+            })
     }
 }
 
@@ -76,6 +114,39 @@ pub struct ClientCircuitBreakerConfig {
     pub consecutive_successes: u32,
 }
 
+/// Configuration for hot-key detection. Zero values use the defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HotKeyTrackingConfig {
+    pub sample_rate: u32,
+    pub qps_threshold: u32,
+}
+
+/// Configuration for the per-node health checks. Zero values use the defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HealthCheckConfig {
+    pub interval_ms: u32,
+    pub timeout_ms: u32,
+    pub failure_threshold: u32,
+}
+
+/// How the host names of nodes are resolved.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DnsConfig {
+    pub resolver: DnsResolverType,
+    /// Name servers queried by the async resolver, as `ip` or `ip:port`. Empty uses the system
+    /// configuration.
+    pub name_servers: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub enum DnsResolverType {
+    /// Non-blocking resolver, configured from the system's resolver configuration and hosts file.
+    #[default]
+    Async,
+    /// The operating system's resolver, called on a blocking thread.
+    System,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct ClientSideCache {
     pub cache_id: String,
@@ -205,6 +276,29 @@ pub(crate) fn none_if_zero(value: u32) -> Option<u32> {
     if value == 0 { None } else { Some(value) }
 }
 
+/// The time a full push queue waits for room when no block timeout is configured.
+#[cfg(feature = "proto")]
+const DEFAULT_PUSH_BLOCK_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[cfg(feature = "proto")]
+pub(crate) fn push_queue_config(config: &protobuf::PushQueueConfig) -> PushQueueConfig {
+    let overflow_policy = match config.overflow_policy.enum_value() {
+        Ok(protobuf::PushOverflowPolicy::DropNewest) => OverflowPolicy::DropNewest,
+        Ok(protobuf::PushOverflowPolicy::Disconnect) => OverflowPolicy::Disconnect,
+        Ok(protobuf::PushOverflowPolicy::BlockWithTimeout) => OverflowPolicy::Block(
+            none_if_zero(config.block_timeout_ms).map_or(DEFAULT_PUSH_BLOCK_TIMEOUT, |ms| {
+                Duration::from_millis(ms.into())
+            }),
+        ),
+        Ok(protobuf::PushOverflowPolicy::DropOldest) | Err(_) => OverflowPolicy::DropOldest,
+    };
+    PushQueueConfig {
+        capacity: none_if_zero(config.capacity)
+            .map_or(DEFAULT_PUSH_QUEUE_CAPACITY, |capacity| capacity as usize),
+        overflow_policy,
+    }
+}
+
 #[cfg(feature = "proto")]
 impl From<protobuf::ConnectionRequest> for ConnectionRequest {
     fn from(value: protobuf::ConnectionRequest) -> Self {
@@ -355,6 +449,15 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
         let inflight_requests_limit = none_if_zero(value.inflight_requests_limit);
         let lazy_connect = value.lazy_connect;
         let refresh_topology_from_initial_nodes = value.refresh_topology_from_initial_nodes;
+        let topology_from_cluster_shards = value.topology_from_cluster_shards;
+        let topology_change_events = value.topology_change_events;
+        let strict_response_validation = value.strict_response_validation;
+        let end_to_end_deadline = value.end_to_end_deadline;
+        let connection_concurrency_limit =
+            value.connection_concurrency_limit.and_then(none_if_zero);
+        let experimental_features = ExperimentalFeatures::from_names(
+            value.experimental_features.iter().map(|name| &**name),
+        );
         let root_certs = value
             .root_certs
             .into_iter()
@@ -419,6 +522,7 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
         let pubsub_reconciliation_interval_ms =
             value.pubsub_reconciliation_interval_ms.filter(|&v| v != 0);
         let read_only = value.read_only.unwrap_or(false);
+        let protocol_fallback = value.protocol_fallback.unwrap_or(false);
 
         let node_discovery_mode = value
             .node_discovery_mode
@@ -438,6 +542,7 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             authentication_info,
             database_id,
             protocol,
+            protocol_fallback,
             tls_mode,
             addresses,
             cluster_mode_enabled,
@@ -449,6 +554,12 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             inflight_requests_limit,
             lazy_connect,
             refresh_topology_from_initial_nodes,
+            topology_from_cluster_shards,
+            topology_change_events,
+            strict_response_validation,
+            end_to_end_deadline,
+            connection_concurrency_limit,
+            experimental_features,
             root_certs,
             client_side_cache,
             client_cert,
@@ -458,8 +569,11 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             pubsub_reconciliation_interval_ms,
             read_only,
             node_discovery_mode,
-            // Address resolver is not set from protobuf - it's set programmatically
+            // The address resolver, replica selector and interceptors are not set from protobuf -
+            // they're set programmatically
             address_resolver: None,
+            replica_selector: None,
+            interceptors: Vec::new(),
             client_circuit_breaker: value.client_circuit_breaker.into_option().map(|cb| {
                 ClientCircuitBreakerConfig {
                     window_size_ms: cb.window_size_ms,
@@ -470,6 +584,48 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
                     consecutive_successes: cb.consecutive_successes,
                 }
             }),
+            memory_budget_bytes: value.memory_budget_bytes.filter(|bytes| *bytes > 0),
+            get_batching_window: value
+                .get_batching_window_us
+                .filter(|window| *window > 0)
+                .map(|window| Duration::from_micros(window.into())),
+            hot_key_tracking: value.hot_key_tracking.into_option().map(|config| {
+                HotKeyTrackingConfig {
+                    sample_rate: config.sample_rate,
+                    qps_threshold: config.qps_threshold,
+                }
+            }),
+            push_queue: value.push_queue.as_ref().map(push_queue_config),
+            runtime_mode: match value.runtime_mode.enum_value() {
+                Ok(protobuf::RuntimeMode::Shared) => RuntimeMode::Shared,
+                Ok(protobuf::RuntimeMode::ThreadPerCore) => RuntimeMode::ThreadPerCore,
+                Ok(protobuf::RuntimeMode::Dedicated) | Err(_) => RuntimeMode::Dedicated,
+            },
+            health_check: value
+                .health_check
+                .into_option()
+                .map(|config| HealthCheckConfig {
+                    interval_ms: config.interval_ms,
+                    timeout_ms: config.timeout_ms,
+                    failure_threshold: config.failure_threshold,
+                }),
+            dns: value
+                .dns
+                .into_option()
+                .map(|config| DnsConfig {
+                    resolver: match config.resolver.enum_value() {
+                        Ok(protobuf::DnsResolverType::SystemResolver) => DnsResolverType::System,
+                        Ok(protobuf::DnsResolverType::AsyncResolver) | Err(_) => {
+                            DnsResolverType::Async
+                        }
+                    },
+                    name_servers: config
+                        .name_servers
+                        .into_iter()
+                        .map(|name_server| name_server.to_string())
+                        .collect(),
+                })
+                .unwrap_or_default(),
         }
     }
 }
@@ -477,9 +633,13 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
 mod tests {
     mod protobuf_conversion_tests {
         use crate::ConnectionRequest;
+        use crate::client_runtime::RuntimeMode;
         use crate::compression::CompressionBackendType;
         use crate::connection_request as protobuf;
+        use crate::experimental::ExperimentalFeature;
+        use crate::pubsub::push_queue::{OverflowPolicy, PushQueueConfig};
         use ::protobuf::EnumOrUnknown;
+        use std::time::Duration;
 
         #[test]
         fn test_compression_config_conversion_none() {
@@ -573,5 +733,222 @@ mod tests {
             // Should fall back to Zstd for unknown backends
             assert_eq!(config.backend, CompressionBackendType::Zstd);
         }
+
+        #[test]
+        fn test_protocol_fallback_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert!(!request.protocol_fallback);
+
+            proto_request.protocol_fallback = Some(true);
+            let request: ConnectionRequest = proto_request.into();
+            assert!(request.protocol_fallback);
+        }
+
+        #[test]
+        fn test_hot_key_tracking_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.hot_key_tracking, None);
+
+            proto_request.hot_key_tracking = Some(protobuf::HotKeyTrackingConfig {
+                sample_rate: 10,
+                qps_threshold: 500,
+                ..Default::default()
+            })
+            .into();
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(
+                request.hot_key_tracking,
+                Some(crate::client::HotKeyTrackingConfig {
+                    sample_rate: 10,
+                    qps_threshold: 500,
+                })
+            );
+        }
+
+        #[test]
+        fn test_health_check_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.health_check, None);
+
+            proto_request.health_check = Some(protobuf::HealthCheckConfig {
+                interval_ms: 1000,
+                failure_threshold: 2,
+                ..Default::default()
+            })
+            .into();
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(
+                request.health_check,
+                Some(crate::client::HealthCheckConfig {
+                    interval_ms: 1000,
+                    timeout_ms: 0,
+                    failure_threshold: 2,
+                })
+            );
+        }
+
+        #[test]
+        fn test_dns_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.dns, crate::client::DnsConfig::default());
+
+            proto_request.dns = Some(protobuf::DnsConfig {
+                resolver: protobuf::DnsResolverType::SystemResolver.into(),
+                name_servers: vec!["10.0.0.2".into(), "[::1]:5353".into()],
+                ..Default::default()
+            })
+            .into();
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(
+                request.dns,
+                crate::client::DnsConfig {
+                    resolver: crate::client::DnsResolverType::System,
+                    name_servers: vec!["10.0.0.2".to_string(), "[::1]:5353".to_string()],
+                }
+            );
+        }
+
+        #[test]
+        fn test_memory_budget_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.memory_budget_bytes, None);
+
+            proto_request.memory_budget_bytes = Some(0);
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.memory_budget_bytes, None);
+
+            proto_request.memory_budget_bytes = Some(1 << 20);
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(request.memory_budget_bytes, Some(1 << 20));
+        }
+
+        #[test]
+        fn test_connection_concurrency_limit_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.connection_concurrency_limit, None);
+
+            proto_request.connection_concurrency_limit = Some(0);
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.connection_concurrency_limit, None);
+
+            proto_request.connection_concurrency_limit = Some(16);
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(request.connection_concurrency_limit, Some(16));
+        }
+
+        #[test]
+        fn test_push_queue_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.push_queue, None);
+
+            proto_request.push_queue = Some(protobuf::PushQueueConfig::new()).into();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.push_queue, Some(PushQueueConfig::default()));
+
+            proto_request.push_queue = Some(protobuf::PushQueueConfig {
+                capacity: 10,
+                overflow_policy: protobuf::PushOverflowPolicy::BlockWithTimeout.into(),
+                block_timeout_ms: 250,
+                ..Default::default()
+            })
+            .into();
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(
+                request.push_queue,
+                Some(PushQueueConfig {
+                    capacity: 10,
+                    overflow_policy: OverflowPolicy::Block(Duration::from_millis(250)),
+                })
+            );
+        }
+
+        #[test]
+        fn test_runtime_mode_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.runtime_mode, RuntimeMode::Dedicated);
+
+            proto_request.runtime_mode = protobuf::RuntimeMode::ThreadPerCore.into();
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(request.runtime_mode, RuntimeMode::ThreadPerCore);
+        }
+
+        #[test]
+        fn test_get_batching_window_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.get_batching_window, None);
+
+            proto_request.get_batching_window_us = Some(0);
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert_eq!(request.get_batching_window, None);
+
+            proto_request.get_batching_window_us = Some(250);
+            let request: ConnectionRequest = proto_request.into();
+            assert_eq!(
+                request.get_batching_window,
+                Some(std::time::Duration::from_micros(250))
+            );
+        }
+
+        #[test]
+        fn test_topology_options_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert!(!request.topology_from_cluster_shards);
+            assert!(!request.topology_change_events);
+
+            proto_request.topology_from_cluster_shards = true;
+            proto_request.topology_change_events = true;
+            let request: ConnectionRequest = proto_request.into();
+            assert!(request.topology_from_cluster_shards);
+            assert!(request.topology_change_events);
+        }
+
+        #[test]
+        fn test_strict_response_validation_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert!(!request.strict_response_validation);
+
+            proto_request.strict_response_validation = true;
+            let request: ConnectionRequest = proto_request.into();
+            assert!(request.strict_response_validation);
+        }
+
+        #[test]
+        fn test_end_to_end_deadline_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert!(!request.end_to_end_deadline);
+
+            proto_request.end_to_end_deadline = true;
+            let request: ConnectionRequest = proto_request.into();
+            assert!(request.end_to_end_deadline);
+        }
+
+        #[test]
+        fn test_experimental_features_conversion() {
+            let mut proto_request = protobuf::ConnectionRequest::new();
+            let request: ConnectionRequest = proto_request.clone().into();
+            assert!(request.experimental_features.is_empty());
+
+            proto_request.experimental_features =
+                vec!["read_coalescing".into(), "not_a_feature".into()];
+            let request: ConnectionRequest = proto_request.into();
+            assert!(
+                request
+                    .experimental_features
+                    .is_enabled(ExperimentalFeature::ReadCoalescing)
+            );
+            assert_eq!(request.experimental_features.names().count(), 1);
+        }
     }
 }
@@ -1,9 +1,13 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 use redis::{
-    Cmd, ErrorKind, RedisResult, Value, cluster_routing::Routable, from_owned_redis_value,
+    Cmd, ErrorKind, RedisError, RedisResult, Value, cluster_routing::Routable,
+    from_owned_redis_value,
 };
 
+/// Maximum length of the raw response attached to `UnexpectedResponseShape` errors.
+const MAX_RAW_RESPONSE_LENGTH: usize = 512;
+
 #[derive(Clone, Copy)]
 pub(crate) enum ExpectedReturnType<'a> {
     Map {
@@ -1692,6 +1696,162 @@ pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType<'_>>
     }
 }
 
+/// Converts the response like [`convert_to_expected_type`], in strict response validation mode.
+/// Instead of being coerced, responses whose shape doesn't match the expected type - and responses
+/// the conversion fails on - fail with an `UnexpectedResponseShape` error carrying the raw response.
+fn convert_to_expected_type_strict(
+    value: Value,
+    expected: Option<ExpectedReturnType>,
+) -> RedisResult<Value> {
+    let Some(expected_type) = expected else {
+        return Ok(value);
+    };
+    if let Value::ServerError(_) = value {
+        return Ok(value);
+    }
+    if !has_expected_shape(&value, expected_type) {
+        return Err(unexpected_shape_error(
+            format!("expected {}", expected_type_name(expected_type)),
+            &value,
+        ));
+    }
+    // The conversion consumes the response, so it's copied in case the conversion fails.
+    let raw = value.clone();
+    convert_to_expected_type(value, expected).map_err(|err| {
+        if err.kind() == ErrorKind::TypeError {
+            unexpected_shape_error(err.to_string(), &raw)
+        } else {
+            err
+        }
+    })
+}
+
+/// Converts the response with [`convert_to_expected_type_strict`] if `strict` is set, and with
+/// [`convert_to_expected_type`] otherwise.
+pub(crate) fn convert_response(
+    value: Value,
+    expected: Option<ExpectedReturnType>,
+    strict: bool,
+) -> RedisResult<Value> {
+    if strict {
+        convert_to_expected_type_strict(value, expected)
+    } else {
+        convert_to_expected_type(value, expected)
+    }
+}
+
+fn unexpected_shape_error(reason: String, raw: &Value) -> RedisError {
+    let mut raw = format!("{raw:?}");
+    if raw.len() > MAX_RAW_RESPONSE_LENGTH {
+        let mut end = MAX_RAW_RESPONSE_LENGTH;
+        while !raw.is_char_boundary(end) {
+            end -= 1;
+        }
+        raw.truncate(end);
+        raw.push_str("...");
+    }
+    (
+        ErrorKind::UnexpectedResponseShape,
+        "Response doesn't match the expected type",
+        format!("{reason} (response was {raw})"),
+    )
+        .into()
+}
+
+fn expected_type_name(expected: ExpectedReturnType) -> &'static str {
+    match expected {
+        ExpectedReturnType::Map { .. } => "a map",
+        ExpectedReturnType::SingleOrMultiNode(..) => "a single node or multi-node response",
+        ExpectedReturnType::MapOfStringToDouble => "a map of strings to doubles",
+        ExpectedReturnType::Double => "a double",
+        ExpectedReturnType::DoubleOrNull => "a double or null",
+        ExpectedReturnType::Boolean => "a boolean",
+        ExpectedReturnType::BulkString | ExpectedReturnType::SimpleString => "a string",
+        ExpectedReturnType::Set => "a set",
+        ExpectedReturnType::ArrayOfStrings => "an array of strings",
+        ExpectedReturnType::ArrayOfBools => "an array of booleans",
+        ExpectedReturnType::ArrayOfDoubleOrNull => "an array of doubles or nulls",
+        ExpectedReturnType::ArrayOfArraysOfDoubleOrNull => "an array of arrays of doubles or nulls",
+        _ => "the command's response type",
+    }
+}
+
+fn is_string(value: &Value) -> bool {
+    matches!(
+        value,
+        Value::BulkString(_) | Value::SimpleString(_) | Value::VerbatimString { .. }
+    )
+}
+
+fn is_double(value: &Value) -> bool {
+    match value {
+        Value::Double(_) => true,
+        Value::BulkString(bytes) => {
+            std::str::from_utf8(bytes).is_ok_and(|s| s.parse::<f64>().is_ok())
+        }
+        Value::SimpleString(s) => s.parse::<f64>().is_ok(),
+        _ => false,
+    }
+}
+
+/// Returns whether the shape of the response is one the server replies with for the expected type.
+/// Types whose conversion already rejects unexpected shapes aren't checked.
+fn has_expected_shape(value: &Value, expected: ExpectedReturnType) -> bool {
+    let is_shape = |value: &Value, expected: &Option<ExpectedReturnType>| {
+        expected.is_none_or(|expected| has_expected_shape(value, expected))
+    };
+    match expected {
+        ExpectedReturnType::Map {
+            key_type,
+            value_type,
+        } => match value {
+            Value::Nil | Value::Array(_) => true,
+            Value::Map(map) => map
+                .iter()
+                .all(|(key, value)| is_shape(key, key_type) && is_shape(value, value_type)),
+            _ => false,
+        },
+        ExpectedReturnType::SingleOrMultiNode(inner, _) => {
+            matches!(value, Value::Map(_)) || is_shape(value, inner)
+        }
+        ExpectedReturnType::MapOfStringToDouble => match value {
+            Value::Nil | Value::Array(_) => true,
+            Value::Map(map) => map
+                .iter()
+                .all(|(key, value)| is_string(key) && is_double(value)),
+            _ => false,
+        },
+        ExpectedReturnType::Double => is_double(value),
+        ExpectedReturnType::DoubleOrNull => matches!(value, Value::Nil) || is_double(value),
+        ExpectedReturnType::Boolean => {
+            matches!(value, Value::Boolean(_) | Value::Int(0) | Value::Int(1))
+        }
+        ExpectedReturnType::BulkString | ExpectedReturnType::SimpleString => is_string(value),
+        ExpectedReturnType::Set => matches!(value, Value::Nil | Value::Set(_) | Value::Array(_)),
+        ExpectedReturnType::ArrayOfStrings => {
+            matches!(value, Value::Array(array) if array.iter().all(is_string))
+        }
+        ExpectedReturnType::ArrayOfBools => matches!(
+            value,
+            Value::Array(array)
+                if array.iter().all(|element| has_expected_shape(element, ExpectedReturnType::Boolean))
+        ),
+        ExpectedReturnType::ArrayOfDoubleOrNull => matches!(
+            value,
+            Value::Array(array)
+                if array.iter().all(|element| has_expected_shape(element, ExpectedReturnType::DoubleOrNull))
+        ),
+        ExpectedReturnType::ArrayOfArraysOfDoubleOrNull => matches!(
+            value,
+            Value::Array(array) if array.iter().all(|element| {
+                matches!(element, Value::Nil)
+                    || has_expected_shape(element, ExpectedReturnType::ArrayOfDoubleOrNull)
+            })
+        ),
+        _ => true,
+    }
+}
+
 /// Gets the enum variant as a string for the `value` given.
 pub(crate) fn get_value_type<'a>(value: &Value) -> &'a str {
     match value {
@@ -1717,6 +1877,83 @@ pub(crate) fn get_value_type<'a>(value: &Value) -> &'a str {
 mod tests {
     use super::*;
 
+    #[test]
+    fn strict_conversion_accepts_expected_shapes() {
+        assert_eq!(
+            convert_to_expected_type_strict(Value::Int(1), Some(ExpectedReturnType::Boolean))
+                .unwrap(),
+            Value::Boolean(true)
+        );
+        assert_eq!(
+            convert_to_expected_type_strict(
+                Value::BulkString(b"1.5".to_vec()),
+                Some(ExpectedReturnType::Double)
+            )
+            .unwrap(),
+            Value::Double(1.5)
+        );
+        assert_eq!(
+            convert_to_expected_type_strict(
+                Value::Array(vec![Value::Int(0), Value::Boolean(true)]),
+                Some(ExpectedReturnType::ArrayOfBools)
+            )
+            .unwrap(),
+            Value::Array(vec![Value::Boolean(false), Value::Boolean(true)])
+        );
+        assert_eq!(
+            convert_to_expected_type_strict(Value::Int(5), None).unwrap(),
+            Value::Int(5)
+        );
+    }
+
+    #[test]
+    fn strict_conversion_rejects_unexpected_shapes() {
+        let err = convert_to_expected_type_strict(
+            Value::BulkString(b"yes".to_vec()),
+            Some(ExpectedReturnType::Boolean),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedResponseShape);
+        assert!(err.to_string().contains("expected a boolean"));
+        assert!(err.to_string().contains("yes"));
+
+        // Coerced to a double in the default mode.
+        assert!(convert_to_expected_type(Value::Int(3), Some(ExpectedReturnType::Double)).is_ok());
+        let err = convert_to_expected_type_strict(Value::Int(3), Some(ExpectedReturnType::Double))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedResponseShape);
+
+        let err = convert_to_expected_type_strict(
+            Value::Map(vec![(
+                Value::BulkString(b"member".to_vec()),
+                Value::BulkString(b"not a score".to_vec()),
+            )]),
+            Some(ExpectedReturnType::MapOfStringToDouble),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedResponseShape);
+
+        // Conversion failures are reported with the raw response too.
+        let err = convert_to_expected_type_strict(
+            Value::Array(vec![Value::Int(1)]),
+            Some(ExpectedReturnType::ZRankReturnType),
+        )
+        .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedResponseShape);
+        assert!(err.to_string().contains("int(1)"));
+    }
+
+    #[test]
+    fn strict_conversion_truncates_the_raw_response() {
+        let err = convert_to_expected_type_strict(
+            Value::Array(vec![Value::Int(7); 1000]),
+            Some(ExpectedReturnType::Boolean),
+        )
+        .unwrap_err();
+        assert!(err.to_string().len() < 2 * MAX_RAW_RESPONSE_LENGTH);
+        assert!(err.to_string().contains("..."));
+    }
+
     #[test]
     fn xinfo_stream_expected_return_type() {
         assert!(matches!(
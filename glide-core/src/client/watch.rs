@@ -0,0 +1,214 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Optimistic transactions: `WATCH` the keys, read them, and `MULTI`/`EXEC` the writes built from
+//! their values, retrying when another client changed a watched key in between.
+//!
+//! `WATCH` applies to the connection it's sent on, and any `EXEC`, `DISCARD` or `UNWATCH` sent on
+//! that connection clears it. The client's connections are shared by all its callers, so
+//! [`Client::watch_transaction`] opens a dedicated connection to the primary that owns the keys,
+//! and runs every attempt on it. The watches are cleared when the connection is closed, once the
+//! call returns. In cluster mode, all the keys must hash to the same slot.
+//!
+//! The connection uses the client's connection timeout, `TCP_NODELAY` and DNS settings, and its
+//! current IAM token as the password. The call holds one of the client's in-flight request slots
+//! until it returns. Clients with compression enabled are rejected, as the values read through
+//! [`WatchedConnection::send_command`] and written by the pipeline bypass the compression.
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use redis::aio::{ConnectionLike, MultiplexedConnection};
+use redis::{Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value};
+
+use super::{Client, ClientWrapper, run_with_timeout};
+use crate::cluster_slots::keys_share_slot;
+
+/// How a transaction aborted by a changed watched key is retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchRetryPolicy {
+    /// How many times the transaction is retried after the first attempt.
+    pub max_retries: u32,
+    /// The delay before the first retry. Each following retry waits twice as long.
+    pub base_backoff: Duration,
+    /// The longest delay between two attempts.
+    pub max_backoff: Duration,
+}
+
+impl Default for WatchRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            base_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl WatchRetryPolicy {
+    /// Returns the delay before retry number `retry`, counted from 0.
+    fn backoff(&self, retry: u32) -> Duration {
+        self.base_backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+/// The dedicated connection a transaction's keys are watched on.
+pub struct WatchedConnection {
+    connection: MultiplexedConnection,
+}
+
+impl WatchedConnection {
+    /// Sends `cmd` on the connection, such as a `GET` of a watched key. `EXEC`, `DISCARD` and
+    /// `UNWATCH` clear the watches, and make the transaction unprotected.
+    pub async fn send_command(&mut self, cmd: &Cmd) -> RedisResult<Value> {
+        self.connection.req_packed_command(cmd).await
+    }
+}
+
+/// Returns the slot all `keys` hash to.
+fn same_slot(keys: &[Vec<u8>]) -> RedisResult<u16> {
+    keys_share_slot(keys)?.ok_or_else(no_keys_error)
+}
+
+fn no_keys_error() -> RedisError {
+    RedisError::from((ErrorKind::ClientError, "No keys to watch"))
+}
+
+impl Client {
+    /// Runs an optimistic transaction on `keys`. Each attempt `WATCH`es the keys, calls `build`
+    /// with the connection they're watched on - to read their current values - and executes the
+    /// returned pipeline as a `MULTI`/`EXEC` transaction. When a watched key changed before the
+    /// `EXEC`, the transaction is aborted, and retried after a backoff as `policy` allows.
+    ///
+    /// Returns the `EXEC` response, which is `Nil` if the last attempt was aborted too. An error
+    /// returned by `build` stops the retries, and is returned as is.
+    pub async fn watch_transaction<F>(
+        &mut self,
+        keys: &[Vec<u8>],
+        policy: WatchRetryPolicy,
+        mut build: F,
+    ) -> RedisResult<Value>
+    where
+        F: for<'a> FnMut(&'a mut WatchedConnection) -> BoxFuture<'a, RedisResult<Pipeline>>,
+    {
+        if self.is_compression_enabled() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Optimistic transactions aren't supported with compression",
+            )));
+        }
+        let _inflight = self.reserve_inflight_request().ok_or_else(|| {
+            RedisError::from((ErrorKind::ClientError, "Reached maximum inflight requests"))
+        })?;
+        let mut connection = self.open_watched_connection(keys).await?;
+        let mut watch = redis::cmd("WATCH");
+        for key in keys {
+            watch.arg(key);
+        }
+        let mut retry = 0;
+        loop {
+            connection.send_command(&watch).await?;
+            let mut pipeline = build(&mut connection).await?;
+            pipeline.atomic();
+            let response: Value = run_with_timeout(
+                Some(self.request_timeout),
+                pipeline.query_async(&mut connection.connection),
+            )
+            .await?;
+            if response != Value::Nil || retry >= policy.max_retries {
+                return Ok(response);
+            }
+            tokio::time::sleep(policy.backoff(retry)).await;
+            retry += 1;
+        }
+    }
+
+    /// Opens a new connection to the primary that owns `keys`, with the client's connection options.
+    async fn open_watched_connection(
+        &mut self,
+        keys: &[Vec<u8>],
+    ) -> RedisResult<WatchedConnection> {
+        let mut connection_info = match self.get_or_initialize_client().await? {
+            ClientWrapper::Standalone(client) => {
+                if keys.is_empty() {
+                    return Err(no_keys_error());
+                }
+                client.primary_connection_info().ok_or_else(|| {
+                    RedisError::from((
+                        ErrorKind::ClientError,
+                        "Transactions can't be watched in read-only mode",
+                    ))
+                })?
+            }
+            ClientWrapper::Cluster { mut client } => {
+                client.get_primary_connection_info(same_slot(keys)?).await?
+            }
+            ClientWrapper::Lazy(_) => {
+                return Err(RedisError::from((
+                    ErrorKind::ClientError,
+                    "Client is not initialized",
+                )));
+            }
+        };
+        if let Some(iam_manager) = &self.iam_token_manager {
+            let token = iam_manager.get_token().await;
+            if token.is_empty() {
+                return Err(RedisError::from((
+                    ErrorKind::ClientError,
+                    "IAM token not available",
+                )));
+            }
+            connection_info.redis.password = Some(token);
+        }
+        let options = self.watch_connection_options.clone();
+        let connection_timeout = options
+            .connection_timeout
+            .unwrap_or(super::DEFAULT_CONNECTION_TIMEOUT);
+        let connection = redis::Client::open(connection_info)?
+            .get_multiplexed_async_connection_with_timeouts(
+                self.request_timeout,
+                connection_timeout,
+                options,
+            )
+            .await?;
+        Ok(WatchedConnection { connection })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::cluster_topology::get_slot;
+
+    #[test]
+    fn test_backoff_doubles_up_to_the_limit() {
+        let policy = WatchRetryPolicy {
+            max_retries: 10,
+            base_backoff: Duration::from_millis(10),
+            max_backoff: Duration::from_millis(50),
+        };
+        let backoffs: Vec<_> = (0..5).map(|retry| policy.backoff(retry)).collect();
+        assert_eq!(
+            backoffs,
+            [10, 20, 40, 50, 50].map(Duration::from_millis).to_vec()
+        );
+        assert_eq!(policy.backoff(u32::MAX), policy.max_backoff);
+    }
+
+    #[test]
+    fn test_watched_keys_must_share_a_slot() {
+        let keys = |keys: &[&str]| -> Vec<Vec<u8>> {
+            keys.iter().map(|key| key.as_bytes().to_vec()).collect()
+        };
+        assert_eq!(
+            same_slot(&keys(&["{user1}:balance", "{user1}:history"])).unwrap(),
+            get_slot(b"user1")
+        );
+        assert_eq!(
+            same_slot(&keys(&["a", "b"])).unwrap_err().kind(),
+            ErrorKind::CrossSlot
+        );
+        assert_eq!(same_slot(&[]).unwrap_err().kind(), ErrorKind::ClientError);
+    }
+}
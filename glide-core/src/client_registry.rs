@@ -0,0 +1,289 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Process-level registry of live clients.
+//!
+//! Clients are registered by their owner - for example, the socket listener registers every
+//! client it creates - and stay registered until the returned [`ClientRegistration`] is dropped.
+//! The registry allows enumerating the clients of the process with their configuration and
+//! statistics, and closing or reconfiguring many clients at once, which is useful for bindings
+//! that close all clients on shutdown, and for diagnostics in processes with many clients.
+//!
+//! Closing a client removes it from the registry and notifies its owner through
+//! [`ClientRegistration::closed`]. The owner is expected to drop the client, which closes its
+//! connections once its in-flight requests complete.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+use futures::future::join_all;
+use once_cell::sync::Lazy;
+use redis::{RedisResult, Value};
+use tokio::sync::watch;
+
+use crate::client::{Client, ConnectionRequest, sanitized_request_string};
+
+struct RegisteredClient {
+    client: Client,
+    summary: ClientSummary,
+    close_sender: watch::Sender<bool>,
+}
+
+static REGISTRY: Lazy<Mutex<HashMap<u64, RegisteredClient>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The configuration of a registered client, captured when it was registered.
+#[derive(Clone, Debug)]
+pub struct ClientSummary {
+    /// The client's id in the registry. Ids are unique within the process.
+    pub id: u64,
+    pub addresses: Vec<String>,
+    pub cluster_mode: bool,
+    pub database_id: i64,
+    pub client_name: Option<String>,
+    pub registered_at: SystemTime,
+    /// The client's configuration, without credentials.
+    pub configuration: String,
+}
+
+/// A registered client's summary, with its current statistics.
+#[derive(Clone, Debug)]
+pub struct ClientInfo {
+    pub summary: ClientSummary,
+    pub inflight_requests: isize,
+    /// The bytes accounted against the client's memory budget, and the budget's limit.
+    pub memory_budget_usage: Option<(u64, u64)>,
+    pub circuit_breaker_healthy: bool,
+}
+
+impl ClientInfo {
+    /// Returns the info as a map, the way it's returned over the socket.
+    pub fn into_value(self) -> Value {
+        let entry = |key: &str, value: Value| (Value::SimpleString(key.to_string()), value);
+        let text = |text: String| Value::BulkString(text.into_bytes());
+        let summary = self.summary;
+        let registered_at_ms = summary
+            .registered_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as i64);
+        let mut entries = vec![
+            entry("id", Value::Int(summary.id as i64)),
+            entry(
+                "addresses",
+                Value::Array(summary.addresses.into_iter().map(text).collect()),
+            ),
+            entry("cluster_mode", Value::Boolean(summary.cluster_mode)),
+            entry("database_id", Value::Int(summary.database_id)),
+            entry("client_name", summary.client_name.map_or(Value::Nil, text)),
+            entry("registered_at_ms", Value::Int(registered_at_ms)),
+            entry("configuration", text(summary.configuration)),
+            entry(
+                "inflight_requests",
+                Value::Int(self.inflight_requests as i64),
+            ),
+            entry(
+                "circuit_breaker_healthy",
+                Value::Boolean(self.circuit_breaker_healthy),
+            ),
+        ];
+        if let Some((used, limit)) = self.memory_budget_usage {
+            entries.push(entry("memory_budget_used", Value::Int(used as i64)));
+            entries.push(entry("memory_budget_limit", Value::Int(limit as i64)));
+        }
+        Value::Map(entries)
+    }
+}
+
+/// Keeps a client registered until dropped.
+pub struct ClientRegistration {
+    id: u64,
+    close_receiver: watch::Receiver<bool>,
+}
+
+impl ClientRegistration {
+    /// The client's id in the registry.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Completes once the client was closed through the registry.
+    pub fn closed(&self) -> impl Future<Output = ()> + Send + 'static {
+        let mut receiver = self.close_receiver.clone();
+        async move {
+            // The sender is dropped without closing the client only when the registration is dropped.
+            let _ = receiver.wait_for(|closed| *closed).await;
+        }
+    }
+}
+
+impl Drop for ClientRegistration {
+    fn drop(&mut self) {
+        // The client is dropped after the lock is released.
+        let _registered = lock_registry().remove(&self.id);
+    }
+}
+
+fn lock_registry() -> std::sync::MutexGuard<'static, HashMap<u64, RegisteredClient>> {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Registers a client that was created with the given request.
+pub fn register(client: Client, request: &ConnectionRequest) -> ClientRegistration {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let summary = ClientSummary {
+        id,
+        addresses: request
+            .addresses
+            .iter()
+            .map(|address| format!("{}:{}", address.host, address.port))
+            .collect(),
+        cluster_mode: request.cluster_mode_enabled,
+        database_id: request.database_id,
+        client_name: request.client_name.clone(),
+        registered_at: SystemTime::now(),
+        configuration: sanitized_request_string(request),
+    };
+    let (close_sender, close_receiver) = watch::channel(false);
+    lock_registry().insert(
+        id,
+        RegisteredClient {
+            client,
+            summary,
+            close_sender,
+        },
+    );
+    ClientRegistration { id, close_receiver }
+}
+
+/// Returns the registered clients, ordered by id.
+pub fn clients() -> Vec<ClientInfo> {
+    let mut clients: Vec<ClientInfo> = lock_registry()
+        .values()
+        .map(|registered| ClientInfo {
+            summary: registered.summary.clone(),
+            inflight_requests: registered.client.inflight_request_count(),
+            memory_budget_usage: registered.client.memory_budget_usage(),
+            circuit_breaker_healthy: registered.client.is_circuit_breaker_healthy(),
+        })
+        .collect();
+    clients.sort_by_key(|info| info.summary.id);
+    clients
+}
+
+/// Closes the clients with the given ids, or all clients if `ids` is empty.
+/// Returns the number of closed clients. Unknown ids are ignored.
+pub fn close_clients(ids: &[u64]) -> usize {
+    let closed: Vec<RegisteredClient> = {
+        let mut registry = lock_registry();
+        if ids.is_empty() {
+            registry.drain().map(|(_, registered)| registered).collect()
+        } else {
+            ids.iter().filter_map(|id| registry.remove(id)).collect()
+        }
+    };
+    for registered in &closed {
+        registered.close_sender.send_replace(true);
+    }
+    closed.len()
+}
+
+/// Updates the password of the clients with the given ids, or of all clients if `ids` is empty.
+/// Returns the result of each client by id. See [`Client::update_connection_password`].
+pub async fn update_connection_passwords(
+    ids: &[u64],
+    password: Option<String>,
+    immediate_auth: bool,
+) -> Vec<(u64, RedisResult<Value>)> {
+    let clients: Vec<(u64, Client)> = {
+        let registry = lock_registry();
+        if ids.is_empty() {
+            registry
+                .iter()
+                .map(|(id, registered)| (*id, registered.client.clone()))
+                .collect()
+        } else {
+            ids.iter()
+                .filter_map(|id| {
+                    registry
+                        .get(id)
+                        .map(|registered| (*id, registered.client.clone()))
+                })
+                .collect()
+        }
+    };
+    join_all(clients.into_iter().map(|(id, mut client)| {
+        let password = password.clone();
+        async move {
+            (
+                id,
+                client
+                    .update_connection_password(password, immediate_auth)
+                    .await,
+            )
+        }
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::NodeAddress;
+
+    async fn lazy_client(request: &ConnectionRequest) -> Client {
+        Client::new(request.clone(), None).await.unwrap()
+    }
+
+    fn request() -> ConnectionRequest {
+        ConnectionRequest {
+            addresses: vec![NodeAddress {
+                host: "localhost".to_string(),
+                port: 6379,
+            }],
+            lazy_connect: true,
+            client_name: Some("registry-test".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn registered_ids() -> Vec<u64> {
+        clients().into_iter().map(|info| info.summary.id).collect()
+    }
+
+    #[tokio::test]
+    async fn test_registration_lifetime() {
+        let request = request();
+        let registration = register(lazy_client(&request).await, &request);
+        let id = registration.id();
+        let info = clients()
+            .into_iter()
+            .find(|info| info.summary.id == id)
+            .unwrap();
+        assert_eq!(info.summary.addresses, vec!["localhost:6379"]);
+        assert_eq!(info.summary.client_name.as_deref(), Some("registry-test"));
+        assert_eq!(info.inflight_requests, 0);
+
+        drop(registration);
+        assert!(!registered_ids().contains(&id));
+    }
+
+    #[tokio::test]
+    async fn test_close_clients_notifies_owner() {
+        let request = request();
+        let closed = register(lazy_client(&request).await, &request);
+        let kept = register(lazy_client(&request).await, &request);
+
+        assert_eq!(close_clients(&[closed.id(), u64::MAX]), 1);
+        tokio::time::timeout(std::time::Duration::from_secs(1), closed.closed())
+            .await
+            .unwrap();
+        let ids = registered_ids();
+        assert!(!ids.contains(&closed.id()));
+        assert!(ids.contains(&kept.id()));
+    }
+}
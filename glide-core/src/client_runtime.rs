@@ -0,0 +1,324 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! The runtimes clients run on.
+//!
+//! A binding that runs each client on a runtime of its own starts threads for every client, which
+//! adds up - in threads and context switches - for processes that create hundreds of clients. The
+//! [`RuntimeMode`] of a connection request selects where the client runs instead:
+//!
+//! - [`RuntimeMode::Dedicated`] leaves it to the binding, typically a runtime per client.
+//! - [`RuntimeMode::Shared`] runs the client on a multi-threaded runtime shared by all the clients
+//!   that select it, with `GlideRuntimeConfig::shared_runtime_worker_threads` workers.
+//! - [`RuntimeMode::ThreadPerCore`] pins the client to a shard - one of
+//!   `GlideRuntimeConfig::runtime_shards` single-threaded runtimes, each on a thread of its own,
+//!   bound to a core on Linux. All the tasks of the client run on the same thread, so they're never
+//!   moved between threads. A new client is pinned to the shard with the fewest clients.
+//!
+//! The shared runtime and the shards are started on first use, and run until the process exits.
+
+use std::future::Future;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+use logger_core::log_warn;
+use once_cell::sync::OnceCell;
+use tokio::runtime::{Builder, Handle, Runtime};
+use tokio::task::JoinHandle;
+
+use crate::runtime_config::GlideRuntimeConfig;
+
+/// Where a client runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RuntimeMode {
+    /// On the runtime the binding provides.
+    #[default]
+    Dedicated,
+    /// On the multi-threaded runtime shared by the clients of the process.
+    Shared,
+    /// On one of the single-threaded runtime shards, one per core.
+    ThreadPerCore,
+}
+
+static SHARED_RUNTIME: OnceCell<Runtime> = OnceCell::new();
+static SHARDS: OnceCell<Vec<Shard>> = OnceCell::new();
+
+struct Shard {
+    handle: Handle,
+    /// The number of clients pinned to the shard.
+    clients: AtomicUsize,
+}
+
+/// The runtime a client runs on. A client pinned to a shard is unpinned when it's dropped.
+pub struct ClientRuntime {
+    handle: Handle,
+    /// The runtime of a dedicated client, shut down when it's dropped.
+    owned: Option<Runtime>,
+    shard: Option<usize>,
+}
+
+impl ClientRuntime {
+    /// Runs a client on `runtime`, which it owns.
+    pub fn dedicated(runtime: Runtime) -> Self {
+        Self {
+            handle: runtime.handle().clone(),
+            owned: Some(runtime),
+            shard: None,
+        }
+    }
+
+    /// Returns the runtime of a client created with `mode`, starting it if needed. Returns `None`
+    /// for [`RuntimeMode::Dedicated`], where the binding provides the runtime.
+    pub fn for_mode(mode: RuntimeMode) -> Result<Option<Self>, String> {
+        match mode {
+            RuntimeMode::Dedicated => Ok(None),
+            RuntimeMode::Shared => Ok(Some(Self {
+                handle: shared_runtime()?.handle().clone(),
+                owned: None,
+                shard: None,
+            })),
+            RuntimeMode::ThreadPerCore => {
+                let shards = SHARDS.get_or_try_init(start_shards)?;
+                let index = reserve_shard(shards)?;
+                Ok(Some(Self {
+                    handle: shards[index].handle.clone(),
+                    owned: None,
+                    shard: Some(index),
+                }))
+            }
+        }
+    }
+
+    pub fn handle(&self) -> &Handle {
+        &self.handle
+    }
+
+    /// Returns the index of the shard the client is pinned to, in [`RuntimeMode::ThreadPerCore`].
+    pub fn shard(&self) -> Option<usize> {
+        self.shard
+    }
+
+    pub fn spawn<F>(&self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.handle.spawn(future)
+    }
+
+    /// Runs `future` to completion on the calling thread. Must not be called from an async task.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match &self.owned {
+            Some(runtime) => runtime.block_on(future),
+            None => self.handle.block_on(future),
+        }
+    }
+}
+
+impl Drop for ClientRuntime {
+    fn drop(&mut self) {
+        if let (Some(index), Some(shards)) = (self.shard, SHARDS.get()) {
+            shards[index].clients.fetch_sub(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Pins a client to the shard with the fewest clients, and returns its index. The count is only
+/// incremented if it hasn't changed since the shard was picked, so clients created concurrently
+/// don't all pile onto the same shard.
+fn reserve_shard(shards: &[Shard]) -> Result<usize, String> {
+    loop {
+        let (index, clients) = shards
+            .iter()
+            .map(|shard| shard.clients.load(Ordering::Relaxed))
+            .enumerate()
+            .min_by_key(|(_, clients)| *clients)
+            .ok_or_else(|| "No runtime shards".to_string())?;
+        if shards[index]
+            .clients
+            .compare_exchange(clients, clients + 1, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+        {
+            return Ok(index);
+        }
+    }
+}
+
+fn core_count() -> usize {
+    thread::available_parallelism().map_or(1, NonZeroUsize::get)
+}
+
+fn shared_runtime() -> Result<&'static Runtime, String> {
+    SHARED_RUNTIME.get_or_try_init(|| {
+        let worker_threads = GlideRuntimeConfig::get()
+            .shared_runtime_worker_threads
+            .unwrap_or_else(core_count);
+        Builder::new_multi_thread()
+            .worker_threads(worker_threads)
+            .thread_name("glide-shared-runtime")
+            .enable_all()
+            .build()
+            .map_err(|err| format!("Failed to create the shared runtime: {err}"))
+    })
+}
+
+fn start_shards() -> Result<Vec<Shard>, String> {
+    let count = GlideRuntimeConfig::get()
+        .runtime_shards
+        .unwrap_or_else(core_count);
+    (0..count)
+        .map(|index| {
+            Ok(Shard {
+                handle: start_shard(index)?,
+                clients: AtomicUsize::new(0),
+            })
+        })
+        .collect()
+}
+
+/// Starts a single-threaded runtime on a thread of its own, bound to a core, and returns its
+/// handle. The runtime runs until the process exits.
+fn start_shard(index: usize) -> Result<Handle, String> {
+    let (tx, rx) = mpsc::sync_channel(1);
+    thread::Builder::new()
+        .name(format!("glide-shard-{index}"))
+        .spawn(move || {
+            pin_to_core(index);
+            match Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => {
+                    let _ = tx.send(Ok(runtime.handle().clone()));
+                    runtime.block_on(std::future::pending::<()>());
+                }
+                Err(err) => {
+                    let _ = tx.send(Err(format!("Failed to create runtime shard: {err}")));
+                }
+            }
+        })
+        .map_err(|err| format!("Failed to spawn the thread of runtime shard {index}: {err}"))?;
+    rx.recv()
+        .map_err(|err| format!("Failed to start runtime shard {index}: {err}"))?
+}
+
+/// Binds the calling thread to the `index`-th core the process may run on, wrapping around.
+#[cfg(target_os = "linux")]
+fn pin_to_core(index: usize) {
+    // SAFETY: `cpu_set_t` is a plain bitmask, valid when zeroed, and the calls only access the
+    // sets passed to them.
+    unsafe {
+        let set_size = std::mem::size_of::<libc::cpu_set_t>();
+        let mut allowed: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(0, set_size, &mut allowed) != 0 {
+            return;
+        }
+        let cores: Vec<usize> = (0..libc::CPU_SETSIZE as usize)
+            .filter(|core| libc::CPU_ISSET(*core, &allowed))
+            .collect();
+        let Some(core) = cores.get(index % cores.len().max(1)) else {
+            return;
+        };
+        let mut pinned: libc::cpu_set_t = std::mem::zeroed();
+        libc::CPU_SET(*core, &mut pinned);
+        if libc::sched_setaffinity(0, set_size, &pinned) != 0 {
+            log_warn(
+                "client runtime",
+                format!("Failed to bind runtime shard {index} to core {core}"),
+            );
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pin_to_core(_index: usize) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thread_name(runtime: &ClientRuntime) -> String {
+        runtime.block_on(async {
+            tokio::spawn(async { thread::current().name().unwrap_or_default().to_string() })
+                .await
+                .unwrap()
+        })
+    }
+
+    #[test]
+    fn test_dedicated_mode_uses_the_binding_runtime() {
+        assert!(
+            ClientRuntime::for_mode(RuntimeMode::Dedicated)
+                .unwrap()
+                .is_none()
+        );
+        let runtime = ClientRuntime::dedicated(Builder::new_current_thread().build().unwrap());
+        assert_eq!(runtime.block_on(async { 1 }), 1);
+        assert_eq!(runtime.shard(), None);
+    }
+
+    #[test]
+    fn test_shared_mode_runs_on_the_shared_runtime() {
+        let runtime = ClientRuntime::for_mode(RuntimeMode::Shared)
+            .unwrap()
+            .unwrap();
+        assert_eq!(thread_name(&runtime), "glide-shared-runtime");
+        assert_eq!(runtime.shard(), None);
+    }
+
+    #[test]
+    fn test_thread_per_core_spreads_clients_over_the_shards() {
+        let runtimes: Vec<ClientRuntime> = (0..core_count())
+            .map(|_| {
+                ClientRuntime::for_mode(RuntimeMode::ThreadPerCore)
+                    .unwrap()
+                    .unwrap()
+            })
+            .collect();
+        let mut shards: Vec<usize> = runtimes.iter().filter_map(ClientRuntime::shard).collect();
+        shards.sort();
+        assert_eq!(shards, (0..core_count()).collect::<Vec<_>>());
+        for runtime in &runtimes {
+            assert_eq!(
+                thread_name(runtime),
+                format!("glide-shard-{}", runtime.shard().unwrap())
+            );
+        }
+
+        // Dropped clients are unpinned.
+        drop(runtimes);
+        let shards = SHARDS.get().unwrap();
+        assert!(
+            shards
+                .iter()
+                .all(|shard| shard.clients.load(Ordering::Relaxed) == 0)
+        );
+        let runtime = ClientRuntime::for_mode(RuntimeMode::ThreadPerCore)
+            .unwrap()
+            .unwrap();
+        assert_eq!(runtime.shard(), Some(0));
+    }
+
+    #[test]
+    fn test_concurrent_clients_are_spread_evenly_over_the_shards() {
+        let runtime = Builder::new_current_thread().build().unwrap();
+        let shards: Vec<Shard> = (0..4)
+            .map(|_| Shard {
+                handle: runtime.handle().clone(),
+                clients: AtomicUsize::new(0),
+            })
+            .collect();
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        reserve_shard(&shards).unwrap();
+                    }
+                });
+            }
+        });
+        assert!(
+            shards
+                .iter()
+                .all(|shard| shard.clients.load(Ordering::Relaxed) == 200)
+        );
+    }
+}
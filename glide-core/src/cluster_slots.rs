@@ -0,0 +1,510 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Helpers for inspecting how keys are spread over the cluster slots.
+//!
+//! This module provides typed wrappers for `CLUSTER COUNTKEYSINSLOT` and
+//! `CLUSTER GETKEYSINSLOT`, and [`slot_population_report`], which samples the key count of
+//! slots across the cluster and aggregates it per node. The report helps deciding how to
+//! reshard, and finding slots that hold a disproportionate share of the keys.
+//!
+//! It also computes the slots of keys as the cluster does: [`cluster_slot`] hashes the
+//! [hash tag](extract_hash_tag) of a key if it has one, so keys with the same hash tag - such as
+//! the keys built by [`colocated_key`] - share a slot. [`keys_share_slot`] validates the keys of a
+//! multi-key operation before it's sent, and [`transaction_slot`] the keys of a transaction.
+
+use std::collections::HashMap;
+
+use futures::{StreamExt, stream};
+use redis::cluster_topology::{get_hashtag, get_slot};
+use redis::{Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value};
+
+use crate::client::Client;
+use crate::command_metadata::command_keys;
+use crate::errors::unexpected_response;
+
+/// Number of hash slots in a cluster.
+pub const SLOT_COUNT: u16 = 16384;
+
+/// Default number of slots between two sampled slots - 256 slots are sampled.
+pub const DEFAULT_SAMPLE_STEP: u16 = 64;
+
+/// Maximum number of `CLUSTER COUNTKEYSINSLOT` requests sent concurrently by the report.
+const MAX_CONCURRENT_COUNTS: usize = 32;
+
+fn validate_slot(slot: u16) -> RedisResult<()> {
+    if slot >= SLOT_COUNT {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Invalid slot",
+            format!("slot must be lower than {SLOT_COUNT}, got {slot}"),
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the slot of `key`.
+pub fn cluster_slot(key: &[u8]) -> u16 {
+    get_slot(key)
+}
+
+/// Returns the hash tag of `key` - the part between its first `{` and the following `}` - if it has
+/// a non-empty one. Only the hash tag of a key is hashed to find its slot.
+pub fn extract_hash_tag(key: &[u8]) -> Option<&[u8]> {
+    get_hashtag(key)
+}
+
+/// Returns `key` prefixed with `hash_tag` in braces, so that it shares the slot of all the keys with
+/// this hash tag. The hash tag must be non-empty, and can't contain `}`.
+pub fn colocated_key(hash_tag: &[u8], key: &[u8]) -> RedisResult<Vec<u8>> {
+    if hash_tag.is_empty() || hash_tag.contains(&b'}') {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Invalid hash tag",
+            "a hash tag must be non-empty, and can't contain `}`".to_string(),
+        )));
+    }
+    let mut colocated = Vec::with_capacity(hash_tag.len() + key.len() + 2);
+    colocated.push(b'{');
+    colocated.extend_from_slice(hash_tag);
+    colocated.push(b'}');
+    colocated.extend_from_slice(key);
+    Ok(colocated)
+}
+
+/// Returns the slot all `keys` hash to, `None` if there are no keys, or a `CrossSlot` error if they
+/// hash to different slots.
+pub fn keys_share_slot<K: AsRef<[u8]>>(keys: &[K]) -> RedisResult<Option<u16>> {
+    let mut slots = keys.iter().map(|key| get_slot(key.as_ref()));
+    let Some(slot) = slots.next() else {
+        return Ok(None);
+    };
+    if let Some(other) = slots.find(|other| *other != slot) {
+        return Err(RedisError::from((
+            ErrorKind::CrossSlot,
+            "Keys don't hash to the same slot",
+            format!("found keys in slots {slot} and {other}"),
+        )));
+    }
+    Ok(Some(slot))
+}
+
+/// Returns the slot of the keys of the commands of `pipeline`, `None` if it has no known keys, or a
+/// `CrossSlot` error if they hash to different slots - as a transaction's keys must share a slot.
+pub fn transaction_slot(pipeline: &Pipeline) -> RedisResult<Option<u16>> {
+    let keys: Vec<&[u8]> = pipeline
+        .cmd_iter()
+        .flat_map(|cmd| command_keys(cmd))
+        .collect();
+    keys_share_slot(&keys)
+}
+
+/// Builds a `CLUSTER COUNTKEYSINSLOT` command. In cluster mode it's routed to the slot owner.
+pub fn count_keys_in_slot_cmd(slot: u16) -> RedisResult<Cmd> {
+    validate_slot(slot)?;
+    let mut cmd = redis::cmd("CLUSTER");
+    cmd.arg("COUNTKEYSINSLOT").arg(slot);
+    Ok(cmd)
+}
+
+/// Builds a `CLUSTER GETKEYSINSLOT` command. In cluster mode it's routed to the slot owner.
+pub fn get_keys_in_slot_cmd(slot: u16, count: u64) -> RedisResult<Cmd> {
+    validate_slot(slot)?;
+    let mut cmd = redis::cmd("CLUSTER");
+    cmd.arg("GETKEYSINSLOT").arg(slot).arg(count);
+    Ok(cmd)
+}
+
+/// Returns the number of keys in the slot.
+pub async fn count_keys_in_slot(client: &mut Client, slot: u16) -> RedisResult<u64> {
+    let mut cmd = count_keys_in_slot_cmd(slot)?;
+    match client.send_command(&mut cmd, None).await? {
+        Value::Int(count) if count >= 0 => Ok(count as u64),
+        other => Err(unexpected_response(
+            "CLUSTER COUNTKEYSINSLOT response",
+            &other,
+        )),
+    }
+}
+
+/// Returns up to `count` keys from the slot.
+pub async fn get_keys_in_slot(
+    client: &mut Client,
+    slot: u16,
+    count: u64,
+) -> RedisResult<Vec<Vec<u8>>> {
+    let mut cmd = get_keys_in_slot_cmd(slot, count)?;
+    match client.send_command(&mut cmd, None).await? {
+        Value::Array(keys) => keys
+            .into_iter()
+            .map(|key| match key {
+                Value::BulkString(key) => Ok(key),
+                other => Err(unexpected_response("key", &other)),
+            })
+            .collect(),
+        other => Err(unexpected_response(
+            "CLUSTER GETKEYSINSLOT response",
+            &other,
+        )),
+    }
+}
+
+/// A range of slots and the address of the primary that owns it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotRange {
+    pub start: u16,
+    pub end: u16,
+    pub primary: String,
+}
+
+/// Parses a `CLUSTER SLOTS` response into the owned slot ranges.
+pub fn parse_cluster_slots(value: Value) -> RedisResult<Vec<SlotRange>> {
+    let Value::Array(ranges) = value else {
+        return Err(unexpected_response("CLUSTER SLOTS response", &value));
+    };
+    ranges
+        .into_iter()
+        .map(|range| {
+            let Value::Array(mut range) = range else {
+                return Err(unexpected_response("slot range", &range));
+            };
+            if range.len() < 3 {
+                return Err(unexpected_response("slot range", &Value::Array(range)));
+            }
+            let primary = match range.swap_remove(2) {
+                Value::Array(node) if node.len() >= 2 => {
+                    let host = match &node[0] {
+                        Value::BulkString(host) => String::from_utf8_lossy(host).into_owned(),
+                        Value::SimpleString(host) => host.clone(),
+                        other => return Err(unexpected_response("node host", other)),
+                    };
+                    let Value::Int(port) = node[1] else {
+                        return Err(unexpected_response("node port", &node[1]));
+                    };
+                    format!("{host}:{port}")
+                }
+                other => return Err(unexpected_response("slot owner", &other)),
+            };
+            let slot = |value: &Value| match value {
+                Value::Int(slot) if (0..SLOT_COUNT as i64).contains(slot) => Ok(*slot as u16),
+                other => Err(unexpected_response("slot", other)),
+            };
+            Ok(SlotRange {
+                start: slot(&range[0])?,
+                end: slot(&range[1])?,
+                primary,
+            })
+        })
+        .collect()
+}
+
+/// Options of [`slot_population_report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotPopulationOptions {
+    /// Every `sample_step`-th slot is sampled. 1 counts the keys of every slot.
+    pub sample_step: u16,
+}
+
+impl Default for SlotPopulationOptions {
+    fn default() -> Self {
+        Self {
+            sample_step: DEFAULT_SAMPLE_STEP,
+        }
+    }
+}
+
+/// The key count of a sampled slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlotPopulation {
+    pub slot: u16,
+    pub node: String,
+    pub keys: u64,
+}
+
+/// The key population of a primary node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodePopulation {
+    pub node: String,
+    pub owned_slots: u64,
+    pub sampled_slots: u64,
+    pub sampled_keys: u64,
+    /// The total keys of the node, extrapolated from the sampled slots.
+    pub estimated_keys: u64,
+}
+
+/// The result of [`slot_population_report`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SlotPopulationReport {
+    /// The sampled slots, most populated first.
+    pub slots: Vec<SlotPopulation>,
+    /// The primary nodes, by estimated key count, most populated first.
+    pub nodes: Vec<NodePopulation>,
+}
+
+fn owner_of(ranges: &[SlotRange], slot: u16) -> Option<&str> {
+    ranges
+        .iter()
+        .find(|range| (range.start..=range.end).contains(&slot))
+        .map(|range| range.primary.as_str())
+}
+
+fn build_report(ranges: &[SlotRange], counts: Vec<(u16, u64)>) -> SlotPopulationReport {
+    let mut nodes: HashMap<&str, NodePopulation> = HashMap::new();
+    for range in ranges {
+        let node = nodes
+            .entry(range.primary.as_str())
+            .or_insert_with(|| NodePopulation {
+                node: range.primary.clone(),
+                owned_slots: 0,
+                sampled_slots: 0,
+                sampled_keys: 0,
+                estimated_keys: 0,
+            });
+        node.owned_slots += (range.end - range.start) as u64 + 1;
+    }
+
+    let mut slots = Vec::with_capacity(counts.len());
+    for (slot, keys) in counts {
+        let Some(owner) = owner_of(ranges, slot) else {
+            continue;
+        };
+        if let Some(node) = nodes.get_mut(owner) {
+            node.sampled_slots += 1;
+            node.sampled_keys += keys;
+        }
+        slots.push(SlotPopulation {
+            slot,
+            node: owner.to_string(),
+            keys,
+        });
+    }
+    slots.sort_by(|a, b| b.keys.cmp(&a.keys).then(a.slot.cmp(&b.slot)));
+
+    let mut nodes: Vec<_> = nodes
+        .into_values()
+        .map(|mut node| {
+            node.estimated_keys = (node.sampled_keys * node.owned_slots)
+                .checked_div(node.sampled_slots)
+                .unwrap_or_default();
+            node
+        })
+        .collect();
+    nodes.sort_by(|a, b| {
+        b.estimated_keys
+            .cmp(&a.estimated_keys)
+            .then_with(|| a.node.cmp(&b.node))
+    });
+
+    SlotPopulationReport { slots, nodes }
+}
+
+/// Samples the key count of the cluster slots, and aggregates it per primary node.
+///
+/// Slots that are not covered by any node are skipped. The counts are read one slot at a time,
+/// so the report is not an atomic snapshot of the cluster.
+pub async fn slot_population_report(
+    client: &mut Client,
+    options: &SlotPopulationOptions,
+) -> RedisResult<SlotPopulationReport> {
+    if options.sample_step == 0 {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "sample_step must be greater than 0",
+        )));
+    }
+    let mut cluster_slots = redis::cmd("CLUSTER");
+    cluster_slots.arg("SLOTS");
+    let ranges = parse_cluster_slots(client.send_command(&mut cluster_slots, None).await?)?;
+
+    let sampled_slots: Vec<u16> = (0..SLOT_COUNT)
+        .step_by(options.sample_step as usize)
+        .filter(|slot| owner_of(&ranges, *slot).is_some())
+        .collect();
+    let counts: Vec<(u16, u64)> = stream::iter(sampled_slots)
+        .map(|slot| {
+            let mut client = client.clone();
+            async move { Ok::<_, RedisError>((slot, count_keys_in_slot(&mut client, slot).await?)) }
+        })
+        .buffer_unordered(MAX_CONCURRENT_COUNTS)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<RedisResult<_>>()?;
+
+    Ok(build_report(&ranges, counts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_tags_decide_the_slot() {
+        assert_eq!(extract_hash_tag(b"{user1}:profile"), Some(&b"user1"[..]));
+        assert_eq!(extract_hash_tag(b"a{b}{c}"), Some(&b"b"[..]));
+        assert_eq!(extract_hash_tag(b"{}user1"), None);
+        assert_eq!(extract_hash_tag(b"user1"), None);
+        assert_eq!(cluster_slot(b"{user1}:profile"), cluster_slot(b"user1"));
+        // The slot of `foo` in the cluster specification.
+        assert_eq!(cluster_slot(b"foo"), 12182);
+    }
+
+    #[test]
+    fn test_colocated_keys_share_a_slot() {
+        let profile = colocated_key(b"user1", b":profile").unwrap();
+        assert_eq!(profile, b"{user1}:profile");
+        let orders = colocated_key(b"user1", b":orders").unwrap();
+        assert_eq!(
+            keys_share_slot(&[profile, orders]).unwrap(),
+            Some(cluster_slot(b"user1"))
+        );
+        assert!(colocated_key(b"", b"key").is_err());
+        assert!(colocated_key(b"a}b", b"key").is_err());
+    }
+
+    #[test]
+    fn test_keys_share_slot() {
+        assert_eq!(keys_share_slot::<&[u8]>(&[]).unwrap(), None);
+        assert_eq!(
+            keys_share_slot(&["{a}1", "{a}2", "a"]).unwrap(),
+            Some(cluster_slot(b"a"))
+        );
+        assert_eq!(
+            keys_share_slot(&["foo", "bar"]).unwrap_err().kind(),
+            ErrorKind::CrossSlot
+        );
+    }
+
+    #[test]
+    fn test_transaction_slot() {
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .cmd("SET")
+            .arg("{user1}:name")
+            .arg("value")
+            .cmd("MGET")
+            .arg("{user1}:a")
+            .arg("{user1}:b")
+            .cmd("PING")
+            .cmd("UNKNOWN.COMMAND")
+            .arg("other");
+        assert_eq!(
+            transaction_slot(&pipeline).unwrap(),
+            Some(cluster_slot(b"user1"))
+        );
+
+        pipeline.cmd("GET").arg("other");
+        assert_eq!(
+            transaction_slot(&pipeline).unwrap_err().kind(),
+            ErrorKind::CrossSlot
+        );
+        assert_eq!(transaction_slot(&Pipeline::new()).unwrap(), None);
+    }
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    fn node(host: &str, port: i64) -> Value {
+        Value::Array(vec![
+            Value::BulkString(host.as_bytes().to_vec()),
+            Value::Int(port),
+            Value::BulkString(b"node-id".to_vec()),
+        ])
+    }
+
+    fn ranges() -> Vec<SlotRange> {
+        vec![
+            SlotRange {
+                start: 0,
+                end: 8191,
+                primary: "a:6379".to_string(),
+            },
+            SlotRange {
+                start: 8192,
+                end: 16383,
+                primary: "b:6379".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn test_slot_commands() {
+        assert_eq!(
+            args(&count_keys_in_slot_cmd(7000).unwrap()),
+            vec!["CLUSTER", "COUNTKEYSINSLOT", "7000"]
+        );
+        assert_eq!(
+            args(&get_keys_in_slot_cmd(7000, 10).unwrap()),
+            vec!["CLUSTER", "GETKEYSINSLOT", "7000", "10"]
+        );
+        assert!(count_keys_in_slot_cmd(SLOT_COUNT).is_err());
+        assert!(get_keys_in_slot_cmd(SLOT_COUNT, 10).is_err());
+    }
+
+    #[test]
+    fn test_parse_cluster_slots() {
+        let response = Value::Array(vec![
+            Value::Array(vec![
+                Value::Int(0),
+                Value::Int(8191),
+                node("a", 6379),
+                node("a-replica", 6380),
+            ]),
+            Value::Array(vec![Value::Int(8192), Value::Int(16383), node("b", 6379)]),
+        ]);
+        assert_eq!(parse_cluster_slots(response).unwrap(), ranges());
+
+        let invalid = Value::Array(vec![Value::Array(vec![Value::Int(0), Value::Int(1)])]);
+        assert!(parse_cluster_slots(invalid).is_err());
+    }
+
+    #[test]
+    fn test_build_report_extrapolates_node_keys() {
+        let report = build_report(&ranges(), vec![(0, 10), (4096, 30), (8192, 5)]);
+
+        assert_eq!(
+            report.slots,
+            vec![
+                SlotPopulation {
+                    slot: 4096,
+                    node: "a:6379".to_string(),
+                    keys: 30
+                },
+                SlotPopulation {
+                    slot: 0,
+                    node: "a:6379".to_string(),
+                    keys: 10
+                },
+                SlotPopulation {
+                    slot: 8192,
+                    node: "b:6379".to_string(),
+                    keys: 5
+                },
+            ]
+        );
+        assert_eq!(
+            report.nodes,
+            vec![
+                NodePopulation {
+                    node: "a:6379".to_string(),
+                    owned_slots: 8192,
+                    sampled_slots: 2,
+                    sampled_keys: 40,
+                    estimated_keys: 40 * 8192 / 2,
+                },
+                NodePopulation {
+                    node: "b:6379".to_string(),
+                    owned_slots: 8192,
+                    sampled_slots: 1,
+                    sampled_keys: 5,
+                    estimated_keys: 5 * 8192,
+                },
+            ]
+        );
+    }
+}
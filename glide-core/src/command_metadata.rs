@@ -0,0 +1,520 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Metadata of the commands known to the core.
+//!
+//! Every [`RequestType`] that maps to a server command is described by a [`CommandMetadata`],
+//! derived from the same tables the core uses to build, route and process the command: its flags,
+//! where its keys are, how it's routed in cluster mode and how the responses of multiple nodes are
+//! combined. Bindings can read the table - directly, or through the socket's
+//! `GetCommandMetadata` request - to generate their typed wrappers and validators, instead of
+//! maintaining their own copy of it.
+
+use once_cell::sync::Lazy;
+use redis::cluster_routing::{
+    AggregateOp, ArrayAggregateOp, LogicalAggregateOp, MultiSlotArgPattern, ResponsePolicy,
+    Routable, RouteBy, RoutingInfo, is_readonly_cmd,
+};
+use redis::{Cmd, Value};
+use strum::IntoEnumIterator;
+
+use crate::client::is_blocking_command;
+use crate::compression::CommandCompressionBehavior;
+use crate::request_type::RequestType;
+
+static COMMAND_TABLE: Lazy<Vec<CommandMetadata>> = Lazy::new(|| {
+    RequestType::iter()
+        .filter_map(CommandMetadata::for_request_type)
+        .collect()
+});
+
+/// Where the keys of a command are found in its arguments.
+/// Argument indices count the first word of the command name as argument 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeySpec {
+    /// The command has no keys.
+    None,
+    /// A single key, at the given index.
+    Index(usize),
+    /// The argument at `count_index` is the number of keys, which follow it.
+    KeyCount { count_index: usize },
+    /// The keys start right after the given keyword, and make up the first half of the remaining arguments.
+    AfterKeyword(&'static str),
+    /// A key every `step` arguments from `first`, up to the last `excluded_last` arguments.
+    Range {
+        first: usize,
+        step: usize,
+        excluded_last: usize,
+    },
+}
+
+impl KeySpec {
+    /// Returns the keys found in `args`, the arguments of a command - including its name.
+    pub fn keys<'a>(&self, args: &[&'a [u8]]) -> Vec<&'a [u8]> {
+        match *self {
+            KeySpec::None => Vec::new(),
+            KeySpec::Index(index) => args.get(index).copied().into_iter().collect(),
+            KeySpec::KeyCount { count_index } => {
+                let count = args
+                    .get(count_index)
+                    .and_then(|count| std::str::from_utf8(count).ok())
+                    .and_then(|count| count.parse::<usize>().ok())
+                    .unwrap_or_default();
+                args.iter()
+                    .skip(count_index + 1)
+                    .take(count)
+                    .copied()
+                    .collect()
+            }
+            KeySpec::AfterKeyword(keyword) => {
+                let Some(position) = args
+                    .iter()
+                    .position(|arg| arg.eq_ignore_ascii_case(keyword.as_bytes()))
+                else {
+                    return Vec::new();
+                };
+                let rest = &args[position + 1..];
+                rest[..rest.len() / 2].to_vec()
+            }
+            KeySpec::Range {
+                first,
+                step,
+                excluded_last,
+            } => args
+                .get(first..args.len().saturating_sub(excluded_last))
+                .unwrap_or_default()
+                .iter()
+                .step_by(step.max(1))
+                .copied()
+                .collect(),
+        }
+    }
+}
+
+/// The metadata of a command known to the core.
+#[derive(Debug, Clone)]
+pub struct CommandMetadata {
+    pub request_type: RequestType,
+    /// The name of the request type, e.g. `ConfigGet`.
+    pub request_type_name: &'static str,
+    /// The words of the command's name, e.g. `CONFIG GET`.
+    pub command: String,
+    /// Whether the command can be sent to replicas when the client reads from replicas.
+    pub readonly: bool,
+    /// Whether the command can block on the server, with a timeout given in its arguments.
+    pub blocking: bool,
+    /// Whether the command is a pubsub command, whose channels aren't keys.
+    pub pubsub: bool,
+    pub compression: CommandCompressionBehavior,
+    /// Whether the command can't be used on keys with compressed values.
+    pub compression_incompatible: bool,
+    pub key_spec: KeySpec,
+    /// How the command is routed in cluster mode, when no route is given.
+    pub routing: RouteBy,
+    /// How the responses are combined when the command is sent to multiple nodes.
+    pub response_policy: Option<ResponsePolicy>,
+    /// The first Valkey version with the command. `None` for commands available since Valkey 7.2,
+    /// the first Valkey release, and for module commands.
+    pub min_server_version: Option<&'static str>,
+}
+
+impl CommandMetadata {
+    fn for_request_type(request_type: RequestType) -> Option<Self> {
+        let cmd = request_type.get_command()?;
+        let words: Vec<&[u8]> = cmd.args_iter().filter_map(arg_bytes).collect();
+        if words.is_empty() {
+            // Custom commands have no name of their own.
+            return None;
+        }
+        let command = String::from_utf8_lossy(&words.join(&b' ')).into_owned();
+        let name = command.as_bytes();
+        let routing = RoutingInfo::base_route(name);
+        let pubsub = is_pubsub_command(&command);
+        let key_spec = if pubsub || is_keyless_command(&command, words.len(), &routing) {
+            KeySpec::None
+        } else {
+            key_spec_for_route(&routing)
+        };
+        Some(Self {
+            request_type,
+            request_type_name: request_type.into(),
+            readonly: is_readonly_cmd(name),
+            blocking: is_blocking_command(name),
+            pubsub,
+            compression: request_type.compression_behavior(),
+            compression_incompatible: request_type.compression_incompatibility_reason().is_some(),
+            key_spec,
+            routing,
+            response_policy: ResponsePolicy::for_command(name),
+            min_server_version: min_server_version(&command),
+            command,
+        })
+    }
+
+    /// Converts the metadata to a map, as returned by the socket's `GetCommandMetadata` request.
+    pub fn into_value(self) -> Value {
+        let entry = |key: &str, value: Value| (Value::SimpleString(key.to_string()), value);
+        let text = |text: &str| Value::BulkString(text.as_bytes().to_vec());
+        let mut flags = vec![if self.readonly { "readonly" } else { "write" }];
+        if self.blocking {
+            flags.push("blocking");
+        }
+        if self.pubsub {
+            flags.push("pubsub");
+        }
+        if self.compression_incompatible {
+            flags.push("compression_incompatible");
+        }
+        let compression = match self.compression {
+            CommandCompressionBehavior::CompressValues => "compress_values",
+            CommandCompressionBehavior::DecompressValues => "decompress_values",
+            CommandCompressionBehavior::NoCompression => "none",
+        };
+        Value::Map(vec![
+            entry("request_type", Value::Int(self.request_type as i64)),
+            entry("name", text(self.request_type_name)),
+            entry("command", text(&self.command)),
+            entry(
+                "flags",
+                Value::Array(
+                    flags
+                        .into_iter()
+                        .map(|flag| Value::SimpleString(flag.to_string()))
+                        .collect(),
+                ),
+            ),
+            entry("compression", Value::SimpleString(compression.to_string())),
+            entry("key_spec", key_spec_value(self.key_spec)),
+            entry(
+                "routing",
+                Value::SimpleString(routing_name(&self.routing).to_string()),
+            ),
+            entry(
+                "response_policy",
+                self.response_policy.map_or(Value::Nil, |policy| {
+                    Value::SimpleString(response_policy_name(policy).to_string())
+                }),
+            ),
+            entry(
+                "min_server_version",
+                self.min_server_version.map_or(Value::Nil, text),
+            ),
+        ])
+    }
+}
+
+/// Returns the metadata of all the commands known to the core, ordered by request type.
+pub fn command_table() -> &'static [CommandMetadata] {
+    &COMMAND_TABLE
+}
+
+/// Returns the metadata of a command by its name, e.g. `config get`. Names are case-insensitive.
+pub fn command_metadata(command: &str) -> Option<&'static CommandMetadata> {
+    COMMAND_TABLE
+        .iter()
+        .find(|metadata| metadata.command.eq_ignore_ascii_case(command.trim()))
+}
+
+/// Returns the keys of `cmd`, found by the key spec of its command. The keys of commands unknown to
+/// the core aren't known.
+pub fn command_keys(cmd: &Cmd) -> Vec<&[u8]> {
+    let Some(metadata) = cmd
+        .command()
+        .and_then(|name| command_metadata(&String::from_utf8_lossy(&name)))
+    else {
+        return Vec::new();
+    };
+    let args: Vec<&[u8]> = cmd.args_iter().filter_map(arg_bytes).collect();
+    metadata.key_spec.keys(&args)
+}
+
+fn arg_bytes(arg: redis::Arg<&[u8]>) -> Option<&[u8]> {
+    match arg {
+        redis::Arg::Simple(bytes) => Some(bytes),
+        redis::Arg::Cursor => None,
+    }
+}
+
+fn is_pubsub_command(command: &str) -> bool {
+    command.contains("SUBSCRIBE")
+        || command.starts_with("PUBSUB ")
+        || matches!(command, "PUBLISH" | "SPUBLISH" | "GET_SUBSCRIPTIONS")
+}
+
+/// Returns whether a command that would be routed by its first argument has no keys.
+fn is_keyless_command(command: &str, words: usize, routing: &RouteBy) -> bool {
+    if *routing != RouteBy::FirstKey {
+        return false;
+    }
+    // The first argument of a command with a subcommand is the subcommand.
+    words > 1
+        // Search indexes aren't keys.
+        || command.starts_with("FT.")
+        || matches!(
+            command,
+            "ASKING"
+                | "DISCARD"
+                | "EXEC"
+                | "FAILOVER"
+                | "HELLO"
+                | "MONITOR"
+                | "MULTI"
+                | "PSYNC"
+                | "QUIT"
+                | "REPLCONF"
+                | "ROLE"
+                | "SWAPDB"
+                | "SYNC"
+        )
+}
+
+fn key_spec_for_route(routing: &RouteBy) -> KeySpec {
+    let keys_from_first_arg = |step, excluded_last| KeySpec::Range {
+        first: 1,
+        step,
+        excluded_last,
+    };
+    match routing {
+        RouteBy::FirstKey => KeySpec::Index(1),
+        RouteBy::SecondArg => KeySpec::Index(2),
+        RouteBy::ThirdArg => KeySpec::Index(3),
+        RouteBy::SecondArgAfterKeyCount => KeySpec::KeyCount { count_index: 1 },
+        RouteBy::ThirdArgAfterKeyCount => KeySpec::KeyCount { count_index: 2 },
+        RouteBy::StreamsIndex => KeySpec::AfterKeyword("STREAMS"),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeysOnly) => keys_from_first_arg(1, 0),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeyValuePairs) => keys_from_first_arg(2, 0),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeysAndLastArg) => keys_from_first_arg(1, 1),
+        RouteBy::MultiShard(MultiSlotArgPattern::KeyWithTwoArgTriples) => keys_from_first_arg(3, 0),
+        RouteBy::AllNodes
+        | RouteBy::AllPrimaries
+        | RouteBy::Random
+        | RouteBy::SecondArgSlot
+        | RouteBy::Undefined => KeySpec::None,
+    }
+}
+
+fn key_spec_value(key_spec: KeySpec) -> Value {
+    let entry = |key: &str, value: Value| (Value::SimpleString(key.to_string()), value);
+    let index = |index: usize| Value::Int(index as i64);
+    let kind = |kind: &str| entry("type", Value::SimpleString(kind.to_string()));
+    match key_spec {
+        KeySpec::None => Value::Nil,
+        KeySpec::Index(key_index) => {
+            Value::Map(vec![kind("index"), entry("index", index(key_index))])
+        }
+        KeySpec::KeyCount { count_index } => Value::Map(vec![
+            kind("keynum"),
+            entry("count_index", index(count_index)),
+        ]),
+        KeySpec::AfterKeyword(keyword) => Value::Map(vec![
+            kind("keyword"),
+            entry("keyword", Value::BulkString(keyword.as_bytes().to_vec())),
+        ]),
+        KeySpec::Range {
+            first,
+            step,
+            excluded_last,
+        } => Value::Map(vec![
+            kind("range"),
+            entry("first", index(first)),
+            entry("step", index(step)),
+            entry("excluded_last", index(excluded_last)),
+        ]),
+    }
+}
+
+fn routing_name(routing: &RouteBy) -> &'static str {
+    match routing {
+        RouteBy::AllNodes => "all_nodes",
+        RouteBy::AllPrimaries => "all_primaries",
+        RouteBy::MultiShard(_) => "multi_shard",
+        RouteBy::Random => "random",
+        RouteBy::SecondArgSlot => "slot",
+        RouteBy::Undefined => "undefined",
+        RouteBy::FirstKey
+        | RouteBy::SecondArg
+        | RouteBy::ThirdArg
+        | RouteBy::SecondArgAfterKeyCount
+        | RouteBy::ThirdArgAfterKeyCount
+        | RouteBy::StreamsIndex => "key",
+    }
+}
+
+fn response_policy_name(policy: ResponsePolicy) -> &'static str {
+    match policy {
+        ResponsePolicy::OneSucceeded => "one_succeeded",
+        ResponsePolicy::FirstSucceededNonEmptyOrAllEmpty => {
+            "first_succeeded_non_empty_or_all_empty"
+        }
+        ResponsePolicy::AllSucceeded => "all_succeeded",
+        ResponsePolicy::AggregateLogical(LogicalAggregateOp::And) => "agg_logical_and",
+        ResponsePolicy::Aggregate(AggregateOp::Sum) => "agg_sum",
+        ResponsePolicy::Aggregate(AggregateOp::Min) => "agg_min",
+        ResponsePolicy::AggregateArray(ArrayAggregateOp::Min) => "agg_array_min",
+        ResponsePolicy::CombineArrays => "combine_arrays",
+        ResponsePolicy::CombineMaps => "combine_maps",
+        ResponsePolicy::Special => "special",
+    }
+}
+
+/// Returns the first Valkey version with the command, for commands newer than Valkey 7.2, the
+/// first Valkey release. Commands that older Redis servers lack, such as the 7.0 additions, are
+/// available in every Valkey version and so have no minimum version here.
+fn min_server_version(command: &str) -> Option<&'static str> {
+    match command {
+        "HEXPIRE" | "HEXPIREAT" | "HEXPIRETIME" | "HGETEX" | "HPERSIST" | "HPEXPIRE"
+        | "HPEXPIREAT" | "HPEXPIRETIME" | "HPTTL" | "HSETEX" | "HTTL" => Some("9.0.0"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_covers_named_request_types() {
+        let table = command_table();
+        assert!(table.len() > 300);
+        assert!(
+            !table
+                .iter()
+                .any(|metadata| matches!(metadata.request_type, RequestType::CustomCommand))
+        );
+        let config_get = command_metadata("config get").unwrap();
+        assert_eq!(config_get.request_type_name, "ConfigGet");
+        assert_eq!(config_get.command, "CONFIG GET");
+        assert_eq!(config_get.key_spec, KeySpec::None);
+        assert!(command_metadata("NOT-A-COMMAND").is_none());
+    }
+
+    #[test]
+    fn test_key_specs() {
+        assert_eq!(command_metadata("GET").unwrap().key_spec, KeySpec::Index(1));
+        assert_eq!(
+            command_metadata("MEMORY USAGE").unwrap().key_spec,
+            KeySpec::Index(2)
+        );
+        assert_eq!(
+            command_metadata("FCALL").unwrap().key_spec,
+            KeySpec::KeyCount { count_index: 2 }
+        );
+        assert_eq!(
+            command_metadata("MSET").unwrap().key_spec,
+            KeySpec::Range {
+                first: 1,
+                step: 2,
+                excluded_last: 0
+            }
+        );
+        assert_eq!(
+            command_metadata("XREAD").unwrap().key_spec,
+            KeySpec::AfterKeyword("STREAMS")
+        );
+        assert_eq!(command_metadata("MULTI").unwrap().key_spec, KeySpec::None);
+        assert_eq!(
+            command_metadata("SUBSCRIBE").unwrap().key_spec,
+            KeySpec::None
+        );
+    }
+
+    #[test]
+    fn test_command_keys() {
+        let keys = |cmd: &Cmd| -> Vec<String> {
+            command_keys(cmd)
+                .into_iter()
+                .map(|key| String::from_utf8_lossy(key).into_owned())
+                .collect()
+        };
+        assert_eq!(keys(redis::cmd("GET").arg("a")), ["a"]);
+        assert_eq!(
+            keys(redis::cmd("MSET").arg("a").arg(1).arg("b").arg(2)),
+            ["a", "b"]
+        );
+        assert_eq!(
+            keys(
+                redis::cmd("FCALL")
+                    .arg("f")
+                    .arg(2)
+                    .arg("a")
+                    .arg("b")
+                    .arg("x")
+            ),
+            ["a", "b"]
+        );
+        assert_eq!(
+            keys(
+                redis::cmd("XREAD")
+                    .arg("COUNT")
+                    .arg(1)
+                    .arg("streams")
+                    .arg("a")
+                    .arg("b")
+                    .arg(0)
+                    .arg(0)
+            ),
+            ["a", "b"]
+        );
+        assert_eq!(keys(redis::cmd("memory").arg("usage").arg("a")), ["a"]);
+        assert!(keys(&redis::cmd("PING")).is_empty());
+        assert!(keys(redis::cmd("NOT-A-COMMAND").arg("a")).is_empty());
+    }
+
+    #[test]
+    fn test_flags_and_policies() {
+        let get = command_metadata("GET").unwrap();
+        assert!(get.readonly && !get.blocking);
+        assert_eq!(
+            get.compression,
+            CommandCompressionBehavior::DecompressValues
+        );
+
+        let blpop = command_metadata("BLPOP").unwrap();
+        assert!(!blpop.readonly && blpop.blocking);
+
+        let dbsize = command_metadata("DBSIZE").unwrap();
+        assert_eq!(dbsize.routing, RouteBy::AllPrimaries);
+        assert_eq!(
+            dbsize.response_policy,
+            Some(ResponsePolicy::Aggregate(AggregateOp::Sum))
+        );
+
+        assert_eq!(
+            command_metadata("WAITAOF").unwrap().min_server_version,
+            None
+        );
+        assert_eq!(command_metadata("LMPOP").unwrap().min_server_version, None);
+        assert_eq!(
+            command_metadata("HEXPIRE").unwrap().min_server_version,
+            Some("9.0.0")
+        );
+        assert!(command_metadata("INCR").unwrap().compression_incompatible);
+    }
+
+    #[test]
+    fn test_into_value() {
+        let Value::Map(entries) = command_metadata("MGET").unwrap().clone().into_value() else {
+            panic!("expected a map");
+        };
+        let get = |key: &str| {
+            entries
+                .iter()
+                .find(|(entry_key, _)| *entry_key == Value::SimpleString(key.to_string()))
+                .map(|(_, value)| value.clone())
+                .unwrap()
+        };
+        assert_eq!(get("command"), Value::BulkString(b"MGET".to_vec()));
+        assert_eq!(
+            get("routing"),
+            Value::SimpleString("multi_shard".to_string())
+        );
+        assert_eq!(
+            get("response_policy"),
+            Value::SimpleString("combine_arrays".to_string())
+        );
+        assert_eq!(
+            get("flags"),
+            Value::Array(vec![Value::SimpleString("readonly".to_string())])
+        );
+        assert_eq!(get("min_server_version"), Value::Nil);
+    }
+}
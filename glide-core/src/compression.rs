@@ -880,6 +880,8 @@ pub fn process_command_args_for_compression(
         RequestType::SetEx => compress_single_value_command(args, manager, 2),
         RequestType::PSetEx => compress_single_value_command(args, manager, 2),
         RequestType::SetNX => compress_single_value_command(args, manager, 1),
+        RequestType::HSet | RequestType::HMSet => compress_hset_command(args, manager),
+        RequestType::HSetNX => compress_single_value_command(args, manager, 2),
         _ => Ok(()),
     }
 }
@@ -938,6 +940,21 @@ fn compress_mset_command(
     Ok(())
 }
 
+fn compress_hset_command(
+    args: &mut [Vec<u8>],
+    manager: &CompressionManager,
+) -> CompressionResult<()> {
+    // HSET format: key field1 value1 field2 value2 ...
+    // Values are at indices 2, 4, 6, etc. (even indices starting from 2)
+    let mut i = 2;
+    while i < args.len() {
+        let compressed_value = manager.compress_value(&args[i]);
+        args[i] = compressed_value.into_owned();
+        i += 2; // Skip to next value (skip the field)
+    }
+    Ok(())
+}
+
 pub fn process_response_for_decompression(
     value: redis::Value,
     request_type: RequestType,
@@ -968,6 +985,9 @@ pub fn process_response_for_decompression(
         RequestType::GetEx => decompress_single_value_response(value, manager),
         RequestType::GetDel => decompress_single_value_response(value, manager),
         RequestType::GetSet => decompress_single_value_response(value, manager),
+        RequestType::HGet => decompress_single_value_response(value, manager),
+        RequestType::HMGet | RequestType::HVals => decompress_mget_response(value, manager),
+        RequestType::HGetAll => decompress_hgetall_response(value, manager),
         _ => Ok(value),
     }
 }
@@ -1025,6 +1045,40 @@ pub fn decompress_mget_response(
     }
 }
 
+pub fn decompress_hgetall_response(
+    value: redis::Value,
+    manager: &CompressionManager,
+) -> CompressionResult<redis::Value> {
+    use redis::Value;
+
+    match value {
+        // RESP3 returns a map of field to value
+        Value::Map(entries) => {
+            let decompressed_entries: Result<Vec<_>, _> = entries
+                .into_iter()
+                .map(|(field, v)| Ok((field, decompress_single_value_response(v, manager)?)))
+                .collect();
+            Ok(Value::Map(decompressed_entries?))
+        }
+        // RESP2 returns a flat array of field1, value1, field2, value2, ...
+        Value::Array(values) => {
+            let decompressed_values: Result<Vec<_>, _> = values
+                .into_iter()
+                .enumerate()
+                .map(|(index, v)| {
+                    if index % 2 == 1 {
+                        decompress_single_value_response(v, manager)
+                    } else {
+                        Ok(v)
+                    }
+                })
+                .collect();
+            Ok(Value::Array(decompressed_values?))
+        }
+        _ => Ok(value),
+    }
+}
+
 /// Decompress a batch (pipeline/transaction) response.
 ///
 /// This function processes the response from a batch operation and decompresses
@@ -1056,6 +1110,7 @@ pub fn decompress_batch_response(
                 // We take ownership of resp to avoid cloning
                 let processed = match resp {
                     Value::Array(_) => decompress_batch_response(resp, manager)?,
+                    Value::Map(_) => decompress_hgetall_response(resp, manager)?,
                     other => decompress_single_value_response(other, manager)?,
                 };
                 processed_responses.push(processed);
@@ -0,0 +1,291 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Detection of configuration drift between the nodes of a cluster.
+//!
+//! [`detect_config_drift`] sends `CONFIG GET` for each requested parameter to all the nodes,
+//! primaries and replicas, and reports the parameters whose value isn't the same on every node -
+//! e.g. a replica with a different `maxmemory-policy` than the rest of the cluster.
+
+use std::collections::{BTreeMap, HashMap};
+
+use redis::cluster_routing::{MultipleNodeRoutingInfo, RoutingInfo};
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+
+use crate::client::Client;
+use crate::errors::unexpected_response;
+
+/// Parameters compared when no parameters are given to [`detect_config_drift`].
+pub const DEFAULT_DRIFT_PARAMETERS: &[&str] = &[
+    "maxmemory",
+    "maxmemory-policy",
+    "appendonly",
+    "appendfsync",
+    "save",
+    "timeout",
+    "maxclients",
+    "cluster-node-timeout",
+    "notify-keyspace-events",
+];
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(string) => Some(string.clone()),
+        Value::VerbatimString { text, .. } => Some(text.clone()),
+        Value::Int(int) => Some(int.to_string()),
+        _ => None,
+    }
+}
+
+/// A parameter that doesn't have the same value on all the nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigMismatch {
+    pub parameter: String,
+    /// The value of the parameter on each node, or `None` if the node didn't return it.
+    pub values: BTreeMap<String, Option<String>>,
+}
+
+impl ConfigMismatch {
+    /// Returns the nodes whose value differs from the most common value.
+    /// When several values are equally common, the smallest one is considered the common value.
+    pub fn outliers(&self) -> Vec<&str> {
+        let mut counts: BTreeMap<&Option<String>, usize> = BTreeMap::new();
+        for value in self.values.values() {
+            *counts.entry(value).or_default() += 1;
+        }
+        let Some(common) = counts
+            .iter()
+            .max_by(|(a_value, a_count), (b_value, b_count)| {
+                a_count.cmp(b_count).then_with(|| b_value.cmp(a_value))
+            })
+            .map(|(value, _)| *value)
+        else {
+            return Vec::new();
+        };
+        self.values
+            .iter()
+            .filter(|(_, value)| *value != common)
+            .map(|(node, _)| node.as_str())
+            .collect()
+    }
+}
+
+/// The result of [`detect_config_drift`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDriftReport {
+    /// The addresses of the compared nodes.
+    pub nodes: Vec<String>,
+    /// The parameters that differ between nodes, sorted by name.
+    pub mismatches: Vec<ConfigMismatch>,
+}
+
+impl ConfigDriftReport {
+    /// Returns whether all the compared parameters have the same value on all the nodes.
+    pub fn is_consistent(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Builds a `CONFIG GET` command. The parameter may be a glob-style pattern.
+pub fn config_get_cmd(parameter: &str) -> Cmd {
+    let mut cmd = redis::cmd("CONFIG");
+    cmd.arg("GET").arg(parameter);
+    cmd
+}
+
+/// Compares the configuration of all the cluster nodes, and returns the parameters whose value
+/// differs between nodes. If `parameters` is empty, [`DEFAULT_DRIFT_PARAMETERS`] are compared.
+/// Parameters may be glob-style patterns, as accepted by `CONFIG GET`.
+/// Returns an error in standalone mode, or if any of the nodes fails to respond.
+pub async fn detect_config_drift(
+    client: &mut Client,
+    parameters: &[&str],
+) -> RedisResult<ConfigDriftReport> {
+    if !client.is_cluster().await? {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "Configuration drift detection is only supported in cluster mode",
+        )));
+    }
+    let parameters = if parameters.is_empty() {
+        DEFAULT_DRIFT_PARAMETERS
+    } else {
+        parameters
+    };
+
+    // Each parameter is requested separately, since older servers accept a single parameter
+    let mut configs: HashMap<String, BTreeMap<String, String>> = HashMap::new();
+    for parameter in parameters {
+        let routing = RoutingInfo::MultiNode((MultipleNodeRoutingInfo::AllNodes, None));
+        let response = client
+            .send_command(&mut config_get_cmd(parameter), Some(routing))
+            .await?;
+        for (node, config) in parse_broadcast_response(response)? {
+            configs.entry(node).or_default().extend(config);
+        }
+    }
+    Ok(build_report(configs))
+}
+
+/// Parses the per-node `CONFIG GET` responses of a command sent to all nodes.
+fn parse_broadcast_response(
+    response: Value,
+) -> RedisResult<Vec<(String, BTreeMap<String, String>)>> {
+    let Value::Map(nodes) = response else {
+        return Err(unexpected_response("a response per node", &response));
+    };
+    nodes
+        .into_iter()
+        .map(|(node, config)| {
+            let node =
+                value_to_string(&node).ok_or_else(|| unexpected_response("node address", &node))?;
+            Ok((node, parse_config_get_response(&config)?))
+        })
+        .collect()
+}
+
+/// Parses a `CONFIG GET` response - a map in RESP3, and an array of alternating names and values
+/// in RESP2.
+pub fn parse_config_get_response(response: &Value) -> RedisResult<BTreeMap<String, String>> {
+    let entries = response
+        .as_map_iter()
+        .ok_or_else(|| unexpected_response("CONFIG GET response", response))?;
+    entries
+        .map(
+            |(name, value)| match (value_to_string(name), value_to_string(value)) {
+                (Some(name), Some(value)) => Ok((name, value)),
+                _ => Err(unexpected_response("configuration parameter", response)),
+            },
+        )
+        .collect()
+}
+
+/// Builds the report from the configuration of each node.
+fn build_report(configs: HashMap<String, BTreeMap<String, String>>) -> ConfigDriftReport {
+    let mut nodes: Vec<String> = configs.keys().cloned().collect();
+    nodes.sort();
+    let mut parameters: Vec<&String> = configs.values().flat_map(|config| config.keys()).collect();
+    parameters.sort();
+    parameters.dedup();
+
+    let mismatches = parameters
+        .into_iter()
+        .filter_map(|parameter| {
+            let values: BTreeMap<String, Option<String>> = nodes
+                .iter()
+                .map(|node| (node.clone(), configs[node].get(parameter).cloned()))
+                .collect();
+            let mut distinct = values.values();
+            let first = distinct.next()?;
+            distinct
+                .any(|value| value != first)
+                .then(|| ConfigMismatch {
+                    parameter: parameter.clone(),
+                    values,
+                })
+        })
+        .collect();
+    ConfigDriftReport { nodes, mismatches }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bulk;
+
+    fn config(entries: &[(&str, &str)]) -> BTreeMap<String, String> {
+        entries
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_broadcast_response() {
+        let response = Value::Map(vec![
+            (
+                bulk("node-1:6379"),
+                Value::Map(vec![(bulk("maxmemory-policy"), bulk("noeviction"))]),
+            ),
+            (
+                bulk("node-2:6379"),
+                Value::Array(vec![bulk("maxmemory-policy"), bulk("allkeys-lru")]),
+            ),
+        ]);
+        assert_eq!(
+            parse_broadcast_response(response).unwrap(),
+            vec![
+                (
+                    "node-1:6379".to_string(),
+                    config(&[("maxmemory-policy", "noeviction")])
+                ),
+                (
+                    "node-2:6379".to_string(),
+                    config(&[("maxmemory-policy", "allkeys-lru")])
+                ),
+            ]
+        );
+        assert!(parse_broadcast_response(Value::Okay).is_err());
+        assert!(
+            parse_broadcast_response(Value::Map(vec![(
+                bulk("node-1:6379"),
+                Value::Array(vec![bulk("maxmemory")])
+            )]))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_build_report_finds_mismatches() {
+        let configs = HashMap::from([
+            (
+                "primary:6379".to_string(),
+                config(&[("maxmemory-policy", "noeviction"), ("timeout", "0")]),
+            ),
+            (
+                "replica-1:6379".to_string(),
+                config(&[("maxmemory-policy", "noeviction"), ("timeout", "0")]),
+            ),
+            (
+                "replica-2:6379".to_string(),
+                config(&[("maxmemory-policy", "allkeys-lru"), ("timeout", "0")]),
+            ),
+        ]);
+
+        let report = build_report(configs);
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report.nodes,
+            vec!["primary:6379", "replica-1:6379", "replica-2:6379"]
+        );
+        assert_eq!(report.mismatches.len(), 1);
+        let mismatch = &report.mismatches[0];
+        assert_eq!(mismatch.parameter, "maxmemory-policy");
+        assert_eq!(
+            mismatch.values["replica-2:6379"].as_deref(),
+            Some("allkeys-lru")
+        );
+        assert_eq!(mismatch.outliers(), vec!["replica-2:6379"]);
+    }
+
+    #[test]
+    fn test_missing_parameter_is_a_mismatch() {
+        let configs = HashMap::from([
+            ("node-1:6379".to_string(), config(&[("appendonly", "no")])),
+            ("node-2:6379".to_string(), config(&[])),
+        ]);
+
+        let report = build_report(configs);
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].values["node-2:6379"], None);
+    }
+
+    #[test]
+    fn test_consistent_configuration() {
+        let configs = HashMap::from([
+            ("node-1:6379".to_string(), config(&[("save", "")])),
+            ("node-2:6379".to_string(), config(&[("save", "")])),
+        ]);
+        assert!(build_report(configs).is_consistent());
+    }
+}
@@ -0,0 +1,241 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Helpers for standalone deployments that shard data by logical database.
+//!
+//! [`swap_db`] swaps two logical databases, and [`DatabaseScanner`] iterates the keys of several
+//! logical databases without changing the database used by the client's other commands.
+//! Both return an error when the client is connected to a cluster.
+
+use redis::{Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value};
+
+use crate::client::Client;
+use crate::errors::unexpected_response;
+
+fn cluster_mode_error(operation: &str) -> RedisError {
+    RedisError::from((
+        ErrorKind::ClientError,
+        "Logical databases are only supported in standalone mode",
+        format!("{operation} can't be used with a cluster client"),
+    ))
+}
+
+/// Builds a `SWAPDB` command.
+pub fn swap_db_cmd(first: i64, second: i64) -> Cmd {
+    let mut cmd = redis::cmd("SWAPDB");
+    cmd.arg(first).arg(second);
+    cmd
+}
+
+/// Swaps the content of two logical databases. Returns an error in cluster mode.
+pub async fn swap_db(client: &mut Client, first: i64, second: i64) -> RedisResult<()> {
+    if client.is_cluster().await? {
+        return Err(cluster_mode_error("SWAPDB"));
+    }
+    client
+        .send_command(&mut swap_db_cmd(first, second), None)
+        .await
+        .map(|_| ())
+}
+
+/// Filters for the keys returned by [`DatabaseScanner`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabaseScanOptions {
+    /// Only return keys matching this glob-style pattern.
+    pub match_pattern: Option<Vec<u8>>,
+    /// A hint for the number of keys returned by each `SCAN` call.
+    pub count: Option<u64>,
+    /// Only return keys of this type, e.g. `string` or `hash`.
+    pub object_type: Option<String>,
+}
+
+/// A batch of keys from a single logical database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseKeys {
+    pub database: i64,
+    pub keys: Vec<Vec<u8>>,
+}
+
+/// Iterates the keys of several logical databases, one database after the other.
+///
+/// Each `SCAN` call is sent in a transaction that selects the scanned database and then
+/// re-selects the client's database, so concurrent commands of the client aren't affected.
+/// Like `SCAN`, keys that are modified during the iteration might be returned more than once,
+/// or not at all.
+pub struct DatabaseScanner {
+    databases: Vec<i64>,
+    position: usize,
+    cursor: String,
+    options: DatabaseScanOptions,
+}
+
+impl DatabaseScanner {
+    pub fn new(databases: impl IntoIterator<Item = i64>, options: DatabaseScanOptions) -> Self {
+        Self {
+            databases: databases.into_iter().collect(),
+            position: 0,
+            cursor: "0".to_string(),
+            options,
+        }
+    }
+
+    /// Returns whether all the databases were scanned.
+    pub fn is_finished(&self) -> bool {
+        self.position >= self.databases.len()
+    }
+
+    fn scan_cmd(&self) -> Cmd {
+        let mut cmd = redis::cmd("SCAN");
+        cmd.arg(&self.cursor);
+        if let Some(pattern) = &self.options.match_pattern {
+            cmd.arg("MATCH").arg(pattern.as_slice());
+        }
+        if let Some(count) = self.options.count {
+            cmd.arg("COUNT").arg(count);
+        }
+        if let Some(object_type) = &self.options.object_type {
+            cmd.arg("TYPE").arg(object_type);
+        }
+        cmd
+    }
+
+    fn scan_transaction(&self, database: i64, client_database: i64) -> Pipeline {
+        let mut pipeline = redis::pipe();
+        pipeline.atomic();
+        pipeline.cmd("SELECT").arg(database);
+        pipeline.add_command(self.scan_cmd());
+        pipeline.cmd("SELECT").arg(client_database);
+        pipeline
+    }
+
+    /// Returns the next batch of keys, or `None` once all the databases were scanned.
+    /// A batch might be empty even though the iteration isn't finished.
+    pub async fn next_batch(&mut self, client: &mut Client) -> RedisResult<Option<DatabaseKeys>> {
+        if self.is_finished() {
+            return Ok(None);
+        }
+        if client.is_cluster().await? {
+            return Err(cluster_mode_error("Database scan"));
+        }
+        let client_database = client.db_namespace().parse().unwrap_or_default();
+        let database = self.databases[self.position];
+        let pipeline = self.scan_transaction(database, client_database);
+        let response = client.send_transaction(&pipeline, None, None, true).await?;
+        let (cursor, keys) = parse_transaction_response(response)?;
+
+        if cursor == "0" {
+            self.position += 1;
+        }
+        self.cursor = cursor;
+        Ok(Some(DatabaseKeys { database, keys }))
+    }
+}
+
+/// Parses the `[OK, [cursor, keys], OK]` response of the scan transaction.
+fn parse_transaction_response(response: Value) -> RedisResult<(String, Vec<Vec<u8>>)> {
+    let Value::Array(mut results) = response else {
+        return Err(unexpected_response("transaction results", &response));
+    };
+    if results.len() != 3 {
+        return Err(unexpected_response(
+            "transaction results",
+            &Value::Array(results),
+        ));
+    }
+    parse_scan_response(results.swap_remove(1))
+}
+
+pub(crate) fn parse_scan_response(response: Value) -> RedisResult<(String, Vec<Vec<u8>>)> {
+    let Value::Array(mut parts) = response else {
+        return Err(unexpected_response("SCAN response", &response));
+    };
+    if parts.len() != 2 {
+        return Err(unexpected_response("SCAN response", &Value::Array(parts)));
+    }
+    let keys = match parts.pop() {
+        Some(Value::Array(keys)) => keys
+            .into_iter()
+            .map(|key| match key {
+                Value::BulkString(key) => Ok(key),
+                other => Err(unexpected_response("key", &other)),
+            })
+            .collect::<RedisResult<_>>()?,
+        Some(other) => return Err(unexpected_response("SCAN keys", &other)),
+        None => unreachable!(),
+    };
+    let cursor = match parts.pop() {
+        Some(Value::BulkString(cursor)) => String::from_utf8_lossy(&cursor).into_owned(),
+        Some(Value::SimpleString(cursor)) => cursor,
+        Some(other) => return Err(unexpected_response("SCAN cursor", &other)),
+        None => unreachable!(),
+    };
+    Ok((cursor, keys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_swap_db_cmd() {
+        assert_eq!(args(&swap_db_cmd(0, 3)), vec!["SWAPDB", "0", "3"]);
+    }
+
+    #[test]
+    fn test_scan_transaction_restores_the_client_database() {
+        let scanner = DatabaseScanner::new(
+            [2],
+            DatabaseScanOptions {
+                match_pattern: Some(b"user:*".to_vec()),
+                count: Some(100),
+                object_type: Some("hash".to_string()),
+            },
+        );
+        let pipeline = scanner.scan_transaction(2, 5);
+        let commands: Vec<_> = pipeline.cmd_iter().map(|cmd| args(cmd)).collect();
+        assert_eq!(
+            commands,
+            vec![
+                vec!["SELECT", "2"],
+                vec![
+                    "SCAN", "0", "MATCH", "user:*", "COUNT", "100", "TYPE", "hash"
+                ],
+                vec!["SELECT", "5"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_transaction_response() {
+        let response = Value::Array(vec![
+            Value::Okay,
+            Value::Array(vec![
+                Value::BulkString(b"17".to_vec()),
+                Value::Array(vec![
+                    Value::BulkString(b"a".to_vec()),
+                    Value::BulkString(b"b".to_vec()),
+                ]),
+            ]),
+            Value::Okay,
+        ]);
+        assert_eq!(
+            parse_transaction_response(response).unwrap(),
+            ("17".to_string(), vec![b"a".to_vec(), b"b".to_vec()])
+        );
+        assert!(parse_transaction_response(Value::Array(vec![Value::Okay])).is_err());
+    }
+
+    #[test]
+    fn test_empty_scanner_is_finished() {
+        let scanner = DatabaseScanner::new([], DatabaseScanOptions::default());
+        assert!(scanner.is_finished());
+    }
+}
@@ -0,0 +1,473 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Deserialization of responses into user types with serde.
+//!
+//! [`from_value`] reads a response the way a user type expects it, rather than the way the server
+//! encoded it: bulk strings are parsed into numbers and booleans, maps - and the flat key-value
+//! arrays of RESP2 - fill structs and maps by key, and RESP3 doubles, booleans, big numbers and
+//! verbatim strings deserialize like their RESP2 counterparts. Attributes are skipped, and server
+//! errors fail the deserialization.
+//!
+//! This is for Rust consumers of the client. The language bindings keep converting responses with
+//! `convert_to_expected_type`, whose
+//! rules follow `FromRedisValue` - a nil is `false`, and any non-zero integer is `true`.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use redis::cluster_routing::RoutingInfo;
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, SeqAccess, Visitor,
+};
+use thiserror::Error;
+
+use crate::client::Client;
+
+/// The error of a response that doesn't match the type it's deserialized into.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{0}")]
+pub struct DeserializeError(String);
+
+impl de::Error for DeserializeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl From<DeserializeError> for RedisError {
+    fn from(err: DeserializeError) -> Self {
+        RedisError::from((
+            ErrorKind::TypeError,
+            "Failed to deserialize the response",
+            err.0,
+        ))
+    }
+}
+
+type Result<T> = std::result::Result<T, DeserializeError>;
+
+/// Deserializes a response into `T`.
+pub fn from_value<T: DeserializeOwned>(value: Value) -> Result<T> {
+    T::deserialize(ValueDeserializer(value))
+}
+
+impl Client {
+    /// Sends a command, and deserializes its response into `T`.
+    pub async fn send_command_typed<T: DeserializeOwned>(
+        &mut self,
+        cmd: &mut Cmd,
+        routing: Option<RoutingInfo>,
+    ) -> RedisResult<T> {
+        let value = self.send_command(cmd, routing).await?;
+        Ok(from_value(value)?)
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Nil => "nil",
+        Value::Int(_) => "integer",
+        Value::BulkString(_) => "bulk string",
+        Value::Array(_) => "array",
+        Value::SimpleString(_) => "simple string",
+        Value::Okay => "OK",
+        Value::Map(_) => "map",
+        Value::Attribute { .. } => "attribute",
+        Value::Set(_) => "set",
+        Value::Double(_) => "double",
+        Value::Boolean(_) => "boolean",
+        Value::VerbatimString { .. } => "verbatim string",
+        Value::BigNumber(_) => "big number",
+        Value::Push { .. } => "push",
+        Value::ServerError(_) => "server error",
+    }
+}
+
+fn unexpected(value: &Value, expected: &str) -> DeserializeError {
+    DeserializeError(format!("expected {expected}, got {}", kind(value)))
+}
+
+/// Pairs the elements of a flat `[key, value, key, value, ...]` array.
+fn pairs(values: Vec<Value>) -> Result<Vec<(Value, Value)>> {
+    if !values.len().is_multiple_of(2) {
+        return Err(DeserializeError(format!(
+            "expected an array of key-value pairs, got {} elements",
+            values.len()
+        )));
+    }
+    let mut values = values.into_iter();
+    let mut pairs = Vec::with_capacity(values.len() / 2);
+    while let (Some(key), Some(value)) = (values.next(), values.next()) {
+        pairs.push((key, value));
+    }
+    Ok(pairs)
+}
+
+struct ValueDeserializer(Value);
+
+impl ValueDeserializer {
+    fn new(value: Value) -> Result<Self> {
+        match value {
+            Value::Attribute { data, .. } => Self::new(*data),
+            Value::ServerError(err) => Err(DeserializeError(RedisError::from(err).to_string())),
+            value => Ok(Self(value)),
+        }
+    }
+
+    /// Returns the text of a string-like value, or the decimal representation of a number.
+    fn into_text(self, expected: &str) -> Result<String> {
+        match self.0 {
+            Value::BulkString(bytes) => {
+                String::from_utf8(bytes).map_err(|_| DeserializeError("invalid UTF-8".into()))
+            }
+            Value::SimpleString(text) | Value::VerbatimString { text, .. } => Ok(text),
+            Value::Okay => Ok("OK".to_string()),
+            Value::Int(int) => Ok(int.to_string()),
+            Value::Double(double) => Ok(double.to_string()),
+            Value::BigNumber(number) => Ok(number.to_string()),
+            value => Err(unexpected(&value, expected)),
+        }
+    }
+
+    fn parse<N: FromStr>(self, expected: &str) -> Result<N> {
+        let text = self.into_text(expected)?;
+        text.parse()
+            .map_err(|_| DeserializeError(format!("expected {expected}, got {text:?}")))
+    }
+}
+
+macro_rules! deserialize_number {
+    ($($method:ident => $visit:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+                visitor.$visit(Self::new(self.0)?.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer {
+    type Error = DeserializeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::Nil => visitor.visit_unit(),
+            Value::Int(int) => visitor.visit_i64(int),
+            Value::Double(double) => visitor.visit_f64(double),
+            Value::Boolean(boolean) => visitor.visit_bool(boolean),
+            Value::BulkString(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => visitor.visit_string(text),
+                Err(err) => visitor.visit_byte_buf(err.into_bytes()),
+            },
+            Value::Array(values) | Value::Set(values) | Value::Push { data: values, .. } => {
+                visitor.visit_seq(SeqDeserializer(values.into_iter()))
+            }
+            Value::Map(entries) => visitor.visit_map(MapDeserializer::new(entries)),
+            value => visitor.visit_string(Self(value).into_text("a value")?),
+        }
+    }
+
+    deserialize_number! {
+        deserialize_i8 => visit_i8(i8),
+        deserialize_i16 => visit_i16(i16),
+        deserialize_i32 => visit_i32(i32),
+        deserialize_i64 => visit_i64(i64),
+        deserialize_i128 => visit_i128(i128),
+        deserialize_u8 => visit_u8(u8),
+        deserialize_u16 => visit_u16(u16),
+        deserialize_u32 => visit_u32(u32),
+        deserialize_u64 => visit_u64(u64),
+        deserialize_u128 => visit_u128(u128),
+        deserialize_f32 => visit_f32(f32),
+        deserialize_f64 => visit_f64(f64),
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::Boolean(boolean) => visitor.visit_bool(boolean),
+            Value::Int(int @ (0 | 1)) => visitor.visit_bool(int == 1),
+            value => match Self(value).into_text("a boolean")?.as_str() {
+                "1" | "true" => visitor.visit_bool(true),
+                "0" | "false" => visitor.visit_bool(false),
+                text => Err(DeserializeError(format!(
+                    "expected a boolean, got {text:?}"
+                ))),
+            },
+        }
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_string(Self::new(self.0)?.into_text("a string")?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::BulkString(bytes) => visitor.visit_byte_buf(bytes),
+            value => visitor.visit_byte_buf(Self(value).into_text("bytes")?.into_bytes()),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::Nil => visitor.visit_none(),
+            value => visitor.visit_some(Self(value)),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::Nil | Value::Okay => visitor.visit_unit(),
+            value => Err(unexpected(&value, "nil or OK")),
+        }
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::Array(values) | Value::Set(values) | Value::Push { data: values, .. } => {
+                visitor.visit_seq(SeqDeserializer(values.into_iter()))
+            }
+            // Each entry is deserialized as a `[key, value]` pair.
+            Value::Map(entries) => visitor.visit_seq(SeqDeserializer(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| Value::Array(vec![key, value]))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            )),
+            value => Err(unexpected(&value, "an array")),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        match Self::new(self.0)?.0 {
+            Value::Map(entries) => visitor.visit_map(MapDeserializer::new(entries)),
+            Value::Array(values) => visitor.visit_map(MapDeserializer::new(pairs(values)?)),
+            value => Err(unexpected(&value, "a map")),
+        }
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        self.deserialize_map(visitor)
+    }
+
+    /// Only unit variants are supported, deserialized from their names.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        let variant = Self::new(self.0)?.into_text("an enum variant name")?;
+        visitor.visit_enum(IntoDeserializer::<DeserializeError>::into_deserializer(
+            variant,
+        ))
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        visitor.visit_unit()
+    }
+}
+
+struct SeqDeserializer(std::vec::IntoIter<Value>);
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = DeserializeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        self.0
+            .next()
+            .map(|value| seed.deserialize(ValueDeserializer(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.0.len())
+    }
+}
+
+struct MapDeserializer {
+    entries: std::vec::IntoIter<(Value, Value)>,
+    value: Option<Value>,
+}
+
+impl MapDeserializer {
+    fn new(entries: Vec<(Value, Value)>) -> Self {
+        Self {
+            entries: entries.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = DeserializeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+        self.value = Some(value);
+        seed.deserialize(ValueDeserializer(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| DeserializeError("value requested before its key".into()))?;
+        seed.deserialize(ValueDeserializer(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::test_utils::bulk;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    #[serde(rename_all = "lowercase")]
+    enum Role {
+        Admin,
+        Member,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct User {
+        name: String,
+        age: u32,
+        score: f64,
+        active: bool,
+        role: Role,
+        nickname: Option<String>,
+    }
+
+    #[test]
+    fn test_struct_from_resp3_map_and_resp2_flat_array() {
+        let expected = User {
+            name: "ana".to_string(),
+            age: 31,
+            score: 9.5,
+            active: true,
+            role: Role::Admin,
+            nickname: None,
+        };
+        let entries = vec![
+            (bulk("name"), bulk("ana")),
+            (bulk("age"), bulk("31")),
+            (bulk("score"), Value::Double(9.5)),
+            (bulk("active"), Value::Boolean(true)),
+            (bulk("role"), bulk("admin")),
+        ];
+        let flat = entries
+            .iter()
+            .flat_map(|(key, value)| [key.clone(), value.clone()])
+            .collect();
+
+        assert_eq!(from_value::<User>(Value::Map(entries)).unwrap(), expected);
+        assert_eq!(from_value::<User>(Value::Array(flat)).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_bulk_strings_are_parsed_into_numbers_and_booleans() {
+        assert_eq!(from_value::<i64>(bulk("-42")).unwrap(), -42);
+        assert_eq!(from_value::<f32>(bulk("1.5")).unwrap(), 1.5);
+        assert_eq!(from_value::<u8>(Value::Int(7)).unwrap(), 7);
+        assert!(from_value::<bool>(bulk("1")).unwrap());
+        assert!(!from_value::<bool>(Value::Int(0)).unwrap());
+        assert_eq!(from_value::<String>(Value::Int(3)).unwrap(), "3");
+        assert_eq!(
+            from_value::<u8>(bulk("300")).unwrap_err().to_string(),
+            "expected u8, got \"300\""
+        );
+    }
+
+    #[test]
+    fn test_collections_options_and_attributes() {
+        let values = Value::Array(vec![bulk("a"), Value::Nil]);
+        assert_eq!(
+            from_value::<Vec<Option<String>>>(values).unwrap(),
+            [Some("a".to_string()), None]
+        );
+        let map = Value::Map(vec![(bulk("x"), Value::Int(1))]);
+        assert_eq!(
+            from_value::<HashMap<String, i32>>(map.clone()).unwrap(),
+            HashMap::from([("x".to_string(), 1)])
+        );
+        assert_eq!(
+            from_value::<Vec<(String, i32)>>(map).unwrap(),
+            [("x".to_string(), 1)]
+        );
+        let attributed = Value::Attribute {
+            data: Box::new(Value::Okay),
+            attributes: vec![],
+        };
+        from_value::<()>(attributed).unwrap();
+    }
+
+    #[test]
+    fn test_server_errors_fail_the_deserialization() {
+        let err = RedisError::from((ErrorKind::ResponseError, "boom"));
+        let redis_err =
+            RedisError::from(from_value::<String>(Value::ServerError(err.into())).unwrap_err());
+        assert_eq!(redis_err.kind(), ErrorKind::TypeError);
+    }
+}
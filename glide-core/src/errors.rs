@@ -1,6 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use redis::RedisError;
+use redis::{ErrorKind, RedisError, Value};
 
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq)]
@@ -10,6 +10,18 @@ pub enum RequestErrorType {
     Timeout = 2,
     Disconnect = 3,
     CircuitBreakerOpen = 4,
+    OutOfClientMemory = 5,
+    UnexpectedResponseShape = 6,
+    ClientClosing = 7,
+}
+
+/// Returns the error of a response that doesn't have the `expected` shape.
+pub(crate) fn unexpected_response(expected: &str, value: &Value) -> RedisError {
+    RedisError::from((
+        ErrorKind::TypeError,
+        "Response couldn't be converted to the expected type",
+        format!("(expected {expected}, response was {value:?})"),
+    ))
 }
 
 pub fn error_type(error: &RedisError) -> RequestErrorType {
@@ -21,6 +33,12 @@ pub fn error_type(error: &RedisError) -> RequestErrorType {
         RequestErrorType::ExecAbort
     } else if matches!(error.kind(), redis::ErrorKind::CircuitBreakerOpen) {
         RequestErrorType::CircuitBreakerOpen
+    } else if matches!(error.kind(), redis::ErrorKind::OutOfClientMemory) {
+        RequestErrorType::OutOfClientMemory
+    } else if matches!(error.kind(), redis::ErrorKind::UnexpectedResponseShape) {
+        RequestErrorType::UnexpectedResponseShape
+    } else if matches!(error.kind(), redis::ErrorKind::ClientClosing) {
+        RequestErrorType::ClientClosing
     } else {
         RequestErrorType::Unspecified
     }
@@ -47,4 +65,31 @@ mod tests {
         ));
         assert_eq!(error_type(&err), RequestErrorType::CircuitBreakerOpen);
     }
+
+    #[test]
+    fn out_of_client_memory_error_type() {
+        let err = redis::RedisError::from((
+            redis::ErrorKind::OutOfClientMemory,
+            "Client memory budget exceeded",
+        ));
+        assert_eq!(error_type(&err), RequestErrorType::OutOfClientMemory);
+    }
+
+    #[test]
+    fn unexpected_response_shape_error_type() {
+        let err = redis::RedisError::from((
+            redis::ErrorKind::UnexpectedResponseShape,
+            "Response doesn't match the expected type",
+        ));
+        assert_eq!(error_type(&err), RequestErrorType::UnexpectedResponseShape);
+    }
+
+    #[test]
+    fn client_closing_error_type() {
+        let err = redis::RedisError::from((
+            redis::ErrorKind::ClientClosing,
+            "The client shut down before the request completed",
+        ));
+        assert_eq!(error_type(&err), RequestErrorType::ClientClosing);
+    }
 }
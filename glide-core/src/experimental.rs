@@ -0,0 +1,111 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Runtime feature flags for experimental subsystems.
+//!
+//! Large new subsystems ship disabled, and are enabled per client by listing their flag in the
+//! connection request's `experimental_features` - so they can be rolled out gradually, and turned
+//! off again, without rebuilding the core or the wrappers. Every flag is a variant of
+//! [`ExperimentalFeature`]; the variant's documentation describes the subsystem it enables.
+//!
+//! Flags the core doesn't know are logged and ignored, so that applications and wrappers built
+//! against another version of the core - which may list flags that were since removed or not yet
+//! added - keep connecting.
+
+use std::str::FromStr;
+
+use logger_core::log_warn;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIter, EnumString, IntoStaticStr};
+
+/// The registry of the experimental subsystems that can be enabled per client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum ExperimentalFeature {
+    /// Identical read-only commands sent while one of them is in flight wait for its response,
+    /// instead of being sent. A command only waits for one sent after the client's last write.
+    ReadCoalescing,
+}
+
+impl ExperimentalFeature {
+    /// The name of the flag, as listed in connection requests.
+    pub fn name(self) -> &'static str {
+        self.into()
+    }
+
+    /// Returns all the registered flags.
+    pub fn all() -> impl Iterator<Item = ExperimentalFeature> {
+        Self::iter()
+    }
+}
+
+/// The experimental features enabled for a client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExperimentalFeatures {
+    enabled: Vec<ExperimentalFeature>,
+}
+
+impl ExperimentalFeatures {
+    /// Enables the features named in `names`. Unknown names are logged and ignored.
+    pub fn from_names<S: AsRef<str>>(names: impl IntoIterator<Item = S>) -> Self {
+        let mut features = Self::default();
+        for name in names {
+            let name = name.as_ref();
+            match ExperimentalFeature::from_str(name) {
+                Ok(feature) => features.enable(feature),
+                Err(_) => log_warn(
+                    "experimental_features",
+                    format!("Ignoring the unknown experimental feature `{name}`"),
+                ),
+            }
+        }
+        features
+    }
+
+    /// Enables `feature`.
+    pub fn enable(&mut self, feature: ExperimentalFeature) {
+        if !self.is_enabled(feature) {
+            self.enabled.push(feature);
+        }
+    }
+
+    /// Returns whether `feature` is enabled.
+    pub fn is_enabled(&self, feature: ExperimentalFeature) -> bool {
+        self.enabled.contains(&feature)
+    }
+
+    /// Returns whether no feature is enabled.
+    pub fn is_empty(&self) -> bool {
+        self.enabled.is_empty()
+    }
+
+    /// Returns the names of the enabled features.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.enabled.iter().map(|feature| feature.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features_are_enabled_by_name() {
+        let features = ExperimentalFeatures::from_names(["read_coalescing", "read_coalescing"]);
+        assert!(features.is_enabled(ExperimentalFeature::ReadCoalescing));
+        assert_eq!(features.names().collect::<Vec<_>>(), ["read_coalescing"]);
+    }
+
+    #[test]
+    fn test_unknown_features_are_ignored() {
+        let features = ExperimentalFeatures::from_names(["io_uring", "READ_COALESCING"]);
+        assert!(features.is_empty());
+        assert!(!features.is_enabled(ExperimentalFeature::ReadCoalescing));
+    }
+
+    #[test]
+    fn test_flag_names_round_trip() {
+        for feature in ExperimentalFeature::all() {
+            assert_eq!(ExperimentalFeature::from_str(feature.name()), Ok(feature));
+        }
+    }
+}
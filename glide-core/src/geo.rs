@@ -0,0 +1,604 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Typed builders for geospatial commands.
+//!
+//! [`GeoAddBuilder`] and [`GeoSearchBuilder`] build `GEOADD` and `GEOSEARCH` commands from typed
+//! options, and [`GeoSearchBuilder::parse_response`] turns the nested arrays returned for
+//! `WITHDIST`, `WITHHASH` and `WITHCOORD` into [`GeoSearchResult`]s.
+
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+
+use crate::errors::unexpected_response;
+
+/// The latitude limits of the Web Mercator projection used by the server.
+const MAX_LATITUDE: f64 = 85.05112878;
+const MAX_LONGITUDE: f64 = 180.0;
+
+/// A point on the map.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoCoordinates {
+    pub longitude: f64,
+    pub latitude: f64,
+}
+
+impl GeoCoordinates {
+    fn validate(&self) -> RedisResult<()> {
+        if !(-MAX_LONGITUDE..=MAX_LONGITUDE).contains(&self.longitude)
+            || !(-MAX_LATITUDE..=MAX_LATITUDE).contains(&self.latitude)
+        {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid coordinates",
+                format!(
+                    "longitude must be within ±{MAX_LONGITUDE} and latitude within ±{MAX_LATITUDE}, got ({}, {})",
+                    self.longitude, self.latitude
+                ),
+            )));
+        }
+        Ok(())
+    }
+
+    /// Parses a `[longitude, latitude]` pair, as returned by `GEOPOS` and `WITHCOORD`.
+    fn from_value(value: Value) -> RedisResult<Self> {
+        let Value::Array(mut pair) = value else {
+            return Err(unexpected_response("coordinates", &value));
+        };
+        if pair.len() != 2 {
+            return Err(unexpected_response("coordinates", &Value::Array(pair)));
+        }
+        let latitude = value_to_f64(pair.pop().unwrap_or(Value::Nil))?;
+        let longitude = value_to_f64(pair.pop().unwrap_or(Value::Nil))?;
+        Ok(Self {
+            longitude,
+            latitude,
+        })
+    }
+}
+
+fn value_to_f64(value: Value) -> RedisResult<f64> {
+    let parsed = match &value {
+        Value::Double(double) => Some(*double),
+        Value::Int(int) => Some(*int as f64),
+        Value::BulkString(bytes) => std::str::from_utf8(bytes).ok().and_then(|s| s.parse().ok()),
+        Value::SimpleString(string) => string.parse().ok(),
+        _ => None,
+    };
+    parsed.ok_or_else(|| unexpected_response("number", &value))
+}
+
+/// The unit of distances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoUnit {
+    Meters,
+    Kilometers,
+    Miles,
+    Feet,
+}
+
+impl GeoUnit {
+    fn as_arg(self) -> &'static str {
+        match self {
+            GeoUnit::Meters => "M",
+            GeoUnit::Kilometers => "KM",
+            GeoUnit::Miles => "MI",
+            GeoUnit::Feet => "FT",
+        }
+    }
+}
+
+/// Restricts which members `GEOADD` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoAddCondition {
+    /// Only update existing members (`XX`).
+    OnlyIfExists,
+    /// Only add new members (`NX`).
+    OnlyIfDoesNotExist,
+}
+
+/// Builder for `GEOADD key [NX|XX] [CH] longitude latitude member ...`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoAddBuilder {
+    key: Vec<u8>,
+    condition: Option<GeoAddCondition>,
+    changed: bool,
+    members: Vec<(GeoCoordinates, Vec<u8>)>,
+}
+
+impl GeoAddBuilder {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            condition: None,
+            changed: false,
+            members: Vec::new(),
+        }
+    }
+
+    pub fn condition(mut self, condition: GeoAddCondition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// If `true`, sends `CH` so that the reply counts updated members as well as added ones.
+    pub fn changed(mut self, changed: bool) -> Self {
+        self.changed = changed;
+        self
+    }
+
+    pub fn member(mut self, member: impl Into<Vec<u8>>, coordinates: GeoCoordinates) -> Self {
+        self.members.push((coordinates, member.into()));
+        self
+    }
+
+    pub fn build(&self) -> RedisResult<Cmd> {
+        if self.members.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "GEOADD requires at least one member",
+            )));
+        }
+        let mut cmd = redis::cmd("GEOADD");
+        cmd.arg(&self.key);
+        match self.condition {
+            Some(GeoAddCondition::OnlyIfExists) => {
+                cmd.arg("XX");
+            }
+            Some(GeoAddCondition::OnlyIfDoesNotExist) => {
+                cmd.arg("NX");
+            }
+            None => {}
+        }
+        if self.changed {
+            cmd.arg("CH");
+        }
+        for (coordinates, member) in &self.members {
+            coordinates.validate()?;
+            cmd.arg(coordinates.longitude)
+                .arg(coordinates.latitude)
+                .arg(member);
+        }
+        Ok(cmd)
+    }
+}
+
+/// The center of a `GEOSEARCH`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoSearchOrigin {
+    /// The position of an existing member (`FROMMEMBER`).
+    Member(Vec<u8>),
+    /// A given position (`FROMLONLAT`).
+    Coordinates(GeoCoordinates),
+}
+
+/// The area searched by `GEOSEARCH`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GeoSearchShape {
+    /// A circle around the origin (`BYRADIUS`).
+    Radius { radius: f64, unit: GeoUnit },
+    /// A rectangle centered on the origin (`BYBOX`).
+    Box {
+        width: f64,
+        height: f64,
+        unit: GeoUnit,
+    },
+}
+
+/// The order of `GEOSEARCH` results, by distance from the origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeoSortOrder {
+    Ascending,
+    Descending,
+}
+
+/// Builder for `GEOSEARCH key FROMMEMBER member|FROMLONLAT longitude latitude
+/// BYRADIUS radius unit|BYBOX width height unit [ASC|DESC] [COUNT count [ANY]] [WITHCOORD]
+/// [WITHDIST] [WITHHASH]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoSearchBuilder {
+    key: Vec<u8>,
+    origin: GeoSearchOrigin,
+    shape: GeoSearchShape,
+    order: Option<GeoSortOrder>,
+    count: Option<(u64, bool)>,
+    with_coord: bool,
+    with_dist: bool,
+    with_hash: bool,
+}
+
+impl GeoSearchBuilder {
+    pub fn new(key: impl Into<Vec<u8>>, origin: GeoSearchOrigin, shape: GeoSearchShape) -> Self {
+        Self {
+            key: key.into(),
+            origin,
+            shape,
+            order: None,
+            count: None,
+            with_coord: false,
+            with_dist: false,
+            with_hash: false,
+        }
+    }
+
+    pub fn order(mut self, order: GeoSortOrder) -> Self {
+        self.order = Some(order);
+        self
+    }
+
+    /// Returns at most `count` results. With `any`, the server returns as soon as it found enough
+    /// matches, so the results aren't necessarily the closest ones.
+    pub fn count(mut self, count: u64, any: bool) -> Self {
+        self.count = Some((count, any));
+        self
+    }
+
+    pub fn with_coord(mut self, with_coord: bool) -> Self {
+        self.with_coord = with_coord;
+        self
+    }
+
+    pub fn with_dist(mut self, with_dist: bool) -> Self {
+        self.with_dist = with_dist;
+        self
+    }
+
+    pub fn with_hash(mut self, with_hash: bool) -> Self {
+        self.with_hash = with_hash;
+        self
+    }
+
+    pub fn build(&self) -> RedisResult<Cmd> {
+        let mut cmd = redis::cmd("GEOSEARCH");
+        cmd.arg(&self.key);
+        match &self.origin {
+            GeoSearchOrigin::Member(member) => {
+                cmd.arg("FROMMEMBER").arg(member);
+            }
+            GeoSearchOrigin::Coordinates(coordinates) => {
+                coordinates.validate()?;
+                cmd.arg("FROMLONLAT")
+                    .arg(coordinates.longitude)
+                    .arg(coordinates.latitude);
+            }
+        }
+        let (dimensions, unit) = match self.shape {
+            GeoSearchShape::Radius { radius, unit } => {
+                cmd.arg("BYRADIUS").arg(radius);
+                (vec![radius], unit)
+            }
+            GeoSearchShape::Box {
+                width,
+                height,
+                unit,
+            } => {
+                cmd.arg("BYBOX").arg(width).arg(height);
+                (vec![width, height], unit)
+            }
+        };
+        if dimensions
+            .iter()
+            .any(|dimension| !dimension.is_finite() || *dimension < 0.0)
+        {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "GEOSEARCH dimensions must be non-negative numbers",
+            )));
+        }
+        cmd.arg(unit.as_arg());
+        match self.order {
+            Some(GeoSortOrder::Ascending) => {
+                cmd.arg("ASC");
+            }
+            Some(GeoSortOrder::Descending) => {
+                cmd.arg("DESC");
+            }
+            None => {}
+        }
+        if let Some((count, any)) = self.count {
+            if count == 0 {
+                return Err(RedisError::from((
+                    ErrorKind::ClientError,
+                    "GEOSEARCH COUNT must be positive",
+                )));
+            }
+            cmd.arg("COUNT").arg(count);
+            if any {
+                cmd.arg("ANY");
+            }
+        }
+        if self.with_coord {
+            cmd.arg("WITHCOORD");
+        }
+        if self.with_dist {
+            cmd.arg("WITHDIST");
+        }
+        if self.with_hash {
+            cmd.arg("WITHHASH");
+        }
+        Ok(cmd)
+    }
+
+    /// Parses the response of the command built by this builder. Accepts both the raw response and
+    /// the response converted by the client, which groups the details of each member in an array.
+    pub fn parse_response(&self, value: Value) -> RedisResult<Vec<GeoSearchResult>> {
+        let Value::Array(items) = value else {
+            return Err(unexpected_response("GEOSEARCH response", &value));
+        };
+        let has_details = self.with_coord || self.with_dist || self.with_hash;
+        items
+            .into_iter()
+            .map(|item| {
+                if !has_details {
+                    return Ok(GeoSearchResult::new(value_to_bytes(item)?));
+                }
+                let Value::Array(mut details) = item else {
+                    return Err(unexpected_response("GEOSEARCH result", &item));
+                };
+                if details.is_empty() {
+                    return Err(unexpected_response(
+                        "GEOSEARCH result",
+                        &Value::Array(details),
+                    ));
+                }
+                let member = value_to_bytes(details.remove(0))?;
+                // The converted response holds the details in a nested array. With only
+                // `WITHCOORD`, the raw coordinates are an array too, of numbers.
+                let only_coord = self.with_coord && !self.with_dist && !self.with_hash;
+                if let [Value::Array(nested)] = details.as_mut_slice()
+                    && (!only_coord || matches!(nested.first(), Some(Value::Array(_))))
+                {
+                    details = std::mem::take(nested);
+                }
+                self.parse_details(member, details)
+            })
+            .collect()
+    }
+
+    /// Parses the details of a member, in the order the server returns them - distance, hash, and
+    /// coordinates.
+    fn parse_details(&self, member: Vec<u8>, details: Vec<Value>) -> RedisResult<GeoSearchResult> {
+        let expected = [self.with_dist, self.with_hash, self.with_coord]
+            .into_iter()
+            .filter(|with| *with)
+            .count();
+        if details.len() != expected {
+            return Err(unexpected_response(
+                "GEOSEARCH result details",
+                &Value::Array(details),
+            ));
+        }
+        let mut details = details.into_iter();
+        let mut result = GeoSearchResult::new(member);
+        if self.with_dist {
+            result.distance = details.next().map(value_to_f64).transpose()?;
+        }
+        if self.with_hash {
+            result.hash = match details.next() {
+                Some(Value::Int(hash)) => Some(hash),
+                Some(other) => return Err(unexpected_response("geohash", &other)),
+                None => None,
+            };
+        }
+        if self.with_coord {
+            result.coordinates = details.next().map(GeoCoordinates::from_value).transpose()?;
+        }
+        Ok(result)
+    }
+}
+
+fn value_to_bytes(value: Value) -> RedisResult<Vec<u8>> {
+    match value {
+        Value::BulkString(bytes) => Ok(bytes),
+        Value::SimpleString(string) => Ok(string.into_bytes()),
+        other => Err(unexpected_response("member", &other)),
+    }
+}
+
+/// A member found by `GEOSEARCH`. The details are only set when requested.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeoSearchResult {
+    pub member: Vec<u8>,
+    /// The distance from the origin, in the unit of the search shape.
+    pub distance: Option<f64>,
+    /// The raw geohash of the position.
+    pub hash: Option<i64>,
+    pub coordinates: Option<GeoCoordinates>,
+}
+
+impl GeoSearchResult {
+    fn new(member: Vec<u8>) -> Self {
+        Self {
+            member,
+            distance: None,
+            hash: None,
+            coordinates: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bulk;
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    const PALERMO: GeoCoordinates = GeoCoordinates {
+        longitude: 13.361389,
+        latitude: 38.115556,
+    };
+
+    #[test]
+    fn test_geoadd_builder() {
+        let cmd = GeoAddBuilder::new("places")
+            .condition(GeoAddCondition::OnlyIfDoesNotExist)
+            .changed(true)
+            .member("Palermo", PALERMO)
+            .build()
+            .unwrap();
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "GEOADD",
+                "places",
+                "NX",
+                "CH",
+                "13.361389",
+                "38.115556",
+                "Palermo"
+            ]
+        );
+
+        assert!(GeoAddBuilder::new("places").build().is_err());
+        let invalid = GeoCoordinates {
+            longitude: 0.0,
+            latitude: 90.0,
+        };
+        assert!(
+            GeoAddBuilder::new("places")
+                .member("pole", invalid)
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_geosearch_builder() {
+        let cmd = GeoSearchBuilder::new(
+            "places",
+            GeoSearchOrigin::Coordinates(PALERMO),
+            GeoSearchShape::Box {
+                width: 400.0,
+                height: 200.5,
+                unit: GeoUnit::Kilometers,
+            },
+        )
+        .order(GeoSortOrder::Ascending)
+        .count(2, true)
+        .with_coord(true)
+        .with_dist(true)
+        .build()
+        .unwrap();
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "GEOSEARCH",
+                "places",
+                "FROMLONLAT",
+                "13.361389",
+                "38.115556",
+                "BYBOX",
+                "400.0",
+                "200.5",
+                "KM",
+                "ASC",
+                "COUNT",
+                "2",
+                "ANY",
+                "WITHCOORD",
+                "WITHDIST"
+            ]
+        );
+
+        let builder = GeoSearchBuilder::new(
+            "places",
+            GeoSearchOrigin::Member(b"Palermo".to_vec()),
+            GeoSearchShape::Radius {
+                radius: -1.0,
+                unit: GeoUnit::Meters,
+            },
+        );
+        assert!(builder.build().is_err());
+    }
+
+    #[test]
+    fn test_parse_geosearch_response() {
+        let builder = GeoSearchBuilder::new(
+            "places",
+            GeoSearchOrigin::Member(b"Palermo".to_vec()),
+            GeoSearchShape::Radius {
+                radius: 200.0,
+                unit: GeoUnit::Kilometers,
+            },
+        )
+        .with_coord(true)
+        .with_dist(true)
+        .with_hash(true);
+        let expected = vec![GeoSearchResult {
+            member: b"Palermo".to_vec(),
+            distance: Some(0.0),
+            hash: Some(3479099956230698),
+            coordinates: Some(GeoCoordinates {
+                longitude: 13.5,
+                latitude: 38.25,
+            }),
+        }];
+
+        let raw = Value::Array(vec![Value::Array(vec![
+            bulk("Palermo"),
+            bulk("0.0000"),
+            Value::Int(3479099956230698),
+            Value::Array(vec![bulk("13.5"), bulk("38.25")]),
+        ])]);
+        assert_eq!(builder.parse_response(raw).unwrap(), expected);
+
+        let converted = Value::Array(vec![Value::Array(vec![
+            bulk("Palermo"),
+            Value::Array(vec![
+                Value::Double(0.0),
+                Value::Int(3479099956230698),
+                Value::Array(vec![Value::Double(13.5), Value::Double(38.25)]),
+            ]),
+        ])]);
+        assert_eq!(builder.parse_response(converted).unwrap(), expected);
+
+        let malformed = Value::Array(vec![Value::Array(vec![bulk("Palermo"), bulk("0.0")])]);
+        assert!(builder.parse_response(malformed).is_err());
+    }
+
+    #[test]
+    fn test_parse_geosearch_response_with_coordinates_only() {
+        let builder = GeoSearchBuilder::new(
+            "places",
+            GeoSearchOrigin::Member(b"Palermo".to_vec()),
+            GeoSearchShape::Radius {
+                radius: 200.0,
+                unit: GeoUnit::Kilometers,
+            },
+        );
+        let members = Value::Array(vec![bulk("Palermo"), bulk("Catania")]);
+        let results = builder.parse_response(members).unwrap();
+        assert_eq!(results[1], GeoSearchResult::new(b"Catania".to_vec()));
+
+        let builder = builder.with_coord(true);
+        let coordinates = Value::Array(vec![Value::Array(vec![
+            bulk("Palermo"),
+            Value::Array(vec![bulk("13.5"), bulk("38.25")]),
+        ])]);
+        let converted = Value::Array(vec![Value::Array(vec![
+            bulk("Palermo"),
+            Value::Array(vec![Value::Array(vec![
+                Value::Double(13.5),
+                Value::Double(38.25),
+            ])]),
+        ])]);
+        let expected = Some(GeoCoordinates {
+            longitude: 13.5,
+            latitude: 38.25,
+        });
+        assert_eq!(
+            builder.parse_response(coordinates).unwrap()[0].coordinates,
+            expected
+        );
+        assert_eq!(
+            builder.parse_response(converted).unwrap()[0].coordinates,
+            expected
+        );
+    }
+}
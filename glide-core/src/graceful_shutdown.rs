@@ -0,0 +1,131 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Graceful shutdown of the socket listener on SIGTERM / SIGINT.
+//!
+//! When `GlideRuntimeConfig::handle_shutdown_signals` is set, the first socket listener installs
+//! handlers for SIGTERM and SIGINT. On a signal, the listeners stop accepting connections and
+//! reading requests, every connected wrapper receives a shutdown frame, and the process exits
+//! once the in-flight requests complete, or when `shutdown_grace_period` elapses - whichever
+//! comes first. Telemetry is flushed before exiting.
+
+use std::sync::Once;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use logger_core::{log_info, log_warn};
+use once_cell::sync::Lazy;
+use telemetrylib::GlideOpenTelemetry;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::watch;
+
+use crate::runtime_config::GlideRuntimeConfig;
+
+/// Set to `true` once a shutdown starts.
+static SHUTDOWN: Lazy<watch::Sender<bool>> = Lazy::new(|| watch::channel(false).0);
+
+/// The number of socket requests that didn't complete yet.
+static INFLIGHT_REQUESTS: AtomicUsize = AtomicUsize::new(0);
+
+/// How often the in-flight requests are checked while draining.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Counts a socket request as in-flight until dropped.
+pub(crate) struct InflightRequest(());
+
+impl InflightRequest {
+    pub(crate) fn new() -> Self {
+        INFLIGHT_REQUESTS.fetch_add(1, Ordering::Relaxed);
+        Self(())
+    }
+}
+
+impl Drop for InflightRequest {
+    fn drop(&mut self) {
+        INFLIGHT_REQUESTS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Completes once a shutdown has started.
+pub(crate) async fn shutdown_started() {
+    let mut receiver = SHUTDOWN.subscribe();
+    // The sender is static, so the channel is never closed.
+    let _ = receiver.wait_for(|started| *started).await;
+}
+
+/// Installs the signal handlers, if enabled in the runtime configuration.
+/// Must be called from within the Glide runtime. Only the first call has an effect.
+pub(crate) fn install_signal_handlers() {
+    static INSTALLED: Once = Once::new();
+    let config = GlideRuntimeConfig::get();
+    if !config.handle_shutdown_signals {
+        return;
+    }
+    let grace_period = config.shutdown_grace_period;
+    INSTALLED.call_once(|| {
+        tokio::spawn(async move {
+            let (Ok(mut terminate), Ok(mut interrupt)) = (
+                signal(SignalKind::terminate()),
+                signal(SignalKind::interrupt()),
+            ) else {
+                log_warn(
+                    "graceful shutdown",
+                    "Failed to install the SIGTERM and SIGINT handlers",
+                );
+                return;
+            };
+            let signal_name = tokio::select! {
+                _ = terminate.recv() => "SIGTERM",
+                _ = interrupt.recv() => "SIGINT",
+            };
+            log_info(
+                "graceful shutdown",
+                format!("Received {signal_name}, shutting down"),
+            );
+            let drained = shutdown(grace_period).await;
+            std::process::exit(if drained { 0 } else { 1 });
+        });
+    });
+}
+
+/// Starts the shutdown, and waits for the in-flight requests to complete.
+/// Returns whether all the requests completed within the grace period.
+async fn shutdown(grace_period: Duration) -> bool {
+    SHUTDOWN.send_replace(true);
+    let drained = wait_for_inflight_requests(grace_period).await;
+    if !drained {
+        log_warn(
+            "graceful shutdown",
+            format!(
+                "{} requests didn't complete within the grace period of {grace_period:?}",
+                INFLIGHT_REQUESTS.load(Ordering::Relaxed)
+            ),
+        );
+    }
+    GlideOpenTelemetry::shutdown();
+    drained
+}
+
+async fn wait_for_inflight_requests(grace_period: Duration) -> bool {
+    tokio::time::timeout(grace_period, async {
+        while INFLIGHT_REQUESTS.load(Ordering::Relaxed) > 0 {
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_wait_for_inflight_requests() {
+        let request = InflightRequest::new();
+        assert!(!wait_for_inflight_requests(Duration::from_millis(50)).await);
+
+        let wait = tokio::spawn(wait_for_inflight_requests(Duration::from_secs(5)));
+        drop(request);
+        assert!(wait.await.unwrap());
+    }
+}
@@ -6,8 +6,8 @@ use aws_sigv4::http_request::{
 use aws_sigv4::sign::v4;
 use logger_core::{log_debug, log_error, log_info, log_warn};
 use rand::Rng;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::time::SystemTime;
 use strum_macros::IntoStaticStr;
@@ -168,6 +168,8 @@ pub(crate) struct IamTokenState {
     service_type: ServiceType,
     /// Token refresh interval in seconds
     refresh_interval_seconds: u32,
+    /// Expiry of the AWS credentials used to sign the last token, shared by all clones
+    credentials_expiry: Arc<Mutex<Option<SystemTime>>>,
 }
 
 /// IAM-based token manager for ElastiCache/MemoryDB.
@@ -232,6 +234,7 @@ impl IAMTokenManager {
             service_type,
             refresh_interval_seconds: validated_refresh_interval
                 .unwrap_or(DEFAULT_REFRESH_INTERVAL_SECONDS),
+            credentials_expiry: Arc::new(Mutex::new(None)),
         };
 
         // Generate initial token using the state
@@ -399,6 +402,12 @@ impl IAMTokenManager {
         }
     }
 
+    /// Signals the background refresh task to stop, without waiting for it. Unlike
+    /// [`Self::stop_refresh_task`], it can be called on a shared manager.
+    pub fn stop(&self) {
+        self.shutdown_notify.notify_one();
+    }
+
     /// Set a new cached token (static version for use in background tasks)
     async fn set_cached_token_static(cached_token: &Arc<RwLock<String>>, new_token: String) {
         let mut token_guard = cached_token.write().await;
@@ -421,6 +430,21 @@ impl IAMTokenManager {
         self.token_changed.store(false, Ordering::Release)
     }
 
+    /// Expiry of the AWS credentials used to sign the current token.
+    /// `None` if the credentials don't expire, e.g. long-term access keys.
+    pub fn credentials_expiry(&self) -> Option<SystemTime> {
+        *self
+            .iam_token_state
+            .credentials_expiry
+            .lock()
+            .expect("Lock poisoned")
+    }
+
+    /// Returns a handle to the expiry of the AWS credentials, updated on every token refresh.
+    pub(crate) fn credentials_expiry_handle(&self) -> Arc<Mutex<Option<SystemTime>>> {
+        Arc::clone(&self.iam_token_state.credentials_expiry)
+    }
+
     /// Create a lightweight handle to the token cache for use by the reconnection path.
     ///
     /// The returned handle shares the same `Arc`s as this manager, so any token
@@ -444,6 +468,7 @@ impl IAMTokenManager {
         // Fetch fresh credentials on every token generation to handle credential rotation
         // (e.g., EC2 instance profile credentials rotate every ~6 hours)
         let creds = get_signing_identity(&state.region, state.service_type).await?;
+        *state.credentials_expiry.lock().expect("Lock poisoned") = creds.expiry();
         let identity_value = creds.into();
 
         let mut signing_settings = SigningSettings::default();
@@ -599,6 +624,7 @@ mod tests {
             username: username.to_string(),
             service_type,
             refresh_interval_seconds: DEFAULT_REFRESH_INTERVAL_SECONDS,
+            credentials_expiry: Arc::new(Mutex::new(None)),
         }
     }
 
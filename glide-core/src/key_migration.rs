@@ -0,0 +1,703 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Migration of keys between deployments with `DUMP` and `RESTORE`.
+//!
+//! [`migrate_keys`] scans the keys matching a pattern on a source client, and copies them to a
+//! destination client - for example, from one cluster to another. Each batch of scanned keys is
+//! dumped with a pipeline of `DUMP` and `PTTL` on the source, and restored with a pipeline of
+//! `RESTORE` on the destination. In cluster mode the pipelines are split by slot, so the source
+//! and the destination may have different topologies.
+//!
+//! Hashes, lists and sorted sets that are larger than [`MigrationOptions::big_key_threshold`] are
+//! copied in chunks of elements instead, so that a single big key doesn't block the servers with
+//! a huge `DUMP` payload. Chunked copies aren't atomic: readers of the destination may observe a
+//! partially copied key, and changes to the source key during its copy may be partially applied.
+
+use std::time::{Duration, SystemTime};
+
+use redis::{
+    ClusterScanArgs, Cmd, ErrorKind, Pipeline, PipelineRetryStrategy, RedisError, RedisResult,
+    ScanStateRC, Value,
+};
+
+use crate::client::{Client, FINISHED_SCAN_CURSOR};
+use crate::cluster_scan_container::{get_cluster_scan_cursor, remove_scan_state_cursor};
+use crate::databases::parse_scan_response;
+use crate::errors::unexpected_response;
+
+/// Default number of keys scanned and migrated by each batch.
+pub const DEFAULT_MIGRATION_BATCH_SIZE: usize = 100;
+/// Default memory usage above which hashes, lists and sorted sets are copied in chunks (8 MiB).
+pub const DEFAULT_BIG_KEY_THRESHOLD: u64 = 8 * 1024 * 1024;
+/// Default number of elements copied by each command of a chunked copy.
+pub const DEFAULT_MIGRATION_CHUNK_SIZE: usize = 1000;
+
+/// The behavior of [`migrate_keys`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationOptions {
+    /// Whether to replace keys that already exist on the destination. Otherwise, such keys are
+    /// reported as failures and left unchanged.
+    pub replace: bool,
+    /// Whether to restore TTLs as absolute expiration times, so that the time spent migrating a
+    /// key doesn't extend its TTL.
+    pub absolute_ttl: bool,
+    /// Number of keys scanned and migrated by each batch.
+    pub batch_size: usize,
+    /// Hashes, lists and sorted sets whose `MEMORY USAGE` exceeds this number of bytes are copied
+    /// in chunks instead of with `DUMP`. `None` dumps all the keys.
+    pub big_key_threshold: Option<u64>,
+    /// Number of elements copied by each command of a chunked copy.
+    pub chunk_size: usize,
+}
+
+impl Default for MigrationOptions {
+    fn default() -> Self {
+        Self {
+            replace: false,
+            absolute_ttl: false,
+            batch_size: DEFAULT_MIGRATION_BATCH_SIZE,
+            big_key_threshold: Some(DEFAULT_BIG_KEY_THRESHOLD),
+            chunk_size: DEFAULT_MIGRATION_CHUNK_SIZE,
+        }
+    }
+}
+
+impl MigrationOptions {
+    fn validate(&self) -> RedisResult<()> {
+        if self.batch_size == 0 || self.chunk_size == 0 {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "Invalid migration options",
+                "batch_size and chunk_size must be greater than 0".to_string(),
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A key that couldn't be migrated.
+#[derive(Debug)]
+pub struct KeyMigrationFailure {
+    pub key: Vec<u8>,
+    pub error: RedisError,
+}
+
+/// The progress of [`migrate_keys`], reported after every batch.
+#[derive(Debug, Default)]
+pub struct MigrationProgress {
+    /// Number of keys returned by the scan so far.
+    pub scanned_keys: u64,
+    /// Number of keys migrated with `DUMP` and `RESTORE`.
+    pub restored_keys: u64,
+    /// Number of big keys migrated in chunks.
+    pub chunked_keys: u64,
+    /// Number of scanned keys that were deleted or expired before they were migrated.
+    pub missing_keys: u64,
+    /// The keys that couldn't be migrated, e.g. because they exist on the destination.
+    pub failures: Vec<KeyMigrationFailure>,
+}
+
+impl MigrationProgress {
+    fn record_failure(&mut self, key: &[u8], error: RedisError) {
+        self.failures.push(KeyMigrationFailure {
+            key: key.to_vec(),
+            error,
+        });
+    }
+}
+
+/// The types of keys that can be copied in chunks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ChunkedType {
+    Hash,
+    List,
+    ZSet,
+}
+
+impl ChunkedType {
+    fn from_type_response(response: &Value) -> Option<Self> {
+        let key_type = match response {
+            Value::SimpleString(key_type) => key_type.as_bytes(),
+            Value::BulkString(key_type) => key_type.as_slice(),
+            _ => return None,
+        };
+        match key_type {
+            b"hash" => Some(Self::Hash),
+            b"list" => Some(Self::List),
+            b"zset" => Some(Self::ZSet),
+            _ => None,
+        }
+    }
+}
+
+/// Iterates the keys matching a pattern with `SCAN` in standalone mode, and with a cluster scan
+/// in cluster mode.
+struct KeyScanner {
+    pattern: Vec<u8>,
+    count: usize,
+    /// The `SCAN` cursor, or the id of the cluster scan state. `None` before the first batch.
+    cursor: Option<String>,
+    finished: bool,
+}
+
+impl KeyScanner {
+    async fn next_batch(&mut self, client: &mut Client) -> RedisResult<Vec<Vec<u8>>> {
+        let (cursor, keys) = if client.is_cluster().await? {
+            let scan_state = match self.cursor.take() {
+                Some(id) => {
+                    let scan_state = get_cluster_scan_cursor(id.clone())?;
+                    remove_scan_state_cursor(id);
+                    scan_state
+                }
+                None => ScanStateRC::new(),
+            };
+            let args = ClusterScanArgs::builder()
+                .with_match_pattern(self.pattern.clone())
+                .with_count(u32::try_from(self.count).unwrap_or(u32::MAX))
+                .build();
+            let (cursor, keys) =
+                parse_scan_response(client.cluster_scan(&scan_state, args).await?)?;
+            (cursor, keys)
+        } else {
+            let mut scan = redis::cmd("SCAN");
+            scan.arg(self.cursor.as_deref().unwrap_or("0"))
+                .arg("MATCH")
+                .arg(self.pattern.as_slice())
+                .arg("COUNT")
+                .arg(self.count);
+            parse_scan_response(client.send_command(&mut scan, None).await?)?
+        };
+        self.finished = cursor == "0" || cursor == FINISHED_SCAN_CURSOR;
+        self.cursor = Some(cursor);
+        Ok(keys)
+    }
+}
+
+impl Drop for KeyScanner {
+    fn drop(&mut self) {
+        // Release the cluster scan state of a migration that didn't complete.
+        if let Some(cursor) = self.cursor.take()
+            && !self.finished
+        {
+            remove_scan_state_cursor(cursor);
+        }
+    }
+}
+
+/// The retry strategy of the pipelines sent to the source, which only read.
+fn read_retry_strategy() -> PipelineRetryStrategy {
+    PipelineRetryStrategy {
+        retry_server_error: true,
+        retry_connection_error: true,
+    }
+}
+
+/// Splits the responses of a pipeline into groups of `commands_per_key` responses.
+fn split_responses(
+    response: Value,
+    keys: usize,
+    commands_per_key: usize,
+) -> RedisResult<Vec<Vec<Value>>> {
+    let Value::Array(responses) = response else {
+        return Err(unexpected_response("pipeline responses", &response));
+    };
+    if responses.len() != keys * commands_per_key {
+        return Err(unexpected_response(
+            "a response per command",
+            &Value::Array(responses),
+        ));
+    }
+    let mut responses = responses.into_iter();
+    Ok((0..keys)
+        .map(|_| responses.by_ref().take(commands_per_key).collect())
+        .collect())
+}
+
+/// Builds the pipeline of `TYPE` and `MEMORY USAGE` for each key.
+fn size_pipeline(keys: &[Vec<u8>]) -> Pipeline {
+    let mut pipeline = redis::pipe();
+    for key in keys {
+        pipeline.cmd("TYPE").arg(key);
+        pipeline.cmd("MEMORY").arg("USAGE").arg(key);
+    }
+    pipeline
+}
+
+/// Splits `keys` into the keys to dump, and the big keys to copy in chunks.
+async fn find_big_keys(
+    source: &mut Client,
+    keys: Vec<Vec<u8>>,
+    threshold: u64,
+) -> RedisResult<(Vec<Vec<u8>>, Vec<(Vec<u8>, ChunkedType)>)> {
+    let keys_len = keys.len();
+    let response = source
+        .send_pipeline(
+            &size_pipeline(&keys),
+            None,
+            false,
+            None,
+            read_retry_strategy(),
+        )
+        .await?;
+    let mut dumped = Vec::with_capacity(keys.len());
+    let mut chunked = Vec::new();
+    for (key, responses) in keys
+        .into_iter()
+        .zip(split_responses(response, keys_len, 2)?)
+    {
+        let chunked_type = ChunkedType::from_type_response(&responses[0]);
+        let big = matches!(&responses[1], Value::Int(usage) if *usage as u64 > threshold);
+        match chunked_type {
+            Some(chunked_type) if big => chunked.push((key, chunked_type)),
+            _ => dumped.push(key),
+        }
+    }
+    Ok((dumped, chunked))
+}
+
+/// Builds the pipeline of `DUMP` and `PTTL` for each key.
+fn dump_pipeline(keys: &[Vec<u8>]) -> Pipeline {
+    let mut pipeline = redis::pipe();
+    for key in keys {
+        pipeline.cmd("DUMP").arg(key);
+        pipeline.cmd("PTTL").arg(key);
+    }
+    pipeline
+}
+
+/// Returns the TTL argument of `RESTORE` or `PEXPIREAT` for a key with the given `PTTL`, or
+/// `None` if the key doesn't exist.
+fn restore_ttl(pttl: &Value, absolute_ttl: bool, now: SystemTime) -> RedisResult<Option<u64>> {
+    let pttl = match pttl {
+        Value::Int(pttl) => *pttl,
+        Value::ServerError(error) => return Err(error.clone().into()),
+        other => return Err(unexpected_response("PTTL response", other)),
+    };
+    // -2 means that the key doesn't exist, and -1 that it has no expiration
+    Ok(match pttl {
+        -2 => None,
+        pttl if pttl < 0 => Some(0),
+        pttl if absolute_ttl => {
+            let expire_at = now + Duration::from_millis(pttl as u64);
+            Some(
+                expire_at
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_millis() as u64),
+            )
+        }
+        // A TTL of 0 means no expiration to RESTORE, so the shortest TTL is rounded up
+        pttl => Some(pttl.max(1) as u64),
+    })
+}
+
+fn restore_cmd(key: &[u8], ttl: u64, payload: &[u8], options: &MigrationOptions) -> Cmd {
+    let mut cmd = redis::cmd("RESTORE");
+    cmd.arg(key).arg(ttl).arg(payload);
+    if options.replace {
+        cmd.arg("REPLACE");
+    }
+    if options.absolute_ttl && ttl > 0 {
+        cmd.arg("ABSTTL");
+    }
+    cmd
+}
+
+/// Migrates `keys` with `DUMP` and `RESTORE`.
+async fn restore_keys(
+    source: &mut Client,
+    destination: &mut Client,
+    keys: &[Vec<u8>],
+    options: &MigrationOptions,
+    progress: &mut MigrationProgress,
+) -> RedisResult<()> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let response = source
+        .send_pipeline(
+            &dump_pipeline(keys),
+            None,
+            false,
+            None,
+            read_retry_strategy(),
+        )
+        .await?;
+    let now = SystemTime::now();
+    let mut restored_keys = Vec::with_capacity(keys.len());
+    let mut restores = redis::pipe();
+    for (key, responses) in keys.iter().zip(split_responses(response, keys.len(), 2)?) {
+        let ttl = match restore_ttl(&responses[1], options.absolute_ttl, now) {
+            Ok(ttl) => ttl,
+            Err(error) => {
+                progress.record_failure(key, error);
+                continue;
+            }
+        };
+        match (&responses[0], ttl) {
+            (Value::BulkString(payload), Some(ttl)) => {
+                restores.add_command(restore_cmd(key, ttl, payload, options));
+                restored_keys.push(key);
+            }
+            (Value::Nil, _) | (_, None) => progress.missing_keys += 1,
+            (Value::ServerError(error), _) => progress.record_failure(key, error.clone().into()),
+            (other, _) => progress.record_failure(key, unexpected_response("DUMP response", other)),
+        }
+    }
+    if restored_keys.is_empty() {
+        return Ok(());
+    }
+    let restored_keys_len = restored_keys.len();
+    // Retrying a RESTORE without REPLACE after it was applied would fail, so it isn't retried.
+    let response = destination
+        .send_pipeline(
+            &restores,
+            None,
+            false,
+            None,
+            PipelineRetryStrategy::default(),
+        )
+        .await?;
+    for (key, mut responses) in
+        restored_keys
+            .into_iter()
+            .zip(split_responses(response, restored_keys_len, 1)?)
+    {
+        match responses.pop() {
+            Some(Value::ServerError(error)) => progress.record_failure(key, error.into()),
+            _ => progress.restored_keys += 1,
+        }
+    }
+    Ok(())
+}
+
+/// Flattens the responses of `ZRANGE WITHSCORES` into `ZADD` arguments.
+fn zadd_args(response: Value) -> RedisResult<Vec<Vec<u8>>> {
+    let score_arg = |score: Value| match score {
+        Value::Double(score) => Ok(score.to_string().into_bytes()),
+        Value::BulkString(score) => Ok(score),
+        other => Err(unexpected_response("score", &other)),
+    };
+    let member_arg = |member: Value| match member {
+        Value::BulkString(member) => Ok(member),
+        other => Err(unexpected_response("member", &other)),
+    };
+    let pairs: Vec<(Value, Value)> = match response {
+        Value::Map(pairs) => pairs,
+        Value::Array(values) => {
+            let mut values = values.into_iter();
+            let mut pairs = Vec::new();
+            while let Some(value) = values.next() {
+                match value {
+                    Value::Array(pair) if pair.len() == 2 => {
+                        let mut pair = pair.into_iter();
+                        pairs.push((pair.next().unwrap(), pair.next().unwrap()));
+                    }
+                    member => {
+                        let score = values
+                            .next()
+                            .ok_or_else(|| unexpected_response("score", &Value::Nil))?;
+                        pairs.push((member, score));
+                    }
+                }
+            }
+            pairs
+        }
+        other => return Err(unexpected_response("ZRANGE WITHSCORES response", &other)),
+    };
+    let mut args = Vec::with_capacity(pairs.len() * 2);
+    for (member, score) in pairs {
+        args.push(score_arg(score)?);
+        args.push(member_arg(member)?);
+    }
+    Ok(args)
+}
+
+fn bulk_strings(response: Value) -> RedisResult<Vec<Vec<u8>>> {
+    let Value::Array(values) = response else {
+        return Err(unexpected_response("array", &response));
+    };
+    values
+        .into_iter()
+        .map(|value| match value {
+            Value::BulkString(value) => Ok(value),
+            other => Err(unexpected_response("bulk string", &other)),
+        })
+        .collect()
+}
+
+/// Reads the next chunk of a big key from the source. Returns the arguments to write to the
+/// destination, and whether the key was read completely.
+async fn read_chunk(
+    source: &mut Client,
+    key: &[u8],
+    chunked_type: ChunkedType,
+    position: &mut String,
+    chunk_size: usize,
+) -> RedisResult<(Vec<Vec<u8>>, bool)> {
+    match chunked_type {
+        ChunkedType::Hash => {
+            let mut cmd = redis::cmd("HSCAN");
+            cmd.arg(key)
+                .arg(position.as_str())
+                .arg("COUNT")
+                .arg(chunk_size);
+            let (cursor, fields) = parse_scan_response(source.send_command(&mut cmd, None).await?)?;
+            let finished = cursor == "0";
+            *position = cursor;
+            Ok((fields, finished))
+        }
+        ChunkedType::List => {
+            let start: usize = position.parse().unwrap_or_default();
+            let mut cmd = redis::cmd("LRANGE");
+            cmd.arg(key).arg(start).arg(start + chunk_size - 1);
+            let elements = bulk_strings(source.send_command(&mut cmd, None).await?)?;
+            *position = (start + elements.len()).to_string();
+            let finished = elements.len() < chunk_size;
+            Ok((elements, finished))
+        }
+        ChunkedType::ZSet => {
+            let start: usize = position.parse().unwrap_or_default();
+            let mut cmd = redis::cmd("ZRANGE");
+            cmd.arg(key)
+                .arg(start)
+                .arg(start + chunk_size - 1)
+                .arg("WITHSCORES");
+            let elements = zadd_args(source.send_command(&mut cmd, None).await?)?;
+            // The arguments are pairs of score and member
+            let members = elements.len() / 2;
+            *position = (start + members).to_string();
+            Ok((elements, members < chunk_size))
+        }
+    }
+}
+
+/// Copies a big key in chunks of elements, and then sets its TTL.
+async fn copy_in_chunks(
+    source: &mut Client,
+    destination: &mut Client,
+    key: &[u8],
+    chunked_type: ChunkedType,
+    options: &MigrationOptions,
+) -> RedisResult<bool> {
+    let mut pttl = redis::cmd("PTTL");
+    pttl.arg(key);
+    let Some(ttl) = restore_ttl(
+        &source.send_command(&mut pttl, None).await?,
+        options.absolute_ttl,
+        SystemTime::now(),
+    )?
+    else {
+        return Ok(false);
+    };
+
+    if options.replace {
+        let mut del = redis::cmd("DEL");
+        del.arg(key);
+        destination.send_command(&mut del, None).await?;
+    } else {
+        let mut exists = redis::cmd("EXISTS");
+        exists.arg(key);
+        if destination.send_command(&mut exists, None).await? != Value::Int(0) {
+            return Err(RedisError::from((
+                ErrorKind::ResponseError,
+                "Target key name already exists on the destination",
+            )));
+        }
+    }
+
+    let mut position = "0".to_string();
+    let mut finished = false;
+    while !finished {
+        let elements;
+        (elements, finished) =
+            read_chunk(source, key, chunked_type, &mut position, options.chunk_size).await?;
+        if elements.is_empty() {
+            continue;
+        }
+        let mut write = redis::cmd(match chunked_type {
+            ChunkedType::Hash => "HSET",
+            ChunkedType::List => "RPUSH",
+            ChunkedType::ZSet => "ZADD",
+        });
+        write.arg(key).arg(elements);
+        destination.send_command(&mut write, None).await?;
+    }
+
+    if ttl > 0 {
+        let mut expire = redis::cmd(if options.absolute_ttl {
+            "PEXPIREAT"
+        } else {
+            "PEXPIRE"
+        });
+        expire.arg(key).arg(ttl);
+        destination.send_command(&mut expire, None).await?;
+    }
+    Ok(true)
+}
+
+/// Migrates the keys matching the glob-style `pattern` from `source` to `destination`, and calls
+/// `on_progress` after every batch of keys. Returns the final progress.
+///
+/// Keys that can't be migrated - for example, because they already exist on the destination and
+/// [`MigrationOptions::replace`] isn't set, or because the destination doesn't support the `DUMP`
+/// payload version of the source - are reported in [`MigrationProgress::failures`]. Errors that
+/// prevent the migration from continuing, such as a failed scan, stop the migration and are
+/// returned. Like `SCAN`, keys that are modified during the migration might be migrated more than
+/// once, or not at all.
+pub async fn migrate_keys(
+    source: &mut Client,
+    pattern: &[u8],
+    destination: &mut Client,
+    options: &MigrationOptions,
+    mut on_progress: impl FnMut(&MigrationProgress),
+) -> RedisResult<MigrationProgress> {
+    options.validate()?;
+    let mut scanner = KeyScanner {
+        pattern: pattern.to_vec(),
+        count: options.batch_size,
+        cursor: None,
+        finished: false,
+    };
+    let mut progress = MigrationProgress::default();
+    while !scanner.finished {
+        let keys = scanner.next_batch(source).await?;
+        progress.scanned_keys += keys.len() as u64;
+        for batch in keys.chunks(options.batch_size) {
+            let (dumped, chunked) = match options.big_key_threshold {
+                Some(threshold) => find_big_keys(source, batch.to_vec(), threshold).await?,
+                None => (batch.to_vec(), Vec::new()),
+            };
+            restore_keys(source, destination, &dumped, options, &mut progress).await?;
+            for (key, chunked_type) in chunked {
+                match copy_in_chunks(source, destination, &key, chunked_type, options).await {
+                    Ok(true) => progress.chunked_keys += 1,
+                    Ok(false) => progress.missing_keys += 1,
+                    Err(error) => progress.record_failure(&key, error),
+                }
+            }
+        }
+        on_progress(&progress);
+    }
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bulk;
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_restore_ttl() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        assert_eq!(restore_ttl(&Value::Int(-2), false, now).unwrap(), None);
+        assert_eq!(restore_ttl(&Value::Int(-1), true, now).unwrap(), Some(0));
+        assert_eq!(restore_ttl(&Value::Int(0), false, now).unwrap(), Some(1));
+        assert_eq!(
+            restore_ttl(&Value::Int(500), false, now).unwrap(),
+            Some(500)
+        );
+        assert_eq!(
+            restore_ttl(&Value::Int(500), true, now).unwrap(),
+            Some(1_000_500)
+        );
+        assert!(restore_ttl(&bulk("500"), false, now).is_err());
+    }
+
+    #[test]
+    fn test_restore_cmd() {
+        let options = MigrationOptions::default();
+        assert_eq!(
+            args(&restore_cmd(b"key", 0, b"payload", &options)),
+            vec!["RESTORE", "key", "0", "payload"]
+        );
+
+        let options = MigrationOptions {
+            replace: true,
+            absolute_ttl: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            args(&restore_cmd(b"key", 1_000_500, b"payload", &options)),
+            vec!["RESTORE", "key", "1000500", "payload", "REPLACE", "ABSTTL"]
+        );
+        // Keys without expiration are restored without ABSTTL
+        assert_eq!(
+            args(&restore_cmd(b"key", 0, b"payload", &options)),
+            vec!["RESTORE", "key", "0", "payload", "REPLACE"]
+        );
+    }
+
+    #[test]
+    fn test_zadd_args() {
+        let expected = vec![b"1.5".to_vec(), b"a".to_vec(), b"2".to_vec(), b"b".to_vec()];
+        // As converted by the client
+        let map = Value::Map(vec![
+            (bulk("a"), Value::Double(1.5)),
+            (bulk("b"), Value::Double(2.0)),
+        ]);
+        assert_eq!(zadd_args(map).unwrap(), expected);
+        // RESP3 pairs and flat RESP2 responses
+        let pairs = Value::Array(vec![
+            Value::Array(vec![bulk("a"), Value::Double(1.5)]),
+            Value::Array(vec![bulk("b"), Value::Double(2.0)]),
+        ]);
+        assert_eq!(zadd_args(pairs).unwrap(), expected);
+        let flat = Value::Array(vec![bulk("a"), bulk("1.5"), bulk("b"), bulk("2")]);
+        assert_eq!(zadd_args(flat).unwrap(), expected);
+        assert!(zadd_args(Value::Array(vec![bulk("a")])).is_err());
+    }
+
+    #[test]
+    fn test_split_responses() {
+        let response = Value::Array(vec![
+            Value::Int(1),
+            Value::Int(2),
+            Value::Int(3),
+            Value::Int(4),
+        ]);
+        assert_eq!(
+            split_responses(response.clone(), 2, 2).unwrap(),
+            vec![
+                vec![Value::Int(1), Value::Int(2)],
+                vec![Value::Int(3), Value::Int(4)]
+            ]
+        );
+        assert!(split_responses(response, 3, 2).is_err());
+    }
+
+    #[test]
+    fn test_chunked_types() {
+        assert_eq!(
+            ChunkedType::from_type_response(&Value::SimpleString("zset".to_string())),
+            Some(ChunkedType::ZSet)
+        );
+        assert_eq!(
+            ChunkedType::from_type_response(&bulk("hash")),
+            Some(ChunkedType::Hash)
+        );
+        assert_eq!(ChunkedType::from_type_response(&bulk("string")), None);
+        assert_eq!(ChunkedType::from_type_response(&Value::Nil), None);
+    }
+
+    #[test]
+    fn test_invalid_options_are_rejected() {
+        assert!(MigrationOptions::default().validate().is_ok());
+        let options = MigrationOptions {
+            chunk_size: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            options.validate().unwrap_err().kind(),
+            ErrorKind::InvalidClientConfig
+        );
+    }
+}
@@ -0,0 +1,237 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Batched lookup of key metadata.
+//!
+//! [`keys_metadata`] fetches the type, TTL and encoding of many keys with `TYPE`, `PTTL` and
+//! `OBJECT ENCODING`, pipelined in batches. In cluster mode the pipeline is split by slot, so the
+//! keys may belong to different nodes.
+
+use std::time::Duration;
+
+use redis::{Cmd, Pipeline, PipelineRetryStrategy, RedisResult, Value};
+
+use crate::client::Client;
+use crate::errors::unexpected_response;
+
+/// Maximal number of keys looked up by a single pipeline.
+const KEYS_PER_PIPELINE: usize = 1000;
+/// The commands sent for each key.
+const COMMANDS_PER_KEY: usize = 3;
+
+/// The type of a key, as returned by `TYPE`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyType {
+    String,
+    List,
+    Set,
+    ZSet,
+    Hash,
+    Stream,
+    /// A type added by a module, or by a newer server.
+    Other(String),
+}
+
+impl KeyType {
+    /// Parses the response of `TYPE`. Returns `None` if the key doesn't exist.
+    fn from_type_response(response: &str) -> Option<Self> {
+        Some(match response {
+            "none" => return None,
+            "string" => KeyType::String,
+            "list" => KeyType::List,
+            "set" => KeyType::Set,
+            "zset" => KeyType::ZSet,
+            "hash" => KeyType::Hash,
+            "stream" => KeyType::Stream,
+            other => KeyType::Other(other.to_string()),
+        })
+    }
+}
+
+/// The metadata of a single key, as returned by [`keys_metadata`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyMetadata {
+    pub key: Vec<u8>,
+    /// The type of the key, or `None` if the key doesn't exist.
+    pub key_type: Option<KeyType>,
+    /// The remaining time to live, or `None` if the key doesn't exist or has no expiration.
+    pub ttl: Option<Duration>,
+    /// The internal encoding of the value, e.g. `listpack` or `hashtable`, or `None` if the key
+    /// doesn't exist.
+    pub encoding: Option<String>,
+}
+
+impl KeyMetadata {
+    /// Returns whether the key existed when its metadata was fetched.
+    pub fn exists(&self) -> bool {
+        self.key_type.is_some()
+    }
+}
+
+/// Builds the pipeline of `TYPE`, `PTTL` and `OBJECT ENCODING` for each key.
+fn metadata_pipeline(keys: &[Vec<u8>]) -> Pipeline {
+    let mut pipeline = redis::pipe();
+    for key in keys {
+        pipeline.cmd("TYPE").arg(key);
+        pipeline.cmd("PTTL").arg(key);
+        let mut encoding: Cmd = redis::cmd("OBJECT");
+        encoding.arg("ENCODING").arg(key);
+        pipeline.add_command(encoding);
+    }
+    pipeline
+}
+
+/// Returns the type, TTL and encoding of each key, in the order of `keys`. Keys that don't exist
+/// are returned with empty metadata.
+pub async fn keys_metadata(client: &mut Client, keys: &[Vec<u8>]) -> RedisResult<Vec<KeyMetadata>> {
+    let mut metadata = Vec::with_capacity(keys.len());
+    for batch in keys.chunks(KEYS_PER_PIPELINE) {
+        // The commands are read-only, so they're safe to retry
+        let retry_strategy = PipelineRetryStrategy {
+            retry_server_error: true,
+            retry_connection_error: true,
+        };
+        let response = client
+            .send_pipeline(&metadata_pipeline(batch), None, false, None, retry_strategy)
+            .await?;
+        metadata.extend(parse_pipeline_response(batch, response)?);
+    }
+    Ok(metadata)
+}
+
+/// Parses the responses of [`metadata_pipeline`].
+fn parse_pipeline_response(keys: &[Vec<u8>], response: Value) -> RedisResult<Vec<KeyMetadata>> {
+    let Value::Array(responses) = response else {
+        return Err(unexpected_response("pipeline responses", &response));
+    };
+    if responses.len() != keys.len() * COMMANDS_PER_KEY {
+        return Err(unexpected_response(
+            "3 responses per key",
+            &Value::Array(responses),
+        ));
+    }
+    let mut responses = responses.into_iter();
+    keys.iter()
+        .map(|key| {
+            let (Some(key_type), Some(ttl), Some(encoding)) =
+                (responses.next(), responses.next(), responses.next())
+            else {
+                unreachable!("the number of responses was checked");
+            };
+            parse_key_metadata(key.clone(), key_type, ttl, encoding)
+        })
+        .collect()
+}
+
+fn parse_key_metadata(
+    key: Vec<u8>,
+    key_type: Value,
+    ttl: Value,
+    encoding: Value,
+) -> RedisResult<KeyMetadata> {
+    let key_type = match key_type {
+        Value::SimpleString(key_type) => KeyType::from_type_response(&key_type),
+        Value::BulkString(key_type) => {
+            KeyType::from_type_response(&String::from_utf8_lossy(&key_type))
+        }
+        Value::ServerError(error) => return Err(error.into()),
+        other => return Err(unexpected_response("TYPE response", &other)),
+    };
+    // -2 means that the key doesn't exist, and -1 that it has no expiration
+    let ttl = match ttl {
+        Value::Int(ttl) => u64::try_from(ttl).ok().map(Duration::from_millis),
+        Value::ServerError(error) => return Err(error.into()),
+        other => return Err(unexpected_response("PTTL response", &other)),
+    };
+    // OBJECT ENCODING fails if the key doesn't exist, or was removed after TYPE was read
+    let encoding = match encoding {
+        Value::BulkString(encoding) => Some(String::from_utf8_lossy(&encoding).into_owned()),
+        Value::SimpleString(encoding) => Some(encoding),
+        Value::Nil | Value::ServerError(_) => None,
+        other => return Err(unexpected_response("OBJECT ENCODING response", &other)),
+    }
+    .filter(|_| key_type.is_some());
+    Ok(KeyMetadata {
+        key,
+        key_type,
+        ttl,
+        encoding,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bulk;
+    use redis::cluster_routing::Routable;
+
+    fn no_such_key() -> Value {
+        let error = redis::parse_redis_value(b"-ERR no such key\r\n").unwrap();
+        assert!(matches!(error, Value::ServerError(_)));
+        error
+    }
+
+    #[test]
+    fn test_metadata_pipeline() {
+        let pipeline = metadata_pipeline(&[b"a".to_vec(), b"b".to_vec()]);
+        let commands: Vec<Vec<u8>> = pipeline
+            .cmd_iter()
+            .map(|cmd| cmd.command().unwrap())
+            .collect();
+        let expected: Vec<Vec<u8>> = ["TYPE", "PTTL", "OBJECT ENCODING"]
+            .repeat(2)
+            .into_iter()
+            .map(|command| command.as_bytes().to_vec())
+            .collect();
+        assert_eq!(commands, expected);
+    }
+
+    #[test]
+    fn test_parse_pipeline_response() {
+        let keys = vec![b"hash".to_vec(), b"missing".to_vec(), b"module".to_vec()];
+        let response = Value::Array(vec![
+            Value::SimpleString("hash".to_string()),
+            Value::Int(1500),
+            bulk("listpack"),
+            Value::SimpleString("none".to_string()),
+            Value::Int(-2),
+            no_such_key(),
+            Value::SimpleString("ReJSON-RL".to_string()),
+            Value::Int(-1),
+            bulk("raw"),
+        ]);
+
+        let metadata = parse_pipeline_response(&keys, response).unwrap();
+        assert_eq!(
+            metadata,
+            vec![
+                KeyMetadata {
+                    key: b"hash".to_vec(),
+                    key_type: Some(KeyType::Hash),
+                    ttl: Some(Duration::from_millis(1500)),
+                    encoding: Some("listpack".to_string()),
+                },
+                KeyMetadata {
+                    key: b"missing".to_vec(),
+                    key_type: None,
+                    ttl: None,
+                    encoding: None,
+                },
+                KeyMetadata {
+                    key: b"module".to_vec(),
+                    key_type: Some(KeyType::Other("ReJSON-RL".to_string())),
+                    ttl: None,
+                    encoding: Some("raw".to_string()),
+                },
+            ]
+        );
+        assert!(!metadata[1].exists());
+    }
+
+    #[test]
+    fn test_parse_pipeline_response_errors() {
+        let keys = vec![b"key".to_vec()];
+        assert!(parse_pipeline_response(&keys, Value::Array(vec![Value::Int(1)])).is_err());
+        let response = Value::Array(vec![no_such_key(), Value::Int(-1), bulk("raw")]);
+        assert!(parse_pipeline_response(&keys, response).is_err());
+    }
+}
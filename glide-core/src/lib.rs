@@ -3,7 +3,11 @@
 #[cfg(feature = "proto")]
 include!("generated/mod.rs");
 pub mod client;
+#[cfg(feature = "socket-layer")]
+mod graceful_shutdown;
 pub mod otel_db_semantics;
+#[cfg(feature = "protocol-testing")]
+pub mod protocol_testing;
 #[cfg(feature = "socket-layer")]
 pub mod rotating_buffer;
 #[cfg(feature = "socket-layer")]
@@ -11,18 +15,41 @@ mod socket_listener;
 #[cfg(feature = "socket-layer")]
 pub use socket_listener::*;
 pub mod address_resolver_registry;
+pub mod bitmap;
+pub mod client_registry;
+pub mod client_runtime;
 pub mod compression;
 pub mod errors;
+mod memory_limit;
 pub mod scripts_container;
 pub mod timeout_watchdog;
 pub use client::ConnectionRequest;
 pub mod cluster_scan_container;
+pub mod cluster_slots;
+pub mod command_metadata;
+pub mod config_drift;
+pub mod databases;
+#[cfg(feature = "serde")]
+pub mod deserialize;
+pub mod experimental;
+pub mod geo;
 pub mod iam;
+pub mod key_migration;
+pub mod keys_metadata;
 pub mod pubsub;
+#[cfg(feature = "socket-layer")]
+mod request_queue;
 pub mod request_type;
+pub mod runtime_config;
+pub mod scheduler;
+pub mod streams;
+#[cfg(test)]
+mod test_utils;
+pub mod tools;
 pub use telemetrylib::{
     DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, DEFAULT_TRACE_SAMPLE_PERCENTAGE, GlideOpenTelemetry,
-    GlideOpenTelemetryConfigBuilder, GlideOpenTelemetrySignalsExporter, GlideSpan, Telemetry,
+    GlideOpenTelemetryConfigBuilder, GlideOpenTelemetrySignalsExporter, GlideSpan, QueuePriority,
+    Telemetry,
 };
 
 #[cfg(feature = "proto")]
@@ -56,3 +83,65 @@ mod cache_metric_conversion {
 
 #[cfg(feature = "proto")]
 pub use cache_metric_conversion::cache_metric_type_from_proto;
+
+#[cfg(feature = "proto")]
+mod route_conversion {
+    use crate::command_request::ResponseAggregation;
+    use protobuf::EnumOrUnknown;
+    use redis::cluster_routing::{LogicalAggregateOp, ResponsePolicy, Routable};
+    use redis::{Cmd, ErrorKind, RedisError, RedisResult};
+
+    /// Returns the response policy of a command routed to multiple nodes with the given
+    /// aggregation. `None` combines the responses into a map of node addresses to responses.
+    pub fn multi_node_response_policy(
+        aggregation: EnumOrUnknown<ResponseAggregation>,
+        cmd: Option<&Cmd>,
+    ) -> RedisResult<Option<ResponsePolicy>> {
+        let aggregation = aggregation.enum_value().map_err(|value| {
+            RedisError::from((
+                ErrorKind::ClientError,
+                "Invalid response aggregation",
+                format!("Value: {value}"),
+            ))
+        })?;
+        Ok(match aggregation {
+            ResponseAggregation::CommandDefault => cmd
+                .and_then(|cmd| cmd.command())
+                .and_then(|cmd| ResponsePolicy::for_command(&cmd)),
+            ResponseAggregation::FirstSucceeded => Some(ResponsePolicy::OneSucceeded),
+            ResponseAggregation::MapByNode => None,
+            ResponseAggregation::MergeArrays => Some(ResponsePolicy::CombineArrays),
+            ResponseAggregation::LogicalAnd => {
+                Some(ResponsePolicy::AggregateLogical(LogicalAggregateOp::And))
+            }
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_multi_node_response_policy() {
+            let cmd = redis::cmd("DBSIZE");
+            assert_eq!(
+                multi_node_response_policy(ResponseAggregation::CommandDefault.into(), Some(&cmd))
+                    .unwrap(),
+                ResponsePolicy::for_command(b"DBSIZE")
+            );
+            assert_eq!(
+                multi_node_response_policy(ResponseAggregation::MapByNode.into(), Some(&cmd))
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                multi_node_response_policy(ResponseAggregation::MergeArrays.into(), None).unwrap(),
+                Some(ResponsePolicy::CombineArrays)
+            );
+            assert!(multi_node_response_policy(EnumOrUnknown::from_i32(42), None).is_err());
+        }
+    }
+}
+
+#[cfg(feature = "proto")]
+pub use route_conversion::multi_node_response_policy;
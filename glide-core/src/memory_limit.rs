@@ -0,0 +1,223 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Process-level soft memory limit.
+//!
+//! When `GlideRuntimeConfig::soft_memory_limit` is set, a background task measures the memory of
+//! the process every `memory_check_interval` - the bytes allocated through jemalloc with the
+//! `jemalloc-stats` feature, and the resident set size otherwise. While the limit is exceeded, the
+//! core sheds load instead of growing until the process is OOM-killed:
+//! - requests larger than `memory_shedding_request_size` fail with an `OutOfClientMemory` error,
+//!   while smaller requests - including the ones that free memory on the server - are still sent,
+//! - the client-side caches are flushed, and
+//! - the socket listeners shrink their read buffers back to their initial size.
+//!
+//! Crossing the limit in either direction is logged. The last measurement is reported in the
+//! `process_memory_bytes` statistic, and the rejected requests in `soft_memory_limit_rejections`.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Once, OnceLock};
+use std::time::Duration;
+
+use logger_core::{log_info, log_warn, log_warn_rate_limited};
+use redis::{ErrorKind, RedisError, RedisResult};
+use telemetrylib::Telemetry;
+
+use crate::runtime_config::GlideRuntimeConfig;
+
+static SOFT_MEMORY_LIMIT: OnceLock<SoftMemoryLimit> = OnceLock::new();
+
+struct SoftMemoryLimit {
+    limit: u64,
+    shedding_request_size: u64,
+    used: AtomicU64,
+    exceeded: AtomicBool,
+}
+
+impl SoftMemoryLimit {
+    fn new(limit: u64, shedding_request_size: u64) -> Self {
+        Self {
+            limit,
+            shedding_request_size,
+            used: AtomicU64::new(0),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+
+    /// Records a measurement of the process memory.
+    /// Returns whether the limit is exceeded, if that changed with this measurement.
+    fn update(&self, used: u64) -> Option<bool> {
+        self.used.store(used, Ordering::Relaxed);
+        let exceeded = used > self.limit;
+        (self.exceeded.swap(exceeded, Ordering::Relaxed) != exceeded).then_some(exceeded)
+    }
+
+    fn is_exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    fn check_request(&self, bytes: u64) -> RedisResult<()> {
+        if !self.is_exceeded() || bytes <= self.shedding_request_size {
+            return Ok(());
+        }
+        Telemetry::incr_soft_memory_limit_rejections();
+        let used = self.used.load(Ordering::Relaxed);
+        log_warn_rate_limited!(
+            "memory_limit",
+            10,
+            format!(
+                "Rejecting large requests while the soft memory limit is exceeded. used={used}, limit={}, request_size={bytes}",
+                self.limit
+            )
+        );
+        Err(RedisError::from((
+            ErrorKind::OutOfClientMemory,
+            "Process soft memory limit exceeded",
+            format!(
+                "the request of {bytes} bytes is larger than {} bytes, while the process uses {used} bytes out of {}",
+                self.shedding_request_size, self.limit
+            ),
+        )))
+    }
+}
+
+/// Returns the memory currently used by the process, in bytes.
+#[cfg(feature = "jemalloc-stats")]
+fn process_memory_bytes() -> Option<u64> {
+    use tikv_jemalloc_ctl::{epoch, stats};
+    // The statistics are cached by jemalloc until the epoch is advanced.
+    epoch::advance().ok()?;
+    stats::allocated::read().ok().map(|bytes| bytes as u64)
+}
+
+/// Returns the memory currently used by the process, in bytes.
+#[cfg(not(feature = "jemalloc-stats"))]
+fn process_memory_bytes() -> Option<u64> {
+    // The second field is the resident set size, in pages.
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages: u64 = statm.split_whitespace().nth(1)?.parse().ok()?;
+    // SAFETY: `sysconf` has no preconditions.
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    u64::try_from(page_size)
+        .ok()
+        .map(|page_size| pages * page_size)
+}
+
+/// Starts monitoring the process memory, if a soft memory limit is configured. Only the first call
+/// has an effect.
+///
+/// The monitor runs on a dedicated thread rather than on a client's runtime, so that it keeps
+/// running when the client that started it is closed along with its runtime.
+pub(crate) fn start_monitor() {
+    static STARTED: Once = Once::new();
+    let config = GlideRuntimeConfig::get();
+    let Some(limit) = config.soft_memory_limit else {
+        return;
+    };
+    let check_interval = config.memory_check_interval;
+    STARTED.call_once(|| {
+        let soft_limit = SOFT_MEMORY_LIMIT
+            .get_or_init(|| SoftMemoryLimit::new(limit, config.memory_shedding_request_size));
+        let spawned = std::thread::Builder::new()
+            .name("glide-memory-monitor".into())
+            .spawn(move || run_monitor(soft_limit, check_interval));
+        if let Err(err) = spawned {
+            log_warn(
+                "memory_limit",
+                format!(
+                    "Failed to start the memory monitor, the soft memory limit is disabled: {err}"
+                ),
+            );
+        }
+    });
+}
+
+/// Measures the process memory every `check_interval`, and sheds load while the limit is exceeded.
+fn run_monitor(soft_limit: &SoftMemoryLimit, check_interval: Duration) {
+    let limit = soft_limit.limit;
+    loop {
+        let Some(used) = process_memory_bytes() else {
+            log_warn(
+                "memory_limit",
+                "Failed to measure the process memory, the soft memory limit is disabled",
+            );
+            // Requests must not be shed based on a measurement that is never updated again
+            soft_limit.exceeded.store(false, Ordering::Relaxed);
+            return;
+        };
+        Telemetry::set_process_memory_bytes(used);
+        let transition = soft_limit.update(used);
+        if !soft_limit.is_exceeded() {
+            if transition.is_some() {
+                log_info(
+                    "memory_limit",
+                    format!("Soft memory limit no longer exceeded. used={used}, limit={limit}"),
+                );
+            }
+        } else {
+            // Caches are refilled by new responses, so they're flushed on every check.
+            let flushed_caches = redis::cache::flush_all_caches();
+            if transition.is_some() {
+                log_warn(
+                    "memory_limit",
+                    format!(
+                        "Soft memory limit exceeded, shedding load. used={used}, limit={limit}, flushed_caches={flushed_caches}"
+                    ),
+                );
+            }
+        }
+        std::thread::sleep(check_interval);
+    }
+}
+
+/// Returns whether the process currently exceeds its soft memory limit.
+#[cfg(feature = "socket-layer")]
+pub(crate) fn is_exceeded() -> bool {
+    SOFT_MEMORY_LIMIT
+        .get()
+        .is_some_and(SoftMemoryLimit::is_exceeded)
+}
+
+/// Returns an `OutOfClientMemory` error if the soft memory limit is exceeded and the request is
+/// too large to be sent while shedding load.
+pub(crate) fn check_request(bytes: u64) -> RedisResult<()> {
+    match SOFT_MEMORY_LIMIT.get() {
+        Some(soft_limit) => soft_limit.check_request(bytes),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_transitions() {
+        let soft_limit = SoftMemoryLimit::new(1000, 10);
+        assert_eq!(soft_limit.update(500), None);
+        assert_eq!(soft_limit.update(1001), Some(true));
+        assert_eq!(soft_limit.update(2000), None);
+        assert!(soft_limit.is_exceeded());
+        assert_eq!(soft_limit.update(1000), Some(false));
+        assert!(!soft_limit.is_exceeded());
+    }
+
+    #[test]
+    fn test_only_large_requests_are_shed() {
+        let soft_limit = SoftMemoryLimit::new(1000, 10);
+        assert!(soft_limit.check_request(100).is_ok());
+
+        soft_limit.update(1500);
+        assert!(soft_limit.check_request(10).is_ok());
+        let err = soft_limit.check_request(11).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OutOfClientMemory);
+
+        soft_limit.update(900);
+        assert!(soft_limit.check_request(100).is_ok());
+    }
+
+    #[test]
+    #[cfg(all(target_os = "linux", not(feature = "jemalloc-stats")))]
+    fn test_process_memory_is_measured() {
+        assert!(process_memory_bytes().is_some_and(|bytes| bytes > 0));
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Property-based checks of the socket protocol framing, enabled by the `protocol-testing` feature.
+//!
+//! The strategies generate arbitrary requests and responses, and [`Chunking`]s that split the
+//! framed stream at arbitrary points and interleave parsing with the arrival of the chunks, the way
+//! socket reads fragment the stream. The checks return [`TestCaseError`]s so they can be called from
+//! `proptest!` blocks, and take the encoder under test as a parameter, so binding test suites can
+//! check their own request encoders against [`RotatingBuffer`], the decoder used by the core.
+
+use std::io;
+
+use bytes::BufMut;
+use integer_encoding::VarInt;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use protobuf::Message;
+
+use crate::command_request::{Command, CommandRequest, RequestType, command, command_request};
+use crate::response::{self, ConstantResponse, RequestError, RequestErrorType, Response};
+use crate::rotating_buffer::{CHECKSUMMED_FRAME_VERSION, FrameFormat, RotatingBuffer};
+
+/// Splits a framed stream into chunks, and decides after which chunks the buffer is parsed.
+#[derive(Debug, Clone)]
+pub struct Chunking {
+    /// Split points, reduced modulo the stream length + 1.
+    pub cuts: Vec<usize>,
+    /// Whether the buffer is parsed after each chunk. The buffer is always parsed after the last
+    /// chunk.
+    pub parse_after_chunk: Vec<bool>,
+}
+
+impl Chunking {
+    /// Returns the chunks of `stream`, in order.
+    pub fn chunks<'a>(&self, stream: &'a [u8]) -> Vec<&'a [u8]> {
+        let mut points: Vec<usize> = self
+            .cuts
+            .iter()
+            .map(|cut| cut % (stream.len() + 1))
+            .collect();
+        points.push(stream.len());
+        points.sort_unstable();
+        points.dedup();
+        let mut start = 0;
+        points
+            .into_iter()
+            .map(|end| {
+                let chunk = &stream[start..end];
+                start = end;
+                chunk
+            })
+            .collect()
+    }
+
+    fn parse_after(&self, chunk_index: usize) -> bool {
+        if self.parse_after_chunk.is_empty() {
+            return true;
+        }
+        self.parse_after_chunk[chunk_index % self.parse_after_chunk.len()]
+    }
+}
+
+/// Appends `request` to `output` in the given framing - the way the bindings write requests.
+pub fn encode_request(request: &CommandRequest, format: FrameFormat, output: &mut Vec<u8>) {
+    let request = request
+        .write_to_bytes()
+        .expect("Generated requests should be encodable");
+    let frame_len = match format {
+        FrameFormat::Plain => request.len(),
+        FrameFormat::Checksummed => request.len() + 1 + size_of::<u32>(),
+    };
+    let mut length = [0; 5];
+    let length_bytes = (frame_len as u32).encode_var(&mut length);
+    output.extend_from_slice(&length[..length_bytes]);
+    if format == FrameFormat::Checksummed {
+        output.put_u8(CHECKSUMMED_FRAME_VERSION);
+    }
+    output.extend_from_slice(&request);
+    if format == FrameFormat::Checksummed {
+        output.put_u32_le(crc32fast::hash(&request));
+    }
+}
+
+/// Appends `response` to `output` with the core's response encoder.
+pub fn encode_response(response: &Response, output: &mut Vec<u8>) {
+    crate::socket_listener::encode_response(response, output)
+        .expect("Generated responses should be encodable");
+}
+
+/// Feeds `stream` to a [`RotatingBuffer`] chunk by chunk, and returns the parsed messages.
+pub fn decode_stream<T: Message>(
+    stream: &[u8],
+    format: FrameFormat,
+    chunking: &Chunking,
+    buffer_size: usize,
+) -> io::Result<Vec<T>> {
+    let mut buffer = RotatingBuffer::new(buffer_size);
+    buffer.set_frame_format(format);
+    let chunks = chunking.chunks(stream);
+    let last_chunk = chunks.len() - 1;
+    let mut messages = Vec::new();
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        buffer.current_buffer().extend_from_slice(chunk);
+        if index == last_chunk || chunking.parse_after(index) {
+            messages.extend(buffer.get_requests::<T>()?);
+        }
+    }
+    ensure_consumed(buffer.current_buffer().len())?;
+    Ok(messages)
+}
+
+fn ensure_consumed(remaining: usize) -> io::Result<()> {
+    if remaining != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("{remaining} bytes of an incomplete frame left in the buffer"),
+        ));
+    }
+    Ok(())
+}
+
+fn decode_error(err: io::Error) -> TestCaseError {
+    TestCaseError::fail(format!("Failed to decode the stream: {err}"))
+}
+
+/// Checks that the requests written by `encode` are parsed back unchanged by the core, however the
+/// stream is chunked.
+pub fn check_request_encoder<F>(
+    requests: &[CommandRequest],
+    format: FrameFormat,
+    chunking: &Chunking,
+    encode: F,
+) -> Result<(), TestCaseError>
+where
+    F: Fn(&CommandRequest, FrameFormat, &mut Vec<u8>),
+{
+    let mut stream = Vec::new();
+    for request in requests {
+        encode(request, format, &mut stream);
+    }
+    let decoded: Vec<CommandRequest> =
+        decode_stream(&stream, format, chunking, 64).map_err(decode_error)?;
+    prop_assert_eq!(decoded.as_slice(), requests);
+    Ok(())
+}
+
+/// Checks that the responses written by `encode` are parsed back unchanged as length-delimited
+/// messages, however the stream is chunked.
+pub fn check_response_encoder<F>(
+    responses: &[Response],
+    chunking: &Chunking,
+    encode: F,
+) -> Result<(), TestCaseError>
+where
+    F: Fn(&Response, &mut Vec<u8>),
+{
+    let mut stream = Vec::new();
+    for response in responses {
+        encode(response, &mut stream);
+    }
+    let decoded: Vec<Response> =
+        decode_stream(&stream, FrameFormat::Plain, chunking, 64).map_err(decode_error)?;
+    prop_assert_eq!(decoded.as_slice(), responses);
+    Ok(())
+}
+
+/// Checks that flipping the bits of `flip_mask` in a single byte of a checksummed frame, after its
+/// length, makes the core reject the frame instead of parsing a corrupted request.
+pub fn check_corrupted_frame_is_rejected(
+    request: &CommandRequest,
+    flip_position: usize,
+    flip_mask: u8,
+    chunking: &Chunking,
+) -> Result<(), TestCaseError> {
+    prop_assume!(flip_mask != 0);
+    let mut stream = Vec::new();
+    encode_request(request, FrameFormat::Checksummed, &mut stream);
+    let length_bytes = u32::decode_var(&stream)
+        .expect("The frame starts with its length")
+        .1;
+    let position = length_bytes + flip_position % (stream.len() - length_bytes);
+    stream[position] ^= flip_mask;
+    match decode_stream::<CommandRequest>(&stream, FrameFormat::Checksummed, chunking, 64) {
+        Err(err) => prop_assert_eq!(err.kind(), io::ErrorKind::InvalidData),
+        Ok(decoded) => {
+            return Err(TestCaseError::fail(format!(
+                "A corrupted frame was parsed as {decoded:?}"
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn arb_frame_format() -> impl Strategy<Value = FrameFormat> {
+    prop_oneof![Just(FrameFormat::Plain), Just(FrameFormat::Checksummed)]
+}
+
+/// Chunkings of up to 16 split points.
+pub fn arb_chunking() -> impl Strategy<Value = Chunking> {
+    (vec(any::<usize>(), 0..16), vec(any::<bool>(), 0..16)).prop_map(|(cuts, parse_after_chunk)| {
+        Chunking {
+            cuts,
+            parse_after_chunk,
+        }
+    })
+}
+
+fn arb_arg() -> impl Strategy<Value = Vec<u8>> {
+    // Mostly short arguments, with a few that are larger than the buffer
+    prop_oneof![
+        4 => vec(any::<u8>(), 0..32),
+        1 => vec(any::<u8>(), 256..2048),
+    ]
+}
+
+/// Single-command requests with inline arguments.
+pub fn arb_command_request() -> impl Strategy<Value = CommandRequest> {
+    let request_type = prop_oneof![
+        Just(RequestType::Get),
+        Just(RequestType::Set),
+        Just(RequestType::Ping),
+        Just(RequestType::CustomCommand),
+    ];
+    (
+        any::<u32>(),
+        request_type,
+        vec(arb_arg(), 0..6),
+        any::<u32>(),
+    )
+        .prop_map(|(callback_idx, request_type, args, client_id)| {
+            let mut args_array = command::ArgsArray::new();
+            args_array.args = args.into_iter().map(Into::into).collect();
+            let mut command = Command::new();
+            command.request_type = request_type.into();
+            command.args = Some(command::Args::ArgsArray(args_array));
+            let mut request = CommandRequest::new();
+            request.callback_idx = callback_idx;
+            request.client_id = client_id;
+            request.command = Some(command_request::Command::SingleCommand(command));
+            request
+        })
+}
+
+/// Responses with each kind of value.
+pub fn arb_response() -> impl Strategy<Value = Response> {
+    let value = prop_oneof![
+        Just(None),
+        any::<u64>().prop_map(|pointer| Some(response::response::Value::RespPointer(pointer))),
+        Just(Some(response::response::Value::ConstantResponse(
+            ConstantResponse::OK.into()
+        ))),
+        ".{0,64}".prop_map(|message| {
+            Some(response::response::Value::RequestError(RequestError {
+                type_: RequestErrorType::Timeout.into(),
+                message: message.into(),
+                ..Default::default()
+            }))
+        }),
+        ".{0,64}".prop_map(|message| Some(response::response::Value::ClosingError(message.into()))),
+    ];
+    (any::<u32>(), value, any::<bool>(), any::<u32>()).prop_map(
+        |(callback_idx, value, is_push, client_id)| {
+            let mut response = Response::new();
+            response.callback_idx = callback_idx;
+            response.value = value;
+            response.is_push = is_push;
+            response.client_id = client_id;
+            response
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn requests_survive_any_chunking(
+            requests in vec(arb_command_request(), 1..8),
+            format in arb_frame_format(),
+            chunking in arb_chunking(),
+        ) {
+            check_request_encoder(&requests, format, &chunking, encode_request)?;
+        }
+
+        #[test]
+        fn responses_survive_any_chunking(
+            responses in vec(arb_response(), 1..8),
+            chunking in arb_chunking(),
+        ) {
+            check_response_encoder(&responses, &chunking, encode_response)?;
+        }
+
+        #[test]
+        fn corrupted_frames_are_rejected(
+            request in arb_command_request(),
+            flip_position in any::<usize>(),
+            flip_mask in any::<u8>(),
+            chunking in arb_chunking(),
+        ) {
+            check_corrupted_frame_is_rejected(&request, flip_position, flip_mask, &chunking)?;
+        }
+    }
+
+    #[test]
+    fn test_chunks_cover_the_stream() {
+        let chunking = Chunking {
+            cuts: vec![7, 2, 2, 100],
+            parse_after_chunk: vec![false],
+        };
+        let stream: Vec<u8> = (0..10).collect();
+        let chunks = chunking.chunks(&stream);
+        assert_eq!(
+            chunks,
+            vec![&stream[..1], &stream[1..2], &stream[2..7], &stream[7..]]
+        );
+    }
+}
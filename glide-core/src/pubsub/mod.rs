@@ -12,6 +12,7 @@ use tokio::sync::{RwLock, mpsc};
 
 #[cfg(feature = "mock-pubsub")]
 mod mock;
+pub mod push_queue;
 
 #[cfg(feature = "mock-pubsub")]
 pub use mock::MockPubSubBroker;
@@ -21,7 +22,7 @@ pub mod synchronizer;
 
 /// Factory function to create a synchronizer with internal client reference
 pub async fn create_pubsub_synchronizer(
-    _push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
     initial_subscriptions: Option<redis::PubSubSubscriptionInfo>,
     is_cluster: bool,
     internal_client: Weak<RwLock<ClientWrapper>>,
@@ -31,7 +32,7 @@ pub async fn create_pubsub_synchronizer(
     #[cfg(feature = "mock-pubsub")]
     {
         let sync = mock::MockPubSubSynchronizer::create(
-            _push_sender,
+            push_sender,
             initial_subscriptions,
             is_cluster,
             reconciliation_interval,
@@ -50,6 +51,7 @@ pub async fn create_pubsub_synchronizer(
     #[cfg(not(feature = "mock-pubsub"))]
     {
         let sync = synchronizer::GlidePubSubSynchronizer::new(
+            push_sender,
             initial_subscriptions,
             is_cluster,
             reconciliation_interval,
@@ -0,0 +1,534 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Bounded delivery of a client's push messages.
+//!
+//! The connections send the push messages of a client - the messages of its subscriptions - to an
+//! unbounded channel, so a subscriber that consumes them slower than they arrive makes the channel
+//! grow without bound. A [`PushReceiver`] created with a [`PushQueueConfig`] moves the messages
+//! from the channel to a queue per subscription - channel, pattern or sharded channel - of at most
+//! `capacity` messages as soon as they arrive, and applies the [`OverflowPolicy`] to the queue that
+//! is full, so a noisy channel doesn't evict the messages of the other subscriptions. The other
+//! push messages, e.g. the confirmations of the subscriptions, share a queue of their own. The
+//! messages are received in the order they arrived. Dropped messages are counted in the
+//! `dropped_push_messages` statistic, and the clients disconnected by an overflow in
+//! `push_queue_disconnects`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use redis::{PushInfo, PushKind, Value};
+use telemetrylib::Telemetry;
+use tokio::sync::{Notify, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// The number of messages a push queue holds when no capacity is configured.
+pub const DEFAULT_PUSH_QUEUE_CAPACITY: usize = 1000;
+
+/// What happens to a message that arrives while the queue of its subscription is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued message of the subscription to make room for the new one.
+    #[default]
+    DropOldest,
+    /// Drop the new message.
+    DropNewest,
+    /// Stop delivering messages, and disconnect the client once the queued messages are consumed,
+    /// so that the subscriber can resubscribe and resynchronize its state. The subscriptions of a
+    /// client share its connections, so they are all disconnected.
+    Disconnect,
+    /// Wait up to the given time for the subscriber to make room, then drop the new message. Up to
+    /// `capacity` messages of the subscription that arrive meanwhile wait in line, and the ones
+    /// beyond are dropped. The messages of the other subscriptions don't wait.
+    Block(Duration),
+}
+
+/// The bounds of the push queues of a client's subscriptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PushQueueConfig {
+    /// The messages held by the queue of each subscription.
+    pub capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for PushQueueConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_PUSH_QUEUE_CAPACITY,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+/// The subscription a push message is queued under.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Subscription {
+    Exact(Vec<u8>),
+    Pattern(Vec<u8>),
+    Sharded(Vec<u8>),
+    /// The push messages that aren't messages of a subscription.
+    Other,
+}
+
+impl Subscription {
+    fn of(message: &PushInfo) -> Self {
+        // The channel, or the pattern, comes first in the data of a message.
+        let name = match message.data.first() {
+            Some(Value::BulkString(name)) => name.clone(),
+            _ => return Subscription::Other,
+        };
+        match message.kind {
+            PushKind::Message => Subscription::Exact(name),
+            PushKind::PMessage => Subscription::Pattern(name),
+            PushKind::SMessage => Subscription::Sharded(name),
+            _ => Subscription::Other,
+        }
+    }
+}
+
+#[derive(Default)]
+struct QueueState {
+    /// The queued messages of each subscription, with the order they arrived in.
+    subscriptions: HashMap<Subscription, VecDeque<(u64, PushInfo)>>,
+    /// The subscription of each queued message, by the order it arrived in.
+    arrivals: BTreeMap<u64, Subscription>,
+    next_arrival: u64,
+    /// No more messages will be queued.
+    closed: bool,
+    /// The queue was closed by the `Disconnect` policy.
+    overflowed: bool,
+}
+
+impl QueueState {
+    fn len(&self, subscription: &Subscription) -> usize {
+        self.subscriptions
+            .get(subscription)
+            .map_or(0, VecDeque::len)
+    }
+
+    fn push(&mut self, subscription: Subscription, message: PushInfo) {
+        let arrival = self.next_arrival;
+        self.next_arrival += 1;
+        self.arrivals.insert(arrival, subscription.clone());
+        self.subscriptions
+            .entry(subscription)
+            .or_default()
+            .push_back((arrival, message));
+    }
+
+    fn pop_oldest(&mut self, subscription: &Subscription) {
+        if let Some(messages) = self.subscriptions.get_mut(subscription)
+            && let Some((arrival, _)) = messages.pop_front()
+        {
+            self.arrivals.remove(&arrival);
+        }
+    }
+
+    /// Removes the message that arrived first, whatever its subscription.
+    fn pop(&mut self) -> Option<PushInfo> {
+        let (_, subscription) = self.arrivals.pop_first()?;
+        let messages = self.subscriptions.get_mut(&subscription)?;
+        let (_, message) = messages.pop_front()?;
+        if messages.is_empty() {
+            self.subscriptions.remove(&subscription);
+        }
+        Some(message)
+    }
+}
+
+/// What became of a message pushed to the queue.
+enum Pushed {
+    /// The message was queued, or dropped by the overflow policy.
+    Done,
+    /// The queue of the message's subscription is full, and the message may wait for room under
+    /// the `Block` policy.
+    Full(PushInfo),
+    /// The queue was closed by the `Disconnect` policy.
+    Overflowed,
+}
+
+#[derive(Default)]
+struct PushQueue {
+    state: Mutex<QueueState>,
+    message_pushed: Notify,
+    message_popped: Notify,
+}
+
+impl PushQueue {
+    /// Queues `message` under `subscription` as `config` allows.
+    fn push(
+        &self,
+        subscription: Subscription,
+        message: PushInfo,
+        config: &PushQueueConfig,
+    ) -> Pushed {
+        let mut state = self.state.lock().unwrap();
+        if state.len(&subscription) < config.capacity {
+            state.push(subscription, message);
+            self.message_pushed.notify_one();
+            return Pushed::Done;
+        }
+        match config.overflow_policy {
+            OverflowPolicy::DropOldest => {
+                state.pop_oldest(&subscription);
+                state.push(subscription, message);
+                Telemetry::incr_dropped_push_messages();
+                self.message_pushed.notify_one();
+                Pushed::Done
+            }
+            OverflowPolicy::DropNewest => {
+                Telemetry::incr_dropped_push_messages();
+                Pushed::Done
+            }
+            OverflowPolicy::Disconnect => {
+                state.closed = true;
+                state.overflowed = true;
+                Telemetry::incr_push_queue_disconnects();
+                self.message_pushed.notify_one();
+                Pushed::Overflowed
+            }
+            OverflowPolicy::Block(_) => Pushed::Full(message),
+        }
+    }
+
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.message_pushed.notify_one();
+    }
+}
+
+/// The messages of a subscription waiting for room in its queue under the `Block` policy.
+struct WaitingLine {
+    /// When the first message of the line is dropped if there is still no room.
+    deadline: Instant,
+    messages: VecDeque<PushInfo>,
+}
+
+/// Moves the messages of `push_rx` to `queue` until the channel or the queue is closed. The
+/// channel keeps being drained while messages wait for room, so the messages waiting in line are
+/// bounded by the queue's capacity rather than left in the unbounded channel.
+async fn relay(
+    mut push_rx: mpsc::UnboundedReceiver<PushInfo>,
+    queue: Arc<PushQueue>,
+    config: PushQueueConfig,
+) {
+    let block_timeout = match config.overflow_policy {
+        OverflowPolicy::Block(timeout) => timeout,
+        _ => Duration::ZERO,
+    };
+    let mut waiting: HashMap<Subscription, WaitingLine> = HashMap::new();
+    let mut channel_open = true;
+    while channel_open || !waiting.is_empty() {
+        let room_made = queue.message_popped.notified();
+        let next_deadline = waiting.values().map(|line| line.deadline).min();
+        let deadline_reached = async move {
+            match next_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::select! {
+            biased;
+            message = push_rx.recv(), if channel_open => {
+                let Some(message) = message else {
+                    channel_open = false;
+                    continue;
+                };
+                let subscription = Subscription::of(&message);
+                if let Some(line) = waiting.get_mut(&subscription) {
+                    // The first message of the line waits for room, and up to `capacity` messages
+                    // wait behind it.
+                    if line.messages.len() <= config.capacity {
+                        line.messages.push_back(message);
+                    } else {
+                        Telemetry::incr_dropped_push_messages();
+                    }
+                    continue;
+                }
+                match queue.push(subscription.clone(), message, &config) {
+                    Pushed::Done => {}
+                    Pushed::Full(message) => {
+                        waiting.insert(
+                            subscription,
+                            WaitingLine {
+                                deadline: Instant::now() + block_timeout,
+                                messages: VecDeque::from([message]),
+                            },
+                        );
+                    }
+                    Pushed::Overflowed => return,
+                }
+            }
+            _ = room_made => {}
+            _ = deadline_reached => {}
+        }
+        let now = Instant::now();
+        waiting.retain(|subscription, line| {
+            while let Some(message) = line.messages.pop_front() {
+                match queue.push(subscription.clone(), message, &config) {
+                    Pushed::Full(message) if now < line.deadline => {
+                        line.messages.push_front(message);
+                        return true;
+                    }
+                    Pushed::Full(_) => {
+                        Telemetry::incr_dropped_push_messages();
+                    }
+                    Pushed::Done | Pushed::Overflowed => {}
+                }
+                line.deadline = now + block_timeout;
+            }
+            false
+        });
+    }
+    queue.close();
+}
+
+enum Inner {
+    Unbounded(mpsc::UnboundedReceiver<PushInfo>),
+    Bounded {
+        queue: Arc<PushQueue>,
+        relay: JoinHandle<()>,
+    },
+}
+
+/// Receives the push messages of a client.
+pub struct PushReceiver {
+    inner: Inner,
+}
+
+impl PushReceiver {
+    /// Receives the messages of `push_rx`, through a bounded queue if `config` is set. Must be
+    /// called within a Tokio runtime if `config` is set.
+    pub fn new(
+        push_rx: mpsc::UnboundedReceiver<PushInfo>,
+        config: Option<PushQueueConfig>,
+    ) -> Self {
+        let inner = match config {
+            None => Inner::Unbounded(push_rx),
+            Some(config) => {
+                let queue = Arc::new(PushQueue::default());
+                let relay = tokio::spawn(relay(push_rx, queue.clone(), config));
+                Inner::Bounded { queue, relay }
+            }
+        };
+        Self { inner }
+    }
+
+    /// Returns the next message, or `None` once the client's push channel is closed - or the queue
+    /// overflowed with the `Disconnect` policy - and the queued messages were received.
+    pub async fn recv(&mut self) -> Option<PushInfo> {
+        let queue = match &mut self.inner {
+            Inner::Unbounded(push_rx) => return push_rx.recv().await,
+            Inner::Bounded { queue, .. } => queue,
+        };
+        loop {
+            let pushed = queue.message_pushed.notified();
+            {
+                let mut state = queue.state.lock().unwrap();
+                if let Some(message) = state.pop() {
+                    queue.message_popped.notify_one();
+                    return Some(message);
+                }
+                if state.closed {
+                    return None;
+                }
+            }
+            pushed.await;
+        }
+    }
+
+    /// Returns whether the queue overflowed with the `Disconnect` policy.
+    pub fn overflowed(&self) -> bool {
+        match &self.inner {
+            Inner::Unbounded(_) => false,
+            Inner::Bounded { queue, .. } => queue.state.lock().unwrap().overflowed,
+        }
+    }
+}
+
+impl Drop for PushReceiver {
+    fn drop(&mut self) {
+        if let Inner::Bounded { relay, .. } = &self.inner {
+            relay.abort();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::{PushKind, Value};
+
+    fn message(id: i64) -> PushInfo {
+        PushInfo {
+            kind: PushKind::Message,
+            data: vec![Value::Int(id)],
+        }
+    }
+
+    fn channel_message(channel: &str, id: i64) -> PushInfo {
+        PushInfo {
+            kind: PushKind::Message,
+            data: vec![Value::BulkString(channel.into()), Value::Int(id)],
+        }
+    }
+
+    fn id(message: PushInfo) -> i64 {
+        match message.data.last() {
+            Some(Value::Int(id)) => *id,
+            _ => panic!("unexpected message {message:?}"),
+        }
+    }
+
+    /// Sends `count` messages, and closes the channel once they're all relayed to the queue.
+    async fn receive_all(count: i64, overflow_policy: OverflowPolicy) -> (Vec<i64>, PushReceiver) {
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let mut receiver = PushReceiver::new(
+            push_rx,
+            Some(PushQueueConfig {
+                capacity: 3,
+                overflow_policy,
+            }),
+        );
+        for message_id in 0..count {
+            push_tx.send(message(message_id)).unwrap();
+        }
+        drop(push_tx);
+        if let Inner::Bounded { relay, .. } = &mut receiver.inner {
+            relay.await.unwrap();
+        }
+        let mut received = Vec::new();
+        while let Some(message) = receiver.recv().await {
+            received.push(id(message));
+        }
+        (received, receiver)
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest_keeps_the_newest_messages() {
+        let (received, receiver) = receive_all(5, OverflowPolicy::DropOldest).await;
+        assert_eq!(received, [2, 3, 4]);
+        assert!(!receiver.overflowed());
+    }
+
+    #[tokio::test]
+    async fn test_drop_newest_keeps_the_oldest_messages() {
+        let (received, _) = receive_all(5, OverflowPolicy::DropNewest).await;
+        assert_eq!(received, [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_delivers_the_queued_messages_and_closes() {
+        let (received, receiver) = receive_all(5, OverflowPolicy::Disconnect).await;
+        assert_eq!(received, [0, 1, 2]);
+        assert!(receiver.overflowed());
+    }
+
+    #[tokio::test]
+    async fn test_block_drops_after_the_timeout() {
+        let (received, _) = receive_all(5, OverflowPolicy::Block(Duration::from_millis(10))).await;
+        assert_eq!(received, [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_block_waits_for_the_subscriber() {
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let mut receiver = PushReceiver::new(
+            push_rx,
+            Some(PushQueueConfig {
+                capacity: 1,
+                overflow_policy: OverflowPolicy::Block(Duration::from_secs(10)),
+            }),
+        );
+        for message_id in 0..3 {
+            push_tx.send(message(message_id)).unwrap();
+        }
+        drop(push_tx);
+        let mut received = Vec::new();
+        while let Some(message) = receiver.recv().await {
+            received.push(id(message));
+        }
+        assert_eq!(received, [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_block_drops_the_messages_beyond_the_capacity_while_waiting() {
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let mut receiver = PushReceiver::new(
+            push_rx,
+            Some(PushQueueConfig {
+                capacity: 1,
+                overflow_policy: OverflowPolicy::Block(Duration::from_secs(10)),
+            }),
+        );
+        for message_id in 0..5 {
+            push_tx.send(message(message_id)).unwrap();
+        }
+        // 0 is queued, 1 waits for room, 2 waits in line, and 3 and 4 are dropped.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(push_tx);
+        let mut received = Vec::new();
+        while let Some(message) = receiver.recv().await {
+            received.push(id(message));
+        }
+        assert_eq!(received, [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_a_full_subscription_keeps_the_messages_of_the_others() {
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let mut receiver = PushReceiver::new(
+            push_rx,
+            Some(PushQueueConfig {
+                capacity: 2,
+                overflow_policy: OverflowPolicy::DropOldest,
+            }),
+        );
+        push_tx.send(channel_message("quiet", 0)).unwrap();
+        for message_id in 1..5 {
+            push_tx.send(channel_message("noisy", message_id)).unwrap();
+        }
+        push_tx.send(channel_message("quiet", 5)).unwrap();
+        drop(push_tx);
+        let mut received = Vec::new();
+        while let Some(message) = receiver.recv().await {
+            received.push(id(message));
+        }
+        assert_eq!(received, [0, 3, 4, 5]);
+    }
+
+    #[tokio::test]
+    async fn test_block_holds_only_the_full_subscription() {
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let mut receiver = PushReceiver::new(
+            push_rx,
+            Some(PushQueueConfig {
+                capacity: 1,
+                overflow_policy: OverflowPolicy::Block(Duration::from_secs(10)),
+            }),
+        );
+        push_tx.send(channel_message("noisy", 0)).unwrap();
+        push_tx.send(channel_message("noisy", 1)).unwrap();
+        push_tx.send(channel_message("quiet", 2)).unwrap();
+        // 1 waits for room, and 2 is queued meanwhile.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(push_tx);
+        let mut received = Vec::new();
+        while let Some(message) = receiver.recv().await {
+            received.push(id(message));
+        }
+        assert_eq!(received, [0, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_unbounded_receiver_forwards_the_channel() {
+        let (push_tx, push_rx) = mpsc::unbounded_channel();
+        let mut receiver = PushReceiver::new(push_rx, None);
+        push_tx.send(message(7)).unwrap();
+        drop(push_tx);
+        assert_eq!(receiver.recv().await.map(id), Some(7));
+        assert!(receiver.recv().await.is_none());
+        assert!(!receiver.overflowed());
+    }
+}
@@ -6,14 +6,14 @@ use logger_core::{log_debug, log_error, log_warn};
 use once_cell::sync::OnceCell;
 use redis::{
     Cmd, ErrorKind, PubSubChannelOrPattern, PubSubSubscriptionInfo, PubSubSubscriptionKind,
-    PubSubSynchronizer, RedisError, RedisResult, SlotMap, Value, cluster_routing::Routable,
-    cluster_routing::SingleNodeRoutingInfo,
+    PubSubSynchronizer, PushInfo, PushKind, RedisError, RedisResult, SlotMap, Value,
+    cluster_routing::Routable, cluster_routing::SingleNodeRoutingInfo,
 };
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, RwLock, Weak};
 use std::time::{Duration, Instant};
 use telemetrylib::GlideOpenTelemetry;
-use tokio::sync::{Notify, RwLock as TokioRwLock};
+use tokio::sync::{Notify, RwLock as TokioRwLock, mpsc};
 
 const LOCK_ERR: &str = "Lock poisoned";
 const DEFAULT_RECONCILIATION_INTERVAL: Duration = Duration::from_secs(3);
@@ -30,10 +30,15 @@ const STANDALONE_SUBSCRIPTION_KINDS: &[PubSubSubscriptionKind] = &[
     PubSubSubscriptionKind::Pattern,
 ];
 
+/// Denied subscriptions by kind, each with the error message the server refused it with
+type DeniedSubscriptions = HashMap<PubSubSubscriptionKind, HashMap<PubSubChannelOrPattern, String>>;
+
 /// Result of checking synchronization state - avoids recomputation
 struct SyncDiff {
     is_synchronized: bool,
     to_subscribe: PubSubSubscriptionInfo,
+    /// Patterns to subscribe to on specific primaries, see `all_primaries_patterns`
+    to_subscribe_by_address: HashMap<String, HashSet<PubSubChannelOrPattern>>,
     to_unsubscribe_by_address: HashMap<String, PubSubSubscriptionInfo>,
 }
 
@@ -69,6 +74,21 @@ pub struct GlidePubSubSynchronizer {
     /// Pending unsubscribes due to topology change that need to be sent to specific addresses
     pending_unsubscribes: RwLock<HashMap<String, PubSubSubscriptionInfo>>,
 
+    /// Desired subscriptions refused by the server with NOPERM. These aren't retried by the
+    /// reconciliation until the user subscribes to them again.
+    denied_subscriptions: RwLock<DeniedSubscriptions>,
+
+    /// Desired patterns that are subscribed to on every primary instead of a single node, such
+    /// as keyspace notification patterns - the notifications are published only by the node that
+    /// holds the key.
+    all_primaries_patterns: RwLock<HashSet<PubSubChannelOrPattern>>,
+
+    /// The addresses of the primaries, as of the last topology refresh
+    primary_addresses: RwLock<HashSet<String>>,
+
+    /// Sender for the `SubscriptionDenied` pushes
+    push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+
     /// Configurable reconciliation interval
     reconciliation_interval: Duration,
 
@@ -78,6 +98,7 @@ pub struct GlidePubSubSynchronizer {
 
 impl GlidePubSubSynchronizer {
     pub fn new(
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
         initial_subscriptions: Option<PubSubSubscriptionInfo>,
         is_cluster: bool,
         reconciliation_interval: Option<Duration>,
@@ -94,6 +115,10 @@ impl GlidePubSubSynchronizer {
             reconciliation_complete_notify: Notify::new(),
             reconciliation_task_handle: Mutex::new(None),
             pending_unsubscribes: RwLock::new(HashMap::new()),
+            denied_subscriptions: RwLock::new(HashMap::new()),
+            all_primaries_patterns: RwLock::new(HashSet::new()),
+            primary_addresses: RwLock::new(HashSet::new()),
+            push_sender,
             reconciliation_interval: interval,
             request_timeout,
         });
@@ -186,16 +211,29 @@ impl GlidePubSubSynchronizer {
         }
 
         let mut to_subscribe = PubSubSubscriptionInfo::new();
+        let denied = self.denied_subscriptions.read().expect(LOCK_ERR);
+        let all_primaries_patterns = self.all_primaries_patterns.read().expect(LOCK_ERR);
+        let primary_addresses = self.primary_addresses.read().expect(LOCK_ERR);
+        // Until the primaries are known, the all-primaries patterns are subscribed on a single node
+        let per_primary = |kind: &PubSubSubscriptionKind, channel: &PubSubChannelOrPattern| {
+            *kind == PubSubSubscriptionKind::Pattern
+                && !primary_addresses.is_empty()
+                && all_primaries_patterns.contains(channel)
+        };
 
         // Pass 2: O(desired_subscriptions)
-        // Iterate over desired subscriptions and add to to_sub each subscription not in actual
+        // Iterate over desired subscriptions and add to to_sub each subscription not in actual,
+        // skipping the ones the server refused
         for kind in self.subscription_kinds() {
             if let Some(desired_channels) = desired.get(kind) {
                 let actual_channels = actual.get(kind);
+                let denied_channels = denied.get(kind);
 
                 let to_sub: HashSet<_> = desired_channels
                     .iter()
+                    .filter(|ch| !per_primary(kind, ch))
                     .filter(|ch| actual_channels.is_none_or(|a| !a.contains(*ch)))
+                    .filter(|ch| denied_channels.is_none_or(|d| !d.contains_key(*ch)))
                     .cloned()
                     .collect();
 
@@ -205,11 +243,39 @@ impl GlidePubSubSynchronizer {
             }
         }
 
-        let is_synchronized = to_subscribe.is_empty() && to_unsubscribe_by_address.is_empty();
+        // Pass 3: O(all_primaries_patterns * primaries)
+        // Subscribe to the all-primaries patterns on each primary that misses them
+        let mut to_subscribe_by_address: HashMap<String, HashSet<PubSubChannelOrPattern>> =
+            HashMap::new();
+        let denied_patterns = denied.get(&PubSubSubscriptionKind::Pattern);
+        for pattern in all_primaries_patterns.iter() {
+            if !per_primary(&PubSubSubscriptionKind::Pattern, pattern)
+                || denied_patterns.is_some_and(|d| d.contains_key(pattern))
+            {
+                continue;
+            }
+            for addr in primary_addresses.iter() {
+                let subscribed = current_by_addr
+                    .get(addr)
+                    .and_then(|subs| subs.get(&PubSubSubscriptionKind::Pattern))
+                    .is_some_and(|patterns| patterns.contains(pattern));
+                if !subscribed {
+                    to_subscribe_by_address
+                        .entry(addr.clone())
+                        .or_default()
+                        .insert(pattern.clone());
+                }
+            }
+        }
+
+        let is_synchronized = to_subscribe.is_empty()
+            && to_subscribe_by_address.is_empty()
+            && to_unsubscribe_by_address.is_empty();
 
         SyncDiff {
             is_synchronized,
             to_subscribe,
+            to_subscribe_by_address,
             to_unsubscribe_by_address,
         }
     }
@@ -324,6 +390,17 @@ impl GlidePubSubSynchronizer {
                 .await;
         }
 
+        for (addr, patterns) in diff.to_subscribe_by_address {
+            let routing = Self::parse_address_to_routing(&addr).ok();
+            self.execute_subscription_change(
+                patterns,
+                PubSubSubscriptionKind::Pattern,
+                true,
+                routing,
+            )
+            .await;
+        }
+
         for (addr, subs_by_kind) in diff.to_unsubscribe_by_address {
             let routing = Self::parse_address_to_routing(&addr).ok();
 
@@ -414,8 +491,12 @@ impl GlidePubSubSynchronizer {
         } else {
             "unsubscribe"
         };
-        match self.apply_pubsub(&mut cmd, routing).await {
+        match self.apply_pubsub(&mut cmd, routing.clone()).await {
             Ok(_) => {}
+            Err(e) if is_subscribe && e.kind() == ErrorKind::PermissionDenied => {
+                self.subscribe_allowed_channels(channels, kind, cmd_name, routing)
+                    .await;
+            }
             Err(e) => {
                 log_error(
                     "pubsub_synchronizer",
@@ -425,6 +506,122 @@ impl GlidePubSubSynchronizer {
         }
     }
 
+    /// The server rejects a whole subscribe command if any of its channels is denied by ACL.
+    /// Subscribe to each channel separately, so that the allowed channels still work, and
+    /// record the denied ones together with the server's error.
+    async fn subscribe_allowed_channels(
+        &self,
+        channels: HashSet<PubSubChannelOrPattern>,
+        kind: PubSubSubscriptionKind,
+        cmd_name: &str,
+        routing: Option<SingleNodeRoutingInfo>,
+    ) {
+        let mut denied = HashMap::new();
+        for channel in channels {
+            let mut cmd = redis::cmd(cmd_name);
+            cmd.arg(channel.as_slice());
+            match self.apply_pubsub(&mut cmd, routing.clone()).await {
+                Ok(_) => {}
+                Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+                    denied.insert(channel, e.to_string());
+                }
+                Err(e) => {
+                    log_error(
+                        "pubsub_synchronizer",
+                        format!("Failed to subscribe {:?} channel: {:?}", kind, e),
+                    );
+                }
+            }
+        }
+
+        if denied.is_empty() {
+            return;
+        }
+        log_warn(
+            "pubsub_synchronizer",
+            format!(
+                "Subscription denied by ACL for {:?} channels: {}",
+                kind,
+                Self::format_channels(denied.keys())
+            ),
+        );
+        if let Some(push_sender) = &self.push_sender {
+            for (channel, error) in &denied {
+                let _ = push_sender.send(PushInfo {
+                    kind: PushKind::SubscriptionDenied,
+                    data: vec![
+                        Value::BulkString(Self::kind_name(kind).as_bytes().to_vec()),
+                        Value::BulkString(channel.clone()),
+                        Value::BulkString(error.as_bytes().to_vec()),
+                    ],
+                });
+            }
+        }
+        self.denied_subscriptions
+            .write()
+            .expect(LOCK_ERR)
+            .entry(kind)
+            .or_default()
+            .extend(denied);
+    }
+
+    fn kind_name(kind: PubSubSubscriptionKind) -> &'static str {
+        match kind {
+            PubSubSubscriptionKind::Exact => "exact",
+            PubSubSubscriptionKind::Pattern => "pattern",
+            PubSubSubscriptionKind::Sharded => "sharded",
+        }
+    }
+
+    fn format_channels<'a>(
+        channels: impl IntoIterator<Item = &'a PubSubChannelOrPattern>,
+    ) -> String {
+        channels
+            .into_iter()
+            .map(|channel| String::from_utf8_lossy(channel).into_owned())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn denial_error(channel: &PubSubChannelOrPattern, server_error: &str) -> RedisError {
+        RedisError::from((
+            ErrorKind::PermissionDenied,
+            "Subscription denied by ACL",
+            format!("{}: {server_error}", String::from_utf8_lossy(channel)),
+        ))
+    }
+
+    /// Returns an error if any of the given channels was denied by ACL, naming each denied
+    /// channel with the error the server refused it with.
+    fn check_denied_channels(
+        &self,
+        channels: &HashSet<PubSubChannelOrPattern>,
+        kind: PubSubSubscriptionKind,
+    ) -> RedisResult<()> {
+        let denied = self.denied_subscriptions.read().expect(LOCK_ERR);
+        let Some(denied_for_kind) = denied.get(&kind) else {
+            return Ok(());
+        };
+        let mut denied_channels: Vec<_> = channels
+            .iter()
+            .filter_map(|channel| denied_for_kind.get_key_value(channel))
+            .collect();
+        if denied_channels.is_empty() {
+            return Ok(());
+        }
+        denied_channels.sort();
+        let details = denied_channels
+            .into_iter()
+            .map(|(channel, error)| Self::denial_error(channel, error).to_string())
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(RedisError::from((
+            ErrorKind::PermissionDenied,
+            "Subscription denied by ACL",
+            format!("{:?} channels: {details}", kind),
+        )))
+    }
+
     /// Execute sharded unsubscribe, grouping by slot
     async fn execute_sharded_unsubscribe_by_slot(
         &self,
@@ -556,6 +753,8 @@ impl GlidePubSubSynchronizer {
             self.remove_desired_subscriptions(to_remove, kind);
         }
 
+        let denied_check = is_subscribe.then(|| channels_set.clone());
+
         // Build expected args based on subscription kind
         let (expected_channels, expected_patterns, expected_sharded) = match kind {
             PubSubSubscriptionKind::Exact => (Some(channels_set), None, None),
@@ -563,13 +762,20 @@ impl GlidePubSubSynchronizer {
             PubSubSubscriptionKind::Sharded => (None, None, Some(channels_set)),
         };
 
-        self.wait_for_sync(
-            timeout_ms,
-            expected_channels,
-            expected_patterns,
-            expected_sharded,
-        )
-        .await?;
+        let sync_result = self
+            .wait_for_sync(
+                timeout_ms,
+                expected_channels,
+                expected_patterns,
+                expected_sharded,
+            )
+            .await;
+
+        // Denied channels never become synced, so report them instead of the sync result.
+        if let Some(channels) = denied_check {
+            self.check_denied_channels(&channels, kind)?;
+        }
+        sync_result?;
 
         Ok(Value::Nil)
     }
@@ -641,11 +847,24 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
         self
     }
 
+    fn stop(&self) {
+        if let Some(handle) = self.reconciliation_task_handle.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
     fn add_desired_subscriptions(
         &self,
         channels: HashSet<PubSubChannelOrPattern>,
         subscription_type: PubSubSubscriptionKind,
     ) {
+        {
+            // Subscribing again retries channels that were denied, as the ACL may have changed
+            let mut denied = self.denied_subscriptions.write().expect(LOCK_ERR);
+            if let Some(denied_for_kind) = denied.get_mut(&subscription_type) {
+                denied_for_kind.retain(|channel, _| !channels.contains(channel));
+            }
+        }
         {
             let mut desired = self.desired_subscriptions.write().expect(LOCK_ERR);
             desired
@@ -657,15 +876,34 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
         self.trigger_reconciliation();
     }
 
+    fn add_all_primaries_subscriptions(&self, patterns: HashSet<PubSubChannelOrPattern>) {
+        self.all_primaries_patterns
+            .write()
+            .expect(LOCK_ERR)
+            .extend(patterns.iter().cloned());
+        self.add_desired_subscriptions(patterns, PubSubSubscriptionKind::Pattern);
+    }
+
     fn remove_desired_subscriptions(
         &self,
         channels: Option<HashSet<PubSubChannelOrPattern>>,
         subscription_type: PubSubSubscriptionKind,
     ) {
         {
+            if subscription_type == PubSubSubscriptionKind::Pattern {
+                let mut all_primaries = self.all_primaries_patterns.write().expect(LOCK_ERR);
+                match &channels {
+                    Some(patterns) => all_primaries.retain(|p| !patterns.contains(p)),
+                    None => all_primaries.clear(),
+                }
+            }
             let mut desired = self.desired_subscriptions.write().expect(LOCK_ERR);
+            let mut denied = self.denied_subscriptions.write().expect(LOCK_ERR);
             match channels {
                 Some(channels_to_remove) => {
+                    if let Some(denied_for_kind) = denied.get_mut(&subscription_type) {
+                        denied_for_kind.retain(|channel, _| !channels_to_remove.contains(channel));
+                    }
                     if let Some(existing) = desired.get_mut(&subscription_type) {
                         for channel in channels_to_remove {
                             existing.remove(&channel);
@@ -674,6 +912,7 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
                 }
                 None => {
                     desired.remove(&subscription_type);
+                    denied.remove(&subscription_type);
                 }
             }
         }
@@ -772,6 +1011,19 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
             .collect();
 
         let mut modified = false;
+        let primary_addresses: HashSet<String> = new_slot_map
+            .addresses_for_all_primaries()
+            .iter()
+            .map(|arc| arc.to_string())
+            .collect();
+        {
+            let mut known_primaries = self.primary_addresses.write().expect(LOCK_ERR);
+            if *known_primaries != primary_addresses {
+                *known_primaries = primary_addresses;
+                modified = true;
+            }
+        }
+        let all_primaries_patterns = self.all_primaries_patterns.read().expect(LOCK_ERR).clone();
 
         {
             let mut current_by_addr = self
@@ -830,6 +1082,12 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
                     let mut migrated_channels: HashSet<PubSubChannelOrPattern> = HashSet::new();
 
                     channels.retain(|channel| {
+                        // Subscribed on every primary, regardless of the slot
+                        if *kind == PubSubSubscriptionKind::Pattern
+                            && all_primaries_patterns.contains(channel)
+                        {
+                            return true;
+                        }
                         let slot = redis::cluster_topology::get_slot(channel);
 
                         match new_slot_map.shard_addrs_for_slot(slot) {
@@ -878,6 +1136,30 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
         (desired, actual)
     }
 
+    fn get_denied_subscriptions(&self) -> PubSubSubscriptionInfo {
+        self.denied_subscriptions
+            .read()
+            .expect(LOCK_ERR)
+            .iter()
+            .map(|(kind, channels)| (*kind, channels.keys().cloned().collect()))
+            .collect()
+    }
+
+    fn get_subscription_errors(
+        &self,
+    ) -> Vec<(PubSubSubscriptionKind, PubSubChannelOrPattern, RedisError)> {
+        self.denied_subscriptions
+            .read()
+            .expect(LOCK_ERR)
+            .iter()
+            .flat_map(|(kind, channels)| {
+                channels.iter().map(|(channel, error)| {
+                    (*kind, channel.clone(), Self::denial_error(channel, error))
+                })
+            })
+            .collect()
+    }
+
     fn trigger_reconciliation(&self) {
         self.reconciliation_notify.notify_one();
     }
@@ -1010,6 +1292,7 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
                 } else {
                     // Check that specified channels are synced (desired == actual for those channels)
                     let (desired, actual) = self.get_subscription_state();
+                    let denied = self.get_denied_subscriptions();
 
                     let is_synced_for_channels = |channels: &Option<
                         HashSet<PubSubChannelOrPattern>,
@@ -1019,6 +1302,7 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
                         channels.as_ref().is_none_or(|chs| {
                             let desired_set = desired.get(&kind);
                             let actual_set = actual.get(&kind);
+                            let denied_set = denied.get(&kind);
 
                             if chs.is_empty() {
                                 // When expecting empty set (unsubscribe-all), verify both
@@ -1027,11 +1311,13 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
                                 let actual_empty = actual_set.is_none_or(|a| a.is_empty());
                                 desired_empty && actual_empty
                             } else {
-                                // Check each specified channel has matching state in desired and actual
+                                // Check each specified channel has matching state in desired and actual,
+                                // or was denied by the server and won't be synced
                                 chs.iter().all(|ch| {
                                     let in_desired = desired_set.is_some_and(|d| d.contains(ch));
                                     let in_actual = actual_set.is_some_and(|a| a.contains(ch));
-                                    in_desired == in_actual
+                                    let is_denied = denied_set.is_some_and(|d| d.contains(ch));
+                                    in_desired == in_actual || is_denied
                                 })
                             }
                         })
@@ -1074,3 +1360,128 @@ impl PubSubSynchronizer for GlidePubSubSynchronizer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channels(names: &[&str]) -> HashSet<PubSubChannelOrPattern> {
+        names.iter().map(|name| name.as_bytes().to_vec()).collect()
+    }
+
+    fn synchronizer_with_denied_channel() -> Arc<GlidePubSubSynchronizer> {
+        let initial = HashMap::from([(PubSubSubscriptionKind::Exact, channels(&["a", "b"]))]);
+        let sync = GlidePubSubSynchronizer::new(
+            None,
+            Some(initial),
+            false,
+            Some(Duration::from_secs(60)),
+            Duration::from_secs(1),
+        );
+        sync.denied_subscriptions.write().unwrap().insert(
+            PubSubSubscriptionKind::Exact,
+            HashMap::from([(b"b".to_vec(), "NOPERM no access to 'b'".to_string())]),
+        );
+        sync
+    }
+
+    #[tokio::test]
+    async fn test_denied_channels_are_not_resubscribed() {
+        let sync = synchronizer_with_denied_channel();
+
+        let diff = sync.compute_sync_diff();
+
+        assert_eq!(
+            diff.to_subscribe.get(&PubSubSubscriptionKind::Exact),
+            Some(&channels(&["a"]))
+        );
+        let err = sync
+            .check_denied_channels(&channels(&["a", "b"]), PubSubSubscriptionKind::Exact)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::PermissionDenied);
+        assert!(
+            sync.check_denied_channels(&channels(&["a"]), PubSubSubscriptionKind::Exact)
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_denied_channels_report_the_server_error() {
+        let sync = synchronizer_with_denied_channel();
+
+        let errors = sync.get_subscription_errors();
+
+        assert_eq!(errors.len(), 1);
+        let (kind, channel, error) = &errors[0];
+        assert_eq!(*kind, PubSubSubscriptionKind::Exact);
+        assert_eq!(channel, b"b");
+        assert_eq!(error.kind(), ErrorKind::PermissionDenied);
+        assert!(error.to_string().contains("NOPERM no access to 'b'"));
+        let err = sync
+            .check_denied_channels(&channels(&["b"]), PubSubSubscriptionKind::Exact)
+            .unwrap_err();
+        assert!(err.to_string().contains("NOPERM no access to 'b'"));
+    }
+
+    #[tokio::test]
+    async fn test_subscribing_again_retries_denied_channels() {
+        let sync = synchronizer_with_denied_channel();
+
+        sync.add_desired_subscriptions(channels(&["b"]), PubSubSubscriptionKind::Exact);
+
+        assert!(
+            sync.get_denied_subscriptions()
+                .get(&PubSubSubscriptionKind::Exact)
+                .is_none_or(|denied| denied.is_empty())
+        );
+        assert_eq!(
+            sync.compute_sync_diff()
+                .to_subscribe
+                .get(&PubSubSubscriptionKind::Exact),
+            Some(&channels(&["a", "b"]))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsubscribing_clears_denied_channels() {
+        let sync = synchronizer_with_denied_channel();
+
+        sync.remove_desired_subscriptions(None, PubSubSubscriptionKind::Exact);
+
+        assert!(sync.get_denied_subscriptions().is_empty());
+    }
+    #[tokio::test]
+    async fn test_all_primaries_patterns_are_subscribed_on_each_primary() {
+        let sync = GlidePubSubSynchronizer::new(
+            None,
+            None,
+            true,
+            Some(Duration::from_secs(60)),
+            Duration::from_secs(1),
+        );
+        *sync.primary_addresses.write().unwrap() =
+            HashSet::from(["node1:6379".to_string(), "node2:6379".to_string()]);
+        let pattern = channels(&["__keyevent@0__:*"]);
+        sync.add_all_primaries_subscriptions(pattern.clone());
+        sync.add_current_subscriptions(
+            pattern.clone(),
+            PubSubSubscriptionKind::Pattern,
+            "node1:6379".to_string(),
+        );
+
+        let diff = sync.compute_sync_diff();
+
+        assert!(!diff.is_synchronized);
+        assert!(diff.to_subscribe.is_empty());
+        assert_eq!(
+            diff.to_subscribe_by_address,
+            HashMap::from([("node2:6379".to_string(), pattern.clone())])
+        );
+
+        sync.remove_desired_subscriptions(Some(pattern), PubSubSubscriptionKind::Pattern);
+
+        let diff = sync.compute_sync_diff();
+        assert!(diff.to_subscribe_by_address.is_empty());
+        assert!(sync.all_primaries_patterns.read().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Fair dispatching of the requests read from a socket.
+//!
+//! A wrapper sends all the requests of its process over a single socket, so a small
+//! latency-sensitive request - a health check, a GET - can be read right after a large batch.
+//! Instead of dispatching the requests in the order they were read, the socket listener queues
+//! them by their `priority`, and dispatches them by weighted round-robin: while all the queues are
+//! busy, every 13 dispatched requests are 8 high, 4 normal and 1 low priority requests. A request
+//! that waited for more than `MAX_QUEUE_WAIT` is dispatched before the others, whatever its
+//! priority, so a steady flow of high priority requests can't starve the rest.
+//!
+//! The queued requests of every priority are reported in the `queued_<priority>_priority_requests`
+//! statistics, and the requests dispatched ahead of their priority in `starved_requests`.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use telemetrylib::{QueuePriority, Telemetry};
+
+use crate::command_request::{CommandRequest, RequestPriority};
+
+/// The priorities, by dispatch order in a round.
+const PRIORITIES: [QueuePriority; 3] = [
+    QueuePriority::High,
+    QueuePriority::Normal,
+    QueuePriority::Low,
+];
+
+/// The share of the dispatched requests of each priority, indexed as `PRIORITIES`.
+const WEIGHTS: [u32; 3] = [8, 4, 1];
+
+/// How long a request can wait before it's dispatched ahead of the higher priority requests.
+const MAX_QUEUE_WAIT: Duration = Duration::from_millis(50);
+
+fn queue_priority(request: &CommandRequest) -> QueuePriority {
+    match request.priority.enum_value_or_default() {
+        RequestPriority::High => QueuePriority::High,
+        RequestPriority::Normal => QueuePriority::Normal,
+        RequestPriority::Low => QueuePriority::Low,
+    }
+}
+
+struct QueuedRequest {
+    request: CommandRequest,
+    queued_at: Instant,
+}
+
+/// The requests of a socket that were read but not dispatched yet.
+pub(crate) struct RequestQueue {
+    queues: [VecDeque<QueuedRequest>; 3],
+    /// The requests each priority may still dispatch in the current round.
+    credits: [u32; 3],
+}
+
+impl Default for RequestQueue {
+    fn default() -> Self {
+        Self {
+            queues: Default::default(),
+            credits: WEIGHTS,
+        }
+    }
+}
+
+impl RequestQueue {
+    pub(crate) fn push(&mut self, request: CommandRequest) {
+        let priority = queue_priority(&request);
+        Telemetry::incr_queued_requests(priority);
+        self.queues[priority as usize].push_back(QueuedRequest {
+            request,
+            queued_at: Instant::now(),
+        });
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+
+    /// Removes the next request to dispatch.
+    pub(crate) fn pop(&mut self) -> Option<CommandRequest> {
+        let index = match self.starved_queue() {
+            Some(index) => {
+                Telemetry::incr_starved_requests();
+                index
+            }
+            None => self.next_weighted_queue()?,
+        };
+        let queued = self.queues[index].pop_front()?;
+        Telemetry::decr_queued_requests(PRIORITIES[index], 1);
+        Some(queued.request)
+    }
+
+    /// Returns the lower priority queue whose oldest request waited the longest beyond
+    /// `MAX_QUEUE_WAIT`.
+    fn starved_queue(&self) -> Option<usize> {
+        self.queues
+            .iter()
+            .enumerate()
+            .skip(1)
+            .filter_map(|(index, queue)| Some((index, queue.front()?.queued_at)))
+            .filter(|(_, queued_at)| queued_at.elapsed() >= MAX_QUEUE_WAIT)
+            .min_by_key(|(_, queued_at)| *queued_at)
+            .map(|(index, _)| index)
+    }
+
+    fn next_weighted_queue(&mut self) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        loop {
+            let next = (0..PRIORITIES.len())
+                .find(|&index| self.credits[index] > 0 && !self.queues[index].is_empty());
+            if let Some(index) = next {
+                self.credits[index] -= 1;
+                return Some(index);
+            }
+            // Every busy priority used its share of the round.
+            self.credits = WEIGHTS;
+        }
+    }
+}
+
+impl Drop for RequestQueue {
+    fn drop(&mut self) {
+        for (priority, queue) in PRIORITIES.into_iter().zip(&self.queues) {
+            Telemetry::decr_queued_requests(priority, queue.len() as u64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(callback_idx: u32, priority: RequestPriority) -> CommandRequest {
+        let mut request = CommandRequest::new();
+        request.callback_idx = callback_idx;
+        request.priority = priority.into();
+        request
+    }
+
+    fn dispatch_order(queue: &mut RequestQueue) -> Vec<u32> {
+        std::iter::from_fn(|| queue.pop())
+            .map(|request| request.callback_idx)
+            .collect()
+    }
+
+    #[test]
+    fn test_requests_are_dispatched_by_weighted_round_robin() {
+        let mut queue = RequestQueue::default();
+        for idx in 0..3 {
+            queue.push(request(100 + idx, RequestPriority::Low));
+        }
+        for idx in 0..6 {
+            queue.push(request(idx, RequestPriority::Normal));
+        }
+        for idx in 0..10 {
+            queue.push(request(10 + idx, RequestPriority::High));
+        }
+        assert_eq!(
+            dispatch_order(&mut queue),
+            [
+                10, 11, 12, 13, 14, 15, 16, 17, 0, 1, 2, 3, 100, // first round
+                18, 19, 4, 5, 101, // second round
+                102
+            ]
+        );
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_requests_of_a_priority_keep_their_order() {
+        let mut queue = RequestQueue::default();
+        for idx in 0..20 {
+            queue.push(request(idx, RequestPriority::Normal));
+        }
+        assert_eq!(dispatch_order(&mut queue), (0..20).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_starved_requests_are_dispatched_first() {
+        let mut queue = RequestQueue::default();
+        queue.push(request(1, RequestPriority::Low));
+        queue.push(request(2, RequestPriority::High));
+        queue.queues[QueuePriority::Low as usize][0].queued_at -= MAX_QUEUE_WAIT;
+        assert_eq!(dispatch_order(&mut queue), [1, 2]);
+    }
+}
@@ -1,12 +1,13 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
 use redis::{Cmd, cmd};
+use strum_macros::{EnumIter, IntoStaticStr};
 
 #[cfg(feature = "proto")]
 use crate::command_request::RequestType as ProtobufRequestType;
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, EnumIter, IntoStaticStr)]
 pub enum RequestType {
     /// Invalid request type
     InvalidRequest = 0,
@@ -856,6 +857,13 @@ impl RequestType {
             RequestType::GetEx => CommandCompressionBehavior::DecompressValues,
             RequestType::GetDel => CommandCompressionBehavior::DecompressValues,
             RequestType::GetSet => CommandCompressionBehavior::DecompressValues,
+            RequestType::HSet => CommandCompressionBehavior::CompressValues,
+            RequestType::HMSet => CommandCompressionBehavior::CompressValues,
+            RequestType::HSetNX => CommandCompressionBehavior::CompressValues,
+            RequestType::HGet => CommandCompressionBehavior::DecompressValues,
+            RequestType::HMGet => CommandCompressionBehavior::DecompressValues,
+            RequestType::HVals => CommandCompressionBehavior::DecompressValues,
+            RequestType::HGetAll => CommandCompressionBehavior::DecompressValues,
             _ => CommandCompressionBehavior::NoCompression,
         }
     }
@@ -864,8 +872,9 @@ impl RequestType {
     /// Returns `None` if the command is compatible with compression.
     ///
     /// Commands that are incompatible with compression are those that:
-    /// - Manipulate string data on the server side (APPEND, GETRANGE, SETRANGE, STRLEN, LCS)
+    /// - Manipulate string data on the server side (APPEND, GETRANGE, SETRANGE, STRLEN, LCS, HSTRLEN)
     /// - Perform numeric operations on string values (INCR, INCRBY, INCRBYFLOAT, DECR, DECRBY)
+    ///   or hash field values (HINCRBY, HINCRBYFLOAT)
     /// - Perform bit operations on string values (GETBIT, SETBIT, BITCOUNT, BITPOS, BITFIELD, BITFIELD_RO, BITOP)
     pub fn compression_incompatibility_reason(self) -> Option<&'static str> {
         match self {
@@ -888,6 +897,9 @@ impl RequestType {
             RequestType::Substr => Some(
                 "SUBSTR (deprecated alias for GETRANGE) returns a substring of raw bytes, which would return compressed data",
             ),
+            RequestType::HStrlen => Some(
+                "HSTRLEN returns the length of the raw field value, which would return the compressed size instead of the original size",
+            ),
 
             // Numeric operations - expect numeric string values
             RequestType::Incr => {
@@ -905,6 +917,12 @@ impl RequestType {
             RequestType::DecrBy => {
                 Some("DECRBY expects a numeric string value, but would receive compressed bytes")
             }
+            RequestType::HIncrBy => {
+                Some("HINCRBY expects a numeric field value, but would receive compressed bytes")
+            }
+            RequestType::HIncrByFloat => Some(
+                "HINCRBYFLOAT expects a numeric field value, but would receive compressed bytes",
+            ),
 
             // Bit operations - operate on raw binary data
             RequestType::GetBit => Some(
@@ -968,6 +986,16 @@ impl RequestType {
             RequestType::GetEx => Some("GETEX"),
             RequestType::GetDel => Some("GETDEL"),
             RequestType::GetSet => Some("GETSET"),
+            RequestType::HSet => Some("HSET"),
+            RequestType::HMSet => Some("HMSET"),
+            RequestType::HSetNX => Some("HSETNX"),
+            RequestType::HGet => Some("HGET"),
+            RequestType::HMGet => Some("HMGET"),
+            RequestType::HVals => Some("HVALS"),
+            RequestType::HGetAll => Some("HGETALL"),
+            RequestType::HStrlen => Some("HSTRLEN"),
+            RequestType::HIncrBy => Some("HINCRBY"),
+            RequestType::HIncrByFloat => Some("HINCRBYFLOAT"),
             _ => None, // For other commands, return None
         }
     }
@@ -991,6 +1019,13 @@ impl RequestType {
             "GETEX" => Some(RequestType::GetEx),
             "GETDEL" => Some(RequestType::GetDel),
             "GETSET" => Some(RequestType::GetSet),
+            "HSET" => Some(RequestType::HSet),
+            "HMSET" => Some(RequestType::HMSet),
+            "HSETNX" => Some(RequestType::HSetNX),
+            "HGET" => Some(RequestType::HGet),
+            "HMGET" => Some(RequestType::HMGet),
+            "HVALS" => Some(RequestType::HVals),
+            "HGETALL" => Some(RequestType::HGetAll),
             // Incompatible commands - string manipulation
             "APPEND" => Some(RequestType::Append),
             "GETRANGE" => Some(RequestType::GetRange),
@@ -998,12 +1033,15 @@ impl RequestType {
             "STRLEN" => Some(RequestType::Strlen),
             "LCS" => Some(RequestType::LCS),
             "SUBSTR" => Some(RequestType::Substr),
+            "HSTRLEN" => Some(RequestType::HStrlen),
             // Incompatible commands - numeric operations
             "INCR" => Some(RequestType::Incr),
             "INCRBY" => Some(RequestType::IncrBy),
             "INCRBYFLOAT" => Some(RequestType::IncrByFloat),
             "DECR" => Some(RequestType::Decr),
             "DECRBY" => Some(RequestType::DecrBy),
+            "HINCRBY" => Some(RequestType::HIncrBy),
+            "HINCRBYFLOAT" => Some(RequestType::HIncrByFloat),
             // Incompatible commands - bit operations
             "GETBIT" => Some(RequestType::GetBit),
             "SETBIT" => Some(RequestType::SetBit),
@@ -1418,7 +1456,21 @@ impl RequestType {
             RequestType::SSubscribeBlocking => Some(cmd("SSUBSCRIBE_BLOCKING")),
             RequestType::SUnsubscribeBlocking => Some(cmd("SUNSUBSCRIBE_BLOCKING")),
             RequestType::GetSubscriptions => Some(cmd("GET_SUBSCRIPTIONS")),
-            _ => todo!(),
+            RequestType::BRPopLPush => Some(cmd("BRPOPLPUSH")),
+            RequestType::Eval => Some(cmd("EVAL")),
+            RequestType::EvalSha => Some(cmd("EVALSHA")),
+            RequestType::GeoRadius => Some(cmd("GEORADIUS")),
+            RequestType::GeoRadiusByMember => Some(cmd("GEORADIUSBYMEMBER")),
+            RequestType::GeoRadiusByMemberReadOnly => Some(cmd("GEORADIUSBYMEMBER_RO")),
+            RequestType::GeoRadiusReadOnly => Some(cmd("GEORADIUS_RO")),
+            RequestType::GetSet => Some(cmd("GETSET")),
+            RequestType::PSetEx => Some(cmd("PSETEX")),
+            RequestType::RPopLPush => Some(cmd("RPOPLPUSH")),
+            RequestType::ScriptLoad => Some(get_two_word_command("SCRIPT", "LOAD")),
+            RequestType::SetEx => Some(cmd("SETEX")),
+            RequestType::SetNX => Some(cmd("SETNX")),
+            RequestType::Substr => Some(cmd("SUBSTR")),
+            RequestType::XSetId => Some(cmd("XSETID")),
         }
     }
 }
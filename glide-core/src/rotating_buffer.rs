@@ -1,60 +1,166 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 #[allow(unused_imports)]
-use bytes::{Bytes, BytesMut};
+use bytes::{Buf, Bytes, BytesMut};
 use integer_encoding::VarInt;
 use logger_core::log_error;
 use protobuf::Message;
 use std::io;
+use thiserror::Error;
+
+/// The version byte written at the start of every checksummed frame.
+pub const CHECKSUMMED_FRAME_VERSION: u8 = 1;
+/// The size of the version byte and the trailing CRC32 of a checksummed frame.
+const CHECKSUMMED_FRAME_OVERHEAD: usize = 1 + size_of::<u32>();
+/// The maximum number of bytes in the varint encoding of a frame length.
+const MAX_LENGTH_BYTES: usize = 5;
+
+/// The way requests are framed on the socket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameFormat {
+    /// The varint length of the request, followed by the request.
+    #[default]
+    Plain,
+    /// The varint length of the frame, followed by [`CHECKSUMMED_FRAME_VERSION`], the request,
+    /// and the CRC32 of the request in little-endian.
+    Checksummed,
+}
+
+/// Errors in the structure of a received frame.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FrameError {
+    #[error("Frame of {length} bytes is too short to contain a version and a checksum")]
+    TooShort { length: usize },
+    #[error("Unsupported frame version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Frame checksum mismatch: frame carries {expected:#010x}, computed {actual:#010x}")]
+    ChecksumMismatch { expected: u32, actual: u32 },
+    #[error("Frame of {length} bytes exceeds the maximum request size of {max} bytes")]
+    TooLarge { length: usize, max: usize },
+    #[error("Frame length isn't a valid varint")]
+    InvalidLength,
+}
+
+impl From<FrameError> for io::Error {
+    fn from(err: FrameError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// Validates a checksummed frame, and returns the request it carries.
+fn unwrap_checksummed_frame(frame: Bytes) -> Result<Bytes, FrameError> {
+    if frame.len() < CHECKSUMMED_FRAME_OVERHEAD {
+        return Err(FrameError::TooShort {
+            length: frame.len(),
+        });
+    }
+    if frame[0] != CHECKSUMMED_FRAME_VERSION {
+        return Err(FrameError::UnsupportedVersion(frame[0]));
+    }
+    let checksum_start = frame.len() - size_of::<u32>();
+    let request = frame.slice(1..checksum_start);
+    let expected = u32::from_le_bytes(
+        frame[checksum_start..]
+            .try_into()
+            .expect("Checksum should be 4 bytes"),
+    );
+    let actual = crc32fast::hash(&request);
+    if expected != actual {
+        return Err(FrameError::ChecksumMismatch { expected, actual });
+    }
+    Ok(request)
+}
 
 /// An object handling a arranging read buffers, and parsing the data in the buffers into requests.
+/// Complete frames are split off the front of the buffer without copying, and the remainder of
+/// a partially received frame stays in place, with room reserved for the rest of it, so large
+/// frames are read into the buffer directly instead of being copied on every read.
 pub struct RotatingBuffer {
     backing_buffer: BytesMut,
+    frame_format: FrameFormat,
+    max_request_size: usize,
 }
 
 impl RotatingBuffer {
     pub fn new(buffer_size: usize) -> Self {
         Self {
             backing_buffer: BytesMut::with_capacity(buffer_size),
+            frame_format: FrameFormat::Plain,
+            max_request_size: u32::MAX as usize,
         }
     }
 
+    /// Sets the maximum size of a frame. Larger frames are rejected as soon as their length is read.
+    pub fn set_max_request_size(&mut self, max_request_size: usize) {
+        self.max_request_size = max_request_size;
+    }
+
+    /// Sets the framing of the requests parsed from now on.
+    pub fn set_frame_format(&mut self, frame_format: FrameFormat) {
+        self.frame_format = frame_format;
+    }
+
     /// Parses the requests in the buffer.
+    /// Corrupted or oversized frames are reported with an [`io::ErrorKind::InvalidData`] error wrapping a [`FrameError`].
     pub fn get_requests<T: Message>(&mut self) -> io::Result<Vec<T>> {
-        let buffer = self.backing_buffer.split().freeze();
         let mut results: Vec<T> = vec![];
-        let mut prev_position = 0;
-        let buffer_len = buffer.len();
-        while prev_position < buffer_len {
-            if let Some((request_len, bytes_read)) = u32::decode_var(&buffer[prev_position..]) {
-                let start_pos = prev_position + bytes_read;
-                if (start_pos + request_len as usize) > buffer_len {
-                    break;
-                } else {
-                    match T::parse_from_tokio_bytes(
-                        &buffer.slice(start_pos..start_pos + request_len as usize),
-                    ) {
-                        Ok(request) => {
-                            prev_position += request_len as usize + bytes_read;
-                            results.push(request);
-                        }
-                        Err(err) => {
-                            log_error("parse input", format!("Failed to parse request: {err}"));
-                            return Err(err.into());
-                        }
-                    }
+        loop {
+            let Some((request_len, bytes_read)) = u32::decode_var(&self.backing_buffer) else {
+                if self.backing_buffer.len() >= MAX_LENGTH_BYTES {
+                    return Err(self.frame_error(FrameError::InvalidLength));
                 }
-            } else {
+                break;
+            };
+            let request_len = request_len as usize;
+            if request_len > self.max_request_size {
+                return Err(self.frame_error(FrameError::TooLarge {
+                    length: request_len,
+                    max: self.max_request_size,
+                }));
+            }
+            let frame_len = bytes_read + request_len;
+            if frame_len > self.backing_buffer.len() {
+                // Reserve the rest of the frame at once, instead of growing the buffer on every read.
+                self.backing_buffer
+                    .reserve(frame_len - self.backing_buffer.len());
                 break;
             }
-        }
 
-        if prev_position != buffer.len() {
-            self.backing_buffer
-                .extend_from_slice(&buffer[prev_position..]);
+            let mut frame = self.backing_buffer.split_to(frame_len).freeze();
+            frame.advance(bytes_read);
+            let request_bytes = match self.frame_format {
+                FrameFormat::Plain => frame,
+                FrameFormat::Checksummed => match unwrap_checksummed_frame(frame) {
+                    Ok(request_bytes) => request_bytes,
+                    Err(err) => return Err(self.frame_error(err)),
+                },
+            };
+            match T::parse_from_tokio_bytes(&request_bytes) {
+                Ok(request) => results.push(request),
+                Err(err) => {
+                    log_error("parse input", format!("Failed to parse request: {err}"));
+                    return Err(err.into());
+                }
+            }
         }
         Ok(results)
     }
 
+    fn frame_error(&self, err: FrameError) -> io::Error {
+        log_error("parse input", format!("Received invalid frame: {err}"));
+        err.into()
+    }
+
+    /// Reallocates the buffer with `capacity` bytes if it holds more, to release the memory reserved
+    /// for large frames. Does nothing if the unparsed bytes don't fit in `capacity`.
+    pub fn shrink_to(&mut self, capacity: usize) {
+        if self.backing_buffer.capacity() <= capacity || self.backing_buffer.len() > capacity {
+            return;
+        }
+        let mut buffer = BytesMut::with_capacity(capacity);
+        buffer.extend_from_slice(&self.backing_buffer);
+        self.backing_buffer = buffer;
+    }
+
     pub fn current_buffer(&mut self) -> &mut BytesMut {
         &mut self.backing_buffer
     }
@@ -114,6 +220,28 @@ mod tests {
         buffer.extend_from_slice(&request.write_to_bytes().unwrap());
     }
 
+    fn write_checksummed_get(buffer: &mut BytesMut, callback_index: u32, key: &str) {
+        let request = create_command_request(
+            callback_index,
+            vec![Bytes::from(key.to_string())],
+            RequestType::Get,
+            false,
+        );
+        let request = request.write_to_bytes().unwrap();
+        write_length(buffer, (request.len() + CHECKSUMMED_FRAME_OVERHEAD) as u32);
+        buffer.put_u8(CHECKSUMMED_FRAME_VERSION);
+        buffer.extend_from_slice(&request);
+        buffer.put_u32_le(crc32fast::hash(&request));
+    }
+
+    fn frame_error(err: io::Error) -> FrameError {
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let inner = err.into_inner().expect("Expected a wrapped error");
+        *inner
+            .downcast::<FrameError>()
+            .expect("Expected a frame error")
+    }
+
     fn write_get(buffer: &mut BytesMut, callback_index: u32, key: &str, args_pointer: bool) {
         write_message(
             buffer,
@@ -365,4 +493,162 @@ mod tests {
             args_pointer,
         );
     }
+
+    #[rstest]
+    fn get_checksummed_requests() {
+        let mut rotating_buffer = RotatingBuffer::new(50);
+        rotating_buffer.set_frame_format(FrameFormat::Checksummed);
+        write_checksummed_get(rotating_buffer.current_buffer(), 100, "key1");
+        write_checksummed_get(rotating_buffer.current_buffer(), 101, "key2");
+        let requests = rotating_buffer.get_requests().unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_request(
+            &requests[0],
+            RequestType::Get,
+            100,
+            vec!["key1".into()],
+            false,
+        );
+        assert_request(
+            &requests[1],
+            RequestType::Get,
+            101,
+            vec!["key2".into()],
+            false,
+        );
+    }
+
+    #[rstest]
+    fn corrupted_checksummed_frame_is_reported() {
+        let mut rotating_buffer = RotatingBuffer::new(50);
+        rotating_buffer.set_frame_format(FrameFormat::Checksummed);
+        write_checksummed_get(rotating_buffer.current_buffer(), 100, "key");
+        // Flip a bit in the key
+        let buffer = rotating_buffer.current_buffer();
+        let index = buffer.len() - size_of::<u32>() - 1;
+        buffer[index] ^= 1;
+
+        let err = rotating_buffer
+            .get_requests::<CommandRequest>()
+            .unwrap_err();
+        assert!(matches!(
+            frame_error(err),
+            FrameError::ChecksumMismatch { .. }
+        ));
+    }
+
+    #[rstest]
+    fn unsupported_frame_version_is_reported() {
+        let mut rotating_buffer = RotatingBuffer::new(50);
+        rotating_buffer.set_frame_format(FrameFormat::Checksummed);
+        write_checksummed_get(rotating_buffer.current_buffer(), 100, "key");
+        let buffer = rotating_buffer.current_buffer();
+        buffer[1] = CHECKSUMMED_FRAME_VERSION + 1;
+
+        let err = rotating_buffer
+            .get_requests::<CommandRequest>()
+            .unwrap_err();
+        assert_eq!(
+            frame_error(err),
+            FrameError::UnsupportedVersion(CHECKSUMMED_FRAME_VERSION + 1)
+        );
+    }
+
+    #[rstest]
+    fn oversized_frame_is_rejected_before_it_is_received() {
+        let mut rotating_buffer = RotatingBuffer::new(24);
+        rotating_buffer.set_max_request_size(1000);
+        let mut request_bytes = BytesMut::new();
+        write_get(
+            &mut request_bytes,
+            100,
+            generate_random_string(2000).as_str(),
+            false,
+        );
+        // Only the length and a few bytes of the request were received
+        rotating_buffer
+            .current_buffer()
+            .extend_from_slice(&request_bytes[..10]);
+
+        let err = rotating_buffer
+            .get_requests::<CommandRequest>()
+            .unwrap_err();
+        assert!(matches!(
+            frame_error(err),
+            FrameError::TooLarge { max: 1000, .. }
+        ));
+    }
+
+    #[rstest]
+    fn invalid_length_is_rejected() {
+        let mut rotating_buffer = RotatingBuffer::new(24);
+        rotating_buffer
+            .current_buffer()
+            .extend_from_slice(&[0xff; MAX_LENGTH_BYTES]);
+
+        let err = rotating_buffer
+            .get_requests::<CommandRequest>()
+            .unwrap_err();
+        assert_eq!(frame_error(err), FrameError::InvalidLength);
+    }
+
+    #[rstest]
+    fn large_frame_is_received_in_place() {
+        const KEY_LENGTH: usize = 1_000_000;
+        const CHUNK_SIZE: usize = 65_536;
+        let mut rotating_buffer = RotatingBuffer::new(CHUNK_SIZE);
+        let key = generate_random_string(KEY_LENGTH);
+        let mut request_bytes = BytesMut::new();
+        write_get(&mut request_bytes, 100, key.as_str(), false);
+
+        let mut chunks = request_bytes.chunks(CHUNK_SIZE);
+        rotating_buffer
+            .current_buffer()
+            .extend_from_slice(chunks.next().unwrap());
+        assert!(
+            rotating_buffer
+                .get_requests::<CommandRequest>()
+                .unwrap()
+                .is_empty()
+        );
+        // The rest of the frame fits without growing the buffer
+        let capacity = rotating_buffer.current_buffer().capacity();
+        assert!(capacity >= request_bytes.len());
+
+        let mut requests = vec![];
+        for chunk in chunks {
+            rotating_buffer.current_buffer().extend_from_slice(chunk);
+            assert_eq!(rotating_buffer.current_buffer().capacity(), capacity);
+            requests = rotating_buffer.get_requests().unwrap();
+        }
+        assert_eq!(requests.len(), 1);
+        assert_request(&requests[0], RequestType::Get, 100, vec![key.into()], false);
+    }
+
+    #[rstest]
+    fn shrink_keeps_unparsed_bytes() {
+        let mut rotating_buffer = RotatingBuffer::new(1024);
+        rotating_buffer.current_buffer().reserve(1_000_000);
+        let mut request_bytes = BytesMut::new();
+        write_get(&mut request_bytes, 100, "key", false);
+        let (first, rest) = request_bytes.split_at(2);
+        rotating_buffer.current_buffer().extend_from_slice(first);
+
+        rotating_buffer.shrink_to(1024);
+        assert_eq!(rotating_buffer.current_buffer().capacity(), 1024);
+        // Unparsed bytes that don't fit are kept in place
+        rotating_buffer.shrink_to(1);
+        assert_eq!(rotating_buffer.current_buffer().capacity(), 1024);
+
+        rotating_buffer.current_buffer().extend_from_slice(rest);
+        let requests = rotating_buffer.get_requests::<CommandRequest>().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_request(
+            &requests[0],
+            RequestType::Get,
+            100,
+            vec!["key".into()],
+            false,
+        );
+    }
 }
@@ -0,0 +1,425 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Process-wide runtime configuration.
+//!
+//! [`GlideRuntimeConfig`] holds the defaults that apply to every client created in the process,
+//! for values that aren't set explicitly in the connection request. It can be initialized once,
+//! before the first client is created; otherwise the built-in defaults are used. The bindings
+//! initialize it from a serialized `RuntimeConfig` protobuf message, with
+//! [`GlideRuntimeConfig::initialize_from_protobuf`].
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use crate::client::{
+    DEFAULT_CONNECTION_TIMEOUT, DEFAULT_MAX_INFLIGHT_REQUESTS,
+    DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL, DEFAULT_RESPONSE_TIMEOUT, DEFAULT_RETRIES,
+};
+use crate::{DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, DEFAULT_TRACE_SAMPLE_PERCENTAGE};
+
+/// Default size of the buffer used to read requests from the socket.
+pub const DEFAULT_SOCKET_BUFFER_SIZE: usize = 65_536;
+
+/// Default time to wait for in-flight requests when shutting down on a signal.
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// Default maximum size of a single request read from the socket (512 MiB, the server's default `proto-max-bulk-len`).
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 512 * 1024 * 1024;
+
+/// Default warning threshold before the TLS client certificate expires (30 days).
+pub const DEFAULT_TLS_CERT_EXPIRY_WARNING: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Default warning threshold before the IAM credentials expire (15 minutes).
+pub const DEFAULT_IAM_CREDENTIALS_EXPIRY_WARNING: Duration = Duration::from_secs(15 * 60);
+
+/// Default interval between two checks of the process memory against the soft memory limit.
+pub const DEFAULT_MEMORY_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default size above which requests are rejected while the soft memory limit is exceeded (64 KiB).
+pub const DEFAULT_MEMORY_SHEDDING_REQUEST_SIZE: u64 = 64 * 1024;
+
+static RUNTIME_CONFIG: OnceLock<GlideRuntimeConfig> = OnceLock::new();
+
+/// The number of clients in the process, counted against `max_clients`.
+static CLIENTS: AtomicUsize = AtomicUsize::new(0);
+
+/// Process-wide defaults, used when a connection request doesn't override them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlideRuntimeConfig {
+    /// Maximum number of clients in the process. `None` means unlimited.
+    pub max_clients: Option<usize>,
+    /// Default timeout for a single request.
+    pub default_request_timeout: Duration,
+    /// Default timeout for establishing a connection.
+    pub default_connection_timeout: Duration,
+    /// Default maximum number of inflight requests per client.
+    pub default_inflight_requests_limit: u32,
+    /// Default number of retries for cluster requests.
+    pub default_retries: u32,
+    /// Default interval of the periodic topology checks in cluster mode.
+    pub default_periodic_topology_checks_interval: Duration,
+    /// Initial size of the buffer used to read requests from the socket.
+    pub socket_buffer_size: usize,
+    /// Maximum size of a single request read from the socket. Larger requests close the socket.
+    pub max_request_size: usize,
+    /// Whether the socket listener handles SIGTERM and SIGINT by shutting down gracefully and
+    /// exiting the process. Meant for processes that only host the socket listener.
+    pub handle_shutdown_signals: bool,
+    /// Maximum time to wait for in-flight requests during a graceful shutdown, before exiting.
+    pub shutdown_grace_period: Duration,
+    /// Whether to record the queue/network/decode latency breakdown of every command. Off by
+    /// default, as it takes a few timestamps per command.
+    pub record_latency_breakdown: bool,
+    /// Default interval between two exports of the OpenTelemetry signals, used when the
+    /// OpenTelemetry configuration of a binding doesn't set one.
+    pub telemetry_flush_interval: Duration,
+    /// Default percentage of the requests a span is created for, used when the OpenTelemetry
+    /// traces configuration of a binding doesn't set one.
+    pub telemetry_trace_sample_percentage: u32,
+    /// How long before the TLS client certificate expires to start warning about it.
+    pub tls_cert_expiry_warning: Duration,
+    /// How long before the AWS credentials used for IAM authentication expire to start warning
+    /// about them.
+    pub iam_credentials_expiry_warning: Duration,
+    /// Soft limit on the memory of the process, in bytes. While it's exceeded, requests larger
+    /// than `memory_shedding_request_size` are rejected, client-side caches are flushed and the
+    /// socket buffers are shrunk. `None` disables the limit.
+    pub soft_memory_limit: Option<u64>,
+    /// How often the memory of the process is checked against `soft_memory_limit`.
+    pub memory_check_interval: Duration,
+    /// Size in bytes above which requests are rejected while `soft_memory_limit` is exceeded.
+    pub memory_shedding_request_size: u64,
+    /// Size in bytes above which the arguments of a command are logged and counted as a large
+    /// request. `None` disables the check.
+    pub large_request_threshold: Option<u64>,
+    /// Size in bytes above which the response of a command is logged and counted as a large
+    /// response. `None` disables the check.
+    pub large_response_threshold: Option<u64>,
+    /// Whether requests larger than `large_request_threshold` fail instead of being sent.
+    pub reject_large_requests: bool,
+    /// Number of worker threads of the runtime shared by the clients in `Shared` runtime mode.
+    /// `None` starts a worker per core.
+    pub shared_runtime_worker_threads: Option<usize>,
+    /// Number of single-threaded runtimes the clients in `ThreadPerCore` runtime mode are spread
+    /// over. `None` starts one per core.
+    pub runtime_shards: Option<usize>,
+}
+
+impl Default for GlideRuntimeConfig {
+    fn default() -> Self {
+        Self {
+            max_clients: None,
+            default_request_timeout: DEFAULT_RESPONSE_TIMEOUT,
+            default_connection_timeout: DEFAULT_CONNECTION_TIMEOUT,
+            default_inflight_requests_limit: DEFAULT_MAX_INFLIGHT_REQUESTS,
+            default_retries: DEFAULT_RETRIES,
+            default_periodic_topology_checks_interval: DEFAULT_PERIODIC_TOPOLOGY_CHECKS_INTERVAL,
+            socket_buffer_size: DEFAULT_SOCKET_BUFFER_SIZE,
+            max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+            handle_shutdown_signals: false,
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            record_latency_breakdown: false,
+            telemetry_flush_interval: Duration::from_millis(
+                DEFAULT_FLUSH_SIGNAL_INTERVAL_MS as u64,
+            ),
+            telemetry_trace_sample_percentage: DEFAULT_TRACE_SAMPLE_PERCENTAGE,
+            tls_cert_expiry_warning: DEFAULT_TLS_CERT_EXPIRY_WARNING,
+            iam_credentials_expiry_warning: DEFAULT_IAM_CREDENTIALS_EXPIRY_WARNING,
+            soft_memory_limit: None,
+            memory_check_interval: DEFAULT_MEMORY_CHECK_INTERVAL,
+            memory_shedding_request_size: DEFAULT_MEMORY_SHEDDING_REQUEST_SIZE,
+            large_request_threshold: None,
+            large_response_threshold: None,
+            reject_large_requests: false,
+            shared_runtime_worker_threads: None,
+            runtime_shards: None,
+        }
+    }
+}
+
+impl GlideRuntimeConfig {
+    /// Sets the process-wide configuration.
+    /// Fails if the configuration is invalid, or if the configuration was already initialized -
+    /// either explicitly, or implicitly by a call to [`GlideRuntimeConfig::get`].
+    pub fn initialize(config: GlideRuntimeConfig) -> Result<(), String> {
+        config.validate()?;
+        RUNTIME_CONFIG
+            .set(config)
+            .map_err(|_| "Glide runtime configuration is already initialized".to_string())
+    }
+
+    /// Sets the process-wide configuration from a serialized `RuntimeConfig` protobuf message.
+    /// Fields that aren't set keep their defaults.
+    #[cfg(feature = "proto")]
+    pub fn initialize_from_protobuf(bytes: &[u8]) -> Result<(), String> {
+        use protobuf::Message;
+
+        let config = crate::connection_request::RuntimeConfig::parse_from_bytes(bytes)
+            .map_err(|err| format!("Invalid runtime configuration: {err}"))?;
+        Self::initialize(config.into())
+    }
+
+    /// Returns the process-wide configuration, initializing it with the defaults if needed.
+    pub fn get() -> &'static GlideRuntimeConfig {
+        RUNTIME_CONFIG.get_or_init(GlideRuntimeConfig::default)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.max_clients == Some(0) {
+            return Err("max_clients must be greater than 0".to_string());
+        }
+        if self.default_request_timeout.is_zero() {
+            return Err("default_request_timeout must be greater than 0".to_string());
+        }
+        if self.default_connection_timeout.is_zero() {
+            return Err("default_connection_timeout must be greater than 0".to_string());
+        }
+        if self.default_inflight_requests_limit == 0 {
+            return Err("default_inflight_requests_limit must be greater than 0".to_string());
+        }
+        if self.default_periodic_topology_checks_interval.is_zero() {
+            return Err(
+                "default_periodic_topology_checks_interval must be greater than 0".to_string(),
+            );
+        }
+        if self.socket_buffer_size == 0 {
+            return Err("socket_buffer_size must be greater than 0".to_string());
+        }
+        if self.max_request_size == 0 || self.max_request_size > u32::MAX as usize {
+            return Err("max_request_size must be between 1 and u32::MAX".to_string());
+        }
+        if self.telemetry_flush_interval.is_zero() {
+            return Err("telemetry_flush_interval must be greater than 0".to_string());
+        }
+        if self.telemetry_trace_sample_percentage > 100 {
+            return Err("telemetry_trace_sample_percentage must be between 0 and 100".to_string());
+        }
+        if self.soft_memory_limit == Some(0) {
+            return Err("soft_memory_limit must be greater than 0".to_string());
+        }
+        if self.memory_check_interval.is_zero() {
+            return Err("memory_check_interval must be greater than 0".to_string());
+        }
+        if self.large_request_threshold == Some(0) || self.large_response_threshold == Some(0) {
+            return Err("large payload thresholds must be greater than 0".to_string());
+        }
+        if self.shared_runtime_worker_threads == Some(0) || self.runtime_shards == Some(0) {
+            return Err("runtime thread counts must be greater than 0".to_string());
+        }
+        if self.reject_large_requests && self.large_request_threshold.is_none() {
+            return Err("reject_large_requests requires large_request_threshold".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "proto")]
+impl From<crate::connection_request::RuntimeConfig> for GlideRuntimeConfig {
+    fn from(value: crate::connection_request::RuntimeConfig) -> Self {
+        let defaults = Self::default();
+        let millis = |value: Option<u64>, default: Duration| {
+            value.map(Duration::from_millis).unwrap_or(default)
+        };
+        Self {
+            max_clients: value.max_clients.map(|max| max as usize),
+            default_request_timeout: millis(
+                value.default_request_timeout_ms.map(u64::from),
+                defaults.default_request_timeout,
+            ),
+            default_connection_timeout: millis(
+                value.default_connection_timeout_ms.map(u64::from),
+                defaults.default_connection_timeout,
+            ),
+            default_inflight_requests_limit: value
+                .default_inflight_requests_limit
+                .unwrap_or(defaults.default_inflight_requests_limit),
+            default_retries: value.default_retries.unwrap_or(defaults.default_retries),
+            default_periodic_topology_checks_interval: millis(
+                value.default_periodic_topology_checks_interval_ms,
+                defaults.default_periodic_topology_checks_interval,
+            ),
+            socket_buffer_size: value
+                .socket_buffer_size
+                .map_or(defaults.socket_buffer_size, |size| size as usize),
+            max_request_size: value
+                .max_request_size
+                .map_or(defaults.max_request_size, |size| size as usize),
+            handle_shutdown_signals: value
+                .handle_shutdown_signals
+                .unwrap_or(defaults.handle_shutdown_signals),
+            shutdown_grace_period: millis(
+                value.shutdown_grace_period_ms,
+                defaults.shutdown_grace_period,
+            ),
+            record_latency_breakdown: value
+                .record_latency_breakdown
+                .unwrap_or(defaults.record_latency_breakdown),
+            telemetry_flush_interval: millis(
+                value.telemetry_flush_interval_ms,
+                defaults.telemetry_flush_interval,
+            ),
+            telemetry_trace_sample_percentage: value
+                .telemetry_trace_sample_percentage
+                .unwrap_or(defaults.telemetry_trace_sample_percentage),
+            tls_cert_expiry_warning: millis(
+                value.tls_cert_expiry_warning_ms,
+                defaults.tls_cert_expiry_warning,
+            ),
+            iam_credentials_expiry_warning: millis(
+                value.iam_credentials_expiry_warning_ms,
+                defaults.iam_credentials_expiry_warning,
+            ),
+            soft_memory_limit: value.soft_memory_limit,
+            memory_check_interval: millis(
+                value.memory_check_interval_ms,
+                defaults.memory_check_interval,
+            ),
+            memory_shedding_request_size: value
+                .memory_shedding_request_size
+                .unwrap_or(defaults.memory_shedding_request_size),
+            large_request_threshold: value.large_request_threshold,
+            large_response_threshold: value.large_response_threshold,
+            reject_large_requests: value
+                .reject_large_requests
+                .unwrap_or(defaults.reject_large_requests),
+            shared_runtime_worker_threads: value
+                .shared_runtime_worker_threads
+                .map(|threads| threads as usize),
+            runtime_shards: value.runtime_shards.map(|shards| shards as usize),
+        }
+    }
+}
+
+/// A client's place in the `max_clients` limit, released when the last clone of the client is
+/// dropped.
+#[derive(Debug)]
+pub(crate) struct ClientSlot(&'static AtomicUsize);
+
+impl ClientSlot {
+    /// Reserves a place for a new client. Fails if the process already has `max_clients`
+    /// clients.
+    pub(crate) fn reserve() -> Result<Self, String> {
+        Self::reserve_in(&CLIENTS, GlideRuntimeConfig::get().max_clients)
+    }
+
+    fn reserve_in(
+        clients: &'static AtomicUsize,
+        max_clients: Option<usize>,
+    ) -> Result<Self, String> {
+        let max_clients = max_clients.unwrap_or(usize::MAX);
+        // Counting and checking in one step, so clients created concurrently can't exceed the limit.
+        clients
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |count| {
+                (count < max_clients).then_some(count + 1)
+            })
+            .map(|_| Self(clients))
+            .map_err(|_| format!("reached the maximum number of clients ({max_clients})"))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert_eq!(GlideRuntimeConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let config = GlideRuntimeConfig {
+            default_request_timeout: Duration::ZERO,
+            ..Default::default()
+        };
+        assert!(GlideRuntimeConfig::initialize(config).is_err());
+
+        let config = GlideRuntimeConfig {
+            max_clients: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = GlideRuntimeConfig {
+            soft_memory_limit: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = GlideRuntimeConfig {
+            reject_large_requests: true,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = GlideRuntimeConfig {
+            runtime_shards: Some(0),
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+
+        let config = GlideRuntimeConfig {
+            telemetry_trace_sample_percentage: 101,
+            ..Default::default()
+        };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_client_slots_are_reserved_atomically() {
+        static CLIENTS: AtomicUsize = AtomicUsize::new(0);
+        let slots: Vec<ClientSlot> = std::thread::scope(|scope| {
+            let threads: Vec<_> = (0..16)
+                .map(|_| scope.spawn(|| ClientSlot::reserve_in(&CLIENTS, Some(4))))
+                .collect();
+            threads
+                .into_iter()
+                .filter_map(|thread| thread.join().unwrap().ok())
+                .collect()
+        });
+        assert_eq!(slots.len(), 4);
+        assert!(ClientSlot::reserve_in(&CLIENTS, Some(4)).is_err());
+
+        drop(slots);
+        assert_eq!(CLIENTS.load(Ordering::Acquire), 0);
+        assert!(ClientSlot::reserve_in(&CLIENTS, Some(4)).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "proto")]
+    fn test_config_from_protobuf_keeps_unset_defaults() {
+        let mut proto = crate::connection_request::RuntimeConfig::new();
+        proto.max_clients = Some(10);
+        proto.default_request_timeout_ms = Some(1_000);
+        proto.soft_memory_limit = Some(1 << 30);
+        proto.runtime_shards = Some(4);
+        proto.telemetry_flush_interval_ms = Some(250);
+        let config = GlideRuntimeConfig::from(proto);
+        assert_eq!(
+            config,
+            GlideRuntimeConfig {
+                max_clients: Some(10),
+                default_request_timeout: Duration::from_secs(1),
+                soft_memory_limit: Some(1 << 30),
+                runtime_shards: Some(4),
+                telemetry_flush_interval: Duration::from_millis(250),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_config_is_initialized_once() {
+        // Another test may have already read the configuration, which initializes it.
+        let _ = GlideRuntimeConfig::initialize(GlideRuntimeConfig::default());
+        assert_eq!(GlideRuntimeConfig::get(), &GlideRuntimeConfig::default());
+        assert!(GlideRuntimeConfig::initialize(GlideRuntimeConfig::default()).is_err());
+    }
+}
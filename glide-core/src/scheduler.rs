@@ -0,0 +1,394 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! A delayed job scheduler on top of sorted sets.
+//!
+//! Jobs are stored in four keys sharing the `{name}` hash tag, so they always belong to the same
+//! slot in cluster mode:
+//! - `{name}:scheduled` - a sorted set of the job ids, scored by their due time.
+//! - `{name}:leased` - a sorted set of the jobs handed to a worker, scored by their lease expiry.
+//! - `{name}:payloads` - a hash of the job payloads.
+//! - `{name}:lease_tokens` - a hash of the token of each lease.
+//!
+//! [`Scheduler::pop_due`] atomically moves the due jobs to the leased set, under a new lease token.
+//! A worker acknowledges a job with [`Scheduler::ack`] once it's processed, and jobs whose lease
+//! expired without an ack are due again. Acks carry the lease token, so a worker whose lease
+//! expired can't acknowledge a job that was leased again to another worker. All times are read from the server's clock, so workers with skewed clocks agree
+//! on which jobs are due.
+
+use std::time::Duration;
+
+use rand::Rng;
+use redis::{ErrorKind, RedisError, RedisResult, Value};
+
+use crate::client::Client;
+use crate::errors::unexpected_response;
+use crate::scripts_container::{add_script, remove_script};
+
+const SCHEDULE_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+redis.call('HSET', KEYS[3], ARGV[1], ARGV[2])
+redis.call('ZREM', KEYS[2], ARGV[1])
+redis.call('HDEL', KEYS[4], ARGV[1])
+redis.call('ZADD', KEYS[1], now + tonumber(ARGV[3]), ARGV[1])
+return 1
+"#;
+
+const POP_DUE_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+for _, id in ipairs(redis.call('ZRANGEBYSCORE', KEYS[2], '-inf', now)) do
+    redis.call('ZREM', KEYS[2], id)
+    redis.call('HDEL', KEYS[4], id)
+    redis.call('ZADD', KEYS[1], now, id)
+end
+local jobs = {}
+local due = redis.call('ZRANGEBYSCORE', KEYS[1], '-inf', now, 'LIMIT', 0, tonumber(ARGV[1]))
+for _, id in ipairs(due) do
+    redis.call('ZREM', KEYS[1], id)
+    local payload = redis.call('HGET', KEYS[3], id)
+    if payload then
+        redis.call('ZADD', KEYS[2], now + tonumber(ARGV[2]), id)
+        redis.call('HSET', KEYS[4], id, ARGV[3])
+        table.insert(jobs, id)
+        table.insert(jobs, payload)
+    end
+end
+return jobs
+"#;
+
+const ACK_SCRIPT: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local acked = 0
+for i = 1, #ARGV, 2 do
+    local id = ARGV[i]
+    local expiry = redis.call('ZSCORE', KEYS[2], id)
+    if expiry and tonumber(expiry) > now and redis.call('HGET', KEYS[4], id) == ARGV[i + 1] then
+        redis.call('ZREM', KEYS[2], id)
+        redis.call('HDEL', KEYS[3], id)
+        redis.call('HDEL', KEYS[4], id)
+        acked = acked + 1
+    end
+end
+return acked
+"#;
+
+const CANCEL_SCRIPT: &str = r#"
+local cancelled = 0
+for _, id in ipairs(ARGV) do
+    redis.call('ZREM', KEYS[1], id)
+    redis.call('ZREM', KEYS[2], id)
+    redis.call('HDEL', KEYS[4], id)
+    cancelled = cancelled + redis.call('HDEL', KEYS[3], id)
+end
+return cancelled
+"#;
+
+/// Configuration of a [`Scheduler`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchedulerConfig {
+    /// The name of the scheduler, used as the hash tag of its keys.
+    pub name: String,
+    /// How long a popped job is reserved for its worker before it's due again.
+    pub lease: Duration,
+    /// Maximum number of jobs returned by a single pop.
+    pub batch_size: u64,
+}
+
+/// A job returned by [`Scheduler::pop_due`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduledJob {
+    pub id: Vec<u8>,
+    pub payload: Vec<u8>,
+    /// Identifies the lease of the job, and is required to acknowledge it.
+    pub lease_token: Vec<u8>,
+}
+
+/// A delayed job queue. The scripts it uses are registered for as long as it's alive.
+#[derive(Debug)]
+pub struct Scheduler {
+    config: SchedulerConfig,
+    schedule_hash: String,
+    pop_due_hash: String,
+    ack_hash: String,
+    cancel_hash: String,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        Self {
+            config,
+            schedule_hash: add_script(SCHEDULE_SCRIPT.as_bytes()),
+            pop_due_hash: add_script(POP_DUE_SCRIPT.as_bytes()),
+            ack_hash: add_script(ACK_SCRIPT.as_bytes()),
+            cancel_hash: add_script(CANCEL_SCRIPT.as_bytes()),
+        }
+    }
+
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.config
+    }
+
+    /// Returns the scheduled, leased, payloads and lease tokens keys.
+    pub fn keys(&self) -> [Vec<u8>; 4] {
+        let name = &self.config.name;
+        ["scheduled", "leased", "payloads", "lease_tokens"]
+            .map(|suffix| format!("{{{name}}}:{suffix}").into_bytes())
+    }
+
+    async fn invoke(&self, client: &mut Client, hash: &str, args: &[&[u8]]) -> RedisResult<Value> {
+        let keys = self.keys();
+        let keys = keys.iter().map(Vec::as_slice).collect();
+        client
+            .invoke_script(hash, &keys, &args.to_vec(), None)
+            .await
+    }
+
+    /// Schedules a job to be due after `delay`. Scheduling an existing id replaces its payload and
+    /// due time, and cancels its lease.
+    pub async fn schedule(
+        &self,
+        client: &mut Client,
+        id: &[u8],
+        payload: &[u8],
+        delay: Duration,
+    ) -> RedisResult<()> {
+        let delay = delay.as_millis().to_string();
+        self.invoke(
+            client,
+            &self.schedule_hash,
+            &[id, payload, delay.as_bytes()],
+        )
+        .await
+        .map(|_| ())
+    }
+
+    /// Returns up to `batch_size` due jobs, and leases them to the caller. Jobs whose lease expired
+    /// are returned again, under a new lease token.
+    pub async fn pop_due(&self, client: &mut Client) -> RedisResult<Vec<ScheduledJob>> {
+        let batch_size = self.config.batch_size.to_string();
+        let lease = self.config.lease.as_millis().to_string();
+        let lease_token = format!("{:032x}", rand::thread_rng().r#gen::<u128>());
+        let response = self
+            .invoke(
+                client,
+                &self.pop_due_hash,
+                &[
+                    batch_size.as_bytes(),
+                    lease.as_bytes(),
+                    lease_token.as_bytes(),
+                ],
+            )
+            .await?;
+        parse_jobs(response, lease_token.as_bytes())
+    }
+
+    /// Acknowledges leased jobs, removing them. Returns the number of acknowledged jobs - jobs
+    /// whose lease expired, that were already acknowledged, or that were leased again since they
+    /// were popped, are not counted.
+    pub async fn ack(&self, client: &mut Client, jobs: &[ScheduledJob]) -> RedisResult<i64> {
+        let args: Vec<&[u8]> = jobs
+            .iter()
+            .flat_map(|job| [job.id.as_slice(), job.lease_token.as_slice()])
+            .collect();
+        let response = self.invoke(client, &self.ack_hash, &args).await?;
+        let Value::Int(acked) = response else {
+            return Err(unexpected_response(
+                "number of acknowledged jobs",
+                &response,
+            ));
+        };
+        Ok(acked)
+    }
+
+    /// Removes jobs, whether they're scheduled or leased. Returns the number of removed jobs.
+    pub async fn cancel(&self, client: &mut Client, ids: &[&[u8]]) -> RedisResult<i64> {
+        let response = self.invoke(client, &self.cancel_hash, ids).await?;
+        let Value::Int(cancelled) = response else {
+            return Err(unexpected_response("number of cancelled jobs", &response));
+        };
+        Ok(cancelled)
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        for hash in [
+            &self.schedule_hash,
+            &self.pop_due_hash,
+            &self.ack_hash,
+            &self.cancel_hash,
+        ] {
+            remove_script(hash);
+        }
+    }
+}
+
+/// Parses the `[id, payload, id, payload, ...]` response of the pop script.
+fn parse_jobs(response: Value, lease_token: &[u8]) -> RedisResult<Vec<ScheduledJob>> {
+    let Value::Array(values) = response else {
+        return Err(unexpected_response("jobs", &response));
+    };
+    if !values.len().is_multiple_of(2) {
+        return Err(unexpected_response(
+            "id and payload pairs",
+            &Value::Array(values),
+        ));
+    }
+    let mut values = values.into_iter();
+    let mut jobs = Vec::new();
+    while let (Some(id), Some(payload)) = (values.next(), values.next()) {
+        match (id, payload) {
+            (Value::BulkString(id), Value::BulkString(payload)) => jobs.push(ScheduledJob {
+                id,
+                payload,
+                lease_token: lease_token.to_vec(),
+            }),
+            (id, _) => return Err(unexpected_response("job id and payload", &id)),
+        }
+    }
+    Ok(jobs)
+}
+
+/// Polls a [`Scheduler`] until jobs are due.
+///
+/// Between empty polls, the poller sleeps for the poll interval, randomly shortened or lengthened
+/// by up to the jitter, so that many workers started together don't poll in lockstep.
+#[derive(Debug)]
+pub struct SchedulerPoller {
+    scheduler: Scheduler,
+    interval: Duration,
+    jitter: Duration,
+}
+
+impl SchedulerPoller {
+    /// Fails if `interval` is zero, which would poll the server in a busy loop.
+    pub fn new(scheduler: Scheduler, interval: Duration, jitter: Duration) -> RedisResult<Self> {
+        if interval.is_zero() {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "The scheduler poll interval must be positive",
+            )));
+        }
+        Ok(Self {
+            scheduler,
+            interval,
+            jitter,
+        })
+    }
+
+    pub fn scheduler(&self) -> &Scheduler {
+        &self.scheduler
+    }
+
+    /// Returns the next due jobs, waiting until there are any.
+    pub async fn next_jobs(&self, client: &mut Client) -> RedisResult<Vec<ScheduledJob>> {
+        loop {
+            let jobs = self.scheduler.pop_due(client).await?;
+            if !jobs.is_empty() {
+                return Ok(jobs);
+            }
+            tokio::time::sleep(self.next_delay()).await;
+        }
+    }
+
+    fn next_delay(&self) -> Duration {
+        let jitter = self.jitter.as_millis() as i64;
+        let offset = if jitter > 0 {
+            rand::thread_rng().gen_range(-jitter..=jitter)
+        } else {
+            0
+        };
+        Duration::from_millis((self.interval.as_millis() as i64 + offset).max(1) as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SchedulerConfig {
+        SchedulerConfig {
+            name: "emails".to_string(),
+            lease: Duration::from_secs(30),
+            batch_size: 10,
+        }
+    }
+
+    #[test]
+    fn test_keys_share_a_slot() {
+        let scheduler = Scheduler::new(config());
+        let keys = scheduler.keys();
+        assert_eq!(keys[0], b"{emails}:scheduled".to_vec());
+        let slots: Vec<u16> = keys
+            .iter()
+            .map(|key| redis::cluster_topology::get_slot(key))
+            .collect();
+        assert!(slots.iter().all(|slot| *slot == slots[0]));
+    }
+
+    #[test]
+    fn test_parse_jobs() {
+        let response = Value::Array(vec![
+            Value::BulkString(b"1".to_vec()),
+            Value::BulkString(b"first".to_vec()),
+            Value::BulkString(b"2".to_vec()),
+            Value::BulkString(b"second".to_vec()),
+        ]);
+        assert_eq!(
+            parse_jobs(response, b"token").unwrap(),
+            vec![
+                ScheduledJob {
+                    id: b"1".to_vec(),
+                    payload: b"first".to_vec(),
+                    lease_token: b"token".to_vec(),
+                },
+                ScheduledJob {
+                    id: b"2".to_vec(),
+                    payload: b"second".to_vec(),
+                    lease_token: b"token".to_vec(),
+                },
+            ]
+        );
+        assert!(
+            parse_jobs(
+                Value::Array(vec![Value::BulkString(b"1".to_vec())]),
+                b"token"
+            )
+            .is_err()
+        );
+        assert!(parse_jobs(Value::Okay, b"token").is_err());
+    }
+
+    #[test]
+    fn test_poll_delay_is_within_jitter() {
+        let poller = SchedulerPoller::new(
+            Scheduler::new(config()),
+            Duration::from_millis(100),
+            Duration::from_millis(20),
+        )
+        .unwrap();
+        for _ in 0..100 {
+            let delay = poller.next_delay();
+            assert!(delay >= Duration::from_millis(80) && delay <= Duration::from_millis(120));
+        }
+        // A jitter as long as the interval never results in a busy loop
+        let poller = SchedulerPoller::new(
+            Scheduler::new(config()),
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+        )
+        .unwrap();
+        for _ in 0..100 {
+            assert!(poller.next_delay() >= Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn test_zero_poll_interval_is_rejected() {
+        let err = SchedulerPoller::new(Scheduler::new(config()), Duration::ZERO, Duration::ZERO)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidClientConfig);
+    }
+}
@@ -1,44 +1,67 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use super::rotating_buffer::RotatingBuffer;
-use crate::client::Client;
+use super::rotating_buffer::{FrameFormat, RotatingBuffer};
+use crate::client::config_validation::{ValidationError, validate_connection_request};
+use crate::client::durability::Durability;
 use crate::client::get_or_init_runtime;
+use crate::client::inline_command;
+use crate::client::push_queue_config;
+use crate::client::response_size;
+use crate::client::{Client, MemoryReservation};
+use crate::client_registry::{self, ClientRegistration};
 use crate::compression::process_command_args_for_compression;
 
 use crate::cluster_scan_container::get_cluster_scan_cursor;
+use crate::cluster_slots;
+use crate::command_metadata;
 use crate::command_request::{
-    Batch, ClusterScan, Command, CommandRequest, Routes, SlotTypes, command, command_request,
+    Batch, CloseClients, ClusterScan, Command, CommandRequest, ConfigureLogger, CreateClient,
+    GetCommandMetadata, GetKeySlots, LogFile, LogFormat, LogLevel, LogRotationFrequency,
+    ReconfigureClients, Routes, SlotTypes, StreamConsumerPoll, ValidateConnectionRequest, command,
+    command_request, log_file,
 };
+use crate::connection_request;
 use crate::connection_request::ConnectionRequest;
 use crate::errors::{RequestErrorType, error_message, error_type};
+use crate::graceful_shutdown::{self, InflightRequest};
+use crate::memory_limit;
 use crate::otel_db_semantics::{
     set_db_attributes, set_db_batch_attributes, set_db_script_attributes,
 };
+use crate::pubsub::push_queue::{PushQueueConfig, PushReceiver};
+use crate::request_queue::RequestQueue;
 use crate::response;
 use crate::response::Response;
+use crate::runtime_config::GlideRuntimeConfig;
+use crate::streams::{StreamConsumer, StreamConsumerConfig};
 use ClosingReason::*;
 use PipeListeningResult::*;
 use bytes::Bytes;
 use directories::BaseDirs;
-use logger_core::{log_debug, log_error, log_info, log_trace, log_warn};
+use futures::FutureExt;
+use logger_core::{
+    Level, LogContext, log_debug, log_error, log_info, log_trace, log_warn, log_with_context,
+};
 use once_cell::sync::Lazy;
 use protobuf::{Chars, Message};
 use redis::cluster_routing::{
     MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
 };
-use redis::cluster_routing::{ResponsePolicy, Routable};
 use redis::{
     ClusterScanArgs, Cmd, PipelineRetryStrategy, PushInfo, RedisError, ScanStateRC, Value,
 };
-use std::cell::Cell;
-use std::collections::HashSet;
+use std::cell::{Cell, RefCell};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::os::unix::fs::PermissionsExt;
+use std::os::unix::io::AsRawFd;
 use std::ptr::from_mut;
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::str;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 use telemetrylib::{GlideSpan, GlideSpanStatus};
 use thiserror::Error;
 
@@ -65,10 +88,52 @@ pub const ZSET: &str = "zset";
 pub const HASH: &str = "hash";
 pub const STREAM: &str = "stream";
 
+/// The id of the client created by the socket's initial connection request.
+const DEFAULT_CLIENT_ID: u32 = 0;
+/// The number of queued requests dispatched before yielding to the dispatched requests.
+const DISPATCH_ROUND_SIZE: usize = 64;
+
+/// Default time between heartbeats sent to the wrapper.
+const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+/// Default time without reading from the socket before it's closed, when heartbeats are enabled.
+const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Heartbeats sent to the wrapper, to detect a wrapper that died without closing its socket.
+#[derive(Debug, Clone, Copy)]
+struct SocketHeartbeat {
+    interval: Duration,
+    timeout: Duration,
+}
+
+impl SocketHeartbeat {
+    fn from_config(config: &connection_request::SocketHeartbeatConfig) -> Self {
+        let duration_or = |ms: u32, default: Duration| match ms {
+            0 => default,
+            ms => Duration::from_millis(ms.into()),
+        };
+        let interval = duration_or(config.interval_ms, DEFAULT_HEARTBEAT_INTERVAL);
+        // The wrapper answers each heartbeat, so the socket may be silent for up to an interval.
+        let timeout = duration_or(config.timeout_ms, DEFAULT_HEARTBEAT_TIMEOUT).max(interval * 2);
+        Self { interval, timeout }
+    }
+}
+
+/// A client served over a socket, registered in the process's client registry while it's open.
+struct SocketClient {
+    client: Client,
+    registration: ClientRegistration,
+}
+
+/// The clients served over a single socket, by their client id.
+type Clients = Rc<RefCell<HashMap<u32, SocketClient>>>;
+
 /// struct containing all objects needed to read from a unix stream.
 struct UnixStreamListener {
     read_socket: Rc<UnixStream>,
     rotating_buffer: RotatingBuffer,
+    initial_buffer_size: usize,
+    /// When data was last read from the socket.
+    last_read: Rc<Cell<Instant>>,
 }
 
 /// struct containing all objects needed to write to a socket.
@@ -76,6 +141,9 @@ struct Writer {
     socket: Rc<UnixStream>,
     lock: Mutex<()>,
     accumulated_outputs: Cell<Vec<u8>>,
+    /// The memory budget reservations of the responses in `accumulated_outputs`, released once
+    /// they're written to the socket.
+    accumulated_reservations: Cell<Vec<MemoryReservation>>,
     closing_sender: Sender<ClosingReason>,
 }
 
@@ -94,10 +162,14 @@ impl UnixStreamListener {
     fn new(read_socket: Rc<UnixStream>) -> Self {
         // if the logger has been initialized by the user (external or internal) on info level this log will be shown
         log_debug("connection", "new socket listener initiated");
-        let rotating_buffer = RotatingBuffer::new(65_536);
+        let config = GlideRuntimeConfig::get();
+        let mut rotating_buffer = RotatingBuffer::new(config.socket_buffer_size);
+        rotating_buffer.set_max_request_size(config.max_request_size);
         Self {
             read_socket,
             rotating_buffer,
+            initial_buffer_size: config.socket_buffer_size,
+            last_read: Rc::new(Cell::new(Instant::now())),
         }
     }
 
@@ -115,7 +187,12 @@ impl UnixStreamListener {
                     return ReadSocketClosed.into();
                 }
                 Ok(_) => {
-                    match self.rotating_buffer.get_requests() {
+                    self.last_read.set(Instant::now());
+                    let requests = self.rotating_buffer.get_requests();
+                    if memory_limit::is_exceeded() {
+                        self.rotating_buffer.shrink_to(self.initial_buffer_size);
+                    }
+                    match requests {
                         Ok(requests) => {
                             if !requests.is_empty() {
                                 return ReceivedValues(requests);
@@ -144,6 +221,7 @@ async fn write_to_output(writer: &Rc<Writer>) {
     };
 
     let mut output = writer.accumulated_outputs.take();
+    let mut reservations = writer.accumulated_reservations.take();
     loop {
         if output.is_empty() {
             return;
@@ -171,7 +249,9 @@ async fn write_to_output(writer: &Rc<Writer>) {
             }
         }
         output.clear();
+        reservations.clear();
         output = writer.accumulated_outputs.replace(output);
+        reservations = writer.accumulated_reservations.replace(reservations);
     }
 }
 
@@ -193,11 +273,37 @@ async fn write_closing_error(
 async fn write_result(
     resp_result: ClientUsageResult<Value>,
     callback_index: u32,
+    client_id: u32,
     writer: &Rc<Writer>,
     command_span_ptr: Option<u64>,
 ) -> Result<(), io::Error> {
+    // Only errors are logged with the context.
+    let client_id_text = resp_result.is_err().then(|| client_id.to_string());
+    let context = LogContext {
+        client_id: client_id_text.as_deref(),
+        ..Default::default()
+    };
+    let response = result_response(
+        resp_result,
+        callback_index,
+        client_id,
+        command_span_ptr,
+        &context,
+    );
+    write_to_writer(response, writer).await
+}
+
+/// Create the response of a request. Errors are logged with `context`.
+fn result_response(
+    resp_result: ClientUsageResult<Value>,
+    callback_index: u32,
+    client_id: u32,
+    command_span_ptr: Option<u64>,
+    context: &LogContext,
+) -> Response {
     let mut response = Response::new();
     response.callback_idx = callback_index;
+    response.client_id = client_id;
     response.is_push = false;
     response.root_span_ptr = command_span_ptr;
     let otel_command_span: Option<GlideSpan> = get_unsafe_span_from_ptr(command_span_ptr);
@@ -220,7 +326,7 @@ async fn write_result(
             }
         }
         Err(ClientUsageError::Internal(error_message)) => {
-            log_error("internal error", &error_message);
+            log_with_context(Level::Error, "internal error", &error_message, context);
             if let Some(span) = otel_command_span {
                 span.set_status(GlideSpanStatus::Error((&error_message).into()));
             }
@@ -229,7 +335,7 @@ async fn write_result(
             ))
         }
         Err(ClientUsageError::User(error_message)) => {
-            log_error("user error", &error_message);
+            log_with_context(Level::Error, "user error", &error_message, context);
             if let Some(span) = otel_command_span {
                 span.set_status(GlideSpanStatus::Error((&error_message).into()));
             }
@@ -242,8 +348,13 @@ async fn write_result(
         }
         Err(ClientUsageError::Redis(err)) => {
             let error_message = error_message(&err);
-            log_warn("received error", error_message.as_str());
-            log_debug("received error", format!("for callback {callback_index}"));
+            log_with_context(Level::Warn, "received error", &error_message, context);
+            log_with_context(
+                Level::Debug,
+                "received error",
+                format!("for callback {callback_index}"),
+                context,
+            );
             if let Some(span) = otel_command_span {
                 span.set_status(GlideSpanStatus::Error((&error_message).into()));
             }
@@ -256,6 +367,13 @@ async fn write_result(
                     RequestErrorType::CircuitBreakerOpen => {
                         response::RequestErrorType::CircuitBreakerOpen
                     }
+                    RequestErrorType::OutOfClientMemory => {
+                        response::RequestErrorType::OutOfClientMemory
+                    }
+                    RequestErrorType::UnexpectedResponseShape => {
+                        response::RequestErrorType::UnexpectedResponseShape
+                    }
+                    RequestErrorType::ClientClosing => response::RequestErrorType::ClientClosing,
                 }
                 .into(),
                 message: error_message.into(),
@@ -264,17 +382,37 @@ async fn write_result(
             Some(response::response::Value::RequestError(request_error))
         }
     };
-    write_to_writer(response, writer).await
+    response
+}
+
+/// Appends the varint length of the response, followed by the response, to `output`.
+pub(crate) fn encode_response(response: &Response, output: &mut Vec<u8>) -> protobuf::Result<()> {
+    response.write_length_delimited_to_vec(output)
 }
 
 async fn write_to_writer(response: Response, writer: &Rc<Writer>) -> Result<(), io::Error> {
+    write_response_to_writer(response, None, writer).await
+}
+
+/// Writes the response, keeping its memory budget reservation until it's written to the socket -
+/// possibly by another task, if that task holds the writer lock.
+async fn write_response_to_writer(
+    response: Response,
+    reservation: Option<MemoryReservation>,
+    writer: &Rc<Writer>,
+) -> Result<(), io::Error> {
     let mut vec = writer.accumulated_outputs.take();
-    let encode_result = response.write_length_delimited_to_vec(&mut vec);
+    let encode_result = encode_response(&response, &mut vec);
 
     // Write the response' length to the buffer
     match encode_result {
         Ok(_) => {
             writer.accumulated_outputs.set(vec);
+            if let Some(reservation) = reservation {
+                let mut reservations = writer.accumulated_reservations.take();
+                reservations.push(reservation);
+                writer.accumulated_reservations.set(reservations);
+            }
             write_to_output(writer).await;
             Ok(())
         }
@@ -314,6 +452,22 @@ fn get_redis_command(command: &Command) -> Result<Cmd, ClientUsageError> {
                 cmd.arg(arg.as_ref());
             }
         }
+        Some(command::Args::InlineCommand(inline)) => {
+            let request_type: crate::request_type::RequestType = command.request_type.into();
+            if !matches!(
+                request_type,
+                crate::request_type::RequestType::CustomCommand
+            ) {
+                return Err(ClientUsageError::User(
+                    "Inline commands must use the CustomCommand request type".to_string(),
+                ));
+            }
+            let args = inline_command::tokenize(&inline.text)
+                .map_err(|err| ClientUsageError::User(err.to_string()))?;
+            for arg in args {
+                cmd.arg(arg);
+            }
+        }
         None => {
             return Err(ClientUsageError::Internal(
                 "Failed to get request arguments, no arguments are set".to_string(),
@@ -330,11 +484,30 @@ fn get_redis_command(command: &Command) -> Result<Cmd, ClientUsageError> {
     Ok(cmd)
 }
 
+/// Routes an inline command without a route by its key index, if it has one.
+fn inline_key_route(
+    routes: Option<RoutingInfo>,
+    command: &Command,
+    cmd: &Cmd,
+) -> ClientUsageResult<Option<RoutingInfo>> {
+    match (&routes, &command.args) {
+        (None, Some(command::Args::InlineCommand(inline))) => inline
+            .key_index
+            .map(|index| inline_command::key_routing(cmd, index as usize))
+            .transpose()
+            .map_err(|err| ClientUsageError::User(err.to_string())),
+        _ => Ok(routes),
+    }
+}
+
+/// Sends a single command. With `durability`, returns whether the write was acknowledged by the
+/// requested number of replicas, or `None` for read-only commands.
 async fn send_command(
     mut cmd: Cmd,
     mut client: Client,
     routing: Option<RoutingInfo>,
-) -> ClientUsageResult<Value> {
+    durability: Option<Durability>,
+) -> ClientUsageResult<(Value, Option<bool>)> {
     if let Some(ref span) = cmd.span() {
         set_db_attributes(span, &cmd, &client);
     }
@@ -363,10 +536,23 @@ async fn send_command(
         }
     }
 
-    client
-        .send_command(&mut cmd, routing)
-        .await
-        .map_err(|err| err.into())
+    let Some(durability) = durability else {
+        return client
+            .send_command(&mut cmd, routing)
+            .await
+            .map(|value| (value, None))
+            .map_err(|err| err.into());
+    };
+    let (value, acknowledged) = client
+        .send_command_with_durability(&mut cmd, routing, durability)
+        .await?;
+    // Unlike single commands, pipelined responses aren't decompressed by the client
+    let value = process_batch_response_for_decompression(value, &client)
+        .map_err(|err| ClientUsageError::User(err.to_string()))?;
+    Ok((
+        value,
+        acknowledged.map(|acknowledged| acknowledged >= durability.num_replicas),
+    ))
 }
 
 /// Process a command for compression by extracting arguments and applying compression
@@ -439,6 +625,9 @@ fn process_command_for_compression(
         "SETEX" => crate::request_type::RequestType::SetEx,
         "PSETEX" => crate::request_type::RequestType::PSetEx,
         "SETNX" => crate::request_type::RequestType::SetNX,
+        "HSET" => crate::request_type::RequestType::HSet,
+        "HMSET" => crate::request_type::RequestType::HMSet,
+        "HSETNX" => crate::request_type::RequestType::HSetNX,
         // Incompatible commands - string manipulation
         "APPEND" => crate::request_type::RequestType::Append,
         "GETRANGE" => crate::request_type::RequestType::GetRange,
@@ -446,12 +635,15 @@ fn process_command_for_compression(
         "STRLEN" => crate::request_type::RequestType::Strlen,
         "LCS" => crate::request_type::RequestType::LCS,
         "SUBSTR" => crate::request_type::RequestType::Substr,
+        "HSTRLEN" => crate::request_type::RequestType::HStrlen,
         // Incompatible commands - numeric operations
         "INCR" => crate::request_type::RequestType::Incr,
         "INCRBY" => crate::request_type::RequestType::IncrBy,
         "INCRBYFLOAT" => crate::request_type::RequestType::IncrByFloat,
         "DECR" => crate::request_type::RequestType::Decr,
         "DECRBY" => crate::request_type::RequestType::DecrBy,
+        "HINCRBY" => crate::request_type::RequestType::HIncrBy,
+        "HINCRBYFLOAT" => crate::request_type::RequestType::HIncrByFloat,
         // Incompatible commands - bit operations
         "GETBIT" => crate::request_type::RequestType::GetBit,
         "SETBIT" => crate::request_type::RequestType::SetBit,
@@ -519,6 +711,34 @@ async fn cluster_scan(cluster_scan: ClusterScan, mut client: Client) -> ClientUs
         .map_err(|err| err.into())
 }
 
+// Run a single step of a managed stream consumer: ack the previous batch, claim idle entries and read new ones.
+async fn stream_consumer_poll(
+    request: StreamConsumerPoll,
+    mut client: Client,
+) -> ClientUsageResult<Value> {
+    if request.count == 0 {
+        return Err(ClientUsageError::User(
+            "Stream consumer poll requires a positive count".to_string(),
+        ));
+    }
+    let mut consumer = StreamConsumer::with_claim_cursor(
+        StreamConsumerConfig {
+            key: request.key.to_vec(),
+            group: request.group.to_vec(),
+            consumer: request.consumer.to_vec(),
+            count: request.count as u64,
+            block: request.block_ms.map(Duration::from_millis),
+            min_idle_time: request.min_idle_time_ms.map(Duration::from_millis),
+        },
+        request.claim_cursor.to_string(),
+    );
+    if !request.ack_ids.is_empty() {
+        consumer.ack(&mut client, &request.ack_ids).await?;
+    }
+    let result = consumer.poll(&mut client).await?;
+    Ok(result.into_value(consumer.claim_cursor()))
+}
+
 async fn invoke_script(
     hash: Chars,
     keys: Option<Vec<Bytes>>,
@@ -648,14 +868,17 @@ fn get_route(
     cmd: Option<&Cmd>,
 ) -> ClientUsageResult<Option<RoutingInfo>> {
     use crate::command_request::routes::Value;
-    let Some(route) = route.and_then(|route| route.value) else {
+    let Some(Routes {
+        value: Some(route),
+        aggregation,
+        ..
+    }) = route.map(|route| *route)
+    else {
         return Ok(None);
     };
     let get_response_policy = |cmd: Option<&Cmd>| {
-        cmd.and_then(|cmd| {
-            cmd.command()
-                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
-        })
+        crate::multi_node_response_policy(aggregation, cmd)
+            .map_err(|err| ClientUsageError::User(err.to_string()))
     };
     match route {
         Value::SimpleRoutes(simple_route) => {
@@ -664,12 +887,12 @@ fn get_route(
             })?;
             match simple_route {
                 crate::command_request::SimpleRoutes::AllNodes => Ok(Some(RoutingInfo::MultiNode(
-                    (MultipleNodeRoutingInfo::AllNodes, get_response_policy(cmd)),
+                    (MultipleNodeRoutingInfo::AllNodes, get_response_policy(cmd)?),
                 ))),
                 crate::command_request::SimpleRoutes::AllPrimaries => {
                     Ok(Some(RoutingInfo::MultiNode((
                         MultipleNodeRoutingInfo::AllMasters,
-                        get_response_policy(cmd),
+                        get_response_policy(cmd)?,
                     ))))
                 }
                 crate::command_request::SimpleRoutes::Random => {
@@ -704,16 +927,70 @@ fn get_route(
     }
 }
 
+/// The request type recorded in the logs of a request: the request type of a single command, and the
+/// kind of request otherwise.
+fn request_type_name(command: &command_request::Command) -> &'static str {
+    match command {
+        command_request::Command::SingleCommand(command) => {
+            let request_type: crate::request_type::RequestType = command.request_type.into();
+            request_type.into()
+        }
+        command_request::Command::Batch(_) => "Batch",
+        command_request::Command::ScriptInvocation(_)
+        | command_request::Command::ScriptInvocationPointers(_) => "ScriptInvocation",
+        command_request::Command::ClusterScan(_) => "ClusterScan",
+        command_request::Command::UpdateConnectionPassword(_) => "UpdateConnectionPassword",
+        command_request::Command::RefreshIamToken(_) => "RefreshIamToken",
+        command_request::Command::GetCacheMetrics(_) => "GetCacheMetrics",
+        command_request::Command::StreamConsumerPoll(_) => "StreamConsumerPoll",
+        _ => "ClientManagement",
+    }
+}
+
+/// The address the client was created with, recorded in the logs of its requests.
+fn seed_address(client: &Client) -> String {
+    format!("{}:{}", client.server_address(), client.server_port())
+}
+
 fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer>) {
+    let inflight_request = InflightRequest::new();
     task::spawn_local(async move {
+        let _inflight_request = inflight_request;
+        let started = Instant::now();
+        let request_type = request.command.as_ref().map(request_type_name);
+        let trace_enabled = logger_core::is_enabled(Level::Trace);
+        // The seed is read before the client is handed to the request, so it's only recorded with the
+        // traces of the request.
+        let seed = trace_enabled.then(|| seed_address(&client));
+        if trace_enabled {
+            let client_id = request.client_id.to_string();
+            log_with_context(
+                Level::Trace,
+                "request",
+                format!("received callback {}", request.callback_idx),
+                &LogContext {
+                    client_id: Some(&client_id),
+                    seed: seed.as_deref(),
+                    request_type,
+                    latency: None,
+                },
+            );
+        }
         // send_command() manages its own inflight tracking via InflightRequestTracker
         // on the Cmd. All other paths (batch, pipeline, cluster_scan, script,
         // update_password, refresh_iam) need inflight reservation at this level.
         // The tracker's Drop releases the slot automatically.
+        let durability = request.durability.as_ref().map(|durability| Durability {
+            num_replicas: durability.num_replicas,
+            timeout: Duration::from_millis(durability.timeout_ms.into()),
+        });
+        // Single commands sent with durability are pipelined with a WAIT, and don't go through
+        // send_command()'s inflight tracking either.
         let _inflight_guard = if !matches!(
             &request.command,
             Some(command_request::Command::SingleCommand(_))
-        ) {
+        ) || durability.is_some()
+        {
             match client.reserve_inflight_request() {
                 Some(tracker) => Some(tracker),
                 None => {
@@ -722,6 +999,7 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                             "Reached maximum inflight requests".to_string(),
                         )),
                         request.callback_idx,
+                        request.client_id,
                         &writer,
                         request.root_span_ptr,
                     )
@@ -733,6 +1011,8 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
             None
         };
 
+        let memory_budget = client.memory_budget();
+        let mut durability_achieved = None;
         let result = match request.command {
             Some(action) => match action {
                 command_request::Command::ClusterScan(cluster_scan_command) => {
@@ -741,10 +1021,17 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                 }
                 command_request::Command::SingleCommand(command) => {
                     match get_redis_command(&command) {
-                        Ok(mut cmd) => match get_route(request.route.0, Some(&cmd)) {
+                        Ok(mut cmd) => match get_route(request.route.0, Some(&cmd))
+                            .and_then(|routes| inline_key_route(routes, &command, &cmd))
+                        {
                             Ok(routes) => {
                                 cmd.set_span(get_unsafe_span_from_ptr(request.root_span_ptr));
-                                send_command(cmd, client, routes).await
+                                send_command(cmd, client, routes, durability).await.map(
+                                    |(value, achieved)| {
+                                        durability_achieved = achieved;
+                                        value
+                                    },
+                                )
                             }
                             Err(e) => Err(e),
                         },
@@ -802,6 +1089,10 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                     .await
                     .map_err(|err| err.into()),
 
+                command_request::Command::StreamConsumerPoll(poll) => {
+                    stream_consumer_poll(poll, client).await
+                }
+
                 command_request::Command::RefreshIamToken(_refresh) => client
                     .refresh_iam_token()
                     .await
@@ -834,6 +1125,19 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                         Err(e) => Err(e),
                     }
                 }
+
+                command_request::Command::CreateClient(_)
+                | command_request::Command::CloseClient(_)
+                | command_request::Command::ValidateConnectionRequest(_)
+                | command_request::Command::ListClients(_)
+                | command_request::Command::CloseClients(_)
+                | command_request::Command::ReconfigureClients(_)
+                | command_request::Command::Heartbeat(_)
+                | command_request::Command::GetCommandMetadata(_)
+                | command_request::Command::ConfigureLogger(_)
+                | command_request::Command::GetKeySlots(_) => Err(ClientUsageError::Internal(
+                    "Client management requests must be handled by the socket listener".to_string(),
+                )),
             },
             None => {
                 log_debug(
@@ -849,23 +1153,454 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
             }
         };
 
+        // The response is accounted against the memory budget until it's written to the socket.
+        let response_memory = memory_budget
+            .zip(result.as_ref().ok())
+            .map(|(budget, value)| budget.force_reserve(response_size(value)));
+
+        // The client id is only formatted if there is a log to record it in: the trace of the response, or
+        // the error.
+        let client_id = (trace_enabled || result.is_err()).then(|| request.client_id.to_string());
+        let context = LogContext {
+            client_id: client_id.as_deref(),
+            seed: seed.as_deref(),
+            request_type,
+            latency: Some(started.elapsed()),
+        };
+        if trace_enabled && result.is_ok() {
+            log_with_context(
+                Level::Trace,
+                "response",
+                format!("completed callback {}", request.callback_idx),
+                &context,
+            );
+        }
+        let mut response = result_response(
+            result,
+            request.callback_idx,
+            request.client_id,
+            request.root_span_ptr,
+            &context,
+        );
+        response.durability_achieved = durability_achieved;
         // _inflight_guard is dropped here, releasing the slot automatically.
-        let _res = write_result(result, request.callback_idx, &writer, request.root_span_ptr).await;
+        let _res = write_response_to_writer(response, response_memory, &writer).await;
     });
 }
 
-async fn handle_requests(
-    received_requests: Vec<CommandRequest>,
-    client: &Client,
-    writer: &Rc<Writer>,
+/// Creates an additional client on the socket, and registers it under the request's client id.
+fn handle_create_client(
+    request: CommandRequest,
+    create_client: CreateClient,
+    clients: Clients,
+    writer: Rc<Writer>,
+) {
+    task::spawn_local(async move {
+        let client_id = request.client_id;
+        let result = async {
+            if clients.borrow().contains_key(&client_id) {
+                return Err(ClientUsageError::User(format!(
+                    "Client id {client_id} is already in use"
+                )));
+            }
+            let connection_request =
+                ConnectionRequest::parse_from_tokio_bytes(&create_client.connection_request)
+                    .map_err(|err| {
+                        ClientUsageError::User(format!("Invalid connection request: {err}"))
+                    })?;
+            let push_queue = connection_request
+                .push_queue
+                .as_ref()
+                .map(push_queue_config);
+            let (push_tx, push_rx) = mpsc::unbounded_channel();
+            let (client, registration) = build_client(connection_request, Some(push_tx))
+                .await
+                .map_err(|err| ClientUsageError::User(err.to_string()))?;
+            let closed = registration.closed();
+            let registry_id = registration.id();
+            // Another request with the same id might have completed while this client was connecting.
+            match clients.borrow_mut().entry(client_id) {
+                Entry::Occupied(_) => {
+                    return Err(ClientUsageError::User(format!(
+                        "Client id {client_id} is already in use"
+                    )));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(SocketClient {
+                        client,
+                        registration,
+                    });
+                }
+            }
+            let push_rx = PushReceiver::new(push_rx, push_queue);
+            let push_writer = writer.clone();
+            task::spawn_local(async move {
+                if push_manager_loop(push_rx, push_writer, client_id).await {
+                    client_registry::close_clients(&[registry_id]);
+                }
+            });
+            task::spawn_local(remove_when_closed(
+                closed,
+                Rc::downgrade(&clients),
+                client_id,
+                registry_id,
+            ));
+            log_info("connection", format!("client {client_id} created"));
+            Ok(Value::Okay)
+        }
+        .await;
+        let _res = write_result(result, request.callback_idx, client_id, &writer, None).await;
+    });
+}
+
+/// Removes a client from the socket once it's closed through the client registry.
+/// Also completes when the client is closed by the socket, since dropping the registration
+/// completes `closed`.
+async fn remove_when_closed(
+    closed: impl Future<Output = ()>,
+    clients: Weak<RefCell<HashMap<u32, SocketClient>>>,
+    client_id: u32,
+    registry_id: u64,
 ) {
-    for request in received_requests {
-        handle_request(request, client.clone(), writer.clone());
+    closed.await;
+    let Some(clients) = clients.upgrade() else {
+        return;
+    };
+    let mut clients = clients.borrow_mut();
+    // The client id might have been reused by a client created after this one was closed.
+    if clients
+        .get(&client_id)
+        .is_some_and(|entry| entry.registration.id() == registry_id)
+    {
+        clients.remove(&client_id);
+        log_info(
+            "connection",
+            format!("client {client_id} closed through the client registry"),
+        );
+    }
+}
+
+/// Handles the requests that operate on the clients of the whole process.
+fn handle_client_registry_request(request: CommandRequest, writer: Rc<Writer>) {
+    task::spawn_local(async move {
+        let result = match &request.command {
+            Some(command_request::Command::ListClients(_)) => Ok(Value::Array(
+                client_registry::clients()
+                    .into_iter()
+                    .map(|info| info.into_value())
+                    .collect(),
+            )),
+            Some(command_request::Command::CloseClients(CloseClients { registry_ids, .. })) => Ok(
+                Value::Int(client_registry::close_clients(registry_ids) as i64),
+            ),
+            Some(command_request::Command::ReconfigureClients(reconfigure)) => {
+                reconfigure_clients(reconfigure).await
+            }
+            _ => Err(ClientUsageError::Internal(
+                "Expected a client registry request".to_string(),
+            )),
+        };
+        let _res = write_result(
+            result,
+            request.callback_idx,
+            request.client_id,
+            &writer,
+            None,
+        )
+        .await;
+    });
+}
+
+async fn reconfigure_clients(reconfigure: &ReconfigureClients) -> ClientUsageResult<Value> {
+    let Some(update_password) = reconfigure.update_connection_password.as_ref() else {
+        return Err(ClientUsageError::User(
+            "No reconfiguration was requested".to_string(),
+        ));
+    };
+    let results = client_registry::update_connection_passwords(
+        &reconfigure.registry_ids,
+        update_password
+            .password
+            .as_ref()
+            .map(|password| password.to_string()),
+        update_password.immediate_auth,
+    )
+    .await;
+    Ok(Value::Map(
+        results
+            .into_iter()
+            .map(|(id, result)| {
+                let result = match result {
+                    Ok(_) => Value::Okay,
+                    Err(err) => Value::BulkString(err.to_string().into_bytes()),
+                };
+                (Value::Int(id as i64), result)
+            })
+            .collect(),
+    ))
+}
+
+/// Validates a connection request without connecting, and responds with the problems found.
+fn handle_validate_connection_request(
+    request: CommandRequest,
+    validate: ValidateConnectionRequest,
+    writer: Rc<Writer>,
+) {
+    task::spawn_local(async move {
+        let result = match ConnectionRequest::parse_from_tokio_bytes(&validate.connection_request) {
+            Ok(connection_request) => Ok(Value::Array(
+                validate_connection_request(&connection_request)
+                    .await
+                    .into_iter()
+                    .map(ValidationError::into_value)
+                    .collect(),
+            )),
+            Err(err) => Err(ClientUsageError::User(format!(
+                "Invalid connection request: {err}"
+            ))),
+        };
+        let _res = write_result(
+            result,
+            request.callback_idx,
+            request.client_id,
+            &writer,
+            None,
+        )
+        .await;
+    });
+}
+
+/// Responds with the metadata of the requested commands, or of all commands.
+fn handle_get_command_metadata(
+    request: CommandRequest,
+    get_metadata: GetCommandMetadata,
+    writer: Rc<Writer>,
+) {
+    let metadata = if get_metadata.commands.is_empty() {
+        command_metadata::command_table()
+            .iter()
+            .map(|metadata| metadata.clone().into_value())
+            .collect()
+    } else {
+        get_metadata
+            .commands
+            .iter()
+            .map(|command| {
+                command_metadata::command_metadata(command)
+                    .map_or(Value::Nil, |metadata| metadata.clone().into_value())
+            })
+            .collect()
+    };
+    task::spawn_local(async move {
+        let _res = write_result(
+            Ok(Value::Array(metadata)),
+            request.callback_idx,
+            request.client_id,
+            &writer,
+            None,
+        )
+        .await;
+    });
+}
+
+/// Responds with the slot of each key, or a cross-slot error if the keys are required to share a slot
+/// and don't.
+fn handle_get_key_slots(request: CommandRequest, get_slots: GetKeySlots, writer: Rc<Writer>) {
+    let result = if get_slots.require_same_slot {
+        cluster_slots::keys_share_slot(&get_slots.keys)
+            .map(|_| ())
+            .map_err(ClientUsageError::Redis)
+    } else {
+        Ok(())
+    };
+    let result = result.map(|()| {
+        Value::Array(
+            get_slots
+                .keys
+                .iter()
+                .map(|key| Value::Int(cluster_slots::cluster_slot(key).into()))
+                .collect(),
+        )
+    });
+    task::spawn_local(async move {
+        let _res = write_result(
+            result,
+            request.callback_idx,
+            request.client_id,
+            &writer,
+            None,
+        )
+        .await;
+    });
+}
+
+/// Changes the level, format and file of the process' logs.
+fn handle_configure_logger(
+    request: CommandRequest,
+    configure: ConfigureLogger,
+    writer: Rc<Writer>,
+) {
+    let result = configure_logger(configure).map(|()| Value::Okay);
+    task::spawn_local(async move {
+        let _res = write_result(
+            result,
+            request.callback_idx,
+            request.client_id,
+            &writer,
+            None,
+        )
+        .await;
+    });
+}
+
+fn configure_logger(configure: ConfigureLogger) -> ClientUsageResult<()> {
+    let rotation = configure
+        .file
+        .as_ref()
+        .map(|file| log_file_rotation(file).map(|rotation| (&*file.file_name, rotation)))
+        .transpose()?;
+    if let Some(level) = configure.level {
+        logger_core::set_level(match level.enum_value_or_default() {
+            LogLevel::LogLevelError => logger_core::Level::Error,
+            LogLevel::LogLevelWarn => logger_core::Level::Warn,
+            LogLevel::LogLevelInfo => logger_core::Level::Info,
+            LogLevel::LogLevelDebug => logger_core::Level::Debug,
+            LogLevel::LogLevelTrace => logger_core::Level::Trace,
+            LogLevel::LogLevelOff => logger_core::Level::Off,
+        });
+    }
+    if let Some((file_name, rotation)) = rotation {
+        logger_core::set_file(file_name, rotation);
+    }
+    if let Some(format) = configure.format {
+        logger_core::set_format(match format.enum_value_or_default() {
+            LogFormat::LogFormatText => logger_core::LogFormat::Text,
+            LogFormat::LogFormatJson => logger_core::LogFormat::Json,
+        });
+    }
+    Ok(())
+}
+
+fn log_file_rotation(file: &LogFile) -> ClientUsageResult<logger_core::FileRotation> {
+    if file.file_name.is_empty() {
+        return Err(ClientUsageError::User(
+            "The log file name is empty".to_string(),
+        ));
+    }
+    Ok(match &file.rotation {
+        None => logger_core::FileRotation::Hourly,
+        Some(log_file::Rotation::Size(size)) => {
+            if size.max_bytes == 0 {
+                return Err(ClientUsageError::User(
+                    "The log file size limit must be positive".to_string(),
+                ));
+            }
+            logger_core::FileRotation::Size {
+                max_bytes: usize::try_from(size.max_bytes).unwrap_or(usize::MAX),
+                max_files: size.max_files as usize,
+            }
+        }
+        Some(log_file::Rotation::Time(time)) => logger_core::FileRotation::Time {
+            frequency: match time.frequency.enum_value_or_default() {
+                LogRotationFrequency::LogRotationHourly => logger_core::RotationFrequency::Hourly,
+                LogRotationFrequency::LogRotationDaily => logger_core::RotationFrequency::Daily,
+                LogRotationFrequency::LogRotationWeekly => logger_core::RotationFrequency::Weekly,
+            },
+            max_files: time.max_files as usize,
+        },
+    })
+}
+
+/// Dispatches up to `DISPATCH_ROUND_SIZE` queued requests.
+async fn dispatch_requests(queue: &RefCell<RequestQueue>, clients: &Clients, writer: &Rc<Writer>) {
+    for _ in 0..DISPATCH_ROUND_SIZE {
+        let Some(request) = queue.borrow_mut().pop() else {
+            break;
+        };
+        dispatch_request(request, clients, writer);
     }
     // Yield to ensure that the subtasks aren't starved.
     task::yield_now().await;
 }
 
+fn dispatch_request(mut request: CommandRequest, clients: &Clients, writer: &Rc<Writer>) {
+    match request.command.take() {
+        Some(command_request::Command::CreateClient(create_client)) => {
+            handle_create_client(request, create_client, clients.clone(), writer.clone());
+        }
+        Some(command_request::Command::ValidateConnectionRequest(validate)) => {
+            handle_validate_connection_request(request, validate, writer.clone());
+        }
+        Some(command_request::Command::GetCommandMetadata(get_metadata)) => {
+            handle_get_command_metadata(request, get_metadata, writer.clone());
+        }
+        Some(command_request::Command::ConfigureLogger(configure)) => {
+            handle_configure_logger(request, configure, writer.clone());
+        }
+        Some(command_request::Command::GetKeySlots(get_slots)) => {
+            handle_get_key_slots(request, get_slots, writer.clone());
+        }
+        Some(command_request::Command::Heartbeat(_)) => {
+            // Reading the heartbeat from the socket is all it's for.
+        }
+        command @ Some(
+            command_request::Command::ListClients(_)
+            | command_request::Command::CloseClients(_)
+            | command_request::Command::ReconfigureClients(_),
+        ) => {
+            request.command = command;
+            handle_client_registry_request(request, writer.clone());
+        }
+        Some(command_request::Command::CloseClient(close)) => {
+            // Dropping the client closes its connections once its in-flight requests complete.
+            let removed = clients.borrow_mut().remove(&request.client_id);
+            let writer = writer.clone();
+            task::spawn_local(async move {
+                let result = match (removed, close.shutdown_deadline_ms) {
+                    (Some(removed), Some(deadline_ms)) => {
+                        let deadline = Duration::from_millis(deadline_ms.into());
+                        let cancelled = removed.client.shutdown(deadline).await;
+                        Ok(Value::Int(cancelled as i64))
+                    }
+                    (Some(_), None) => Ok(Value::Okay),
+                    (None, _) => Err(unknown_client_error(request.client_id)),
+                };
+                let _res = write_result(
+                    result,
+                    request.callback_idx,
+                    request.client_id,
+                    &writer,
+                    None,
+                )
+                .await;
+            });
+        }
+        command => {
+            request.command = command;
+            let client = clients
+                .borrow()
+                .get(&request.client_id)
+                .map(|entry| entry.client.clone());
+            match client {
+                Some(client) => handle_request(request, client, writer.clone()),
+                None => {
+                    let writer = writer.clone();
+                    task::spawn_local(async move {
+                        let _res = write_result(
+                            Err(unknown_client_error(request.client_id)),
+                            request.callback_idx,
+                            request.client_id,
+                            &writer,
+                            request.root_span_ptr,
+                        )
+                        .await;
+                    });
+                }
+            }
+        }
+    }
+}
+
 /// This function converts a raw pointer to a GlideSpan into a safe Rust reference.
 /// It handles the unsafe pointer operations internally, incrementing the reference count
 /// to ensure the span remains valid while in use.
@@ -899,11 +1634,16 @@ pub fn close_socket(socket_path: &String) {
     let _ = std::fs::remove_file(socket_path);
 }
 
-async fn create_client(
-    writer: &Rc<Writer>,
+fn unknown_client_error(client_id: u32) -> ClientUsageError {
+    ClientUsageError::User(format!(
+        "No client with id {client_id} exists on this socket"
+    ))
+}
+
+async fn build_client(
     request: ConnectionRequest,
     push_tx: Option<mpsc::UnboundedSender<PushInfo>>,
-) -> Result<Client, ClientCreationError> {
+) -> Result<(Client, ClientRegistration), crate::client::ConnectionError> {
     // Extract the address resolver key before converting (protobuf field won't survive into())
     let resolver_key = request
         .address_resolver_key
@@ -912,6 +1652,14 @@ async fn create_client(
         .map(|k| k.to_string());
 
     let mut conn_request: crate::client::ConnectionRequest = request.into();
+    // The clients of the socket listener all run on its runtime.
+    if conn_request.runtime_mode != crate::client_runtime::RuntimeMode::Dedicated {
+        return Err(crate::client::ConnectionError::Configuration(
+            "The runtime mode isn't supported by the socket listener".to_string(),
+        ));
+    }
+    // The socket listener applies the push queue to the client's push messages itself.
+    conn_request.push_queue = None;
 
     // Look up the address resolver from the global registry using the key
     // provided in the connection request.
@@ -921,25 +1669,63 @@ async fn create_client(
         conn_request.address_resolver = Some(resolver);
     }
 
-    let client = match Client::new(conn_request, push_tx).await {
-        Ok(client) => client,
+    let client = Client::new(conn_request.clone(), push_tx).await?;
+    let registration = client_registry::register(client.clone(), &conn_request);
+    Ok((client, registration))
+}
+
+async fn create_client(
+    writer: &Rc<Writer>,
+    request: ConnectionRequest,
+    push_tx: Option<mpsc::UnboundedSender<PushInfo>>,
+) -> Result<SocketClient, ClientCreationError> {
+    let (client, registration) = match build_client(request, push_tx).await {
+        Ok(created) => created,
         Err(err) => return Err(ClientCreationError::ConnectionError(err)),
     };
-    write_result(Ok(Value::Okay), 0, writer, None).await?;
-    Ok(client)
+    write_result(Ok(Value::Okay), 0, DEFAULT_CLIENT_ID, writer, None).await?;
+    Ok(SocketClient {
+        client,
+        registration,
+    })
+}
+
+/// The socket's first client, with the socket settings of its connection request.
+struct DefaultClient {
+    client: SocketClient,
+    heartbeat: Option<SocketHeartbeat>,
+    push_queue: Option<PushQueueConfig>,
 }
 
 async fn wait_for_connection_configuration_and_create_client(
     client_listener: &mut UnixStreamListener,
     writer: &Rc<Writer>,
     push_tx: Option<mpsc::UnboundedSender<PushInfo>>,
-) -> Result<Client, ClientCreationError> {
+) -> Result<DefaultClient, ClientCreationError> {
     // Wait for the server's address
     match client_listener.next_values::<ConnectionRequest>().await {
         Closed(reason) => Err(ClientCreationError::SocketListenerClosed(reason)),
         ReceivedValues(mut received_requests) => {
             if let Some(request) = received_requests.pop() {
-                create_client(writer, request, push_tx).await
+                let frame_format = match request.frame_format.enum_value_or_default() {
+                    connection_request::FrameFormat::Plain => FrameFormat::Plain,
+                    connection_request::FrameFormat::Checksummed => FrameFormat::Checksummed,
+                };
+                let heartbeat = request
+                    .socket_heartbeat
+                    .as_ref()
+                    .map(SocketHeartbeat::from_config);
+                let push_queue = request.push_queue.as_ref().map(push_queue_config);
+                let client = create_client(writer, request, push_tx).await?;
+                // The wrapper switches to the requested framing once the client was created.
+                client_listener
+                    .rotating_buffer
+                    .set_frame_format(frame_format);
+                Ok(DefaultClient {
+                    client,
+                    heartbeat,
+                    push_queue,
+                })
             } else {
                 Err(ClientCreationError::UnhandledError(
                     "No received requests".to_string(),
@@ -951,33 +1737,89 @@ async fn wait_for_connection_configuration_and_create_client(
 
 async fn read_values_loop(
     mut client_listener: UnixStreamListener,
-    client: &Client,
+    clients: &Clients,
     writer: Rc<Writer>,
+    queue: &RefCell<RequestQueue>,
 ) -> ClosingReason {
     loop {
-        match client_listener.next_values().await {
-            Closed(reason) => {
+        // Wait for requests only when none are queued. Otherwise, queue the requests that were
+        // already sent, so that they are dispatched by priority with the queued ones.
+        let received = if queue.borrow().is_empty() {
+            Some(client_listener.next_values().await)
+        } else {
+            client_listener.next_values().now_or_never()
+        };
+        match received {
+            Some(Closed(reason)) => {
                 return reason;
             }
-            ReceivedValues(received_requests) => {
-                handle_requests(received_requests, client, &writer).await;
+            Some(ReceivedValues(received_requests)) => {
+                let mut queue = queue.borrow_mut();
+                for request in received_requests {
+                    queue.push(request);
+                }
             }
+            None => {}
+        }
+        dispatch_requests(queue, clients, &writer).await;
+    }
+}
+
+/// Sends heartbeats to the wrapper. Completes when nothing was read from the socket for the
+/// heartbeat timeout, and returns how long the socket was silent. Never completes if heartbeats
+/// are disabled.
+async fn heartbeat_loop(
+    heartbeat: Option<SocketHeartbeat>,
+    last_read: Rc<Cell<Instant>>,
+    writer: Rc<Writer>,
+) -> Duration {
+    let Some(heartbeat) = heartbeat else {
+        return std::future::pending().await;
+    };
+    let mut interval = tokio::time::interval(heartbeat.interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let silence = last_read.get().elapsed();
+        if silence >= heartbeat.timeout {
+            return silence;
         }
+        let mut response = Response::new();
+        response.is_heartbeat = true;
+        let _res = write_to_writer(response, &writer).await;
     }
 }
 
-async fn push_manager_loop(mut push_rx: mpsc::UnboundedReceiver<PushInfo>, writer: Rc<Writer>) {
+/// Writes the push messages of a client to the socket. Returns true if the client should be
+/// disconnected because its push queue overflowed.
+async fn push_manager_loop(mut push_rx: PushReceiver, writer: Rc<Writer>, client_id: u32) -> bool {
     loop {
         let result = push_rx.recv().await;
         match result {
-            None => {
+            None if push_rx.overflowed() => {
+                log_warn(
+                    "push manager loop",
+                    format!("push queue of client {client_id} overflowed, disconnecting"),
+                );
+                return true;
+            }
+            None if client_id == DEFAULT_CLIENT_ID => {
                 log_error("push manager loop", "got None from push manager");
-                return;
+                return false;
+            }
+            None => {
+                // Additional clients are closed by dropping them, which closes their push channel.
+                log_debug(
+                    "push manager loop",
+                    format!("push channel of client {client_id} closed"),
+                );
+                return false;
             }
             Some(push_msg) => {
                 log_debug("push manager loop", format!("got PushInfo: {push_msg:?}"));
                 let mut response = Response::new();
                 response.callback_idx = 0; // callback_idx is not used with push notifications
+                response.client_id = client_id;
                 response.is_push = true;
                 response.value = {
                     let push_val = Value::Push {
@@ -1007,6 +1849,7 @@ async fn listen_on_client_stream(socket: UnixStream) {
         socket,
         lock: write_lock,
         accumulated_outputs,
+        accumulated_reservations: Cell::default(),
         closing_sender: sender,
     });
     let client_creation = wait_for_connection_configuration_and_create_client(
@@ -1014,8 +1857,12 @@ async fn listen_on_client_stream(socket: UnixStream) {
         &writer,
         Some(push_tx),
     );
-    let client = match client_creation.await {
-        Ok(conn) => conn,
+    let DefaultClient {
+        client,
+        heartbeat,
+        push_queue,
+    } = match client_creation.await {
+        Ok(created) => created,
         Err(ClientCreationError::SocketListenerClosed(ClosingReason::ReadSocketClosed)) => {
             // This isn't an error - it can happen when a new wrapper-client creates a connection in order to check whether something already listens on the socket.
             log_debug(
@@ -1051,8 +1898,12 @@ async fn listen_on_client_stream(socket: UnixStream) {
         }
     };
     log_info("connection", "new connection started");
+    let last_read = client_listener.last_read.clone();
+    let default_client_closed = client.registration.closed();
+    let clients: Clients = Rc::new(RefCell::new(HashMap::from([(DEFAULT_CLIENT_ID, client)])));
+    let queue = RefCell::new(RequestQueue::default());
     tokio::select! {
-            reader_closing = read_values_loop(client_listener, &client, writer.clone()) => {
+            reader_closing = read_values_loop(client_listener, &clients, writer.clone(), &queue) => {
                 if let ClosingReason::UnhandledError(err) = reader_closing {
                     let _res = write_closing_error(ClosingError{err_message: err.to_string()}, u32::MAX, &writer, "client closing").await;
                 };
@@ -1065,10 +1916,42 @@ async fn listen_on_client_stream(socket: UnixStream) {
                     log_trace("client closing", "writer closed");
                 }
             },
-            _ = push_manager_loop(push_rx, writer.clone()) => {
+            overflowed = push_manager_loop(PushReceiver::new(push_rx, push_queue), writer.clone(), DEFAULT_CLIENT_ID) => {
+                if overflowed {
+                    let err_message = "The push message queue overflowed".to_string();
+                    let _res = write_closing_error(ClosingError { err_message }, u32::MAX, &writer, "client closing").await;
+                }
                 log_trace("client closing", "push manager closed");
+            },
+            silence = heartbeat_loop(heartbeat, last_read, writer.clone()) => {
+                log_warn(
+                    "client closing",
+                    format!("Nothing was read from the socket for {silence:?}, the wrapper is presumed dead"),
+                );
+                // Fail the writes of in-flight requests right away, instead of when they complete.
+                // SAFETY: the descriptor is owned by the socket, which outlives this call.
+                unsafe { libc::shutdown(writer.socket.as_raw_fd(), libc::SHUT_RDWR) };
+            },
+            _ = default_client_closed => {
+                let err_message = "The client was closed through the client registry".to_string();
+                let _res = write_closing_error(ClosingError { err_message }, u32::MAX, &writer, "client closing").await;
+                log_trace("client closing", "closed through the client registry");
+            },
+            _ = graceful_shutdown::shutdown_started() => {
+                // In-flight requests keep running on the local set, and write their responses.
+                let mut response = Response::new();
+                response.is_shutdown = true;
+                let _res = write_to_writer(response, &writer).await;
+                log_trace("client closing", "shutting down");
             }
     }
+    // The requests that were read are answered, even if they weren't dispatched yet.
+    loop {
+        let Some(request) = queue.borrow_mut().pop() else {
+            break;
+        };
+        dispatch_request(request, &clients, &writer);
+    }
     log_trace("client closing", "closing connection");
 }
 
@@ -1239,10 +2122,18 @@ pub fn start_socket_listener_internal<InitCallback>(
 
         // Signal initialization is successful.
         let _ = tx.send(Ok(socket_path_cloned.clone()));
+        graceful_shutdown::install_signal_handlers();
 
         let local_set_pool = LocalPoolHandle::new(num_cpus::get());
         loop {
-            match listener_socket.accept().await {
+            let accepted = tokio::select! {
+                accepted = listener_socket.accept() => accepted,
+                _ = graceful_shutdown::shutdown_started() => {
+                    log_info("listen_on_socket", "stopped accepting connections due to shutdown");
+                    break;
+                }
+            };
+            match accepted {
                 Ok((stream, _addr)) => {
                     local_set_pool.spawn_pinned(move || listen_on_client_stream(stream));
                 }
@@ -0,0 +1,845 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Higher-level helpers for stream consumer groups.
+//!
+//! This module provides typed builders for the commands a consumer-group reader needs
+//! (`XADD`, `XREADGROUP`, `XACK`, `XAUTOCLAIM`), a structured [`StreamEntry`] type, and a
+//! [`StreamConsumer`] that implements the usual reliable-consumer step:
+//! acknowledge the previous batch, claim entries that other consumers left idle in the
+//! pending entries list (PEL), then read new entries.
+//!
+//! The consumer keeps no state in the core besides the `XAUTOCLAIM` cursor, which is returned
+//! to the caller with every poll. This lets bindings drive the loop through the socket protocol
+//! (see `StreamConsumerPoll` in `command_request.proto`) without holding server-side handles.
+
+use crate::client::Client;
+use crate::errors::unexpected_response;
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+use std::time::Duration;
+
+/// The `XAUTOCLAIM` cursor used to start scanning the pending entries list from its beginning.
+pub const START_CLAIM_CURSOR: &str = "0-0";
+
+/// The special `XREADGROUP` ID that requests entries never delivered to any consumer.
+const NEW_ENTRIES_ID: &str = ">";
+
+/// A single stream entry, with its fields in the order they were added.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamEntry {
+    pub id: String,
+    pub fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl StreamEntry {
+    /// Converts the entry to the `[id, [[field, value], ...]]` shape used by converted
+    /// `XRANGE`/`XAUTOCLAIM` responses.
+    fn into_map_entry(self) -> (Value, Value) {
+        let fields = self
+            .fields
+            .into_iter()
+            .map(|(field, value)| {
+                Value::Array(vec![Value::BulkString(field), Value::BulkString(value)])
+            })
+            .collect();
+        (
+            Value::BulkString(self.id.into_bytes()),
+            Value::Array(fields),
+        )
+    }
+
+    /// Parses the entries of a converted `XRANGE`/`XCLAIM`/`XAUTOCLAIM` response - a map from
+    /// entry ID to an array of field/value pairs. Entries whose fields are nil (deleted entries
+    /// that are still in the PEL) are skipped.
+    pub fn from_entries(value: Value) -> RedisResult<Vec<StreamEntry>> {
+        let pairs = match value {
+            Value::Nil => return Ok(Vec::new()),
+            Value::Map(pairs) => pairs,
+            Value::Array(entries) => entries
+                .into_iter()
+                .map(|entry| match entry {
+                    Value::Array(mut entry) if entry.len() == 2 => {
+                        let fields = entry.pop().unwrap_or(Value::Nil);
+                        let id = entry.pop().unwrap_or(Value::Nil);
+                        Ok((id, fields))
+                    }
+                    other => Err(unexpected_response("stream entry", &other)),
+                })
+                .collect::<RedisResult<_>>()?,
+            other => return Err(unexpected_response("stream entries", &other)),
+        };
+
+        let mut entries = Vec::with_capacity(pairs.len());
+        for (id, fields) in pairs {
+            let id = value_to_string(id)?;
+            let fields = match fields {
+                Value::Nil => continue,
+                Value::Array(fields) => parse_fields(fields)?,
+                other => return Err(unexpected_response("stream entry fields", &other)),
+            };
+            entries.push(StreamEntry { id, fields });
+        }
+        Ok(entries)
+    }
+
+    /// Parses a converted `XREAD`/`XREADGROUP` response - a map from stream key to its entries.
+    pub fn from_read_response(value: Value) -> RedisResult<Vec<(Vec<u8>, Vec<StreamEntry>)>> {
+        match value {
+            Value::Nil => Ok(Vec::new()),
+            Value::Map(streams) => streams
+                .into_iter()
+                .map(|(key, entries)| {
+                    let key = match key {
+                        Value::BulkString(key) => key,
+                        Value::SimpleString(key) => key.into_bytes(),
+                        other => return Err(unexpected_response("stream key", &other)),
+                    };
+                    Ok((key, StreamEntry::from_entries(entries)?))
+                })
+                .collect(),
+            other => Err(unexpected_response("XREADGROUP response", &other)),
+        }
+    }
+}
+
+fn parse_fields(fields: Vec<Value>) -> RedisResult<Vec<(Vec<u8>, Vec<u8>)>> {
+    // Converted responses hold `[field, value]` pairs, raw responses hold a flat list.
+    if fields.iter().all(|field| matches!(field, Value::Array(_))) {
+        fields
+            .into_iter()
+            .map(|pair| match pair {
+                Value::Array(mut pair) if pair.len() == 2 => {
+                    let value = value_to_bytes(pair.pop().unwrap_or(Value::Nil))?;
+                    let field = value_to_bytes(pair.pop().unwrap_or(Value::Nil))?;
+                    Ok((field, value))
+                }
+                other => Err(unexpected_response("stream field pair", &other)),
+            })
+            .collect()
+    } else if fields.len().is_multiple_of(2) {
+        let mut iter = fields.into_iter();
+        let mut result = Vec::with_capacity(iter.len() / 2);
+        while let (Some(field), Some(value)) = (iter.next(), iter.next()) {
+            result.push((value_to_bytes(field)?, value_to_bytes(value)?));
+        }
+        Ok(result)
+    } else {
+        Err(RedisError::from((
+            ErrorKind::TypeError,
+            "Stream entry has an odd number of field elements",
+        )))
+    }
+}
+
+fn value_to_bytes(value: Value) -> RedisResult<Vec<u8>> {
+    match value {
+        Value::BulkString(bytes) => Ok(bytes),
+        Value::SimpleString(string) => Ok(string.into_bytes()),
+        Value::Int(int) => Ok(int.to_string().into_bytes()),
+        other => Err(unexpected_response("stream field", &other)),
+    }
+}
+
+fn value_to_string(value: Value) -> RedisResult<String> {
+    String::from_utf8(value_to_bytes(value)?).map_err(|_| {
+        RedisError::from((
+            ErrorKind::TypeError,
+            "Stream entry ID is not a valid UTF-8 string",
+        ))
+    })
+}
+
+/// The trimming strategy applied by `XADD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamTrim {
+    /// Evict entries as long as the stream length exceeds the threshold.
+    MaxLen { threshold: u64, exact: bool },
+    /// Evict entries with IDs lower than the threshold.
+    MinId { threshold: String, exact: bool },
+}
+
+/// Builder for `XADD key [NOMKSTREAM] [MAXLEN|MINID [=|~] threshold [LIMIT count]] id field value ...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XAddBuilder {
+    key: Vec<u8>,
+    id: Option<String>,
+    make_stream: bool,
+    trim: Option<StreamTrim>,
+    limit: Option<u64>,
+    fields: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl XAddBuilder {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            id: None,
+            make_stream: true,
+            trim: None,
+            limit: None,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Sets an explicit entry ID. By default the server generates one (`*`).
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// If `false`, sends `NOMKSTREAM` so that the stream isn't created when missing.
+    pub fn make_stream(mut self, make_stream: bool) -> Self {
+        self.make_stream = make_stream;
+        self
+    }
+
+    pub fn trim(mut self, trim: StreamTrim) -> Self {
+        self.trim = Some(trim);
+        self
+    }
+
+    /// Limits the number of evicted entries. Only valid for approximate trimming.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn field(mut self, field: impl Into<Vec<u8>>, value: impl Into<Vec<u8>>) -> Self {
+        self.fields.push((field.into(), value.into()));
+        self
+    }
+
+    pub fn build(&self) -> RedisResult<Cmd> {
+        if self.fields.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "XADD requires at least one field",
+            )));
+        }
+        let mut cmd = redis::cmd("XADD");
+        cmd.arg(&self.key);
+        if !self.make_stream {
+            cmd.arg("NOMKSTREAM");
+        }
+        if let Some(trim) = &self.trim {
+            let exact = match trim {
+                StreamTrim::MaxLen { threshold, exact } => {
+                    cmd.arg("MAXLEN").arg(if *exact { "=" } else { "~" });
+                    cmd.arg(threshold);
+                    *exact
+                }
+                StreamTrim::MinId { threshold, exact } => {
+                    cmd.arg("MINID").arg(if *exact { "=" } else { "~" });
+                    cmd.arg(threshold);
+                    *exact
+                }
+            };
+            if let Some(limit) = self.limit {
+                if exact {
+                    return Err(RedisError::from((
+                        ErrorKind::ClientError,
+                        "XADD LIMIT can only be used with approximate trimming",
+                    )));
+                }
+                cmd.arg("LIMIT").arg(limit);
+            }
+        }
+        cmd.arg(self.id.as_deref().unwrap_or("*"));
+        for (field, value) in &self.fields {
+            cmd.arg(field).arg(value);
+        }
+        Ok(cmd)
+    }
+}
+
+/// Builder for `XREADGROUP GROUP group consumer [COUNT count] [BLOCK ms] [NOACK] STREAMS key ... id ...`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XReadGroupBuilder {
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    count: Option<u64>,
+    block: Option<Duration>,
+    no_ack: bool,
+    streams: Vec<(Vec<u8>, String)>,
+}
+
+impl XReadGroupBuilder {
+    pub fn new(group: impl Into<Vec<u8>>, consumer: impl Into<Vec<u8>>) -> Self {
+        Self {
+            group: group.into(),
+            consumer: consumer.into(),
+            count: None,
+            block: None,
+            no_ack: false,
+            streams: Vec::new(),
+        }
+    }
+
+    pub fn count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// Blocks for up to `block` when no entries are available. A zero duration blocks indefinitely.
+    pub fn block(mut self, block: Duration) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn no_ack(mut self, no_ack: bool) -> Self {
+        self.no_ack = no_ack;
+        self
+    }
+
+    /// Reads entries of `key` that were never delivered to other consumers.
+    pub fn new_entries(self, key: impl Into<Vec<u8>>) -> Self {
+        self.stream(key, NEW_ENTRIES_ID)
+    }
+
+    /// Reads entries of `key` after `id`. IDs other than `>` return this consumer's pending entries.
+    pub fn stream(mut self, key: impl Into<Vec<u8>>, id: impl Into<String>) -> Self {
+        self.streams.push((key.into(), id.into()));
+        self
+    }
+
+    pub fn build(&self) -> RedisResult<Cmd> {
+        if self.streams.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::ClientError,
+                "XREADGROUP requires at least one stream",
+            )));
+        }
+        let mut cmd = redis::cmd("XREADGROUP");
+        cmd.arg("GROUP").arg(&self.group).arg(&self.consumer);
+        if let Some(count) = self.count {
+            cmd.arg("COUNT").arg(count);
+        }
+        if let Some(block) = self.block {
+            cmd.arg("BLOCK").arg(block.as_millis() as u64);
+        }
+        if self.no_ack {
+            cmd.arg("NOACK");
+        }
+        cmd.arg("STREAMS");
+        for (key, _) in &self.streams {
+            cmd.arg(key);
+        }
+        for (_, id) in &self.streams {
+            cmd.arg(id);
+        }
+        Ok(cmd)
+    }
+}
+
+/// Builds `XACK key group id ...`.
+pub fn xack_cmd<I, T>(key: &[u8], group: &[u8], ids: I) -> RedisResult<Cmd>
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<[u8]>,
+{
+    let mut cmd = redis::cmd("XACK");
+    cmd.arg(key).arg(group);
+    let mut has_ids = false;
+    for id in ids {
+        cmd.arg(id.as_ref());
+        has_ids = true;
+    }
+    if !has_ids {
+        return Err(RedisError::from((
+            ErrorKind::ClientError,
+            "XACK requires at least one entry ID",
+        )));
+    }
+    Ok(cmd)
+}
+
+/// Builder for `XAUTOCLAIM key group consumer min-idle-time start [COUNT count] [JUSTID]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XAutoClaimBuilder {
+    key: Vec<u8>,
+    group: Vec<u8>,
+    consumer: Vec<u8>,
+    min_idle_time: Duration,
+    start: String,
+    count: Option<u64>,
+    just_id: bool,
+}
+
+impl XAutoClaimBuilder {
+    pub fn new(
+        key: impl Into<Vec<u8>>,
+        group: impl Into<Vec<u8>>,
+        consumer: impl Into<Vec<u8>>,
+        min_idle_time: Duration,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            group: group.into(),
+            consumer: consumer.into(),
+            min_idle_time,
+            start: START_CLAIM_CURSOR.to_string(),
+            count: None,
+            just_id: false,
+        }
+    }
+
+    /// Sets the cursor to continue from, as returned by the previous `XAUTOCLAIM` call.
+    pub fn start(mut self, start: impl Into<String>) -> Self {
+        self.start = start.into();
+        self
+    }
+
+    pub fn count(mut self, count: u64) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    pub fn just_id(mut self, just_id: bool) -> Self {
+        self.just_id = just_id;
+        self
+    }
+
+    pub fn build(&self) -> Cmd {
+        let mut cmd = redis::cmd("XAUTOCLAIM");
+        cmd.arg(&self.key)
+            .arg(&self.group)
+            .arg(&self.consumer)
+            .arg(self.min_idle_time.as_millis() as u64)
+            .arg(&self.start);
+        if let Some(count) = self.count {
+            cmd.arg("COUNT").arg(count);
+        }
+        if self.just_id {
+            cmd.arg("JUSTID");
+        }
+        cmd
+    }
+}
+
+/// The parsed result of a (non-`JUSTID`) `XAUTOCLAIM` call.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoClaimResult {
+    /// The cursor to pass to the next call. [`START_CLAIM_CURSOR`] once the PEL was fully scanned.
+    pub next_start: String,
+    pub entries: Vec<StreamEntry>,
+    /// IDs that were in the PEL but no longer exist in the stream. Always empty before Valkey 7.0.
+    pub deleted_ids: Vec<String>,
+}
+
+impl AutoClaimResult {
+    pub fn from_response(value: Value) -> RedisResult<Self> {
+        let Value::Array(mut array) = value else {
+            return Err(unexpected_response("XAUTOCLAIM response", &value));
+        };
+        if array.len() != 2 && array.len() != 3 {
+            return Err(unexpected_response(
+                "XAUTOCLAIM response",
+                &Value::Array(array),
+            ));
+        }
+        let deleted_ids = if array.len() == 3 {
+            match array.remove(2) {
+                Value::Array(ids) => ids
+                    .into_iter()
+                    .map(value_to_string)
+                    .collect::<RedisResult<_>>()?,
+                Value::Nil => Vec::new(),
+                other => return Err(unexpected_response("deleted entry IDs", &other)),
+            }
+        } else {
+            Vec::new()
+        };
+        let entries = StreamEntry::from_entries(array.remove(1))?;
+        let next_start = value_to_string(array.remove(0))?;
+        Ok(Self {
+            next_start,
+            entries,
+            deleted_ids,
+        })
+    }
+}
+
+/// Configuration of a [`StreamConsumer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamConsumerConfig {
+    pub key: Vec<u8>,
+    pub group: Vec<u8>,
+    pub consumer: Vec<u8>,
+    /// Maximum number of entries returned by a single poll.
+    pub count: u64,
+    /// How long to block waiting for new entries. `None` returns immediately.
+    pub block: Option<Duration>,
+    /// Entries pending for longer than this on other consumers are claimed. `None` disables claiming.
+    pub min_idle_time: Option<Duration>,
+}
+
+/// The result of a single [`StreamConsumer::poll`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct StreamPollResult {
+    /// Entries claimed from idle consumers, followed by newly delivered entries.
+    pub entries: Vec<StreamEntry>,
+    /// PEL entries that were deleted from the stream. The server removes them from the PEL on claim.
+    pub deleted_ids: Vec<String>,
+}
+
+impl StreamPollResult {
+    /// Converts the result to `[next_claim_cursor, {id: [[field, value], ...]}, [deleted_id, ...]]`,
+    /// the same shape as a converted `XAUTOCLAIM` response.
+    pub fn into_value(self, next_claim_cursor: &str) -> Value {
+        Value::Array(vec![
+            Value::BulkString(next_claim_cursor.as_bytes().to_vec()),
+            Value::Map(
+                self.entries
+                    .into_iter()
+                    .map(StreamEntry::into_map_entry)
+                    .collect(),
+            ),
+            Value::Array(
+                self.deleted_ids
+                    .into_iter()
+                    .map(|id| Value::BulkString(id.into_bytes()))
+                    .collect(),
+            ),
+        ])
+    }
+}
+
+/// A managed consumer-group reader.
+///
+/// Each [`poll`](Self::poll) acknowledges nothing by itself - callers acknowledge processed
+/// entries with [`ack`](Self::ack), or pass them to the next poll through the socket protocol.
+#[derive(Debug, Clone)]
+pub struct StreamConsumer {
+    config: StreamConsumerConfig,
+    claim_cursor: String,
+}
+
+impl StreamConsumer {
+    pub fn new(config: StreamConsumerConfig) -> Self {
+        Self::with_claim_cursor(config, START_CLAIM_CURSOR)
+    }
+
+    /// Creates a consumer that resumes claiming from a cursor returned by a previous poll.
+    pub fn with_claim_cursor(
+        config: StreamConsumerConfig,
+        claim_cursor: impl Into<String>,
+    ) -> Self {
+        let claim_cursor = claim_cursor.into();
+        Self {
+            config,
+            claim_cursor: if claim_cursor.is_empty() {
+                START_CLAIM_CURSOR.to_string()
+            } else {
+                claim_cursor
+            },
+        }
+    }
+
+    /// The `XAUTOCLAIM` cursor that the next poll starts from.
+    pub fn claim_cursor(&self) -> &str {
+        &self.claim_cursor
+    }
+
+    /// Creates the consumer group (and the stream, if needed), starting from new entries.
+    /// An already existing group is not an error.
+    pub async fn create_group(&self, client: &mut Client) -> RedisResult<()> {
+        let mut cmd = redis::cmd("XGROUP");
+        cmd.arg("CREATE")
+            .arg(&self.config.key)
+            .arg(&self.config.group)
+            .arg("$")
+            .arg("MKSTREAM");
+        match client.send_command(&mut cmd, None).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.code() == Some("BUSYGROUP") => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Claims idle pending entries from other consumers, then reads new entries,
+    /// returning at most `count` entries in total.
+    pub async fn poll(&mut self, client: &mut Client) -> RedisResult<StreamPollResult> {
+        let mut result = StreamPollResult::default();
+
+        if let Some(min_idle_time) = self.config.min_idle_time {
+            let mut cmd = XAutoClaimBuilder::new(
+                self.config.key.clone(),
+                self.config.group.clone(),
+                self.config.consumer.clone(),
+                min_idle_time,
+            )
+            .start(self.claim_cursor.clone())
+            .count(self.config.count)
+            .build();
+            let claimed =
+                AutoClaimResult::from_response(client.send_command(&mut cmd, None).await?)?;
+            self.claim_cursor = claimed.next_start;
+            result.entries = claimed.entries;
+            result.deleted_ids = claimed.deleted_ids;
+        }
+
+        let remaining = self
+            .config
+            .count
+            .saturating_sub(result.entries.len() as u64);
+        if remaining == 0 {
+            return Ok(result);
+        }
+
+        let mut builder =
+            XReadGroupBuilder::new(self.config.group.clone(), self.config.consumer.clone())
+                .count(remaining)
+                .new_entries(self.config.key.clone());
+        // Don't block when claimed entries are already waiting to be processed.
+        if let Some(block) = self.config.block
+            && result.entries.is_empty()
+        {
+            builder = builder.block(block);
+        }
+        let mut cmd = builder.build()?;
+        let response = client.send_command(&mut cmd, None).await?;
+        for (_, entries) in StreamEntry::from_read_response(response)? {
+            result.entries.extend(entries);
+        }
+        Ok(result)
+    }
+
+    /// Acknowledges processed entries, returning the number of entries removed from the PEL.
+    pub async fn ack<I, T>(&self, client: &mut Client, ids: I) -> RedisResult<i64>
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[u8]>,
+    {
+        let mut cmd = xack_cmd(&self.config.key, &self.config.group, ids)?;
+        match client.send_command(&mut cmd, None).await? {
+            Value::Int(count) => Ok(count),
+            other => Err(unexpected_response("XACK response", &other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::bulk;
+
+    fn args(cmd: &Cmd) -> Vec<String> {
+        cmd.args_iter()
+            .map(|arg| match arg {
+                redis::Arg::Simple(bytes) => String::from_utf8_lossy(bytes).to_string(),
+                redis::Arg::Cursor => "<cursor>".to_string(),
+            })
+            .collect()
+    }
+
+    fn pair(field: &str, value: &str) -> Value {
+        Value::Array(vec![bulk(field), bulk(value)])
+    }
+
+    #[test]
+    fn test_xadd_builder() {
+        let cmd = XAddBuilder::new("stream")
+            .make_stream(false)
+            .trim(StreamTrim::MaxLen {
+                threshold: 100,
+                exact: false,
+            })
+            .limit(10)
+            .field("f1", "v1")
+            .field("f2", "v2")
+            .build()
+            .unwrap();
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "XADD",
+                "stream",
+                "NOMKSTREAM",
+                "MAXLEN",
+                "~",
+                "100",
+                "LIMIT",
+                "10",
+                "*",
+                "f1",
+                "v1",
+                "f2",
+                "v2"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_xadd_builder_rejects_invalid_options() {
+        assert!(XAddBuilder::new("stream").build().is_err());
+        assert!(
+            XAddBuilder::new("stream")
+                .trim(StreamTrim::MinId {
+                    threshold: "1-0".to_string(),
+                    exact: true,
+                })
+                .limit(10)
+                .field("f", "v")
+                .build()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_xreadgroup_builder() {
+        let cmd = XReadGroupBuilder::new("group", "consumer")
+            .count(5)
+            .block(Duration::from_millis(1500))
+            .no_ack(true)
+            .new_entries("s1")
+            .stream("s2", "0")
+            .build()
+            .unwrap();
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "XREADGROUP",
+                "GROUP",
+                "group",
+                "consumer",
+                "COUNT",
+                "5",
+                "BLOCK",
+                "1500",
+                "NOACK",
+                "STREAMS",
+                "s1",
+                "s2",
+                ">",
+                "0"
+            ]
+        );
+        assert!(XReadGroupBuilder::new("g", "c").build().is_err());
+    }
+
+    #[test]
+    fn test_xack_and_xautoclaim_builders() {
+        let cmd = xack_cmd(b"stream", b"group", ["1-0", "1-1"]).unwrap();
+        assert_eq!(args(&cmd), vec!["XACK", "stream", "group", "1-0", "1-1"]);
+        assert!(xack_cmd(b"stream", b"group", Vec::<&str>::new()).is_err());
+
+        let cmd = XAutoClaimBuilder::new("stream", "group", "consumer", Duration::from_secs(2))
+            .start("5-0")
+            .count(3)
+            .just_id(true)
+            .build();
+        assert_eq!(
+            args(&cmd),
+            vec![
+                "XAUTOCLAIM",
+                "stream",
+                "group",
+                "consumer",
+                "2000",
+                "5-0",
+                "COUNT",
+                "3",
+                "JUSTID"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_entries_skips_deleted() {
+        let value = Value::Map(vec![
+            (
+                bulk("1-0"),
+                Value::Array(vec![pair("f1", "v1"), pair("f2", "v2")]),
+            ),
+            (bulk("1-1"), Value::Nil),
+        ]);
+        let entries = StreamEntry::from_entries(value).unwrap();
+        assert_eq!(
+            entries,
+            vec![StreamEntry {
+                id: "1-0".to_string(),
+                fields: vec![
+                    (b"f1".to_vec(), b"v1".to_vec()),
+                    (b"f2".to_vec(), b"v2".to_vec())
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_raw_entries() {
+        let value = Value::Array(vec![Value::Array(vec![
+            bulk("2-0"),
+            Value::Array(vec![bulk("f"), bulk("v")]),
+        ])]);
+        let entries = StreamEntry::from_entries(value).unwrap();
+        assert_eq!(entries[0].id, "2-0");
+        assert_eq!(entries[0].fields, vec![(b"f".to_vec(), b"v".to_vec())]);
+    }
+
+    #[test]
+    fn test_parse_read_response() {
+        let value = Value::Map(vec![(
+            bulk("stream"),
+            Value::Map(vec![(bulk("1-0"), Value::Array(vec![pair("f", "v")]))]),
+        )]);
+        let streams = StreamEntry::from_read_response(value).unwrap();
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].0, b"stream".to_vec());
+        assert_eq!(streams[0].1[0].id, "1-0");
+        assert!(
+            StreamEntry::from_read_response(Value::Nil)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_parse_autoclaim_response() {
+        let value = Value::Array(vec![
+            bulk("3-0"),
+            Value::Map(vec![(bulk("1-0"), Value::Array(vec![pair("f", "v")]))]),
+            Value::Array(vec![bulk("1-1")]),
+        ]);
+        let result = AutoClaimResult::from_response(value).unwrap();
+        assert_eq!(result.next_start, "3-0");
+        assert_eq!(result.entries.len(), 1);
+        assert_eq!(result.deleted_ids, vec!["1-1".to_string()]);
+
+        // Servers older than 7.0 don't return deleted IDs.
+        let value = Value::Array(vec![bulk("0-0"), Value::Map(vec![])]);
+        let result = AutoClaimResult::from_response(value).unwrap();
+        assert_eq!(result.next_start, START_CLAIM_CURSOR);
+        assert!(result.deleted_ids.is_empty());
+
+        assert!(AutoClaimResult::from_response(Value::Nil).is_err());
+    }
+
+    #[test]
+    fn test_poll_result_round_trips_through_value() {
+        let result = StreamPollResult {
+            entries: vec![StreamEntry {
+                id: "1-0".to_string(),
+                fields: vec![(b"f".to_vec(), b"v".to_vec())],
+            }],
+            deleted_ids: vec!["0-1".to_string()],
+        };
+        let parsed = AutoClaimResult::from_response(result.clone().into_value("5-0")).unwrap();
+        assert_eq!(parsed.next_start, "5-0");
+        assert_eq!(parsed.entries, result.entries);
+        assert_eq!(parsed.deleted_ids, result.deleted_ids);
+    }
+
+    #[test]
+    fn test_empty_claim_cursor_starts_from_beginning() {
+        let config = StreamConsumerConfig {
+            key: b"stream".to_vec(),
+            group: b"group".to_vec(),
+            consumer: b"consumer".to_vec(),
+            count: 10,
+            block: None,
+            min_idle_time: None,
+        };
+        let consumer = StreamConsumer::with_claim_cursor(config, "");
+        assert_eq!(consumer.claim_cursor(), START_CLAIM_CURSOR);
+    }
+}
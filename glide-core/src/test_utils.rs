@@ -0,0 +1,10 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Fixtures shared by the unit tests.
+
+use redis::Value;
+
+/// Returns a bulk string response holding `text`.
+pub(crate) fn bulk(text: &str) -> Value {
+    Value::BulkString(text.as_bytes().to_vec())
+}
@@ -12,6 +12,7 @@ use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
+use telemetrylib::{GlideSpan, Telemetry};
 use tokio::sync::oneshot;
 
 // ─── Public Types ────────────────────────────────────────────────────────────
@@ -584,6 +585,52 @@ impl LatencyTracker {
     }
 }
 
+// ─── Latency Breakdown ───────────────────────────────────────────────────────
+
+/// Where the latency of a single completed command was spent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBreakdown {
+    /// From the command entering the client until it was first sent to a node.
+    pub queue_time: Duration,
+    /// From the first send until the first byte of the response arrived, including server time
+    /// and retries.
+    pub network_time: Duration,
+    /// From the first byte of the response until it was read, decompressed and converted to the
+    /// command's expected type.
+    pub decode_time: Duration,
+}
+
+impl LatencyBreakdown {
+    /// Build the breakdown from the command's timestamps.
+    /// Returns `None` if the command was never sent to a node.
+    pub fn new(
+        start: Instant,
+        sent_at: Option<Instant>,
+        first_byte_at: Instant,
+        decoded_at: Instant,
+    ) -> Option<Self> {
+        let sent_at = sent_at?;
+        Some(Self {
+            queue_time: sent_at.saturating_duration_since(start),
+            network_time: first_byte_at.saturating_duration_since(sent_at),
+            decode_time: decoded_at.saturating_duration_since(first_byte_at),
+        })
+    }
+
+    /// Publish the breakdown to the global statistics and to the command's span, if any.
+    pub fn record(&self, span: Option<&GlideSpan>) {
+        let queue_us = self.queue_time.as_micros() as u64;
+        let network_us = self.network_time.as_micros() as u64;
+        let decode_us = self.decode_time.as_micros() as u64;
+        Telemetry::record_latency_breakdown(queue_us, network_us, decode_us);
+        if let Some(span) = span {
+            span.set_attribute_i64("glide.latency.queue_us", queue_us as i64);
+            span.set_attribute_i64("glide.latency.network_us", network_us as i64);
+            span.set_attribute_i64("glide.latency.decode_us", decode_us as i64);
+        }
+    }
+}
+
 // ─── Deadline Entry ──────────────────────────────────────────────────────────
 
 /// Internal entry sent from callers to the watchdog thread.
@@ -855,6 +902,27 @@ mod tests {
         assert!(p99 <= Duration::from_millis(20));
     }
 
+    // ── Latency Breakdown ────────────────────────────────────────────────
+
+    #[test]
+    fn latency_breakdown_splits_phases() {
+        let start = Instant::now();
+        let sent_at = start + Duration::from_micros(100);
+        let first_byte_at = sent_at + Duration::from_micros(2_000);
+        let decoded_at = first_byte_at + Duration::from_micros(30);
+        let breakdown =
+            LatencyBreakdown::new(start, Some(sent_at), first_byte_at, decoded_at).unwrap();
+        assert_eq!(breakdown.queue_time, Duration::from_micros(100));
+        assert_eq!(breakdown.network_time, Duration::from_micros(2_000));
+        assert_eq!(breakdown.decode_time, Duration::from_micros(30));
+    }
+
+    #[test]
+    fn latency_breakdown_requires_send_time() {
+        let now = Instant::now();
+        assert!(LatencyBreakdown::new(now, None, now, now).is_none());
+    }
+
     // ── Concurrency & Throughput ─────────────────────────────────────────
 
     #[tokio::test]
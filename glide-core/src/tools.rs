@@ -0,0 +1,205 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Standalone utilities that don't require a client.
+
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use redis::{
+    ConnectionAddr, ConnectionInfo, ErrorKind, GlideConnectionOptions, RedisConnectionInfo,
+    RedisError, RedisResult, Value,
+};
+
+use crate::client::{DEFAULT_CONNECTION_TIMEOUT, NodeAddress, TlsMode};
+
+/// Options for [`probe_endpoints_with_options`].
+#[derive(Clone, Debug)]
+pub struct ProbeOptions {
+    pub tls_mode: TlsMode,
+    /// Credentials and protocol used to connect to the endpoints.
+    pub connection_info: RedisConnectionInfo,
+    /// Maximum time for connecting to an endpoint and receiving its PING response.
+    pub timeout: Duration,
+}
+
+impl Default for ProbeOptions {
+    fn default() -> Self {
+        Self {
+            tls_mode: TlsMode::NoTls,
+            connection_info: RedisConnectionInfo::default(),
+            timeout: DEFAULT_CONNECTION_TIMEOUT,
+        }
+    }
+}
+
+/// Measured latency of a reachable endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProbeLatency {
+    /// Time to establish the connection, including the TLS and authentication handshakes.
+    pub connect: Duration,
+    /// Round trip time of a PING on the established connection.
+    pub ping: Duration,
+}
+
+impl ProbeLatency {
+    pub fn total(&self) -> Duration {
+        self.connect + self.ping
+    }
+}
+
+/// The result of probing a single endpoint.
+#[derive(Debug)]
+pub struct EndpointProbe {
+    pub address: NodeAddress,
+    pub latency: RedisResult<ProbeLatency>,
+}
+
+/// Measures the connect and PING latency of each candidate endpoint, in parallel, using the
+/// default [`ProbeOptions`].
+/// See [`probe_endpoints_with_options`].
+pub async fn probe_endpoints(candidates: &[NodeAddress]) -> Vec<EndpointProbe> {
+    probe_endpoints_with_options(candidates, &ProbeOptions::default()).await
+}
+
+/// Measures the connect and PING latency of each candidate endpoint, in parallel.
+/// Returns the results ranked from the fastest endpoint to the slowest, followed by the
+/// unreachable endpoints in their original order.
+/// This can be used by applications to pick the closest region or replica at startup.
+pub async fn probe_endpoints_with_options(
+    candidates: &[NodeAddress],
+    options: &ProbeOptions,
+) -> Vec<EndpointProbe> {
+    let probes = candidates.iter().map(|address| async move {
+        let latency = match tokio::time::timeout(options.timeout, probe(address, options)).await {
+            Ok(result) => result,
+            Err(_) => Err(RedisError::from((
+                ErrorKind::IoError,
+                "Endpoint probe timed out",
+            ))),
+        };
+        EndpointProbe {
+            address: address.clone(),
+            latency,
+        }
+    });
+    let mut results = join_all(probes).await;
+    rank(&mut results);
+    results
+}
+
+async fn probe(address: &NodeAddress, options: &ProbeOptions) -> RedisResult<ProbeLatency> {
+    let addr = match options.tls_mode {
+        TlsMode::NoTls => ConnectionAddr::Tcp(address.host.clone(), address.port),
+        _ => ConnectionAddr::TcpTls {
+            host: address.host.clone(),
+            port: address.port,
+            insecure: matches!(options.tls_mode, TlsMode::InsecureTls),
+            tls_params: None,
+        },
+    };
+    let client = redis::Client::open(ConnectionInfo {
+        addr,
+        redis: options.connection_info.clone(),
+    })?;
+
+    let connect_start = Instant::now();
+    let mut connection = client
+        .get_multiplexed_async_connection_with_timeouts(
+            options.timeout,
+            options.timeout,
+            GlideConnectionOptions {
+                connection_timeout: Some(options.timeout),
+                ..Default::default()
+            },
+        )
+        .await?;
+    let connect = connect_start.elapsed();
+
+    let ping_start = Instant::now();
+    redis::cmd("PING")
+        .query_async::<_, Value>(&mut connection)
+        .await?;
+    let ping = ping_start.elapsed();
+
+    Ok(ProbeLatency { connect, ping })
+}
+
+/// Sorts reachable endpoints by total latency, and moves unreachable endpoints to the end.
+/// The sort is stable, so unreachable endpoints keep their relative order.
+fn rank(results: &mut [EndpointProbe]) {
+    results.sort_by_key(|probe| match &probe.latency {
+        Ok(latency) => (false, latency.total()),
+        Err(_) => (true, Duration::ZERO),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(host: &str) -> NodeAddress {
+        NodeAddress {
+            host: host.to_string(),
+            port: 6379,
+        }
+    }
+
+    fn reachable(host: &str, connect_ms: u64, ping_ms: u64) -> EndpointProbe {
+        EndpointProbe {
+            address: address(host),
+            latency: Ok(ProbeLatency {
+                connect: Duration::from_millis(connect_ms),
+                ping: Duration::from_millis(ping_ms),
+            }),
+        }
+    }
+
+    fn unreachable(host: &str) -> EndpointProbe {
+        EndpointProbe {
+            address: address(host),
+            latency: Err(RedisError::from((ErrorKind::IoError, "unreachable"))),
+        }
+    }
+
+    #[test]
+    fn test_rank_orders_by_total_latency_and_puts_failures_last() {
+        let mut results = vec![
+            unreachable("down-1"),
+            reachable("far", 40, 20),
+            unreachable("down-2"),
+            reachable("near", 5, 1),
+            reachable("mid", 5, 30),
+        ];
+
+        rank(&mut results);
+
+        let hosts: Vec<_> = results
+            .iter()
+            .map(|probe| probe.address.host.as_str())
+            .collect();
+        assert_eq!(hosts, vec!["near", "mid", "far", "down-1", "down-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_is_reported() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Close the listener so that connecting to the port is refused
+        drop(listener);
+
+        let results = probe_endpoints_with_options(
+            &[NodeAddress {
+                host: "127.0.0.1".to_string(),
+                port,
+            }],
+            &ProbeOptions {
+                timeout: Duration::from_millis(500),
+                ..Default::default()
+            },
+        )
+        .await;
+
+        assert_eq!(results.len(), 1);
+        assert!(results[0].latency.is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use lazy_static::lazy_static;
 use serde::Serialize;
-use std::sync::RwLock as StdRwLock;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex as StdMutex, RwLock as StdRwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 mod metrics_exporter_file;
 mod open_telemetry;
 mod span_exporter_file;
@@ -34,8 +37,77 @@ pub struct Telemetry {
     subscription_last_sync_timestamp: u64,
 }
 
+/// Maximum number of connection errors kept by `Telemetry::record_connection_error`
+pub const CONNECTION_ERROR_HISTORY_SIZE: usize = 64;
+
+/// A connection-level error, kept for diagnosing transient failures after the fact.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectionErrorRecord {
+    /// Unix timestamp (in milliseconds) of the error
+    pub timestamp: u64,
+    /// Address of the node the connection was to
+    pub node: String,
+    /// Kind of the error, e.g. `IoError`
+    pub error_class: String,
+    pub message: String,
+}
+
 lazy_static! {
     static ref TELEMETRY: StdRwLock<Telemetry> = StdRwLock::<Telemetry>::default();
+    /// The most recent connection errors, oldest first
+    static ref CONNECTION_ERRORS: StdMutex<VecDeque<ConnectionErrorRecord>> =
+        StdMutex::new(VecDeque::with_capacity(CONNECTION_ERROR_HISTORY_SIZE));
+}
+
+// Latency breakdown counters are updated once per command, so they are kept as atomics
+// instead of behind the `TELEMETRY` lock.
+/// Number of commands whose latency breakdown was recorded
+static LATENCY_BREAKDOWN_SAMPLES: AtomicU64 = AtomicU64::new(0);
+/// Total time (in microseconds) commands spent queued in the client before being sent
+static TOTAL_QUEUE_TIME_US: AtomicU64 = AtomicU64::new(0);
+/// Total time (in microseconds) between sending commands and receiving their responses
+static TOTAL_NETWORK_TIME_US: AtomicU64 = AtomicU64::new(0);
+/// Total time (in microseconds) spent decoding responses into their final form
+static TOTAL_DECODE_TIME_US: AtomicU64 = AtomicU64::new(0);
+/// Number of times a key was detected as hot
+static HOT_KEY_WARNINGS: AtomicU64 = AtomicU64::new(0);
+/// Bytes currently accounted against the memory budgets of all clients
+static MEMORY_BUDGET_BYTES_IN_USE: AtomicU64 = AtomicU64::new(0);
+/// Number of requests rejected because a client's memory budget was exhausted
+static MEMORY_BUDGET_REJECTIONS: AtomicU64 = AtomicU64::new(0);
+/// Memory of the process, as last measured for the soft memory limit
+static PROCESS_MEMORY_BYTES: AtomicU64 = AtomicU64::new(0);
+/// Number of requests rejected because the process exceeded its soft memory limit
+static SOFT_MEMORY_LIMIT_REJECTIONS: AtomicU64 = AtomicU64::new(0);
+/// Number of commands whose arguments exceeded the large request threshold
+static LARGE_REQUESTS: AtomicU64 = AtomicU64::new(0);
+/// Number of commands whose response exceeded the large response threshold
+static LARGE_RESPONSES: AtomicU64 = AtomicU64::new(0);
+/// Number of health-check PINGs sent to nodes
+static HEALTH_CHECKS: AtomicU64 = AtomicU64::new(0);
+/// Number of health-check PINGs that failed or timed out
+static HEALTH_CHECK_FAILURES: AtomicU64 = AtomicU64::new(0);
+/// Number of node connections closed and rebuilt by health checks
+static HEALTH_CHECK_EVICTIONS: AtomicU64 = AtomicU64::new(0);
+/// Number of nodes whose last health check failed
+static UNHEALTHY_NODES: AtomicU64 = AtomicU64::new(0);
+/// Number of socket requests read but not dispatched yet, by `QueuePriority`
+static QUEUED_REQUESTS: [AtomicU64; 3] = [const { AtomicU64::new(0) }; 3];
+/// Number of socket requests dispatched ahead of their priority because they waited too long
+static STARVED_REQUESTS: AtomicU64 = AtomicU64::new(0);
+/// Number of push messages dropped because a client's push queue was full
+static DROPPED_PUSH_MESSAGES: AtomicU64 = AtomicU64::new(0);
+/// Number of clients disconnected because their push queue was full
+static PUSH_QUEUE_DISCONNECTS: AtomicU64 = AtomicU64::new(0);
+/// Number of connection errors recorded, including the ones no longer kept in the history
+static TOTAL_CONNECTION_ERRORS: AtomicU64 = AtomicU64::new(0);
+
+/// The scheduling priorities of socket requests, for the queued requests statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueuePriority {
+    High = 0,
+    Normal = 1,
+    Low = 2,
 }
 
 const MUTEX_WRITE_ERR: &str = "Failed to obtain write lock for mutex. Poisoned mutex";
@@ -202,8 +274,357 @@ impl Telemetry {
             .subscription_last_sync_timestamp
     }
 
+    /// Record the latency breakdown of a single command, in microseconds
+    pub fn record_latency_breakdown(queue_time_us: u64, network_time_us: u64, decode_time_us: u64) {
+        LATENCY_BREAKDOWN_SAMPLES.fetch_add(1, Ordering::Relaxed);
+        TOTAL_QUEUE_TIME_US.fetch_add(queue_time_us, Ordering::Relaxed);
+        TOTAL_NETWORK_TIME_US.fetch_add(network_time_us, Ordering::Relaxed);
+        TOTAL_DECODE_TIME_US.fetch_add(decode_time_us, Ordering::Relaxed);
+    }
+
+    /// Return the number of commands whose latency breakdown was recorded
+    pub fn latency_breakdown_samples() -> u64 {
+        LATENCY_BREAKDOWN_SAMPLES.load(Ordering::Relaxed)
+    }
+
+    /// Return the total time (in microseconds) commands spent queued in the client
+    pub fn total_queue_time_us() -> u64 {
+        TOTAL_QUEUE_TIME_US.load(Ordering::Relaxed)
+    }
+
+    /// Return the total time (in microseconds) commands spent on the wire and in the server
+    pub fn total_network_time_us() -> u64 {
+        TOTAL_NETWORK_TIME_US.load(Ordering::Relaxed)
+    }
+
+    /// Return the total time (in microseconds) spent decoding responses
+    pub fn total_decode_time_us() -> u64 {
+        TOTAL_DECODE_TIME_US.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of times a key was detected as hot
+    pub fn incr_hot_key_warnings() -> u64 {
+        HOT_KEY_WARNINGS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of times a key was detected as hot
+    pub fn hot_key_warnings() -> u64 {
+        HOT_KEY_WARNINGS.load(Ordering::Relaxed)
+    }
+
+    /// Increase the bytes accounted against the client memory budgets by `bytes`
+    pub fn incr_memory_budget_bytes_in_use(bytes: u64) -> u64 {
+        MEMORY_BUDGET_BYTES_IN_USE.fetch_add(bytes, Ordering::Relaxed) + bytes
+    }
+
+    /// Decrease the bytes accounted against the client memory budgets by `bytes`
+    pub fn decr_memory_budget_bytes_in_use(bytes: u64) -> u64 {
+        let previous = MEMORY_BUDGET_BYTES_IN_USE
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |used| {
+                Some(used.saturating_sub(bytes))
+            })
+            .unwrap_or_default();
+        previous.saturating_sub(bytes)
+    }
+
+    /// Return the bytes currently accounted against the client memory budgets
+    pub fn memory_budget_bytes_in_use() -> u64 {
+        MEMORY_BUDGET_BYTES_IN_USE.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of requests rejected by a client memory budget
+    pub fn incr_memory_budget_rejections() -> u64 {
+        MEMORY_BUDGET_REJECTIONS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of requests rejected by a client memory budget
+    pub fn memory_budget_rejections() -> u64 {
+        MEMORY_BUDGET_REJECTIONS.load(Ordering::Relaxed)
+    }
+
+    /// Set the memory of the process, as measured for the soft memory limit
+    pub fn set_process_memory_bytes(bytes: u64) {
+        PROCESS_MEMORY_BYTES.store(bytes, Ordering::Relaxed);
+    }
+
+    /// Return the memory of the process, as last measured for the soft memory limit
+    pub fn process_memory_bytes() -> u64 {
+        PROCESS_MEMORY_BYTES.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of requests rejected by the soft memory limit
+    pub fn incr_soft_memory_limit_rejections() -> u64 {
+        SOFT_MEMORY_LIMIT_REJECTIONS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of requests rejected by the soft memory limit
+    pub fn soft_memory_limit_rejections() -> u64 {
+        SOFT_MEMORY_LIMIT_REJECTIONS.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of commands whose arguments exceeded the large request threshold
+    pub fn incr_large_requests() -> u64 {
+        LARGE_REQUESTS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of commands whose arguments exceeded the large request threshold
+    pub fn large_requests() -> u64 {
+        LARGE_REQUESTS.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of commands whose response exceeded the large response threshold
+    pub fn incr_large_responses() -> u64 {
+        LARGE_RESPONSES.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of commands whose response exceeded the large response threshold
+    pub fn large_responses() -> u64 {
+        LARGE_RESPONSES.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of health-check PINGs sent to nodes
+    pub fn incr_health_checks() -> u64 {
+        HEALTH_CHECKS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of health-check PINGs sent to nodes
+    pub fn health_checks() -> u64 {
+        HEALTH_CHECKS.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of failed health-check PINGs
+    pub fn incr_health_check_failures() -> u64 {
+        HEALTH_CHECK_FAILURES.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of failed health-check PINGs
+    pub fn health_check_failures() -> u64 {
+        HEALTH_CHECK_FAILURES.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of node connections rebuilt by health checks
+    pub fn incr_health_check_evictions() -> u64 {
+        HEALTH_CHECK_EVICTIONS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of node connections rebuilt by health checks
+    pub fn health_check_evictions() -> u64 {
+        HEALTH_CHECK_EVICTIONS.load(Ordering::Relaxed)
+    }
+
+    /// Increase the number of unhealthy nodes by `nodes`
+    pub fn incr_unhealthy_nodes(nodes: u64) -> u64 {
+        UNHEALTHY_NODES.fetch_add(nodes, Ordering::Relaxed) + nodes
+    }
+
+    /// Decrease the number of unhealthy nodes by `nodes`
+    pub fn decr_unhealthy_nodes(nodes: u64) -> u64 {
+        let previous = UNHEALTHY_NODES
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count.saturating_sub(nodes))
+            })
+            .unwrap_or_default();
+        previous.saturating_sub(nodes)
+    }
+
+    /// Return the number of nodes whose last health check failed
+    pub fn unhealthy_nodes() -> u64 {
+        UNHEALTHY_NODES.load(Ordering::Relaxed)
+    }
+
+    /// Increase the number of queued socket requests of `priority` by one
+    pub fn incr_queued_requests(priority: QueuePriority) -> u64 {
+        QUEUED_REQUESTS[priority as usize].fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Decrease the number of queued socket requests of `priority` by `requests`
+    pub fn decr_queued_requests(priority: QueuePriority, requests: u64) -> u64 {
+        let previous = QUEUED_REQUESTS[priority as usize]
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |count| {
+                Some(count.saturating_sub(requests))
+            })
+            .unwrap_or_default();
+        previous.saturating_sub(requests)
+    }
+
+    /// Return the number of socket requests of `priority` read but not dispatched yet
+    pub fn queued_requests(priority: QueuePriority) -> u64 {
+        QUEUED_REQUESTS[priority as usize].load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of socket requests dispatched ahead of their priority
+    pub fn incr_starved_requests() -> u64 {
+        STARVED_REQUESTS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of socket requests dispatched ahead of their priority because they
+    /// waited too long
+    pub fn starved_requests() -> u64 {
+        STARVED_REQUESTS.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of push messages dropped because a push queue was full
+    pub fn incr_dropped_push_messages() -> u64 {
+        DROPPED_PUSH_MESSAGES.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of push messages dropped because a push queue was full
+    pub fn dropped_push_messages() -> u64 {
+        DROPPED_PUSH_MESSAGES.load(Ordering::Relaxed)
+    }
+
+    /// Increment the number of clients disconnected because their push queue was full
+    pub fn incr_push_queue_disconnects() -> u64 {
+        PUSH_QUEUE_DISCONNECTS.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Return the number of clients disconnected because their push queue was full
+    pub fn push_queue_disconnects() -> u64 {
+        PUSH_QUEUE_DISCONNECTS.load(Ordering::Relaxed)
+    }
+
+    /// Record a connection-level error, such as a failed connection attempt or a dropped
+    /// connection. Only the latest `CONNECTION_ERROR_HISTORY_SIZE` errors are kept.
+    pub fn record_connection_error(node: &str, error_class: &str, message: &str) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_millis() as u64)
+            .unwrap_or_default();
+        let record = ConnectionErrorRecord {
+            timestamp,
+            node: node.to_string(),
+            error_class: error_class.to_string(),
+            message: message.to_string(),
+        };
+        TOTAL_CONNECTION_ERRORS.fetch_add(1, Ordering::Relaxed);
+        push_connection_error(
+            &mut CONNECTION_ERRORS.lock().expect(MUTEX_WRITE_ERR),
+            record,
+        );
+    }
+
+    /// Return the number of connection errors recorded
+    pub fn total_connection_errors() -> u64 {
+        TOTAL_CONNECTION_ERRORS.load(Ordering::Relaxed)
+    }
+
+    /// Return the most recent connection errors, oldest first
+    pub fn recent_connection_errors() -> Vec<ConnectionErrorRecord> {
+        CONNECTION_ERRORS
+            .lock()
+            .expect(MUTEX_READ_ERR)
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Return the most recent connection errors as a JSON array, oldest first
+    pub fn recent_connection_errors_json() -> String {
+        serde_json::to_string(&Self::recent_connection_errors()).unwrap_or_default()
+    }
+
+    /// Log the recent connection errors of the given nodes. Called when a client is closed, so
+    /// that errors which were never returned to callers still end up in the logs.
+    pub fn log_connection_errors(nodes: &[String]) {
+        let errors: Vec<String> = CONNECTION_ERRORS
+            .lock()
+            .expect(MUTEX_READ_ERR)
+            .iter()
+            .filter(|record| nodes.contains(&record.node))
+            .map(|record| {
+                format!(
+                    "{} {} {}: {}",
+                    record.timestamp, record.node, record.error_class, record.message
+                )
+            })
+            .collect();
+        if errors.is_empty() {
+            return;
+        }
+        logger_core::log_info(
+            "connection_errors",
+            format!(
+                "Recent connection errors of the closed client:\n{}",
+                errors.join("\n")
+            ),
+        );
+    }
+
     /// Reset the telemetry collected thus far
     pub fn reset() {
         *TELEMETRY.write().expect(MUTEX_WRITE_ERR) = Telemetry::default();
+        LATENCY_BREAKDOWN_SAMPLES.store(0, Ordering::Relaxed);
+        TOTAL_QUEUE_TIME_US.store(0, Ordering::Relaxed);
+        TOTAL_NETWORK_TIME_US.store(0, Ordering::Relaxed);
+        TOTAL_DECODE_TIME_US.store(0, Ordering::Relaxed);
+        HOT_KEY_WARNINGS.store(0, Ordering::Relaxed);
+        MEMORY_BUDGET_REJECTIONS.store(0, Ordering::Relaxed);
+        SOFT_MEMORY_LIMIT_REJECTIONS.store(0, Ordering::Relaxed);
+        LARGE_REQUESTS.store(0, Ordering::Relaxed);
+        LARGE_RESPONSES.store(0, Ordering::Relaxed);
+        HEALTH_CHECKS.store(0, Ordering::Relaxed);
+        HEALTH_CHECK_FAILURES.store(0, Ordering::Relaxed);
+        HEALTH_CHECK_EVICTIONS.store(0, Ordering::Relaxed);
+        STARVED_REQUESTS.store(0, Ordering::Relaxed);
+        DROPPED_PUSH_MESSAGES.store(0, Ordering::Relaxed);
+        PUSH_QUEUE_DISCONNECTS.store(0, Ordering::Relaxed);
+        TOTAL_CONNECTION_ERRORS.store(0, Ordering::Relaxed);
+        CONNECTION_ERRORS.lock().expect(MUTEX_WRITE_ERR).clear();
+    }
+}
+
+/// Appends `record` to `errors`, dropping the oldest error once the history is full
+fn push_connection_error(
+    errors: &mut VecDeque<ConnectionErrorRecord>,
+    record: ConnectionErrorRecord,
+) {
+    if errors.len() == CONNECTION_ERROR_HISTORY_SIZE {
+        errors.pop_front();
+    }
+    errors.push_back(record);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(message: String) -> ConnectionErrorRecord {
+        ConnectionErrorRecord {
+            timestamp: 0,
+            node: "node:6379".to_string(),
+            error_class: "IoError".to_string(),
+            message,
+        }
+    }
+
+    #[test]
+    fn test_connection_error_history_is_bounded() {
+        let mut errors = VecDeque::new();
+        let extra = 3;
+        for i in 0..CONNECTION_ERROR_HISTORY_SIZE + extra {
+            push_connection_error(&mut errors, record(i.to_string()));
+        }
+        assert_eq!(errors.len(), CONNECTION_ERROR_HISTORY_SIZE);
+        // The oldest errors are dropped first
+        assert_eq!(errors[0].message, extra.to_string());
+        assert_eq!(
+            errors.back().unwrap().message,
+            (CONNECTION_ERROR_HISTORY_SIZE + extra - 1).to_string()
+        );
+    }
+
+    #[test]
+    fn test_connection_errors_are_recorded() {
+        // The history is global, so only this test's node is inspected
+        let node = "test_connection_errors_are_recorded:6379";
+        let total_before = Telemetry::total_connection_errors();
+        Telemetry::record_connection_error(node, "IoError", "connection refused");
+        assert!(Telemetry::total_connection_errors() > total_before);
+        assert!(
+            Telemetry::recent_connection_errors()
+                .iter()
+                .any(|error| error.node == node && error.message == "connection refused")
+        );
+        assert!(Telemetry::recent_connection_errors_json().contains(node));
     }
 }
@@ -610,6 +610,10 @@ static SUBSCRIPTION_OUT_OF_SYNC_COUNTER: OnceLock<opentelemetry::metrics::Counte
     OnceLock::new();
 static SUBSCRIPTION_LAST_SYNC_GAUGE: OnceLock<opentelemetry::metrics::Gauge<u64>> = OnceLock::new();
 
+/// The providers set as global, kept to flush them.
+static TRACER_PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+static METER_PROVIDER: OnceLock<SdkMeterProvider> = OnceLock::new();
+
 /// Singleton instance of GlideOpenTelemetry. Ensures that telemetry setup happens only once across the application.
 static OTEL: OnceCell<RwLock<GlideOpenTelemetry>> = OnceCell::new();
 
@@ -833,6 +837,7 @@ impl GlideOpenTelemetry {
         let provider = TracerProvider::builder()
             .with_span_processor(trace_exporter)
             .build();
+        let _ = TRACER_PROVIDER.set(provider.clone());
         global::set_tracer_provider(provider);
 
         Ok(())
@@ -902,6 +907,7 @@ impl GlideOpenTelemetry {
         let meter_provider = SdkMeterProvider::builder()
             .with_reader(metrics_exporter)
             .build();
+        let _ = METER_PROVIDER.set(meter_provider.clone());
         global::set_meter_provider(meter_provider);
 
         Ok(())
@@ -1101,6 +1107,23 @@ impl GlideOpenTelemetry {
         global::shutdown_tracer_provider();
     }
 
+    /// Exports the spans and metrics recorded so far, without shutting the exporters down.
+    /// Blocks until the export completes, so it must not be called from an async task.
+    pub fn flush() {
+        if let Some(provider) = TRACER_PROVIDER.get() {
+            for result in provider.force_flush() {
+                if let Err(err) = result {
+                    log_warn("opentelemetry", format!("Failed to flush spans: {err}"));
+                }
+            }
+        }
+        if let Some(provider) = METER_PROVIDER.get()
+            && let Err(err) = provider.force_flush()
+        {
+            log_warn("opentelemetry", format!("Failed to flush metrics: {err}"));
+        }
+    }
+
     /// Check if OpenTelemetry is initialized
     pub fn is_initialized() -> bool {
         OTEL.get().is_some()
@@ -409,6 +409,61 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_update_connection_password_with_immediate_auth(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let password = "ReallySecurePassword".to_string();
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    use_tls: true,
+                    connection_info: Some(redis::RedisConnectionInfo {
+                        password: Some(password.clone()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            // Re-authenticating all live connections with the current password succeeds.
+            let result = test_basics
+                .client
+                .update_connection_password(Some(password.clone()), true)
+                .await;
+            assert_eq!(result, Ok(Value::Okay));
+
+            // Authenticating with a wrong password is reported by the servers.
+            let result = test_basics
+                .client
+                .update_connection_password(Some("WrongPassword".to_string()), true)
+                .await;
+            assert!(result.is_err());
+
+            // Removing the password can't be combined with immediate authentication.
+            let err = test_basics
+                .client
+                .update_connection_password(None, true)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::UserOperationError);
+
+            // Restore the stored password without authenticating, and verify that the
+            // existing connections are still usable.
+            let result = test_basics
+                .client
+                .update_connection_password(Some(password), false)
+                .await;
+            assert_eq!(result, Ok(Value::Okay));
+            let key = generate_random_string(6);
+            send_set_and_get(test_basics.client.clone(), key).await;
+        });
+    }
+
     #[cfg(feature = "iam_tests")]
     fn remove_test_credentials() {
         // Clear any existing AWS credentials
@@ -3569,4 +3624,797 @@ pub(crate) mod shared_client_tests {
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
         });
     }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_stream_consumer_reads_acks_and_claims(#[values(false, true)] use_cluster: bool) {
+        use glide_core::streams::{StreamConsumer, StreamConsumerConfig, XAddBuilder};
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let key = generate_random_string(6);
+            let config = |consumer: &str| StreamConsumerConfig {
+                key: key.clone().into_bytes(),
+                group: b"group".to_vec(),
+                consumer: consumer.as_bytes().to_vec(),
+                count: 2,
+                block: None,
+                min_idle_time: Some(std::time::Duration::ZERO),
+            };
+            let mut first = StreamConsumer::new(config("first"));
+            first.create_group(&mut test_basics.client).await.unwrap();
+            // Creating an existing group is not an error.
+            first.create_group(&mut test_basics.client).await.unwrap();
+
+            for i in 0..3 {
+                let mut cmd = XAddBuilder::new(key.clone())
+                    .field("index", i.to_string())
+                    .build()
+                    .unwrap();
+                test_basics
+                    .client
+                    .send_command(&mut cmd, None)
+                    .await
+                    .unwrap();
+            }
+
+            let batch = first.poll(&mut test_basics.client).await.unwrap();
+            assert_eq!(batch.entries.len(), 2);
+            let acked = first
+                .ack(&mut test_basics.client, [batch.entries[0].id.as_str()])
+                .await
+                .unwrap();
+            assert_eq!(acked, 1);
+
+            // The unacknowledged entry is claimed by the second consumer before it reads the new one.
+            let mut second = StreamConsumer::new(config("second"));
+            let batch = second.poll(&mut test_basics.client).await.unwrap();
+            let indexes: Vec<_> = batch
+                .entries
+                .iter()
+                .map(|entry| entry.fields[0].1.clone())
+                .collect();
+            assert_eq!(indexes, vec![b"1".to_vec(), b"2".to_vec()]);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_scan_and_swap_logical_databases(#[values(false, true)] use_cluster: bool) {
+        use glide_core::databases::{DatabaseScanOptions, DatabaseScanner, swap_db};
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: false,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let mut scanner = DatabaseScanner::new(1..3, DatabaseScanOptions::default());
+            if use_cluster {
+                scanner
+                    .next_batch(&mut test_basics.client)
+                    .await
+                    .unwrap_err();
+                swap_db(&mut test_basics.client, 1, 2).await.unwrap_err();
+                return;
+            }
+
+            for (database, key) in [(1, "first"), (2, "second")] {
+                let mut cmd = redis::cmd("SELECT");
+                cmd.arg(database);
+                test_basics
+                    .client
+                    .send_command(&mut cmd, None)
+                    .await
+                    .unwrap();
+                let mut cmd = redis::cmd("SET");
+                cmd.arg(key).arg("value");
+                test_basics
+                    .client
+                    .send_command(&mut cmd, None)
+                    .await
+                    .unwrap();
+            }
+
+            let mut keys = Vec::new();
+            while let Some(batch) = scanner.next_batch(&mut test_basics.client).await.unwrap() {
+                for key in batch.keys {
+                    keys.push((batch.database, key));
+                }
+            }
+            assert_eq!(keys, vec![(1, b"first".to_vec()), (2, b"second".to_vec())]);
+
+            // The client stays on the database it selected
+            let mut cmd = redis::cmd("GET");
+            cmd.arg("second");
+            let value = test_basics
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+
+            swap_db(&mut test_basics.client, 1, 2).await.unwrap();
+            let mut cmd = redis::cmd("GET");
+            cmd.arg("first");
+            let value = test_basics
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_health_checks_report_each_node(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async move {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    health_check: Some(glide_core::connection_request::HealthCheckConfig {
+                        interval_ms: 100,
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            )
+            .await;
+            let client = test_basics.client;
+            tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+
+            let nodes = client.node_health();
+            assert!(!nodes.is_empty());
+            for node in nodes {
+                assert!(node.healthy, "{node:?}");
+                assert!(node.last_rtt.is_some(), "{node:?}");
+                assert_eq!(node.evictions, 0);
+            }
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_server_info_by_node(#[values(false, true)] use_cluster: bool) {
+        use glide_core::client::server_info::{FieldValue, NodeSelection};
+
+        block_on_all(async move {
+            let test_basics = setup_test_basics(use_cluster, TestConfiguration::default()).await;
+            let mut client = test_basics.client;
+
+            let info = client
+                .info_by_node(&["server"], NodeSelection::AllPrimaries)
+                .await
+                .unwrap();
+            assert!(!info.is_empty());
+            for sections in info.values() {
+                assert!(matches!(
+                    sections["server"]["tcp_port"],
+                    FieldValue::Integer(_)
+                ));
+            }
+
+            let addresses: Vec<String> = info.keys().cloned().collect();
+            let config = client
+                .config_get_by_node(&["maxmemory"], NodeSelection::Nodes(addresses.clone()))
+                .await
+                .unwrap();
+            assert_eq!(config.keys().cloned().collect::<Vec<_>>(), addresses);
+            for parameters in config.values() {
+                assert!(matches!(parameters["maxmemory"], FieldValue::Integer(_)));
+            }
+
+            let clients = client
+                .client_list_by_node(NodeSelection::AllNodes)
+                .await
+                .unwrap();
+            assert!(clients.values().all(|clients| !clients.is_empty()));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_batched_gets(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async move {
+            let test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    get_batching_window_us: Some(500),
+                    ..Default::default()
+                },
+            )
+            .await;
+            let mut client = test_basics.client;
+            for i in 0..20 {
+                let mut cmd = redis::cmd("SET");
+                cmd.arg(format!("batched_{i}")).arg(i);
+                client.send_command(&mut cmd, None).await.unwrap();
+            }
+
+            // Half of the keys are missing
+            let gets = (0..40).map(|i| {
+                let mut client = client.clone();
+                async move {
+                    let mut cmd = redis::cmd("GET");
+                    cmd.arg(format!("batched_{i}"));
+                    client.send_command(&mut cmd, None).await.unwrap()
+                }
+            });
+            let values = futures::future::join_all(gets).await;
+            for (i, value) in values.into_iter().enumerate() {
+                let expected = if i < 20 {
+                    Value::BulkString(i.to_string().into_bytes())
+                } else {
+                    Value::Nil
+                };
+                assert_eq!(value, expected);
+            }
+
+            // MGET returns nil for keys of other types, but the GET still fails with WRONGTYPE
+            let mut cmd = redis::cmd("LPUSH");
+            cmd.arg("batched_list").arg("item");
+            client.send_command(&mut cmd, None).await.unwrap();
+            let mut cmd = redis::cmd("GET");
+            cmd.arg("batched_list");
+            let err = client.send_command(&mut cmd, None).await.unwrap_err();
+            assert!(err.to_string().contains("WRONGTYPE"), "{err:?}");
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_keys_metadata(#[values(false, true)] use_cluster: bool) {
+        use glide_core::keys_metadata::{KeyType, keys_metadata};
+        block_on_all(async move {
+            let mut test_basics =
+                setup_test_basics(use_cluster, TestConfiguration::default()).await;
+            let mut cmd = redis::cmd("SET");
+            cmd.arg("metadata_string")
+                .arg("value")
+                .arg("PX")
+                .arg(100_000);
+            test_basics
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            let mut cmd = redis::cmd("HSET");
+            cmd.arg("metadata_hash").arg("field").arg("value");
+            test_basics
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+
+            let keys = [
+                b"metadata_string".to_vec(),
+                b"metadata_hash".to_vec(),
+                b"metadata_missing".to_vec(),
+            ];
+            let metadata = keys_metadata(&mut test_basics.client, &keys).await.unwrap();
+            assert_eq!(metadata.len(), 3);
+            assert_eq!(metadata[0].key_type, Some(KeyType::String));
+            assert!(metadata[0].ttl.unwrap() <= std::time::Duration::from_secs(100));
+            assert_eq!(metadata[1].key_type, Some(KeyType::Hash));
+            assert_eq!(metadata[1].ttl, None);
+            assert!(metadata[1].encoding.is_some());
+            assert!(!metadata[2].exists());
+            assert_eq!(metadata[2].encoding, None);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_migrate_keys(#[values(false, true)] use_cluster: bool) {
+        use glide_core::key_migration::{MigrationOptions, migrate_keys};
+        block_on_all(async move {
+            let configuration = || TestConfiguration {
+                shared_server: false,
+                ..Default::default()
+            };
+            let mut source = setup_test_basics(use_cluster, configuration()).await;
+            let mut destination = setup_test_basics(use_cluster, configuration()).await;
+            let mut commands = vec![
+                redis::cmd("SET"),
+                redis::cmd("RPUSH"),
+                redis::cmd("ZADD"),
+                redis::cmd("SET"),
+            ];
+            commands[0]
+                .arg("migrate_string")
+                .arg("value")
+                .arg("PX")
+                .arg(100_000);
+            commands[1]
+                .arg("migrate_list")
+                .arg(&["a", "b", "c", "d", "e"]);
+            commands[2]
+                .arg("migrate_zset")
+                .arg(&["1", "a", "2.5", "b", "3", "c"]);
+            commands[3].arg("other_key").arg("value");
+            for cmd in &mut commands {
+                source.client.send_command(cmd, None).await.unwrap();
+            }
+            // The existing key is only migrated with REPLACE
+            let mut cmd = redis::cmd("SET");
+            cmd.arg("migrate_string").arg("old");
+            destination
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+
+            // Every hash, list and sorted set is big enough to be copied in chunks
+            let options = MigrationOptions {
+                big_key_threshold: Some(0),
+                chunk_size: 2,
+                ..Default::default()
+            };
+            let mut reported = 0;
+            let progress = migrate_keys(
+                &mut source.client,
+                b"migrate_*",
+                &mut destination.client,
+                &options,
+                |_| reported += 1,
+            )
+            .await
+            .unwrap();
+            assert!(reported > 0);
+            assert_eq!(progress.scanned_keys, 3);
+            assert_eq!(progress.chunked_keys, 2);
+            assert_eq!(progress.failures.len(), 1);
+            assert_eq!(progress.failures[0].key, b"migrate_string".to_vec());
+
+            let options = MigrationOptions {
+                replace: true,
+                absolute_ttl: true,
+                big_key_threshold: None,
+                ..Default::default()
+            };
+            let progress = migrate_keys(
+                &mut source.client,
+                b"migrate_*",
+                &mut destination.client,
+                &options,
+                |_| {},
+            )
+            .await
+            .unwrap();
+            assert_eq!(progress.restored_keys, 3);
+            assert!(progress.failures.is_empty());
+
+            let mut cmd = redis::cmd("PTTL");
+            cmd.arg("migrate_string");
+            let ttl = destination
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            assert!(matches!(ttl, Value::Int(ttl) if ttl > 0 && ttl <= 100_000));
+            let mut cmd = redis::cmd("LRANGE");
+            cmd.arg("migrate_list").arg(0).arg(-1);
+            let list = destination
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            assert_eq!(
+                list,
+                Value::Array(
+                    ["a", "b", "c", "d", "e"]
+                        .iter()
+                        .map(|element| Value::BulkString(element.as_bytes().to_vec()))
+                        .collect()
+                )
+            );
+            let mut cmd = redis::cmd("ZSCORE");
+            cmd.arg("migrate_zset").arg("b");
+            let score = destination
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            assert_eq!(score, Value::Double(2.5));
+            let mut cmd = redis::cmd("EXISTS");
+            cmd.arg("other_key");
+            let exists = destination
+                .client
+                .send_command(&mut cmd, None)
+                .await
+                .unwrap();
+            assert_eq!(exists, Value::Int(0));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_command_with_durability(#[values(false, true)] use_cluster: bool) {
+        use glide_core::client::durability::Durability;
+        block_on_all(async move {
+            let mut test_basics =
+                setup_test_basics(use_cluster, TestConfiguration::default()).await;
+            let durability = Durability {
+                num_replicas: 0,
+                timeout: std::time::Duration::from_millis(100),
+            };
+
+            let mut cmd = redis::cmd("SET");
+            cmd.arg("durable_key").arg("value");
+            let (value, acknowledged) = test_basics
+                .client
+                .send_command_with_durability(&mut cmd, None, durability)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::Okay);
+            assert!(acknowledged.is_some());
+
+            // Read-only commands aren't followed by WAIT
+            let mut cmd = redis::cmd("GET");
+            cmd.arg("durable_key");
+            let (value, acknowledged) = test_basics
+                .client
+                .send_command_with_durability(&mut cmd, None, durability)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::BulkString(b"value".to_vec()));
+            assert_eq!(acknowledged, None);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_scheduler_pops_due_jobs_and_acks(#[values(false, true)] use_cluster: bool) {
+        use glide_core::scheduler::{Scheduler, SchedulerConfig};
+        block_on_all(async move {
+            let mut test_basics =
+                setup_test_basics(use_cluster, TestConfiguration::default()).await;
+            let scheduler = Scheduler::new(SchedulerConfig {
+                name: "scheduler_test".to_string(),
+                lease: std::time::Duration::from_millis(200),
+                batch_size: 10,
+            });
+            let client = &mut test_basics.client;
+            scheduler
+                .schedule(client, b"now", b"first", std::time::Duration::ZERO)
+                .await
+                .unwrap();
+            scheduler
+                .schedule(
+                    client,
+                    b"later",
+                    b"second",
+                    std::time::Duration::from_secs(60),
+                )
+                .await
+                .unwrap();
+
+            let first_lease = scheduler.pop_due(client).await.unwrap();
+            assert_eq!(first_lease.len(), 1);
+            assert_eq!(first_lease[0].payload, b"first".to_vec());
+            assert!(scheduler.pop_due(client).await.unwrap().is_empty());
+
+            // The job is due again once its lease expires without an ack
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            let jobs = scheduler.pop_due(client).await.unwrap();
+            assert_eq!(jobs.len(), 1);
+            // The token of the expired lease doesn't acknowledge the job
+            assert_eq!(scheduler.ack(client, &first_lease).await.unwrap(), 0);
+            assert_eq!(scheduler.ack(client, &jobs).await.unwrap(), 1);
+            assert_eq!(scheduler.cancel(client, &[b"later"]).await.unwrap(), 1);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_watch_transaction_holds_an_inflight_slot(#[values(false, true)] use_cluster: bool) {
+        use futures::FutureExt;
+        use glide_core::client::watch::WatchRetryPolicy;
+        block_on_all(async move {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let key = generate_random_string(6).into_bytes();
+            let observer = test_basics.client.clone();
+            let idle = observer.available_inflight_count();
+            let response = test_basics
+                .client
+                .watch_transaction(
+                    std::slice::from_ref(&key),
+                    WatchRetryPolicy::default(),
+                    |connection| {
+                        let key = key.clone();
+                        let observer = observer.clone();
+                        async move {
+                            assert_eq!(observer.available_inflight_count(), idle - 1);
+                            let value = connection.send_command(cmd("GET").arg(&key)).await?;
+                            assert_eq!(value, Value::Nil);
+                            let mut pipeline = Pipeline::new();
+                            pipeline.cmd("SET").arg(&key).arg("watched");
+                            Ok(pipeline)
+                        }
+                        .boxed()
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(response, Value::Array(vec![Value::Okay]));
+            assert_eq!(observer.available_inflight_count(), idle);
+
+            let value = test_basics
+                .client
+                .send_command(cmd("GET").arg(&key), None)
+                .await
+                .unwrap();
+            assert_eq!(value, Value::BulkString(b"watched".to_vec()));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_watch_transaction_is_rejected_with_compression() {
+        use futures::FutureExt;
+        use glide_core::client::watch::WatchRetryPolicy;
+        use glide_core::compression::{CompressionBackendType, CompressionConfig};
+        block_on_all(async move {
+            let address = get_shared_server_address(false);
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                std::slice::from_ref(&address),
+                &TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .into();
+            request.compression_config = Some(CompressionConfig::new(CompressionBackendType::Zstd));
+            let mut client = Client::new(request, None).await.unwrap();
+            let idle = client.available_inflight_count();
+
+            let err = client
+                .watch_transaction(&[b"key".to_vec()], WatchRetryPolicy::default(), |_| {
+                    async { Ok(Pipeline::new()) }.boxed()
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError);
+            assert_eq!(client.available_inflight_count(), idle);
+        });
+    }
+
+    #[test]
+    fn test_client_rejects_a_push_queue() {
+        use glide_core::client::ConnectionError;
+        use glide_core::pubsub::push_queue::PushQueueConfig;
+        block_on_all(async move {
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                &[redis::ConnectionAddr::Tcp("localhost".to_string(), 6379)],
+                &TestConfiguration::default(),
+            )
+            .into();
+            request.push_queue = Some(PushQueueConfig::default());
+            // The push queue is rejected before connecting.
+            assert!(matches!(
+                Client::new(request, None).await,
+                Err(ConnectionError::Configuration(_))
+            ));
+        });
+    }
+
+    /// Prefixes the key of single-key commands, routes them with `routing` if set, and returns
+    /// the length of bulk string responses instead of the strings.
+    #[derive(Debug)]
+    struct PrefixingInterceptor {
+        prefix: &'static str,
+        routing: Option<RoutingInfo>,
+    }
+
+    impl glide_core::client::CommandInterceptor for PrefixingInterceptor {
+        fn before_command<'a>(
+            &'a self,
+            cmd: &'a mut redis::Cmd,
+            routing: &'a mut Option<RoutingInfo>,
+        ) -> redis::RedisFuture<'a, ()> {
+            Box::pin(async move {
+                let mut prefixed = redis::Cmd::new();
+                for (index, arg) in cmd.args_iter().enumerate() {
+                    if let redis::Arg::Simple(bytes) = arg {
+                        if index == 1 {
+                            prefixed.arg([self.prefix.as_bytes(), bytes].concat());
+                        } else {
+                            prefixed.arg(bytes);
+                        }
+                    }
+                }
+                prefixed.set_span(cmd.span());
+                *cmd = prefixed;
+                if self.routing.is_some() {
+                    *routing = self.routing.clone();
+                }
+                Ok(())
+            })
+        }
+
+        fn after_response<'a>(
+            &'a self,
+            _cmd: &'a redis::Cmd,
+            result: redis::RedisResult<Value>,
+        ) -> redis::RedisFuture<'a, Value> {
+            Box::pin(async move {
+                match result? {
+                    Value::BulkString(bytes) => Ok(Value::Int(bytes.len() as i64)),
+                    value => Ok(value),
+                }
+            })
+        }
+    }
+
+    /// Fails every command it intercepts.
+    #[derive(Debug)]
+    struct RejectingInterceptor;
+
+    impl glide_core::client::CommandInterceptor for RejectingInterceptor {
+        fn before_command<'a>(
+            &'a self,
+            _cmd: &'a mut redis::Cmd,
+            _routing: &'a mut Option<RoutingInfo>,
+        ) -> redis::RedisFuture<'a, ()> {
+            Box::pin(async {
+                Err(redis::RedisError::from((
+                    redis::ErrorKind::ClientError,
+                    "intercepted",
+                )))
+            })
+        }
+    }
+
+    fn create_interceptor_mock() -> utilities::mocks::ServerMock {
+        let mut responses = HashMap::new();
+        responses.insert(
+            "*2\r\n$4\r\nINFO\r\n$11\r\nREPLICATION\r\n".to_string(),
+            Value::BulkString(b"role:master\r\nconnected_slaves:0\r\n".to_vec()),
+        );
+        utilities::mocks::ServerMock::new(responses)
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_send_command_runs_the_interceptors() {
+        use utilities::mocks::Mock;
+        let mock = create_interceptor_mock();
+        let mut request: glide_core::client::ConnectionRequest =
+            create_connection_request(mock.get_addresses().as_slice(), &Default::default()).into();
+        request.interceptors = vec![std::sync::Arc::new(PrefixingInterceptor {
+            prefix: "app:",
+            routing: None,
+        })];
+        // The server receives the prefixed key.
+        mock.add_response(cmd("GET").arg("app:key"), "$5\r\nvalue\r\n".to_string());
+
+        block_on_all(async move {
+            let mut client = Client::new(request, None).await.unwrap();
+            let result = client
+                .send_command(cmd("GET").arg("key"), None)
+                .await
+                .unwrap();
+            assert_eq!(result, Value::Int(5));
+            assert_eq!(mock.get_number_of_received_commands(), 1);
+        });
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_pipelines_and_scripts_are_not_intercepted() {
+        use utilities::mocks::Mock;
+        let mock = create_interceptor_mock();
+        let mut request: glide_core::client::ConnectionRequest =
+            create_connection_request(mock.get_addresses().as_slice(), &Default::default()).into();
+        request.interceptors = vec![std::sync::Arc::new(RejectingInterceptor)];
+        mock.add_response(cmd("GET").arg("key"), "$5\r\nvalue\r\n".to_string());
+        mock.add_response(
+            cmd("EVALSHA").arg("hash").arg(1).arg("key"),
+            ":1\r\n".to_string(),
+        );
+
+        block_on_all(async move {
+            let mut client = Client::new(request, None).await.unwrap();
+            let err = client
+                .send_command(cmd("GET").arg("key"), None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::ClientError);
+
+            let mut pipeline = Pipeline::new();
+            pipeline.get("key");
+            let result = client
+                .send_pipeline(
+                    &pipeline,
+                    None,
+                    true,
+                    None,
+                    PipelineRetryStrategy {
+                        retry_server_error: false,
+                        retry_connection_error: false,
+                    },
+                )
+                .await
+                .unwrap();
+            assert_eq!(
+                result,
+                Value::Array(vec![Value::BulkString(b"value".to_vec())])
+            );
+
+            let result = client
+                .invoke_script("hash", &vec![b"key".as_slice()], &vec![], None)
+                .await
+                .unwrap();
+            assert_eq!(result, Value::Int(1));
+            assert_eq!(mock.get_number_of_received_commands(), 2);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_send_command_uses_the_routing_of_the_interceptors() {
+        block_on_all(async move {
+            let cluster = get_shared_cluster_addresses(false);
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                &cluster,
+                &TestConfiguration {
+                    cluster_mode: ClusterMode::Enabled,
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .into();
+            request.interceptors = vec![std::sync::Arc::new(PrefixingInterceptor {
+                prefix: "",
+                routing: Some(RoutingInfo::MultiNode((
+                    MultipleNodeRoutingInfo::AllMasters,
+                    None,
+                ))),
+            })];
+            let mut client = Client::new(request, None).await.unwrap();
+
+            // Routed to every primary, ECHO answers with a reply per node.
+            let result = client
+                .send_command(cmd("ECHO").arg("hello"), None)
+                .await
+                .unwrap();
+            let Value::Map(replies) = result else {
+                panic!("Expected a reply per primary, got {result:?}");
+            };
+            assert!(replies.len() > 1);
+            assert!(
+                replies
+                    .iter()
+                    .all(|(_, reply)| *reply == Value::BulkString(b"hello".to_vec()))
+            );
+        });
+    }
 }
@@ -52,6 +52,52 @@ mod cluster_client_tests {
             })
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_detect_config_drift() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics_internal(TestConfiguration {
+                cluster_mode: ClusterMode::Enabled,
+                shared_server: false,
+                ..Default::default()
+            })
+            .await;
+
+            let report = glide_core::config_drift::detect_config_drift(
+                &mut test_basics.client,
+                &["timeout"],
+            )
+            .await
+            .unwrap();
+            assert!(report.is_consistent());
+            assert_eq!(report.nodes.len(), 3);
+
+            let mut cmd = redis::cmd("CONFIG");
+            cmd.arg("SET").arg("timeout").arg("123");
+            test_basics
+                .client
+                .send_command(
+                    &mut cmd,
+                    Some(RoutingInfo::SingleNode(
+                        SingleNodeRoutingInfo::SpecificNode(Route::new(0, SlotAddr::Master)),
+                    )),
+                )
+                .await
+                .unwrap();
+
+            let report = glide_core::config_drift::detect_config_drift(
+                &mut test_basics.client,
+                &["timeout"],
+            )
+            .await
+            .unwrap();
+            assert_eq!(report.mismatches.len(), 1);
+            assert_eq!(report.mismatches[0].parameter, "timeout");
+            assert_eq!(report.mismatches[0].outliers().len(), 1);
+        });
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_send_routing_no_provided_route() {
@@ -1183,9 +1183,19 @@ mod compression_tests {
             Some(RequestType::Append)
         ));
 
+        // Test hash commands
+        assert!(matches!(
+            RequestType::from_command_name("hset"),
+            Some(RequestType::HSet)
+        ));
+        assert!(matches!(
+            RequestType::from_command_name("HGETALL"),
+            Some(RequestType::HGetAll)
+        ));
+
         // Test unknown commands return None
         assert!(RequestType::from_command_name("UNKNOWN").is_none());
-        assert!(RequestType::from_command_name("HSET").is_none());
+        assert!(RequestType::from_command_name("HDEL").is_none());
         assert!(RequestType::from_command_name("LPUSH").is_none());
         assert!(RequestType::from_command_name("ZADD").is_none());
         assert!(RequestType::from_command_name("").is_none());
@@ -1500,4 +1510,80 @@ mod compression_tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), test_data_bytes);
     }
+
+    #[test]
+    fn test_hash_values_are_compressed_and_decompressed() {
+        use glide_core::compression::zstd_backend::ZstdBackend;
+        use redis::Value;
+
+        let backend = Box::new(ZstdBackend::new());
+        let config =
+            CompressionConfig::new(CompressionBackendType::Zstd).with_min_compression_size(64);
+        let manager = CompressionManager::new(backend, config).unwrap();
+        let large_value = "A".repeat(200).into_bytes();
+
+        // HSET key field1 value1 field2 value2 - only the values are compressed
+        let mut args = vec![
+            b"key".to_vec(),
+            b"field1".to_vec(),
+            large_value.clone(),
+            b"field2".to_vec(),
+            b"small".to_vec(),
+        ];
+        process_command_args_for_compression(&mut args, RequestType::HSet, Some(&manager)).unwrap();
+        assert_eq!(args[0], b"key");
+        assert_eq!(args[1], b"field1");
+        assert!(has_magic_header(&args[2]));
+        assert_eq!(args[3], b"field2");
+        assert_eq!(args[4], b"small");
+        let compressed = args[2].clone();
+
+        // RESP3 HGETALL returns a map
+        let response = Value::Map(vec![(
+            Value::BulkString(b"field1".to_vec()),
+            Value::BulkString(compressed.clone()),
+        )]);
+        let decompressed =
+            process_response_for_decompression(response, RequestType::HGetAll, Some(&manager))
+                .unwrap();
+        assert_eq!(
+            decompressed,
+            Value::Map(vec![(
+                Value::BulkString(b"field1".to_vec()),
+                Value::BulkString(large_value.clone()),
+            )])
+        );
+
+        // RESP2 HGETALL returns a flat array of fields and values
+        let response = Value::Array(vec![
+            Value::BulkString(b"field1".to_vec()),
+            Value::BulkString(compressed.clone()),
+        ]);
+        let decompressed =
+            process_response_for_decompression(response, RequestType::HGetAll, Some(&manager))
+                .unwrap();
+        assert_eq!(
+            decompressed,
+            Value::Array(vec![
+                Value::BulkString(b"field1".to_vec()),
+                Value::BulkString(large_value.clone()),
+            ])
+        );
+
+        let response = Value::Array(vec![Value::BulkString(compressed), Value::Nil]);
+        let decompressed =
+            process_response_for_decompression(response, RequestType::HMGet, Some(&manager))
+                .unwrap();
+        assert_eq!(
+            decompressed,
+            Value::Array(vec![Value::BulkString(large_value), Value::Nil])
+        );
+
+        let result =
+            validate_command_compression_compatibility(RequestType::HIncrBy, Some(&manager));
+        assert!(matches!(
+            result,
+            Err(CompressionError::IncompatibleCommand { .. })
+        ));
+    }
 }
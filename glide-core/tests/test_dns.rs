@@ -15,10 +15,12 @@ mod dns_tests {
     };
     use glide_core::{
         client::{Client, StandaloneClient},
-        connection_request::TlsMode,
+        connection_request::{DnsConfig, DnsResolverType, TlsMode},
     };
     use once_cell::sync::Lazy;
     use rstest::rstest;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
 
     // Shared temp directory and TLS paths for all DNS tests
     static TLS_TEMPDIR: Lazy<tempfile::TempDir> =
@@ -245,4 +247,99 @@ mod dns_tests {
             assert!(result.is_none());
         });
     }
+
+    // ==================== Re-resolution Tests ====================
+
+    /// A host name answered by the fake name server.
+    const FAKE_HOSTNAME: &str = "valkey.glide.test";
+
+    /// Answers the `A` query in `query` with `127.0.0.1` and a TTL of 0, so that resolvers don't
+    /// cache it, and any other query with no records. Returns `None` for malformed queries.
+    fn fake_dns_response(query: &[u8]) -> Option<(Vec<u8>, bool)> {
+        // The question follows the 12 bytes header, and ends with its type and class
+        let mut name_end = 12;
+        while *query.get(name_end)? != 0 {
+            name_end += usize::from(query[name_end]) + 1;
+        }
+        let question = query.get(12..name_end + 5)?;
+        let is_a_query = question[question.len() - 4..question.len() - 2] == [0, 1];
+        let mut response = query[..2].to_vec();
+        response.extend([0x81, 0x80, 0, 1, 0, u8::from(is_a_query), 0, 0, 0, 0]);
+        response.extend(question);
+        if is_a_query {
+            // A pointer to the question's name, type A, class IN, TTL 0, and the address
+            response.extend([0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 0, 0, 4, 127, 0, 0, 1]);
+        }
+        Some((response, is_a_query))
+    }
+
+    /// Starts a name server that resolves any host name to `127.0.0.1`. Returns its address, and
+    /// the number of `A` queries it answered.
+    fn start_fake_name_server() -> (std::net::SocketAddr, Arc<AtomicUsize>) {
+        let socket = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let address = socket.local_addr().unwrap();
+        let lookups = Arc::new(AtomicUsize::new(0));
+        let counter = lookups.clone();
+        std::thread::spawn(move || {
+            let mut buffer = [0; 512];
+            while let Ok((len, peer)) = socket.recv_from(&mut buffer) {
+                let Some((response, is_a_query)) = fake_dns_response(&buffer[..len]) else {
+                    continue;
+                };
+                if is_a_query {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                }
+                let _ = socket.send_to(&response, peer);
+            }
+        });
+        (address, lookups)
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_host_is_resolved_again_on_reconnect() {
+        block_on_all(async move {
+            let (name_server, lookups) = start_fake_name_server();
+            let server = RedisServer::new(ServerType::Tcp { tls: false });
+            let port = extract_port(&server.get_client_addr());
+            let addr = redis::ConnectionAddr::Tcp(FAKE_HOSTNAME.to_string(), port);
+            let mut connection_request = create_connection_request(
+                &[addr],
+                &TestConfiguration {
+                    shared_server: false,
+                    ..Default::default()
+                },
+            );
+            connection_request.dns = Some(DnsConfig {
+                resolver: DnsResolverType::AsyncResolver.into(),
+                name_servers: vec![name_server.to_string().into()],
+                ..Default::default()
+            })
+            .into();
+
+            // Wait to ensure server is ready before connecting.
+            tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+
+            let mut client = Client::new(connection_request.into(), None)
+                .await
+                .expect("Failed to connect");
+            assert_connected(&mut client).await;
+            let lookups_before_reconnect = lookups.load(Ordering::SeqCst);
+            assert!(lookups_before_reconnect > 0);
+
+            kill_connection(&mut client).await;
+            retry(|| async {
+                let mut client = client.clone();
+                client
+                    .send_command(&mut redis::cmd("PING"), None)
+                    .await
+                    .ok()
+            })
+            .await;
+
+            // The reconnection resolved the host name again, instead of reusing the old addresses
+            assert!(lookups.load(Ordering::SeqCst) > lookups_before_reconnect);
+        });
+    }
 }
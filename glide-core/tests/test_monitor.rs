@@ -23,6 +23,7 @@ mod test_monitor {
             lib_name: None,
             cache: None,
             server_assisted_cache: false,
+            protocol_fallback: false,
         }
     }
 
@@ -7,6 +7,7 @@ mod constants;
 mod utilities;
 
 use glide_core::client::GlideClientForTests;
+use glide_core::client::keyspace_events::KeyspaceEventClass;
 use redis::PubSubSubscriptionKind;
 use rstest::rstest;
 use std::collections::HashSet;
@@ -784,3 +785,73 @@ fn test_reset_does_not_resubscribe_after_reconnect() {
         );
     });
 }
+
+/// Waits until `pattern` is subscribed on `primaries` nodes.
+async fn wait_for_pattern_on_primaries(
+    setup: &PubSubTestSetup,
+    pattern: &[u8],
+    primaries: usize,
+) -> bool {
+    let deadline = tokio::time::Instant::now() + RESUBSCRIPTION_TIMEOUT;
+    loop {
+        let subscribed = setup
+            .get_subscriptions_by_address()
+            .values()
+            .filter(|subs| {
+                subs.get(&PubSubSubscriptionKind::Pattern)
+                    .is_some_and(|patterns| patterns.contains(pattern))
+            })
+            .count();
+        if subscribed >= primaries {
+            return true;
+        }
+        if tokio::time::Instant::now() > deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+}
+
+/// Keyspace event patterns are subscribed on every primary, and restored on every primary
+/// after the subscriptions are lost.
+#[rstest]
+#[serial_test::serial]
+#[timeout(LONG_CLUSTER_TEST_TIMEOUT)]
+fn test_keyspace_event_subscriptions_are_restored_on_all_primaries() {
+    block_on_all(async {
+        let cluster = RedisCluster::new(false, &None, Some(3), Some(0));
+        let addresses = cluster.get_server_addresses();
+        let mut setup = PubSubTestSetup::new(&addresses).await;
+        let pattern = b"__keyevent@0__:*".to_vec();
+
+        setup
+            .glide_client
+            .subscribe_keyspace_events(&[], &[KeyspaceEventClass::Generic])
+            .await
+            .expect("Failed to subscribe to keyspace events");
+        assert!(
+            wait_for_pattern_on_primaries(&setup, &pattern, 3).await,
+            "keyspace events should be subscribed on all primaries"
+        );
+
+        // Drop the subscriptions of all the nodes
+        let mut kill_cmd = redis::cmd("CLIENT");
+        kill_cmd.arg("KILL").arg("TYPE").arg("PUBSUB");
+        let _ = setup
+            .connection
+            .send_command(
+                &mut kill_cmd,
+                Some(redis::cluster_routing::RoutingInfo::MultiNode((
+                    redis::cluster_routing::MultipleNodeRoutingInfo::AllNodes,
+                    Some(redis::cluster_routing::ResponsePolicy::AllSucceeded),
+                ))),
+            )
+            .await;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        assert!(
+            wait_for_pattern_on_primaries(&setup, &pattern, 3).await,
+            "keyspace events should be resubscribed on all primaries"
+        );
+    });
+}
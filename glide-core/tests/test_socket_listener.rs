@@ -25,8 +25,12 @@ mod socket_listener {
 
     use super::*;
     use command_request::{CommandRequest, RequestType};
+    use glide_core::command_request::InlineCommand;
     use glide_core::command_request::command::{Args, ArgsArray};
-    use glide_core::command_request::{Batch, Command};
+    use glide_core::command_request::{
+        Batch, CloseClients, Command, ConfigureLogger, CreateClient, GetCommandMetadata, Heartbeat,
+        ListClients, LogFile, LogSizeRotation, ValidateConnectionRequest, log_file,
+    };
     use glide_core::response::{ConstantResponse, Response, response};
     use glide_core::scripts_container::add_script;
     use protobuf::{EnumOrUnknown, Message};
@@ -397,12 +401,7 @@ mod socket_listener {
         assert_ok_response(&mut buffer, &mut socket, CALLBACK_INDEX);
     }
 
-    fn setup_socket(
-        use_tls: Tls,
-        socket_path: Option<String>,
-        addresses: &[ConnectionAddr],
-        cluster_mode: ClusterMode,
-    ) -> UnixStream {
+    fn start_listener(socket_path: Option<String>) -> String {
         let socket_listener_state: Arc<ManualResetEvent> =
             Arc::new(ManualResetEvent::new(EventState::Unset));
         let cloned_state = socket_listener_state.clone();
@@ -419,19 +418,32 @@ mod socket_listener {
         );
         socket_listener_state.wait();
         let path = path_arc.lock().unwrap();
-        let path = path.as_ref().expect("Didn't get any socket path");
+        path.clone().expect("Didn't get any socket path")
+    }
+
+    fn setup_socket(
+        use_tls: Tls,
+        socket_path: Option<String>,
+        addresses: &[ConnectionAddr],
+        cluster_mode: ClusterMode,
+    ) -> UnixStream {
+        let path = start_listener(socket_path);
         let socket = std::os::unix::net::UnixStream::connect(path).unwrap();
         connect_to_redis(addresses, &socket, use_tls, cluster_mode);
         socket
     }
 
-    fn setup_mocked_test_basics(socket_path: Option<String>) -> ServerTestBasicsWithMock {
+    fn create_primary_mock() -> ServerMock {
         let mut responses = std::collections::HashMap::new();
         responses.insert(
             "*2\r\n$4\r\nINFO\r\n$11\r\nREPLICATION\r\n".to_string(),
             Value::BulkString(b"role:master\r\nconnected_slaves:0\r\n".to_vec()),
         );
-        let server_mock = ServerMock::new(responses);
+        ServerMock::new(responses)
+    }
+
+    fn setup_mocked_test_basics(socket_path: Option<String>) -> ServerTestBasicsWithMock {
+        let server_mock = create_primary_mock();
         let addresses = server_mock.get_addresses();
         let socket = setup_socket(
             Tls::NoTls,
@@ -733,6 +745,36 @@ mod socket_listener {
         }
     }
 
+    #[rstest]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_socket_route_to_all_primaries_with_aggregation() {
+        let mut test_basics = setup_cluster_test_basics(Tls::NoTls, TestServer::Shared);
+
+        const CALLBACK1_INDEX: u32 = 100;
+        let approx_message_length = 4 + APPROX_RESP_HEADER_LEN;
+        let mut buffer = Vec::with_capacity(approx_message_length);
+        let mut request = get_command_request(
+            CALLBACK1_INDEX,
+            vec!["ECHO".to_string().into(), "foo".to_string().into()],
+            RequestType::CustomCommand.into(),
+            false,
+        );
+        let mut routes = command_request::Routes::default();
+        routes.set_simple_routes(command_request::SimpleRoutes::AllPrimaries);
+        routes.aggregation = command_request::ResponseAggregation::FirstSucceeded.into();
+        request.route = Some(routes).into();
+        write_request(&mut buffer, &mut test_basics.socket, request);
+
+        let response = get_response(&mut buffer, Some(&mut test_basics.socket));
+
+        assert_eq!(response.callback_idx, CALLBACK1_INDEX);
+        let Some(response::Value::RespPointer(pointer)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        let received_value = pointer_to_value(pointer);
+        assert_eq!(*received_value, Value::BulkString(b"foo".to_vec()));
+    }
+
     #[rstest]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
     fn test_socket_cluster_route_by_address_reaches_correct_node() {
@@ -827,6 +869,397 @@ mod socket_listener {
         assert_null_response(&mut buffer, &mut test_basics.socket, CALLBACK_INDEX);
     }
 
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_multiple_clients_on_one_connection() {
+        const CLIENT_ID: u32 = 7;
+        let mut test_basics = setup_mocked_test_basics(None);
+        let second_server_mock = create_primary_mock();
+        let mut buffer = Vec::new();
+
+        // Create a second client, connected to the second server
+        let connection_request = create_connection_request(
+            second_server_mock.get_addresses().as_slice(),
+            &TestConfiguration {
+                request_timeout: Some(REQUEST_TIMEOUT_MS),
+                ..Default::default()
+            },
+        );
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.client_id = CLIENT_ID;
+        request.command = Some(command_request::command_request::Command::CreateClient(
+            CreateClient {
+                connection_request: connection_request.write_to_bytes().unwrap().into(),
+                ..Default::default()
+            },
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = assert_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            1,
+            Some(Value::Okay),
+            ResponseType::Value,
+        );
+        assert_eq!(response.client_id, CLIENT_ID);
+
+        // Requests with the new client id are sent through the second client
+        let key = generate_random_string(KEY_LENGTH);
+        let mut expected_command = Cmd::new();
+        expected_command.arg("GET").arg(key.clone());
+        second_server_mock.add_response(&expected_command, "$3\r\nfoo\r\n".to_string());
+        let mut request =
+            get_command_request(2, vec![key.clone().into()], RequestType::Get.into(), false);
+        request.client_id = CLIENT_ID;
+        buffer.clear();
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = assert_value_response(
+            &mut buffer,
+            Some(&mut test_basics.socket),
+            2,
+            Value::BulkString(b"foo".to_vec()),
+        );
+        assert_eq!(response.client_id, CLIENT_ID);
+        assert_eq!(second_server_mock.get_number_of_received_commands(), 1);
+        assert_eq!(test_basics.server_mock.get_number_of_received_commands(), 0);
+
+        // After the client is closed, its id is no longer valid
+        let mut request = CommandRequest::new();
+        request.callback_idx = 3;
+        request.client_id = CLIENT_ID;
+        request.command = Some(command_request::command_request::Command::CloseClient(
+            Default::default(),
+        ));
+        buffer.clear();
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_ok_response(&mut buffer, &mut test_basics.socket, 3);
+
+        let mut request = get_command_request(4, vec![key.into()], RequestType::Get.into(), false);
+        request.client_id = CLIENT_ID;
+        buffer.clear();
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_error_response(
+            &mut buffer,
+            &mut test_basics.socket,
+            4,
+            ResponseType::RequestError,
+        );
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_validate_connection_request() {
+        let mut test_basics = setup_mocked_test_basics(None);
+        let mut buffer = Vec::new();
+
+        let mut connection_request = create_connection_request(
+            test_basics.server_mock.get_addresses().as_slice(),
+            &TestConfiguration::default(),
+        );
+        connection_request.read_from = glide_core::connection_request::ReadFrom::AZAffinity.into();
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.command = Some(
+            command_request::command_request::Command::ValidateConnectionRequest(
+                ValidateConnectionRequest {
+                    connection_request: connection_request.write_to_bytes().unwrap().into(),
+                    ..Default::default()
+                },
+            ),
+        );
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = get_response(&mut buffer, Some(&mut test_basics.socket));
+        assert_eq!(response.callback_idx, 1);
+        let Some(response::Value::RespPointer(pointer)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        let Value::Array(errors) = *pointer_to_value(pointer) else {
+            panic!("Expected an array of validation errors");
+        };
+        assert_eq!(errors.len(), 1);
+        let Value::Map(error) = &errors[0] else {
+            panic!("Unexpected validation error {:?}", errors[0]);
+        };
+        assert_eq!(
+            error[0],
+            (
+                Value::SimpleString("field".to_string()),
+                Value::SimpleString("client_az".to_string())
+            )
+        );
+        // Validation doesn't connect to the servers
+        assert_eq!(test_basics.server_mock.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_get_command_metadata() {
+        let mut test_basics = setup_mocked_test_basics(None);
+        let mut buffer = Vec::new();
+
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.command = Some(
+            command_request::command_request::Command::GetCommandMetadata(GetCommandMetadata {
+                commands: vec!["config get".into(), "NOT-A-COMMAND".into()],
+                ..Default::default()
+            }),
+        );
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = get_response(&mut buffer, Some(&mut test_basics.socket));
+        assert_eq!(response.callback_idx, 1);
+        let Some(response::Value::RespPointer(pointer)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        let Value::Array(metadata) = *pointer_to_value(pointer) else {
+            panic!("Expected an array of command metadata");
+        };
+        assert_eq!(metadata.len(), 2);
+        let Value::Map(config_get) = &metadata[0] else {
+            panic!("Unexpected command metadata {:?}", metadata[0]);
+        };
+        assert!(config_get.contains(&(
+            Value::SimpleString("command".to_string()),
+            Value::BulkString(b"CONFIG GET".to_vec())
+        )));
+        assert_eq!(metadata[1], Value::Nil);
+        // The metadata is known without asking the servers
+        assert_eq!(test_basics.server_mock.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_configure_logger_rejects_invalid_file_rotation() {
+        let mut test_basics = setup_mocked_test_basics(None);
+        let mut buffer = Vec::new();
+
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.command = Some(command_request::command_request::Command::ConfigureLogger(
+            ConfigureLogger {
+                file: Some(LogFile {
+                    file_name: "glide.log".into(),
+                    rotation: Some(log_file::Rotation::Size(LogSizeRotation {
+                        max_bytes: 0,
+                        max_files: 3,
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                })
+                .into(),
+                ..Default::default()
+            },
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = get_response(&mut buffer, Some(&mut test_basics.socket));
+        assert_eq!(response.callback_idx, 1);
+        let Some(response::Value::RequestError(error)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        assert_eq!(&*error.message, "The log file size limit must be positive");
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_rejects_inline_command_with_a_request_type() {
+        let mut test_basics = setup_mocked_test_basics(None);
+        let mut buffer = Vec::new();
+
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.command = Some(command_request::command_request::Command::SingleCommand(
+            Command {
+                request_type: RequestType::Get.into(),
+                args: Some(Args::InlineCommand(InlineCommand {
+                    text: "GET key".into(),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            },
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        let response = get_response(&mut buffer, Some(&mut test_basics.socket));
+        assert_eq!(response.callback_idx, 1);
+        let Some(response::Value::RequestError(error)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        assert_eq!(
+            &*error.message,
+            "Inline commands must use the CustomCommand request type"
+        );
+        assert_eq!(test_basics.server_mock.get_number_of_received_commands(), 0);
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_rejects_a_runtime_mode() {
+        let server_mock = create_primary_mock();
+        let mut socket = UnixStream::connect(start_listener(None)).unwrap();
+        let mut connection_request = create_connection_request(
+            server_mock.get_addresses().as_slice(),
+            &TestConfiguration::default(),
+        );
+        connection_request.runtime_mode = connection_request::RuntimeMode::Shared.into();
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, connection_request);
+        socket.write_all(&buffer).unwrap();
+        let response = assert_error_response(
+            &mut buffer,
+            &mut socket,
+            u32::MAX,
+            ResponseType::ClosingError,
+        );
+        assert!(
+            response
+                .closing_error()
+                .contains("The runtime mode isn't supported by the socket listener"),
+            "Received {:?}",
+            response.closing_error()
+        );
+        assert_eq!(server_mock.get_number_of_received_commands(), 0);
+    }
+
+    /// Returns the registry ids of the listed clients that connect to `address`.
+    fn list_registry_ids(
+        buffer: &mut Vec<u8>,
+        socket: &mut UnixStream,
+        callback_idx: u32,
+        address: &str,
+    ) -> Vec<u64> {
+        let mut request = CommandRequest::new();
+        request.callback_idx = callback_idx;
+        request.command = Some(command_request::command_request::Command::ListClients(
+            ListClients::default(),
+        ));
+        buffer.clear();
+        write_request(buffer, socket, request);
+        let response = get_response(buffer, Some(socket));
+        assert_eq!(response.callback_idx, callback_idx);
+        let Some(response::Value::RespPointer(pointer)) = response.value else {
+            panic!("Unexpected response {:?}", response.value);
+        };
+        let Value::Array(clients) = *pointer_to_value(pointer) else {
+            panic!("Expected an array of clients");
+        };
+        // The clients of other tests in this process are listed too
+        let expected_addresses = Value::Array(vec![Value::BulkString(address.into())]);
+        clients
+            .into_iter()
+            .filter_map(|client| {
+                let Value::Map(fields) = client else {
+                    panic!("Unexpected client {client:?}");
+                };
+                let field = |name: &str| {
+                    fields
+                        .iter()
+                        .find(|(key, _)| *key == Value::SimpleString(name.to_string()))
+                        .map(|(_, value)| value.clone())
+                        .unwrap()
+                };
+                let Value::Int(id) = field("id") else {
+                    panic!("Unexpected client id");
+                };
+                (field("addresses") == expected_addresses).then_some(id as u64)
+            })
+            .collect()
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_list_and_close_clients() {
+        let mut test_basics = setup_mocked_test_basics(None);
+        let second_server_mock = create_primary_mock();
+        let second_address = second_server_mock.get_addresses()[0].to_string();
+        let mut buffer = Vec::new();
+
+        let connection_request = create_connection_request(
+            second_server_mock.get_addresses().as_slice(),
+            &TestConfiguration::default(),
+        );
+        let mut request = CommandRequest::new();
+        request.callback_idx = 1;
+        request.client_id = 7;
+        request.command = Some(command_request::command_request::Command::CreateClient(
+            CreateClient {
+                connection_request: connection_request.write_to_bytes().unwrap().into(),
+                ..Default::default()
+            },
+        ));
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_ok_response(&mut buffer, &mut test_basics.socket, 1);
+
+        let registry_ids =
+            list_registry_ids(&mut buffer, &mut test_basics.socket, 2, &second_address);
+        assert_eq!(registry_ids.len(), 1);
+
+        let mut request = CommandRequest::new();
+        request.callback_idx = 3;
+        request.command = Some(command_request::command_request::Command::CloseClients(
+            CloseClients {
+                registry_ids,
+                ..Default::default()
+            },
+        ));
+        buffer.clear();
+        write_request(&mut buffer, &mut test_basics.socket, request);
+        assert_value_response(&mut buffer, Some(&mut test_basics.socket), 3, Value::Int(1));
+
+        // The closed client is no longer registered, and the socket's default client still is
+        assert!(
+            list_registry_ids(&mut buffer, &mut test_basics.socket, 4, &second_address).is_empty()
+        );
+        let address = test_basics.server_mock.get_addresses()[0].to_string();
+        assert_eq!(
+            list_registry_ids(&mut buffer, &mut test_basics.socket, 5, &address).len(),
+            1
+        );
+    }
+
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_socket_closed_when_heartbeats_are_not_answered() {
+        let server_mock = create_primary_mock();
+        let mut socket = UnixStream::connect(start_listener(None)).unwrap();
+        let mut connection_request = create_connection_request(
+            server_mock.get_addresses().as_slice(),
+            &TestConfiguration::default(),
+        );
+        connection_request.socket_heartbeat = Some(connection_request::SocketHeartbeatConfig {
+            interval_ms: 50,
+            timeout_ms: 200,
+            ..Default::default()
+        })
+        .into();
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, connection_request);
+        socket.write_all(&buffer).unwrap();
+        assert_ok_response(&mut buffer, &mut socket, 0);
+
+        // Answering the heartbeats keeps the socket open past the timeout
+        for _ in 0..8 {
+            let response = get_response(&mut buffer, Some(&mut socket));
+            assert!(response.is_heartbeat, "Received {response:?}");
+            let mut request = CommandRequest::new();
+            request.command = Some(command_request::command_request::Command::Heartbeat(
+                Heartbeat::default(),
+            ));
+            buffer.clear();
+            write_request(&mut buffer, &mut socket, request);
+        }
+
+        // Once the heartbeats aren't answered, the socket is closed
+        socket
+            .set_read_timeout(Some(std::time::Duration::from_secs(5)))
+            .unwrap();
+        loop {
+            buffer.resize(300, 0_u8);
+            if socket.read(&mut buffer).unwrap() == 0 {
+                break;
+            }
+        }
+    }
+
     #[rstest]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
     fn test_socket_report_error() {
@@ -638,6 +638,50 @@ mod standalone_client_tests {
         }
     }
 
+    #[rstest]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_resp2_fallback_normalizes_responses() {
+        let mock = ServerMock::new_resp2_only(create_primary_responses());
+        let mut connection_request =
+            create_connection_request(mock.get_addresses().as_slice(), &Default::default());
+        connection_request.protocol = ProtocolVersion::RESP3.into();
+
+        block_on_all(async {
+            // Without the fallback, a server that predates RESP3 fails the connection.
+            let client_res = GlideClient::new(connection_request.clone().into(), None).await;
+            assert!(client_res.is_err());
+        });
+
+        let mock = ServerMock::new_resp2_only(create_primary_responses());
+        let mut hgetall = redis::cmd("HGETALL");
+        hgetall.arg("hash");
+        let mut zscore = redis::cmd("ZSCORE");
+        zscore.arg("zset").arg("member");
+        mock.add_response(&hgetall, "*2\r\n$5\r\nfield\r\n$5\r\nvalue\r\n".to_string());
+        mock.add_response(&zscore, "$3\r\n1.5\r\n".to_string());
+        connection_request.addresses = mock.get_addresses().iter().map(get_address_info).collect();
+        connection_request.protocol_fallback = Some(true);
+
+        block_on_all(async {
+            let mut client = GlideClient::new(connection_request.into(), None)
+                .await
+                .unwrap();
+
+            // The RESP2 responses have the shapes RESP3 responses have.
+            let result = client.send_command(&mut hgetall, None).await.unwrap();
+            assert_eq!(
+                result,
+                Value::Map(vec![(
+                    Value::BulkString(b"field".to_vec()),
+                    Value::BulkString(b"value".to_vec())
+                )])
+            );
+            let result = client.send_command(&mut zscore, None).await.unwrap();
+            assert_eq!(result, Value::Double(1.5));
+        });
+        assert_eq!(mock.get_number_of_received_commands(), 2);
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
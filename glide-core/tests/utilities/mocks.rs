@@ -76,6 +76,7 @@ fn receive_and_respond_to_next_message(
     received_commands: &Arc<AtomicU16>,
     constant_responses: &HashMap<String, Value>,
     closing_signal: &Arc<ManualResetEvent>,
+    supports_hello: bool,
 ) -> bool {
     let mut buffer = vec![0; 1024];
     let size = match read_from_socket(&mut buffer, socket, closing_signal) {
@@ -97,6 +98,13 @@ fn receive_and_respond_to_next_message(
         return true;
     }
 
+    if message.contains("HELLO") && !supports_hello {
+        socket
+            .write_all(b"-ERR unknown command `HELLO`, with args beginning with: `3`\r\n")
+            .unwrap();
+        return true;
+    }
+
     if message.contains("HELLO") {
         let mut buffer = Vec::new();
         let response = Value::Map(vec![
@@ -143,6 +151,23 @@ impl ServerMock {
     pub fn new_with_listener(
         constant_responses: HashMap<String, Value>,
         listener: TcpListener,
+    ) -> Self {
+        Self::start(constant_responses, listener, true)
+    }
+
+    /// Creates a mock of a server that predates RESP3, and fails `HELLO`.
+    pub fn new_resp2_only(constant_responses: HashMap<String, Value>) -> Self {
+        Self::start(
+            constant_responses,
+            super::get_listener_on_available_port(),
+            false,
+        )
+    }
+
+    fn start(
+        constant_responses: HashMap<String, Value>,
+        listener: TcpListener,
+        supports_hello: bool,
     ) -> Self {
         let (request_sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
         let received_commands = Arc::new(AtomicU16::new(0));
@@ -167,6 +192,7 @@ impl ServerMock {
                 &received_commands_clone,
                 &constant_responses,
                 &closing_signal_clone,
+                supports_hello,
             ) {}
 
             // Terminate the connection
@@ -764,6 +764,9 @@ pub fn create_connection_request(
 
     connection_request.client_side_cache =
         protobuf::MessageField::from_option(configuration.client_side_cache.clone());
+    connection_request.get_batching_window_us = configuration.get_batching_window_us;
+    connection_request.health_check =
+        protobuf::MessageField::from_option(configuration.health_check.clone());
 
     connection_request
 }
@@ -785,6 +788,8 @@ pub struct TestConfiguration {
     pub client_side_cache: Option<connection_request::ClientSideCache>,
     /// Skip ACL setup when creating a cluster client (use when ACL is already configured).
     pub skip_acl_setup: bool,
+    pub get_batching_window_us: Option<u32>,
+    pub health_check: Option<connection_request::HealthCheckConfig>,
 }
 
 pub(crate) async fn setup_test_basics_internal(configuration: &TestConfiguration) -> TestBasics {
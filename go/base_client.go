@@ -10190,6 +10190,22 @@ func (client *baseClient) Watch(ctx context.Context, keys []string) (string, err
 //	  - compression_skipped_count: Number of times compression was skipped
 //	  - subscription_out_of_sync_count: Number of times subscriptions were out of sync during reconciliation
 //	  - subscription_last_sync_timestamp: Timestamp of last successful subscription sync (milliseconds since epoch)
+//	  - latency_breakdown_samples: Number of commands whose latency breakdown was recorded
+//	  - total_queue_time_us: Total time (in microseconds) commands spent queued in the client before being sent
+//	  - total_network_time_us: Total time (in microseconds) between sending commands and receiving their responses
+//	  - total_decode_time_us: Total time (in microseconds) spent decoding responses
+//	  - hot_key_warnings: Number of times a key was detected as hot
+//	  - memory_budget_bytes_in_use: Bytes currently held by in-flight requests and undelivered responses of clients with a memory budget
+//	  - memory_budget_rejections: Number of requests rejected because a client's memory budget was exhausted
+//	  - process_memory_bytes: Memory of the process, as last measured for the soft memory limit
+//	  - soft_memory_limit_rejections: Number of requests rejected because the process exceeded its soft memory limit
+//	  - large_requests: Number of commands whose arguments exceeded the large request threshold
+//	  - large_responses: Number of commands whose response exceeded the large response threshold
+//	  - health_checks: Number of health-check PINGs sent to nodes
+//	  - health_check_failures: Number of health-check PINGs that failed or timed out
+//	  - health_check_evictions: Number of node connections closed and rebuilt after failing consecutive health checks
+//	  - unhealthy_nodes: Number of nodes whose last health check failed
+//	  - total_connection_errors: Number of connection errors recorded, see [baseClient.GetRecentConnectionErrors]
 func (client *baseClient) GetStatistics() map[string]uint64 {
 	stats := C.get_statistics()
 	return map[string]uint64{
@@ -10203,7 +10219,36 @@ func (client *baseClient) GetStatistics() map[string]uint64 {
 		"compression_skipped_count":        uint64(stats.compression_skipped_count),
 		"subscription_out_of_sync_count":   uint64(stats.subscription_out_of_sync_count),
 		"subscription_last_sync_timestamp": uint64(stats.subscription_last_sync_timestamp),
-	}
+		"latency_breakdown_samples":        uint64(stats.latency_breakdown_samples),
+		"total_queue_time_us":              uint64(stats.total_queue_time_us),
+		"total_network_time_us":            uint64(stats.total_network_time_us),
+		"total_decode_time_us":             uint64(stats.total_decode_time_us),
+		"hot_key_warnings":                 uint64(stats.hot_key_warnings),
+		"memory_budget_bytes_in_use":       uint64(stats.memory_budget_bytes_in_use),
+		"memory_budget_rejections":         uint64(stats.memory_budget_rejections),
+		"process_memory_bytes":             uint64(stats.process_memory_bytes),
+		"soft_memory_limit_rejections":     uint64(stats.soft_memory_limit_rejections),
+		"large_requests":                   uint64(stats.large_requests),
+		"large_responses":                  uint64(stats.large_responses),
+		"health_checks":                    uint64(stats.health_checks),
+		"health_check_failures":            uint64(stats.health_check_failures),
+		"health_check_evictions":           uint64(stats.health_check_evictions),
+		"unhealthy_nodes":                  uint64(stats.unhealthy_nodes),
+		"total_connection_errors":          uint64(stats.total_connection_errors),
+	}
+}
+
+// GetRecentConnectionErrors retrieves the most recent connection-level errors of all clients, such as failed
+// connection attempts and dropped connections, including the ones that were retried without failing a command.
+//
+// Return value:
+//
+//	A JSON array of the errors, oldest first. Each error has a "timestamp" (milliseconds since epoch), "node",
+//	"error_class" and "message".
+func (client *baseClient) GetRecentConnectionErrors() string {
+	errors := C.get_recent_connection_errors()
+	defer C.free_recent_connection_errors(errors)
+	return C.GoString(errors)
 }
 
 // AllChannels represents "unsubscribe from all channels".
@@ -0,0 +1,168 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+package glide
+
+/*
+#include "lib.h"
+*/
+import "C"
+
+import (
+	"time"
+
+	"github.com/valkey-io/valkey-glide/go/v2/internal/protobuf"
+	"google.golang.org/protobuf/proto"
+)
+
+// RuntimeConfig holds the process-wide settings of the GLIDE core. Settings that are not set keep
+// their defaults. It must be applied with [InitRuntimeConfig] before the first client is created.
+type RuntimeConfig struct {
+	config protobuf.RuntimeConfig
+}
+
+// NewRuntimeConfig returns a [RuntimeConfig] with every setting left at its default.
+func NewRuntimeConfig() *RuntimeConfig {
+	return &RuntimeConfig{}
+}
+
+// WithMaxClients limits the number of clients that can be open in the process at once.
+func (c *RuntimeConfig) WithMaxClients(maxClients uint32) *RuntimeConfig {
+	c.config.MaxClients = &maxClients
+	return c
+}
+
+// WithDefaultRequestTimeout sets the request timeout of clients that do not set their own.
+func (c *RuntimeConfig) WithDefaultRequestTimeout(timeout time.Duration) *RuntimeConfig {
+	ms := uint32(timeout.Milliseconds())
+	c.config.DefaultRequestTimeoutMs = &ms
+	return c
+}
+
+// WithDefaultConnectionTimeout sets the connection timeout of clients that do not set their own.
+func (c *RuntimeConfig) WithDefaultConnectionTimeout(timeout time.Duration) *RuntimeConfig {
+	ms := uint32(timeout.Milliseconds())
+	c.config.DefaultConnectionTimeoutMs = &ms
+	return c
+}
+
+// WithDefaultInflightRequestsLimit sets the inflight requests limit of clients that do not set their own.
+func (c *RuntimeConfig) WithDefaultInflightRequestsLimit(limit uint32) *RuntimeConfig {
+	c.config.DefaultInflightRequestsLimit = &limit
+	return c
+}
+
+// WithDefaultRetries sets the number of reconnection retries of clients that do not set their own.
+func (c *RuntimeConfig) WithDefaultRetries(retries uint32) *RuntimeConfig {
+	c.config.DefaultRetries = &retries
+	return c
+}
+
+// WithDefaultPeriodicTopologyChecksInterval sets the topology check interval of cluster clients that do not set their own.
+func (c *RuntimeConfig) WithDefaultPeriodicTopologyChecksInterval(interval time.Duration) *RuntimeConfig {
+	ms := uint64(interval.Milliseconds())
+	c.config.DefaultPeriodicTopologyChecksIntervalMs = &ms
+	return c
+}
+
+// WithSocketBufferSize sets the size of the buffer used to read from the server.
+func (c *RuntimeConfig) WithSocketBufferSize(size uint64) *RuntimeConfig {
+	c.config.SocketBufferSize = &size
+	return c
+}
+
+// WithMaxRequestSize sets the largest request, in bytes, that the core accepts.
+func (c *RuntimeConfig) WithMaxRequestSize(size uint64) *RuntimeConfig {
+	c.config.MaxRequestSize = &size
+	return c
+}
+
+// WithHandleShutdownSignals makes the core close its clients when the process receives a shutdown signal.
+func (c *RuntimeConfig) WithHandleShutdownSignals(handle bool) *RuntimeConfig {
+	c.config.HandleShutdownSignals = &handle
+	return c
+}
+
+// WithShutdownGracePeriod sets how long in-flight requests may run after a shutdown starts.
+func (c *RuntimeConfig) WithShutdownGracePeriod(period time.Duration) *RuntimeConfig {
+	ms := uint64(period.Milliseconds())
+	c.config.ShutdownGracePeriodMs = &ms
+	return c
+}
+
+// WithRecordLatencyBreakdown records where the time of each command is spent.
+func (c *RuntimeConfig) WithRecordLatencyBreakdown(record bool) *RuntimeConfig {
+	c.config.RecordLatencyBreakdown = &record
+	return c
+}
+
+// WithTlsCertExpiryWarning sets how long before a certificate expires a warning is raised.
+func (c *RuntimeConfig) WithTlsCertExpiryWarning(window time.Duration) *RuntimeConfig {
+	ms := uint64(window.Milliseconds())
+	c.config.TlsCertExpiryWarningMs = &ms
+	return c
+}
+
+// WithIamCredentialsExpiryWarning sets how long before IAM credentials expire a warning is raised.
+func (c *RuntimeConfig) WithIamCredentialsExpiryWarning(window time.Duration) *RuntimeConfig {
+	ms := uint64(window.Milliseconds())
+	c.config.IamCredentialsExpiryWarningMs = &ms
+	return c
+}
+
+// WithSoftMemoryLimit sets the process memory, in bytes, above which large requests are shed.
+func (c *RuntimeConfig) WithSoftMemoryLimit(limit uint64) *RuntimeConfig {
+	c.config.SoftMemoryLimit = &limit
+	return c
+}
+
+// WithMemoryCheckInterval sets how often the process memory is checked against the soft limit.
+func (c *RuntimeConfig) WithMemoryCheckInterval(interval time.Duration) *RuntimeConfig {
+	ms := uint64(interval.Milliseconds())
+	c.config.MemoryCheckIntervalMs = &ms
+	return c
+}
+
+// WithMemorySheddingRequestSize sets the request size, in bytes, from which requests are shed above the soft limit.
+func (c *RuntimeConfig) WithMemorySheddingRequestSize(size uint64) *RuntimeConfig {
+	c.config.MemorySheddingRequestSize = &size
+	return c
+}
+
+// WithLargeRequestThreshold sets the request size, in bytes, from which a request counts as large.
+func (c *RuntimeConfig) WithLargeRequestThreshold(threshold uint64) *RuntimeConfig {
+	c.config.LargeRequestThreshold = &threshold
+	return c
+}
+
+// WithLargeResponseThreshold sets the response size, in bytes, from which a response counts as large.
+func (c *RuntimeConfig) WithLargeResponseThreshold(threshold uint64) *RuntimeConfig {
+	c.config.LargeResponseThreshold = &threshold
+	return c
+}
+
+// WithRejectLargeRequests rejects large requests instead of only reporting them.
+func (c *RuntimeConfig) WithRejectLargeRequests(reject bool) *RuntimeConfig {
+	c.config.RejectLargeRequests = &reject
+	return c
+}
+
+// InitRuntimeConfig applies the process-wide runtime configuration. It returns an error if the
+// configuration is invalid, was already applied, or if a client was created before it.
+func InitRuntimeConfig(config *RuntimeConfig) error {
+	msg, err := proto.Marshal(&config.config)
+	if err != nil {
+		return NewConfigurationError(err.Error())
+	}
+	var bytesPtr *C.uchar
+	if len(msg) > 0 {
+		bytes := C.CBytes(msg)
+		defer C.free(bytes)
+		bytesPtr = (*C.uchar)(bytes)
+	}
+	errMsg := C.init_runtime_config(bytesPtr, C.uintptr_t(len(msg)))
+	if errMsg != nil {
+		defer C.free_c_string(errMsg)
+		return NewConfigurationError(C.GoString(errMsg))
+	}
+	return nil
+}
@@ -0,0 +1,94 @@
+/** Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0 */
+package glide.api;
+
+import connection_request.ConnectionRequestOuterClass.RuntimeConfig;
+import glide.api.models.configuration.RuntimeConfiguration;
+import glide.api.models.exceptions.ConfigurationError;
+import glide.ffi.resolvers.RuntimeConfigResolver;
+
+/**
+ * Process-wide configuration of the Rust core.
+ *
+ * <p>The configuration can only be applied once, and only before the first client is created.
+ */
+public final class GlideRuntime {
+
+    private GlideRuntime() {}
+
+    /**
+     * Applies the process-wide runtime configuration.
+     *
+     * @param config The runtime configuration.
+     * @throws ConfigurationError If the configuration is invalid, was already applied, or a client
+     *     was created before it.
+     */
+    public static void init(RuntimeConfiguration config) {
+        String error = RuntimeConfigResolver.initRuntimeConfig(toProtobuf(config).toByteArray());
+        if (error != null) {
+            throw new ConfigurationError(error);
+        }
+    }
+
+    static RuntimeConfig toProtobuf(RuntimeConfiguration config) {
+        RuntimeConfig.Builder builder = RuntimeConfig.newBuilder();
+        if (config.getMaxClients() != null) {
+            builder.setMaxClients(config.getMaxClients());
+        }
+        if (config.getDefaultRequestTimeout() != null) {
+            builder.setDefaultRequestTimeoutMs(config.getDefaultRequestTimeout());
+        }
+        if (config.getDefaultConnectionTimeout() != null) {
+            builder.setDefaultConnectionTimeoutMs(config.getDefaultConnectionTimeout());
+        }
+        if (config.getDefaultInflightRequestsLimit() != null) {
+            builder.setDefaultInflightRequestsLimit(config.getDefaultInflightRequestsLimit());
+        }
+        if (config.getDefaultRetries() != null) {
+            builder.setDefaultRetries(config.getDefaultRetries());
+        }
+        if (config.getDefaultPeriodicTopologyChecksInterval() != null) {
+            builder.setDefaultPeriodicTopologyChecksIntervalMs(
+                    config.getDefaultPeriodicTopologyChecksInterval());
+        }
+        if (config.getSocketBufferSize() != null) {
+            builder.setSocketBufferSize(config.getSocketBufferSize());
+        }
+        if (config.getMaxRequestSize() != null) {
+            builder.setMaxRequestSize(config.getMaxRequestSize());
+        }
+        if (config.getHandleShutdownSignals() != null) {
+            builder.setHandleShutdownSignals(config.getHandleShutdownSignals());
+        }
+        if (config.getShutdownGracePeriod() != null) {
+            builder.setShutdownGracePeriodMs(config.getShutdownGracePeriod());
+        }
+        if (config.getRecordLatencyBreakdown() != null) {
+            builder.setRecordLatencyBreakdown(config.getRecordLatencyBreakdown());
+        }
+        if (config.getTlsCertExpiryWarning() != null) {
+            builder.setTlsCertExpiryWarningMs(config.getTlsCertExpiryWarning());
+        }
+        if (config.getIamCredentialsExpiryWarning() != null) {
+            builder.setIamCredentialsExpiryWarningMs(config.getIamCredentialsExpiryWarning());
+        }
+        if (config.getSoftMemoryLimit() != null) {
+            builder.setSoftMemoryLimit(config.getSoftMemoryLimit());
+        }
+        if (config.getMemoryCheckInterval() != null) {
+            builder.setMemoryCheckIntervalMs(config.getMemoryCheckInterval());
+        }
+        if (config.getMemorySheddingRequestSize() != null) {
+            builder.setMemorySheddingRequestSize(config.getMemorySheddingRequestSize());
+        }
+        if (config.getLargeRequestThreshold() != null) {
+            builder.setLargeRequestThreshold(config.getLargeRequestThreshold());
+        }
+        if (config.getLargeResponseThreshold() != null) {
+            builder.setLargeResponseThreshold(config.getLargeResponseThreshold());
+        }
+        if (config.getRejectLargeRequests() != null) {
+            builder.setRejectLargeRequests(config.getRejectLargeRequests());
+        }
+        return builder.build();
+    }
+}
@@ -0,0 +1,71 @@
+/** Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0 */
+package glide.api.models.configuration;
+
+import lombok.Builder;
+import lombok.Getter;
+
+/**
+ * Process-wide settings of the Rust core, applied with {@link glide.api.GlideRuntime#init}. Settings
+ * that are not set keep the defaults of the Rust core.
+ */
+@Getter
+@Builder
+public class RuntimeConfiguration {
+
+    /** Maximum number of clients that can be open in the process at once. */
+    private final Integer maxClients;
+
+    /** Request timeout in milliseconds for clients that do not set their own. */
+    private final Integer defaultRequestTimeout;
+
+    /** Connection timeout in milliseconds for clients that do not set their own. */
+    private final Integer defaultConnectionTimeout;
+
+    /** Inflight requests limit for clients that do not set their own. */
+    private final Integer defaultInflightRequestsLimit;
+
+    /** Number of reconnection retries for clients that do not set their own. */
+    private final Integer defaultRetries;
+
+    /** Topology check interval in milliseconds for cluster clients that do not set their own. */
+    private final Long defaultPeriodicTopologyChecksInterval;
+
+    /** Size in bytes of the buffer used to read from the server. */
+    private final Long socketBufferSize;
+
+    /** Largest request in bytes that the core accepts. */
+    private final Long maxRequestSize;
+
+    /** Whether the core closes its clients when the process receives a shutdown signal. */
+    private final Boolean handleShutdownSignals;
+
+    /** How long in milliseconds in-flight requests may run after a shutdown starts. */
+    private final Long shutdownGracePeriod;
+
+    /** Whether to record where the time of each command is spent. */
+    private final Boolean recordLatencyBreakdown;
+
+    /** How long in milliseconds before a TLS certificate expires a warning is raised. */
+    private final Long tlsCertExpiryWarning;
+
+    /** How long in milliseconds before IAM credentials expire a warning is raised. */
+    private final Long iamCredentialsExpiryWarning;
+
+    /** Process memory in bytes above which large requests are shed. */
+    private final Long softMemoryLimit;
+
+    /** How often in milliseconds the process memory is checked against the soft limit. */
+    private final Long memoryCheckInterval;
+
+    /** Request size in bytes from which requests are shed above the soft memory limit. */
+    private final Long memorySheddingRequestSize;
+
+    /** Request size in bytes from which a request counts as large. */
+    private final Long largeRequestThreshold;
+
+    /** Response size in bytes from which a response counts as large. */
+    private final Long largeResponseThreshold;
+
+    /** Whether large requests are rejected instead of only reported. */
+    private final Boolean rejectLargeRequests;
+}
@@ -0,0 +1,17 @@
+/** Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0 */
+package glide.ffi.resolvers;
+
+public final class RuntimeConfigResolver {
+
+    static {
+        NativeUtils.loadGlideLib();
+    }
+
+    /**
+     * Sets the process-wide runtime configuration of the Rust core.
+     *
+     * @param config A serialized <code>RuntimeConfig</code> protobuf message
+     * @return <code>null</code> on success, or the error message
+     */
+    public static native String initRuntimeConfig(byte[] config);
+}
@@ -327,26 +327,27 @@ public final class AsyncRegistry {
             }
         }
 
-        RuntimeException ex;
+        return future.completeExceptionally(exceptionForErrorCode(errorTypeCode, msg));
+    }
+
+    /**
+     * Create the exception for a structured error code from native layer, as described in {@link
+     * #completeCallbackWithErrorCode}. Also called by native code for the failed commands of a
+     * multi-command submission.
+     */
+    public static RuntimeException exceptionForErrorCode(int errorTypeCode, String errorMessage) {
         switch (errorTypeCode) {
             case 2:
-                ex = new TimeoutException(msg);
-                break;
+                return new TimeoutException(errorMessage);
             case 3:
-                ex = new ClosingException(msg);
-                break;
+                return new ClosingException(errorMessage);
             case 1:
-                ex = new ExecAbortException(msg);
-                break;
+                return new ExecAbortException(errorMessage);
             case 4:
-                ex = new CircuitBreakerException(msg);
-                break;
+                return new CircuitBreakerException(errorMessage);
             default:
-                ex = new RequestException(msg);
-                break;
+                return new RequestException(errorMessage);
         }
-
-        return future.completeExceptionally(ex);
     }
 
     /** Get current pending operation count. */
@@ -68,6 +68,33 @@ public class GlideCoreClient implements AutoCloseable {
             throw new RuntimeException("Failed to load native library", e);
         }
         onNativeInit();
+        registerConfiguredResponseRing();
+    }
+
+    /**
+     * Registers the response ring sized by the {@code glide.responseRingSlotSize} and {@code
+     * glide.responseRingSlots} system properties, when both are set.
+     */
+    private static void registerConfiguredResponseRing() {
+        String slotSize = System.getProperty("glide.responseRingSlotSize");
+        String slots = System.getProperty("glide.responseRingSlots");
+        if (slotSize == null || slots == null) {
+            return;
+        }
+        boolean registered;
+        try {
+            registered =
+                    registerResponseRing(
+                            Integer.parseInt(slotSize.trim()), Integer.parseInt(slots.trim()));
+        } catch (NumberFormatException e) {
+            registered = false;
+        }
+        if (!registered) {
+            Logger.log(
+                    Logger.Level.WARN,
+                    "GlideCoreClient",
+                    "Invalid response ring size: " + slots + " slots of " + slotSize + " bytes");
+        }
     }
 
     private static native void onNativeInit();
@@ -280,6 +307,130 @@ public class GlideCoreClient implements AutoCloseable {
         }
     }
 
+    /**
+     * Execute several commands asynchronously in one native call. The future completes with an
+     * {@code Object[]} of the results in request order, where a failed command's result is the
+     * exception it would have failed with on its own.
+     */
+    public CompletableFuture<Object> executeCommandsAsync(
+            byte[][] requestsBytes, boolean expectUtf8Response) {
+        try {
+            long handle = nativeClientHandle.get();
+            if (handle == 0) {
+                CompletableFuture<Object> future = new CompletableFuture<>();
+                future.completeExceptionally(
+                        new glide.api.models.exceptions.ClosingException("Client is closed"));
+                return future;
+            }
+
+            // Create future and register it with the async registry
+            CompletableFuture<Object> future = new CompletableFuture<>();
+            long correlationId;
+            try {
+                correlationId =
+                        AsyncRegistry.register(
+                                future, this.maxInflightRequests, handle, this.requestTimeoutMillis);
+            } catch (glide.api.models.exceptions.RequestException e) {
+                future.completeExceptionally(e);
+                return future;
+            }
+
+            // Execute all the commands with a single JNI crossing and completion
+            GlideNativeBridge.executeCommandsAsync(
+                    handle, requestsBytes, correlationId, expectUtf8Response);
+
+            return future;
+
+        } catch (Exception e) {
+            CompletableFuture<Object> future = new CompletableFuture<>();
+            future.completeExceptionally(e);
+            return future;
+        }
+    }
+
+    /**
+     * Execute command asynchronously, passing the request to native code in a direct buffer, which
+     * is read in place instead of being copied. Falls back to the byte array entrypoints when native
+     * code can't read the buffer.
+     *
+     * @param request Direct buffer holding the serialized request between its position and limit.
+     *     It's only read during the call, so it can be reused once this method returns.
+     * @param expectUtf8Response Whether to convert the response to UTF-8 strings
+     */
+    public CompletableFuture<Object> executeCommandDirectAsync(
+            java.nio.ByteBuffer request, boolean expectUtf8Response) {
+        try {
+            long handle = nativeClientHandle.get();
+            if (handle == 0) {
+                CompletableFuture<Object> future = new CompletableFuture<>();
+                future.completeExceptionally(
+                        new glide.api.models.exceptions.ClosingException("Client is closed"));
+                return future;
+            }
+
+            // Create future and register it with the async registry
+            CompletableFuture<Object> future = new CompletableFuture<>();
+            long correlationId;
+            try {
+                correlationId =
+                        AsyncRegistry.register(
+                                future, this.maxInflightRequests, handle, this.requestTimeoutMillis);
+            } catch (glide.api.models.exceptions.RequestException e) {
+                future.completeExceptionally(e);
+                return future;
+            }
+
+            boolean submitted =
+                    GlideNativeBridge.executeCommandDirectAsync(
+                            handle,
+                            request,
+                            request.position(),
+                            request.remaining(),
+                            correlationId,
+                            expectUtf8Response);
+            if (!submitted) {
+                // The callback wasn't completed, so the request is sent through the copy path.
+                byte[] requestBytes = new byte[request.remaining()];
+                request.duplicate().get(requestBytes);
+                if (expectUtf8Response) {
+                    GlideNativeBridge.executeCommandAsync(handle, requestBytes, correlationId);
+                } else {
+                    GlideNativeBridge.executeBinaryCommandAsync(handle, requestBytes, correlationId);
+                }
+            }
+
+            return future;
+
+        } catch (Exception e) {
+            CompletableFuture<Object> future = new CompletableFuture<>();
+            future.completeExceptionally(e);
+            return future;
+        }
+    }
+
+    /**
+     * Register a direct buffer of {@code slotCount} slots of {@code slotSize} bytes, into which large
+     * bulk string responses are written instead of freshly allocated native buffers. Replaces the
+     * previously registered buffer.
+     *
+     * @return Whether the buffer was registered
+     */
+    public static boolean registerResponseRing(int slotSize, int slotCount) {
+        if (slotSize <= 0 || slotCount <= 0 || (long) slotSize * slotCount > Integer.MAX_VALUE) {
+            return false;
+        }
+        java.nio.ByteBuffer ring = java.nio.ByteBuffer.allocateDirect(slotSize * slotCount);
+        return GlideNativeBridge.registerResponseRing(ring, slotSize);
+    }
+
+    /**
+     * Stop writing responses into the registered response ring. Responses already written into it
+     * stay valid until they are garbage collected.
+     */
+    public static void unregisterResponseRing() {
+        GlideNativeBridge.unregisterResponseRing();
+    }
+
     /** Execute batch asynchronously using raw protobuf bytes. */
     public CompletableFuture<Object> executeBatchAsync(
             byte[] batchRequestBytes, boolean expectUtf8Response, Integer timeoutOverrideMs) {
@@ -46,6 +46,35 @@ public class GlideNativeBridge {
     public static native void executeBinaryCommandAsync(
             long clientPtr, byte[] requestBytes, long callbackId);
 
+    /**
+     * Execute several commands asynchronously in one call. The callback completes with an {@code
+     * Object[]} of the results in request order, where a failed command's result is the exception it
+     * would have failed with on its own.
+     */
+    public static native void executeCommandsAsync(
+            long clientPtr, byte[][] requestBytes, long callbackId, boolean expectUtf8Response);
+
+    /**
+     * Execute command asynchronously, reading the request in place from a direct buffer. Returns
+     * false, without completing the callback, if the buffer isn't direct.
+     */
+    public static native boolean executeCommandDirectAsync(
+            long clientPtr,
+            java.nio.ByteBuffer request,
+            int offset,
+            int length,
+            long callbackId,
+            boolean expectUtf8Response);
+
+    /**
+     * Register a direct buffer whose slots of {@code slotSize} bytes hold large bulk string
+     * responses. Returns false if the buffer isn't direct or is smaller than a slot.
+     */
+    public static native boolean registerResponseRing(java.nio.ByteBuffer buffer, int slotSize);
+
+    /** Stop writing responses into the registered response ring */
+    public static native void unregisterResponseRing();
+
     /** Execute batch (pipeline/transaction) asynchronously */
     public static native void executeBatchAsync(
             long clientPtr, byte[] batchRequestBytes, boolean expectUtf8Response, long callbackId);
@@ -37,6 +37,7 @@ import glide.utils.Java8Utils;
 import java.math.BigInteger;
 import java.nio.ByteBuffer;
 import java.nio.ByteOrder;
+import java.util.ArrayList;
 import java.util.Collections;
 import java.util.List;
 import java.util.Optional;
@@ -68,6 +69,18 @@ public class CommandManager {
                             "WAIT",
                             "WAITAOF"));
 
+    /**
+     * Requests of at least this many bytes are serialized into a direct buffer, which native code
+     * reads in place instead of copying.
+     */
+    static final int DIRECT_REQUEST_MIN_SIZE = 16 * 1024;
+
+    /** Larger requests go through the copy path, so that threads don't keep huge buffers. */
+    static final int DIRECT_REQUEST_MAX_SIZE = 8 * 1024 * 1024;
+
+    /** Per-thread direct buffer the large requests are serialized into. */
+    private static final ThreadLocal<ByteBuffer> DIRECT_REQUEST_BUFFER = new ThreadLocal<>();
+
     /** Core client connection. */
     private final GlideCoreClient coreClient;
 
@@ -209,6 +222,57 @@ public class CommandManager {
                 command, responseHandler, true, false); // GlideString arguments -> expect binary response
     }
 
+    /**
+     * Build several commands and submit them to native code in one call, completing them through a
+     * single callback. Each returned future completes with its own command's result or error, in
+     * the order of {@code argumentsList}.
+     */
+    public <T> List<CompletableFuture<T>> submitNewCommands(
+            RequestType requestType,
+            List<String[]> argumentsList,
+            GlideExceptionCheckedFunction<Response, T> responseHandler) {
+        List<CompletableFuture<T>> futures = new ArrayList<>(argumentsList.size());
+        byte[][] requestsBytes = new byte[argumentsList.size()][];
+        for (int i = 0; i < requestsBytes.length; i++) {
+            requestsBytes[i] =
+                    prepareCommandRequest(requestType, argumentsList.get(i)).build().toByteArray();
+            futures.add(new CompletableFuture<>());
+        }
+
+        if (!coreClient.isConnected()) {
+            ClosingException error = new ClosingException("Client closed: Unable to submit command.");
+            futures.forEach(future -> future.completeExceptionally(error));
+            return futures;
+        }
+
+        coreClient
+                .executeCommandsAsync(requestsBytes, true)
+                .whenComplete(
+                        (results, error) -> {
+                            for (int i = 0; i < futures.size(); i++) {
+                                CompletableFuture<T> future = futures.get(i);
+                                if (error != null) {
+                                    future.completeExceptionally(error);
+                                    continue;
+                                }
+                                Object result = ((Object[]) results)[i];
+                                if (result instanceof Throwable) {
+                                    // The command's own error, with its type kept by native code
+                                    future.completeExceptionally((Throwable) result);
+                                    continue;
+                                }
+                                try {
+                                    future.complete(
+                                            applyHandlerWithCleanup(
+                                                    buildResponseFromJniResult(result, true), responseHandler));
+                                } catch (Exception e) {
+                                    future.completeExceptionally(e);
+                                }
+                            }
+                        });
+        return futures;
+    }
+
     // ==================== BLOCKING COMMAND METHODS ====================
     // These methods skip Java-side timeout because blocking commands (BLPOP, BRPOP, etc.)
     // have their own timeout in the command arguments, which Rust handles correctly.
@@ -718,16 +782,25 @@ public class CommandManager {
         }
 
         try {
-            // Serialize the protobuf command request
-            byte[] requestBytes = command.build().toByteArray();
+            CommandRequest request = command.build();
+            int requestSize = request.getSerializedSize();
 
             // Execute via JNI - returns converted Java objects directly
             // No need to wrap in Response since JNI already provides the final object
             // Use binary or UTF-8 mode based on expected response type, not argument type
-            CompletableFuture<Object> jniFuture =
-                    expectUtf8Response
-                            ? coreClient.executeCommandAsync(requestBytes) // Force UTF-8 conversion
-                            : coreClient.executeBinaryCommandAsync(requestBytes); // Allow binary conversion
+            CompletableFuture<Object> jniFuture;
+            if (requestSize >= DIRECT_REQUEST_MIN_SIZE && requestSize <= DIRECT_REQUEST_MAX_SIZE) {
+                jniFuture =
+                        coreClient.executeCommandDirectAsync(
+                                serializeToDirectBuffer(request, requestSize), expectUtf8Response);
+            } else {
+                // Serialize the protobuf command request
+                byte[] requestBytes = request.toByteArray();
+                jniFuture =
+                        expectUtf8Response
+                                ? coreClient.executeCommandAsync(requestBytes) // Force UTF-8 conversion
+                                : coreClient.executeBinaryCommandAsync(requestBytes); // Allow binary conversion
+            }
 
             return jniFuture
                     .thenApply(result -> buildResponseFromJniResult(result, expectUtf8Response))
@@ -740,6 +813,26 @@ public class CommandManager {
         }
     }
 
+    /**
+     * Serializes {@code request} into this thread's direct request buffer, growing it if needed. The
+     * buffer is only read during the native call, so it's reused by the thread's next request.
+     */
+    static ByteBuffer serializeToDirectBuffer(CommandRequest request, int requestSize)
+            throws java.io.IOException {
+        ByteBuffer buffer = DIRECT_REQUEST_BUFFER.get();
+        if (buffer == null || buffer.capacity() < requestSize) {
+            buffer = ByteBuffer.allocateDirect(Integer.highestOneBit(requestSize - 1) << 1);
+            DIRECT_REQUEST_BUFFER.set(buffer);
+        }
+        buffer.clear();
+        com.google.protobuf.CodedOutputStream output =
+                com.google.protobuf.CodedOutputStream.newInstance(buffer);
+        request.writeTo(output);
+        output.flush();
+        buffer.flip();
+        return buffer;
+    }
+
     /**
      * Submit a blocking command to JNI without Java-side timeout. Blocking commands (BLPOP, BRPOP,
      * etc.) have their own timeout in the command arguments, which Rust handles correctly.
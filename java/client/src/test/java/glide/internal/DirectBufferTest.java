@@ -0,0 +1,44 @@
+/** Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0 */
+package glide.internal;
+
+import static org.junit.jupiter.api.Assertions.assertFalse;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+import java.nio.ByteBuffer;
+import org.junit.jupiter.api.AfterEach;
+import org.junit.jupiter.api.Test;
+
+public class DirectBufferTest {
+
+    @AfterEach
+    void tearDown() {
+        GlideCoreClient.unregisterResponseRing();
+    }
+
+    @Test
+    void executeCommandDirectAsync_returnsFalseForHeapBuffers() {
+        // The buffer is checked before the client, so the caller can fall back to the copy path.
+        ByteBuffer heapBuffer = ByteBuffer.allocate(16);
+        assertFalse(GlideNativeBridge.executeCommandDirectAsync(0, heapBuffer, 0, 16, 1, true));
+    }
+
+    @Test
+    void registerResponseRing_rejectsHeapAndUndersizedBuffers() {
+        assertFalse(GlideNativeBridge.registerResponseRing(ByteBuffer.allocate(1024), 256));
+        assertFalse(GlideNativeBridge.registerResponseRing(ByteBuffer.allocateDirect(128), 256));
+        assertFalse(GlideNativeBridge.registerResponseRing(ByteBuffer.allocateDirect(1024), 0));
+    }
+
+    @Test
+    void registerResponseRing_acceptsDirectBuffers() {
+        assertTrue(GlideNativeBridge.registerResponseRing(ByteBuffer.allocateDirect(1024), 256));
+        assertTrue(GlideCoreClient.registerResponseRing(64 * 1024, 4));
+    }
+
+    @Test
+    void registerResponseRing_rejectsInvalidSizes() {
+        assertFalse(GlideCoreClient.registerResponseRing(0, 4));
+        assertFalse(GlideCoreClient.registerResponseRing(1024, -1));
+        assertFalse(GlideCoreClient.registerResponseRing(Integer.MAX_VALUE, 2));
+    }
+}
@@ -3,10 +3,20 @@ package glide.managers;
 
 import static org.junit.jupiter.api.Assertions.assertEquals;
 import static org.junit.jupiter.api.Assertions.assertNull;
+import static org.junit.jupiter.api.Assertions.assertSame;
 import static org.junit.jupiter.api.Assertions.assertThrows;
 import static org.junit.jupiter.api.Assertions.assertTrue;
+import static org.mockito.ArgumentMatchers.any;
+import static org.mockito.ArgumentMatchers.eq;
 import static org.mockito.Mockito.mock;
-
+import static org.mockito.Mockito.never;
+import static org.mockito.Mockito.verify;
+import static org.mockito.Mockito.when;
+
+import com.google.protobuf.ByteString;
+import command_request.CommandRequestOuterClass.Command;
+import command_request.CommandRequestOuterClass.CommandRequest;
+import command_request.CommandRequestOuterClass.RequestType;
 import glide.internal.GlideCoreClient;
 import java.lang.reflect.InvocationTargetException;
 import java.lang.reflect.Method;
@@ -15,16 +25,53 @@ import java.nio.ByteBuffer;
 import java.nio.ByteOrder;
 import java.nio.charset.StandardCharsets;
 import java.util.LinkedHashMap;
+import java.util.concurrent.CompletableFuture;
 import org.junit.jupiter.api.BeforeEach;
 import org.junit.jupiter.api.Test;
 
 public class CommandManagerDirectBufferTest {
 
+    private GlideCoreClient coreClient;
     private CommandManager commandManager;
 
     @BeforeEach
     void setUp() {
-        commandManager = new CommandManager(mock(GlideCoreClient.class));
+        coreClient = mock(GlideCoreClient.class);
+        commandManager = new CommandManager(coreClient);
+    }
+
+    // ==================== Direct Request Buffer Tests ====================
+
+    @Test
+    void serializeToDirectBuffer_writesTheRequestIntoAReusedDirectBuffer() throws Exception {
+        CommandRequest large = setRequest(CommandManager.DIRECT_REQUEST_MIN_SIZE * 2).build();
+        ByteBuffer buffer = CommandManager.serializeToDirectBuffer(large, large.getSerializedSize());
+
+        assertTrue(buffer.isDirect());
+        assertEquals(large.getSerializedSize(), buffer.remaining());
+        assertEquals(large, CommandRequest.parseFrom(buffer.duplicate()));
+
+        CommandRequest smaller = setRequest(CommandManager.DIRECT_REQUEST_MIN_SIZE).build();
+        ByteBuffer reused =
+                CommandManager.serializeToDirectBuffer(smaller, smaller.getSerializedSize());
+        assertSame(buffer, reused);
+        assertEquals(smaller, CommandRequest.parseFrom(reused.duplicate()));
+    }
+
+    @Test
+    void submitCommandToJni_sendsLargeRequestsThroughTheDirectPath() {
+        when(coreClient.isConnected()).thenReturn(true);
+        when(coreClient.executeCommandDirectAsync(any(ByteBuffer.class), eq(true)))
+                .thenReturn(new CompletableFuture<>());
+        when(coreClient.executeCommandAsync(any(byte[].class))).thenReturn(new CompletableFuture<>());
+
+        commandManager.submitCommandToJni(
+                setRequest(CommandManager.DIRECT_REQUEST_MIN_SIZE), response -> null, false, true);
+        verify(coreClient).executeCommandDirectAsync(any(ByteBuffer.class), eq(true));
+        verify(coreClient, never()).executeCommandAsync(any(byte[].class));
+
+        commandManager.submitCommandToJni(setRequest(16), response -> null, false, true);
+        verify(coreClient).executeCommandAsync(any(byte[].class));
     }
 
     @Test
@@ -265,6 +312,18 @@ public class CommandManagerDirectBufferTest {
 
     // ==================== Helper Methods ====================
 
+    private static CommandRequest.Builder setRequest(int valueSize) {
+        return CommandRequest.newBuilder()
+                .setCallbackIdx(1)
+                .setSingleCommand(
+                        Command.newBuilder()
+                                .setRequestType(RequestType.Set)
+                                .setArgsArray(
+                                        Command.ArgsArray.newBuilder()
+                                                .addArgs(ByteString.copyFromUtf8("key"))
+                                                .addArgs(ByteString.copyFrom(new byte[valueSize]))));
+    }
+
     private Object[] deserializeByteBufferArray(ByteBuffer buffer, boolean expectUtf8Response)
             throws Exception {
         Method method =
@@ -0,0 +1,98 @@
+/** Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0 */
+package glide.managers;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertInstanceOf;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+import static org.mockito.ArgumentMatchers.any;
+import static org.mockito.ArgumentMatchers.eq;
+import static org.mockito.Mockito.mock;
+import static org.mockito.Mockito.never;
+import static org.mockito.Mockito.verify;
+import static org.mockito.Mockito.when;
+
+import command_request.CommandRequestOuterClass.RequestType;
+import glide.api.models.exceptions.ClosingException;
+import glide.api.models.exceptions.RequestException;
+import glide.api.models.exceptions.TimeoutException;
+import glide.internal.AsyncRegistry;
+import glide.internal.GlideCoreClient;
+import java.util.Arrays;
+import java.util.List;
+import java.util.concurrent.CompletableFuture;
+import java.util.concurrent.ExecutionException;
+import org.junit.jupiter.api.BeforeEach;
+import org.junit.jupiter.api.Test;
+
+public class CommandManagerMultiCommandTest {
+
+    private GlideCoreClient coreClient;
+    private CommandManager commandManager;
+
+    @BeforeEach
+    void setUp() {
+        coreClient = mock(GlideCoreClient.class);
+        commandManager = new CommandManager(coreClient);
+    }
+
+    private List<CompletableFuture<Object>> submitGets(String... keys) {
+        List<String[]> arguments = Arrays.asList(new String[keys.length][]);
+        for (int i = 0; i < keys.length; i++) {
+            arguments.set(i, new String[] {keys[i]});
+        }
+        return commandManager.submitNewCommands(
+                RequestType.Get,
+                arguments,
+                response ->
+                        response.hasConstantResponse()
+                                ? "OK"
+                                : JniResponseRegistry.retrieveAndRemove(response.getRespPointer()));
+    }
+
+    @Test
+    void submitNewCommands_completesEachFutureWithItsOwnResult() throws Exception {
+        CompletableFuture<Object> nativeFuture = new CompletableFuture<>();
+        when(coreClient.isConnected()).thenReturn(true);
+        when(coreClient.executeCommandsAsync(any(byte[][].class), eq(true))).thenReturn(nativeFuture);
+
+        List<CompletableFuture<Object>> futures = submitGets("a", "b", "c");
+        nativeFuture.complete(
+                new Object[] {
+                    "value",
+                    AsyncRegistry.exceptionForErrorCode(2, "timed out"),
+                    AsyncRegistry.exceptionForErrorCode(3, "disconnected")
+                });
+
+        assertEquals("value", futures.get(0).get());
+        ExecutionException timeout = assertThrows(ExecutionException.class, futures.get(1)::get);
+        assertInstanceOf(TimeoutException.class, timeout.getCause());
+        ExecutionException closing = assertThrows(ExecutionException.class, futures.get(2)::get);
+        assertInstanceOf(ClosingException.class, closing.getCause());
+    }
+
+    @Test
+    void submitNewCommands_failsEveryFutureWhenTheSubmissionFails() {
+        CompletableFuture<Object> nativeFuture = new CompletableFuture<>();
+        when(coreClient.isConnected()).thenReturn(true);
+        when(coreClient.executeCommandsAsync(any(byte[][].class), eq(true))).thenReturn(nativeFuture);
+
+        List<CompletableFuture<Object>> futures = submitGets("a", "b");
+        nativeFuture.completeExceptionally(new RequestException("Failed to read requests"));
+
+        for (CompletableFuture<Object> future : futures) {
+            ExecutionException error = assertThrows(ExecutionException.class, future::get);
+            assertInstanceOf(RequestException.class, error.getCause());
+        }
+    }
+
+    @Test
+    void submitNewCommands_failsWithoutSubmittingWhenTheClientIsClosed() {
+        when(coreClient.isConnected()).thenReturn(false);
+
+        List<CompletableFuture<Object>> futures = submitGets("a");
+
+        ExecutionException error = assertThrows(ExecutionException.class, futures.get(0)::get);
+        assertInstanceOf(ClosingException.class, error.getCause());
+        verify(coreClient, never()).executeCommandsAsync(any(byte[][].class), eq(true));
+    }
+}
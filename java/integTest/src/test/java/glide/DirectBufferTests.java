@@ -0,0 +1,104 @@
+/** Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0 */
+package glide;
+
+import static glide.TestUtilities.commonClientConfig;
+import static glide.TestUtilities.commonClusterClientConfig;
+import static glide.api.BaseClient.OK;
+import static org.junit.jupiter.api.Assertions.assertArrayEquals;
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+import glide.api.BaseClient;
+import glide.api.GlideClient;
+import glide.api.GlideClusterClient;
+import glide.api.models.GlideString;
+import glide.internal.GlideCoreClient;
+import java.util.Random;
+import java.util.UUID;
+import java.util.stream.Stream;
+import lombok.SneakyThrows;
+import org.junit.jupiter.api.AfterEach;
+import org.junit.jupiter.api.Timeout;
+import org.junit.jupiter.params.ParameterizedTest;
+import org.junit.jupiter.params.provider.MethodSource;
+
+/** Large requests and responses, which go through the direct buffer paths. */
+@Timeout(35)
+public class DirectBufferTests {
+
+    private static final int LARGE_VALUE_SIZE = 256 * 1024;
+
+    @SneakyThrows
+    static Stream<BaseClient> getClients() {
+        return Stream.of(
+                GlideClient.createClient(commonClientConfig().build()).get(),
+                GlideClusterClient.createClient(commonClusterClientConfig().build()).get());
+    }
+
+    @AfterEach
+    void tearDown() {
+        GlideCoreClient.unregisterResponseRing();
+    }
+
+    private static byte[] randomBytes(int size) {
+        byte[] value = new byte[size];
+        new Random().nextBytes(value);
+        return value;
+    }
+
+    @SneakyThrows
+    @ParameterizedTest
+    @MethodSource("getClients")
+    public void large_requests_are_sent_through_the_direct_buffer(BaseClient client) {
+        String key = UUID.randomUUID().toString();
+        byte[] value = randomBytes(LARGE_VALUE_SIZE);
+
+        assertEquals(OK, client.set(GlideString.of(key), GlideString.of(value)).get());
+        assertArrayEquals(value, client.get(GlideString.of(key)).get().getBytes());
+
+        // Smaller than the direct path's threshold, and larger than its buffers.
+        byte[] small = randomBytes(64);
+        assertEquals(OK, client.set(GlideString.of(key), GlideString.of(small)).get());
+        assertArrayEquals(small, client.get(GlideString.of(key)).get().getBytes());
+        byte[] huge = randomBytes(9 * 1024 * 1024);
+        assertEquals(OK, client.set(GlideString.of(key), GlideString.of(huge)).get());
+        assertArrayEquals(huge, client.get(GlideString.of(key)).get().getBytes());
+
+        client.del(new String[] {key}).get();
+        client.close();
+    }
+
+    @SneakyThrows
+    @ParameterizedTest
+    @MethodSource("getClients")
+    public void large_responses_are_written_into_the_response_ring(BaseClient client) {
+        assertTrue(GlideCoreClient.registerResponseRing(LARGE_VALUE_SIZE * 2, 2));
+        String[] keys = new String[3];
+        byte[][] values = new byte[3][];
+        for (int i = 0; i < keys.length; i++) {
+            keys[i] = "{ring}" + UUID.randomUUID();
+            values[i] = randomBytes(LARGE_VALUE_SIZE);
+            assertEquals(OK, client.set(GlideString.of(keys[i]), GlideString.of(values[i])).get());
+        }
+
+        // More responses than slots, and larger than a slot, fall back to native buffers.
+        GlideString[] responses = new GlideString[keys.length];
+        for (int i = 0; i < keys.length; i++) {
+            responses[i] = client.get(GlideString.of(keys[i])).get();
+        }
+        for (int i = 0; i < keys.length; i++) {
+            assertArrayEquals(values[i], responses[i].getBytes());
+        }
+        byte[] oversized = randomBytes(LARGE_VALUE_SIZE * 3);
+        assertEquals(OK, client.set(GlideString.of(keys[0]), GlideString.of(oversized)).get());
+        assertArrayEquals(oversized, client.get(GlideString.of(keys[0])).get().getBytes());
+
+        // Responses written into the ring stay valid after it's unregistered.
+        GlideCoreClient.unregisterResponseRing();
+        assertArrayEquals(values[1], responses[1].getBytes());
+        assertArrayEquals(values[2], client.get(GlideString.of(keys[2])).get().getBytes());
+
+        client.del(keys).get();
+        client.close();
+    }
+}
@@ -0,0 +1,181 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Direct ByteBuffer paths between Java and the native layer.
+//!
+//! Requests can be passed as a direct `ByteBuffer`, whose memory is parsed in place instead of
+//! being copied into a Rust `Vec` first. Responses large enough to be returned as a direct
+//! `ByteBuffer` are written into a slot of a response ring - a direct `ByteBuffer` registered once
+//! by Java - instead of a freshly allocated native buffer.
+//!
+//! Ring slots are leased per response and returned through the same Java Cleaner path that frees
+//! native buffers, so the Java side handles both the same way. Every lease keeps the ring alive:
+//! unregistering the ring only stops new leases, and the ring's `ByteBuffer` is released once the
+//! last response written into it is collected. When no ring is registered, all of its slots are
+//! leased, or the response doesn't fit in a slot, responses fall back to native buffers.
+
+use dashmap::DashMap;
+use jni::JNIEnv;
+use jni::objects::{GlobalRef, JByteBuffer};
+use parking_lot::Mutex;
+use std::sync::{Arc, OnceLock};
+
+/// The free slots of a response ring.
+#[derive(Debug)]
+struct SlotAllocator {
+    slot_size: usize,
+    free: Mutex<Vec<usize>>,
+}
+
+impl SlotAllocator {
+    fn new(capacity: usize, slot_size: usize) -> Option<Self> {
+        if slot_size == 0 || capacity < slot_size {
+            return None;
+        }
+        // Reversed, so slots are leased from the start of the buffer.
+        let free = (0..capacity / slot_size).rev().collect();
+        Some(Self {
+            slot_size,
+            free: Mutex::new(free),
+        })
+    }
+
+    /// Leases a slot for `len` bytes, returning its index.
+    fn lease(&self, len: usize) -> Option<usize> {
+        if len > self.slot_size {
+            return None;
+        }
+        self.free.lock().pop()
+    }
+
+    fn release(&self, slot: usize) {
+        self.free.lock().push(slot);
+    }
+}
+
+/// A direct ByteBuffer registered by Java to hold responses.
+struct ResponseRing {
+    // Keeps the buffer, and therefore its memory, alive while the ring or any of its leases is.
+    _buffer: GlobalRef,
+    base: usize,
+    slots: SlotAllocator,
+}
+
+static RESPONSE_RING: OnceLock<Mutex<Option<Arc<ResponseRing>>>> = OnceLock::new();
+static SLOT_LEASES: OnceLock<DashMap<u64, (Arc<ResponseRing>, usize)>> = OnceLock::new();
+
+fn response_ring() -> &'static Mutex<Option<Arc<ResponseRing>>> {
+    RESPONSE_RING.get_or_init(|| Mutex::new(None))
+}
+
+fn slot_leases() -> &'static DashMap<u64, (Arc<ResponseRing>, usize)> {
+    SLOT_LEASES.get_or_init(DashMap::new)
+}
+
+/// Registers `buffer` as the response ring, split into slots of `slot_size` bytes, replacing the
+/// previous ring. Returns `false` if the buffer isn't direct or can't hold a single slot.
+pub(crate) fn register_response_ring(
+    env: &mut JNIEnv,
+    buffer: &JByteBuffer,
+    slot_size: usize,
+) -> jni::errors::Result<bool> {
+    let (Ok(base), Ok(capacity)) = (
+        env.get_direct_buffer_address(buffer),
+        env.get_direct_buffer_capacity(buffer),
+    ) else {
+        return Ok(false);
+    };
+    let Some(slots) = SlotAllocator::new(capacity, slot_size) else {
+        return Ok(false);
+    };
+    let ring = ResponseRing {
+        _buffer: env.new_global_ref(buffer)?,
+        base: base as usize,
+        slots,
+    };
+    *response_ring().lock() = Some(Arc::new(ring));
+    Ok(true)
+}
+
+/// Stops leasing slots of the response ring. Slots still leased stay valid until released.
+pub(crate) fn unregister_response_ring() {
+    response_ring().lock().take();
+}
+
+/// Copies `data` into a free slot of the response ring, returning the lease id, and the slot's
+/// pointer and length. Returns `None` if no slot is available for it.
+pub(crate) fn lease_response_slot(data: &[u8]) -> Option<(u64, *mut u8, usize)> {
+    let ring = response_ring().lock().clone()?;
+    let slot = ring.slots.lease(data.len())?;
+    let ptr = (ring.base + slot * ring.slots.slot_size) as *mut u8;
+    // SAFETY: the slot lies within the ring's buffer, which the lease keeps alive, and is leased
+    // to this response only.
+    unsafe { std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len()) };
+    let id = crate::jni_client::next_native_buffer_id();
+    slot_leases().insert(id, (ring, slot));
+    Some((id, ptr, data.len()))
+}
+
+/// Returns the slot leased under `id` to its ring. Returns `false` if `id` isn't a slot lease.
+pub(crate) fn release_response_slot(id: u64) -> bool {
+    match slot_leases().remove(&id) {
+        Some((_, (ring, slot))) => {
+            ring.slots.release(slot);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns the `length` bytes at `offset` of the direct `buffer`, or `None` if the buffer isn't
+/// direct.
+///
+/// # Safety
+///
+/// The returned slice borrows the buffer's memory: it must not outlive the JNI call the buffer was
+/// passed to, and Java must not write to the buffer during the call.
+pub(crate) unsafe fn direct_buffer_slice<'a>(
+    env: &mut JNIEnv,
+    buffer: &JByteBuffer,
+    offset: usize,
+    length: usize,
+) -> Option<Result<&'a [u8], String>> {
+    let (Ok(base), Ok(capacity)) = (
+        env.get_direct_buffer_address(buffer),
+        env.get_direct_buffer_capacity(buffer),
+    ) else {
+        return None;
+    };
+    if offset.checked_add(length).is_none_or(|end| end > capacity) {
+        return Some(Err(format!(
+            "Request range {offset}..{} is out of the buffer's capacity of {capacity} bytes",
+            offset.saturating_add(length)
+        )));
+    }
+    Some(Ok(unsafe {
+        std::slice::from_raw_parts(base.add(offset), length)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SlotAllocator;
+
+    #[test]
+    fn slot_allocator_rejects_buffers_smaller_than_a_slot() {
+        assert!(SlotAllocator::new(1024, 0).is_none());
+        assert!(SlotAllocator::new(1024, 2048).is_none());
+    }
+
+    #[test]
+    fn slot_allocator_leases_each_slot_once() {
+        let slots = SlotAllocator::new(3 * 1024 + 512, 1024).unwrap();
+        assert_eq!(slots.lease(1025), None);
+        assert_eq!(slots.lease(1024), Some(0));
+        assert_eq!(slots.lease(1), Some(1));
+        assert_eq!(slots.lease(1), Some(2));
+        assert_eq!(slots.lease(1), None);
+
+        slots.release(1);
+        assert_eq!(slots.lease(1), Some(1));
+    }
+}
@@ -5,6 +5,7 @@ use anyhow::Result;
 use dashmap::DashMap;
 use glide_core::client::Client as GlideClient;
 use glide_core::client::ConnectionRequest;
+use glide_core::client::MemoryReservation;
 use glide_core::errors::{error_message, error_type};
 use jni::JNIEnv;
 use jni::JavaVM;
@@ -96,8 +97,13 @@ fn get_native_buffer_registry() -> &'static dashmap::DashMap<u64, Vec<u8>> {
     NATIVE_BUFFER_REGISTRY.get_or_init(dashmap::DashMap::new)
 }
 
+/// Allocates an id for memory freed by the Java Cleaner through `freeNativeBuffer`.
+pub(crate) fn next_native_buffer_id() -> u64 {
+    NEXT_NATIVE_BUFFER_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+}
+
 pub fn register_native_buffer(bytes: Vec<u8>) -> (u64, *mut u8, usize) {
-    let id = NEXT_NATIVE_BUFFER_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let id = next_native_buffer_id();
     let registry = get_native_buffer_registry();
     registry.insert(id, bytes);
     // Obtain stable pointer/len from stored Vec
@@ -109,7 +115,7 @@ pub fn register_native_buffer(bytes: Vec<u8>) -> (u64, *mut u8, usize) {
 
 pub fn free_native_buffer(id: u64) -> bool {
     let registry = get_native_buffer_registry();
-    registry.remove(&id).is_some()
+    registry.remove(&id).is_some() || crate::direct_buffers::release_response_slot(id)
 }
 
 fn get_timed_out_callbacks() -> &'static dashmap::DashMap<jlong, ()> {
@@ -160,6 +166,18 @@ pub(crate) fn get_handle_table() -> &'static JniHandleTable {
     JNI_HANDLE_TABLE.get_or_init(|| Arc::new(DashMap::new()))
 }
 
+/// Accounts a successful response against the memory budget of the handle's client, until the
+/// returned reservation is dropped.
+pub(crate) fn reserve_response_memory(
+    handle_id: u64,
+    result: &CallbackResult,
+) -> Option<MemoryReservation> {
+    let value = result.as_ref().ok()?;
+    get_handle_table()
+        .get(&handle_id)?
+        .reserve_response_memory(value)
+}
+
 pub(crate) fn get_pending_map() -> &'static PendingMap {
     PENDING_CONFIGS.get_or_init(|| Arc::new(DashMap::new()))
 }
@@ -299,6 +317,7 @@ pub(crate) struct MethodCache {
     async_handle_table_class: GlobalRef,
     complete_callback_method: JStaticMethodID,
     complete_error_with_code_method: JStaticMethodID,
+    exception_for_error_code_method: JStaticMethodID,
     fail_all_method: JStaticMethodID,
 }
 
@@ -337,6 +356,14 @@ pub(crate) fn get_method_cache(env: &mut JNIEnv) -> Result<MethodCache> {
             anyhow::anyhow!("Failed to get completeCallbackWithErrorCode method ID: {e}")
         })?;
 
+    let exception_for_error_code_method = env
+        .get_static_method_id(
+            &class,
+            "exceptionForErrorCode",
+            "(ILjava/lang/String;)Ljava/lang/RuntimeException;",
+        )
+        .map_err(|e| anyhow::anyhow!("Failed to get exceptionForErrorCode method ID: {e}"))?;
+
     let fail_all_method = env
         .get_static_method_id(&class, "failAllWithError", "(Ljava/lang/String;)V")
         .map_err(|e| anyhow::anyhow!("Failed to get failAllWithError method ID: {e}"))?;
@@ -345,6 +372,7 @@ pub(crate) fn get_method_cache(env: &mut JNIEnv) -> Result<MethodCache> {
         async_handle_table_class: global_class,
         complete_callback_method,
         complete_error_with_code_method,
+        exception_for_error_code_method,
         fail_all_method,
     };
 
@@ -357,8 +385,24 @@ pub(crate) fn get_method_cache(env: &mut JNIEnv) -> Result<MethodCache> {
     Ok(method_cache)
 }
 
-/// Callback job type handled by dedicated callback workers
-type CallbackJob = (Arc<JavaVM>, jlong, CallbackResult, bool);
+/// The response a callback is completed with.
+pub(crate) enum CallbackResponse {
+    /// The result of a single request.
+    Single(CallbackResult),
+    /// The results of several commands. The callback completes with an `Object[]`, where a failed
+    /// command's result is the exception it would have failed with on its own.
+    PerCommand(Vec<CallbackResult>),
+}
+
+/// Callback job type handled by dedicated callback workers. The response's memory budget
+/// reservations are released once the Java future is completed.
+type CallbackJob = (
+    Arc<JavaVM>,
+    jlong,
+    CallbackResponse,
+    bool,
+    Vec<MemoryReservation>,
+);
 
 /// Global unbounded callback queue sender
 static CALLBACK_SENDER: std::sync::OnceLock<Sender<CallbackJob>> = std::sync::OnceLock::new();
@@ -401,7 +445,8 @@ pub fn init_callback_workers() -> &'static Sender<CallbackJob> {
                             let guard = rx_clone.lock().unwrap();
                             guard.recv().ok()
                         };
-                        let Some((_, callback_id, result, binary_mode)) = job_opt else {
+                        let Some((_, callback_id, result, binary_mode, _response_memory)) = job_opt
+                        else {
                             break;
                         };
 
@@ -421,7 +466,7 @@ pub fn init_callback_workers() -> &'static Sender<CallbackJob> {
 fn process_callback_job_with_env(
     env: &mut JNIEnv,
     callback_id: jlong,
-    result: CallbackResult,
+    response: CallbackResponse,
     binary_mode: bool,
 ) {
     if take_timed_out_callback(callback_id) {
@@ -436,8 +481,8 @@ fn process_callback_job_with_env(
         return;
     }
 
-    match result {
-        Ok(server_value) => {
+    match response {
+        CallbackResponse::Single(Ok(server_value)) => {
             let _ = env.push_local_frame(16);
 
             let java_result = if should_use_direct_buffer(&server_value) {
@@ -445,46 +490,15 @@ fn process_callback_job_with_env(
             } else {
                 crate::resp_value_to_java(env, server_value, !binary_mode)
             };
+            complete_with_java_result(env, callback_id, java_result);
+        }
+        CallbackResponse::PerCommand(results) => {
+            let _ = env.push_local_frame(16);
 
-            if take_timed_out_callback(callback_id) {
-                let _ = unsafe { env.pop_local_frame(&JObject::null()) };
-                return;
-            }
-
-            match java_result {
-                Ok(java_result) => {
-                    if let Err(e) = complete_java_callback(env, callback_id, &java_result) {
-                        log::error!("JNI completion failed for callback {callback_id}: {e}");
-                        let _ = env.exception_clear();
-                        invalidate_jni_caches();
-                        fail_all_pending_futures(
-                            env,
-                            "JNI callback completion failed — cached method IDs may be stale",
-                        );
-                    }
-                }
-                Err(e) => {
-                    let error_code = 0;
-                    let error_msg = format!("Response conversion failed: {e}");
-                    if let Err(e2) = complete_java_callback_with_error_code(
-                        env,
-                        callback_id,
-                        error_code,
-                        &error_msg,
-                    ) {
-                        log::error!("JNI error completion failed for callback {callback_id}: {e2}");
-                        let _ = env.exception_clear();
-                        invalidate_jni_caches();
-                        fail_all_pending_futures(
-                            env,
-                            "JNI error callback completion failed — cached method IDs may be stale",
-                        );
-                    }
-                }
-            }
-            let _ = unsafe { env.pop_local_frame(&JObject::null()) };
+            let java_result = per_command_results_to_java(env, results, !binary_mode);
+            complete_with_java_result(env, callback_id, java_result);
         }
-        Err(server_err) => {
+        CallbackResponse::Single(Err(server_err)) => {
             if take_timed_out_callback(callback_id) {
                 return;
             }
@@ -506,6 +520,97 @@ fn process_callback_job_with_env(
     }
 }
 
+/// Completes the callback with the converted response, or with the conversion's error, and pops
+/// the local frame pushed for the conversion.
+fn complete_with_java_result(
+    env: &mut JNIEnv,
+    callback_id: jlong,
+    java_result: std::result::Result<JObject, crate::errors::FFIError>,
+) {
+    if take_timed_out_callback(callback_id) {
+        let _ = unsafe { env.pop_local_frame(&JObject::null()) };
+        return;
+    }
+
+    match java_result {
+        Ok(java_result) => {
+            if let Err(e) = complete_java_callback(env, callback_id, &java_result) {
+                log::error!("JNI completion failed for callback {callback_id}: {e}");
+                let _ = env.exception_clear();
+                invalidate_jni_caches();
+                fail_all_pending_futures(
+                    env,
+                    "JNI callback completion failed — cached method IDs may be stale",
+                );
+            }
+        }
+        Err(e) => {
+            let error_code = 0;
+            let error_msg = format!("Response conversion failed: {e}");
+            if let Err(e2) =
+                complete_java_callback_with_error_code(env, callback_id, error_code, &error_msg)
+            {
+                log::error!("JNI error completion failed for callback {callback_id}: {e2}");
+                let _ = env.exception_clear();
+                invalidate_jni_caches();
+                fail_all_pending_futures(
+                    env,
+                    "JNI error callback completion failed — cached method IDs may be stale",
+                );
+            }
+        }
+    }
+    let _ = unsafe { env.pop_local_frame(&JObject::null()) };
+}
+
+/// Converts the results of several commands into an `Object[]`, where a failed command's result is
+/// the exception its error code maps to, as if it had been sent on its own.
+fn per_command_results_to_java<'local>(
+    env: &mut JNIEnv<'local>,
+    results: Vec<CallbackResult>,
+    encoding_utf8: bool,
+) -> std::result::Result<JObject<'local>, crate::errors::FFIError> {
+    let items = env.new_object_array(results.len() as i32, "java/lang/Object", JObject::null())?;
+    for (i, result) in results.into_iter().enumerate() {
+        let item = match result {
+            Ok(value) => crate::resp_value_to_java(env, value, encoding_utf8)?,
+            Err(err) => exception_for_error(env, &err)?,
+        };
+        env.set_object_array_element(&items, i as i32, &item)?;
+        env.delete_local_ref(item)?;
+    }
+    Ok(items.into())
+}
+
+/// Creates the Java exception a request failing with `err` completes with.
+fn exception_for_error<'local>(
+    env: &mut JNIEnv<'local>,
+    err: &ServerError,
+) -> jni::errors::Result<JObject<'local>> {
+    let method_cache = get_method_cache(env).map_err(|e| {
+        log::error!("Failed to get the method cache: {e}");
+        jni::errors::Error::MethodNotFound {
+            name: "exceptionForErrorCode".to_string(),
+            sig: "(ILjava/lang/String;)Ljava/lang/RuntimeException;".to_string(),
+        }
+    })?;
+    let message = env.new_string(error_message(err))?;
+    let exception = unsafe {
+        env.call_static_method_unchecked(
+            &method_cache.async_handle_table_class,
+            method_cache.exception_for_error_code_method,
+            jni::signature::ReturnType::Object,
+            &[
+                JValue::Int(error_type(err) as i32).as_jni(),
+                JValue::Object(&message).as_jni(),
+            ],
+        )
+    }?
+    .l()?;
+    env.delete_local_ref(message)?;
+    Ok(exception)
+}
+
 /// Enqueue callback job to dedicated workers.
 /// If the channel is dead (all workers terminated), sweeps all pending futures with error.
 pub fn complete_callback(
@@ -513,9 +618,62 @@ pub fn complete_callback(
     callback_id: jlong,
     result: CallbackResult,
     binary_mode: bool,
+) {
+    complete_callback_with_memory(jvm, callback_id, result, binary_mode, None);
+}
+
+/// Like [`complete_callback`], holding the response's memory budget reservation until the Java
+/// future is completed.
+pub fn complete_callback_with_memory(
+    jvm: Arc<JavaVM>,
+    callback_id: jlong,
+    result: CallbackResult,
+    binary_mode: bool,
+    response_memory: Option<MemoryReservation>,
+) {
+    send_callback_job(
+        jvm,
+        callback_id,
+        CallbackResponse::Single(result),
+        binary_mode,
+        response_memory.into_iter().collect(),
+    );
+}
+
+/// Like [`complete_callback_with_memory`], completing the callback with the results of several
+/// commands. Failed commands keep their error types, as their results are the exceptions they
+/// would have failed with on their own.
+pub fn complete_callback_with_results(
+    jvm: Arc<JavaVM>,
+    callback_id: jlong,
+    results: Vec<CallbackResult>,
+    binary_mode: bool,
+    response_memory: Vec<MemoryReservation>,
+) {
+    send_callback_job(
+        jvm,
+        callback_id,
+        CallbackResponse::PerCommand(results),
+        binary_mode,
+        response_memory,
+    );
+}
+
+fn send_callback_job(
+    jvm: Arc<JavaVM>,
+    callback_id: jlong,
+    response: CallbackResponse,
+    binary_mode: bool,
+    response_memory: Vec<MemoryReservation>,
 ) {
     let sender = init_callback_workers();
-    if let Err(e) = sender.send((jvm.clone(), callback_id, result, binary_mode)) {
+    if let Err(e) = sender.send((
+        jvm.clone(),
+        callback_id,
+        response,
+        binary_mode,
+        response_memory,
+    )) {
         log::error!("Callback channel dead, sweeping all pending futures: {e}");
         // Workers are dead — sweep the entire AsyncRegistry table
         if let Ok(mut env) = jvm.attach_current_thread_as_daemon() {
@@ -712,7 +870,9 @@ fn create_direct_byte_buffer<'local>(
 ) -> Result<JObject<'local>, crate::errors::FFIError> {
     match value {
         redis::Value::BulkString(data) => {
-            let (id, ptr, len) = register_native_buffer(data);
+            // Prefer a slot of the registered response ring, avoiding a native allocation.
+            let (id, ptr, len) = crate::direct_buffers::lease_response_slot(&data)
+                .unwrap_or_else(|| register_native_buffer(data));
             let bb = unsafe { env.new_direct_byte_buffer(ptr.cast(), len)? };
             // Register Java-side cleaner to free native buffer when GC'd
             let obj: JObject = bb.into();
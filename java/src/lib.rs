@@ -19,7 +19,8 @@ use protobuf::Message;
 use jni::JNIEnv;
 use jni::errors::Error as JniError;
 use jni::objects::{
-    GlobalRef, JByteArray, JClass, JMethodID, JObject, JObjectArray, JStaticMethodID, JString,
+    GlobalRef, JByteArray, JByteBuffer, JClass, JMethodID, JObject, JObjectArray, JStaticMethodID,
+    JString,
 };
 use jni::sys::{jint, jlong};
 use parking_lot::Mutex;
@@ -28,6 +29,7 @@ use std::str::FromStr;
 use std::sync::{Arc, OnceLock};
 
 mod address_resolver;
+mod direct_buffers;
 mod errors;
 mod jni_client;
 mod linked_hashmap;
@@ -181,12 +183,21 @@ fn parse_request_bytes(
         }
     };
 
+    parse_request(env, &raw_bytes, callback_id)
+}
+
+/// Parse a serialized CommandRequest, completing the callback with an error on failure.
+fn parse_request(
+    env: &mut JNIEnv,
+    raw_bytes: &[u8],
+    callback_id: jlong,
+) -> Option<protobuf_bridge::CommandRequest> {
     if raw_bytes.is_empty() {
         complete_callback_with_error_on_caller(env, callback_id, "Empty request bytes");
         return None;
     }
 
-    match protobuf_bridge::parse_command_request(&raw_bytes) {
+    match protobuf_bridge::parse_command_request(raw_bytes) {
         Ok(r) => Some(r),
         Err(e) => {
             let msg = format!("Failed to parse command request: {e}");
@@ -216,6 +227,68 @@ fn get_jvm_or_complete_error(
     }
 }
 
+/// Synchronously checks that the client can take `request_count` more requests, completing the
+/// callback with an error and returning false otherwise.
+fn check_capacity_or_complete_error(
+    env: &mut JNIEnv,
+    handle_id: u64,
+    callback_id: jlong,
+    request_count: isize,
+) -> bool {
+    // Synchronous inflight check: reject immediately if at capacity.
+    // This prevents Java threads from parking on futures that would be
+    // rejected asynchronously, avoiding thread explosion under memory pressure.
+    let handle_table = jni_client::get_handle_table();
+    if let Some(client_ref) = handle_table.get(&handle_id) {
+        if client_ref.available_inflight_count() < request_count {
+            drop(client_ref);
+            jni_client::complete_error_sync(
+                env,
+                callback_id,
+                "Client reached maximum inflight requests",
+                0,
+            );
+            return false;
+        }
+        // Synchronous circuit breaker check: reject immediately if core is unhealthy.
+        if !client_ref.is_circuit_breaker_healthy() {
+            drop(client_ref);
+            jni_client::complete_error_sync(
+                env,
+                callback_id,
+                "Client circuit breaker is open - core unhealthy",
+                4,
+            );
+            return false;
+        }
+    }
+    true
+}
+
+/// Spawn the execution of a parsed CommandRequest, completing the callback with an error
+/// instead if the client is at capacity or unhealthy.
+fn submit_command_request(
+    env: &mut JNIEnv,
+    client_ptr: jlong,
+    command_request: protobuf_bridge::CommandRequest,
+    callback_id: jlong,
+    jvm: Arc<jni::JavaVM>,
+    expect_utf8: bool,
+) {
+    let handle_id = client_ptr as u64;
+    if !check_capacity_or_complete_error(env, handle_id, callback_id, 1) {
+        return;
+    }
+
+    get_runtime().spawn(execute_command_request_and_complete(
+        handle_id,
+        command_request,
+        callback_id,
+        jvm,
+        expect_utf8,
+    ));
+}
+
 // Internal helper: execute a parsed CommandRequest and complete Java callback
 async fn execute_command_request_and_complete(
     handle_id: u64,
@@ -224,7 +297,69 @@ async fn execute_command_request_and_complete(
     jvm: std::sync::Arc<jni::JavaVM>,
     expect_utf8: bool,
 ) {
-    let result: Result<redis::Value, redis::RedisError> = async {
+    let result = execute_command_request(handle_id, command_request, callback_id).await;
+    let binary_mode = !expect_utf8;
+    let response_memory = jni_client::reserve_response_memory(handle_id, &result);
+    jni_client::complete_callback_with_memory(
+        jvm,
+        callback_id,
+        result,
+        binary_mode,
+        response_memory,
+    );
+}
+
+// Internal helper: execute several parsed CommandRequests concurrently and complete the Java
+// callback once with an array of their results, in request order. Failed requests are
+// represented by the exception of their error type, so one failure doesn't fail the others.
+async fn execute_command_requests_and_complete(
+    handle_id: u64,
+    command_requests: Vec<protobuf_bridge::CommandRequest>,
+    callback_id: jlong,
+    jvm: std::sync::Arc<jni::JavaVM>,
+    expect_utf8: bool,
+) {
+    let tasks: Vec<_> = command_requests
+        .into_iter()
+        .map(|command_request| {
+            get_runtime().spawn(execute_command_request(
+                handle_id,
+                command_request,
+                callback_id,
+            ))
+        })
+        .collect();
+    let mut results = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        let result = task.await.unwrap_or_else(|e| {
+            Err(redis::RedisError::from((
+                redis::ErrorKind::ClientError,
+                "Command task failed",
+                e.to_string(),
+            )))
+        });
+        results.push(result);
+    }
+    let response_memory = results
+        .iter()
+        .filter_map(|result| jni_client::reserve_response_memory(handle_id, result))
+        .collect();
+    jni_client::complete_callback_with_results(
+        jvm,
+        callback_id,
+        results,
+        !expect_utf8,
+        response_memory,
+    );
+}
+
+// Internal helper: execute a parsed CommandRequest
+async fn execute_command_request(
+    handle_id: u64,
+    command_request: protobuf_bridge::CommandRequest,
+    callback_id: jlong,
+) -> Result<redis::Value, redis::RedisError> {
+    async {
         let mut client = jni_client::ensure_client_for_handle(handle_id)
             .await
             .map_err(|e| {
@@ -284,6 +419,15 @@ async fn execute_command_request_and_complete(
                 } else {
                     None
                 };
+                let routing = protobuf_bridge::inline_key_route(routing, command, &cmd).map_err(
+                    |e| {
+                        redis::RedisError::from((
+                            redis::ErrorKind::ClientError,
+                            "Routing error",
+                            e.to_string(),
+                        ))
+                    },
+                )?;
 
                 let send_start = std::time::Instant::now();
                 let exec = client.send_command(&mut cmd, routing).await;
@@ -450,10 +594,7 @@ async fn execute_command_request_and_complete(
             ))),
         }
     }
-    .await;
-
-    let binary_mode = !expect_utf8;
-    jni_client::complete_callback(jvm, callback_id, result, binary_mode);
+    .await
 }
 
 /// Configuration for OpenTelemetry integration in the Java client.
@@ -911,6 +1052,31 @@ pub extern "system" fn Java_glide_ffi_resolvers_ScriptResolver_storeScript<'loca
     .unwrap_or(JString::<'_>::default())
 }
 
+/// Sets the process-wide runtime configuration from a serialized `RuntimeConfig` message.
+/// Returns null on success, or the error message.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_ffi_resolvers_RuntimeConfigResolver_initRuntimeConfig<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    config: JByteArray<'local>,
+) -> JString<'local> {
+    run_ffi(|| {
+        fn init_runtime_config<'a>(
+            env: &mut JNIEnv<'a>,
+            config: JByteArray<'a>,
+        ) -> Result<JString<'a>, FFIError> {
+            let bytes = env.convert_byte_array(&config)?;
+            match glide_core::runtime_config::GlideRuntimeConfig::initialize_from_protobuf(&bytes) {
+                Ok(()) => Ok(JString::default()),
+                Err(err) => Ok(env.new_string(err)?),
+            }
+        }
+        let result = init_runtime_config(&mut env, config);
+        handle_errors(&mut env, result)
+    })
+    .unwrap_or(JString::<'_>::default())
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_glide_ffi_resolvers_ScriptResolver_dropScript<'local>(
     mut env: JNIEnv<'local>,
@@ -1244,6 +1410,125 @@ pub extern "system" fn Java_glide_ffi_resolvers_StatisticsResolver_getStatistics
         &format!("{}", Telemetry::subscription_last_sync_timestamp()),
     );
 
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "latency_breakdown_samples",
+        &format!("{}", Telemetry::latency_breakdown_samples()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "total_queue_time_us",
+        &format!("{}", Telemetry::total_queue_time_us()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "total_network_time_us",
+        &format!("{}", Telemetry::total_network_time_us()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "total_decode_time_us",
+        &format!("{}", Telemetry::total_decode_time_us()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "hot_key_warnings",
+        &format!("{}", Telemetry::hot_key_warnings()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "memory_budget_bytes_in_use",
+        &format!("{}", Telemetry::memory_budget_bytes_in_use()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "memory_budget_rejections",
+        &format!("{}", Telemetry::memory_budget_rejections()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "process_memory_bytes",
+        &format!("{}", Telemetry::process_memory_bytes()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "soft_memory_limit_rejections",
+        &format!("{}", Telemetry::soft_memory_limit_rejections()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "large_requests",
+        &format!("{}", Telemetry::large_requests()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "large_responses",
+        &format!("{}", Telemetry::large_responses()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "health_checks",
+        &format!("{}", Telemetry::health_checks()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "health_check_failures",
+        &format!("{}", Telemetry::health_check_failures()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "health_check_evictions",
+        &format!("{}", Telemetry::health_check_evictions()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "unhealthy_nodes",
+        &format!("{}", Telemetry::unhealthy_nodes()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "total_connection_errors",
+        &format!("{}", Telemetry::total_connection_errors()),
+    );
+
+    linked_hashmap::put_strings(
+        &mut env,
+        &mut map,
+        "recent_connection_errors",
+        &Telemetry::recent_connection_errors_json(),
+    );
+
     map
 }
 
@@ -1468,6 +1753,11 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_createClient(
 
         // Convert protobuf to glide_core ConnectionRequest
         let mut connection_request = glide_core::client::ConnectionRequest::from(request);
+        // The clients all run on the runtime of the bridge.
+        if connection_request.runtime_mode != glide_core::client_runtime::RuntimeMode::Dedicated {
+            log::error!("Failed to create client: the runtime mode isn't supported");
+            return Some(0);
+        }
 
         // Cache JVM for push callbacks
         if let Ok(jvm) = env.get_java_vm() {
@@ -1548,45 +1838,14 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeCommandAsync
             return Some(());
         };
 
-        let handle_id = client_ptr as u64;
-
-        // Synchronous inflight check: reject immediately if at capacity.
-        // This prevents Java threads from parking on futures that would be
-        // rejected asynchronously, avoiding thread explosion under memory pressure.
-        {
-            let handle_table = jni_client::get_handle_table();
-            if let Some(client_ref) = handle_table.get(&handle_id) {
-                if client_ref.available_inflight_count() <= 0 {
-                    drop(client_ref);
-                    jni_client::complete_error_sync(
-                        &mut env,
-                        callback_id,
-                        "Client reached maximum inflight requests",
-                        0,
-                    );
-                    return Some(());
-                }
-                // Synchronous circuit breaker check: reject immediately if core is unhealthy.
-                if !client_ref.is_circuit_breaker_healthy() {
-                    drop(client_ref);
-                    jni_client::complete_error_sync(
-                        &mut env,
-                        callback_id,
-                        "Client circuit breaker is open - core unhealthy",
-                        4,
-                    );
-                    return Some(());
-                }
-            }
-        }
-
-        get_runtime().spawn(execute_command_request_and_complete(
-            handle_id,
+        submit_command_request(
+            &mut env,
+            client_ptr,
             command_request,
             callback_id,
             jvm,
             true, // executeCommandAsync expects UTF-8 decoding
-        ));
+        );
 
         Some(())
     })
@@ -1901,7 +2160,11 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeBatchAsync(
                         .await;
 
                         let binary_mode = expect_utf8 == 0;
-                        complete_callback(jvm, callback_id, result, binary_mode);
+                        let response_memory = result
+                            .as_ref()
+                            .ok()
+                            .and_then(|value| client.reserve_response_memory(value));
+                        complete_callback_with_memory(jvm, callback_id, result, binary_mode, response_memory);
                     }
                     Err(err) => {
                         let error = Err(redis::RedisError::from((
@@ -1940,41 +2203,78 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeBinaryComman
             return Some(());
         };
 
-        let handle_id = client_ptr as u64;
+        submit_command_request(
+            &mut env,
+            client_ptr,
+            command_request,
+            callback_id,
+            jvm,
+            false, // binary entrypoint expects binary decoding
+        );
 
-        // Synchronous inflight check (same as executeCommandAsync)
-        {
-            let handle_table = jni_client::get_handle_table();
-            if let Some(client_ref) = handle_table.get(&handle_id) {
-                if client_ref.available_inflight_count() <= 0 {
-                    drop(client_ref);
-                    jni_client::complete_error_sync(
-                        &mut env,
-                        callback_id,
-                        "Client reached maximum inflight requests",
-                        0,
-                    );
-                    return Some(());
-                }
-                if !client_ref.is_circuit_breaker_healthy() {
-                    drop(client_ref);
-                    jni_client::complete_error_sync(
-                        &mut env,
-                        callback_id,
-                        "Client circuit breaker is open - core unhealthy",
-                        4,
-                    );
+        Some(())
+    })
+    .unwrap_or(())
+}
+
+/// Execute several commands asynchronously in a single JNI call. The callback is completed once,
+/// with an array holding each command's result - or its error - in request order.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeCommandsAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    client_ptr: jlong,
+    requests: JObjectArray,
+    callback_id: jlong,
+    expect_utf8: jni::sys::jboolean,
+) {
+    run_ffi(|| {
+        let request_count = match env.get_array_length(&requests) {
+            Ok(count) => count,
+            Err(e) => {
+                let msg = format!("Failed to read requests: {e}");
+                complete_callback_with_error_on_caller(&mut env, callback_id, &msg);
+                return Some(());
+            }
+        };
+        let mut command_requests = Vec::with_capacity(request_count as usize);
+        for i in 0..request_count {
+            let request_bytes = match env.get_object_array_element(&requests, i) {
+                Ok(element) => JByteArray::from(element),
+                Err(e) => {
+                    let msg = format!("Failed to read request {i}: {e}");
+                    complete_callback_with_error_on_caller(&mut env, callback_id, &msg);
                     return Some(());
                 }
-            }
+            };
+            let Some(command_request) = parse_request_bytes(&mut env, &request_bytes, callback_id)
+            else {
+                return Some(());
+            };
+            let _ = env.delete_local_ref(request_bytes);
+            command_requests.push(command_request);
         }
+        let Some(jvm) = get_jvm_or_complete_error(&mut env, callback_id, "executeCommandsAsync")
+        else {
+            return Some(());
+        };
 
-        get_runtime().spawn(execute_command_request_and_complete(
+        let handle_id = client_ptr as u64;
+        if !check_capacity_or_complete_error(
+            &mut env,
             handle_id,
-            command_request,
+            callback_id,
+            command_requests.len() as isize,
+        ) {
+            return Some(());
+        }
+
+        get_runtime().spawn(execute_command_requests_and_complete(
+            handle_id,
+            command_requests,
             callback_id,
             jvm,
-            false, // binary entrypoint expects binary decoding
+            expect_utf8 != 0,
         ));
 
         Some(())
@@ -1982,6 +2282,99 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeBinaryComman
     .unwrap_or(())
 }
 
+/// Execute a command whose serialized request is read in place from a direct ByteBuffer.
+/// Returns false, without completing the callback, if the buffer isn't direct or the call fails
+/// before handling the request - the caller then falls back to the byte array entrypoints. The
+/// buffer is only read during the call.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeCommandDirectAsync(
+    mut env: JNIEnv,
+    _class: JClass,
+    client_ptr: jlong,
+    request_buffer: JByteBuffer,
+    offset: jint,
+    length: jint,
+    callback_id: jlong,
+    expect_utf8: jni::sys::jboolean,
+) -> jni::sys::jboolean {
+    run_ffi(|| {
+        let (Ok(offset), Ok(length)) = (usize::try_from(offset), usize::try_from(length)) else {
+            let msg = format!("Invalid request range: offset {offset}, length {length}");
+            complete_callback_with_error_on_caller(&mut env, callback_id, &msg);
+            return Some(1);
+        };
+        // SAFETY: the slice is only used to parse the request, before this call returns.
+        let command_request = match unsafe {
+            direct_buffers::direct_buffer_slice(&mut env, &request_buffer, offset, length)
+        } {
+            None => return Some(0),
+            Some(Err(msg)) => {
+                complete_callback_with_error_on_caller(&mut env, callback_id, &msg);
+                return Some(1);
+            }
+            Some(Ok(raw_bytes)) => parse_request(&mut env, raw_bytes, callback_id),
+        };
+        let Some(command_request) = command_request else {
+            return Some(1);
+        };
+        let Some(jvm) =
+            get_jvm_or_complete_error(&mut env, callback_id, "executeCommandDirectAsync")
+        else {
+            return Some(1);
+        };
+
+        submit_command_request(
+            &mut env,
+            client_ptr,
+            command_request,
+            callback_id,
+            jvm,
+            expect_utf8 != 0,
+        );
+
+        Some(1)
+    })
+    .unwrap_or(0)
+}
+
+/// Register a direct ByteBuffer whose slots of `slot_size` bytes hold large bulk string
+/// responses. Returns false if the buffer isn't direct or is smaller than a slot.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_internal_GlideNativeBridge_registerResponseRing(
+    mut env: JNIEnv,
+    _class: JClass,
+    buffer: JByteBuffer,
+    slot_size: jint,
+) -> jni::sys::jboolean {
+    run_ffi(|| {
+        let Ok(slot_size) = usize::try_from(slot_size) else {
+            return Some(0);
+        };
+        match direct_buffers::register_response_ring(&mut env, &buffer, slot_size) {
+            Ok(registered) => Some(registered as jni::sys::jboolean),
+            Err(e) => {
+                log::error!("Failed to register the response ring: {e}");
+                Some(0)
+            }
+        }
+    })
+    .unwrap_or(0)
+}
+
+/// Stop writing responses into the registered response ring. Responses already written into it
+/// stay valid until they are garbage collected.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_internal_GlideNativeBridge_unregisterResponseRing(
+    _env: JNIEnv,
+    _class: JClass,
+) {
+    run_ffi(|| {
+        direct_buffers::unregister_response_ring();
+        Some(())
+    })
+    .unwrap_or(())
+}
+
 /// Execute a script asynchronously using FFI-imported logic
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeScriptAsync(
@@ -2239,7 +2632,17 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_executeScriptAsync(
                         });
 
                     let binary_mode = expect_utf8 == 0;
-                    complete_callback(jvm, callback_id, result, binary_mode);
+                    let response_memory = result
+                        .as_ref()
+                        .ok()
+                        .and_then(|value| client.reserve_response_memory(value));
+                    complete_callback_with_memory(
+                        jvm,
+                        callback_id,
+                        result,
+                        binary_mode,
+                        response_memory,
+                    );
                 }
                 Err(err) => {
                     let error = Err(redis::RedisError::from((
@@ -2767,6 +3170,7 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_createMonitorClient
                         lib_name: None,
                         cache: None,
                         server_assisted_cache: false,
+                        protocol_fallback: false,
                     }
                 } else {
                     redis::RedisConnectionInfo {
@@ -2783,6 +3187,7 @@ pub extern "system" fn Java_glide_internal_GlideNativeBridge_createMonitorClient
                         lib_name: None,
                         cache: None,
                         server_assisted_cache: false,
+                        protocol_fallback: false,
                     }
                 };
 
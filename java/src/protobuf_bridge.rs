@@ -5,7 +5,6 @@ use anyhow::{Result, anyhow};
 use protobuf::Message;
 use redis::cluster_routing::RoutingInfo;
 use redis::cluster_routing::{MultipleNodeRoutingInfo, Route, SingleNodeRoutingInfo, SlotAddr};
-use redis::cluster_routing::{ResponsePolicy, Routable};
 use redis::{Cmd, RedisError, RedisResult};
 
 // Reuse existing protobuf types from glide-core (no wrapper types needed)
@@ -44,6 +43,21 @@ pub fn create_valkey_command(command: &Command) -> Result<redis::Cmd> {
                 cmd.arg(arg.as_ref());
             }
         }
+        Some(glide_core::command_request::command::Args::InlineCommand(inline)) => {
+            if !matches!(
+                request_type,
+                glide_core::request_type::RequestType::CustomCommand
+            ) {
+                return Err(anyhow!(
+                    "Inline commands must use the CustomCommand request type"
+                ));
+            }
+            let args = glide_core::client::inline_command::tokenize(&inline.text)
+                .map_err(|e| anyhow!("{}", e))?;
+            for arg in args {
+                cmd.arg(arg);
+            }
+        }
         None => {
             return Err(anyhow!(
                 "Failed to get request arguments, no arguments are set"
@@ -64,6 +78,22 @@ pub fn create_valkey_command(command: &Command) -> Result<redis::Cmd> {
     Ok(cmd)
 }
 
+/// Routes an inline command without a route to the node that owns its `key_index` argument, as the
+/// socket listener does. Other commands keep `routing`.
+pub(crate) fn inline_key_route(
+    routing: Option<RoutingInfo>,
+    command: &Command,
+    cmd: &Cmd,
+) -> RedisResult<Option<RoutingInfo>> {
+    match (&routing, &command.args) {
+        (None, Some(glide_core::command_request::command::Args::InlineCommand(inline))) => inline
+            .key_index
+            .map(|index| glide_core::client::inline_command::key_routing(cmd, index as usize))
+            .transpose(),
+        _ => Ok(routing),
+    }
+}
+
 fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> Result<SlotAddr, RedisError> {
     slot_type
         .enum_value()
@@ -98,16 +128,13 @@ fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> Result<SlotA
 /// * `Err(RedisError)` if the route is invalid or cannot be converted.
 pub(crate) fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<RoutingInfo>> {
     use glide_core::command_request::routes::Value;
+    let aggregation = route.aggregation;
     let route = match route.value {
         Some(route) => route,
         None => return Ok(None),
     };
-    let get_response_policy = |cmd: Option<&Cmd>| {
-        cmd.and_then(|cmd| {
-            cmd.command()
-                .and_then(|cmd| ResponsePolicy::for_command(&cmd))
-        })
-    };
+    let get_response_policy =
+        |cmd: Option<&Cmd>| glide_core::multi_node_response_policy(aggregation, cmd);
     match route {
         Value::SimpleRoutes(simple_route) => {
             let simple_route = match simple_route.enum_value() {
@@ -123,11 +150,11 @@ pub(crate) fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<
             match simple_route {
                 SimpleRoutes::AllNodes => Ok(Some(RoutingInfo::MultiNode((
                     MultipleNodeRoutingInfo::AllNodes,
-                    get_response_policy(cmd),
+                    get_response_policy(cmd)?,
                 )))),
                 SimpleRoutes::AllPrimaries => Ok(Some(RoutingInfo::MultiNode((
                     MultipleNodeRoutingInfo::AllMasters,
-                    get_response_policy(cmd),
+                    get_response_policy(cmd)?,
                 )))),
                 SimpleRoutes::Random => {
                     Ok(Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)))
@@ -166,3 +193,49 @@ pub(crate) fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glide_core::command_request::command::Args;
+    use glide_core::command_request::{InlineCommand, RequestType};
+    use redis::cluster_topology::get_slot;
+
+    fn inline(request_type: RequestType, text: &str, key_index: Option<u32>) -> Command {
+        Command {
+            request_type: request_type.into(),
+            args: Some(Args::InlineCommand(InlineCommand {
+                text: text.into(),
+                key_index,
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn inline_commands_require_the_custom_command_request_type() {
+        assert!(create_valkey_command(&inline(RequestType::Get, "GET key", None)).is_err());
+        assert!(
+            create_valkey_command(&inline(RequestType::CustomCommand, "GET key", None)).is_ok()
+        );
+    }
+
+    #[test]
+    fn inline_commands_are_routed_by_their_key_index() {
+        let command = inline(RequestType::CustomCommand, "SET key value", Some(1));
+        let cmd = create_valkey_command(&command).unwrap();
+        assert_eq!(
+            inline_key_route(None, &command, &cmd).unwrap(),
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(get_slot(b"key"), SlotAddr::Master))
+            ))
+        );
+        // An explicit route is kept.
+        let random = Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random));
+        assert_eq!(
+            inline_key_route(random.clone(), &command, &cmd).unwrap(),
+            random
+        );
+    }
+}
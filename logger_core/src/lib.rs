@@ -1,21 +1,32 @@
 /**
  * Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
  */
+use file_rotate::{
+    ContentLimit, FileRotate, TimeFrequency, compression::Compression, suffix::AppendCount,
+};
 use once_cell::sync::OnceCell;
 use std::{
+    fmt::{self, Write as _},
+    io,
     path::{Path, PathBuf},
-    sync::RwLock,
+    sync::{
+        Mutex, MutexGuard, RwLock,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+    time::Duration,
 };
-use tracing::{self, event};
+use tracing::{self, Event, Subscriber, event, field::Field, field::Visit};
 use tracing_appender::rolling::{RollingFileAppender, RollingWriter, Rotation};
 use tracing_subscriber::{
     Registry,
     filter::Filtered,
     fmt::{
-        Layer,
-        format::{DefaultFields, Format},
+        FmtContext, FormatEvent, FormatFields, Layer,
+        format::{DefaultFields, Format, Writer},
+        time::{FormatTime, SystemTime},
     },
     layer::Layered,
+    registry::LookupSpan,
 };
 
 use tracing_subscriber::{
@@ -28,13 +39,17 @@ use tracing_subscriber::{
 use std::str::FromStr;
 
 // Layer-Filter pair determines whether a log will be collected
-type InnerFiltered = Filtered<Layer<Registry>, LevelFilter, Registry>;
+type InnerFiltered = Filtered<
+    Layer<Registry, DefaultFields, GlideFormat, fn() -> io::Stdout>,
+    LevelFilter,
+    Registry,
+>;
 // A Reloadable pair of layer-filter
 type InnerLayered = Layered<reload::Layer<InnerFiltered, Registry>, Registry>;
 // A reloadable layer of subscriber to a rolling file
 type FileReload = Handle<
     Filtered<
-        Layer<InnerLayered, DefaultFields, Format, LazyRollingFileAppender>,
+        Layer<InnerLayered, DefaultFields, GlideFormat, LazyRollingFileAppender>,
         LevelFilter,
         InnerLayered,
     >,
@@ -44,6 +59,8 @@ type FileReload = Handle<
 pub struct Reloads {
     console_reload: RwLock<reload::Handle<InnerFiltered, Registry>>,
     file_reload: RwLock<FileReload>,
+    // Whether the logs are written to a file rather than the console
+    file_active: AtomicBool,
 }
 
 pub struct InitiateOnce {
@@ -54,22 +71,55 @@ pub static INITIATE_ONCE: InitiateOnce = InitiateOnce {
     init_once: OnceCell::new(),
 };
 
+// The level of the logs collected, as set by the last [init] or [set_level]
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
 const FILE_DIRECTORY: &str = "glide-logs";
 const ENV_GLIDE_LOG_DIR: &str = "GLIDE_LOG_DIR";
 
-/// Wraps [RollingFileAppender] to defer initialization until logging is required,
+/// How the log file is rotated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileRotation {
+    /// A new file every hour, with the date and hour appended to its name. Files are never deleted.
+    Hourly,
+    /// Rotate the file once it reaches `max_bytes`, keeping the last `max_files` rotated files.
+    Size { max_bytes: usize, max_files: usize },
+    /// Rotate the file every `frequency`, keeping the last `max_files` rotated files.
+    Time {
+        frequency: RotationFrequency,
+        max_files: usize,
+    },
+}
+
+/// How often a time-rotated log file is rotated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationFrequency {
+    Hourly,
+    Daily,
+    Weekly,
+}
+
+/// Wraps the file appender to defer initialization until logging is required,
 /// allowing [init] to disable file logging on read-only filesystems.
-/// This is needed because [RollingFileAppender] tries to create the log directory on initialization.
+/// This is needed because the appenders try to create the log directory on initialization.
 struct LazyRollingFileAppender {
-    file_appender: OnceCell<RollingFileAppender>,
-    rotation: Rotation,
+    file_appender: OnceCell<FileAppender>,
+    rotation: FileRotation,
     directory: PathBuf,
     filename_prefix: PathBuf,
 }
 
+enum FileAppender {
+    Rolling(RollingFileAppender),
+    // Rotated files are named `<file>.1`, `<file>.2`, ..., the greater the older.
+    Rotating(Mutex<FileRotate<AppendCount>>),
+    // The log directory couldn't be created.
+    Unavailable,
+}
+
 impl LazyRollingFileAppender {
     fn new(
-        rotation: Rotation,
+        rotation: FileRotation,
         directory: impl AsRef<Path>,
         filename_prefix: impl AsRef<Path>,
     ) -> LazyRollingFileAppender {
@@ -80,23 +130,203 @@ impl LazyRollingFileAppender {
             filename_prefix: filename_prefix.as_ref().to_path_buf(),
         }
     }
+
+    fn create_appender(&self) -> FileAppender {
+        let (content_limit, max_files) = match &self.rotation {
+            FileRotation::Hourly => {
+                return FileAppender::Rolling(RollingFileAppender::new(
+                    Rotation::HOURLY,
+                    self.directory.clone(),
+                    self.filename_prefix.clone(),
+                ));
+            }
+            FileRotation::Size {
+                max_bytes,
+                max_files,
+            } => (
+                ContentLimit::BytesSurpassed((*max_bytes).max(1)),
+                *max_files,
+            ),
+            FileRotation::Time {
+                frequency,
+                max_files,
+            } => {
+                let frequency = match frequency {
+                    RotationFrequency::Hourly => TimeFrequency::Hourly,
+                    RotationFrequency::Daily => TimeFrequency::Daily,
+                    RotationFrequency::Weekly => TimeFrequency::Weekly,
+                };
+                (ContentLimit::Time(frequency), *max_files)
+            }
+        };
+        if std::fs::create_dir_all(&self.directory).is_err() {
+            return FileAppender::Unavailable;
+        }
+        FileAppender::Rotating(Mutex::new(FileRotate::new(
+            self.directory.join(&self.filename_prefix),
+            AppendCount::new(max_files),
+            content_limit,
+            Compression::None,
+            #[cfg(unix)]
+            None,
+        )))
+    }
+}
+
+/// A writer to the log file.
+enum FileWriter<'a> {
+    Rolling(RollingWriter<'a>),
+    Rotating(MutexGuard<'a, FileRotate<AppendCount>>),
+    Unavailable,
+}
+
+impl io::Write for FileWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            FileWriter::Rolling(writer) => writer.write(buf),
+            FileWriter::Rotating(writer) => writer.write(buf),
+            FileWriter::Unavailable => Ok(buf.len()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            FileWriter::Rolling(writer) => writer.flush(),
+            FileWriter::Rotating(writer) => writer.flush(),
+            FileWriter::Unavailable => Ok(()),
+        }
+    }
 }
 
 impl<'a> tracing_subscriber::fmt::writer::MakeWriter<'a> for LazyRollingFileAppender {
-    type Writer = RollingWriter<'a>;
+    type Writer = FileWriter<'a>;
     fn make_writer(&'a self) -> Self::Writer {
-        let file_appender = self.file_appender.get_or_init(|| {
-            RollingFileAppender::new(
-                self.rotation.clone(),
-                self.directory.clone(),
-                self.filename_prefix.clone(),
-            )
-        });
-        file_appender.make_writer()
+        match self.file_appender.get_or_init(|| self.create_appender()) {
+            FileAppender::Rolling(appender) => FileWriter::Rolling(appender.make_writer()),
+            FileAppender::Rotating(appender) => FileWriter::Rotating(
+                appender
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            ),
+            FileAppender::Unavailable => FileWriter::Unavailable,
+        }
+    }
+}
+
+/// The format of the log entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human readable lines.
+    Text,
+    /// One JSON object per line, with the timestamp, level, target, message and context fields of
+    /// the entry.
+    Json,
+}
+
+static JSON_FORMAT: AtomicBool = AtomicBool::new(false);
+
+/// Sets the format of the log entries written from now on, to the console or to the file.
+pub fn set_format(format: LogFormat) {
+    JSON_FORMAT.store(format == LogFormat::Json, Ordering::Relaxed);
+}
+
+/// Formats the entries as text or JSON, as set by [set_format].
+struct GlideFormat {
+    text: Format,
+}
+
+impl<S, N> FormatEvent<S, N> for GlideFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        if !JSON_FORMAT.load(Ordering::Relaxed) {
+            return self.text.format_event(ctx, writer, event);
+        }
+        let mut timestamp = String::new();
+        SystemTime.format_time(&mut Writer::new(&mut timestamp))?;
+        let metadata = event.metadata();
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+        writeln!(
+            writer,
+            "{{\"timestamp\":{},\"level\":\"{}\",\"target\":{}{}}}",
+            json_string(&timestamp),
+            metadata.level(),
+            json_string(metadata.target()),
+            fields.0
+        )
+    }
+}
+
+/// The fields of an entry, as `,"name":value` JSON members.
+#[derive(Default)]
+struct JsonFields(String);
+
+impl JsonFields {
+    fn push(&mut self, field: &Field, value: impl fmt::Display) {
+        let _ = write!(self.0, ",{}:{value}", json_string(field.name()));
+    }
+}
+
+impl Visit for JsonFields {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        if value.is_finite() {
+            self.push(field, value);
+        } else {
+            self.push(field, json_string(&value.to_string()));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.push(field, value);
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.push(field, value);
     }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.push(field, value);
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, json_string(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, json_string(&format!("{value:?}")));
+    }
+}
+
+/// Quotes and escapes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Level {
     Error = 0,
     Warn = 1,
@@ -106,7 +336,18 @@ pub enum Level {
     Off = 5,
 }
 impl Level {
-    fn to_filter(&self) -> filter::LevelFilter {
+    fn from_u8(level: u8) -> Level {
+        match level {
+            0 => Level::Error,
+            1 => Level::Warn,
+            2 => Level::Info,
+            3 => Level::Debug,
+            4 => Level::Trace,
+            _ => Level::Off,
+        }
+    }
+
+    fn to_filter(self) -> filter::LevelFilter {
         match self {
             Level::Trace => LevelFilter::TRACE,
             Level::Debug => LevelFilter::DEBUG,
@@ -138,10 +379,24 @@ pub fn create_directory_from_env(envname: &str) -> Option<String> {
 // provided by using the global reloadable handle
 // The logger will save only logs of the given level or above.
 pub fn init(minimal_level: Option<Level>, file_name: Option<&str>) -> Level {
+    init_with_rotation(minimal_level, file_name, FileRotation::Hourly)
+}
+
+// Same as [init], with the log file rotated as set by `rotation`.
+pub fn init_with_rotation(
+    minimal_level: Option<Level>,
+    file_name: Option<&str>,
+    rotation: FileRotation,
+) -> Level {
     let level = minimal_level.unwrap_or(Level::Warn);
+    LEVEL.store(level as u8, Ordering::Relaxed);
     let level_filter = level.to_filter();
     let reloads = INITIATE_ONCE.init_once.get_or_init(|| {
         let stdout_fmt = tracing_subscriber::fmt::layer()
+            .event_format(GlideFormat {
+                text: tracing_subscriber::fmt::format(),
+            })
+            .with_writer(io::stdout as fn() -> io::Stdout)
             .with_ansi(true)
             .with_filter(LevelFilter::OFF);
 
@@ -151,12 +406,15 @@ pub fn init(minimal_level: Option<Level>, file_name: Option<&str>) -> Level {
         let logs_dir =
             create_directory_from_env(ENV_GLIDE_LOG_DIR).unwrap_or(FILE_DIRECTORY.to_string());
         let file_appender = LazyRollingFileAppender::new(
-            Rotation::HOURLY,
+            rotation.clone(),
             logs_dir,
             file_name.unwrap_or("output.log"),
         );
 
         let file_fmt = tracing_subscriber::fmt::layer()
+            .event_format(GlideFormat {
+                text: tracing_subscriber::fmt::format(),
+            })
             .with_writer(file_appender)
             .with_filter(LevelFilter::OFF);
         let (file_layer, file_reload) = reload::Layer::new(file_fmt);
@@ -189,10 +447,14 @@ pub fn init(minimal_level: Option<Level>, file_name: Option<&str>) -> Level {
         let reloads: Reloads = Reloads {
             console_reload: RwLock::new(stdout_reload),
             file_reload: RwLock::new(file_reload),
+            file_active: AtomicBool::new(false),
         };
         reloads
     });
 
+    reloads
+        .file_active
+        .store(file_name.is_some(), Ordering::Relaxed);
     match file_name {
         None => {
             let _ = reloads
@@ -212,7 +474,7 @@ pub fn init(minimal_level: Option<Level>, file_name: Option<&str>) -> Level {
             // Check if the environment variable GLIDE_LOG is set
             let logs_dir =
                 create_directory_from_env(ENV_GLIDE_LOG_DIR).unwrap_or(FILE_DIRECTORY.to_string());
-            let file_appender = LazyRollingFileAppender::new(Rotation::HOURLY, logs_dir, file);
+            let file_appender = LazyRollingFileAppender::new(rotation, logs_dir, file);
             let _ = reloads
                 .file_reload
                 .write()
@@ -231,6 +493,47 @@ pub fn init(minimal_level: Option<Level>, file_name: Option<&str>) -> Level {
     level
 }
 
+// Changes the level of the logs collected from now on, keeping them written to the console or to the file
+// set by the last [init]. Initializes the logger to the console if it isn't initialized yet.
+pub fn set_level(level: Level) -> Level {
+    let Some(reloads) = INITIATE_ONCE.init_once.get() else {
+        return init(Some(level), None);
+    };
+    LEVEL.store(level as u8, Ordering::Relaxed);
+    let level_filter = level.to_filter();
+    if reloads.file_active.load(Ordering::Relaxed) {
+        let _ = reloads
+            .file_reload
+            .write()
+            .expect("error reloading file appender")
+            .modify(|layer| *layer.filter_mut() = level_filter);
+    } else {
+        let _ = reloads
+            .console_reload
+            .write()
+            .expect("error reloading stdout")
+            .modify(|layer| *layer.filter_mut() = level_filter);
+    }
+    level
+}
+
+// Whether logs of `level` are collected, as set by the last [init] or [set_level]. Lets callers skip
+// building a log entry that would be dropped.
+pub fn is_enabled(level: Level) -> bool {
+    let collected = Level::from_u8(LEVEL.load(Ordering::Relaxed));
+    level != Level::Off && collected != Level::Off && level as u8 <= collected as u8
+}
+
+// Writes the logs collected from now on to `file_name`, rotated as set by `rotation`, keeping the level
+// set by the last [init] or [set_level].
+pub fn set_file(file_name: &str, rotation: FileRotation) -> Level {
+    init_with_rotation(
+        Some(Level::from_u8(LEVEL.load(Ordering::Relaxed))),
+        Some(file_name),
+        rotation,
+    )
+}
+
 macro_rules! create_log {
     ($name:ident, $uppercase_level:tt) => {
         pub fn $name<Message: AsRef<str>, Identifier: AsRef<str>>(
@@ -364,6 +667,53 @@ macro_rules! log_debug_rate_limited {
     }};
 }
 
+/// Context of a log entry about a request, recorded as separate fields - e.g. the members of a JSON
+/// entry.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogContext<'a> {
+    pub client_id: Option<&'a str>,
+    /// The address the client was created with. In cluster mode, the request may be served by another node.
+    pub seed: Option<&'a str>,
+    pub request_type: Option<&'a str>,
+    pub latency: Option<Duration>,
+}
+
+macro_rules! log_context_event {
+    ($level:ident, $context:expr, $identifier:expr, $message:expr) => {
+        event!(
+            tracing::Level::$level,
+            client_id = $context.client_id,
+            seed = $context.seed,
+            request_type = $context.request_type,
+            latency_us = $context.latency.map(|latency| latency.as_micros() as u64),
+            "{} - {}",
+            $identifier,
+            $message
+        )
+    };
+}
+
+// Same as [log], with the context of the request the log is about.
+pub fn log_with_context<Message: AsRef<str>, Identifier: AsRef<str>>(
+    log_level: Level,
+    log_identifier: Identifier,
+    message: Message,
+    context: &LogContext,
+) {
+    if INITIATE_ONCE.init_once.get().is_none() {
+        init(Some(Level::Warn), None);
+    };
+    let (identifier, message) = (log_identifier.as_ref(), message.as_ref());
+    match log_level {
+        Level::Error => log_context_event!(ERROR, context, identifier, message),
+        Level::Warn => log_context_event!(WARN, context, identifier, message),
+        Level::Info => log_context_event!(INFO, context, identifier, message),
+        Level::Debug => log_context_event!(DEBUG, context, identifier, message),
+        Level::Trace => log_context_event!(TRACE, context, identifier, message),
+        Level::Off => (),
+    }
+}
+
 // Logs the given log, with log_identifier and log level prefixed. If the given log level is below the threshold of given when the logger was initialized, the log will be ignored.
 // log_identifier should be used to add context to a log, and make it easier to connect it to other relevant logs. For example, it can be used to pass a task identifier.
 // If this is called before a logger was initialized the log will not be registered.
@@ -387,6 +737,60 @@ pub fn log<Message: AsRef<str>, Identifier: AsRef<str>>(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_json_string_escaping() {
+        assert_eq!(json_string("plain"), r#""plain""#);
+        assert_eq!(
+            json_string("a \"quote\"\\path\nline\u{1}"),
+            r#""a \"quote\"\\path\nline\u0001""#
+        );
+    }
+
+    #[test]
+    fn test_json_fields_of_an_entry() {
+        struct Capture(String);
+        impl Subscriber for Capture {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+                tracing::span::Id::from_u64(1)
+            }
+            fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+            fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = JsonFields::default();
+                event.record(&mut fields);
+                assert_eq!(fields.0, self.0);
+            }
+            fn enter(&self, _: &tracing::span::Id) {}
+            fn exit(&self, _: &tracing::span::Id) {}
+        }
+
+        let expected = r#","message":"id - msg","client_id":"7","latency_us":1500"#;
+        tracing::subscriber::with_default(Capture(expected.to_string()), || {
+            let context = LogContext {
+                client_id: Some("7"),
+                latency: Some(Duration::from_micros(1500)),
+                ..Default::default()
+            };
+            log_context_event!(INFO, context, "id", "msg");
+        });
+    }
+
+    #[test]
+    fn test_is_enabled_follows_the_collected_level() {
+        LEVEL.store(Level::Info as u8, Ordering::Relaxed);
+        assert!(is_enabled(Level::Error));
+        assert!(is_enabled(Level::Info));
+        assert!(!is_enabled(Level::Debug));
+        assert!(!is_enabled(Level::Off));
+
+        LEVEL.store(Level::Off as u8, Ordering::Relaxed);
+        assert!(!is_enabled(Level::Error));
+        assert!(!is_enabled(Level::Trace));
+    }
+
     #[test]
     fn test_directory_from_env() {
         let dir_path = format!("{}/glide-logs", std::env::temp_dir().display());
@@ -7,11 +7,15 @@ use test_env_helpers::*;
 #[after_all]
 #[before_all]
 mod tests {
-    use logger_core::{init, log_debug, log_trace};
+    use logger_core::{
+        FileRotation, LogContext, LogFormat, init, init_with_rotation, log_debug, log_trace,
+        log_with_context, set_file, set_format,
+    };
     use rand::{Rng, distributions::Alphanumeric};
     use std::{
         fs::{read_dir, read_to_string, remove_dir_all},
         path::Path,
+        time::Duration,
     };
     const FILE_DIRECTORY: &str = "glide-logs";
 
@@ -117,6 +121,88 @@ mod tests {
         assert!(!contents.contains("boo"), "Contents: {contents}");
     }
 
+    #[test]
+    fn log_to_file_rotates_by_size() {
+        let identifier = generate_random_string(10);
+        init_with_rotation(
+            Some(logger_core::Level::Debug),
+            Some(identifier.as_str()),
+            FileRotation::Size {
+                max_bytes: 256,
+                max_files: 2,
+            },
+        );
+        for _ in 0..20 {
+            log_debug(identifier.clone(), "foo");
+        }
+        let files = read_dir(FILE_DIRECTORY)
+            .unwrap()
+            .filter(|entry| {
+                entry
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_str()
+                    .unwrap()
+                    .starts_with(identifier.as_str())
+            })
+            .count();
+        // The active file, and the 2 rotated files that are kept.
+        assert_eq!(files, 3);
+    }
+
+    #[test]
+    fn set_file_keeps_the_level() {
+        let identifier = generate_random_string(10);
+        init(Some(logger_core::Level::Debug), None);
+        set_file(
+            identifier.as_str(),
+            FileRotation::Size {
+                max_bytes: 1 << 20,
+                max_files: 1,
+            },
+        );
+        log_debug(identifier.clone(), "foo");
+        log_trace(identifier.clone(), "boo");
+        let contents = get_file_contents(identifier.as_str());
+        assert!(contents.contains("foo"), "Contents: {contents}");
+        assert!(!contents.contains("boo"), "Contents: {contents}");
+    }
+
+    #[test]
+    fn log_to_file_as_json_with_context() {
+        let identifier = generate_random_string(10);
+        init(Some(logger_core::Level::Debug), Some(identifier.as_str()));
+        set_format(LogFormat::Json);
+        log_with_context(
+            logger_core::Level::Debug,
+            identifier.clone(),
+            "foo",
+            &LogContext {
+                client_id: Some("42"),
+                seed: Some("127.0.0.1:6379"),
+                request_type: Some("Get"),
+                latency: Some(Duration::from_micros(250)),
+            },
+        );
+        set_format(LogFormat::Text);
+        let contents = get_file_contents(identifier.as_str());
+        assert!(
+            contents.contains(r#""level":"DEBUG""#),
+            "Contents: {contents}"
+        );
+        assert!(
+            contents.contains(&format!(r#""message":"{identifier} - foo""#)),
+            "Contents: {contents}"
+        );
+        assert!(
+            contents.contains(
+                r#""client_id":"42","seed":"127.0.0.1:6379","request_type":"Get","latency_us":250"#
+            ),
+            "Contents: {contents}"
+        );
+    }
+
     fn clean() -> Result<(), std::io::Error> {
         remove_dir_all(FILE_DIRECTORY)
     }
@@ -2,8 +2,8 @@
 
 use glide_core::errors::error_message;
 use glide_core::{
-    DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, GlideOpenTelemetry, GlideOpenTelemetryConfigBuilder,
-    GlideOpenTelemetrySignalsExporter, GlideSpan, Telemetry,
+    GlideOpenTelemetry, GlideOpenTelemetryConfigBuilder, GlideOpenTelemetrySignalsExporter,
+    GlideSpan, QueuePriority, Telemetry,
 };
 use logger_core::log_warn_lazy;
 use redis::GlideConnectionOptions;
@@ -19,6 +19,7 @@ use bytes::Bytes;
 use glide_core::MAX_REQUEST_ARGS_LENGTH;
 use glide_core::client::ConnectionError;
 use glide_core::client::get_or_init_runtime;
+use glide_core::runtime_config::GlideRuntimeConfig;
 use glide_core::start_socket_listener;
 use napi::bindgen_prelude::BigInt;
 use napi::bindgen_prelude::Either;
@@ -79,7 +80,7 @@ pub struct OpenTelemetryConfig {
     pub traces: Option<OpenTelemetryTracesConfig>,
     /// Optional configuration for exporting metrics data. If `None`, metrics data will not be exported.
     pub metrics: Option<OpenTelemetryMetricsConfig>,
-    /// Optional interval in milliseconds between consecutive exports of telemetry data. If `None`, the default `DEFAULT_FLUSH_SIGNAL_INTERVAL_MS` will be used.
+    /// Optional interval in milliseconds between consecutive exports of telemetry data. If `None`, the `telemetry_flush_interval` of the runtime configuration will be used.
     pub flush_interval_ms: Option<i64>,
 }
 
@@ -89,7 +90,7 @@ pub struct OpenTelemetryConfig {
 ///   - For gRPC: `grpc://host:port`
 ///   - For HTTP: `http://host:port` or `https://host:port`
 ///   - For file exporter: `file:///absolute/path/to/folder/file.json`
-/// - `sample_percentage`: The percentage of requests to sample and create a span for, used to measure command duration. If `None`, a default value DEFAULT_TRACE_SAMPLE_PERCENTAGE will be used.
+/// - `sample_percentage`: The percentage of requests to sample and create a span for, used to measure command duration. If `None`, the `telemetry_trace_sample_percentage` of the runtime configuration will be used.
 ///   Note: There is a tradeoff between sampling percentage and performance. Higher sampling percentages will provide more detailed telemetry data but will impact performance.
 ///   It is recommended to keep this number low (1-5%) in production environments unless you have specific needs for higher sampling rates.
 #[napi(object)]
@@ -97,7 +98,7 @@ pub struct OpenTelemetryConfig {
 pub struct OpenTelemetryTracesConfig {
     /// The endpoint to which trace data will be exported.
     pub endpoint: String,
-    /// The percentage of requests to sample and create a span for, used to measure command duration. If `None`, a default value DEFAULT_TRACE_SAMPLE_PERCENTAGE will be used.
+    /// The percentage of requests to sample and create a span for, used to measure command duration. If `None`, the `telemetry_trace_sample_percentage` of the runtime configuration will be used.
     /// Note: There is a tradeoff between sampling percentage and performance. Higher sampling percentages will provide more detailed telemetry data but will impact performance.
     /// It is recommended to keep this number low (1-5%) in production environments unless you have specific needs for higher sampling rates.
     pub sample_percentage: Option<u32>,
@@ -195,6 +196,14 @@ pub fn start_socket_listener_external(env: Env) -> Result<JsObject> {
     Ok(promise)
 }
 
+/// Sets the process-wide runtime configuration from a serialized `RuntimeConfig` message.
+/// Must be called before the first client is created.
+#[napi(js_name = "InitRuntimeConfig")]
+pub fn init_runtime_config(runtime_config: Uint8Array) -> Result<()> {
+    glide_core::runtime_config::GlideRuntimeConfig::initialize_from_protobuf(&runtime_config)
+        .map_err(|e| napi::Error::new(Status::InvalidArg, e))
+}
+
 #[napi(js_name = "InitOpenTelemetry")]
 pub fn init_open_telemetry(open_telemetry_config: OpenTelemetryConfig) -> Result<()> {
     // At least one of traces or metrics must be provided
@@ -213,7 +222,11 @@ pub fn init_open_telemetry(open_telemetry_config: OpenTelemetryConfig) -> Result
             GlideOpenTelemetrySignalsExporter::from_str(&traces.endpoint)
                 .map_err(ConnectionError::IoError)
                 .map_err(|e| napi::Error::new(Status::Unknown, format!("{e}")))?,
-            traces.sample_percentage,
+            Some(
+                traces
+                    .sample_percentage
+                    .unwrap_or(GlideRuntimeConfig::get().telemetry_trace_sample_percentage),
+            ),
         );
     }
 
@@ -226,9 +239,11 @@ pub fn init_open_telemetry(open_telemetry_config: OpenTelemetryConfig) -> Result
         );
     }
 
-    let flush_interval_ms = open_telemetry_config
-        .flush_interval_ms
-        .unwrap_or(DEFAULT_FLUSH_SIGNAL_INTERVAL_MS as i64);
+    let flush_interval_ms = open_telemetry_config.flush_interval_ms.unwrap_or_else(|| {
+        GlideRuntimeConfig::get()
+            .telemetry_flush_interval
+            .as_millis() as i64
+    });
 
     if flush_interval_ms <= 0 {
         return Err(napi::Error::new(
@@ -726,6 +741,30 @@ pub fn get_statistics(env: Env) -> Result<JsObject> {
     let subscription_out_of_sync_count = Telemetry::subscription_out_of_sync_count().to_string();
     let subscription_last_sync_timestamp =
         Telemetry::subscription_last_sync_timestamp().to_string();
+    let latency_breakdown_samples = Telemetry::latency_breakdown_samples().to_string();
+    let total_queue_time_us = Telemetry::total_queue_time_us().to_string();
+    let total_network_time_us = Telemetry::total_network_time_us().to_string();
+    let total_decode_time_us = Telemetry::total_decode_time_us().to_string();
+    let hot_key_warnings = Telemetry::hot_key_warnings().to_string();
+    let memory_budget_bytes_in_use = Telemetry::memory_budget_bytes_in_use().to_string();
+    let memory_budget_rejections = Telemetry::memory_budget_rejections().to_string();
+    let process_memory_bytes = Telemetry::process_memory_bytes().to_string();
+    let soft_memory_limit_rejections = Telemetry::soft_memory_limit_rejections().to_string();
+    let large_requests = Telemetry::large_requests().to_string();
+    let large_responses = Telemetry::large_responses().to_string();
+    let health_checks = Telemetry::health_checks().to_string();
+    let health_check_failures = Telemetry::health_check_failures().to_string();
+    let health_check_evictions = Telemetry::health_check_evictions().to_string();
+    let unhealthy_nodes = Telemetry::unhealthy_nodes().to_string();
+    let queued_high_priority_requests = Telemetry::queued_requests(QueuePriority::High).to_string();
+    let queued_normal_priority_requests =
+        Telemetry::queued_requests(QueuePriority::Normal).to_string();
+    let queued_low_priority_requests = Telemetry::queued_requests(QueuePriority::Low).to_string();
+    let starved_requests = Telemetry::starved_requests().to_string();
+    let dropped_push_messages = Telemetry::dropped_push_messages().to_string();
+    let push_queue_disconnects = Telemetry::push_queue_disconnects().to_string();
+    let total_connection_errors = Telemetry::total_connection_errors().to_string();
+    let recent_connection_errors = Telemetry::recent_connection_errors_json();
 
     let mut stats: JsObject = env.create_object()?;
     stats.set_named_property("total_connections", total_connections)?;
@@ -744,6 +783,35 @@ pub fn get_statistics(env: Env) -> Result<JsObject> {
         "subscription_last_sync_timestamp",
         subscription_last_sync_timestamp,
     )?;
+    stats.set_named_property("latency_breakdown_samples", latency_breakdown_samples)?;
+    stats.set_named_property("total_queue_time_us", total_queue_time_us)?;
+    stats.set_named_property("total_network_time_us", total_network_time_us)?;
+    stats.set_named_property("total_decode_time_us", total_decode_time_us)?;
+    stats.set_named_property("hot_key_warnings", hot_key_warnings)?;
+    stats.set_named_property("memory_budget_bytes_in_use", memory_budget_bytes_in_use)?;
+    stats.set_named_property("memory_budget_rejections", memory_budget_rejections)?;
+    stats.set_named_property("process_memory_bytes", process_memory_bytes)?;
+    stats.set_named_property("soft_memory_limit_rejections", soft_memory_limit_rejections)?;
+    stats.set_named_property("large_requests", large_requests)?;
+    stats.set_named_property("large_responses", large_responses)?;
+    stats.set_named_property("health_checks", health_checks)?;
+    stats.set_named_property("health_check_failures", health_check_failures)?;
+    stats.set_named_property("health_check_evictions", health_check_evictions)?;
+    stats.set_named_property("unhealthy_nodes", unhealthy_nodes)?;
+    stats.set_named_property(
+        "queued_high_priority_requests",
+        queued_high_priority_requests,
+    )?;
+    stats.set_named_property(
+        "queued_normal_priority_requests",
+        queued_normal_priority_requests,
+    )?;
+    stats.set_named_property("queued_low_priority_requests", queued_low_priority_requests)?;
+    stats.set_named_property("starved_requests", starved_requests)?;
+    stats.set_named_property("dropped_push_messages", dropped_push_messages)?;
+    stats.set_named_property("push_queue_disconnects", push_queue_disconnects)?;
+    stats.set_named_property("total_connection_errors", total_connection_errors)?;
+    stats.set_named_property("recent_connection_errors", recent_connection_errors)?;
 
     Ok(stats)
 }
@@ -1099,6 +1099,32 @@ export interface AdvancedBaseClientConfiguration {
      * ```
      */
     pubsubReconciliationIntervalMs?: number;
+
+    /**
+     * Heartbeats exchanged with the Rust core over the client's socket, so that the core closes the socket of a
+     * process that was killed without closing its clients.
+     *
+     * The core sends a heartbeat every `intervalMs`, which the client answers, and closes the socket once nothing was
+     * read from it for `timeoutMs`. The timeout is raised to at least twice the interval.
+     *
+     * If not set, heartbeats are disabled.
+     *
+     * @example
+     * ```typescript
+     * const config: GlideClientConfiguration = {
+     *   addresses: [{ host: "localhost", port: 6379 }],
+     *   advancedConfiguration: {
+     *     socketHeartbeat: { intervalMs: 1000, timeoutMs: 5000 }
+     *   }
+     * };
+     * ```
+     */
+    socketHeartbeat?: {
+        /** Time between heartbeats, in milliseconds. Default: 1000. */
+        intervalMs?: number;
+        /** Time without reading from the socket before the core closes it, in milliseconds. Default: 5000. */
+        timeoutMs?: number;
+    };
 }
 
 /**
@@ -1260,7 +1286,9 @@ export class BaseClient {
                 }
             }
 
-            if (message.isPush) {
+            if (message.isHeartbeat) {
+                this.answerHeartbeat();
+            } else if (message.isPush) {
                 this.processPush(message);
             } else {
                 this.processResponse(message);
@@ -1741,6 +1769,21 @@ export class BaseClient {
         );
     }
 
+    private answerHeartbeat() {
+        if (this.isClosed) {
+            return;
+        }
+
+        this.writeOrBufferRequest(
+            new command_request.CommandRequest({
+                heartbeat: command_request.Heartbeat.create(),
+            }),
+            (message: command_request.CommandRequest, writer: Writer) => {
+                command_request.CommandRequest.encodeDelimited(message, writer);
+            },
+        );
+    }
+
     protected writeOrBufferRequest<TRequest>(
         message: TRequest,
         encodeDelimited: (message: TRequest, writer: Writer) => void,
@@ -9553,6 +9596,24 @@ export class BaseClient {
                 options.pubsubReconciliationIntervalMs;
         }
 
+        if (options.socketHeartbeat) {
+            const { intervalMs, timeoutMs } = options.socketHeartbeat;
+
+            if (intervalMs !== undefined && intervalMs <= 0) {
+                throw new ConfigurationError(
+                    "socketHeartbeat.intervalMs must be positive",
+                );
+            }
+
+            if (timeoutMs !== undefined && timeoutMs <= 0) {
+                throw new ConfigurationError(
+                    "socketHeartbeat.timeoutMs must be positive",
+                );
+            }
+
+            request.socketHeartbeat = { intervalMs, timeoutMs };
+        }
+
         // Apply TLS configuration if present
         if (options.tlsAdvancedConfiguration) {
             // request.tlsMode is either SecureTls or InsecureTls here
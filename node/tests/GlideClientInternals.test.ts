@@ -675,6 +675,37 @@ describe("SocketConnectionInternals", () => {
         closeTestResources(connection, server, socket);
     });
 
+    it("should pass socket heartbeat configuration", async () => {
+        const { connection, server, socket } = await getConnectionAndSocket(
+            (request: connection_request.ConnectionRequest) =>
+                request.socketHeartbeat?.intervalMs === 100 &&
+                request.socketHeartbeat?.timeoutMs === 500,
+            {
+                addresses: [{ host: "foo" }],
+                advancedConfiguration: {
+                    socketHeartbeat: { intervalMs: 100, timeoutMs: 500 },
+                },
+            },
+        );
+        closeTestResources(connection, server, socket);
+    });
+
+    it("should answer heartbeats", async () => {
+        await testWithResources(async (connection, socket) => {
+            const answer = new Promise<CommandRequest>((resolve) => {
+                socket.once("data", (data) => {
+                    resolve(CommandRequest.decodeDelimited(Reader.create(data)));
+                });
+            });
+            const heartbeat = response.Response.create({ isHeartbeat: true });
+            socket.write(response.Response.encodeDelimited(heartbeat).finish());
+
+            const request = await answer;
+            expect(request.heartbeat).toBeDefined();
+            expect(request.heartbeat).not.toBeNull();
+        });
+    });
+
     it("should pass database id", async () => {
         const { connection, server, socket } = await getConnectionAndSocket(
             (request: connection_request.ConnectionRequest) =>
@@ -2,15 +2,16 @@
 
 use bytes::Bytes;
 use glide_core::MAX_REQUEST_ARGS_LENGTH;
-use glide_core::Telemetry;
 use glide_core::client::FINISHED_SCAN_CURSOR;
 use glide_core::client::get_or_init_runtime;
 use glide_core::errors::error_message;
+use glide_core::runtime_config::GlideRuntimeConfig;
 use glide_core::start_socket_listener;
 use glide_core::{
     DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, DEFAULT_TRACE_SAMPLE_PERCENTAGE, GlideOpenTelemetry,
     GlideOpenTelemetrySignalsExporter, GlideSpan,
 };
+use glide_core::{QueuePriority, Telemetry};
 use pyo3::Python;
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
@@ -44,7 +45,7 @@ pub struct OpenTelemetryConfig {
     traces: Option<OpenTelemetryTracesConfig>,
     /// Optional configuration for exporting metrics data. If `None`, metrics data will not be exported.
     metrics: Option<OpenTelemetryMetricsConfig>,
-    /// Optional interval in milliseconds between consecutive exports of telemetry data. If `None`, the default `DEFAULT_FLUSH_SIGNAL_INTERVAL_MS` will be used.
+    /// Optional interval in milliseconds between consecutive exports of telemetry data. If `None`, the `telemetry_flush_interval` of the runtime configuration will be used.
     #[pyo3(get, set)]
     pub flush_interval_ms: Option<i64>,
 }
@@ -84,7 +85,7 @@ impl OpenTelemetryConfig {
 ///   - For gRPC: `grpc://host:port`
 ///   - For HTTP: `http://host:port` or `https://host:port`
 ///   - For file exporter: `file:///absolute/path/to/folder/file.json`
-/// - `sample_percentage`: The percentage of requests to sample and create a span for, used to measure command duration. If `None`, a default value DEFAULT_TRACE_SAMPLE_RATE will be used.
+/// - `sample_percentage`: The percentage of requests to sample and create a span for, used to measure command duration. If `None`, the `telemetry_trace_sample_percentage` of the runtime configuration will be used.
 ///   Note: There is a tradeoff between sampling percentage and performance. Higher sampling percentages will provide more detailed telemetry data but will impact performance.
 ///   It is recommended to keep this number low (1-5%) in production environments unless you have specific needs for higher sampling rates.
 #[pyclass(from_py_object)]
@@ -92,7 +93,7 @@ impl OpenTelemetryConfig {
 pub struct OpenTelemetryTracesConfig {
     /// The endpoint to which trace data will be exported.
     endpoint: String,
-    /// The percentage of requests to sample and create a span for, used to measure command duration. If `None`, a default value DEFAULT_TRACE_SAMPLE_RATE will be used.
+    /// The percentage of requests to sample and create a span for, used to measure command duration. If `None`, the `telemetry_trace_sample_percentage` of the runtime configuration will be used.
     /// Note: There is a tradeoff between sampling percentage and performance. Higher sampling percentages will provide more detailed telemetry data but will impact performance.
     /// It is recommended to keep this number low (1-5%) in production environments unless you have specific needs for higher sampling rates.
     sample_percentage: Option<u32>,
@@ -258,6 +259,7 @@ fn glide(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(create_otel_span, m)?)?;
     m.add_function(wrap_pyfunction!(drop_otel_span, m)?)?;
     m.add_function(wrap_pyfunction!(init_opentelemetry, m)?)?;
+    m.add_function(wrap_pyfunction!(init_runtime_config, m)?)?;
     m.add_function(wrap_pyfunction!(get_min_compressed_size, m)?)?;
     m.add_function(wrap_pyfunction!(get_cache_metric_from_registry, m)?)?;
     m.add_function(wrap_pyfunction!(register_address_resolver, m)?)?;
@@ -387,6 +389,98 @@ fn glide(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
             "subscription_last_sync_timestamp".to_string(),
             Telemetry::subscription_last_sync_timestamp().to_string(),
         );
+        stats_map.insert(
+            "latency_breakdown_samples".to_string(),
+            Telemetry::latency_breakdown_samples().to_string(),
+        );
+        stats_map.insert(
+            "total_queue_time_us".to_string(),
+            Telemetry::total_queue_time_us().to_string(),
+        );
+        stats_map.insert(
+            "total_network_time_us".to_string(),
+            Telemetry::total_network_time_us().to_string(),
+        );
+        stats_map.insert(
+            "total_decode_time_us".to_string(),
+            Telemetry::total_decode_time_us().to_string(),
+        );
+        stats_map.insert(
+            "hot_key_warnings".to_string(),
+            Telemetry::hot_key_warnings().to_string(),
+        );
+        stats_map.insert(
+            "memory_budget_bytes_in_use".to_string(),
+            Telemetry::memory_budget_bytes_in_use().to_string(),
+        );
+        stats_map.insert(
+            "memory_budget_rejections".to_string(),
+            Telemetry::memory_budget_rejections().to_string(),
+        );
+        stats_map.insert(
+            "process_memory_bytes".to_string(),
+            Telemetry::process_memory_bytes().to_string(),
+        );
+        stats_map.insert(
+            "soft_memory_limit_rejections".to_string(),
+            Telemetry::soft_memory_limit_rejections().to_string(),
+        );
+        stats_map.insert(
+            "large_requests".to_string(),
+            Telemetry::large_requests().to_string(),
+        );
+        stats_map.insert(
+            "large_responses".to_string(),
+            Telemetry::large_responses().to_string(),
+        );
+        stats_map.insert(
+            "health_checks".to_string(),
+            Telemetry::health_checks().to_string(),
+        );
+        stats_map.insert(
+            "health_check_failures".to_string(),
+            Telemetry::health_check_failures().to_string(),
+        );
+        stats_map.insert(
+            "health_check_evictions".to_string(),
+            Telemetry::health_check_evictions().to_string(),
+        );
+        stats_map.insert(
+            "unhealthy_nodes".to_string(),
+            Telemetry::unhealthy_nodes().to_string(),
+        );
+        stats_map.insert(
+            "queued_high_priority_requests".to_string(),
+            Telemetry::queued_requests(QueuePriority::High).to_string(),
+        );
+        stats_map.insert(
+            "queued_normal_priority_requests".to_string(),
+            Telemetry::queued_requests(QueuePriority::Normal).to_string(),
+        );
+        stats_map.insert(
+            "queued_low_priority_requests".to_string(),
+            Telemetry::queued_requests(QueuePriority::Low).to_string(),
+        );
+        stats_map.insert(
+            "starved_requests".to_string(),
+            Telemetry::starved_requests().to_string(),
+        );
+        stats_map.insert(
+            "dropped_push_messages".to_string(),
+            Telemetry::dropped_push_messages().to_string(),
+        );
+        stats_map.insert(
+            "push_queue_disconnects".to_string(),
+            Telemetry::push_queue_disconnects().to_string(),
+        );
+        stats_map.insert(
+            "total_connection_errors".to_string(),
+            Telemetry::total_connection_errors().to_string(),
+        );
+        stats_map.insert(
+            "recent_connection_errors".to_string(),
+            Telemetry::recent_connection_errors_json(),
+        );
 
         Python::attach(|py| {
             let py_dict = PyDict::new(py);
@@ -691,6 +785,14 @@ pub fn drop_otel_span(span_ptr: usize) {
     }
 }
 
+/// Sets the process-wide runtime configuration from a serialized `RuntimeConfig` message.
+/// Must be called before the first client is created.
+#[pyfunction]
+pub fn init_runtime_config(runtime_config: &[u8]) -> PyResult<()> {
+    glide_core::runtime_config::GlideRuntimeConfig::initialize_from_protobuf(runtime_config)
+        .map_err(PyTypeError::new_err)
+}
+
 #[pyfunction]
 pub fn init_opentelemetry(open_telemetry_config: OpenTelemetryConfig) -> PyResult<()> {
     // At least one of traces or metrics must be provided
@@ -706,7 +808,10 @@ pub fn init_opentelemetry(open_telemetry_config: OpenTelemetryConfig) -> PyResul
     if let Some(traces) = open_telemetry_config.traces {
         let exporter = GlideOpenTelemetrySignalsExporter::from_str(&traces.endpoint)
             .map_err(|e| PyTypeError::new_err(format!("Invalid traces endpoint: {e}")))?;
-        config_builder = config_builder.with_trace_exporter(exporter, traces.sample_percentage);
+        let sample_percentage = traces
+            .sample_percentage
+            .unwrap_or(GlideRuntimeConfig::get().telemetry_trace_sample_percentage);
+        config_builder = config_builder.with_trace_exporter(exporter, Some(sample_percentage));
     }
 
     // Initialize OpenTelemetry metrics exporter
@@ -716,9 +821,11 @@ pub fn init_opentelemetry(open_telemetry_config: OpenTelemetryConfig) -> PyResul
         config_builder = config_builder.with_metrics_exporter(exporter);
     }
 
-    let flush_interval_ms = open_telemetry_config
-        .flush_interval_ms
-        .unwrap_or(DEFAULT_FLUSH_INTERVAL_SIGNAL_MS);
+    let flush_interval_ms = open_telemetry_config.flush_interval_ms.unwrap_or_else(|| {
+        GlideRuntimeConfig::get()
+            .telemetry_flush_interval
+            .as_millis() as i64
+    });
 
     // Set flush interval if provided
     if flush_interval_ms <= 0 {